@@ -1,18 +1,33 @@
 use actix_web::web;
 
 use crate::service::{
-    admin::admin_api, auth::auth_api, collection::collection_api, project::project_api,
-    record::record_api, root::root_api, token::token_api,
+    admin::admin_api, admin_session::admin_session_api, audit_log::audit_log_api, auth::auth_api,
+    backup::backup_api, collection::collection_api, collection_plugin::collection_plugin_api,
+    health::health_api, ingest::ingest_api, job::job_api, log_filter::log_filter_api,
+    project::project_api, project_member::project_member_api,
+    project_transfer::project_transfer_api, project_usage::project_usage_api, record::record_api,
+    root::root_api, token::token_api, user::user_api,
 };
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-    cfg.configure(root_api).service(
+    cfg.configure(root_api).configure(health_api).service(
         web::scope("/api/rest")
             .configure(auth_api)
+            .configure(admin_session_api)
             .configure(admin_api)
+            .configure(audit_log_api)
+            .configure(log_filter_api)
+            .configure(backup_api)
+            .configure(job_api)
             .configure(token_api)
+            .configure(project_transfer_api)
             .configure(project_api)
+            .configure(project_member_api)
+            .configure(project_usage_api)
+            .configure(user_api)
             .configure(collection_api)
-            .configure(record_api),
+            .configure(collection_plugin_api)
+            .configure(record_api)
+            .configure(ingest_api),
     );
 }
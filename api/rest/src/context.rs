@@ -1,19 +1,38 @@
-use std::sync::mpsc::Sender;
+use std::sync::{mpsc::Sender, Arc, Mutex};
 
-use hb_dao::Db;
+use ahash::{HashMap, HashMapExt};
+use anyhow::{Error, Result};
+use hb_dao::{
+    jwt_revocation::JwtRevocationDao, scheduler::JobStatuses, usage_meter::RequestCounter, Db,
+};
+use hb_event::EventBus;
 use hb_hash_argon2::argon2::Argon2Hash;
-use hb_mailer::MailPayload;
-use hb_token_jwt::token::JwtToken;
+use hb_mailer::{MailPayload, MailerHealth};
+use hb_plugin_wasm::{WasmPlugin, WasmRuntime};
+use hb_token_jwt::{claim::Claim, token::JwtToken};
+use hb_token_totp::totp::TotpToken;
+use hb_token_webauthn::webauthn::WebauthnToken;
+use uuid::Uuid;
+
+use crate::{index_advisor::IndexAdvisor, login_throttle::LoginThrottle};
 
 pub struct ApiRestCtx {
     hash: HashCtx,
     token: TokenCtx,
     mailer: MailerCtx,
     dao: DaoCtx,
+    quota: QuotaCtx,
+    plugin: PluginCtx,
+    request_counter: RequestCounter,
+    event_bus: EventBus,
+    jobs: JobStatuses,
+    index_advisor: IndexAdvisor,
+    login_throttle: LoginThrottle,
     admin_registration: bool,
     access_token_length: usize,
     registration_ttl: u32,
     reset_password_ttl: u32,
+    operator_emails: Vec<String>,
 }
 
 impl ApiRestCtx {
@@ -22,20 +41,34 @@ impl ApiRestCtx {
         token: TokenCtx,
         mailer: MailerCtx,
         dao: DaoCtx,
+        quota: QuotaCtx,
+        plugin: PluginCtx,
+        request_counter: RequestCounter,
+        event_bus: EventBus,
+        jobs: JobStatuses,
         admin_registration: bool,
         access_token_length: usize,
         registration_ttl: u32,
         reset_password_ttl: u32,
+        operator_emails: Vec<String>,
     ) -> Self {
         Self {
             hash,
             token,
             mailer,
             dao,
+            quota,
+            plugin,
+            request_counter,
+            event_bus,
+            jobs,
+            index_advisor: IndexAdvisor::new(),
+            login_throttle: LoginThrottle::new(),
             admin_registration,
             access_token_length,
             registration_ttl,
             reset_password_ttl,
+            operator_emails,
         }
     }
 
@@ -55,6 +88,30 @@ impl ApiRestCtx {
         &self.dao
     }
 
+    pub fn quota(&self) -> &QuotaCtx {
+        &self.quota
+    }
+
+    pub fn plugin(&self) -> &PluginCtx {
+        &self.plugin
+    }
+
+    pub fn event_bus(&self) -> &EventBus {
+        &self.event_bus
+    }
+
+    pub fn jobs(&self) -> &JobStatuses {
+        &self.jobs
+    }
+
+    pub fn index_advisor(&self) -> &IndexAdvisor {
+        &self.index_advisor
+    }
+
+    pub fn login_throttle(&self) -> &LoginThrottle {
+        &self.login_throttle
+    }
+
     pub fn admin_registration(&self) -> &bool {
         &self.admin_registration
     }
@@ -70,6 +127,41 @@ impl ApiRestCtx {
     pub fn reset_password_ttl(&self) -> &u32 {
         &self.reset_password_ttl
     }
+
+    pub fn is_operator(&self, email: &str) -> bool {
+        self.operator_emails
+            .iter()
+            .any(|operator_email| operator_email.eq_ignore_ascii_case(email))
+    }
+
+    // Requests under /api/rest/project/{project_id} count towards that project's usage
+    pub fn count_request(&self, path: &str) {
+        if let Some(project_id) = path
+            .strip_prefix("/api/rest/project/")
+            .and_then(|path| path.split('/').next())
+            .and_then(|project_id| Uuid::parse_str(project_id).ok())
+        {
+            self.request_counter.increment(&project_id);
+        }
+    }
+
+    pub async fn decode_token(&self, token: &str) -> Result<Claim> {
+        let claim = self.token.jwt.decode(token)?;
+        for id in [
+            Some(*claim.jti()),
+            Some(*claim.id()),
+            claim.session_id(),
+            claim.user_id(),
+        ]
+        .iter()
+        .flatten()
+        {
+            if JwtRevocationDao::db_is_revoked(self.dao.db(), id).await? {
+                return Err(Error::msg("Token has been revoked"));
+            }
+        }
+        Ok(claim)
+    }
 }
 
 pub struct HashCtx {
@@ -88,38 +180,57 @@ impl HashCtx {
 
 pub struct TokenCtx {
     jwt: JwtToken,
+    totp: TotpToken,
+    webauthn: Option<WebauthnToken>,
 }
 
 impl TokenCtx {
-    pub fn new(jwt: JwtToken) -> Self {
-        Self { jwt }
+    pub fn new(jwt: JwtToken, totp: TotpToken, webauthn: Option<WebauthnToken>) -> Self {
+        Self {
+            jwt,
+            totp,
+            webauthn,
+        }
     }
 
     pub fn jwt(&self) -> &JwtToken {
         &self.jwt
     }
+
+    pub fn totp(&self) -> &TotpToken {
+        &self.totp
+    }
+
+    pub fn webauthn(&self) -> &Option<WebauthnToken> {
+        &self.webauthn
+    }
 }
 
 pub struct MailerCtx {
     sender: Sender<MailPayload>,
+    health: MailerHealth,
 }
 
 impl MailerCtx {
-    pub fn new(sender: Sender<MailPayload>) -> Self {
-        Self { sender }
+    pub fn new(sender: Sender<MailPayload>, health: MailerHealth) -> Self {
+        Self { sender, health }
     }
 
     pub fn sender(&self) -> &Sender<MailPayload> {
         &self.sender
     }
+
+    pub fn health(&self) -> &MailerHealth {
+        &self.health
+    }
 }
 
 pub struct DaoCtx {
-    db: Db,
+    db: Arc<Db>,
 }
 
 impl DaoCtx {
-    pub fn new(db: Db) -> Self {
+    pub fn new(db: Arc<Db>) -> Self {
         Self { db }
     }
 
@@ -127,3 +238,80 @@ impl DaoCtx {
         &self.db
     }
 }
+
+pub struct QuotaCtx {
+    max_collections: Option<u64>,
+    max_records_per_collection: Option<u64>,
+    max_storage_bytes: Option<u64>,
+    max_tokens: Option<u64>,
+}
+
+impl QuotaCtx {
+    pub fn new(
+        max_collections: Option<u64>,
+        max_records_per_collection: Option<u64>,
+        max_storage_bytes: Option<u64>,
+        max_tokens: Option<u64>,
+    ) -> Self {
+        Self {
+            max_collections,
+            max_records_per_collection,
+            max_storage_bytes,
+            max_tokens,
+        }
+    }
+
+    pub fn max_collections(&self) -> &Option<u64> {
+        &self.max_collections
+    }
+
+    pub fn max_records_per_collection(&self) -> &Option<u64> {
+        &self.max_records_per_collection
+    }
+
+    pub fn max_storage_bytes(&self) -> &Option<u64> {
+        &self.max_storage_bytes
+    }
+
+    pub fn max_tokens(&self) -> &Option<u64> {
+        &self.max_tokens
+    }
+}
+
+// Compiled plugins are cached per collection, including collections without one, until a plugin
+// is uploaded or removed
+pub struct PluginCtx {
+    runtime: WasmRuntime,
+    max_module_bytes: usize,
+    plugins: Mutex<HashMap<Uuid, Option<Arc<WasmPlugin>>>>,
+}
+
+impl PluginCtx {
+    pub fn new(runtime: WasmRuntime, max_module_bytes: usize) -> Self {
+        Self {
+            runtime,
+            max_module_bytes,
+            plugins: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn runtime(&self) -> &WasmRuntime {
+        &self.runtime
+    }
+
+    pub fn max_module_bytes(&self) -> &usize {
+        &self.max_module_bytes
+    }
+
+    pub fn cached(&self, collection_id: &Uuid) -> Option<Option<Arc<WasmPlugin>>> {
+        self.plugins.lock().unwrap().get(collection_id).cloned()
+    }
+
+    pub fn cache(&self, collection_id: &Uuid, plugin: Option<Arc<WasmPlugin>>) {
+        self.plugins.lock().unwrap().insert(*collection_id, plugin);
+    }
+
+    pub fn invalidate(&self, collection_id: &Uuid) {
+        self.plugins.lock().unwrap().remove(collection_id);
+    }
+}
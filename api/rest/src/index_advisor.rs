@@ -0,0 +1,74 @@
+use std::sync::Mutex;
+
+use ahash::{HashMap, HashMapExt, HashSet};
+use hb_dao::collection::CollectionDao;
+use uuid::Uuid;
+
+pub struct IndexAdvisor {
+    usage: Mutex<HashMap<Uuid, HashMap<String, FieldUsage>>>,
+}
+
+impl IndexAdvisor {
+    pub fn new() -> Self {
+        Self {
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record(&self, collection_id: &Uuid, filter_fields: &HashSet<String>, orders: &[&str]) {
+        if filter_fields.is_empty() && orders.is_empty() {
+            return;
+        }
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage.entry(*collection_id).or_default();
+        for field in filter_fields {
+            usage.entry(field.to_owned()).or_default().filter_count += 1;
+        }
+        for field in orders {
+            usage.entry(field.to_string()).or_default().order_count += 1;
+        }
+    }
+
+    pub fn suggest(&self, collection_data: &CollectionDao) -> Vec<(String, FieldUsage)> {
+        let usage = self.usage.lock().unwrap();
+        let usage = match usage.get(collection_data.id()) {
+            Some(usage) => usage,
+            None => return Vec::new(),
+        };
+        let mut suggestions = usage
+            .iter()
+            .filter(|(field, _)| {
+                field.as_str() != "_id"
+                    && collection_data.schema_fields().contains_key(*field)
+                    && !collection_data.indexes().contains(*field)
+            })
+            .map(|(field, usage)| (field.to_owned(), *usage))
+            .collect::<Vec<_>>();
+        suggestions.sort_by(|a, b| {
+            (b.1.filter_count + b.1.order_count)
+                .cmp(&(a.1.filter_count + a.1.order_count))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        suggestions
+    }
+
+    pub fn clear(&self, collection_id: &Uuid) {
+        self.usage.lock().unwrap().remove(collection_id);
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct FieldUsage {
+    filter_count: u64,
+    order_count: u64,
+}
+
+impl FieldUsage {
+    pub fn filter_count(&self) -> &u64 {
+        &self.filter_count
+    }
+
+    pub fn order_count(&self) -> &u64 {
+        &self.order_count
+    }
+}
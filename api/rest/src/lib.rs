@@ -1,48 +1,106 @@
 use actix_web::{
-    middleware::{ErrorHandlers, Logger},
-    web, App, HttpServer,
+    dev::Service,
+    middleware::{Compress, Condition, ErrorHandlers, Logger},
+    web, App, HttpMessage, HttpServer,
 };
 use anyhow::Result;
 use config::config;
 use context::ApiRestCtx;
 use error_handler::default_error_handler;
-use logger::logger_format;
+use logger::{logger_format, logger_request_id, logger_token_id};
+use request_id::RequestId;
+use tokio::sync::watch;
+use trace_context::TraceContext;
+use tracing::Instrument;
 
 mod config;
 pub mod context;
 mod error_handler;
+mod index_advisor;
 mod logger;
+mod login_throttle;
 mod model;
+mod plugin;
+mod project_access;
+mod quota;
+mod request_id;
+mod rule;
 mod service;
+mod trace_context;
 
 pub struct ApiRestServer {
     address: String,
+    compression: bool,
     context: web::Data<ApiRestCtx>,
 }
 
 impl ApiRestServer {
-    pub fn new(host: &str, port: &str, ctx: ApiRestCtx) -> Self {
+    pub fn new(host: &str, port: &str, compression: &bool, ctx: ApiRestCtx) -> Self {
         hb_log::info(Some("⚡"), "ApiRestServer: Initializing component");
 
         let address = format!("{}:{}", host, port);
         let context = web::Data::new(ctx);
 
-        Self { address, context }
+        Self {
+            address,
+            compression: *compression,
+            context,
+        }
     }
 
-    pub async fn run(self) -> Result<()> {
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
         hb_log::info(Some("💫"), "ApiRestServer: Running component");
 
-        Ok(HttpServer::new(move || {
+        let server = HttpServer::new(move || {
             App::new()
-                .wrap(Logger::new(logger_format()))
+                .wrap(Condition::new(self.compression, Compress::default()))
+                .wrap(
+                    Logger::new(logger_format())
+                        .custom_request_replace("request_id", logger_request_id)
+                        .custom_request_replace("token_id", logger_token_id),
+                )
                 .wrap(ErrorHandlers::new().default_handler(default_error_handler))
+                .wrap_fn(|req, srv| {
+                    let request_id = RequestId::from_headers(req.headers());
+                    let trace_context = TraceContext::from_headers(req.headers());
+                    let span = tracing::info_span!(
+                        "request",
+                        method = %req.method(),
+                        path = req.path(),
+                        request_id = request_id.as_str(),
+                        trace_id = trace_context.trace_id(),
+                        span_id = trace_context.span_id(),
+                        parent_id = trace_context.parent_id().as_deref(),
+                    );
+                    if let Some(ctx) = req.app_data::<web::Data<ApiRestCtx>>() {
+                        ctx.count_request(req.path());
+                    }
+                    req.extensions_mut().insert(request_id.clone());
+                    req.extensions_mut().insert(trace_context.clone());
+                    let fut = srv.call(req);
+                    async move {
+                        let mut res = fut.await?;
+                        request_id.insert_header(res.headers_mut());
+                        trace_context.insert_headers(res.headers_mut());
+                        Ok(res)
+                    }
+                    .instrument(span)
+                })
                 .app_data(self.context.clone())
                 .configure(config)
         })
-        .bind(self.address)
-        .unwrap()
-        .run()
-        .await?)
+        .bind(self.address)?
+        .disable_signals()
+        .run();
+
+        let server_handle = server.handle();
+        tokio::spawn(async move {
+            if shutdown.wait_for(|shutdown| *shutdown).await.is_ok() {
+                hb_log::info(Some("🛑"), "ApiRestServer: Stopping component");
+                server_handle.stop(true).await;
+            }
+        });
+
+        Ok(server.await?)
     }
 }
@@ -1,3 +1,33 @@
+use actix_web::{dev::ServiceRequest, http::header::AUTHORIZATION, web, HttpMessage};
+
+use crate::{context::ApiRestCtx, request_id::RequestId};
+
 pub fn logger_format() -> &'static str {
-    return "🌐 ApiRestServer: %a \"%r\" %s %b \"%{Referer}i\" \"%{User-Agent}i\" %T";
+    return "🌐 ApiRestServer: [%{request_id}xi] %a \"%r\" %s %b \"%{Referer}i\" \"%{User-Agent}i\" %T token=%{token_id}xi";
+}
+
+pub fn logger_request_id(req: &ServiceRequest) -> String {
+    match req.extensions().get::<RequestId>() {
+        Some(request_id) => request_id.as_str().to_owned(),
+        None => "-".to_owned(),
+    }
+}
+
+// The signature is verified so a forged token can't put an arbitrary id in the log, but the
+// revocation list is not consulted since that would cost a database round trip per request
+pub fn logger_token_id(req: &ServiceRequest) -> String {
+    let ctx = match req.app_data::<web::Data<ApiRestCtx>>() {
+        Some(ctx) => ctx,
+        None => return "-".to_owned(),
+    };
+    match req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| ctx.token().jwt().decode(token).ok())
+    {
+        Some(claim) => claim.jti().to_string(),
+        None => "-".to_owned(),
+    }
 }
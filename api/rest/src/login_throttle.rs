@@ -0,0 +1,326 @@
+use std::{net::IpAddr, sync::Mutex};
+
+use ahash::{HashMap, HashMapExt};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Duration, Utc};
+use rand::{thread_rng, Rng};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+const EMAIL_FREE_ATTEMPTS: u32 = 5;
+const IP_FREE_ATTEMPTS: u32 = 20;
+const BASE_LOCKOUT_SECS: i64 = 30;
+const MAX_LOCKOUT_SECS: i64 = 3600;
+const MAX_UNLOCK_ATTEMPTS: u32 = 3;
+const MAX_RESET_CODE_ATTEMPTS: u32 = 5;
+const RESET_CODE_ATTEMPTS_TTL_SECS: i64 = 86400;
+
+pub struct LoginThrottle {
+    emails: Mutex<HashMap<String, LoginAttempt>>,
+    ips: Mutex<HashMap<IpAddr, LoginAttempt>>,
+    reset_codes: Mutex<HashMap<Uuid, LoginAttempt>>,
+}
+
+impl LoginThrottle {
+    pub fn new() -> Self {
+        Self {
+            emails: Mutex::new(HashMap::new()),
+            ips: Mutex::new(HashMap::new()),
+            reset_codes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn check(&self, email: &str, ip: &Option<IpAddr>) -> Result<()> {
+        let now = Utc::now();
+        let mut locked_until = self
+            .emails
+            .lock()
+            .unwrap()
+            .get(&email.to_lowercase())
+            .and_then(|attempt| attempt.locked_until(&now));
+        if let Some(ip_locked_until) = self.ip_locked_until(ip, &now) {
+            locked_until = Some(locked_until.map_or(ip_locked_until, |locked_until| {
+                locked_until.max(ip_locked_until)
+            }));
+        }
+        match locked_until {
+            Some(locked_until) => Err(Error::msg(format!(
+                "Too many failed sign-in attempts. Try again in {} seconds",
+                (locked_until - now).num_seconds().max(1)
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    // Returns an unlock code when this failure is the one that locks the email
+    pub fn record_failure(&self, email: &str, ip: &Option<IpAddr>) -> Option<String> {
+        let now = Utc::now();
+        let unlock_code = {
+            let mut emails = self.emails.lock().unwrap();
+            emails.retain(|_, attempt| !attempt.is_stale(&now));
+            let attempt = emails.entry(email.to_lowercase()).or_default();
+            attempt.fail(&now, &EMAIL_FREE_ATTEMPTS);
+            if attempt.failures == EMAIL_FREE_ATTEMPTS {
+                let code = thread_rng().gen_range(100000..=999999).to_string();
+                attempt.unlock_code = Some(code.clone());
+                attempt.unlock_failures = 0;
+                Some(code)
+            } else {
+                None
+            }
+        };
+        self.record_ip_failure(ip, &now);
+        unlock_code
+    }
+
+    pub fn record_success(&self, email: &str) {
+        self.emails.lock().unwrap().remove(&email.to_lowercase());
+    }
+
+    // Wrong codes count against the IP like wrong passwords, and the code stops working after a few
+    // misses so the lockout can't be sidestepped by guessing it
+    pub fn unlock(&self, email: &str, code: &str, ip: &Option<IpAddr>) -> Result<()> {
+        let now = Utc::now();
+        if let Some(locked_until) = self.ip_locked_until(ip, &now) {
+            return Err(Error::msg(format!(
+                "Too many failed attempts. Try again in {} seconds",
+                (locked_until - now).num_seconds().max(1)
+            )));
+        }
+        {
+            let mut emails = self.emails.lock().unwrap();
+            let email = email.to_lowercase();
+            if let Some(attempt) = emails.get_mut(&email) {
+                if let Some(unlock_code) = &attempt.unlock_code {
+                    if bool::from(unlock_code.as_bytes().ct_eq(code.as_bytes())) {
+                        emails.remove(&email);
+                        return Ok(());
+                    }
+                    attempt.unlock_failures += 1;
+                    if attempt.unlock_failures >= MAX_UNLOCK_ATTEMPTS {
+                        attempt.unlock_code = None;
+                    }
+                }
+            }
+        }
+        self.record_ip_failure(ip, &now);
+        Err(Error::msg("Invalid unlock code"))
+    }
+
+    // A password reset stops accepting codes, even the right one, after a few wrong ones
+    pub fn verify_reset_code(&self, reset_id: &Uuid, expected: &str, code: &str) -> Result<()> {
+        let now = Utc::now();
+        let mut reset_codes = self.reset_codes.lock().unwrap();
+        reset_codes.retain(|_, attempt| {
+            attempt.last_failed_at.is_some_and(|last_failed_at| {
+                now - last_failed_at <= Duration::seconds(RESET_CODE_ATTEMPTS_TTL_SECS)
+            })
+        });
+        if reset_codes
+            .get(reset_id)
+            .is_some_and(|attempt| attempt.failures >= MAX_RESET_CODE_ATTEMPTS)
+        {
+            return Err(Error::msg(
+                "Too many wrong codes. Please request another password reset",
+            ));
+        }
+        if bool::from(expected.as_bytes().ct_eq(code.as_bytes())) {
+            reset_codes.remove(reset_id);
+            return Ok(());
+        }
+        let attempt = reset_codes.entry(*reset_id).or_default();
+        attempt.failures += 1;
+        attempt.last_failed_at = Some(now);
+        match attempt.failures >= MAX_RESET_CODE_ATTEMPTS {
+            true => Err(Error::msg(
+                "Too many wrong codes. Please request another password reset",
+            )),
+            false => Err(Error::msg("Wrong code")),
+        }
+    }
+
+    pub fn is_reset_exhausted(&self, reset_id: &Uuid) -> bool {
+        self.reset_codes
+            .lock()
+            .unwrap()
+            .get(reset_id)
+            .is_some_and(|attempt| attempt.failures >= MAX_RESET_CODE_ATTEMPTS)
+    }
+
+    fn ip_locked_until(&self, ip: &Option<IpAddr>, now: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        ip.and_then(|ip| {
+            self.ips
+                .lock()
+                .unwrap()
+                .get(&ip)
+                .and_then(|attempt| attempt.locked_until(now))
+        })
+    }
+
+    fn record_ip_failure(&self, ip: &Option<IpAddr>, now: &DateTime<Utc>) {
+        if let Some(ip) = ip {
+            let mut ips = self.ips.lock().unwrap();
+            ips.retain(|_, attempt| !attempt.is_stale(now));
+            ips.entry(*ip).or_default().fail(now, &IP_FREE_ATTEMPTS);
+        }
+    }
+}
+
+#[derive(Default)]
+struct LoginAttempt {
+    failures: u32,
+    last_failed_at: Option<DateTime<Utc>>,
+    locked_until: Option<DateTime<Utc>>,
+    unlock_code: Option<String>,
+    unlock_failures: u32,
+}
+
+impl LoginAttempt {
+    fn fail(&mut self, now: &DateTime<Utc>, free_attempts: &u32) {
+        self.failures += 1;
+        self.last_failed_at = Some(*now);
+        if self.failures >= *free_attempts {
+            let exponent = (self.failures - free_attempts).min(16);
+            let lockout_secs = (BASE_LOCKOUT_SECS << exponent).min(MAX_LOCKOUT_SECS);
+            self.locked_until = Some(*now + Duration::seconds(lockout_secs));
+        }
+    }
+
+    fn locked_until(&self, now: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.locked_until.filter(|locked_until| locked_until > now)
+    }
+
+    fn is_stale(&self, now: &DateTime<Utc>) -> bool {
+        match self.last_failed_at {
+            Some(last_failed_at) => {
+                self.locked_until(now).is_none()
+                    && *now - last_failed_at > Duration::seconds(MAX_LOCKOUT_SECS)
+            }
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use super::{
+        LoginAttempt, LoginThrottle, BASE_LOCKOUT_SECS, EMAIL_FREE_ATTEMPTS, IP_FREE_ATTEMPTS,
+        MAX_LOCKOUT_SECS, MAX_RESET_CODE_ATTEMPTS, MAX_UNLOCK_ATTEMPTS,
+    };
+
+    fn lock(throttle: &LoginThrottle, email: &str) -> String {
+        let mut unlock_code = None;
+        for _ in 0..EMAIL_FREE_ATTEMPTS {
+            unlock_code = throttle.record_failure(email, &None);
+        }
+        unlock_code.unwrap()
+    }
+
+    #[test]
+    fn email_is_locked_after_the_free_attempts() {
+        let throttle = LoginThrottle::new();
+        for _ in 1..EMAIL_FREE_ATTEMPTS {
+            assert!(throttle.record_failure("a@example.com", &None).is_none());
+            assert!(throttle.check("a@example.com", &None).is_ok());
+        }
+        assert!(throttle.record_failure("a@example.com", &None).is_some());
+        assert!(throttle.check("A@example.com", &None).is_err());
+        assert!(throttle.check("b@example.com", &None).is_ok());
+    }
+
+    #[test]
+    fn success_forgets_earlier_failures() {
+        let throttle = LoginThrottle::new();
+        for _ in 1..EMAIL_FREE_ATTEMPTS {
+            throttle.record_failure("a@example.com", &None);
+        }
+        throttle.record_success("a@example.com");
+        assert!(throttle.record_failure("a@example.com", &None).is_none());
+        assert!(throttle.check("a@example.com", &None).is_ok());
+    }
+
+    #[test]
+    fn lockout_doubles_up_to_the_cap() {
+        let now = Utc::now();
+        let mut attempt = LoginAttempt::default();
+        let mut lockout_secs = Vec::new();
+        for _ in 0..EMAIL_FREE_ATTEMPTS + 10 {
+            attempt.fail(&now, &EMAIL_FREE_ATTEMPTS);
+            lockout_secs.push(
+                attempt
+                    .locked_until(&now)
+                    .map(|locked_until| (locked_until - now).num_seconds()),
+            );
+        }
+        let free_attempts = EMAIL_FREE_ATTEMPTS as usize - 1;
+        assert!(lockout_secs[..free_attempts].iter().all(Option::is_none));
+        assert_eq!(lockout_secs[free_attempts], Some(BASE_LOCKOUT_SECS));
+        assert_eq!(lockout_secs[free_attempts + 1], Some(BASE_LOCKOUT_SECS * 2));
+        assert_eq!(lockout_secs.last().unwrap(), &Some(MAX_LOCKOUT_SECS));
+        assert!(attempt
+            .locked_until(&(now + Duration::seconds(MAX_LOCKOUT_SECS)))
+            .is_none());
+    }
+
+    #[test]
+    fn unlock_accepts_the_code() {
+        let throttle = LoginThrottle::new();
+        let code = lock(&throttle, "a@example.com");
+        assert!(throttle.check("a@example.com", &None).is_err());
+        assert!(throttle.unlock("A@example.com", &code, &None).is_ok());
+        assert!(throttle.check("a@example.com", &None).is_ok());
+    }
+
+    #[test]
+    fn unlock_code_is_invalidated_after_failed_attempts() {
+        let throttle = LoginThrottle::new();
+        let code = lock(&throttle, "a@example.com");
+        for _ in 0..MAX_UNLOCK_ATTEMPTS {
+            assert!(throttle.unlock("a@example.com", "000000", &None).is_err());
+        }
+        assert!(throttle.unlock("a@example.com", &code, &None).is_err());
+        assert!(throttle.check("a@example.com", &None).is_err());
+    }
+
+    #[test]
+    fn failed_unlocks_count_against_the_ip() {
+        let throttle = LoginThrottle::new();
+        let ip = Some("192.0.2.1".parse().unwrap());
+        for _ in 0..IP_FREE_ATTEMPTS {
+            let _ = throttle.unlock("a@example.com", "000000", &ip);
+        }
+        assert!(throttle.check("b@example.com", &ip).is_err());
+        assert!(throttle.unlock("a@example.com", "000000", &ip).is_err());
+    }
+
+    #[test]
+    fn reset_code_is_rejected_after_failed_attempts() {
+        let throttle = LoginThrottle::new();
+        let reset_id = Uuid::now_v7();
+        for _ in 0..MAX_RESET_CODE_ATTEMPTS {
+            assert!(throttle
+                .verify_reset_code(&reset_id, "123456", "000000")
+                .is_err());
+        }
+        assert!(throttle.is_reset_exhausted(&reset_id));
+        assert!(throttle
+            .verify_reset_code(&reset_id, "123456", "123456")
+            .is_err());
+    }
+
+    #[test]
+    fn reset_code_is_accepted_before_the_limit() {
+        let throttle = LoginThrottle::new();
+        let reset_id = Uuid::now_v7();
+        assert!(throttle
+            .verify_reset_code(&reset_id, "123456", "000000")
+            .is_err());
+        assert!(throttle
+            .verify_reset_code(&reset_id, "123456", "123456")
+            .is_ok());
+        assert!(!throttle.is_reset_exhausted(&reset_id));
+    }
+}
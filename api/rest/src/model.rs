@@ -2,13 +2,26 @@ use actix_header::actix_header;
 use actix_web::{http::StatusCode, HttpResponse, HttpResponseBuilder};
 use hb_error::Error;
 use serde::Serialize;
+use validator::ValidationErrors;
 
 pub mod admin;
+pub mod admin_session;
+pub mod audit_log;
 pub mod auth;
+pub mod backup;
 pub mod collection;
+pub mod collection_plugin;
+pub mod health;
+pub mod ingest;
+pub mod job;
+pub mod log_filter;
 pub mod project;
+pub mod project_member;
+pub mod project_transfer;
+pub mod project_usage;
 pub mod record;
 pub mod token;
+pub mod user;
 
 #[actix_header("Authorization")]
 pub struct TokenReqHeader(String);
@@ -50,7 +63,7 @@ impl Response {
         match serde_json::to_value(data) {
             Ok(data) => HttpResponseBuilder::new(*status_code).json(Self {
                 error: None,
-                pagination: *pagination,
+                pagination: pagination.clone(),
                 data: Some(data),
             }),
             Err(err) => {
@@ -80,30 +93,133 @@ impl Response {
                     None => "Unknown".to_owned(),
                 },
                 message: message.to_owned(),
+                fields: None,
             }),
             pagination: None,
             data: None,
         })
     }
+
+    pub fn error_fields(
+        status_code: &StatusCode,
+        message: &str,
+        fields: &[FieldErrorRes],
+    ) -> HttpResponse {
+        hb_log::error(None, message);
+
+        HttpResponseBuilder::new(*status_code).json(Self {
+            error: Some(ErrorRes {
+                status: match status_code.canonical_reason() {
+                    Some(status_code) => status_code.to_owned(),
+                    None => "Unknown".to_owned(),
+                },
+                message: message.to_owned(),
+                fields: Some(fields.to_vec()),
+            }),
+            pagination: None,
+            data: None,
+        })
+    }
+
+    pub fn error_field(
+        status_code: &StatusCode,
+        field: &str,
+        code: &str,
+        message: &str,
+    ) -> HttpResponse {
+        Self::error_fields(
+            status_code,
+            &format!("Error in field '{field}': {message}"),
+            &[FieldErrorRes::new(field, code, message)],
+        )
+    }
+
+    pub fn error_validation(err: &ValidationErrors) -> HttpResponse {
+        let mut fields = err
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                errors.iter().map(move |error| {
+                    FieldErrorRes::new(
+                        field,
+                        &error.code,
+                        &match &error.message {
+                            Some(message) => message.to_string(),
+                            None => format!("Value failed the '{}' check", error.code),
+                        },
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        fields.sort_by(|a, b| a.field.cmp(&b.field));
+
+        Self::error_fields(&StatusCode::BAD_REQUEST, &err.to_string(), &fields)
+    }
 }
 
 #[derive(Serialize)]
 pub struct ErrorRes {
     status: String,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<Vec<FieldErrorRes>>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct FieldErrorRes {
+    field: String,
+    code: String,
+    message: String,
+}
+
+impl FieldErrorRes {
+    pub fn new(field: &str, code: &str, message: &str) -> Self {
+        Self {
+            field: field.to_owned(),
+            code: code.to_owned(),
+            message: message.to_owned(),
+        }
+    }
 }
 
-#[derive(Serialize, Clone, Copy)]
+#[derive(Serialize, Clone)]
 pub struct PaginationRes {
     count: usize,
-    total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_counts: Option<Vec<GroupCountRes>>,
 }
 
 impl PaginationRes {
-    pub fn new(count: &usize, total: &usize) -> Self {
+    pub fn new(
+        count: &usize,
+        total: &Option<usize>,
+        next_cursor: &Option<String>,
+        group_counts: &Option<Vec<GroupCountRes>>,
+    ) -> Self {
         Self {
             count: *count,
             total: *total,
+            next_cursor: next_cursor.clone(),
+            group_counts: group_counts.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct GroupCountRes {
+    values: serde_json::Map<String, serde_json::Value>,
+    count: i64,
+}
+
+impl GroupCountRes {
+    pub fn new(values: &serde_json::Map<String, serde_json::Value>, count: &i64) -> Self {
+        Self {
+            values: values.clone(),
+            count: *count,
         }
     }
 }
@@ -6,6 +6,7 @@ use validator::Validate;
 #[derive(Deserialize, Validate)]
 pub struct UpdateOneAdminReqJson {
     password: Option<String>,
+    role: Option<String>,
 }
 
 impl UpdateOneAdminReqJson {
@@ -13,8 +14,12 @@ impl UpdateOneAdminReqJson {
         &self.password
     }
 
+    pub fn role(&self) -> &Option<String> {
+        &self.role
+    }
+
     pub fn is_all_none(&self) -> bool {
-        self.password.is_none()
+        self.password.is_none() && self.role.is_none()
     }
 }
 
@@ -24,6 +29,7 @@ pub struct AdminResJson {
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     email: String,
+    role: String,
 }
 
 impl AdminResJson {
@@ -32,12 +38,14 @@ impl AdminResJson {
         created_at: &DateTime<Utc>,
         updated_at: &DateTime<Utc>,
         email: &str,
+        role: &str,
     ) -> Self {
         Self {
             id: *id,
             created_at: *created_at,
             updated_at: *updated_at,
             email: email.to_owned(),
+            role: role.to_owned(),
         }
     }
 }
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct DeleteOneAdminSessionReqPath {
+    session_id: Uuid,
+}
+
+impl DeleteOneAdminSessionReqPath {
+    pub fn session_id(&self) -> &Uuid {
+        &self.session_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct AdminSessionResJson {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip: Option<String>,
+    expired_at: DateTime<Utc>,
+    current: bool,
+}
+
+impl AdminSessionResJson {
+    pub fn new(
+        id: &Uuid,
+        created_at: &DateTime<Utc>,
+        updated_at: &DateTime<Utc>,
+        user_agent: &Option<String>,
+        ip: &Option<String>,
+        expired_at: &DateTime<Utc>,
+        current: &bool,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            user_agent: user_agent.clone(),
+            ip: ip.clone(),
+            expired_at: *expired_at,
+            current: *current,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteAdminSessionResJson {
+    id: Uuid,
+}
+
+impl DeleteAdminSessionResJson {
+    pub fn new(id: &Uuid) -> Self {
+        Self { id: *id }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteManyAdminSessionResJson {
+    ids: Vec<Uuid>,
+}
+
+impl DeleteManyAdminSessionResJson {
+    pub fn new(ids: &[Uuid]) -> Self {
+        Self { ids: ids.to_vec() }
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct FindManyProjectAuditLogReqPath {
+    project_id: Uuid,
+}
+
+impl FindManyProjectAuditLogReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct AuditLogResJson {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    admin_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip: Option<String>,
+    action: String,
+    resource: String,
+    resource_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<Value>,
+}
+
+impl AuditLogResJson {
+    pub fn new(
+        id: &Uuid,
+        created_at: &DateTime<Utc>,
+        admin_id: &Uuid,
+        project_id: &Option<Uuid>,
+        ip: &Option<String>,
+        action: &str,
+        resource: &str,
+        resource_id: &Uuid,
+        before: &Option<Value>,
+        after: &Option<Value>,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            admin_id: *admin_id,
+            project_id: *project_id,
+            ip: ip.clone(),
+            action: action.to_owned(),
+            resource: resource.to_owned(),
+            resource_id: *resource_id,
+            before: before.clone(),
+            after: after.clone(),
+        }
+    }
+}
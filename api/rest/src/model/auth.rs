@@ -1,4 +1,6 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 use validator::Validate;
 
@@ -40,6 +42,7 @@ pub struct PasswordBasedReqJson {
     #[validate(email)]
     email: String,
     password: String,
+    mfa_code: Option<String>,
 }
 
 impl PasswordBasedReqJson {
@@ -50,17 +53,37 @@ impl PasswordBasedReqJson {
     pub fn password(&self) -> &str {
         &self.password
     }
+
+    pub fn mfa_code(&self) -> &Option<String> {
+        &self.mfa_code
+    }
 }
 
 #[derive(Deserialize)]
 pub struct TokenBasedReqJson {
     token: String,
+    project_id: Option<Uuid>,
 }
 
 impl TokenBasedReqJson {
     pub fn token(&self) -> &str {
         &self.token
     }
+
+    pub fn project_id(&self) -> &Option<Uuid> {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RefreshTokenReqJson {
+    refresh_token: String,
+}
+
+impl RefreshTokenReqJson {
+    pub fn refresh_token(&self) -> &str {
+        &self.refresh_token
+    }
 }
 
 #[derive(Deserialize, Validate)]
@@ -75,6 +98,36 @@ impl RequestPasswordResetReqJson {
     }
 }
 
+#[derive(Deserialize, Validate)]
+pub struct UnlockAccountReqJson {
+    #[validate(email)]
+    email: String,
+    code: String,
+}
+
+impl UnlockAccountReqJson {
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+#[derive(Serialize)]
+pub struct UnlockAccountResJson {
+    email: String,
+}
+
+impl UnlockAccountResJson {
+    pub fn new(email: &str) -> Self {
+        Self {
+            email: email.to_owned(),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ConfirmPasswordResetReqJson {
     id: Uuid,
@@ -121,27 +174,97 @@ impl VerifyRegistrationResJson {
 #[derive(Serialize)]
 pub struct AuthTokenResJson {
     token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
 }
 
 impl AuthTokenResJson {
-    pub fn new(token: &str) -> Self {
+    pub fn new(token: &str, refresh_token: &Option<String>) -> Self {
         Self {
             token: token.to_owned(),
+            refresh_token: refresh_token.clone(),
         }
     }
 }
 
 #[derive(Serialize)]
-pub struct RequestPasswordResetResJson {
+pub struct LogoutResJson {
     id: Uuid,
 }
 
-impl RequestPasswordResetResJson {
+impl LogoutResJson {
     pub fn new(id: &Uuid) -> Self {
         Self { id: *id }
     }
 }
 
+#[derive(Deserialize)]
+pub struct MfaCodeReqJson {
+    code: String,
+}
+
+impl MfaCodeReqJson {
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+#[derive(Serialize)]
+pub struct EnrollMfaResJson {
+    secret: String,
+    uri: String,
+}
+
+impl EnrollMfaResJson {
+    pub fn new(secret: &str, uri: &str) -> Self {
+        Self {
+            secret: secret.to_owned(),
+            uri: uri.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct MfaResJson {
+    id: Uuid,
+    mfa_enabled: bool,
+}
+
+impl MfaResJson {
+    pub fn new(id: &Uuid, mfa_enabled: &bool) -> Self {
+        Self {
+            id: *id,
+            mfa_enabled: *mfa_enabled,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RevokeTokenResJson {
+    jti: Uuid,
+}
+
+impl RevokeTokenResJson {
+    pub fn new(jti: &Uuid) -> Self {
+        Self { jti: *jti }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RequestPasswordResetResJson {
+    id: Uuid,
+    expired_at: DateTime<Utc>,
+}
+
+impl RequestPasswordResetResJson {
+    pub fn new(id: &Uuid, expired_at: &DateTime<Utc>) -> Self {
+        Self {
+            id: *id,
+            expired_at: *expired_at,
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct ConfirmPasswordResetResJson {
     id: Uuid,
@@ -152,3 +275,70 @@ impl ConfirmPasswordResetResJson {
         Self { id: *id }
     }
 }
+
+#[derive(Deserialize, Validate)]
+pub struct PasskeyLoginReqJson {
+    #[validate(email)]
+    email: String,
+}
+
+impl PasskeyLoginReqJson {
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FinishPasskeyReqJson {
+    id: Uuid,
+    credential: Value,
+}
+
+impl FinishPasskeyReqJson {
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn credential(&self) -> &Value {
+        &self.credential
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeletePasskeyReqPath {
+    credential_id: String,
+}
+
+impl DeletePasskeyReqPath {
+    pub fn credential_id(&self) -> &str {
+        &self.credential_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct StartPasskeyResJson {
+    id: Uuid,
+    options: Value,
+}
+
+impl StartPasskeyResJson {
+    pub fn new(id: &Uuid, options: &Value) -> Self {
+        Self {
+            id: *id,
+            options: options.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PasskeyResJson {
+    credential_id: String,
+}
+
+impl PasskeyResJson {
+    pub fn new(credential_id: &str) -> Self {
+        Self {
+            credential_id: credential_id.to_owned(),
+        }
+    }
+}
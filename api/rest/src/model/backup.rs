@@ -0,0 +1,29 @@
+use hb_dao::backup::BackupSummary;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct RestoreResJson {
+    admins: usize,
+    projects: usize,
+    project_members: usize,
+    tokens: usize,
+    collections: usize,
+    users: usize,
+    records: usize,
+    skipped: usize,
+}
+
+impl RestoreResJson {
+    pub fn new(summary: &BackupSummary) -> Self {
+        Self {
+            admins: *summary.admins(),
+            projects: *summary.projects(),
+            project_members: *summary.project_members(),
+            tokens: *summary.tokens(),
+            collections: *summary.collections(),
+            users: *summary.users(),
+            records: *summary.records(),
+            skipped: *summary.skipped(),
+        }
+    }
+}
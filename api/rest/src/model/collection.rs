@@ -1,5 +1,10 @@
 use ahash::{HashMap, HashSet};
+use anyhow::Result;
 use chrono::{DateTime, Utc};
+use hb_dao::{
+    collection::{CollectionDao, SchemaFieldComputed, SchemaFieldValidation},
+    value::ColumnKind,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -19,6 +24,10 @@ pub struct InsertOneCollectionReqJson {
     name: String,
     schema_fields: HashMap<String, SchemaFieldPropsJson>,
     indexes: Option<HashSet<String>>,
+    partial_indexes: Option<HashMap<String, PartialIndexJson>>,
+    max_field_size: Option<i64>,
+    max_record_size: Option<i64>,
+    auto_delete_after_seconds: Option<i64>,
 }
 
 impl InsertOneCollectionReqJson {
@@ -33,6 +42,22 @@ impl InsertOneCollectionReqJson {
     pub fn indexes(&self) -> &Option<HashSet<String>> {
         &self.indexes
     }
+
+    pub fn partial_indexes(&self) -> &Option<HashMap<String, PartialIndexJson>> {
+        &self.partial_indexes
+    }
+
+    pub fn max_field_size(&self) -> &Option<i64> {
+        &self.max_field_size
+    }
+
+    pub fn max_record_size(&self) -> &Option<i64> {
+        &self.max_record_size
+    }
+
+    pub fn auto_delete_after_seconds(&self) -> &Option<i64> {
+        &self.auto_delete_after_seconds
+    }
 }
 
 #[derive(Deserialize)]
@@ -71,7 +96,29 @@ impl UpdateOneCollectionReqPath {
 pub struct UpdateOneCollectionReqJson {
     name: Option<String>,
     schema_fields: Option<HashMap<String, SchemaFieldPropsJson>>,
+    rename_fields: Option<HashMap<String, String>>,
     indexes: Option<HashSet<String>>,
+    partial_indexes: Option<HashMap<String, PartialIndexJson>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    max_field_size: Option<Option<i64>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    max_record_size: Option<Option<i64>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    auto_delete_after_seconds: Option<Option<i64>>,
+    transform: Option<bool>,
+    dry_run: Option<bool>,
 }
 
 impl UpdateOneCollectionReqJson {
@@ -87,8 +134,43 @@ impl UpdateOneCollectionReqJson {
         &self.indexes
     }
 
+    pub fn partial_indexes(&self) -> &Option<HashMap<String, PartialIndexJson>> {
+        &self.partial_indexes
+    }
+
+    pub fn rename_fields(&self) -> &Option<HashMap<String, String>> {
+        &self.rename_fields
+    }
+
+    pub fn max_field_size(&self) -> &Option<Option<i64>> {
+        &self.max_field_size
+    }
+
+    pub fn max_record_size(&self) -> &Option<Option<i64>> {
+        &self.max_record_size
+    }
+
+    pub fn auto_delete_after_seconds(&self) -> &Option<Option<i64>> {
+        &self.auto_delete_after_seconds
+    }
+
+    pub fn transform(&self) -> &Option<bool> {
+        &self.transform
+    }
+
+    pub fn dry_run(&self) -> &Option<bool> {
+        &self.dry_run
+    }
+
     pub fn is_all_none(&self) -> bool {
-        self.name.is_none() && self.schema_fields.is_none() && self.indexes.is_none()
+        self.name.is_none()
+            && self.schema_fields.is_none()
+            && self.rename_fields.is_none()
+            && self.indexes.is_none()
+            && self.partial_indexes.is_none()
+            && self.max_field_size.is_none()
+            && self.max_record_size.is_none()
+            && self.auto_delete_after_seconds.is_none()
     }
 }
 
@@ -128,6 +210,10 @@ pub struct CollectionResJson {
     name: String,
     schema_fields: HashMap<String, SchemaFieldPropsJson>,
     indexes: HashSet<String>,
+    partial_indexes: HashMap<String, PartialIndexJson>,
+    max_field_size: Option<i64>,
+    max_record_size: Option<i64>,
+    auto_delete_after_seconds: Option<i64>,
 }
 
 impl CollectionResJson {
@@ -139,6 +225,10 @@ impl CollectionResJson {
         name: &str,
         schema_fields: &HashMap<String, SchemaFieldPropsJson>,
         indexes: &HashSet<String>,
+        partial_indexes: &HashMap<String, PartialIndexJson>,
+        max_field_size: &Option<i64>,
+        max_record_size: &Option<i64>,
+        auto_delete_after_seconds: &Option<i64>,
     ) -> Self {
         Self {
             id: *id,
@@ -148,6 +238,166 @@ impl CollectionResJson {
             name: name.to_owned(),
             schema_fields: schema_fields.clone(),
             indexes: indexes.clone(),
+            partial_indexes: partial_indexes.clone(),
+            max_field_size: *max_field_size,
+            max_record_size: *max_record_size,
+            auto_delete_after_seconds: *auto_delete_after_seconds,
+        }
+    }
+
+    pub fn from_dao(collection_data: &CollectionDao) -> Self {
+        Self::new(
+            collection_data.id(),
+            collection_data.created_at(),
+            collection_data.updated_at(),
+            collection_data.project_id(),
+            collection_data.name(),
+            &collection_data
+                .schema_fields()
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.to_owned(),
+                        SchemaFieldPropsJson::new(
+                            value.kind().to_str(),
+                            &Some(*value.required()),
+                            &value.kind().enum_variants().cloned(),
+                            &SchemaFieldValidationJson::from_dao(value.validation()),
+                            &SchemaFieldComputedJson::from_dao(value.computed()),
+                        ),
+                    )
+                })
+                .collect(),
+            collection_data.indexes(),
+            &collection_data
+                .partial_indexes()
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.to_owned(),
+                        PartialIndexJson::new(
+                            value.fields(),
+                            &Some(*value.unique()),
+                            &Some(value.predicate().clone()),
+                        ),
+                    )
+                })
+                .collect(),
+            collection_data.max_field_size(),
+            collection_data.max_record_size(),
+            collection_data.auto_delete_after_seconds(),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExportOneCollectionReqPath {
+    project_id: Uuid,
+    collection_id: Uuid,
+}
+
+impl ExportOneCollectionReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct CollectionSchemaResJson {
+    name: String,
+    schema_fields: HashMap<String, SchemaFieldPropsJson>,
+    indexes: HashSet<String>,
+    partial_indexes: HashMap<String, PartialIndexJson>,
+    max_field_size: Option<i64>,
+    max_record_size: Option<i64>,
+    auto_delete_after_seconds: Option<i64>,
+}
+
+impl CollectionSchemaResJson {
+    pub fn new(
+        name: &str,
+        schema_fields: &HashMap<String, SchemaFieldPropsJson>,
+        indexes: &HashSet<String>,
+        partial_indexes: &HashMap<String, PartialIndexJson>,
+        max_field_size: &Option<i64>,
+        max_record_size: &Option<i64>,
+        auto_delete_after_seconds: &Option<i64>,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            schema_fields: schema_fields.clone(),
+            indexes: indexes.clone(),
+            partial_indexes: partial_indexes.clone(),
+            max_field_size: *max_field_size,
+            max_record_size: *max_record_size,
+            auto_delete_after_seconds: *auto_delete_after_seconds,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TransformCollectionResJson {
+    failures: Vec<TransformCollectionFailureResJson>,
+}
+
+impl TransformCollectionResJson {
+    pub fn new(failures: &[TransformCollectionFailureResJson]) -> Self {
+        Self {
+            failures: failures.to_vec(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct TransformCollectionFailureResJson {
+    field: String,
+    record_id: Uuid,
+    message: String,
+}
+
+impl TransformCollectionFailureResJson {
+    pub fn new(field: &str, record_id: &Uuid, message: &str) -> Self {
+        Self {
+            field: field.to_owned(),
+            record_id: *record_id,
+            message: message.to_owned(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct IndexSuggestionsCollectionReqPath {
+    project_id: Uuid,
+    collection_id: Uuid,
+}
+
+impl IndexSuggestionsCollectionReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct IndexSuggestionResJson {
+    field: String,
+    filter_count: u64,
+    order_count: u64,
+}
+
+impl IndexSuggestionResJson {
+    pub fn new(field: &str, filter_count: &u64, order_count: &u64) -> Self {
+        Self {
+            field: field.to_owned(),
+            filter_count: *filter_count,
+            order_count: *order_count,
         }
     }
 }
@@ -167,13 +417,28 @@ impl DeleteCollectionResJson {
 pub struct SchemaFieldPropsJson {
     kind: String,
     required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variants: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validation: Option<SchemaFieldValidationJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    computed: Option<SchemaFieldComputedJson>,
 }
 
 impl SchemaFieldPropsJson {
-    pub fn new(kind: &str, required: &Option<bool>) -> Self {
+    pub fn new(
+        kind: &str,
+        required: &Option<bool>,
+        variants: &Option<Vec<String>>,
+        validation: &Option<SchemaFieldValidationJson>,
+        computed: &Option<SchemaFieldComputedJson>,
+    ) -> Self {
         Self {
             kind: kind.to_owned(),
             required: *required,
+            variants: variants.clone(),
+            validation: validation.clone(),
+            computed: computed.clone(),
         }
     }
 
@@ -184,4 +449,111 @@ impl SchemaFieldPropsJson {
     pub fn required(&self) -> &Option<bool> {
         &self.required
     }
+
+    pub fn variants(&self) -> &Option<Vec<String>> {
+        &self.variants
+    }
+
+    pub fn validation(&self) -> &Option<SchemaFieldValidationJson> {
+        &self.validation
+    }
+
+    pub fn computed(&self) -> &Option<SchemaFieldComputedJson> {
+        &self.computed
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct SchemaFieldComputedJson {
+    op: String,
+    fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    separator: Option<String>,
+}
+
+impl SchemaFieldComputedJson {
+    pub fn from_dao(computed: &Option<SchemaFieldComputed>) -> Option<Self> {
+        computed.as_ref().map(|computed| Self {
+            op: computed.op().to_str().to_owned(),
+            fields: computed.fields().clone(),
+            separator: computed.separator().clone(),
+        })
+    }
+
+    pub fn to_dao(&self, kind: &ColumnKind) -> Result<SchemaFieldComputed> {
+        SchemaFieldComputed::new(kind, &self.op, &self.fields, &self.separator)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct SchemaFieldValidationJson {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_length: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_length: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+}
+
+impl SchemaFieldValidationJson {
+    pub fn from_dao(validation: &SchemaFieldValidation) -> Option<Self> {
+        if validation.is_empty() {
+            return None;
+        }
+        Some(Self {
+            min_length: *validation.min_length(),
+            max_length: *validation.max_length(),
+            min: *validation.min(),
+            max: *validation.max(),
+            pattern: validation.pattern().clone(),
+        })
+    }
+
+    pub fn to_dao(&self, kind: &ColumnKind) -> Result<SchemaFieldValidation> {
+        SchemaFieldValidation::new(
+            kind,
+            &self.min_length,
+            &self.max_length,
+            &self.min,
+            &self.max,
+            &self.pattern,
+        )
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct PartialIndexJson {
+    fields: Vec<String>,
+    unique: Option<bool>,
+    predicate: Option<HashMap<String, String>>,
+}
+
+impl PartialIndexJson {
+    pub fn new(
+        fields: &[String],
+        unique: &Option<bool>,
+        predicate: &Option<HashMap<String, String>>,
+    ) -> Self {
+        Self {
+            fields: fields.to_vec(),
+            unique: *unique,
+            predicate: predicate.clone(),
+        }
+    }
+
+    pub fn fields(&self) -> &Vec<String> {
+        &self.fields
+    }
+
+    pub fn unique(&self) -> &Option<bool> {
+        &self.unique
+    }
+
+    pub fn predicate(&self) -> &Option<HashMap<String, String>> {
+        &self.predicate
+    }
 }
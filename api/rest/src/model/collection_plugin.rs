@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use hb_dao::collection_plugin::CollectionPluginDao;
+use hb_plugin_wasm::WasmPlugin;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CollectionPluginReqPath {
+    project_id: Uuid,
+    collection_id: Uuid,
+}
+
+impl CollectionPluginReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct CollectionPluginResJson {
+    collection_id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    size: usize,
+    before: bool,
+    after: bool,
+}
+
+impl CollectionPluginResJson {
+    pub fn new(plugin_data: &CollectionPluginDao, plugin: &WasmPlugin) -> Self {
+        Self {
+            collection_id: *plugin_data.collection_id(),
+            created_at: *plugin_data.created_at(),
+            updated_at: *plugin_data.updated_at(),
+            size: plugin_data.module().len(),
+            before: *plugin.has_before(),
+            after: *plugin.has_after(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteCollectionPluginResJson {
+    collection_id: Uuid,
+}
+
+impl DeleteCollectionPluginResJson {
+    pub fn new(collection_id: &Uuid) -> Self {
+        Self {
+            collection_id: *collection_id,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct HealthResJson {
+    status: String,
+    checks: Vec<HealthCheckResJson>,
+}
+
+impl HealthResJson {
+    pub fn new(checks: &[HealthCheckResJson]) -> Self {
+        Self {
+            status: match checks.iter().all(|check| check.healthy) {
+                true => "ok".to_owned(),
+                false => "unavailable".to_owned(),
+            },
+            checks: checks.to_vec(),
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.checks.iter().all(|check| check.healthy)
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct HealthCheckResJson {
+    name: String,
+    healthy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl HealthCheckResJson {
+    pub fn new(name: &str, healthy: &bool, message: &Option<String>) -> Self {
+        Self {
+            name: name.to_owned(),
+            healthy: *healthy,
+            message: message.clone(),
+        }
+    }
+}
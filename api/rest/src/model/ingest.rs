@@ -0,0 +1,66 @@
+use actix_header::actix_header;
+use ahash::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[actix_header("X-Ingest-Token")]
+pub struct IngestTokenReqHeader(String);
+
+impl IngestTokenReqHeader {
+    pub fn get(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for IngestTokenReqHeader {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<IngestTokenReqHeader> for String {
+    fn from(s: IngestTokenReqHeader) -> Self {
+        s.0
+    }
+}
+
+#[derive(Deserialize)]
+pub struct IngestReqPath {
+    collection_id: Uuid,
+}
+
+impl IngestReqPath {
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum IngestReqJson {
+    One(HashMap<String, Value>),
+    Many(Vec<HashMap<String, Value>>),
+}
+
+impl IngestReqJson {
+    pub fn into_records(self) -> Vec<HashMap<String, Value>> {
+        match self {
+            Self::One(record) => vec![record],
+            Self::Many(records) => records,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct IngestResJson {
+    inserted: usize,
+}
+
+impl IngestResJson {
+    pub fn new(inserted: &usize) -> Self {
+        Self {
+            inserted: *inserted,
+        }
+    }
+}
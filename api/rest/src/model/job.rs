@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use hb_dao::scheduler::JobStatus;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct JobResJson {
+    name: String,
+    schedule: String,
+    running: bool,
+    next_run_at: Option<DateTime<Utc>>,
+    last_started_at: Option<DateTime<Utc>>,
+    last_finished_at: Option<DateTime<Utc>>,
+    last_succeeded: Option<bool>,
+    last_message: Option<String>,
+    run_count: u64,
+    failure_count: u64,
+}
+
+impl JobResJson {
+    pub fn new(status: &JobStatus) -> Self {
+        Self {
+            name: status.name().to_owned(),
+            schedule: status.schedule().to_owned(),
+            running: *status.running(),
+            next_run_at: *status.next_run_at(),
+            last_started_at: *status.last_started_at(),
+            last_finished_at: *status.last_finished_at(),
+            last_succeeded: *status.last_succeeded(),
+            last_message: status.last_message().clone(),
+            run_count: *status.run_count(),
+            failure_count: *status.failure_count(),
+        }
+    }
+}
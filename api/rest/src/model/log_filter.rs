@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct UpdateLogFilterReqJson {
+    #[validate(length(min = 1))]
+    filter: String,
+}
+
+impl UpdateLogFilterReqJson {
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+}
+
+#[derive(Serialize)]
+pub struct LogFilterResJson {
+    filter: String,
+}
+
+impl LogFilterResJson {
+    pub fn new(filter: &str) -> Self {
+        Self {
+            filter: filter.to_owned(),
+        }
+    }
+}
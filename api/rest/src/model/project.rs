@@ -1,16 +1,25 @@
+use ahash::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
+use super::collection::{PartialIndexJson, SchemaFieldPropsJson};
+
 #[derive(Deserialize)]
 pub struct InsertOneProjectReqJson {
     name: String,
+    token_claims: Option<Map<String, Value>>,
 }
 
 impl InsertOneProjectReqJson {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn token_claims(&self) -> &Option<Map<String, Value>> {
+        &self.token_claims
+    }
 }
 
 #[derive(Deserialize)]
@@ -38,6 +47,12 @@ impl UpdateOneProjectReqPath {
 #[derive(Deserialize)]
 pub struct UpdateOneProjectReqJson {
     name: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    token_claims: Option<Option<Map<String, Value>>>,
 }
 
 impl UpdateOneProjectReqJson {
@@ -45,8 +60,67 @@ impl UpdateOneProjectReqJson {
         &self.name
     }
 
+    pub fn token_claims(&self) -> &Option<Option<Map<String, Value>>> {
+        &self.token_claims
+    }
+
     pub fn is_all_none(&self) -> bool {
-        self.name.is_none()
+        self.name.is_none() && self.token_claims.is_none()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SuspendOneProjectReqPath {
+    project_id: Uuid,
+}
+
+impl SuspendOneProjectReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ResumeOneProjectReqPath {
+    project_id: Uuid,
+}
+
+impl ResumeOneProjectReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ArchiveOneProjectReqPath {
+    project_id: Uuid,
+}
+
+impl ArchiveOneProjectReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UnarchiveOneProjectReqPath {
+    project_id: Uuid,
+}
+
+impl UnarchiveOneProjectReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RestoreOneProjectReqPath {
+    project_id: Uuid,
+}
+
+impl RestoreOneProjectReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
     }
 }
 
@@ -67,6 +141,9 @@ pub struct ProjectResJson {
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     name: String,
+    suspended: bool,
+    archived: bool,
+    token_claims: Option<Map<String, Value>>,
 }
 
 impl ProjectResJson {
@@ -75,12 +152,100 @@ impl ProjectResJson {
         created_at: &DateTime<Utc>,
         updated_at: &DateTime<Utc>,
         name: &str,
+        suspended: &bool,
+        archived: &bool,
+        token_claims: &Option<Map<String, Value>>,
     ) -> Self {
         Self {
             id: *id,
             created_at: *created_at,
             updated_at: *updated_at,
             name: name.to_owned(),
+            suspended: *suspended,
+            archived: *archived,
+            token_claims: token_claims.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ApplyProjectConfigReqPath {
+    project_id: Uuid,
+}
+
+impl ApplyProjectConfigReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ApplyProjectConfigReqJson {
+    collections: HashMap<String, ProjectConfigCollectionJson>,
+    dry_run: Option<bool>,
+}
+
+impl ApplyProjectConfigReqJson {
+    pub fn collections(&self) -> &HashMap<String, ProjectConfigCollectionJson> {
+        &self.collections
+    }
+
+    pub fn dry_run(&self) -> &Option<bool> {
+        &self.dry_run
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProjectConfigCollectionJson {
+    schema_fields: HashMap<String, SchemaFieldPropsJson>,
+    indexes: Option<HashSet<String>>,
+    partial_indexes: Option<HashMap<String, PartialIndexJson>>,
+    max_field_size: Option<i64>,
+    max_record_size: Option<i64>,
+    auto_delete_after_seconds: Option<i64>,
+}
+
+impl ProjectConfigCollectionJson {
+    pub fn schema_fields(&self) -> &HashMap<String, SchemaFieldPropsJson> {
+        &self.schema_fields
+    }
+
+    pub fn indexes(&self) -> &Option<HashSet<String>> {
+        &self.indexes
+    }
+
+    pub fn partial_indexes(&self) -> &Option<HashMap<String, PartialIndexJson>> {
+        &self.partial_indexes
+    }
+
+    pub fn max_field_size(&self) -> &Option<i64> {
+        &self.max_field_size
+    }
+
+    pub fn max_record_size(&self) -> &Option<i64> {
+        &self.max_record_size
+    }
+
+    pub fn auto_delete_after_seconds(&self) -> &Option<i64> {
+        &self.auto_delete_after_seconds
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProjectConfigResJson {
+    dry_run: bool,
+    created: Vec<String>,
+    updated: Vec<String>,
+    deleted: Vec<String>,
+}
+
+impl ProjectConfigResJson {
+    pub fn new(dry_run: &bool, created: &[String], updated: &[String], deleted: &[String]) -> Self {
+        Self {
+            dry_run: *dry_run,
+            created: created.to_vec(),
+            updated: updated.to_vec(),
+            deleted: deleted.to_vec(),
         }
     }
 }
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Deserialize)]
+pub struct InsertOneProjectMemberReqPath {
+    project_id: Uuid,
+}
+
+impl InsertOneProjectMemberReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct InsertOneProjectMemberReqJson {
+    #[validate(email)]
+    email: String,
+    role: String,
+}
+
+impl InsertOneProjectMemberReqJson {
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateOneProjectMemberReqPath {
+    project_id: Uuid,
+    member_id: Uuid,
+}
+
+impl UpdateOneProjectMemberReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn member_id(&self) -> &Uuid {
+        &self.member_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct UpdateOneProjectMemberReqJson {
+    role: String,
+}
+
+impl UpdateOneProjectMemberReqJson {
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeleteOneProjectMemberReqPath {
+    project_id: Uuid,
+    member_id: Uuid,
+}
+
+impl DeleteOneProjectMemberReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn member_id(&self) -> &Uuid {
+        &self.member_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FindManyProjectMemberReqPath {
+    project_id: Uuid,
+}
+
+impl FindManyProjectMemberReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProjectMemberResJson {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    project_id: Uuid,
+    admin_id: Uuid,
+    role: String,
+}
+
+impl ProjectMemberResJson {
+    pub fn new(
+        id: &Uuid,
+        created_at: &DateTime<Utc>,
+        updated_at: &DateTime<Utc>,
+        project_id: &Uuid,
+        admin_id: &Uuid,
+        role: &str,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            project_id: *project_id,
+            admin_id: *admin_id,
+            role: role.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteProjectMemberResJson {
+    id: Uuid,
+}
+
+impl DeleteProjectMemberResJson {
+    pub fn new(id: &Uuid) -> Self {
+        Self { id: *id }
+    }
+}
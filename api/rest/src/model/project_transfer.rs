@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Deserialize)]
+pub struct InsertOneProjectTransferReqPath {
+    project_id: Uuid,
+}
+
+impl InsertOneProjectTransferReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct InsertOneProjectTransferReqJson {
+    #[validate(email)]
+    email: String,
+}
+
+impl InsertOneProjectTransferReqJson {
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AcceptProjectTransferReqPath {
+    transfer_id: Uuid,
+}
+
+impl AcceptProjectTransferReqPath {
+    pub fn transfer_id(&self) -> &Uuid {
+        &self.transfer_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeleteOneProjectTransferReqPath {
+    transfer_id: Uuid,
+}
+
+impl DeleteOneProjectTransferReqPath {
+    pub fn transfer_id(&self) -> &Uuid {
+        &self.transfer_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProjectTransferResJson {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    project_id: Uuid,
+    from_admin_id: Uuid,
+    to_admin_id: Uuid,
+}
+
+impl ProjectTransferResJson {
+    pub fn new(
+        id: &Uuid,
+        created_at: &DateTime<Utc>,
+        updated_at: &DateTime<Utc>,
+        project_id: &Uuid,
+        from_admin_id: &Uuid,
+        to_admin_id: &Uuid,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            project_id: *project_id,
+            from_admin_id: *from_admin_id,
+            to_admin_id: *to_admin_id,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteProjectTransferResJson {
+    id: Uuid,
+}
+
+impl DeleteProjectTransferResJson {
+    pub fn new(id: &Uuid) -> Self {
+        Self { id: *id }
+    }
+}
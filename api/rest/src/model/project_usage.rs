@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct FindManyProjectUsageReqPath {
+    project_id: Uuid,
+}
+
+impl FindManyProjectUsageReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProjectUsageResJson {
+    project_id: Uuid,
+    period: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    request_count: i64,
+    record_count: i64,
+    storage_bytes: i64,
+}
+
+impl ProjectUsageResJson {
+    pub fn new(
+        project_id: &Uuid,
+        period: &DateTime<Utc>,
+        updated_at: &DateTime<Utc>,
+        request_count: &i64,
+        record_count: &i64,
+        storage_bytes: &i64,
+    ) -> Self {
+        Self {
+            project_id: *project_id,
+            period: *period,
+            updated_at: *updated_at,
+            request_count: *request_count,
+            record_count: *record_count,
+            storage_bytes: *storage_bytes,
+        }
+    }
+}
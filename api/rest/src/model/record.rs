@@ -2,11 +2,11 @@ use ahash::HashMap;
 use anyhow::{Error, Result};
 use hb_dao::{
     collection::CollectionDao,
-    record::{RecordFilter, RecordFilters},
+    record::{RecordFilter, RecordFilterValue, RecordFilters},
     value::{ColumnKind, ColumnValue},
 };
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
 #[derive(Deserialize)]
@@ -71,6 +71,29 @@ impl UpdateOneRecordReqPath {
 
 pub type UpdateOneRecordReqJson = HashMap<String, Value>;
 
+#[derive(Deserialize)]
+pub struct UpsertOneRecordReqPath {
+    project_id: Uuid,
+    collection_id: Uuid,
+    record_id: Uuid,
+}
+
+impl UpsertOneRecordReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+
+    pub fn record_id(&self) -> &Uuid {
+        &self.record_id
+    }
+}
+
+pub type UpsertOneRecordReqJson = HashMap<String, Value>;
+
 #[derive(Deserialize)]
 pub struct DeleteOneRecordReqPath {
     project_id: Uuid,
@@ -114,6 +137,11 @@ pub struct FindManyRecordReqJson {
     group: Option<Vec<String>>,
     order: Option<Vec<FindManyRecordOrderReqJson>>,
     limit: Option<i32>,
+    sample: Option<i32>,
+    embed: Option<Vec<FindManyRecordEmbedReqJson>>,
+    with_total: Option<bool>,
+    with_cursor: Option<bool>,
+    cursor: Option<String>,
 }
 
 impl FindManyRecordReqJson {
@@ -132,6 +160,90 @@ impl FindManyRecordReqJson {
     pub fn limit(&self) -> &Option<i32> {
         &self.limit
     }
+
+    pub fn sample(&self) -> &Option<i32> {
+        &self.sample
+    }
+
+    pub fn embed(&self) -> &Option<Vec<FindManyRecordEmbedReqJson>> {
+        &self.embed
+    }
+
+    pub fn with_total(&self) -> &Option<bool> {
+        &self.with_total
+    }
+
+    pub fn with_cursor(&self) -> &Option<bool> {
+        &self.with_cursor
+    }
+
+    pub fn cursor(&self) -> &Option<String> {
+        &self.cursor
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CountManyRecordReqPath {
+    project_id: Uuid,
+    collection_id: Uuid,
+}
+
+impl CountManyRecordReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CountManyRecordReqJson {
+    filter: Option<FindManyRecordFiltersReqJson>,
+}
+
+impl CountManyRecordReqJson {
+    pub fn filter(&self) -> &Option<FindManyRecordFiltersReqJson> {
+        &self.filter
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DistinctManyRecordReqPath {
+    project_id: Uuid,
+    collection_id: Uuid,
+}
+
+impl DistinctManyRecordReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DistinctManyRecordReqJson {
+    field: String,
+    filter: Option<FindManyRecordFiltersReqJson>,
+    limit: Option<i32>,
+}
+
+impl DistinctManyRecordReqJson {
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn filter(&self) -> &Option<FindManyRecordFiltersReqJson> {
+        &self.filter
+    }
+
+    pub fn limit(&self) -> &Option<i32> {
+        &self.limit
+    }
 }
 
 #[derive(Deserialize)]
@@ -150,7 +262,7 @@ impl FindManyRecordFiltersReqJson {
                 Some(field) => match collection_data.schema_fields().get(field) {
                     Some(field) => Some(field.kind()),
                     None => match field.as_str() {
-                        "_id" => Some(&ColumnKind::Uuid),
+                        "_id" | "_created_by" => Some(&ColumnKind::Uuid),
                         _ => {
                             return Err(Error::msg(format!(
                                 "Field '{field}' is not exist in the collection",
@@ -161,11 +273,61 @@ impl FindManyRecordFiltersReqJson {
                 None => None,
             };
 
-            let value = if schema_field_kind.is_some() && f.value.is_some() {
-                match ColumnValue::from_serde_json(
-                    schema_field_kind.unwrap(),
-                    f.value.as_ref().unwrap(),
-                ) {
+            let insensitive = f.insensitive.unwrap_or(false);
+            if insensitive {
+                match schema_field_kind {
+                    Some(ColumnKind::String) | Some(ColumnKind::Enum(_)) => (),
+                    _ => {
+                        return Err(Error::msg(format!(
+                            "Filter with 'insensitive' is only supported on string fields, but found in field '{}'",
+                            f.field.as_deref().unwrap_or_default()
+                        )))
+                    }
+                }
+            }
+            if f.child.is_none() && RecordFilter::is_substring_operator(&f.op) {
+                match schema_field_kind {
+                    Some(ColumnKind::String) | Some(ColumnKind::Enum(_)) => (),
+                    _ => return Err(Error::msg(format!(
+                        "Operator '{}' is only supported on string fields, but found in field '{}'",
+                        f.op.to_uppercase(),
+                        f.field.as_deref().unwrap_or_default()
+                    ))),
+                }
+            }
+
+            let value = if schema_field_kind.is_some()
+                && f.value.is_some()
+                && !RecordFilter::is_null_operator(&f.op)
+            {
+                let kind = match RecordFilter::is_substring_operator(&f.op) {
+                    true => &ColumnKind::String,
+                    false => schema_field_kind.unwrap(),
+                };
+                let value = match f.value.as_ref().unwrap() {
+                    Value::Array(values)
+                        if RecordFilter::is_list_operator(&f.op)
+                            || RecordFilter::is_range_operator(&f.op) =>
+                    {
+                        values
+                            .iter()
+                            .map(|value| ColumnValue::from_serde_json(kind, value))
+                            .collect::<Result<Vec<_>>>()
+                            .map(RecordFilterValue::Multiple)
+                    }
+                    value if RecordFilter::is_list_operator(&f.op) => {
+                        ColumnValue::from_serde_json(kind, value)
+                            .map(|value| RecordFilterValue::Multiple(vec![value]))
+                    }
+                    _ if RecordFilter::is_range_operator(&f.op) => Err(Error::msg(format!(
+                        "Operator '{}' requires an array of two values",
+                        f.op.to_uppercase()
+                    ))),
+                    value => {
+                        ColumnValue::from_serde_json(kind, value).map(RecordFilterValue::Single)
+                    }
+                };
+                match value {
                     Ok(value) => Some(value),
                     Err(err) => {
                         return Err(Error::msg(format!(
@@ -187,6 +349,7 @@ impl FindManyRecordFiltersReqJson {
                 } else {
                     None
                 },
+                &insensitive,
             ));
         }
         Ok(RecordFilters::new(&filters))
@@ -199,6 +362,7 @@ pub struct FindManyRecordFilterReqJson {
     op: String,
     value: Option<Value>,
     child: Option<FindManyRecordFiltersReqJson>,
+    insensitive: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -217,14 +381,59 @@ impl FindManyRecordOrderReqJson {
     }
 }
 
+#[derive(Deserialize)]
+pub struct FindManyRecordEmbedReqJson {
+    field: String,
+    collection_id: Uuid,
+}
+
+impl FindManyRecordEmbedReqJson {
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RecordCursorJson {
+    collection_id: Uuid,
+    order: Vec<(String, String)>,
+    values: Vec<Value>,
+}
+
+impl RecordCursorJson {
+    pub fn new(collection_id: &Uuid, order: &[(String, String)], values: &[Value]) -> Self {
+        Self {
+            collection_id: *collection_id,
+            order: order.to_vec(),
+            values: values.to_vec(),
+        }
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+
+    pub fn order(&self) -> &Vec<(String, String)> {
+        &self.order
+    }
+
+    pub fn values(&self) -> &Vec<Value> {
+        &self.values
+    }
+}
+
 #[derive(Serialize)]
 pub struct RecordResJson {
     #[serde(flatten)]
-    data: HashMap<String, Value>,
+    data: Map<String, Value>,
 }
 
 impl RecordResJson {
-    pub fn new(data: &HashMap<String, Value>) -> Self {
+    pub fn new(data: &Map<String, Value>) -> Self {
         Self { data: data.clone() }
     }
 }
@@ -239,3 +448,96 @@ impl DeleteRecordResJson {
         Self { id: *id }
     }
 }
+
+#[derive(Deserialize)]
+pub struct ExportManyRecordReqPath {
+    project_id: Uuid,
+    collection_id: Uuid,
+}
+
+impl ExportManyRecordReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExportManyRecordReqJson {
+    filter: Option<FindManyRecordFiltersReqJson>,
+    format: Option<String>,
+}
+
+impl ExportManyRecordReqJson {
+    pub fn filter(&self) -> &Option<FindManyRecordFiltersReqJson> {
+        &self.filter
+    }
+
+    pub fn format(&self) -> &Option<String> {
+        &self.format
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ImportManyRecordReqPath {
+    project_id: Uuid,
+    collection_id: Uuid,
+}
+
+impl ImportManyRecordReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct ImportRecordResJson {
+    inserted: usize,
+    errors: Vec<ImportRecordErrorResJson>,
+}
+
+impl ImportRecordResJson {
+    pub fn new(inserted: &usize, errors: &[ImportRecordErrorResJson]) -> Self {
+        Self {
+            inserted: *inserted,
+            errors: errors.to_vec(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct ImportRecordErrorResJson {
+    row: usize,
+    message: String,
+}
+
+impl ImportRecordErrorResJson {
+    pub fn new(row: &usize, message: &str) -> Self {
+        Self {
+            row: *row,
+            message: message.to_owned(),
+        }
+    }
+
+    pub fn row(&self) -> &usize {
+        &self.row
+    }
+}
+
+#[derive(Serialize)]
+pub struct CountRecordResJson {
+    total: usize,
+}
+
+impl CountRecordResJson {
+    pub fn new(total: &usize) -> Self {
+        Self { total: *total }
+    }
+}
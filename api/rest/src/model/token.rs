@@ -1,7 +1,10 @@
-use ahash::HashMap;
-use anyhow::Result;
+use ahash::{HashMap, HashMapExt};
+use anyhow::{Error, Result};
 use chrono::{DateTime, Utc};
-use hb_dao::token::TokenRuleMethod;
+use hb_dao::{
+    expression::Expression,
+    token::{TokenDao, TokenRuleMethod, RULE_CONDITION_VARIABLES},
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -9,6 +12,8 @@ use uuid::Uuid;
 pub struct InsertOneTokenReqJson {
     rules: HashMap<Uuid, TokenRuleMethodJson>,
     expired_at: Option<DateTime<Utc>>,
+    allowed_cidrs: Option<Vec<String>>,
+    scopes: Option<Vec<String>>,
 }
 
 impl InsertOneTokenReqJson {
@@ -19,6 +24,14 @@ impl InsertOneTokenReqJson {
     pub fn expired_at(&self) -> &Option<DateTime<Utc>> {
         &self.expired_at
     }
+
+    pub fn allowed_cidrs(&self) -> &Option<Vec<String>> {
+        &self.allowed_cidrs
+    }
+
+    pub fn scopes(&self) -> &Option<Vec<String>> {
+        &self.scopes
+    }
 }
 
 #[derive(Deserialize)]
@@ -52,6 +65,18 @@ pub struct UpdateOneTokenReqJson {
         with = "::serde_with::rust::double_option"
     )]
     expired_at: Option<Option<DateTime<Utc>>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    allowed_cidrs: Option<Option<Vec<String>>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    scopes: Option<Option<Vec<String>>>,
 }
 
 impl UpdateOneTokenReqJson {
@@ -63,8 +88,73 @@ impl UpdateOneTokenReqJson {
         &self.expired_at
     }
 
+    pub fn allowed_cidrs(&self) -> &Option<Option<Vec<String>>> {
+        &self.allowed_cidrs
+    }
+
+    pub fn scopes(&self) -> &Option<Option<Vec<String>>> {
+        &self.scopes
+    }
+
     pub fn is_all_none(&self) -> bool {
-        self.rules.is_none() && self.expired_at.is_none()
+        self.rules.is_none()
+            && self.expired_at.is_none()
+            && self.allowed_cidrs.is_none()
+            && self.scopes.is_none()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ApplyRulesTokenReqPath {
+    token_id: Uuid,
+}
+
+impl ApplyRulesTokenReqPath {
+    pub fn token_id(&self) -> &Uuid {
+        &self.token_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ApplyRulesTokenReqJson {
+    project_id: Option<Uuid>,
+    collection_ids: Option<Vec<Uuid>>,
+    rule: TokenRuleMethodJson,
+}
+
+impl ApplyRulesTokenReqJson {
+    pub fn project_id(&self) -> &Option<Uuid> {
+        &self.project_id
+    }
+
+    pub fn collection_ids(&self) -> &Option<Vec<Uuid>> {
+        &self.collection_ids
+    }
+
+    pub fn rule(&self) -> &TokenRuleMethodJson {
+        &self.rule
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RotateTokenReqPath {
+    token_id: Uuid,
+}
+
+impl RotateTokenReqPath {
+    pub fn token_id(&self) -> &Uuid {
+        &self.token_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RotateTokenReqJson {
+    grace_period: Option<i64>,
+}
+
+impl RotateTokenReqJson {
+    pub fn grace_period(&self) -> &Option<i64> {
+        &self.grace_period
     }
 }
 
@@ -87,6 +177,8 @@ pub struct TokenResJson {
     token: String,
     rules: HashMap<Uuid, TokenRuleMethodJson>,
     expired_at: Option<DateTime<Utc>>,
+    allowed_cidrs: Option<Vec<String>>,
+    scopes: Option<Vec<String>>,
 }
 
 impl TokenResJson {
@@ -97,6 +189,8 @@ impl TokenResJson {
         token: &str,
         rules: &HashMap<Uuid, TokenRuleMethodJson>,
         expired_at: &Option<DateTime<Utc>>,
+        allowed_cidrs: &Option<Vec<String>>,
+        scopes: &Option<Vec<String>>,
     ) -> Self {
         Self {
             id: *id,
@@ -105,8 +199,27 @@ impl TokenResJson {
             token: token.to_owned(),
             rules: rules.clone(),
             expired_at: *expired_at,
+            allowed_cidrs: allowed_cidrs.clone(),
+            scopes: scopes.clone(),
         }
     }
+
+    pub fn from_dao(token_data: &TokenDao) -> Result<Self> {
+        let mut rules = HashMap::with_capacity(token_data.rules().len());
+        for (collection_id, rule) in token_data.rules() {
+            rules.insert(*collection_id, TokenRuleMethodJson::from_dao(rule)?);
+        }
+        Ok(Self::new(
+            token_data.id(),
+            token_data.created_at(),
+            token_data.updated_at(),
+            token_data.token(),
+            &rules,
+            token_data.expired_at(),
+            token_data.allowed_cidrs(),
+            token_data.scopes(),
+        ))
+    }
 }
 
 #[derive(Serialize)]
@@ -127,6 +240,7 @@ pub struct TokenRuleMethodJson {
     insert: Option<bool>,
     update: Option<bool>,
     delete: Option<bool>,
+    condition: Option<String>,
 }
 
 impl TokenRuleMethodJson {
@@ -137,10 +251,26 @@ impl TokenRuleMethodJson {
             insert: Some(*dao.insert()),
             update: Some(*dao.update()),
             delete: Some(*dao.delete()),
+            condition: dao.condition().clone(),
         })
     }
 
     pub fn to_dao(&self) -> Result<TokenRuleMethod> {
+        if let Some(condition) = &self.condition {
+            let expression = match Expression::parse(condition, &RULE_CONDITION_VARIABLES) {
+                Ok(expression) => expression,
+                Err(err) => return Err(Error::msg(format!("Invalid rule condition: {err}"))),
+            };
+            // Listing records applies the condition as a filter, so it must be expressible as one
+            if self.find_many == Some(true) {
+                if let Err(err) = expression.to_filter("record", None) {
+                    return Err(Error::msg(format!(
+                        "Rule condition can't be used with find_many: {err}"
+                    )));
+                }
+            }
+        }
+
         Ok(TokenRuleMethod::new(
             &match self.find_one {
                 Some(find_one) => find_one,
@@ -162,6 +292,7 @@ impl TokenRuleMethodJson {
                 Some(delete) => delete,
                 None => false,
             },
+            &self.condition,
         ))
     }
 }
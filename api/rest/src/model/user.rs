@@ -0,0 +1,219 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct RegisterUserReqJson {
+    token: String,
+    project_id: Uuid,
+    #[validate(email)]
+    email: String,
+    password: String,
+}
+
+impl RegisterUserReqJson {
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct UserPasswordBasedReqJson {
+    token: String,
+    project_id: Uuid,
+    #[validate(email)]
+    email: String,
+    password: String,
+}
+
+impl UserPasswordBasedReqJson {
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AnonymousUserReqJson {
+    token: String,
+    project_id: Uuid,
+}
+
+impl AnonymousUserReqJson {
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct UpgradeUserReqJson {
+    #[validate(email)]
+    email: String,
+    password: String,
+}
+
+impl UpgradeUserReqJson {
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RequestUserPasswordResetReqJson {
+    project_id: Uuid,
+    #[validate(email)]
+    email: String,
+}
+
+impl RequestUserPasswordResetReqJson {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmUserPasswordResetReqJson {
+    id: Uuid,
+    code: String,
+    password: String,
+}
+
+impl ConfirmUserPasswordResetReqJson {
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FindManyUserReqPath {
+    project_id: Uuid,
+}
+
+impl FindManyUserReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeleteOneUserReqPath {
+    project_id: Uuid,
+    user_id: Uuid,
+}
+
+impl DeleteOneUserReqPath {
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+}
+
+#[derive(Serialize)]
+pub struct UserResJson {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    project_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    anonymous: bool,
+}
+
+impl UserResJson {
+    pub fn new(
+        id: &Uuid,
+        created_at: &DateTime<Utc>,
+        updated_at: &DateTime<Utc>,
+        project_id: &Uuid,
+        email: &Option<String>,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            project_id: *project_id,
+            email: email.clone(),
+            anonymous: email.is_none(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RequestUserPasswordResetResJson {
+    id: Uuid,
+}
+
+impl RequestUserPasswordResetResJson {
+    pub fn new(id: &Uuid) -> Self {
+        Self { id: *id }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ConfirmUserPasswordResetResJson {
+    id: Uuid,
+}
+
+impl ConfirmUserPasswordResetResJson {
+    pub fn new(id: &Uuid) -> Self {
+        Self { id: *id }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeleteUserResJson {
+    id: Uuid,
+}
+
+impl DeleteUserResJson {
+    pub fn new(id: &Uuid) -> Self {
+        Self { id: *id }
+    }
+}
@@ -0,0 +1,132 @@
+use std::{fmt, sync::Arc};
+
+use actix_web::{http::StatusCode, HttpResponse};
+use anyhow::{Error, Result};
+use hb_dao::{collection::CollectionDao, collection_plugin::CollectionPluginDao};
+use hb_event::RecordEventKind;
+use hb_plugin_wasm::{BeforeHook, WasmPlugin};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{context::ApiRestCtx, model::Response};
+
+pub enum PluginError {
+    Rejected(String),
+    Internal(Error),
+}
+
+impl PluginError {
+    pub fn to_response(&self) -> HttpResponse {
+        match self {
+            Self::Rejected(message) => Response::error_raw(&StatusCode::BAD_REQUEST, message),
+            Self::Internal(err) => {
+                Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        }
+    }
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rejected(message) => write!(f, "{message}"),
+            Self::Internal(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<Error> for PluginError {
+    fn from(err: Error) -> Self {
+        Self::Internal(err)
+    }
+}
+
+#[derive(Serialize)]
+struct HookInput<'a, T: Serialize> {
+    kind: &'a RecordEventKind,
+    project_id: &'a Uuid,
+    collection_id: &'a Uuid,
+    record_id: &'a Option<Uuid>,
+    record: Option<&'a T>,
+}
+
+pub async fn load(ctx: &ApiRestCtx, collection_id: &Uuid) -> Result<Option<Arc<WasmPlugin>>> {
+    if let Some(plugin) = ctx.plugin().cached(collection_id) {
+        return Ok(plugin);
+    }
+
+    let plugin = match CollectionPluginDao::db_select(ctx.dao().db(), collection_id).await? {
+        Some(plugin_data) => Some(Arc::new(
+            ctx.plugin()
+                .runtime()
+                .compile(&collection_id.to_string(), plugin_data.module())?,
+        )),
+        None => None,
+    };
+    ctx.plugin().cache(collection_id, plugin.clone());
+    Ok(plugin)
+}
+
+// Lets bulk writes skip building each record's JSON when nothing would receive it
+pub async fn has_after(ctx: &ApiRestCtx, collection_data: &CollectionDao) -> bool {
+    matches!(load(ctx, collection_data.id()).await, Ok(Some(plugin)) if *plugin.has_after())
+}
+
+// Lets the collection's plugin reject the fields about to be written or replace them; whatever it
+// returns still goes through the schema checks
+pub async fn before<T: Serialize + FromIterator<(String, Value)>>(
+    ctx: &ApiRestCtx,
+    collection_data: &CollectionDao,
+    kind: &RecordEventKind,
+    record_id: &Option<Uuid>,
+    record: &mut T,
+) -> Result<(), PluginError> {
+    let plugin = match load(ctx, collection_data.id()).await? {
+        Some(plugin) if *plugin.has_before() => plugin,
+        _ => return Ok(()),
+    };
+
+    match plugin.before(&HookInput {
+        kind,
+        project_id: collection_data.project_id(),
+        collection_id: collection_data.id(),
+        record_id,
+        record: Some(&*record),
+    })? {
+        BeforeHook::Continue(Some(replacement)) => {
+            *record = replacement.into_iter().collect();
+            Ok(())
+        }
+        BeforeHook::Continue(None) => Ok(()),
+        BeforeHook::Reject(message) => Err(PluginError::Rejected(message)),
+    }
+}
+
+// The write has already happened by now, so failures are only logged
+pub async fn after(
+    ctx: &ApiRestCtx,
+    collection_data: &CollectionDao,
+    kind: &RecordEventKind,
+    record_id: &Uuid,
+    record: Option<&Map<String, Value>>,
+) {
+    let plugin = match load(ctx, collection_data.id()).await {
+        Ok(Some(plugin)) if *plugin.has_after() => plugin,
+        Ok(_) => return,
+        Err(err) => {
+            hb_log::error(None, &err);
+            return;
+        }
+    };
+
+    if let Err(err) = plugin.after(&HookInput {
+        kind,
+        project_id: collection_data.project_id(),
+        collection_id: collection_data.id(),
+        record_id: &Some(*record_id),
+        record,
+    }) {
+        hb_log::error(None, &err);
+    }
+}
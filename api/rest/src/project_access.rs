@@ -0,0 +1,44 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use anyhow::Error;
+use hb_dao::{admin::AdminRole, project::ProjectDao, project_member::ProjectMemberDao};
+use uuid::Uuid;
+
+use crate::{context::ApiRestCtx, model::Response};
+
+pub enum ProjectAccessError {
+    Suspended,
+    Internal(Error),
+}
+
+impl ProjectAccessError {
+    pub fn to_response(&self) -> HttpResponse {
+        match self {
+            Self::Suspended => {
+                Response::error_raw(&StatusCode::LOCKED, "This project is suspended")
+            }
+            Self::Internal(err) => {
+                Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        }
+    }
+}
+
+impl From<Error> for ProjectAccessError {
+    fn from(err: Error) -> Self {
+        Self::Internal(err)
+    }
+}
+
+// The role the admin, or the admin owning the token, holds in the project. Every project-scoped
+// handler resolves it through here, so a suspended project is locked to admin JWTs and tokens
+// alike, and only the operator endpoints that suspend and resume it still reach it.
+pub async fn role(
+    ctx: &ApiRestCtx,
+    project_data: &ProjectDao,
+    admin_id: &Uuid,
+) -> Result<Option<AdminRole>, ProjectAccessError> {
+    if *project_data.suspended() {
+        return Err(ProjectAccessError::Suspended);
+    }
+    Ok(ProjectMemberDao::db_select_role(ctx.dao().db(), project_data, admin_id).await?)
+}
@@ -0,0 +1,159 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use ahash::HashSet;
+use anyhow::Error;
+use futures::future;
+use hb_dao::{
+    collection::CollectionDao,
+    record::{RecordDao, RecordFilters},
+    token::TokenDao,
+};
+use uuid::Uuid;
+
+use crate::{context::ApiRestCtx, model::Response};
+
+pub enum QuotaError {
+    Exceeded(String),
+    Internal(Error),
+}
+
+impl QuotaError {
+    pub fn to_response(&self) -> HttpResponse {
+        match self {
+            Self::Exceeded(message) => Response::error_raw(&StatusCode::FORBIDDEN, message),
+            Self::Internal(err) => {
+                Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        }
+    }
+}
+
+impl From<Error> for QuotaError {
+    fn from(err: Error) -> Self {
+        Self::Internal(err)
+    }
+}
+
+pub async fn check_collections(
+    ctx: &ApiRestCtx,
+    project_id: &Uuid,
+    new_collections: &usize,
+) -> Result<(), QuotaError> {
+    let max_collections = match ctx.quota().max_collections() {
+        Some(max_collections) if *new_collections > 0 => *max_collections,
+        _ => return Ok(()),
+    };
+
+    let collections = CollectionDao::db_select_many_by_project_id(ctx.dao().db(), project_id)
+        .await?
+        .len();
+    if (collections + new_collections) as u64 > max_collections {
+        return Err(QuotaError::Exceeded(format!(
+            "This project has reached its quota of {max_collections} collections"
+        )));
+    }
+
+    Ok(())
+}
+
+// Checks both the record count of the collection and the storage used by the whole project
+pub async fn check_records(
+    ctx: &ApiRestCtx,
+    collection_data: &CollectionDao,
+    new_records: &usize,
+) -> Result<(), QuotaError> {
+    if let Some(max_records) = ctx.quota().max_records_per_collection() {
+        let records = RecordDao::db_count(
+            ctx.dao().db(),
+            collection_data.id(),
+            &RecordFilters::new(&Vec::new()),
+        )
+        .await?;
+        if records as u64 + *new_records as u64 > *max_records {
+            return Err(QuotaError::Exceeded(format!(
+                "This collection has reached its quota of {max_records} records"
+            )));
+        }
+    }
+
+    if let Some(max_storage_bytes) = ctx.quota().max_storage_bytes() {
+        let collections_data = CollectionDao::db_select_many_by_project_id(
+            ctx.dao().db(),
+            collection_data.project_id(),
+        )
+        .await?;
+        let sizes =
+            future::try_join_all(collections_data.iter().map(|collection_data| {
+                RecordDao::db_table_size(ctx.dao().db(), collection_data.id())
+            }))
+            .await?;
+        if sizes.iter().sum::<i64>() as u64 >= *max_storage_bytes {
+            return Err(QuotaError::Exceeded(format!(
+                "This project has reached its storage quota of {max_storage_bytes} bytes"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// An upsert that replaces an existing record doesn't add to the collection, so only new records are
+// checked
+pub async fn check_upsert(
+    ctx: &ApiRestCtx,
+    collection_data: &CollectionDao,
+    record_id: &Uuid,
+) -> Result<(), QuotaError> {
+    if ctx.quota().max_records_per_collection().is_none()
+        && ctx.quota().max_storage_bytes().is_none()
+    {
+        return Ok(());
+    }
+
+    if RecordDao::db_select(ctx.dao().db(), collection_data, record_id)
+        .await
+        .is_ok()
+    {
+        return Ok(());
+    }
+
+    check_records(ctx, collection_data, &1).await
+}
+
+// Tokens belong to admins rather than projects, so a project's token count only covers the tokens
+// of the admin creating the new one
+pub async fn check_tokens(
+    ctx: &ApiRestCtx,
+    admin_id: &Uuid,
+    project_ids: &HashSet<Uuid>,
+) -> Result<(), QuotaError> {
+    let max_tokens = match ctx.quota().max_tokens() {
+        Some(max_tokens) => *max_tokens,
+        None => return Ok(()),
+    };
+
+    let tokens_data = TokenDao::db_select_many_by_admin_id(ctx.dao().db(), admin_id).await?;
+    for project_id in project_ids {
+        let collection_ids =
+            CollectionDao::db_select_many_by_project_id(ctx.dao().db(), project_id)
+                .await?
+                .iter()
+                .map(|collection_data| *collection_data.id())
+                .collect::<Vec<_>>();
+        let tokens = tokens_data
+            .iter()
+            .filter(|token_data| {
+                token_data
+                    .rules()
+                    .keys()
+                    .any(|collection_id| collection_ids.contains(collection_id))
+            })
+            .count();
+        if tokens as u64 >= max_tokens {
+            return Err(QuotaError::Exceeded(format!(
+                "Project '{project_id}' has reached its quota of {max_tokens} tokens"
+            )));
+        }
+    }
+
+    Ok(())
+}
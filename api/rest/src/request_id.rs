@@ -0,0 +1,40 @@
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+use uuid::Uuid;
+
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+#[derive(Clone)]
+pub struct RequestId(String);
+
+impl RequestId {
+    // Keeps the id assigned by an upstream proxy so both sides log the same value
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        match headers
+            .get(X_REQUEST_ID)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim())
+            .filter(|value| Self::is_valid(value))
+        {
+            Some(value) => Self(value.to_owned()),
+            None => Self(Uuid::now_v7().to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn insert_header(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.0) {
+            headers.insert(X_REQUEST_ID, value);
+        }
+    }
+
+    fn is_valid(value: &str) -> bool {
+        !value.is_empty()
+            && value.len() <= 128
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+
+use actix_web::{http::StatusCode, HttpRequest, HttpResponse};
+use ahash::HashMap;
+use anyhow::{Error, Result};
+use hb_dao::{
+    collection::CollectionDao,
+    expression::Filter,
+    record::{RecordDao, RecordFilters},
+    token::TokenDao,
+};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::model::{record::FindManyRecordFiltersReqJson, Response};
+
+pub enum RuleError {
+    Denied,
+    Failed(Error),
+}
+
+// Both are forbidden, a condition that can't be evaluated never lets the request through
+impl RuleError {
+    pub fn to_response(&self) -> HttpResponse {
+        Response::error_raw(&StatusCode::FORBIDDEN, &self.to_string())
+    }
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Denied => write!(f, "This token's rule condition doesn't allow this request"),
+            Self::Failed(err) => write!(f, "Failed to evaluate this token's rule condition: {err}"),
+        }
+    }
+}
+
+impl From<Error> for RuleError {
+    fn from(err: Error) -> Self {
+        Self::Failed(err)
+    }
+}
+
+// Evaluates the condition of the token's rule for the collection. `record` is the stored record the
+// request touches, or for inserts the record about to be stored, and `data` is the request body.
+pub fn check(
+    req: &HttpRequest,
+    token_data: &TokenDao,
+    user_id: &Option<Uuid>,
+    collection_id: &Uuid,
+    method: &str,
+    record: Option<&RecordDao>,
+    data: Option<&HashMap<String, Value>>,
+) -> Result<(), RuleError> {
+    if !token_data.has_condition(collection_id) {
+        return Ok(());
+    }
+
+    let record = match record {
+        Some(record) => Value::Object(
+            record
+                .data()
+                .iter()
+                .map(|(key, value)| Ok((key.to_owned(), value.to_serde_json()?)))
+                .collect::<Result<Map<_, _>>>()?,
+        ),
+        None => Value::Null,
+    };
+    let context = context(req, token_data, user_id, method, record, data);
+
+    match token_data.is_allow_condition(collection_id, &context)? {
+        true => Ok(()),
+        false => Err(RuleError::Denied),
+    }
+}
+
+// List requests have no single record to evaluate the condition against, so the parts of it that
+// read the record become a filter on the records instead. It is empty when the token has no
+// condition for the collection, and should be joined with the request's own filter.
+pub fn check_find_many(
+    req: &HttpRequest,
+    token_data: &TokenDao,
+    user_id: &Option<Uuid>,
+    collection_data: &CollectionDao,
+) -> Result<RecordFilters, RuleError> {
+    let context = context(req, token_data, user_id, "find_many", Value::Null, None);
+    let filter = match token_data.condition_filter(collection_data.id(), &context)? {
+        None | Some(Filter::Constant(true)) => return Ok(RecordFilters::new(&Vec::new())),
+        Some(Filter::Constant(false)) => return Err(RuleError::Denied),
+        Some(filter) => filter,
+    };
+    let filter =
+        serde_json::from_value::<FindManyRecordFiltersReqJson>(Value::Array(vec![filter_json(
+            &filter,
+        )?]))
+        .map_err(Error::from)?;
+    Ok(filter.to_dao(collection_data)?)
+}
+
+fn context(
+    req: &HttpRequest,
+    token_data: &TokenDao,
+    user_id: &Option<Uuid>,
+    method: &str,
+    record: Value,
+    data: Option<&HashMap<String, Value>>,
+) -> Map<String, Value> {
+    let data = match data {
+        Some(data) => Value::Object(
+            data.iter()
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+        ),
+        None => Value::Null,
+    };
+
+    let mut context = Map::with_capacity(3);
+    context.insert(
+        "request".to_owned(),
+        json!({
+            "method": method,
+            "ip": req.peer_addr().map(|addr| addr.ip().to_string()),
+            "auth": {
+                "id": user_id.unwrap_or(*token_data.id()),
+            },
+            "data": data,
+        }),
+    );
+    context.insert(
+        "token".to_owned(),
+        json!({
+            "id": token_data.id(),
+            "admin_id": token_data.admin_id(),
+            "user_id": user_id,
+        }),
+    );
+    context.insert("record".to_owned(), record);
+    context
+}
+
+// The filter in the shape of a request's filter, so it goes through the same field and value checks
+fn filter_json(filter: &Filter) -> Result<Value> {
+    match filter {
+        Filter::Compare(field, op, Value::Null) => Ok(json!({ "field": field, "op": op })),
+        Filter::Compare(field, op, value) => {
+            Ok(json!({ "field": field, "op": op, "value": value }))
+        }
+        Filter::And(filters) => Ok(json!({
+            "op": "AND",
+            "child": filters.iter().map(filter_json).collect::<Result<Vec<_>>>()?,
+        })),
+        Filter::Or(filters) => Ok(json!({
+            "op": "OR",
+            "child": filters.iter().map(filter_json).collect::<Result<Vec<_>>>()?,
+        })),
+        Filter::Constant(_) => Err(Error::msg("Rule condition has a constant inside a filter")),
+    }
+}
@@ -1,7 +1,19 @@
 pub mod admin;
+pub mod admin_session;
+pub mod audit_log;
 pub mod auth;
+pub mod backup;
 pub mod collection;
+pub mod collection_plugin;
+pub mod health;
+pub mod ingest;
+pub mod job;
+pub mod log_filter;
 pub mod project;
+pub mod project_member;
+pub mod project_transfer;
+pub mod project_usage;
 pub mod record;
 pub mod root;
 pub mod token;
+pub mod user;
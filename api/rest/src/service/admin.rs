@@ -1,5 +1,11 @@
-use actix_web::{http::StatusCode, web, HttpResponse};
-use hb_dao::admin::AdminDao;
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use hb_dao::{
+    admin::{AdminDao, AdminRole},
+    admin_password_reset::AdminPasswordResetDao,
+    audit_log::AuditLogDao,
+    jwt_revocation::JwtRevocationDao,
+};
 use hb_token_jwt::kind::JwtTokenKind;
 
 use crate::{
@@ -22,7 +28,7 @@ async fn find_one(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -47,12 +53,14 @@ async fn find_one(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>
             admin_data.created_at(),
             admin_data.updated_at(),
             admin_data.email(),
+            admin_data.role().to_str(),
         ),
     )
 }
 
 async fn update_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     data: web::Json<UpdateOneAdminReqJson>,
 ) -> HttpResponse {
@@ -61,7 +69,7 @@ async fn update_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -82,6 +90,14 @@ async fn update_one(
         return Response::error_raw(&StatusCode::BAD_REQUEST, "No request fields to be updated");
     }
 
+    let admin_before = AdminResJson::new(
+        admin_data.id(),
+        admin_data.created_at(),
+        admin_data.updated_at(),
+        admin_data.email(),
+        admin_data.role().to_str(),
+    );
+
     if let Some(password) = data.password() {
         let password_hash = match ctx.hash().argon2().hash_password(password.as_bytes()) {
             Ok(hash) => hash,
@@ -93,24 +109,61 @@ async fn update_one(
         admin_data.set_password_hash(&password_hash.to_string());
     }
 
+    if let Some(role) = data.role() {
+        let role = match role.parse::<AdminRole>() {
+            Ok(role) => role,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+        if &role > admin_data.role() {
+            return Response::error_raw(&StatusCode::FORBIDDEN, "Admin role can only be lowered");
+        }
+
+        admin_data.set_role(&role);
+    }
+
     if let Err(err) = admin_data.db_update(ctx.dao().db()).await {
         return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
     }
 
-    Response::data(
-        &StatusCode::OK,
+    if data.password().is_some() {
+        if let Err(err) =
+            AdminPasswordResetDao::db_delete_many_by_admin_id(ctx.dao().db(), admin_data.id()).await
+        {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+    }
+
+    let admin_res = AdminResJson::new(
+        admin_data.id(),
+        admin_data.created_at(),
+        admin_data.updated_at(),
+        admin_data.email(),
+        admin_data.role().to_str(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
         &None,
-        &AdminResJson::new(
-            admin_data.id(),
-            admin_data.created_at(),
-            admin_data.updated_at(),
-            admin_data.email(),
-        ),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "update",
+        "admin",
+        admin_data.id(),
+        &serde_json::to_value(&admin_before).ok(),
+        &serde_json::to_value(&admin_res).ok(),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &admin_res)
 }
 
 async fn delete_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
 ) -> HttpResponse {
     let token = match token.get() {
@@ -118,7 +171,7 @@ async fn delete_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -130,17 +183,53 @@ async fn delete_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
-        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
-    }
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let admin_before = AdminResJson::new(
+        admin_data.id(),
+        admin_data.created_at(),
+        admin_data.updated_at(),
+        admin_data.email(),
+        admin_data.role().to_str(),
+    );
 
     if let Err(err) = AdminDao::db_delete(ctx.dao().db(), token_claim.id()).await {
         return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
     }
 
-    Response::data(
-        &StatusCode::OK,
+    let revocation_expired_at = match i64::try_from(*ctx.token().jwt().expiry_duration()) {
+        Ok(expiry_duration) => Utc::now() + Duration::seconds(expiry_duration),
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+    if let Err(err) = JwtRevocationDao::new(token_claim.id(), &revocation_expired_at)
+        .db_insert(ctx.dao().db())
+        .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let admin_res = DeleteAdminResJson::new(token_claim.id());
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &None,
+        &req.peer_addr().map(|addr| addr.ip()),
+        "delete",
+        "admin",
+        admin_data.id(),
+        &serde_json::to_value(&admin_before).ok(),
         &None,
-        &DeleteAdminResJson::new(token_claim.id()),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &admin_res)
 }
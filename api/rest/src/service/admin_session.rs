@@ -0,0 +1,164 @@
+use actix_web::{http::StatusCode, web, HttpResponse};
+use hb_dao::admin_session::AdminSessionDao;
+use hb_token_jwt::kind::JwtTokenKind;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        admin_session::{
+            AdminSessionResJson, DeleteAdminSessionResJson, DeleteManyAdminSessionResJson,
+            DeleteOneAdminSessionReqPath,
+        },
+        PaginationRes, Response, TokenReqHeader,
+    },
+};
+
+pub fn admin_session_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/auth/sessions", web::get().to(find_many))
+        .route("/auth/sessions", web::delete().to(delete_many))
+        .route("/auth/session/{session_id}", web::delete().to(delete_one));
+}
+
+async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let sessions_data =
+        match AdminSessionDao::db_select_many_by_admin_id(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    let current_session_id = token_claim.session_id();
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &sessions_data.len(),
+            &Some(sessions_data.len()),
+            &None,
+            &None,
+        )),
+        &sessions_data
+            .iter()
+            .map(|data| {
+                AdminSessionResJson::new(
+                    data.id(),
+                    data.created_at(),
+                    data.updated_at(),
+                    data.user_agent(),
+                    data.ip(),
+                    data.expired_at(),
+                    &(current_session_id.as_ref() == Some(data.id())),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+async fn delete_one(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<DeleteOneAdminSessionReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let session_data = match AdminSessionDao::db_select(ctx.dao().db(), path.session_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if session_data.admin_id() != token_claim.id() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This session does not belong to you",
+        );
+    }
+
+    if let Err(err) = session_data
+        .db_revoke(ctx.dao().db(), ctx.token().jwt().expiry_duration())
+        .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &DeleteAdminSessionResJson::new(session_data.id()),
+    )
+}
+
+async fn delete_many(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let sessions_data =
+        match AdminSessionDao::db_select_many_by_admin_id(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    let mut session_ids = Vec::with_capacity(sessions_data.len());
+    for session_data in &sessions_data {
+        if let Err(err) = session_data
+            .db_revoke(ctx.dao().db(), ctx.token().jwt().expiry_duration())
+            .await
+        {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+        session_ids.push(*session_data.id());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &DeleteManyAdminSessionResJson::new(&session_ids),
+    )
+}
@@ -0,0 +1,144 @@
+use actix_web::{http::StatusCode, web, HttpResponse};
+use hb_dao::{audit_log::AuditLogDao, project::ProjectDao};
+use hb_token_jwt::kind::JwtTokenKind;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        audit_log::{AuditLogResJson, FindManyProjectAuditLogReqPath},
+        PaginationRes, Response, TokenReqHeader,
+    },
+    project_access,
+};
+
+pub fn audit_log_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/admin/audit_logs", web::get().to(find_many))
+        .route(
+            "/project/{project_id}/audit_logs",
+            web::get().to(find_many_by_project),
+        );
+}
+
+async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let audit_logs_data =
+        match AuditLogDao::db_select_many_by_admin_id(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &audit_logs_data.len(),
+            &Some(audit_logs_data.len()),
+            &None,
+            &None,
+        )),
+        &audit_logs_data
+            .iter()
+            .map(|data| {
+                AuditLogResJson::new(
+                    data.id(),
+                    data.created_at(),
+                    data.admin_id(),
+                    data.project_id(),
+                    data.ip(),
+                    data.action(),
+                    data.resource(),
+                    data.resource_id(),
+                    data.before(),
+                    data.after(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+async fn find_many_by_project(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<FindManyProjectAuditLogReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let audit_logs_data =
+        match AuditLogDao::db_select_many_by_project_id(ctx.dao().db(), project_data.id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &audit_logs_data.len(),
+            &Some(audit_logs_data.len()),
+            &None,
+            &None,
+        )),
+        &audit_logs_data
+            .iter()
+            .map(|data| {
+                AuditLogResJson::new(
+                    data.id(),
+                    data.created_at(),
+                    data.admin_id(),
+                    data.project_id(),
+                    data.ip(),
+                    data.action(),
+                    data.resource(),
+                    data.resource_id(),
+                    data.before(),
+                    data.after(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
@@ -1,25 +1,40 @@
-use actix_web::{http::StatusCode, web, HttpResponse};
+use std::net::IpAddr;
+
+use actix_web::{
+    http::{header, StatusCode},
+    web, HttpRequest, HttpResponse,
+};
+use chrono::{DateTime, Duration, Utc};
 use hb_dao::{
-    admin::AdminDao, admin_password_reset::AdminPasswordResetDao, register::RegistrationDao,
+    admin::AdminDao, admin_password_reset::AdminPasswordResetDao,
+    admin_refresh_token::AdminRefreshTokenDao, admin_session::AdminSessionDao,
+    jwt_revocation::JwtRevocationDao, project::ProjectDao, register::RegistrationDao,
     token::TokenDao,
 };
 use hb_mailer::MailPayload;
-use hb_token_jwt::kind::JwtTokenKind;
+use hb_token_jwt::{claim::SESSION_ID_CLAIM, kind::JwtTokenKind};
+use hb_token_webauthn::webauthn::WebauthnToken;
+use serde_json::{Map, Value};
 use validator::Validate;
 
 use crate::{
     model::{
         auth::{
             AuthTokenResJson, ConfirmPasswordResetReqJson, ConfirmPasswordResetResJson,
-            PasswordBasedReqJson, RegisterReqJson, RegisterResJson, RequestPasswordResetReqJson,
-            RequestPasswordResetResJson, TokenBasedReqJson, VerifyRegistrationReqJson,
-            VerifyRegistrationResJson,
+            DeletePasskeyReqPath, EnrollMfaResJson, FinishPasskeyReqJson, LogoutResJson,
+            MfaCodeReqJson, MfaResJson, PasskeyLoginReqJson, PasskeyResJson, PasswordBasedReqJson,
+            RefreshTokenReqJson, RegisterReqJson, RegisterResJson, RequestPasswordResetReqJson,
+            RequestPasswordResetResJson, RevokeTokenResJson, StartPasskeyResJson,
+            TokenBasedReqJson, UnlockAccountReqJson, UnlockAccountResJson,
+            VerifyRegistrationReqJson, VerifyRegistrationResJson,
         },
         Response, TokenReqHeader,
     },
-    ApiRestCtx,
+    project_access, ApiRestCtx,
 };
 
+pub const PASSWORD_RESET_RESEND_INTERVAL: i64 = 60;
+
 pub fn auth_api(cfg: &mut web::ServiceConfig) {
     cfg.route("/auth/token", web::get().to(token))
         .route("/auth/register", web::post().to(register))
@@ -28,7 +43,36 @@ pub fn auth_api(cfg: &mut web::ServiceConfig) {
             web::post().to(verify_registration),
         )
         .route("/auth/password-based", web::post().to(password_based))
+        .route("/auth/unlock", web::post().to(unlock))
         .route("/auth/token-based", web::post().to(token_based))
+        .route("/auth/refresh", web::post().to(refresh))
+        .route("/auth/logout", web::post().to(logout))
+        .route("/auth/jwks", web::get().to(jwks))
+        .route("/auth/revoke", web::post().to(revoke))
+        .route("/auth/mfa/enroll", web::post().to(enroll_mfa))
+        .route("/auth/mfa/verify", web::post().to(verify_mfa))
+        .route("/auth/mfa/disable", web::post().to(disable_mfa))
+        .route(
+            "/auth/passkey/register/start",
+            web::post().to(start_passkey_registration),
+        )
+        .route(
+            "/auth/passkey/register/finish",
+            web::post().to(finish_passkey_registration),
+        )
+        .route(
+            "/auth/passkey/login/start",
+            web::post().to(start_passkey_login),
+        )
+        .route(
+            "/auth/passkey/login/finish",
+            web::post().to(finish_passkey_login),
+        )
+        .route("/auth/passkeys", web::get().to(find_many_passkeys))
+        .route(
+            "/auth/passkey/{credential_id}",
+            web::delete().to(delete_one_passkey),
+        )
         .route(
             "/auth/request-password-reset",
             web::post().to(request_password_reset),
@@ -39,13 +83,17 @@ pub fn auth_api(cfg: &mut web::ServiceConfig) {
         );
 }
 
-async fn token(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
+async fn token(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+) -> HttpResponse {
     let token = match token.get() {
         Some(token) => token,
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -59,38 +107,25 @@ async fn token(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -
                 );
             }
         }
-        JwtTokenKind::Token => {
-            if let Err(err) = TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+            }
+            Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
                     &format!("Failed to get token data: {err}"),
-                );
+                )
             }
-        }
+        },
     }
 
-    let token = match ctx.token().jwt().need_renew(&token_claim) {
-        Ok(need) => {
-            if need {
-                match ctx.token().jwt().renew(&token_claim) {
-                    Ok(token) => token,
-                    Err(err) => {
-                        return Response::error_raw(
-                            &StatusCode::INTERNAL_SERVER_ERROR,
-                            &err.to_string(),
-                        )
-                    }
-                }
-            } else {
-                token.to_owned()
-            }
-        }
-        Err(err) => {
-            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
-        }
-    };
-
-    Response::data(&StatusCode::OK, &None, &AuthTokenResJson::new(&token))
+    Response::data(&StatusCode::OK, &None, &AuthTokenResJson::new(token, &None))
 }
 
 async fn register(ctx: web::Data<ApiRestCtx>, data: web::Json<RegisterReqJson>) -> HttpResponse {
@@ -99,7 +134,7 @@ async fn register(ctx: web::Data<ApiRestCtx>, data: web::Json<RegisterReqJson>)
     }
 
     if let Err(err) = data.validate() {
-        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        return Response::error_validation(&err);
     }
 
     if AdminDao::db_select_by_email(ctx.dao().db(), data.email())
@@ -194,15 +229,25 @@ async fn verify_registration(
 
 async fn password_based(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     data: web::Json<PasswordBasedReqJson>,
 ) -> HttpResponse {
     if let Err(err) = data.validate() {
-        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        return Response::error_validation(&err);
+    }
+
+    let ip = req.peer_addr().map(|addr| addr.ip());
+
+    if let Err(err) = ctx.login_throttle().check(data.email(), &ip) {
+        return Response::error_raw(&StatusCode::TOO_MANY_REQUESTS, &err.to_string());
     }
 
     let admin_data = match AdminDao::db_select_by_email(ctx.dao().db(), data.email()).await {
         Ok(data) => data,
-        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        Err(err) => {
+            ctx.login_throttle().record_failure(data.email(), &ip);
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
     };
 
     if let Err(err) = ctx
@@ -210,25 +255,145 @@ async fn password_based(
         .argon2()
         .verify_password(data.password(), admin_data.password_hash())
     {
+        record_failed_sign_in(&ctx, admin_data.email(), &ip);
         return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
     }
 
-    let token = match ctx
-        .token()
-        .jwt()
-        .encode(admin_data.id(), &JwtTokenKind::User)
-    {
+    if *admin_data.mfa_enabled() {
+        let mfa_code = match data.mfa_code() {
+            Some(code) => code,
+            None => return Response::error_raw(&StatusCode::UNAUTHORIZED, "MFA code is required"),
+        };
+
+        let mfa_secret = match admin_data.mfa_secret() {
+            Some(secret) => secret,
+            None => {
+                return Response::error_raw(
+                    &StatusCode::INTERNAL_SERVER_ERROR,
+                    "MFA is enabled but the secret is missing",
+                )
+            }
+        };
+
+        match ctx.token().totp().verify(mfa_secret, mfa_code) {
+            Ok(true) => (),
+            Ok(false) => {
+                record_failed_sign_in(&ctx, admin_data.email(), &ip);
+                return Response::error_raw(&StatusCode::UNAUTHORIZED, "Invalid MFA code");
+            }
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        }
+    }
+
+    ctx.login_throttle().record_success(admin_data.email());
+
+    start_session(&ctx, &req, &admin_data).await
+}
+
+// Issues the access and refresh tokens of a new session once the admin has been authenticated
+async fn start_session(ctx: &ApiRestCtx, req: &HttpRequest, admin_data: &AdminDao) -> HttpResponse {
+    let session_data = match AdminSessionDao::new(
+        admin_data.id(),
+        &req.headers()
+            .get(header::USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok())
+            .map(|user_agent| user_agent.to_owned()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        ctx.token().jwt().refresh_expiry_duration(),
+    ) {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    if let Err(err) = session_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let mut session_claims = Map::new();
+    session_claims.insert(
+        SESSION_ID_CLAIM.to_owned(),
+        Value::String(session_data.id().to_string()),
+    );
+
+    let token = match ctx.token().jwt().encode_with_claims(
+        admin_data.id(),
+        &JwtTokenKind::User,
+        &session_claims,
+    ) {
         Ok(token) => token,
         Err(err) => {
             return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
         }
     };
 
-    Response::data(&StatusCode::OK, &None, &AuthTokenResJson::new(&token))
+    let refresh_token_data = match AdminRefreshTokenDao::new(
+        admin_data.id(),
+        session_data.id(),
+        ctx.token().jwt().refresh_expiry_duration(),
+    ) {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    if let Err(err) = refresh_token_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &AuthTokenResJson::new(&token, &Some(refresh_token_data.token().to_owned())),
+    )
+}
+
+fn record_failed_sign_in(ctx: &ApiRestCtx, email: &str, ip: &Option<IpAddr>) {
+    if let Some(unlock_code) = ctx.login_throttle().record_failure(email, ip) {
+        if let Err(err) = ctx.mailer().sender().send(MailPayload::new(
+            email,
+            "Account Temporarily Locked",
+            &format!(
+                "Your account has been temporarily locked after too many failed sign-in attempts. Your unlock code is {}",
+                unlock_code
+            ),
+        )) {
+            hb_log::error(None, &err);
+        }
+    }
+}
+
+async fn unlock(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    data: web::Json<UnlockAccountReqJson>,
+) -> HttpResponse {
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    }
+
+    if let Err(err) = ctx.login_throttle().unlock(
+        data.email(),
+        data.code(),
+        &req.peer_addr().map(|addr| addr.ip()),
+    ) {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &UnlockAccountResJson::new(data.email()),
+    )
 }
 
 async fn token_based(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     data: web::Json<TokenBasedReqJson>,
 ) -> HttpResponse {
     let token_data = match TokenDao::db_select_by_token(ctx.dao().db(), data.token()).await {
@@ -236,96 +401,835 @@ async fn token_based(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    let token = match ctx
-        .token()
-        .jwt()
-        .encode(token_data.id(), &JwtTokenKind::Token)
-    {
+    if !token_data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This token is not allowed from this IP address",
+        );
+    }
+
+    let mut custom_claims = Map::new();
+
+    if let Some(project_id) = data.project_id() {
+        let project_data = match ProjectDao::db_select(ctx.dao().db(), project_id).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+        match project_access::role(&ctx, &project_data, token_data.admin_id()).await {
+            Ok(Some(_)) => (),
+            Ok(None) => {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "This token doesn't belong to this project",
+                )
+            }
+            Err(err) => return err.to_response(),
+        }
+
+        custom_claims = project_data.custom_token_claims();
+    }
+
+    let token = match ctx.token().jwt().encode_with_claims(
+        token_data.id(),
+        &JwtTokenKind::Token,
+        &custom_claims,
+    ) {
         Ok(token) => token,
         Err(err) => {
             return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
         }
     };
 
-    Response::data(&StatusCode::OK, &None, &AuthTokenResJson::new(&token))
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &AuthTokenResJson::new(&token, &None),
+    )
 }
 
-async fn request_password_reset(
-    ctx: web::Data<ApiRestCtx>,
-    data: web::Json<RequestPasswordResetReqJson>,
-) -> HttpResponse {
-    if let Err(err) = data.validate() {
-        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+async fn refresh(ctx: web::Data<ApiRestCtx>, data: web::Json<RefreshTokenReqJson>) -> HttpResponse {
+    let refresh_token_data = match AdminRefreshTokenDao::db_select_by_token(
+        ctx.dao().db(),
+        data.refresh_token(),
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    let admin_data = match AdminDao::db_select_by_email(ctx.dao().db(), data.email()).await {
+    if let Err(err) = AdminDao::db_select(ctx.dao().db(), refresh_token_data.admin_id()).await {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            &format!("Failed to get user data: {err}"),
+        );
+    }
+
+    let mut session_data =
+        match AdminSessionDao::db_select(ctx.dao().db(), refresh_token_data.session_id()).await {
+            Ok(data) => data,
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Session is no longer active: {err}"),
+                )
+            }
+        };
+
+    if let Err(err) = AdminRefreshTokenDao::db_delete(ctx.dao().db(), refresh_token_data.id()).await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    if let Err(err) = session_data
+        .db_extend(ctx.dao().db(), ctx.token().jwt().refresh_expiry_duration())
+        .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let mut session_claims = Map::new();
+    session_claims.insert(
+        SESSION_ID_CLAIM.to_owned(),
+        Value::String(session_data.id().to_string()),
+    );
+
+    let token = match ctx.token().jwt().encode_with_claims(
+        refresh_token_data.admin_id(),
+        &JwtTokenKind::User,
+        &session_claims,
+    ) {
+        Ok(token) => token,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    let new_refresh_token_data = match AdminRefreshTokenDao::new(
+        refresh_token_data.admin_id(),
+        session_data.id(),
+        ctx.token().jwt().refresh_expiry_duration(),
+    ) {
         Ok(data) => data,
-        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
     };
 
-    let password_reset_data = AdminPasswordResetDao::new(admin_data.id());
+    if let Err(err) = new_refresh_token_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
 
-    if let Err(err) = password_reset_data.db_insert(ctx.dao().db()).await {
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &AuthTokenResJson::new(&token, &Some(new_refresh_token_data.token().to_owned())),
+    )
+}
+
+async fn logout(ctx: web::Data<ApiRestCtx>, data: web::Json<RefreshTokenReqJson>) -> HttpResponse {
+    let refresh_token_data = match AdminRefreshTokenDao::db_select_by_token(
+        ctx.dao().db(),
+        data.refresh_token(),
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if let Err(err) = AdminRefreshTokenDao::db_delete(ctx.dao().db(), refresh_token_data.id()).await
+    {
         return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
     }
 
-    if let Err(err)= ctx.mailer()
-        .sender()
-        .send(MailPayload::new(
-            data.email(),
-            "Request Password Reset Verification Code",
-            &format!(
-                "Your request password reset verification code is {}. This code will expire in {} seconds",
-                password_reset_data.code(),
-                ctx.reset_password_ttl()
-            ),
-        )) {
+    if let Ok(session_data) =
+        AdminSessionDao::db_select(ctx.dao().db(), refresh_token_data.session_id()).await
+    {
+        if let Err(err) = session_data
+            .db_revoke(ctx.dao().db(), ctx.token().jwt().expiry_duration())
+            .await
+        {
             return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &LogoutResJson::new(refresh_token_data.id()),
+    )
+}
+
+async fn revoke(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
 
+    let revocation_expired_at = match i64::try_from(*token_claim.exp())
+        .ok()
+        .and_then(|exp| DateTime::from_timestamp(exp, 0))
+    {
+        Some(expired_at) => expired_at,
+        None => {
+            return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token expiration time")
         }
+    };
+    if let Err(err) = JwtRevocationDao::new(token_claim.jti(), &revocation_expired_at)
+        .db_insert(ctx.dao().db())
+        .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
 
     Response::data(
         &StatusCode::OK,
         &None,
-        &RequestPasswordResetResJson::new(password_reset_data.id()),
+        &RevokeTokenResJson::new(token_claim.jti()),
     )
 }
 
-async fn confirm_password_reset(
+async fn enroll_mfa(
     ctx: web::Data<ApiRestCtx>,
-    data: web::Json<ConfirmPasswordResetReqJson>,
+    token: web::Header<TokenReqHeader>,
 ) -> HttpResponse {
-    let password_reset_data =
-        match AdminPasswordResetDao::db_select(ctx.dao().db(), data.id()).await {
-            Ok(data) => data,
-            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
-        };
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
 
-    if data.code() != password_reset_data.code() {
-        return Response::error_raw(&StatusCode::BAD_REQUEST, "Wrong code");
-    }
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
 
-    let mut admin_data =
-        match AdminDao::db_select(ctx.dao().db(), password_reset_data.admin_id()).await {
-            Ok(data) => data,
-            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
-        };
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
 
-    let password_hash = match ctx
-        .hash()
-        .argon2()
-        .hash_password(data.password().as_bytes())
-    {
-        Ok(hash) => hash,
+    let mut admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
         Err(err) => {
-            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
         }
     };
 
-    admin_data.set_password_hash(&password_hash.to_string());
+    if *admin_data.mfa_enabled() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "MFA is already enabled");
+    }
+
+    let mfa_secret = ctx.token().totp().generate_secret();
+    let mfa_uri = ctx
+        .token()
+        .totp()
+        .provisioning_uri(admin_data.email(), &mfa_secret);
+
+    admin_data.set_mfa_secret(&Some(mfa_secret.clone()));
 
     if let Err(err) = admin_data.db_update(ctx.dao().db()).await {
-        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &EnrollMfaResJson::new(&mfa_secret, &mfa_uri),
+    )
+}
+
+async fn verify_mfa(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    data: web::Json<MfaCodeReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let mut admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if *admin_data.mfa_enabled() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "MFA is already enabled");
+    }
+
+    let mfa_secret = match admin_data.mfa_secret() {
+        Some(secret) => secret,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "MFA is not enrolled"),
+    };
+
+    match ctx.token().totp().verify(mfa_secret, data.code()) {
+        Ok(true) => (),
+        Ok(false) => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid MFA code"),
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    }
+
+    admin_data.set_mfa_enabled(&true);
+
+    if let Err(err) = admin_data.db_update(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &MfaResJson::new(admin_data.id(), admin_data.mfa_enabled()),
+    )
+}
+
+async fn disable_mfa(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    data: web::Json<MfaCodeReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let mut admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    let mfa_secret = match admin_data.mfa_secret() {
+        Some(secret) if *admin_data.mfa_enabled() => secret,
+        _ => return Response::error_raw(&StatusCode::BAD_REQUEST, "MFA is not enabled"),
+    };
+
+    match ctx.token().totp().verify(mfa_secret, data.code()) {
+        Ok(true) => (),
+        Ok(false) => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid MFA code"),
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    }
+
+    admin_data.set_mfa_secret(&None);
+    admin_data.set_mfa_enabled(&false);
+
+    if let Err(err) = admin_data.db_update(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &MfaResJson::new(admin_data.id(), admin_data.mfa_enabled()),
+    )
+}
+
+async fn start_passkey_registration(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+) -> HttpResponse {
+    let webauthn = match ctx.token().webauthn() {
+        Some(webauthn) => webauthn,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Passkeys are not enabled"),
+    };
+
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    match webauthn.start_registration(admin_data.id(), admin_data.email(), admin_data.passkeys()) {
+        Ok((ceremony_id, options)) => Response::data(
+            &StatusCode::OK,
+            &None,
+            &StartPasskeyResJson::new(&ceremony_id, &options),
+        ),
+        Err(err) => Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+async fn finish_passkey_registration(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    data: web::Json<FinishPasskeyReqJson>,
+) -> HttpResponse {
+    let webauthn = match ctx.token().webauthn() {
+        Some(webauthn) => webauthn,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Passkeys are not enabled"),
+    };
+
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let passkey = match webauthn.finish_registration(token_claim.id(), data.id(), data.credential())
+    {
+        Ok(passkey) => passkey,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let credential_id = match WebauthnToken::credential_id(&passkey) {
+        Ok(credential_id) => credential_id,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    // Read the admin after the ceremony so a passkey registered in the meantime isn't overwritten
+    let mut admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    let mut passkeys = admin_data.passkeys().clone();
+    passkeys.push(passkey);
+    admin_data.set_passkeys(&passkeys);
+
+    if let Err(err) = admin_data.db_update(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::CREATED,
+        &None,
+        &PasskeyResJson::new(&credential_id),
+    )
+}
+
+async fn start_passkey_login(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    data: web::Json<PasskeyLoginReqJson>,
+) -> HttpResponse {
+    let webauthn = match ctx.token().webauthn() {
+        Some(webauthn) => webauthn,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Passkeys are not enabled"),
+    };
+
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    }
+
+    let ip = req.peer_addr().map(|addr| addr.ip());
+
+    if let Err(err) = ctx.login_throttle().check(data.email(), &ip) {
+        return Response::error_raw(&StatusCode::TOO_MANY_REQUESTS, &err.to_string());
+    }
+
+    let admin_data = match AdminDao::db_select_by_email(ctx.dao().db(), data.email()).await {
+        Ok(data) => data,
+        Err(err) => {
+            ctx.login_throttle().record_failure(data.email(), &ip);
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
+    };
+
+    match webauthn.start_authentication(admin_data.id(), admin_data.passkeys()) {
+        Ok((ceremony_id, options)) => Response::data(
+            &StatusCode::OK,
+            &None,
+            &StartPasskeyResJson::new(&ceremony_id, &options),
+        ),
+        Err(err) => Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    }
+}
+
+// A passkey login requires user verification on the authenticator, so it stands in for both the
+// password and the MFA code
+async fn finish_passkey_login(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    data: web::Json<FinishPasskeyReqJson>,
+) -> HttpResponse {
+    let webauthn = match ctx.token().webauthn() {
+        Some(webauthn) => webauthn,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Passkeys are not enabled"),
+    };
+
+    let admin_id = match webauthn.authentication_admin_id(data.id()) {
+        Ok(admin_id) => admin_id,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let mut admin_data = match AdminDao::db_select(ctx.dao().db(), &admin_id).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let ip = req.peer_addr().map(|addr| addr.ip());
+
+    if let Err(err) = ctx.login_throttle().check(admin_data.email(), &ip) {
+        return Response::error_raw(&StatusCode::TOO_MANY_REQUESTS, &err.to_string());
+    }
+
+    match webauthn.finish_authentication(
+        admin_data.id(),
+        data.id(),
+        data.credential(),
+        admin_data.passkeys(),
+    ) {
+        Ok(Some(passkeys)) => {
+            admin_data.set_passkeys(&passkeys);
+            if let Err(err) = admin_data.db_update(ctx.dao().db()).await {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+            }
+        }
+        Ok(None) => (),
+        Err(err) => {
+            record_failed_sign_in(&ctx, admin_data.email(), &ip);
+            return Response::error_raw(&StatusCode::UNAUTHORIZED, &err.to_string());
+        }
+    }
+
+    ctx.login_throttle().record_success(admin_data.email());
+
+    start_session(&ctx, &req, &admin_data).await
+}
+
+async fn find_many_passkeys(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    let mut passkeys = Vec::with_capacity(admin_data.passkeys().len());
+    for passkey in admin_data.passkeys() {
+        match WebauthnToken::credential_id(passkey) {
+            Ok(credential_id) => passkeys.push(PasskeyResJson::new(&credential_id)),
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        }
+    }
+
+    Response::data(&StatusCode::OK, &None, &passkeys)
+}
+
+async fn delete_one_passkey(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<DeletePasskeyReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let mut admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    let mut passkeys = Vec::with_capacity(admin_data.passkeys().len());
+    for passkey in admin_data.passkeys() {
+        match WebauthnToken::credential_id(passkey) {
+            Ok(credential_id) if credential_id == path.credential_id() => (),
+            Ok(_) => passkeys.push(passkey.clone()),
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        }
+    }
+
+    if passkeys.len() == admin_data.passkeys().len() {
+        return Response::error_raw(&StatusCode::NOT_FOUND, "Passkey not found");
+    }
+
+    admin_data.set_passkeys(&passkeys);
+
+    if let Err(err) = admin_data.db_update(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &PasskeyResJson::new(path.credential_id()),
+    )
+}
+
+async fn jwks(ctx: web::Data<ApiRestCtx>) -> HttpResponse {
+    HttpResponse::Ok().json(ctx.token().jwt().jwks())
+}
+
+async fn request_password_reset(
+    ctx: web::Data<ApiRestCtx>,
+    data: web::Json<RequestPasswordResetReqJson>,
+) -> HttpResponse {
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    };
+
+    let admin_data = match AdminDao::db_select_by_email(ctx.dao().db(), data.email()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let password_resets_data =
+        match AdminPasswordResetDao::db_select_many_by_admin_id(ctx.dao().db(), admin_data.id())
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+
+    let resend_after = Utc::now() - Duration::seconds(PASSWORD_RESET_RESEND_INTERVAL);
+    if password_resets_data
+        .iter()
+        .any(|password_reset_data| *password_reset_data.created_at() > resend_after)
+    {
+        return Response::error_raw(
+            &StatusCode::TOO_MANY_REQUESTS,
+            "A password reset code was sent recently. Please wait before requesting another one",
+        );
+    }
+
+    if let Err(err) =
+        AdminPasswordResetDao::db_delete_many_by_admin_id(ctx.dao().db(), admin_data.id()).await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let password_reset_data = AdminPasswordResetDao::new(admin_data.id(), ctx.reset_password_ttl());
+
+    if let Err(err) = password_reset_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    if let Err(err)= ctx.mailer()
+        .sender()
+        .send(MailPayload::new(
+            data.email(),
+            "Request Password Reset Verification Code",
+            &format!(
+                "Your request password reset verification code is {}. This code will expire in {} seconds",
+                password_reset_data.code(),
+                ctx.reset_password_ttl()
+            ),
+        )) {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+
+        }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &RequestPasswordResetResJson::new(
+            password_reset_data.id(),
+            password_reset_data.expired_at(),
+        ),
+    )
+}
+
+async fn confirm_password_reset(
+    ctx: web::Data<ApiRestCtx>,
+    data: web::Json<ConfirmPasswordResetReqJson>,
+) -> HttpResponse {
+    let password_reset_data =
+        match AdminPasswordResetDao::db_select(ctx.dao().db(), data.id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    if let Err(err) = ctx.login_throttle().verify_reset_code(
+        password_reset_data.id(),
+        password_reset_data.code(),
+        data.code(),
+    ) {
+        if ctx
+            .login_throttle()
+            .is_reset_exhausted(password_reset_data.id())
+        {
+            if let Err(err) = AdminPasswordResetDao::db_delete_many_by_admin_id(
+                ctx.dao().db(),
+                password_reset_data.admin_id(),
+            )
+            .await
+            {
+                hb_log::error(None, &err);
+            }
+        }
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    if let Err(err) = AdminPasswordResetDao::db_delete_many_by_admin_id(
+        ctx.dao().db(),
+        password_reset_data.admin_id(),
+    )
+    .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let mut admin_data =
+        match AdminDao::db_select(ctx.dao().db(), password_reset_data.admin_id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    let password_hash = match ctx
+        .hash()
+        .argon2()
+        .hash_password(data.password().as_bytes())
+    {
+        Ok(hash) => hash,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    admin_data.set_password_hash(&password_hash.to_string());
+
+    if let Err(err) = admin_data.db_update(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    // A password reset signs the admin out of every existing session
+    let sessions_data =
+        match AdminSessionDao::db_select_many_by_admin_id(ctx.dao().db(), admin_data.id()).await {
+            Ok(data) => data,
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+    for session_data in &sessions_data {
+        if let Err(err) = session_data
+            .db_revoke(ctx.dao().db(), ctx.token().jwt().expiry_duration())
+            .await
+        {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
     }
 
     if let Err(err) = ctx.mailer().sender().send(MailPayload::new(
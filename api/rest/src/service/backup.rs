@@ -0,0 +1,209 @@
+use actix_web::{
+    http::{
+        header::{self, HeaderValue},
+        StatusCode,
+    },
+    web, HttpRequest, HttpResponse,
+};
+use anyhow::Error;
+use futures::{stream, StreamExt};
+use hb_dao::{admin::AdminDao, audit_log::AuditLogDao, backup};
+use hb_token_jwt::kind::JwtTokenKind;
+use tokio::sync::mpsc;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{backup::RestoreResJson, Response, TokenReqHeader},
+};
+
+pub fn backup_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/admin/backup", web::get().to(backup_all))
+        .route("/admin/restore", web::post().to(restore_all));
+}
+
+async fn backup_all(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !ctx.is_operator(admin_data.email()) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Only server operators can create a backup",
+        );
+    }
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &None,
+        &req.peer_addr().map(|addr| addr.ip()),
+        "backup",
+        "database",
+        admin_data.id(),
+        &None,
+        &None,
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    let (tx, rx) = mpsc::channel::<String>(64);
+    let backup_ctx = ctx.clone();
+    actix_web::rt::spawn(async move {
+        match backup::db_backup(backup_ctx.dao().db(), &tx).await {
+            Ok(summary) => hb_log::info(
+                None,
+                format!(
+                    "Backup finished with {} collections and {} records",
+                    summary.collections(),
+                    summary.records()
+                ),
+            ),
+            Err(err) => hb_log::error(None, format!("Failed to create backup: {err}")),
+        }
+    });
+
+    let body = stream::unfold(rx, |mut rx| async move {
+        rx.recv()
+            .await
+            .map(|line| (Ok::<_, actix_web::Error>(web::Bytes::from(line)), rx))
+    });
+
+    let mut res = HttpResponse::Ok();
+    res.content_type("application/x-ndjson");
+    if let Ok(value) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"hyperbase-backup-{}.ndjson\"",
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    )) {
+        res.insert_header((header::CONTENT_DISPOSITION, value));
+    }
+    res.streaming(body)
+}
+
+async fn restore_all(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    payload: web::Payload,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !ctx.is_operator(admin_data.email()) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Only server operators can restore a backup",
+        );
+    }
+
+    // Splits the request body into lines as it arrives so large archives are never held in memory
+    let lines = stream::unfold(
+        (payload, Vec::new(), false),
+        |(mut payload, mut buf, mut is_eof)| async move {
+            loop {
+                if let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+                    let rest = buf.split_off(pos + 1);
+                    let line =
+                        String::from_utf8(buf).map_err(|_| Error::msg("Backup is not valid UTF-8"));
+                    return Some((line, (payload, rest, is_eof)));
+                }
+                if is_eof {
+                    if buf.is_empty() {
+                        return None;
+                    }
+                    let line =
+                        String::from_utf8(buf).map_err(|_| Error::msg("Backup is not valid UTF-8"));
+                    return Some((line, (payload, Vec::new(), is_eof)));
+                }
+                match payload.next().await {
+                    Some(Ok(chunk)) => buf.extend_from_slice(&chunk),
+                    Some(Err(err)) => {
+                        return Some((
+                            Err(Error::msg(err.to_string())),
+                            (payload, Vec::new(), true),
+                        ))
+                    }
+                    None => is_eof = true,
+                }
+            }
+        },
+    );
+
+    let summary = match backup::db_restore(ctx.dao().db(), Box::pin(lines)).await {
+        Ok(summary) => summary,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    hb_log::info(
+        None,
+        format!(
+            "Backup restored by admin {} with {} collections and {} records",
+            admin_data.id(),
+            summary.collections(),
+            summary.records()
+        ),
+    );
+
+    let restore_res = RestoreResJson::new(&summary);
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &None,
+        &req.peer_addr().map(|addr| addr.ip()),
+        "restore",
+        "database",
+        admin_data.id(),
+        &None,
+        &serde_json::to_value(&restore_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &restore_res)
+}
@@ -1,8 +1,11 @@
-use actix_web::{http::StatusCode, web, HttpResponse};
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
 use hb_dao::{
     admin::AdminDao,
-    collection::{CollectionDao, SchemaFieldProps},
+    audit_log::AuditLogDao,
+    collection::{
+        CollectionDao, PartialIndex, SchemaFieldComputed, SchemaFieldProps, SchemaFieldValidation,
+    },
     project::ProjectDao,
     value::ColumnKind,
 };
@@ -12,13 +15,17 @@ use crate::{
     context::ApiRestCtx,
     model::{
         collection::{
-            CollectionResJson, DeleteCollectionResJson, DeleteOneCollectionReqPath,
-            FindManyCollectionReqPath, FindOneCollectionReqPath, InsertOneCollectionReqJson,
-            InsertOneCollectionReqPath, SchemaFieldPropsJson, UpdateOneCollectionReqJson,
-            UpdateOneCollectionReqPath,
+            CollectionResJson, CollectionSchemaResJson, DeleteCollectionResJson,
+            DeleteOneCollectionReqPath, ExportOneCollectionReqPath, FindManyCollectionReqPath,
+            FindOneCollectionReqPath, IndexSuggestionResJson, IndexSuggestionsCollectionReqPath,
+            InsertOneCollectionReqJson, InsertOneCollectionReqPath, PartialIndexJson,
+            SchemaFieldComputedJson, SchemaFieldPropsJson, SchemaFieldValidationJson,
+            TransformCollectionFailureResJson, TransformCollectionResJson,
+            UpdateOneCollectionReqJson, UpdateOneCollectionReqPath,
         },
         PaginationRes, Response, TokenReqHeader,
     },
+    project_access, quota,
 };
 
 pub fn collection_api(cfg: &mut web::ServiceConfig) {
@@ -26,10 +33,22 @@ pub fn collection_api(cfg: &mut web::ServiceConfig) {
         "/project/{project_id}/collection",
         web::post().to(insert_one),
     )
+    .route(
+        "/project/{project_id}/collection/import",
+        web::post().to(insert_one),
+    )
     .route(
         "/project/{project_id}/collection/{collection_id}",
         web::get().to(find_one),
     )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/schema",
+        web::get().to(export_one),
+    )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/index_suggestions",
+        web::get().to(index_suggestions),
+    )
     .route(
         "/project/{project_id}/collection/{collection_id}",
         web::patch().to(update_one),
@@ -46,6 +65,7 @@ pub fn collection_api(cfg: &mut web::ServiceConfig) {
 
 async fn insert_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     path: web::Path<InsertOneCollectionReqPath>,
     data: web::Json<InsertOneCollectionReqJson>,
@@ -55,7 +75,7 @@ async fn insert_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -67,10 +87,20 @@ async fn insert_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_schemas() {
         return Response::error_raw(
-            &StatusCode::BAD_REQUEST,
-            &format!("Failed to get user data: {err}"),
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying schemas",
         );
     }
 
@@ -79,11 +109,30 @@ async fn insert_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if project_data.admin_id() != token_claim.id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_schemas() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying schemas",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
+    }
+
+    if let Err(err) = quota::check_collections(&ctx, project_data.id(), &1).await {
+        return err.to_response();
     }
 
     let mut schema_fields = HashMap::with_capacity(data.schema_fields().len());
@@ -112,19 +161,50 @@ async fn insert_one(
                 }
             }
         }
+        let kind = match ColumnKind::from_str_with_variants(value.kind(), value.variants()) {
+            Ok(kind) => kind,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+        if let Err(err) = kind.check_enum_variants() {
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
+        let validation = match value.validation() {
+            Some(validation) => match validation.to_dao(&kind) {
+                Ok(validation) => validation,
+                Err(err) => {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Error in field '{key}': {err}"),
+                    )
+                }
+            },
+            None => SchemaFieldValidation::default(),
+        };
+        let computed = match value.computed() {
+            Some(computed) => match computed.to_dao(&kind) {
+                Ok(computed) => Some(computed),
+                Err(err) => {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Error in field '{key}': {err}"),
+                    )
+                }
+            },
+            None => None,
+        };
         schema_fields.insert(
             key.to_string(),
             SchemaFieldProps::new(
-                &match ColumnKind::from_str(value.kind()) {
-                    Ok(kind) => kind,
-                    Err(err) => {
-                        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string())
-                    }
-                },
+                &kind,
                 &value.required().unwrap_or(false),
+                &validation,
+                &computed,
             ),
         );
     }
+    if let Err(err) = SchemaFieldComputed::check_schema_fields(&schema_fields) {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
 
     if let Some(indexes) = data.indexes() {
         for index in indexes {
@@ -155,14 +235,82 @@ async fn insert_one(
         }
     }
 
+    let indexes = match data.indexes() {
+        Some(indexes) => indexes.clone(),
+        None => HashSet::new(),
+    };
+
+    let mut partial_indexes = HashMap::new();
+    if let Some(partial_index) = data.partial_indexes() {
+        for (name, value) in partial_index.iter() {
+            if name.is_empty() {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    "Index name in partial_indexes can't be empty string",
+                );
+            }
+            if name.starts_with('_') || !name.chars().all(|c| c == '_' || c.is_ascii_lowercase()) {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Index '{name}' should only have lowercase English letters and an optional underscore (_) after the first character"),
+                );
+            }
+            if indexes.contains(name) {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Index '{name}' in partial_indexes conflicts with a field in indexes"),
+                );
+            }
+            let predicate = value.predicate().clone().unwrap_or_default();
+            for field in value.fields().iter().chain(predicate.keys()) {
+                if !schema_fields.contains_key(field) {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Field '{field}' is in partial index '{name}' but not exist in the schema fields"),
+                    );
+                }
+            }
+            partial_indexes.insert(
+                name.to_owned(),
+                match PartialIndex::new(
+                    value.fields(),
+                    &value.unique().unwrap_or(false),
+                    &predicate,
+                ) {
+                    Ok(partial_index) => partial_index,
+                    Err(err) => {
+                        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string())
+                    }
+                },
+            );
+        }
+    }
+
+    for (name, limit) in [
+        ("max_field_size", data.max_field_size()),
+        ("max_record_size", data.max_record_size()),
+        (
+            "auto_delete_after_seconds",
+            data.auto_delete_after_seconds(),
+        ),
+    ] {
+        if limit.is_some_and(|limit| limit <= 0) {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Value of '{name}' must be greater than 0"),
+            );
+        }
+    }
+
     let collection_data = match CollectionDao::new(
         path.project_id(),
         data.name(),
         &schema_fields,
-        &match data.indexes() {
-            Some(indexes) => indexes.clone(),
-            None => HashSet::new(),
-        },
+        &indexes,
+        &partial_indexes,
+        data.max_field_size(),
+        data.max_record_size(),
+        data.auto_delete_after_seconds(),
     ) {
         Ok(data) => data,
         Err(err) => {
@@ -173,8 +321,81 @@ async fn insert_one(
         return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
     }
 
+    let collection_res = CollectionResJson::from_dao(&collection_data);
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*collection_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "insert",
+        "collection",
+        collection_data.id(),
+        &None,
+        &serde_json::to_value(&collection_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::CREATED, &None, &collection_res)
+}
+
+async fn find_one(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<FindOneCollectionReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            &format!("Failed to get user data: {err}"),
+        );
+    }
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
     Response::data(
-        &StatusCode::CREATED,
+        &StatusCode::OK,
         &None,
         &CollectionResJson::new(
             collection_data.id(),
@@ -188,26 +409,49 @@ async fn insert_one(
                 .map(|(key, value)| {
                     (
                         key.to_owned(),
-                        SchemaFieldPropsJson::new(value.kind().to_str(), &Some(*value.required())),
+                        SchemaFieldPropsJson::new(
+                            value.kind().to_str(),
+                            &Some(*value.required()),
+                            &value.kind().enum_variants().cloned(),
+                            &SchemaFieldValidationJson::from_dao(value.validation()),
+                            &SchemaFieldComputedJson::from_dao(value.computed()),
+                        ),
                     )
                 })
                 .collect(),
             collection_data.indexes(),
+            &collection_data
+                .partial_indexes()
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.to_owned(),
+                        PartialIndexJson::new(
+                            value.fields(),
+                            &Some(*value.unique()),
+                            &Some(value.predicate().clone()),
+                        ),
+                    )
+                })
+                .collect(),
+            collection_data.max_field_size(),
+            collection_data.max_record_size(),
+            collection_data.auto_delete_after_seconds(),
         ),
     )
 }
 
-async fn find_one(
+async fn export_one(
     ctx: web::Data<ApiRestCtx>,
     token: web::Header<TokenReqHeader>,
-    path: web::Path<FindOneCollectionReqPath>,
+    path: web::Path<ExportOneCollectionReqPath>,
 ) -> HttpResponse {
     let token = match token.get() {
         Some(token) => token,
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -234,11 +478,15 @@ async fn find_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if project_data.admin_id() != token_claim.id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
     }
 
     if project_data.id() != collection_data.project_id() {
@@ -248,11 +496,7 @@ async fn find_one(
     Response::data(
         &StatusCode::OK,
         &None,
-        &CollectionResJson::new(
-            collection_data.id(),
-            collection_data.created_at(),
-            collection_data.updated_at(),
-            collection_data.project_id(),
+        &CollectionSchemaResJson::new(
             collection_data.name(),
             &collection_data
                 .schema_fields()
@@ -260,27 +504,49 @@ async fn find_one(
                 .map(|(key, value)| {
                     (
                         key.to_owned(),
-                        SchemaFieldPropsJson::new(value.kind().to_str(), &Some(*value.required())),
+                        SchemaFieldPropsJson::new(
+                            value.kind().to_str(),
+                            &Some(*value.required()),
+                            &value.kind().enum_variants().cloned(),
+                            &SchemaFieldValidationJson::from_dao(value.validation()),
+                            &SchemaFieldComputedJson::from_dao(value.computed()),
+                        ),
                     )
                 })
                 .collect(),
             collection_data.indexes(),
+            &collection_data
+                .partial_indexes()
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.to_owned(),
+                        PartialIndexJson::new(
+                            value.fields(),
+                            &Some(*value.unique()),
+                            &Some(value.predicate().clone()),
+                        ),
+                    )
+                })
+                .collect(),
+            collection_data.max_field_size(),
+            collection_data.max_record_size(),
+            collection_data.auto_delete_after_seconds(),
         ),
     )
 }
 
-async fn update_one(
+async fn index_suggestions(
     ctx: web::Data<ApiRestCtx>,
     token: web::Header<TokenReqHeader>,
-    path: web::Path<UpdateOneCollectionReqPath>,
-    data: web::Json<UpdateOneCollectionReqJson>,
+    path: web::Path<IndexSuggestionsCollectionReqPath>,
 ) -> HttpResponse {
     let token = match token.get() {
         Some(token) => token,
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -299,7 +565,7 @@ async fn update_one(
         );
     }
 
-    let (project_data, mut collection_data) = match tokio::try_join!(
+    let (project_data, collection_data) = match tokio::try_join!(
         ProjectDao::db_select(ctx.dao().db(), path.project_id()),
         CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
     ) {
@@ -307,21 +573,141 @@ async fn update_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if project_data.admin_id() != token_claim.id() {
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    let suggestions = ctx.index_advisor().suggest(&collection_data);
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &suggestions.len(),
+            &Some(suggestions.len()),
+            &None,
+            &None,
+        )),
+        &suggestions
+            .iter()
+            .map(|(field, usage)| {
+                IndexSuggestionResJson::new(field, usage.filter_count(), usage.order_count())
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+async fn update_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<UpdateOneCollectionReqPath>,
+    data: web::Json<UpdateOneCollectionReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_schemas() {
         return Response::error_raw(
             &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
+            "Your admin role doesn't allow modifying schemas",
         );
     }
 
+    let (project_data, mut collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_schemas() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying schemas",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
+    }
+
     if project_data.id() != collection_data.project_id() {
         return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
     }
 
+    let collection_before = CollectionResJson::from_dao(&collection_data);
+
     if let Some(name) = data.name() {
         collection_data.set_name(name);
     }
 
+    if let Some(rename_fields) = data.rename_fields() {
+        for to in rename_fields.values() {
+            if to.is_empty() {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    "Field name in rename_fields can't be empty string",
+                );
+            }
+            if to.starts_with('_') || !to.chars().all(|c| c == '_' || c.is_ascii_lowercase()) {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Field '{to}' should only have lowercase English letters and an optional underscore (_) after the first character"),
+                );
+            }
+        }
+        if let Err(err) = collection_data.rename_schema_fields(rename_fields) {
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
+    }
+
     if let Some(schema_field) = data.schema_fields() {
         let mut schema_fields = HashMap::with_capacity(schema_field.len());
         for (key, value) in schema_field.iter() {
@@ -349,22 +735,55 @@ async fn update_one(
                     }
                 }
             }
+            let kind = match ColumnKind::from_str_with_variants(value.kind(), value.variants()) {
+                Ok(kind) => kind,
+                Err(err) => {
+                    return Response::error_raw(
+                        &StatusCode::INTERNAL_SERVER_ERROR,
+                        &err.to_string(),
+                    )
+                }
+            };
+            if let Err(err) = kind.check_enum_variants() {
+                return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+            }
+            let validation = match value.validation() {
+                Some(validation) => match validation.to_dao(&kind) {
+                    Ok(validation) => validation,
+                    Err(err) => {
+                        return Response::error_raw(
+                            &StatusCode::BAD_REQUEST,
+                            &format!("Error in field '{key}': {err}"),
+                        )
+                    }
+                },
+                None => SchemaFieldValidation::default(),
+            };
+            let computed = match value.computed() {
+                Some(computed) => match computed.to_dao(&kind) {
+                    Ok(computed) => Some(computed),
+                    Err(err) => {
+                        return Response::error_raw(
+                            &StatusCode::BAD_REQUEST,
+                            &format!("Error in field '{key}': {err}"),
+                        )
+                    }
+                },
+                None => None,
+            };
             schema_fields.insert(
                 key.to_owned(),
                 SchemaFieldProps::new(
-                    &match ColumnKind::from_str(value.kind()) {
-                        Ok(kind) => kind,
-                        Err(err) => {
-                            return Response::error_raw(
-                                &StatusCode::INTERNAL_SERVER_ERROR,
-                                &err.to_string(),
-                            )
-                        }
-                    },
+                    &kind,
                     &value.required().unwrap_or(false),
+                    &validation,
+                    &computed,
                 ),
             );
         }
+        if let Err(err) = SchemaFieldComputed::check_schema_fields(&schema_fields) {
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
         collection_data.update_schema_fields(&schema_fields);
     }
 
@@ -400,38 +819,138 @@ async fn update_one(
         collection_data.update_indexes(indexes);
     }
 
+    if let Some(partial_index) = data.partial_indexes() {
+        let mut partial_indexes = HashMap::with_capacity(partial_index.len());
+        for (name, value) in partial_index.iter() {
+            if name.is_empty() {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    "Index name in partial_indexes can't be empty string",
+                );
+            }
+            if name.starts_with('_') || !name.chars().all(|c| c == '_' || c.is_ascii_lowercase()) {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Index '{name}' should only have lowercase English letters and an optional underscore (_) after the first character"),
+                );
+            }
+            if collection_data.indexes().contains(name) {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Index '{name}' in partial_indexes conflicts with a field in indexes"),
+                );
+            }
+            let predicate = value.predicate().clone().unwrap_or_default();
+            for field in value.fields().iter().chain(predicate.keys()) {
+                if !collection_data.schema_fields().contains_key(field) {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Field '{field}' is in partial index '{name}' but not exist in the schema fields"),
+                    );
+                }
+            }
+            partial_indexes.insert(
+                name.to_owned(),
+                match PartialIndex::new(
+                    value.fields(),
+                    &value.unique().unwrap_or(false),
+                    &predicate,
+                ) {
+                    Ok(partial_index) => partial_index,
+                    Err(err) => {
+                        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string())
+                    }
+                },
+            );
+        }
+        collection_data.update_partial_indexes(&partial_indexes);
+    }
+
+    for (name, limit) in [
+        ("max_field_size", data.max_field_size()),
+        ("max_record_size", data.max_record_size()),
+        (
+            "auto_delete_after_seconds",
+            data.auto_delete_after_seconds(),
+        ),
+    ] {
+        if limit.is_some_and(|limit| limit.is_some_and(|limit| limit <= 0)) {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Value of '{name}' must be greater than 0"),
+            );
+        }
+    }
+
+    if let Some(max_field_size) = data.max_field_size() {
+        collection_data.set_max_field_size(max_field_size);
+    }
+
+    if let Some(max_record_size) = data.max_record_size() {
+        collection_data.set_max_record_size(max_record_size);
+    }
+
+    if let Some(auto_delete_after_seconds) = data.auto_delete_after_seconds() {
+        collection_data.update_auto_delete_after_seconds(auto_delete_after_seconds);
+    }
+
+    collection_data.set_transform_schema_fields(&data.transform().unwrap_or(false));
+
+    if data.dry_run().unwrap_or(false) {
+        let failures = match collection_data
+            .db_check_schema_fields_transform(ctx.dao().db())
+            .await
+        {
+            Ok(failures) => failures,
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+
+        return Response::data(
+            &StatusCode::OK,
+            &None,
+            &TransformCollectionResJson::new(
+                &failures
+                    .iter()
+                    .map(|(field, record_id, message)| {
+                        TransformCollectionFailureResJson::new(field, record_id, message)
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+        );
+    }
+
     if !data.is_all_none() {
         if let Err(err) = collection_data.db_update(ctx.dao().db()).await {
             return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
         }
     }
 
-    Response::data(
-        &StatusCode::OK,
-        &None,
-        &CollectionResJson::new(
-            collection_data.id(),
-            collection_data.created_at(),
-            collection_data.updated_at(),
-            collection_data.project_id(),
-            collection_data.name(),
-            &collection_data
-                .schema_fields()
-                .iter()
-                .map(|(key, value)| {
-                    (
-                        key.to_owned(),
-                        SchemaFieldPropsJson::new(value.kind().to_str(), &Some(*value.required())),
-                    )
-                })
-                .collect(),
-            collection_data.indexes(),
-        ),
+    let collection_res = CollectionResJson::from_dao(&collection_data);
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*collection_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "update",
+        "collection",
+        collection_data.id(),
+        &serde_json::to_value(&collection_before).ok(),
+        &serde_json::to_value(&collection_res).ok(),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &collection_res)
 }
 
 async fn delete_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     path: web::Path<DeleteOneCollectionReqPath>,
 ) -> HttpResponse {
@@ -440,7 +959,7 @@ async fn delete_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -452,10 +971,20 @@ async fn delete_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_schemas() {
         return Response::error_raw(
-            &StatusCode::BAD_REQUEST,
-            &format!("Failed to get user data: {err}"),
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying schemas",
         );
     }
 
@@ -467,26 +996,60 @@ async fn delete_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if project_data.admin_id() != token_claim.id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_schemas() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying schemas",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
     }
 
     if project_data.id() != collection_data.project_id() {
         return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
     }
 
+    let collection_before = CollectionResJson::from_dao(&collection_data);
+
     if let Err(err) = CollectionDao::db_delete(ctx.dao().db(), path.collection_id()).await {
         return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
     }
 
-    Response::data(
-        &StatusCode::OK,
+    ctx.index_advisor().clear(path.collection_id());
+    ctx.plugin().invalidate(path.collection_id());
+
+    let collection_res = DeleteCollectionResJson::new(collection_data.id());
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*collection_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "delete",
+        "collection",
+        collection_data.id(),
+        &serde_json::to_value(&collection_before).ok(),
         &None,
-        &DeleteCollectionResJson::new(collection_data.id()),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &collection_res)
 }
 
 async fn find_many(
@@ -499,7 +1062,7 @@ async fn find_many(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -523,11 +1086,15 @@ async fn find_many(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if project_data.admin_id() != token_claim.id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
     }
 
     let collections_data = match CollectionDao::db_select_many_by_project_id(
@@ -544,7 +1111,9 @@ async fn find_many(
         &StatusCode::OK,
         &Some(PaginationRes::new(
             &collections_data.len(),
-            &collections_data.len(),
+            &Some(collections_data.len()),
+            &None,
+            &None,
         )),
         &collections_data
             .iter()
@@ -564,11 +1133,31 @@ async fn find_many(
                                 SchemaFieldPropsJson::new(
                                     value.kind().to_str(),
                                     &Some(*value.required()),
+                                    &value.kind().enum_variants().cloned(),
+                                    &SchemaFieldValidationJson::from_dao(value.validation()),
+                                    &SchemaFieldComputedJson::from_dao(value.computed()),
                                 ),
                             )
                         })
                         .collect(),
                     data.indexes(),
+                    &data
+                        .partial_indexes()
+                        .iter()
+                        .map(|(key, value)| {
+                            (
+                                key.to_owned(),
+                                PartialIndexJson::new(
+                                    value.fields(),
+                                    &Some(*value.unique()),
+                                    &Some(value.predicate().clone()),
+                                ),
+                            )
+                        })
+                        .collect(),
+                    data.max_field_size(),
+                    data.max_record_size(),
+                    data.auto_delete_after_seconds(),
                 )
             })
             .collect::<Vec<_>>(),
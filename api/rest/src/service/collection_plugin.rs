@@ -0,0 +1,360 @@
+use std::sync::Arc;
+
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use futures::StreamExt;
+use hb_dao::{
+    admin::AdminDao, audit_log::AuditLogDao, collection::CollectionDao,
+    collection_plugin::CollectionPluginDao, project::ProjectDao,
+};
+use hb_token_jwt::kind::JwtTokenKind;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        collection_plugin::{
+            CollectionPluginReqPath, CollectionPluginResJson, DeleteCollectionPluginResJson,
+        },
+        Response, TokenReqHeader,
+    },
+    plugin, project_access,
+};
+
+pub fn collection_plugin_api(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        "/project/{project_id}/collection/{collection_id}/plugin",
+        web::get().to(find_one),
+    )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/plugin",
+        web::put().to(upsert_one),
+    )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/plugin",
+        web::delete().to(delete_one),
+    );
+}
+
+async fn find_one(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<CollectionPluginReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            &format!("Failed to get user data: {err}"),
+        );
+    }
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    let plugin_data =
+        match CollectionPluginDao::db_select(ctx.dao().db(), collection_data.id()).await {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                return Response::error_raw(&StatusCode::NOT_FOUND, "This collection has no plugin")
+            }
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+
+    let plugin = match plugin::load(&ctx, collection_data.id()).await {
+        Ok(Some(plugin)) => plugin,
+        Ok(None) => {
+            return Response::error_raw(&StatusCode::NOT_FOUND, "This collection has no plugin")
+        }
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &CollectionPluginResJson::new(&plugin_data, &plugin),
+    )
+}
+
+async fn upsert_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<CollectionPluginReqPath>,
+    mut payload: web::Payload,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_schemas() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying schemas",
+        );
+    }
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_schemas() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying schemas",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    let mut module = web::BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        match chunk {
+            Ok(chunk) => {
+                if module.len() + chunk.len() > *ctx.plugin().max_module_bytes() {
+                    return Response::error_raw(
+                        &StatusCode::PAYLOAD_TOO_LARGE,
+                        &format!(
+                            "Plugin module exceeds the limit of {} bytes",
+                            ctx.plugin().max_module_bytes()
+                        ),
+                    );
+                }
+                module.extend_from_slice(&chunk);
+            }
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        }
+    }
+
+    let plugin = match ctx
+        .plugin()
+        .runtime()
+        .compile(&collection_data.id().to_string(), &module)
+    {
+        Ok(plugin) => Arc::new(plugin),
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let (plugin_data, action) =
+        match CollectionPluginDao::db_select(ctx.dao().db(), collection_data.id()).await {
+            Ok(Some(mut data)) => {
+                data.set_module(&module);
+                (data, "update")
+            }
+            Ok(None) => (
+                CollectionPluginDao::new(collection_data.id(), &module),
+                "insert",
+            ),
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+
+    if let Err(err) = plugin_data.db_upsert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let plugin_res = CollectionPluginResJson::new(&plugin_data, &plugin);
+    ctx.plugin().cache(collection_data.id(), Some(plugin));
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*collection_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        action,
+        "collection_plugin",
+        collection_data.id(),
+        &None,
+        &serde_json::to_value(&plugin_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &plugin_res)
+}
+
+async fn delete_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<CollectionPluginReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_schemas() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying schemas",
+        );
+    }
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_schemas() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying schemas",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    if let Err(err) = CollectionPluginDao::db_delete(ctx.dao().db(), collection_data.id()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    ctx.plugin().invalidate(collection_data.id());
+
+    let plugin_res = DeleteCollectionPluginResJson::new(collection_data.id());
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*collection_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "delete",
+        "collection_plugin",
+        collection_data.id(),
+        &None,
+        &serde_json::to_value(&plugin_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &plugin_res)
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use actix_web::{http::StatusCode, web, HttpResponse};
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        health::{HealthCheckResJson, HealthResJson},
+        Response,
+    },
+};
+
+const DB_PING_TIMEOUT: Duration = Duration::from_secs(5);
+const MAILER_MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+pub fn health_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/healthz", web::get().to(liveness))
+        .route("/readyz", web::get().to(readiness));
+}
+
+async fn liveness() -> HttpResponse {
+    Response::data(&StatusCode::OK, &None, HealthResJson::new(&[]))
+}
+
+async fn readiness(ctx: web::Data<ApiRestCtx>) -> HttpResponse {
+    let db_check = match tokio::time::timeout(DB_PING_TIMEOUT, ctx.dao().db().ping()).await {
+        Ok(Ok(_)) => HealthCheckResJson::new("db", &true, &None),
+        Ok(Err(err)) => HealthCheckResJson::new("db", &false, &Some(err.to_string())),
+        Err(_) => HealthCheckResJson::new(
+            "db",
+            &false,
+            &Some(format!(
+                "Database did not respond within {} seconds",
+                DB_PING_TIMEOUT.as_secs()
+            )),
+        ),
+    };
+
+    let mailer_health = ctx.mailer().health();
+    let mailer_check = if !mailer_health.is_running() {
+        HealthCheckResJson::new("mailer", &false, &Some("Mailer is not running".to_owned()))
+    } else if mailer_health.consecutive_failures() >= MAILER_MAX_CONSECUTIVE_FAILURES {
+        HealthCheckResJson::new(
+            "mailer",
+            &false,
+            &Some(format!(
+                "The last {} mails failed to send",
+                mailer_health.consecutive_failures()
+            )),
+        )
+    } else {
+        HealthCheckResJson::new("mailer", &true, &None)
+    };
+
+    let health = HealthResJson::new(&[db_check, mailer_check]);
+    let status_code = match health.is_healthy() {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    Response::data(&status_code, &None, health)
+}
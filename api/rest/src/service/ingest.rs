@@ -0,0 +1,365 @@
+use std::net::IpAddr;
+
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hb_dao::{
+    admin::{AdminDao, AdminRole},
+    collection::CollectionDao,
+    project::ProjectDao,
+    record::RecordDao,
+    token::TokenDao,
+    value::ColumnValue,
+};
+use hb_event::RecordEventKind;
+use uuid::Uuid;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        ingest::{IngestReqJson, IngestReqPath, IngestResJson, IngestTokenReqHeader},
+        Response,
+    },
+    plugin::{self, PluginError},
+    project_access, quota, rule,
+};
+
+const INGEST_BATCH_SIZE: usize = 100;
+
+pub fn ingest_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/ingest/{collection_id}", web::post().to(insert_many));
+}
+
+// Authorizes with the access token itself, its collection rules and the project role of the admin
+// owning it, skipping the JWT decode and revocation checks of the record API
+async fn insert_many(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<IngestTokenReqHeader>,
+    path: web::Path<IngestReqPath>,
+    data: web::Json<IngestReqJson>,
+) -> HttpResponse {
+    let (token_data, collection_data) = match tokio::try_join!(
+        TokenDao::db_select_by_token(ctx.dao().db(), token.get()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id())
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if let Err(err) = check_token(
+        &token_data,
+        collection_data.id(),
+        &req.peer_addr().map(|addr| addr.ip()),
+        &Utc::now(),
+    ) {
+        return Response::error_raw(&StatusCode::FORBIDDEN, err);
+    }
+
+    let (admin_data, project_data) = tokio::join!(
+        AdminDao::db_select(ctx.dao().db(), token_data.admin_id()),
+        ProjectDao::db_select(ctx.dao().db(), collection_data.project_id())
+    );
+    if let Err(err) = admin_data {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            &format!("Failed to get the admin owning this token: {err}"),
+        );
+    }
+    let project_data = match project_data {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_data.admin_id()).await {
+        Ok(role) => {
+            if let Err(err) = check_role(&role) {
+                return Response::error_raw(&StatusCode::FORBIDDEN, err);
+            }
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
+    }
+
+    let data = data.into_inner().into_records();
+    if data.is_empty() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "No records to ingest");
+    }
+
+    let mut records = Vec::with_capacity(data.len());
+    for (idx, mut data) in data.into_iter().enumerate() {
+        if let Err(err) = plugin::before(
+            &ctx,
+            &collection_data,
+            &RecordEventKind::Insert,
+            &None,
+            &mut data,
+        )
+        .await
+        {
+            return match err {
+                PluginError::Rejected(message) => Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Record {idx}: {message}"),
+                ),
+                err => err.to_response(),
+            };
+        }
+
+        for field_name in data.keys() {
+            if !collection_data.schema_fields().contains_key(field_name) {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Record {idx}: Field '{field_name}' is not exist in the collection"),
+                );
+            }
+        }
+
+        let mut record_data = RecordDao::new(collection_data.id(), &Some(data.len()));
+        record_data.upsert("_created_by", &ColumnValue::Uuid(None));
+        for (field_name, field_props) in collection_data.schema_fields() {
+            let value = data.get(field_name).filter(|value| !value.is_null());
+            if field_props.computed().is_some() {
+                if value.is_some() {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Record {idx}: Field '{field_name}' is computed and can't be set"),
+                    );
+                }
+                continue;
+            }
+            match value {
+                Some(value) => match ColumnValue::from_serde_json(field_props.kind(), value) {
+                    Ok(value) => record_data.upsert(field_name, &value),
+                    Err(err) => {
+                        return Response::error_raw(
+                            &StatusCode::BAD_REQUEST,
+                            &format!("Record {idx}: Error in field '{field_name}': {err}"),
+                        )
+                    }
+                },
+                None if *field_props.required() => {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Record {idx}: Value for '{field_name}' is required"),
+                    )
+                }
+                None => record_data.upsert(field_name, &ColumnValue::none(field_props.kind())),
+            }
+        }
+
+        if let Err(err) = record_data.compute_fields(&collection_data) {
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &format!("Record {idx}: {err}"));
+        }
+        if let Some((field, err)) = record_data.check_validation(&collection_data).first() {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Record {idx}: Error in field '{field}': {}", err.message()),
+            );
+        }
+        if let Err(err) = record_data.check_size(&collection_data) {
+            return Response::error_raw(
+                &StatusCode::PAYLOAD_TOO_LARGE,
+                &format!("Record {idx}: {err}"),
+            );
+        }
+        if let Err(err) = rule::check(
+            &req,
+            &token_data,
+            &None,
+            collection_data.id(),
+            "insert",
+            Some(&record_data),
+            Some(&data),
+        ) {
+            return Response::error_raw(&StatusCode::FORBIDDEN, &format!("Record {idx}: {err}"));
+        }
+
+        records.push(record_data);
+    }
+
+    if let Err(err) = quota::check_records(&ctx, &collection_data, &records.len()).await {
+        return err.to_response();
+    }
+
+    let mut inserted = 0;
+    for batch in records.chunks(INGEST_BATCH_SIZE) {
+        if let Err(err) = RecordDao::db_insert_many(ctx.dao().db(), batch).await {
+            return Response::error_raw(
+                &StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("Failed after inserting {inserted} records: {err}"),
+            );
+        }
+        inserted += batch.len();
+    }
+
+    if ctx.event_bus().has_subscribers() || plugin::has_after(&ctx, &collection_data).await {
+        for record_data in &records {
+            let record_id = match record_data.get("_id") {
+                Some(ColumnValue::Uuid(Some(record_id))) => *record_id,
+                _ => continue,
+            };
+            let record = match record_data
+                .data()
+                .iter()
+                .map(|(key, value)| Ok((key.to_owned(), value.to_serde_json()?)))
+                .collect::<Result<serde_json::Map<_, _>>>()
+            {
+                Ok(record) => record,
+                Err(err) => {
+                    hb_log::error(None, &err);
+                    continue;
+                }
+            };
+            ctx.event_bus().publish_record(
+                &RecordEventKind::Insert,
+                collection_data.project_id(),
+                collection_data.id(),
+                &record_id,
+                Some(&record),
+            );
+            plugin::after(
+                &ctx,
+                &collection_data,
+                &RecordEventKind::Insert,
+                &record_id,
+                Some(&record),
+            )
+            .await;
+        }
+    }
+
+    Response::data(&StatusCode::CREATED, &None, &IngestResJson::new(&inserted))
+}
+
+fn check_token(
+    token_data: &TokenDao,
+    collection_id: &Uuid,
+    ip: &Option<IpAddr>,
+    now: &DateTime<Utc>,
+) -> Result<(), &'static str> {
+    if token_data
+        .expired_at()
+        .is_some_and(|expired_at| expired_at <= *now)
+    {
+        return Err("This token has expired");
+    }
+    if !token_data.is_allow_ip(ip) {
+        return Err("This token is not allowed from this IP address");
+    }
+    if !token_data.is_allow_scope("records:write") {
+        return Err("This token doesn't have the 'records:write' scope");
+    }
+    if !token_data.is_allow_insert(collection_id) {
+        return Err("This token doesn't have permission to write data to this collection");
+    }
+    Ok(())
+}
+
+// The admin owning the token may have been removed from the project or demoted since it was made
+fn check_role(role: &Option<AdminRole>) -> Result<(), &'static str> {
+    match role {
+        Some(role) if role.can_write_records() => Ok(()),
+        Some(_) => Err("The project role of this token's admin doesn't allow modifying records"),
+        None => Err("This token doesn't belong to this project"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use ahash::{HashMap, HashMapExt};
+    use chrono::{Duration, Utc};
+    use hb_dao::{
+        admin::AdminRole,
+        token::{TokenDao, TokenRuleMethod},
+    };
+    use uuid::Uuid;
+
+    use super::{check_role, check_token};
+
+    fn token(
+        collection_id: &Uuid,
+        insert: bool,
+        expired_at: Option<chrono::DateTime<Utc>>,
+        allowed_cidrs: Option<Vec<String>>,
+        scopes: Option<Vec<String>>,
+    ) -> TokenDao {
+        let mut rules = HashMap::new();
+        rules.insert(
+            *collection_id,
+            TokenRuleMethod::new(&true, &true, &insert, &false, &false, &None),
+        );
+        TokenDao::new(
+            &Uuid::now_v7(),
+            &16,
+            &rules,
+            &expired_at,
+            &allowed_cidrs,
+            &scopes,
+        )
+    }
+
+    #[test]
+    fn check_token_accepts_a_valid_token() {
+        let collection_id = Uuid::now_v7();
+        let token_data = token(&collection_id, true, None, None, None);
+        assert!(check_token(&token_data, &collection_id, &None, &Utc::now()).is_ok());
+    }
+
+    #[test]
+    fn check_token_rejects_an_expired_token() {
+        let collection_id = Uuid::now_v7();
+        let now = Utc::now();
+        let token_data = token(&collection_id, true, Some(now), None, None);
+        assert!(check_token(&token_data, &collection_id, &None, &now).is_err());
+        assert!(check_token(
+            &token_data,
+            &collection_id,
+            &None,
+            &(now - Duration::seconds(1))
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn check_token_rejects_other_ips_scopes_and_collections() {
+        let collection_id = Uuid::now_v7();
+        let ip: Option<IpAddr> = Some("192.0.2.1".parse().unwrap());
+
+        let token_data = token(
+            &collection_id,
+            true,
+            None,
+            Some(vec!["198.51.100.0/24".to_owned()]),
+            None,
+        );
+        assert!(check_token(&token_data, &collection_id, &ip, &Utc::now()).is_err());
+
+        let token_data = token(
+            &collection_id,
+            true,
+            None,
+            None,
+            Some(vec!["records:read".to_owned()]),
+        );
+        assert!(check_token(&token_data, &collection_id, &ip, &Utc::now()).is_err());
+
+        let token_data = token(&collection_id, false, None, None, None);
+        assert!(check_token(&token_data, &collection_id, &ip, &Utc::now()).is_err());
+        let token_data = token(&collection_id, true, None, None, None);
+        assert!(check_token(&token_data, &Uuid::now_v7(), &ip, &Utc::now()).is_err());
+    }
+
+    #[test]
+    fn check_role_requires_a_write_role_in_the_project() {
+        assert!(check_role(&Some(AdminRole::Owner)).is_ok());
+        assert!(check_role(&Some(AdminRole::Editor)).is_ok());
+        assert!(check_role(&Some(AdminRole::Viewer)).is_err());
+        assert!(check_role(&None).is_err());
+    }
+}
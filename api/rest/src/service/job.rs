@@ -0,0 +1,56 @@
+use actix_web::{http::StatusCode, web, HttpResponse};
+use hb_dao::admin::AdminDao;
+use hb_token_jwt::kind::JwtTokenKind;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{job::JobResJson, PaginationRes, Response, TokenReqHeader},
+};
+
+pub fn job_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/admin/jobs", web::get().to(find_many));
+}
+
+async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !ctx.is_operator(admin_data.email()) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Only server operators can view scheduled jobs",
+        );
+    }
+
+    let statuses = ctx.jobs().all();
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &statuses.len(),
+            &Some(statuses.len()),
+            &None,
+            &None,
+        )),
+        &statuses.iter().map(JobResJson::new).collect::<Vec<_>>(),
+    )
+}
@@ -0,0 +1,132 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use hb_dao::{admin::AdminDao, audit_log::AuditLogDao};
+use hb_token_jwt::kind::JwtTokenKind;
+use validator::Validate;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        log_filter::{LogFilterResJson, UpdateLogFilterReqJson},
+        Response, TokenReqHeader,
+    },
+};
+
+pub fn log_filter_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/admin/log_filter", web::get().to(find_one))
+        .route("/admin/log_filter", web::patch().to(update_one));
+}
+
+async fn find_one(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !ctx.is_operator(admin_data.email()) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Only server operators can view the log filter",
+        );
+    }
+
+    let filter = match hb_log::filter() {
+        Ok(filter) => filter,
+        Err(err) => return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err),
+    };
+
+    Response::data(&StatusCode::OK, &None, LogFilterResJson::new(&filter))
+}
+
+async fn update_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    data: web::Json<UpdateLogFilterReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !ctx.is_operator(admin_data.email()) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Only server operators can change the log filter",
+        );
+    }
+
+    let log_filter_before = hb_log::filter()
+        .ok()
+        .map(|filter| LogFilterResJson::new(&filter));
+
+    let filter = match hb_log::set_filter(data.filter()) {
+        Ok(filter) => filter,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err),
+    };
+
+    hb_log::info(
+        None,
+        format!(
+            "Log filter changed to '{filter}' by admin {}",
+            admin_data.id()
+        ),
+    );
+
+    let log_filter_res = LogFilterResJson::new(&filter);
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &None,
+        &req.peer_addr().map(|addr| addr.ip()),
+        "update",
+        "log_filter",
+        admin_data.id(),
+        &log_filter_before.and_then(|log_filter| serde_json::to_value(log_filter).ok()),
+        &serde_json::to_value(&log_filter_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &log_filter_res)
+}
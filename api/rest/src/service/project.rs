@@ -1,17 +1,34 @@
-use actix_web::{http::StatusCode, web, HttpResponse};
-use hb_dao::{admin::AdminDao, project::ProjectDao};
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use ahash::{HashMap, HashMapExt, HashSet};
+use anyhow::{Error, Result};
+use chrono::Utc;
+use futures::future;
+use hb_dao::{
+    admin::AdminDao,
+    audit_log::AuditLogDao,
+    collection::{
+        CollectionDao, PartialIndex, SchemaFieldComputed, SchemaFieldProps, SchemaFieldValidation,
+    },
+    project::ProjectDao,
+    project_member::ProjectMemberDao,
+    value::ColumnKind,
+};
 use hb_token_jwt::kind::JwtTokenKind;
 
 use crate::{
     context::ApiRestCtx,
     model::{
         project::{
+            ApplyProjectConfigReqJson, ApplyProjectConfigReqPath, ArchiveOneProjectReqPath,
             DeleteOneProjectReqPath, DeleteProjectResJson, FindOneProjectReqPath,
-            InsertOneProjectReqJson, ProjectResJson, UpdateOneProjectReqJson,
+            InsertOneProjectReqJson, ProjectConfigCollectionJson, ProjectConfigResJson,
+            ProjectResJson, RestoreOneProjectReqPath, ResumeOneProjectReqPath,
+            SuspendOneProjectReqPath, UnarchiveOneProjectReqPath, UpdateOneProjectReqJson,
             UpdateOneProjectReqPath,
         },
         PaginationRes, Response, TokenReqHeader,
     },
+    project_access, quota,
 };
 
 pub fn project_api(cfg: &mut web::ServiceConfig) {
@@ -19,11 +36,21 @@ pub fn project_api(cfg: &mut web::ServiceConfig) {
         .route("/project/{project_id}", web::get().to(find_one))
         .route("/project/{project_id}", web::patch().to(update_one))
         .route("/project/{project_id}", web::delete().to(delete_one))
+        .route("/project/{project_id}/suspend", web::post().to(suspend_one))
+        .route("/project/{project_id}/resume", web::post().to(resume_one))
+        .route("/project/{project_id}/archive", web::post().to(archive_one))
+        .route(
+            "/project/{project_id}/unarchive",
+            web::post().to(unarchive_one),
+        )
+        .route("/project/{project_id}/restore", web::post().to(restore_one))
+        .route("/project/{project_id}/config", web::put().to(apply_config))
         .route("/projects", web::get().to(find_many));
 }
 
 async fn insert_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     data: web::Json<InsertOneProjectReqJson>,
 ) -> HttpResponse {
@@ -32,7 +59,7 @@ async fn insert_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -44,29 +71,60 @@ async fn insert_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_projects() {
         return Response::error_raw(
-            &StatusCode::BAD_REQUEST,
-            &format!("Failed to get user data: {err}"),
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying projects",
         );
     }
 
-    let project_data = ProjectDao::new(token_claim.id(), data.name());
+    let mut project_data = ProjectDao::new(token_claim.id(), data.name());
+
+    if let Err(err) = project_data.set_token_claims(data.token_claims()) {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
 
     if let Err(err) = project_data.db_insert(ctx.dao().db()).await {
         return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
     }
 
-    Response::data(
-        &StatusCode::CREATED,
+    let project_res = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*project_data.id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "insert",
+        "project",
+        project_data.id(),
         &None,
-        &ProjectResJson::new(
-            project_data.id(),
-            project_data.created_at(),
-            project_data.updated_at(),
-            project_data.name(),
-        ),
+        &serde_json::to_value(&project_res).ok(),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::CREATED, &None, &project_res)
 }
 
 async fn find_one(
@@ -79,7 +137,7 @@ async fn find_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -103,11 +161,17 @@ async fn find_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if project_data.admin_id() != token_claim.id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match ProjectMemberDao::db_select_role(ctx.dao().db(), &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
     }
 
     Response::data(
@@ -118,12 +182,16 @@ async fn find_one(
             project_data.created_at(),
             project_data.updated_at(),
             project_data.name(),
+            project_data.suspended(),
+            project_data.archived(),
+            project_data.token_claims(),
         ),
     )
 }
 
 async fn update_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     path: web::Path<UpdateOneProjectReqPath>,
     data: web::Json<UpdateOneProjectReqJson>,
@@ -133,7 +201,7 @@ async fn update_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -145,10 +213,231 @@ async fn update_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_projects() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying projects",
+        );
+    }
+
+    let mut project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_projects() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying projects",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let project_before = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if let Some(name) = data.name() {
+        project_data.set_name(name);
+    }
+
+    if let Some(token_claims) = data.token_claims() {
+        if let Err(err) = project_data.set_token_claims(token_claims) {
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
+    }
+
+    if !data.is_all_none() {
+        if let Err(err) = project_data.db_update(ctx.dao().db()).await {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+    }
+
+    let project_res = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*project_data.id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "update",
+        "project",
+        project_data.id(),
+        &serde_json::to_value(&project_before).ok(),
+        &serde_json::to_value(&project_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &project_res)
+}
+
+async fn suspend_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<SuspendOneProjectReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
         return Response::error_raw(
             &StatusCode::BAD_REQUEST,
-            &format!("Failed to get user data: {err}"),
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    // Suspension is the instance operators' kill switch for abusive tenants
+    if !ctx.is_operator(admin_data.email()) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Only server operators can suspend projects",
+        );
+    }
+
+    let mut project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let project_before = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if !*project_data.suspended() {
+        project_data.set_suspended(&true);
+        if let Err(err) = project_data.db_update(ctx.dao().db()).await {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+    }
+
+    let project_res = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*project_data.id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "suspend",
+        "project",
+        project_data.id(),
+        &serde_json::to_value(&project_before).ok(),
+        &serde_json::to_value(&project_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &project_res)
+}
+
+async fn resume_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<ResumeOneProjectReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    // Owners can't lift a suspension, otherwise a suspended tenant could resume itself
+    if !ctx.is_operator(admin_data.email()) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Only server operators can resume projects",
         );
     }
 
@@ -157,46 +446,173 @@ async fn update_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if project_data.admin_id() != token_claim.id() {
+    let project_before = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if *project_data.suspended() {
+        project_data.set_suspended(&false);
+        if let Err(err) = project_data.db_update(ctx.dao().db()).await {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+    }
+
+    let project_res = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*project_data.id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "resume",
+        "project",
+        project_data.id(),
+        &serde_json::to_value(&project_before).ok(),
+        &serde_json::to_value(&project_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &project_res)
+}
+
+async fn archive_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<ArchiveOneProjectReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_projects() {
         return Response::error_raw(
             &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
+            "Your admin role doesn't allow modifying projects",
         );
     }
 
-    if let Some(name) = data.name() {
-        project_data.set_name(name);
+    let mut project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_projects() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying projects",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
     }
 
-    if !data.is_all_none() {
+    let project_before = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if !*project_data.archived() {
+        project_data.set_archived(&true);
         if let Err(err) = project_data.db_update(ctx.dao().db()).await {
             return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
         }
     }
 
-    Response::data(
-        &StatusCode::OK,
-        &None,
-        &ProjectResJson::new(
-            project_data.id(),
-            project_data.created_at(),
-            project_data.updated_at(),
-            project_data.name(),
-        ),
+    let project_res = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*project_data.id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "archive",
+        "project",
+        project_data.id(),
+        &serde_json::to_value(&project_before).ok(),
+        &serde_json::to_value(&project_res).ok(),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &project_res)
 }
 
-async fn delete_one(
+async fn unarchive_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
-    path: web::Path<DeleteOneProjectReqPath>,
+    path: web::Path<UnarchiveOneProjectReqPath>,
 ) -> HttpResponse {
     let token = match token.get() {
         Some(token) => token,
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -208,34 +624,297 @@ async fn delete_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_projects() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying projects",
+        );
+    }
+
+    let mut project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_projects() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying projects",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let project_before = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if *project_data.archived() {
+        project_data.set_archived(&false);
+        if let Err(err) = project_data.db_update(ctx.dao().db()).await {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+    }
+
+    let project_res = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*project_data.id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "unarchive",
+        "project",
+        project_data.id(),
+        &serde_json::to_value(&project_before).ok(),
+        &serde_json::to_value(&project_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &project_res)
+}
+
+async fn restore_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<RestoreOneProjectReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
         return Response::error_raw(
             &StatusCode::BAD_REQUEST,
-            &format!("Failed to get user data: {err}"),
+            "Must be logged in using password-based login",
         );
     }
 
-    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
         Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_projects() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying projects",
+        );
+    }
+
+    let mut project_data =
+        match ProjectDao::db_select_including_deleted(ctx.dao().db(), path.project_id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_projects() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying projects",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if project_data.deleted_at().is_none() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project is not deleted");
+    }
+
+    if let Err(err) = project_data
+        .db_update_deleted_at(ctx.dao().db(), &None)
+        .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let project_res = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*project_data.id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "restore",
+        "project",
+        project_data.id(),
+        &None,
+        &serde_json::to_value(&project_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &project_res)
+}
+
+async fn delete_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<DeleteOneProjectReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if project_data.admin_id() != token_claim.id() {
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_projects() {
         return Response::error_raw(
             &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
+            "Your admin role doesn't allow modifying projects",
         );
     }
 
-    if let Err(err) = ProjectDao::db_delete(ctx.dao().db(), path.project_id()).await {
+    let mut project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_projects() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying projects",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let project_before = ProjectResJson::new(
+        project_data.id(),
+        project_data.created_at(),
+        project_data.updated_at(),
+        project_data.name(),
+        project_data.suspended(),
+        project_data.archived(),
+        project_data.token_claims(),
+    );
+
+    // Collections, members, and users are kept until the project purger removes the project after
+    // its grace period, so the project can still be restored until then
+    if let Err(err) = project_data
+        .db_update_deleted_at(ctx.dao().db(), &Some(Utc::now()))
+        .await
+    {
         return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
     }
 
-    Response::data(
-        &StatusCode::OK,
+    let project_res = DeleteProjectResJson::new(project_data.id());
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*project_data.id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "delete",
+        "project",
+        project_data.id(),
+        &serde_json::to_value(&project_before).ok(),
         &None,
-        &DeleteProjectResJson::new(project_data.id()),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &project_res)
 }
 
 async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
@@ -244,7 +923,7 @@ async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -263,23 +942,411 @@ async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader
         );
     }
 
-    let projects_data =
+    let mut projects_data =
         match ProjectDao::db_select_many_by_admin_id(ctx.dao().db(), token_claim.id()).await {
             Ok(data) => data,
             Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
         };
 
+    let members_data = match ProjectMemberDao::db_select_many_by_admin_id(
+        ctx.dao().db(),
+        token_claim.id(),
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let mut member_projects_data_fut = Vec::with_capacity(members_data.len());
+    for member_data in &members_data {
+        member_projects_data_fut.push(ProjectDao::db_select_including_deleted(
+            ctx.dao().db(),
+            member_data.project_id(),
+        ));
+    }
+    match future::try_join_all(member_projects_data_fut).await {
+        Ok(data) => projects_data.extend(data),
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    }
+    projects_data.retain(|data| data.deleted_at().is_none());
+
     Response::data(
         &StatusCode::OK,
         &Some(PaginationRes::new(
             &projects_data.len(),
-            &projects_data.len(),
+            &Some(projects_data.len()),
+            &None,
+            &None,
         )),
         &projects_data
             .iter()
             .map(|data| {
-                ProjectResJson::new(data.id(), data.created_at(), data.updated_at(), data.name())
+                ProjectResJson::new(
+                    data.id(),
+                    data.created_at(),
+                    data.updated_at(),
+                    data.name(),
+                    data.suspended(),
+                    data.archived(),
+                    data.token_claims(),
+                )
             })
             .collect::<Vec<_>>(),
     )
 }
+
+async fn apply_config(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<ApplyProjectConfigReqPath>,
+    data: web::Json<ApplyProjectConfigReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_schemas() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying schemas",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_schemas() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying schemas",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let collections_data = match CollectionDao::db_select_many_by_project_id(
+        ctx.dao().db(),
+        project_data.id(),
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let mut collections_config = HashMap::with_capacity(data.collections().len());
+    for (name, config) in data.collections() {
+        match collection_config_to_dao(config) {
+            Ok(config) => collections_config.insert(name.as_str(), config),
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Error in collection '{name}': {err}"),
+                )
+            }
+        };
+    }
+
+    let mut collections_to_update = Vec::new();
+    let mut collections_to_delete = Vec::new();
+    for mut collection_data in collections_data {
+        match collections_config.remove(collection_data.name()) {
+            Some((
+                schema_fields,
+                indexes,
+                partial_indexes,
+                max_field_size,
+                max_record_size,
+                auto_delete_after_seconds,
+            )) => {
+                let is_schema_fields_changed = schema_fields.len()
+                    != collection_data.schema_fields().len()
+                    || schema_fields.iter().any(|(field, props)| {
+                        match collection_data.schema_fields().get(field) {
+                            Some(current) => {
+                                current.kind() != props.kind()
+                                    || current.required() != props.required()
+                                    || current.validation() != props.validation()
+                                    || current.computed() != props.computed()
+                            }
+                            None => true,
+                        }
+                    });
+                if !is_schema_fields_changed
+                    && &indexes == collection_data.indexes()
+                    && &partial_indexes == collection_data.partial_indexes()
+                    && &max_field_size == collection_data.max_field_size()
+                    && &max_record_size == collection_data.max_record_size()
+                    && &auto_delete_after_seconds == collection_data.auto_delete_after_seconds()
+                {
+                    continue;
+                }
+                collection_data.update_schema_fields(&schema_fields);
+                collection_data.update_indexes(&indexes);
+                collection_data.update_partial_indexes(&partial_indexes);
+                collection_data.set_max_field_size(&max_field_size);
+                collection_data.set_max_record_size(&max_record_size);
+                collection_data.update_auto_delete_after_seconds(&auto_delete_after_seconds);
+                collections_to_update.push(collection_data);
+            }
+            None => collections_to_delete.push(collection_data),
+        }
+    }
+
+    if let Err(err) = quota::check_collections(
+        &ctx,
+        project_data.id(),
+        &collections_config
+            .len()
+            .saturating_sub(collections_to_delete.len()),
+    )
+    .await
+    {
+        return err.to_response();
+    }
+
+    let dry_run = data.dry_run().unwrap_or(false);
+    if !dry_run && *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
+    }
+
+    let mut created = Vec::with_capacity(collections_config.len());
+    for (
+        name,
+        (
+            schema_fields,
+            indexes,
+            partial_indexes,
+            max_field_size,
+            max_record_size,
+            auto_delete_after_seconds,
+        ),
+    ) in &collections_config
+    {
+        if !dry_run {
+            let collection_data = match CollectionDao::new(
+                project_data.id(),
+                name,
+                schema_fields,
+                indexes,
+                partial_indexes,
+                max_field_size,
+                max_record_size,
+                auto_delete_after_seconds,
+            ) {
+                Ok(data) => data,
+                Err(err) => {
+                    return Response::error_raw(
+                        &StatusCode::INTERNAL_SERVER_ERROR,
+                        &err.to_string(),
+                    )
+                }
+            };
+            if let Err(err) = collection_data.db_insert(ctx.dao().db()).await {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+            }
+        }
+        created.push(name.to_string());
+    }
+
+    let mut updated = Vec::with_capacity(collections_to_update.len());
+    for collection_data in &mut collections_to_update {
+        if !dry_run {
+            if let Err(err) = collection_data.db_update(ctx.dao().db()).await {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+            }
+        }
+        updated.push(collection_data.name().to_owned());
+    }
+
+    let mut deleted = Vec::with_capacity(collections_to_delete.len());
+    for collection_data in &collections_to_delete {
+        if !dry_run {
+            if let Err(err) = CollectionDao::db_delete(ctx.dao().db(), collection_data.id()).await {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+            }
+        }
+        deleted.push(collection_data.name().to_owned());
+    }
+
+    created.sort();
+    updated.sort();
+    deleted.sort();
+
+    let project_config_res = ProjectConfigResJson::new(&dry_run, &created, &updated, &deleted);
+
+    if !dry_run {
+        if let Err(err) = AuditLogDao::new(
+            token_claim.id(),
+            &Some(*project_data.id()),
+            &req.peer_addr().map(|addr| addr.ip()),
+            "apply_config",
+            "project",
+            project_data.id(),
+            &None,
+            &serde_json::to_value(&project_config_res).ok(),
+        )
+        .db_insert(ctx.dao().db())
+        .await
+        {
+            hb_log::error(None, &err);
+        }
+    }
+
+    Response::data(&StatusCode::OK, &None, &project_config_res)
+}
+
+type CollectionConfig = (
+    HashMap<String, SchemaFieldProps>,
+    HashSet<String>,
+    HashMap<String, PartialIndex>,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+);
+
+fn collection_config_to_dao(config: &ProjectConfigCollectionJson) -> Result<CollectionConfig> {
+    let mut schema_fields = HashMap::with_capacity(config.schema_fields().len());
+    for (key, value) in config.schema_fields() {
+        if key.is_empty() {
+            return Err(Error::msg(
+                "Field name in schema_fields can't be empty string",
+            ));
+        }
+        if key.starts_with('_') || !key.chars().all(|c| c == '_' || c.is_ascii_lowercase()) {
+            return Err(Error::msg(format!("Field '{key}' should only have lowercase English letters and an optional underscore (_) after the first character")));
+        }
+        let kind = ColumnKind::from_str_with_variants(value.kind(), value.variants())?;
+        kind.check_enum_variants()?;
+        let validation = match value.validation() {
+            Some(validation) => validation
+                .to_dao(&kind)
+                .map_err(|err| Error::msg(format!("Error in field '{key}': {err}")))?,
+            None => SchemaFieldValidation::default(),
+        };
+        let computed = match value.computed() {
+            Some(computed) => Some(
+                computed
+                    .to_dao(&kind)
+                    .map_err(|err| Error::msg(format!("Error in field '{key}': {err}")))?,
+            ),
+            None => None,
+        };
+        schema_fields.insert(
+            key.to_owned(),
+            SchemaFieldProps::new(
+                &kind,
+                &value.required().unwrap_or(false),
+                &validation,
+                &computed,
+            ),
+        );
+    }
+    SchemaFieldComputed::check_schema_fields(&schema_fields)?;
+
+    let indexes = config.indexes().clone().unwrap_or_default();
+    for index in &indexes {
+        match schema_fields.get(index) {
+            Some(field) => {
+                if !field.required() {
+                    return Err(Error::msg(format!(
+                        "Field '{index}' must be required because it is in the indexes"
+                    )));
+                }
+            }
+            None => {
+                return Err(Error::msg(format!(
+                    "Field '{index}' is in indexes but not exist in the schema fields"
+                )))
+            }
+        }
+    }
+
+    let mut partial_indexes = HashMap::new();
+    if let Some(partial_index) = config.partial_indexes() {
+        for (name, value) in partial_index {
+            if name.is_empty() {
+                return Err(Error::msg(
+                    "Index name in partial_indexes can't be empty string",
+                ));
+            }
+            if name.starts_with('_') || !name.chars().all(|c| c == '_' || c.is_ascii_lowercase()) {
+                return Err(Error::msg(format!("Index '{name}' should only have lowercase English letters and an optional underscore (_) after the first character")));
+            }
+            if indexes.contains(name) {
+                return Err(Error::msg(format!(
+                    "Index '{name}' in partial_indexes conflicts with a field in indexes"
+                )));
+            }
+            let predicate = value.predicate().clone().unwrap_or_default();
+            for field in value.fields().iter().chain(predicate.keys()) {
+                if !schema_fields.contains_key(field) {
+                    return Err(Error::msg(format!("Field '{field}' is in partial index '{name}' but not exist in the schema fields")));
+                }
+            }
+            partial_indexes.insert(
+                name.to_owned(),
+                PartialIndex::new(value.fields(), &value.unique().unwrap_or(false), &predicate)?,
+            );
+        }
+    }
+
+    for (name, limit) in [
+        ("max_field_size", config.max_field_size()),
+        ("max_record_size", config.max_record_size()),
+        (
+            "auto_delete_after_seconds",
+            config.auto_delete_after_seconds(),
+        ),
+    ] {
+        if limit.is_some_and(|limit| limit <= 0) {
+            return Err(Error::msg(format!(
+                "Value of '{name}' must be greater than 0"
+            )));
+        }
+    }
+
+    Ok((
+        schema_fields,
+        indexes,
+        partial_indexes,
+        *config.max_field_size(),
+        *config.max_record_size(),
+        *config.auto_delete_after_seconds(),
+    ))
+}
@@ -0,0 +1,418 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use hb_dao::{
+    admin::{AdminDao, AdminRole},
+    audit_log::AuditLogDao,
+    project::ProjectDao,
+    project_member::ProjectMemberDao,
+};
+use hb_token_jwt::kind::JwtTokenKind;
+use validator::Validate;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        project_member::{
+            DeleteOneProjectMemberReqPath, DeleteProjectMemberResJson,
+            FindManyProjectMemberReqPath, InsertOneProjectMemberReqJson,
+            InsertOneProjectMemberReqPath, ProjectMemberResJson, UpdateOneProjectMemberReqJson,
+            UpdateOneProjectMemberReqPath,
+        },
+        PaginationRes, Response, TokenReqHeader,
+    },
+    project_access,
+};
+
+pub fn project_member_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/project/{project_id}/member", web::post().to(insert_one))
+        .route(
+            "/project/{project_id}/member/{member_id}",
+            web::patch().to(update_one),
+        )
+        .route(
+            "/project/{project_id}/member/{member_id}",
+            web::delete().to(delete_one),
+        )
+        .route("/project/{project_id}/members", web::get().to(find_many));
+}
+
+async fn insert_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<InsertOneProjectMemberReqPath>,
+    data: web::Json<InsertOneProjectMemberReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    }
+
+    let role = match data.role().parse::<AdminRole>() {
+        Ok(role) => role,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_projects() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying project members",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let admin_data = match AdminDao::db_select_by_email(ctx.dao().db(), data.email()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, admin_data.id()).await {
+        Ok(Some(_)) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                "This admin is already a member of the project",
+            )
+        }
+        Ok(None) => (),
+        Err(err) => return err.to_response(),
+    }
+
+    let member_data = match ProjectMemberDao::new(project_data.id(), admin_data.id(), &role) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    if let Err(err) = member_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let member_res = ProjectMemberResJson::new(
+        member_data.id(),
+        member_data.created_at(),
+        member_data.updated_at(),
+        member_data.project_id(),
+        member_data.admin_id(),
+        member_data.role().to_str(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*member_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "insert",
+        "project_member",
+        member_data.id(),
+        &None,
+        &serde_json::to_value(&member_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::CREATED, &None, &member_res)
+}
+
+async fn update_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<UpdateOneProjectMemberReqPath>,
+    data: web::Json<UpdateOneProjectMemberReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let role = match data.role().parse::<AdminRole>() {
+        Ok(role) => role,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_projects() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying project members",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let mut member_data = match ProjectMemberDao::db_select(ctx.dao().db(), path.member_id()).await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if member_data.project_id() != project_data.id() {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "This member does not belong to the project",
+        );
+    }
+
+    let member_before = ProjectMemberResJson::new(
+        member_data.id(),
+        member_data.created_at(),
+        member_data.updated_at(),
+        member_data.project_id(),
+        member_data.admin_id(),
+        member_data.role().to_str(),
+    );
+
+    if let Err(err) = member_data.set_role(&role) {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    if let Err(err) = member_data.db_update(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let member_res = ProjectMemberResJson::new(
+        member_data.id(),
+        member_data.created_at(),
+        member_data.updated_at(),
+        member_data.project_id(),
+        member_data.admin_id(),
+        member_data.role().to_str(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*member_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "update",
+        "project_member",
+        member_data.id(),
+        &serde_json::to_value(&member_before).ok(),
+        &serde_json::to_value(&member_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &member_res)
+}
+
+async fn delete_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<DeleteOneProjectMemberReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let member_data = match ProjectMemberDao::db_select(ctx.dao().db(), path.member_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if member_data.project_id() != project_data.id() {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "This member does not belong to the project",
+        );
+    }
+
+    if member_data.admin_id() != token_claim.id() {
+        match project_access::role(&ctx, &project_data, token_claim.id()).await {
+            Ok(Some(role)) => {
+                if !role.can_write_projects() {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "Your project role doesn't allow modifying project members",
+                    );
+                }
+            }
+            Ok(None) => {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "This project does not belong to you",
+                )
+            }
+            Err(err) => return err.to_response(),
+        }
+    }
+
+    let member_before = ProjectMemberResJson::new(
+        member_data.id(),
+        member_data.created_at(),
+        member_data.updated_at(),
+        member_data.project_id(),
+        member_data.admin_id(),
+        member_data.role().to_str(),
+    );
+
+    if let Err(err) = ProjectMemberDao::db_delete(ctx.dao().db(), member_data.id()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let member_res = DeleteProjectMemberResJson::new(member_data.id());
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*member_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "delete",
+        "project_member",
+        member_data.id(),
+        &serde_json::to_value(&member_before).ok(),
+        &None,
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &member_res)
+}
+
+async fn find_many(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<FindManyProjectMemberReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let members_data =
+        match ProjectMemberDao::db_select_many_by_project_id(ctx.dao().db(), project_data.id())
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &members_data.len(),
+            &Some(members_data.len()),
+            &None,
+            &None,
+        )),
+        &members_data
+            .iter()
+            .map(|data| {
+                ProjectMemberResJson::new(
+                    data.id(),
+                    data.created_at(),
+                    data.updated_at(),
+                    data.project_id(),
+                    data.admin_id(),
+                    data.role().to_str(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
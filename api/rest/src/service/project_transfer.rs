@@ -0,0 +1,409 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use ahash::HashSet;
+use hb_dao::{
+    admin::{AdminDao, AdminRole},
+    audit_log::AuditLogDao,
+    collection::CollectionDao,
+    project::ProjectDao,
+    project_member::ProjectMemberDao,
+    project_transfer::ProjectTransferDao,
+    token::TokenDao,
+};
+use hb_token_jwt::kind::JwtTokenKind;
+use validator::Validate;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        project_transfer::{
+            AcceptProjectTransferReqPath, DeleteOneProjectTransferReqPath,
+            DeleteProjectTransferResJson, InsertOneProjectTransferReqJson,
+            InsertOneProjectTransferReqPath, ProjectTransferResJson,
+        },
+        PaginationRes, Response, TokenReqHeader,
+    },
+    project_access,
+};
+
+pub fn project_transfer_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/project/{project_id}/transfer", web::post().to(insert_one))
+        .route(
+            "/project/transfer/{transfer_id}/accept",
+            web::post().to(accept_one),
+        )
+        .route(
+            "/project/transfer/{transfer_id}",
+            web::delete().to(delete_one),
+        )
+        .route("/project/transfers", web::get().to(find_many));
+}
+
+async fn insert_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<InsertOneProjectTransferReqPath>,
+    data: web::Json<InsertOneProjectTransferReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_projects() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying projects",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(AdminRole::Owner)) => (),
+        Ok(_) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "Only the project owner can transfer the project",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let to_admin_data = match AdminDao::db_select_by_email(ctx.dao().db(), data.email()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if to_admin_data.id() == token_claim.id() {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "The project already belongs to this admin",
+        );
+    }
+
+    let transfer_data =
+        ProjectTransferDao::new(project_data.id(), token_claim.id(), to_admin_data.id());
+    if let Err(err) = transfer_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let transfer_res = ProjectTransferResJson::new(
+        transfer_data.id(),
+        transfer_data.created_at(),
+        transfer_data.updated_at(),
+        transfer_data.project_id(),
+        transfer_data.from_admin_id(),
+        transfer_data.to_admin_id(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*transfer_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "insert",
+        "project_transfer",
+        transfer_data.id(),
+        &None,
+        &serde_json::to_value(&transfer_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::CREATED, &None, &transfer_res)
+}
+
+async fn accept_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<AcceptProjectTransferReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let transfer_data =
+        match ProjectTransferDao::db_select(ctx.dao().db(), path.transfer_id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    if transfer_data.to_admin_id() != token_claim.id() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This project transfer is not addressed to you",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), transfer_data.project_id()).await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if project_data.admin_id() != transfer_data.from_admin_id() {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "The project owner has changed since this transfer was requested",
+        );
+    }
+
+    if let Err(err) = project_access::role(&ctx, &project_data, transfer_data.from_admin_id()).await
+    {
+        return err.to_response();
+    }
+
+    let collection_ids = match CollectionDao::db_select_many_by_project_id(
+        ctx.dao().db(),
+        project_data.id(),
+    )
+    .await
+    {
+        Ok(data) => data
+            .iter()
+            .map(|collection_data| *collection_data.id())
+            .collect::<HashSet<_>>(),
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    let token_ids =
+        match TokenDao::db_select_many_by_admin_id(ctx.dao().db(), transfer_data.from_admin_id())
+            .await
+        {
+            Ok(data) => data
+                .iter()
+                .filter(|token_data| {
+                    !token_data.rules().is_empty()
+                        && token_data
+                            .rules()
+                            .keys()
+                            .all(|collection_id| collection_ids.contains(collection_id))
+                })
+                .map(|token_data| *token_data.id())
+                .collect::<Vec<_>>(),
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+
+    if let Err(err) = transfer_data.db_accept(ctx.dao().db(), &token_ids).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    match ProjectMemberDao::db_select_many_by_project_id(ctx.dao().db(), project_data.id()).await {
+        Ok(members_data) => {
+            for member_data in &members_data {
+                if member_data.admin_id() == token_claim.id() {
+                    if let Err(err) =
+                        ProjectMemberDao::db_delete(ctx.dao().db(), member_data.id()).await
+                    {
+                        return Response::error_raw(
+                            &StatusCode::INTERNAL_SERVER_ERROR,
+                            &err.to_string(),
+                        );
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    }
+
+    let transfer_res = ProjectTransferResJson::new(
+        transfer_data.id(),
+        transfer_data.created_at(),
+        transfer_data.updated_at(),
+        transfer_data.project_id(),
+        transfer_data.from_admin_id(),
+        transfer_data.to_admin_id(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*transfer_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "accept",
+        "project_transfer",
+        transfer_data.id(),
+        &None,
+        &serde_json::to_value(&transfer_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &transfer_res)
+}
+
+async fn delete_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<DeleteOneProjectTransferReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let transfer_data =
+        match ProjectTransferDao::db_select(ctx.dao().db(), path.transfer_id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    if transfer_data.from_admin_id() != token_claim.id()
+        && transfer_data.to_admin_id() != token_claim.id()
+    {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This project transfer does not belong to you",
+        );
+    }
+
+    let transfer_before = ProjectTransferResJson::new(
+        transfer_data.id(),
+        transfer_data.created_at(),
+        transfer_data.updated_at(),
+        transfer_data.project_id(),
+        transfer_data.from_admin_id(),
+        transfer_data.to_admin_id(),
+    );
+
+    if let Err(err) = ProjectTransferDao::db_delete(ctx.dao().db(), transfer_data.id()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let transfer_res = DeleteProjectTransferResJson::new(transfer_data.id());
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*transfer_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "delete",
+        "project_transfer",
+        transfer_data.id(),
+        &serde_json::to_value(&transfer_before).ok(),
+        &None,
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &transfer_res)
+}
+
+async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let transfers_data =
+        match ProjectTransferDao::db_select_many_by_to_admin_id(ctx.dao().db(), token_claim.id())
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &transfers_data.len(),
+            &Some(transfers_data.len()),
+            &None,
+            &None,
+        )),
+        &transfers_data
+            .iter()
+            .map(|data| {
+                ProjectTransferResJson::new(
+                    data.id(),
+                    data.created_at(),
+                    data.updated_at(),
+                    data.project_id(),
+                    data.from_admin_id(),
+                    data.to_admin_id(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
@@ -0,0 +1,108 @@
+use actix_web::{http::StatusCode, web, HttpResponse};
+use hb_dao::{admin::AdminDao, project::ProjectDao, project_usage::ProjectUsageDao};
+use hb_token_jwt::kind::JwtTokenKind;
+
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        project_usage::{FindManyProjectUsageReqPath, ProjectUsageResJson},
+        PaginationRes, Response, TokenReqHeader,
+    },
+    project_access,
+};
+
+// Usage is stored per UTC day, so this covers roughly the last three months
+const USAGE_PERIODS: i64 = 90;
+
+pub fn project_usage_api(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        "/project/{project_id}/usage",
+        web::get().to(find_many_by_project),
+    );
+}
+
+async fn find_many_by_project(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<FindManyProjectUsageReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    // Server operators can read the usage of every project for billing and capacity planning
+    if !ctx.is_operator(admin_data.email()) {
+        match project_access::role(&ctx, &project_data, token_claim.id()).await {
+            Ok(Some(_)) => (),
+            Ok(None) => {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "This project does not belong to you",
+                )
+            }
+            Err(err) => return err.to_response(),
+        }
+    }
+
+    let project_usages_data = match ProjectUsageDao::db_select_many_by_project_id(
+        ctx.dao().db(),
+        project_data.id(),
+        &USAGE_PERIODS,
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &project_usages_data.len(),
+            &Some(project_usages_data.len()),
+            &None,
+            &None,
+        )),
+        &project_usages_data
+            .iter()
+            .map(|data| {
+                ProjectUsageResJson::new(
+                    data.project_id(),
+                    data.period(),
+                    data.updated_at(),
+                    data.request_count(),
+                    data.record_count(),
+                    data.storage_bytes(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
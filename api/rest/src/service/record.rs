@@ -1,27 +1,50 @@
-use actix_web::{http::StatusCode, web, HttpResponse};
-use ahash::{HashMap, HashMapExt};
+use actix_web::{
+    http::{
+        header::{self, ContentType, ETag, EntityTag, HeaderValue, IfMatch, IfNoneMatch},
+        StatusCode,
+    },
+    web, HttpRequest, HttpResponse,
+};
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use anyhow::{Error, Result};
+use futures::{channel::mpsc, SinkExt};
 use hb_dao::{
     admin::AdminDao,
     collection::CollectionDao,
     project::ProjectDao,
-    record::{RecordDao, RecordFilters, RecordOrder, RecordPagination},
+    record::{
+        RecordDao, RecordFilter, RecordFilterValue, RecordFilters, RecordOrder, RecordPagination,
+    },
     token::TokenDao,
     value::{ColumnKind, ColumnValue},
+    Db,
 };
+use hb_event::RecordEventKind;
 use hb_token_jwt::kind::JwtTokenKind;
+use serde_json::Value;
+use uuid::Uuid;
 
 use crate::{
     context::ApiRestCtx,
     model::{
         record::{
-            DeleteOneRecordReqPath, DeleteRecordResJson, FindManyRecordReqJson,
-            FindManyRecordReqPath, FindOneRecordReqPath, InsertOneRecordReqJson,
-            InsertOneRecordReqPath, RecordResJson, UpdateOneRecordReqJson, UpdateOneRecordReqPath,
+            CountManyRecordReqJson, CountManyRecordReqPath, CountRecordResJson,
+            DeleteOneRecordReqPath, DeleteRecordResJson, DistinctManyRecordReqJson,
+            DistinctManyRecordReqPath, ExportManyRecordReqJson, ExportManyRecordReqPath,
+            FindManyRecordReqJson, FindManyRecordReqPath, FindOneRecordReqPath,
+            ImportManyRecordReqPath, ImportRecordErrorResJson, ImportRecordResJson,
+            InsertOneRecordReqJson, InsertOneRecordReqPath, RecordCursorJson, RecordResJson,
+            UpdateOneRecordReqJson, UpdateOneRecordReqPath, UpsertOneRecordReqJson,
+            UpsertOneRecordReqPath,
         },
-        PaginationRes, Response, TokenReqHeader,
+        FieldErrorRes, GroupCountRes, PaginationRes, Response, TokenReqHeader,
     },
+    plugin, project_access, quota, rule,
 };
 
+const IMPORT_BATCH_SIZE: usize = 100;
+const EXPORT_BATCH_SIZE: usize = 1000;
+
 pub fn record_api(cfg: &mut web::ServiceConfig) {
     cfg.route(
         "/project/{project_id}/collection/{collection_id}/record",
@@ -35,6 +58,10 @@ pub fn record_api(cfg: &mut web::ServiceConfig) {
         "/project/{project_id}/collection/{collection_id}/record/{record_id}",
         web::patch().to(update_one),
     )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/record/{record_id}",
+        web::put().to(upsert_one),
+    )
     .route(
         "/project/{project_id}/collection/{collection_id}/record/{record_id}",
         web::delete().to(delete_one),
@@ -42,11 +69,28 @@ pub fn record_api(cfg: &mut web::ServiceConfig) {
     .route(
         "/project/{project_id}/collection/{collection_id}/records",
         web::post().to(find_many),
+    )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/records/count",
+        web::post().to(count_many),
+    )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/records/distinct",
+        web::post().to(distinct_many),
+    )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/records/import",
+        web::post().to(import_many),
+    )
+    .route(
+        "/project/{project_id}/collection/{collection_id}/records/export",
+        web::post().to(export_many),
     );
 }
 
 async fn insert_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     path: web::Path<InsertOneRecordReqPath>,
     data: web::Json<InsertOneRecordReqJson>,
@@ -56,14 +100,22 @@ async fn insert_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
     let (admin_id, token_data) = match token_claim.kind() {
         JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.id(), None),
+            Ok(data) => {
+                if !data.role().can_write_records() {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "Your admin role doesn't allow modifying records",
+                    );
+                }
+                (*data.id(), None)
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -72,7 +124,21 @@ async fn insert_one(
             }
         },
         JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.admin_id(), Some(data)),
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:write") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:write' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -90,11 +156,26 @@ async fn insert_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if &admin_id != project_data.admin_id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(role)) => {
+            if !role.can_write_records() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying records",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
     }
 
     if project_data.id() != collection_data.project_id() {
@@ -110,6 +191,19 @@ async fn insert_one(
         }
     }
 
+    let mut data = data.into_inner();
+    if let Err(err) = plugin::before(
+        &ctx,
+        &collection_data,
+        &RecordEventKind::Insert,
+        &None,
+        &mut data,
+    )
+    .await
+    {
+        return err.to_response();
+    }
+
     for field_name in data.keys() {
         if !collection_data.schema_fields().contains_key(field_name) {
             return Response::error_raw(
@@ -120,18 +214,37 @@ async fn insert_one(
     }
 
     let mut record_data = RecordDao::new(collection_data.id(), &Some(data.len()));
+    record_data.upsert("_created_by", &ColumnValue::Uuid(token_claim.user_id()));
     for (field_name, field_props) in collection_data.schema_fields() {
+        if field_props.computed().is_some() {
+            if data.get(field_name).is_some_and(|value| !value.is_null()) {
+                return Response::error_field(
+                    &StatusCode::BAD_REQUEST,
+                    field_name,
+                    "computed",
+                    "Field is computed and can't be set",
+                );
+            }
+            continue;
+        }
         if let Some(value) = data.get(field_name) {
             if !value.is_null() {
                 if let Some(value) = value.as_str() {
                     if value == "$request.auth.id" {
                         if *field_props.kind() != ColumnKind::Uuid {
-                            return Response::error_raw(
+                            return Response::error_field(
                                 &StatusCode::BAD_REQUEST,
+                                field_name,
+                                "invalid_type",
                                 "Field for storing '$request.auth.id' must be of type 'uuid'",
                             );
                         }
-                        record_data.upsert(field_name, &ColumnValue::Uuid(Some(*token_claim.id())));
+                        record_data.upsert(
+                            field_name,
+                            &ColumnValue::Uuid(Some(
+                                token_claim.user_id().unwrap_or(*token_claim.id()),
+                            )),
+                        );
                         continue;
                     }
                 }
@@ -140,9 +253,11 @@ async fn insert_one(
                     &match ColumnValue::from_serde_json(field_props.kind(), value) {
                         Ok(value) => value,
                         Err(err) => {
-                            return Response::error_raw(
+                            return Response::error_field(
                                 &StatusCode::BAD_REQUEST,
-                                &format!("Error in field '{}': {}", field_name, err),
+                                field_name,
+                                "invalid_type",
+                                &err.to_string(),
                             )
                         }
                     },
@@ -151,20 +266,60 @@ async fn insert_one(
             }
         }
         if *field_props.required() {
-            return Response::error_raw(
+            return Response::error_field(
                 &StatusCode::BAD_REQUEST,
-                &format!("Value for '{field_name}' is required"),
+                field_name,
+                "required",
+                "Value is required",
             );
         } else {
             record_data.upsert(field_name, &ColumnValue::none(field_props.kind()));
         }
     }
 
+    if let Err(err) = record_data.compute_fields(&collection_data) {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    let validation_errors = record_data.check_validation(&collection_data);
+    if !validation_errors.is_empty() {
+        return Response::error_fields(
+            &StatusCode::BAD_REQUEST,
+            "Record does not satisfy the collection validation rules",
+            &validation_errors
+                .iter()
+                .map(|(field, err)| FieldErrorRes::new(field, err.code(), err.message()))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    if let Err(err) = record_data.check_size(&collection_data) {
+        return Response::error_raw(&StatusCode::PAYLOAD_TOO_LARGE, &err.to_string());
+    }
+
+    if let Some(token_data) = &token_data {
+        if let Err(err) = rule::check(
+            &req,
+            token_data,
+            &token_claim.user_id(),
+            collection_data.id(),
+            "insert",
+            Some(&record_data),
+            Some(&data),
+        ) {
+            return err.to_response();
+        }
+    }
+
+    if let Err(err) = quota::check_records(&ctx, &collection_data, &1).await {
+        return err.to_response();
+    }
+
     if let Err(err) = record_data.db_insert(ctx.dao().db()).await {
         return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
     }
 
-    let mut record = HashMap::with_capacity(record_data.len());
+    let mut record = serde_json::Map::with_capacity(record_data.len());
     for (key, value) in record_data.data() {
         let value = match value.to_serde_json() {
             Ok(value) => value,
@@ -175,27 +330,55 @@ async fn insert_one(
         record.insert(key.to_owned(), value);
     }
 
+    if let Some(ColumnValue::Uuid(Some(record_id))) = record_data.get("_id") {
+        ctx.event_bus().publish_record(
+            &RecordEventKind::Insert,
+            collection_data.project_id(),
+            collection_data.id(),
+            record_id,
+            Some(&record),
+        );
+        plugin::after(
+            &ctx,
+            &collection_data,
+            &RecordEventKind::Insert,
+            record_id,
+            Some(&record),
+        )
+        .await;
+    }
+
     Response::data(&StatusCode::CREATED, &None, &RecordResJson::new(&record))
 }
 
-async fn find_one(
+async fn upsert_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
-    path: web::Path<FindOneRecordReqPath>,
+    path: web::Path<UpsertOneRecordReqPath>,
+    data: web::Json<UpsertOneRecordReqJson>,
 ) -> HttpResponse {
     let token = match token.get() {
         Some(token) => token,
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
     let (admin_id, token_data) = match token_claim.kind() {
         JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.id(), None),
+            Ok(data) => {
+                if !data.role().can_write_records() {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "Your admin role doesn't allow modifying records",
+                    );
+                }
+                (*data.id(), None)
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -204,7 +387,21 @@ async fn find_one(
             }
         },
         JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.admin_id(), Some(data)),
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:write") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:write' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -216,17 +413,32 @@ async fn find_one(
 
     let (project_data, collection_data) = match tokio::try_join!(
         ProjectDao::db_select(ctx.dao().db(), path.project_id()),
-        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id())
     ) {
         Ok(data) => data,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if &admin_id != project_data.admin_id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(role)) => {
+            if !role.can_write_records() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying records",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
     }
 
     if project_data.id() != collection_data.project_id() {
@@ -234,21 +446,164 @@ async fn find_one(
     }
 
     if let Some(token_data) = &token_data {
-        if !token_data.is_allow_find_one(path.collection_id()) {
+        if !token_data.is_allow_insert(path.collection_id())
+            || !token_data.is_allow_update(path.collection_id())
+        {
             return Response::error_raw(
                 &StatusCode::FORBIDDEN,
-                "This token doesn't have permission to read this record",
+                "This token doesn't have permission to write data to this collection",
             );
         }
     }
 
-    let record_data =
-        match RecordDao::db_select(ctx.dao().db(), &collection_data, path.record_id()).await {
-            Ok(data) => data,
-            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
-        };
+    let mut data = data.into_inner();
+    if let Err(err) = plugin::before(
+        &ctx,
+        &collection_data,
+        &RecordEventKind::Upsert,
+        &Some(*path.record_id()),
+        &mut data,
+    )
+    .await
+    {
+        return err.to_response();
+    }
+
+    for field_name in data.keys() {
+        if !collection_data.schema_fields().contains_key(field_name) {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Field '{field_name}' is not exist in the collection"),
+            );
+        }
+    }
+
+    let mut record_data = RecordDao::new(collection_data.id(), &Some(data.len()));
+    record_data.upsert("_id", &ColumnValue::Uuid(Some(*path.record_id())));
+    record_data.upsert("_created_by", &ColumnValue::Uuid(token_claim.user_id()));
+    for (field_name, field_props) in collection_data.schema_fields() {
+        if field_props.computed().is_some() {
+            if data.get(field_name).is_some_and(|value| !value.is_null()) {
+                return Response::error_field(
+                    &StatusCode::BAD_REQUEST,
+                    field_name,
+                    "computed",
+                    "Field is computed and can't be set",
+                );
+            }
+            continue;
+        }
+        if let Some(value) = data.get(field_name) {
+            if !value.is_null() {
+                if let Some(value) = value.as_str() {
+                    if value == "$request.auth.id" {
+                        if *field_props.kind() != ColumnKind::Uuid {
+                            return Response::error_field(
+                                &StatusCode::BAD_REQUEST,
+                                field_name,
+                                "invalid_type",
+                                "Field for storing '$request.auth.id' must be of type 'uuid'",
+                            );
+                        }
+                        record_data.upsert(
+                            field_name,
+                            &ColumnValue::Uuid(Some(
+                                token_claim.user_id().unwrap_or(*token_claim.id()),
+                            )),
+                        );
+                        continue;
+                    }
+                }
+                record_data.upsert(
+                    field_name,
+                    &match ColumnValue::from_serde_json(field_props.kind(), value) {
+                        Ok(value) => value,
+                        Err(err) => {
+                            return Response::error_field(
+                                &StatusCode::BAD_REQUEST,
+                                field_name,
+                                "invalid_type",
+                                &err.to_string(),
+                            )
+                        }
+                    },
+                );
+                continue;
+            }
+        }
+        if *field_props.required() {
+            return Response::error_field(
+                &StatusCode::BAD_REQUEST,
+                field_name,
+                "required",
+                "Value is required",
+            );
+        } else {
+            record_data.upsert(field_name, &ColumnValue::none(field_props.kind()));
+        }
+    }
+
+    if let Err(err) = record_data.compute_fields(&collection_data) {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    let validation_errors = record_data.check_validation(&collection_data);
+    if !validation_errors.is_empty() {
+        return Response::error_fields(
+            &StatusCode::BAD_REQUEST,
+            "Record does not satisfy the collection validation rules",
+            &validation_errors
+                .iter()
+                .map(|(field, err)| FieldErrorRes::new(field, err.code(), err.message()))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    if let Err(err) = record_data.check_size(&collection_data) {
+        return Response::error_raw(&StatusCode::PAYLOAD_TOO_LARGE, &err.to_string());
+    }
+
+    if let Some(token_data) = &token_data {
+        // Replacing a stored record has to pass the update condition as well
+        if token_data.has_condition(collection_data.id()) {
+            if let Ok(stored_data) =
+                RecordDao::db_select(ctx.dao().db(), &collection_data, path.record_id()).await
+            {
+                if let Err(err) = rule::check(
+                    &req,
+                    token_data,
+                    &token_claim.user_id(),
+                    collection_data.id(),
+                    "update",
+                    Some(&stored_data),
+                    Some(&data),
+                ) {
+                    return err.to_response();
+                }
+            }
+        }
+        if let Err(err) = rule::check(
+            &req,
+            token_data,
+            &token_claim.user_id(),
+            collection_data.id(),
+            "insert",
+            Some(&record_data),
+            Some(&data),
+        ) {
+            return err.to_response();
+        }
+    }
+
+    if let Err(err) = quota::check_upsert(&ctx, &collection_data, path.record_id()).await {
+        return err.to_response();
+    }
+
+    if let Err(err) = record_data.db_upsert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
 
-    let mut record = HashMap::with_capacity(record_data.len());
+    let mut record = serde_json::Map::with_capacity(record_data.len());
     for (key, value) in record_data.data() {
         let value = match value.to_serde_json() {
             Ok(value) => value,
@@ -259,21 +614,38 @@ async fn find_one(
         record.insert(key.to_owned(), value);
     }
 
+    ctx.event_bus().publish_record(
+        &RecordEventKind::Upsert,
+        collection_data.project_id(),
+        collection_data.id(),
+        path.record_id(),
+        Some(&record),
+    );
+    plugin::after(
+        &ctx,
+        &collection_data,
+        &RecordEventKind::Upsert,
+        path.record_id(),
+        Some(&record),
+    )
+    .await;
+
     Response::data(&StatusCode::OK, &None, &RecordResJson::new(&record))
 }
 
-async fn update_one(
+async fn find_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
-    path: web::Path<UpdateOneRecordReqPath>,
-    data: web::Json<UpdateOneRecordReqJson>,
+    if_none_match: Option<web::Header<IfNoneMatch>>,
+    path: web::Path<FindOneRecordReqPath>,
 ) -> HttpResponse {
     let token = match token.get() {
         Some(token) => token,
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -289,7 +661,21 @@ async fn update_one(
             }
         },
         JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.admin_id(), Some(data)),
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:read") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:read' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -307,11 +693,15 @@ async fn update_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if &admin_id != project_data.admin_id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
     }
 
     if project_data.id() != collection_data.project_id() {
@@ -319,70 +709,50 @@ async fn update_one(
     }
 
     if let Some(token_data) = &token_data {
-        if !token_data.is_allow_update(path.collection_id()) {
+        if !token_data.is_allow_find_one(path.collection_id()) {
             return Response::error_raw(
                 &StatusCode::FORBIDDEN,
-                "This token doesn't have permission to update this record",
-            );
-        }
-    }
-
-    for field_name in data.keys() {
-        if !collection_data.schema_fields().contains_key(field_name) {
-            return Response::error_raw(
-                &StatusCode::BAD_REQUEST,
-                &format!("Field '{field_name}' is not exist in the collection"),
+                "This token doesn't have permission to read this record",
             );
         }
     }
 
-    let mut record_data =
+    let record_data =
         match RecordDao::db_select(ctx.dao().db(), &collection_data, path.record_id()).await {
             Ok(data) => data,
             Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
         };
-    for (field_name, field_props) in collection_data.schema_fields() {
-        if let Some(value) = data.get(field_name) {
-            if value.is_null() {
-                if *field_props.required() {
-                    return Response::error_raw(
-                        &StatusCode::BAD_REQUEST,
-                        &format!("Value for '{field_name}' is required"),
-                    );
-                }
-            }
-            if let Some(value) = value.as_str() {
-                if value == "$request.auth.id" {
-                    if *field_props.kind() != ColumnKind::Uuid {
-                        return Response::error_raw(
-                            &StatusCode::BAD_REQUEST,
-                            "Field for storing '$request.auth.id' must be of type 'uuid'",
-                        );
-                    }
-                    record_data.upsert(field_name, &ColumnValue::Uuid(Some(*token_claim.id())));
-                    continue;
-                }
-            }
-            record_data.upsert(
-                field_name,
-                &match ColumnValue::from_serde_json(field_props.kind(), value) {
-                    Ok(value) => value,
-                    Err(err) => {
-                        return Response::error_raw(
-                            &StatusCode::BAD_REQUEST,
-                            &format!("Error in field '{}': {}", field_name, err),
-                        )
-                    }
-                },
-            );
+
+    if let Some(token_data) = &token_data {
+        if let Err(err) = rule::check(
+            &req,
+            token_data,
+            &token_claim.user_id(),
+            collection_data.id(),
+            "find_one",
+            Some(&record_data),
+            None,
+        ) {
+            return err.to_response();
         }
     }
 
-    if let Err(err) = record_data.db_update(ctx.dao().db()).await {
-        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    let etag = match record_data.get("_version") {
+        Some(ColumnValue::BigInteger(Some(version))) => {
+            Some(EntityTag::new_strong(version.to_string()))
+        }
+        _ => None,
+    };
+
+    if let Some(etag) = &etag {
+        if is_not_modified(&if_none_match, etag) {
+            return HttpResponse::NotModified()
+                .insert_header(ETag(etag.clone()))
+                .finish();
+        }
     }
 
-    let mut record = HashMap::with_capacity(record_data.len());
+    let mut record = serde_json::Map::with_capacity(record_data.len());
     for (key, value) in record_data.data() {
         let value = match value.to_serde_json() {
             Ok(value) => value,
@@ -393,27 +763,44 @@ async fn update_one(
         record.insert(key.to_owned(), value);
     }
 
-    Response::data(&StatusCode::OK, &None, &RecordResJson::new(&record))
+    let mut res = Response::data(&StatusCode::OK, &None, &RecordResJson::new(&record));
+    if let Some(etag) = etag {
+        if let Ok(etag) = HeaderValue::from_str(&etag.to_string()) {
+            res.headers_mut().insert(header::ETAG, etag);
+        }
+    }
+    res
 }
 
-async fn delete_one(
+async fn update_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
-    path: web::Path<DeleteOneRecordReqPath>,
+    if_match: Option<web::Header<IfMatch>>,
+    path: web::Path<UpdateOneRecordReqPath>,
+    data: web::Json<UpdateOneRecordReqJson>,
 ) -> HttpResponse {
     let token = match token.get() {
         Some(token) => token,
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
     let (admin_id, token_data) = match token_claim.kind() {
         JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.id(), None),
+            Ok(data) => {
+                if !data.role().can_write_records() {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "Your admin role doesn't allow modifying records",
+                    );
+                }
+                (*data.id(), None)
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -422,7 +809,21 @@ async fn delete_one(
             }
         },
         JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.admin_id(), Some(data)),
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:write") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:write' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -440,11 +841,26 @@ async fn delete_one(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if &admin_id != project_data.admin_id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(role)) => {
+            if !role.can_write_records() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying records",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
     }
 
     if project_data.id() != collection_data.project_id() {
@@ -452,46 +868,295 @@ async fn delete_one(
     }
 
     if let Some(token_data) = &token_data {
-        if !token_data.is_allow_delete(path.collection_id()) {
+        if !token_data.is_allow_update(path.collection_id()) {
             return Response::error_raw(
                 &StatusCode::FORBIDDEN,
-                "This token doesn't have permission to delete this record",
+                "This token doesn't have permission to update this record",
             );
         }
     }
 
-    if let Err(err) =
-        RecordDao::db_delete(ctx.dao().db(), collection_data.id(), path.record_id()).await
+    let version = match parse_if_match(&if_match) {
+        Ok(version) => version,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let mut data = data.into_inner();
+    if let Err(err) = plugin::before(
+        &ctx,
+        &collection_data,
+        &RecordEventKind::Update,
+        &Some(*path.record_id()),
+        &mut data,
+    )
+    .await
     {
-        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        return err.to_response();
     }
 
-    Response::data(
-        &StatusCode::OK,
-        &None,
-        &DeleteRecordResJson::new(path.record_id()),
+    for field_name in data.keys() {
+        if !collection_data.schema_fields().contains_key(field_name) {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Field '{field_name}' is not exist in the collection"),
+            );
+        }
+    }
+
+    let mut record_data =
+        match RecordDao::db_select(ctx.dao().db(), &collection_data, path.record_id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    if let Some(token_data) = &token_data {
+        if let Err(err) = rule::check(
+            &req,
+            token_data,
+            &token_claim.user_id(),
+            collection_data.id(),
+            "update",
+            Some(&record_data),
+            Some(&data),
+        ) {
+            return err.to_response();
+        }
+    }
+
+    let mut increments = HashMap::new();
+    for (field_name, field_props) in collection_data.schema_fields() {
+        if field_props.computed().is_some() {
+            if data.get(field_name).is_some_and(|value| !value.is_null()) {
+                return Response::error_field(
+                    &StatusCode::BAD_REQUEST,
+                    field_name,
+                    "computed",
+                    "Field is computed and can't be set",
+                );
+            }
+            continue;
+        }
+        if let Some(value) = data.get(field_name) {
+            if *field_props.kind() != ColumnKind::Json {
+                if let Some(amount) = value.as_object().and_then(|value| match value.len() {
+                    1 => value.get("$inc"),
+                    _ => None,
+                }) {
+                    if let Db::ScyllaDb(_) = ctx.dao().db() {
+                        return Response::error_raw(
+                            &StatusCode::BAD_REQUEST,
+                            "Incrementing a field is not supported in ScyllaDB",
+                        );
+                    }
+                    match field_props.kind() {
+                        ColumnKind::TinyInt
+                        | ColumnKind::SmallInt
+                        | ColumnKind::Int
+                        | ColumnKind::BigInt
+                        | ColumnKind::Float
+                        | ColumnKind::Double => (),
+                        _ => {
+                            return Response::error_raw(
+                                &StatusCode::BAD_REQUEST,
+                                &format!(
+                                    "Field '{field_name}' is not numeric and can't be incremented"
+                                ),
+                            )
+                        }
+                    }
+                    if !field_props.validation().is_empty() {
+                        return Response::error_raw(
+                            &StatusCode::BAD_REQUEST,
+                            &format!("Field '{field_name}' has validation rules and can't be incremented"),
+                        );
+                    }
+                    if collection_data.schema_fields().values().any(|props| {
+                        props
+                            .computed()
+                            .as_ref()
+                            .is_some_and(|computed| computed.fields().contains(field_name))
+                    }) {
+                        return Response::error_raw(
+                            &StatusCode::BAD_REQUEST,
+                            &format!("Field '{field_name}' is used by a computed field and can't be incremented"),
+                        );
+                    }
+                    let amount = match ColumnValue::from_serde_json(field_props.kind(), amount) {
+                        Ok(amount) if !amount.is_null() => amount,
+                        Ok(_) => {
+                            return Response::error_raw(
+                                &StatusCode::BAD_REQUEST,
+                                &format!("Increment for '{field_name}' must be a number"),
+                            )
+                        }
+                        Err(err) => {
+                            return Response::error_field(
+                                &StatusCode::BAD_REQUEST,
+                                field_name,
+                                "invalid_type",
+                                &err.to_string(),
+                            )
+                        }
+                    };
+                    increments.insert(field_name.to_owned(), amount);
+                    continue;
+                }
+            }
+            if value.is_null() {
+                if *field_props.required() {
+                    return Response::error_field(
+                        &StatusCode::BAD_REQUEST,
+                        field_name,
+                        "required",
+                        "Value is required",
+                    );
+                }
+            }
+            if let Some(value) = value.as_str() {
+                if value == "$request.auth.id" {
+                    if *field_props.kind() != ColumnKind::Uuid {
+                        return Response::error_field(
+                            &StatusCode::BAD_REQUEST,
+                            field_name,
+                            "invalid_type",
+                            "Field for storing '$request.auth.id' must be of type 'uuid'",
+                        );
+                    }
+                    record_data.upsert(
+                        field_name,
+                        &ColumnValue::Uuid(Some(
+                            token_claim.user_id().unwrap_or(*token_claim.id()),
+                        )),
+                    );
+                    continue;
+                }
+            }
+            record_data.upsert(
+                field_name,
+                &match ColumnValue::from_serde_json(field_props.kind(), value) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        return Response::error_field(
+                            &StatusCode::BAD_REQUEST,
+                            field_name,
+                            "invalid_type",
+                            &err.to_string(),
+                        )
+                    }
+                },
+            );
+        }
+    }
+
+    if let Err(err) = record_data.compute_fields(&collection_data) {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    let validation_errors = record_data.check_validation(&collection_data);
+    if !validation_errors.is_empty() {
+        return Response::error_fields(
+            &StatusCode::BAD_REQUEST,
+            "Record does not satisfy the collection validation rules",
+            &validation_errors
+                .iter()
+                .map(|(field, err)| FieldErrorRes::new(field, err.code(), err.message()))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    if let Err(err) = record_data.check_size(&collection_data) {
+        return Response::error_raw(&StatusCode::PAYLOAD_TOO_LARGE, &err.to_string());
+    }
+
+    match record_data
+        .db_update(ctx.dao().db(), &increments, &version)
+        .await
+    {
+        Ok(true) => (),
+        Ok(false) => {
+            return Response::error_raw(
+                &StatusCode::CONFLICT,
+                "Record has been modified by another request",
+            )
+        }
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    }
+
+    if increments.is_empty() {
+        if let Some(&ColumnValue::BigInteger(Some(version))) = record_data.get("_version") {
+            record_data.upsert("_version", &ColumnValue::BigInteger(Some(version + 1)));
+        }
+    } else {
+        record_data = match RecordDao::db_select(ctx.dao().db(), &collection_data, path.record_id())
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+    }
+
+    let mut record = serde_json::Map::with_capacity(record_data.len());
+    for (key, value) in record_data.data() {
+        let value = match value.to_serde_json() {
+            Ok(value) => value,
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+        record.insert(key.to_owned(), value);
+    }
+
+    ctx.event_bus().publish_record(
+        &RecordEventKind::Update,
+        collection_data.project_id(),
+        collection_data.id(),
+        path.record_id(),
+        Some(&record),
+    );
+    plugin::after(
+        &ctx,
+        &collection_data,
+        &RecordEventKind::Update,
+        path.record_id(),
+        Some(&record),
     )
+    .await;
+
+    Response::data(&StatusCode::OK, &None, &RecordResJson::new(&record))
 }
 
-async fn find_many(
+async fn delete_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
-    path: web::Path<FindManyRecordReqPath>,
-    query_data: web::Json<FindManyRecordReqJson>,
+    if_match: Option<web::Header<IfMatch>>,
+    path: web::Path<DeleteOneRecordReqPath>,
 ) -> HttpResponse {
     let token = match token.get() {
         Some(token) => token,
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
     let (admin_id, token_data) = match token_claim.kind() {
         JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.id(), None),
+            Ok(data) => {
+                if !data.role().can_write_records() {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "Your admin role doesn't allow modifying records",
+                    );
+                }
+                (*data.id(), None)
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -500,7 +1165,21 @@ async fn find_many(
             }
         },
         JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
-            Ok(data) => (*data.admin_id(), Some(data)),
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:write") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:write' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
             Err(err) => {
                 return Response::error_raw(
                     &StatusCode::BAD_REQUEST,
@@ -518,11 +1197,26 @@ async fn find_many(
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
 
-    if &admin_id != project_data.admin_id() {
-        return Response::error_raw(
-            &StatusCode::FORBIDDEN,
-            "This project does not belong to you",
-        );
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(role)) => {
+            if !role.can_write_records() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying records",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
     }
 
     if project_data.id() != collection_data.project_id() {
@@ -530,80 +1224,325 @@ async fn find_many(
     }
 
     if let Some(token_data) = &token_data {
-        if !token_data.is_allow_find_many(path.collection_id()) {
+        if !token_data.is_allow_delete(path.collection_id()) {
             return Response::error_raw(
                 &StatusCode::FORBIDDEN,
-                "This token doesn't have permission to read these records",
+                "This token doesn't have permission to delete this record",
             );
         }
     }
 
-    let filters = match query_data.filter() {
-        Some(filter) => match filter.to_dao(&collection_data) {
-            Ok(filter) => filter,
-            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
-        },
-        None => RecordFilters::new(&Vec::new()),
-    };
-    let groups = match query_data.group() {
-        Some(group) => {
-            let mut groups = Vec::with_capacity(group.len());
-            for field in group {
-                if collection_data.schema_fields().contains_key(field) || field == "_id" {
-                    groups.push(field.as_str());
-                } else {
-                    return Response::error_raw(
-                        &StatusCode::BAD_REQUEST,
-                        &format!("Field '{field}' is not exist in the collection"),
-                    );
-                }
-            }
-            groups
-        }
-        None => Vec::new(),
+    let version = match parse_if_match(&if_match) {
+        Ok(version) => version,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
-    let orders = match query_data.order() {
-        Some(order) => {
-            let mut orders = Vec::with_capacity(order.len());
-            for o in order {
-                if collection_data.schema_fields().contains_key(o.field()) || o.field() == "_id" {
-                    orders.push(RecordOrder::new(o.field(), o.kind()));
-                } else {
-                    return Response::error_raw(
-                        &StatusCode::BAD_REQUEST,
-                        &format!("Field '{}' is not exist in the collection", o.field()),
-                    );
+
+    if let Some(token_data) = &token_data {
+        if token_data.has_condition(collection_data.id()) {
+            if let Ok(record_data) =
+                RecordDao::db_select(ctx.dao().db(), &collection_data, path.record_id()).await
+            {
+                if let Err(err) = rule::check(
+                    &req,
+                    token_data,
+                    &token_claim.user_id(),
+                    collection_data.id(),
+                    "delete",
+                    Some(&record_data),
+                    None,
+                ) {
+                    return err.to_response();
                 }
             }
-            orders
         }
-        None => Vec::new(),
-    };
-    let pagination = RecordPagination::new(query_data.limit());
-    let (records_data, total) = match RecordDao::db_select_many(
+    }
+
+    let is_deleted = match RecordDao::db_delete(
         ctx.dao().db(),
-        &collection_data,
-        &filters,
-        &groups,
-        &orders,
-        &pagination,
+        collection_data.id(),
+        path.record_id(),
+        &version,
     )
     .await
     {
-        Ok(data) => data,
-        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        Ok(true) => true,
+        Ok(false) if version.is_some() => {
+            return Response::error_raw(
+                &StatusCode::CONFLICT,
+                "Record has been modified by another request",
+            )
+        }
+        Ok(false) => false,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
     };
 
-    let mut records = Vec::with_capacity(records_data.len());
-    for record_data in &records_data {
-        let mut record = HashMap::with_capacity(record_data.len());
-        for (key, value) in record_data.data() {
-            let value = match value.to_serde_json() {
-                Ok(value) => value,
-                Err(err) => {
-                    return Response::error_raw(
-                        &StatusCode::INTERNAL_SERVER_ERROR,
-                        &err.to_string(),
+    if is_deleted {
+        ctx.event_bus().publish_record(
+            &RecordEventKind::Delete,
+            collection_data.project_id(),
+            collection_data.id(),
+            path.record_id(),
+            None,
+        );
+        plugin::after(
+            &ctx,
+            &collection_data,
+            &RecordEventKind::Delete,
+            path.record_id(),
+            None,
+        )
+        .await;
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &DeleteRecordResJson::new(path.record_id()),
+    )
+}
+
+async fn find_many(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<FindManyRecordReqPath>,
+    query_data: web::Json<FindManyRecordReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let (admin_id, token_data) = match token_claim.kind() {
+        JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => (*data.id(), None),
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get user data: {err}"),
+                )
+            }
+        },
+        JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:read") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:read' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get token data: {err}"),
+                )
+            }
+        },
+    };
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    let mut rule_filters = RecordFilters::new(&Vec::new());
+    if let Some(token_data) = &token_data {
+        if !token_data.is_allow_find_many(path.collection_id()) {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This token doesn't have permission to read these records",
+            );
+        }
+        rule_filters =
+            match rule::check_find_many(&req, token_data, &token_claim.user_id(), &collection_data)
+            {
+                Ok(filters) => filters,
+                Err(err) => return err.to_response(),
+            };
+    }
+
+    let mut filters = match query_data.filter() {
+        Some(filter) => match filter.to_dao(&collection_data) {
+            Ok(filter) => filter.and(&rule_filters),
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        },
+        None => rule_filters,
+    };
+    let groups = match query_data.group() {
+        Some(group) => {
+            let mut groups = Vec::with_capacity(group.len());
+            for field in group {
+                if collection_data.schema_fields().contains_key(field) || field == "_id" {
+                    groups.push(field.as_str());
+                } else {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Field '{field}' is not exist in the collection"),
+                    );
+                }
+            }
+            groups
+        }
+        None => Vec::new(),
+    };
+    let mut orders = match query_data.order() {
+        Some(order) => {
+            let mut orders = Vec::with_capacity(order.len());
+            for o in order {
+                if collection_data.schema_fields().contains_key(o.field()) || o.field() == "_id" {
+                    orders.push(RecordOrder::new(o.field(), o.kind()));
+                } else {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Field '{}' is not exist in the collection", o.field()),
+                    );
+                }
+            }
+            orders
+        }
+        None => Vec::new(),
+    };
+    ctx.index_advisor().record(
+        collection_data.id(),
+        &filters.fields(),
+        &orders.iter().map(|o| o.field()).collect::<Vec<_>>(),
+    );
+    let with_cursor = query_data.with_cursor().unwrap_or(false) || query_data.cursor().is_some();
+    if with_cursor {
+        if !groups.is_empty() {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                "Cursor can't be used together with group",
+            );
+        }
+        for o in &orders {
+            if o.field() != "_id"
+                && !collection_data
+                    .schema_fields()
+                    .get(o.field())
+                    .is_some_and(|field| *field.required())
+            {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!(
+                        "Field '{}' must be required to be used in order with cursor",
+                        o.field()
+                    ),
+                );
+            }
+        }
+        if !orders.iter().any(|o| o.field() == "_id") {
+            orders.push(RecordOrder::new("_id", "ASC"));
+        }
+        if let Some(cursor) = query_data.cursor() {
+            let cursor = match ctx.token().jwt().decode_cursor::<RecordCursorJson>(cursor) {
+                Ok(cursor) => cursor,
+                Err(err) => {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Invalid cursor: {err}"),
+                    )
+                }
+            };
+            let cursor = cursor.data();
+            if cursor.collection_id() != collection_data.id()
+                || cursor.order().len() != orders.len()
+                || cursor.values().len() != orders.len()
+                || orders.iter().zip(cursor.order()).any(|(o, (field, kind))| {
+                    o.field() != field || !o.kind().eq_ignore_ascii_case(kind)
+                })
+            {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    "Cursor does not match the collection or order of this request",
+                );
+            }
+            filters = match cursor_filters(&collection_data, &filters, &orders, cursor.values()) {
+                Ok(filters) => filters,
+                Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+            };
+        }
+    }
+    let pagination = match query_data.sample() {
+        Some(sample) => {
+            if *sample <= 0 {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    "Sample size must be greater than 0",
+                );
+            }
+            if query_data.order().is_some()
+                || query_data.limit().is_some()
+                || !groups.is_empty()
+                || with_cursor
+            {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    "Sample can't be used together with order, group, limit, or cursor",
+                );
+            }
+            RecordPagination::sample(sample)
+        }
+        None => RecordPagination::new(query_data.limit()),
+    };
+    let (records_data, total) = match RecordDao::db_select_many(
+        ctx.dao().db(),
+        &collection_data,
+        &filters,
+        &groups,
+        &orders,
+        &pagination,
+        &query_data.with_total().unwrap_or(true),
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let mut records = Vec::with_capacity(records_data.len());
+    for record_data in &records_data {
+        let mut record = serde_json::Map::with_capacity(record_data.len());
+        for (key, value) in record_data.data() {
+            let value = match value.to_serde_json() {
+                Ok(value) => value,
+                Err(err) => {
+                    return Response::error_raw(
+                        &StatusCode::INTERNAL_SERVER_ERROR,
+                        &err.to_string(),
                     )
                 }
             };
@@ -612,16 +1551,1291 @@ async fn find_many(
         records.push(record);
     }
 
-    let total = match usize::try_from(total) {
-        Ok(data) => data,
-        Err(err) => {
-            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+    if let Some(embeds) = query_data.embed() {
+        for embed in embeds {
+            match collection_data.schema_fields().get(embed.field()) {
+                Some(props) if *props.kind() == ColumnKind::Uuid => (),
+                Some(_) => {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!(
+                            "Field '{}' must be of type 'uuid' to be embedded",
+                            embed.field()
+                        ),
+                    )
+                }
+                None => {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Field '{}' is not exist in the collection", embed.field()),
+                    )
+                }
+            }
+
+            let embed_collection_data =
+                match CollectionDao::db_select(ctx.dao().db(), embed.collection_id()).await {
+                    Ok(data) => data,
+                    Err(err) => {
+                        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string())
+                    }
+                };
+
+            if embed_collection_data.project_id() != project_data.id() {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    "Embedded collection does not belong to this project",
+                );
+            }
+
+            let mut embed_rule_filters = RecordFilters::new(&Vec::new());
+            if let Some(token_data) = &token_data {
+                if !token_data.is_allow_find_many(embed.collection_id()) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have permission to read the embedded records",
+                    );
+                }
+                embed_rule_filters = match rule::check_find_many(
+                    &req,
+                    token_data,
+                    &token_claim.user_id(),
+                    &embed_collection_data,
+                ) {
+                    Ok(filters) => filters,
+                    Err(err) => return err.to_response(),
+                };
+            }
+
+            let mut ids = HashSet::new();
+            for record_data in &records_data {
+                if let Some(ColumnValue::Uuid(Some(id))) = record_data.get(embed.field()) {
+                    ids.insert(*id);
+                }
+            }
+
+            let mut embedded_records = HashMap::with_capacity(ids.len());
+            if !ids.is_empty() {
+                let (embedded_records_data, _) = match RecordDao::db_select_many(
+                    ctx.dao().db(),
+                    &embed_collection_data,
+                    &RecordFilters::new(&Vec::from([RecordFilter::new(
+                        &Some("_id".to_owned()),
+                        "IN",
+                        &Some(RecordFilterValue::Multiple(
+                            ids.iter().map(|id| ColumnValue::Uuid(Some(*id))).collect(),
+                        )),
+                        &None,
+                        &false,
+                    )]))
+                    .and(&embed_rule_filters),
+                    &Vec::new(),
+                    &Vec::new(),
+                    &RecordPagination::new(&None),
+                    &false,
+                )
+                .await
+                {
+                    Ok(data) => data,
+                    Err(err) => {
+                        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string())
+                    }
+                };
+                for embedded_record_data in &embedded_records_data {
+                    let id = match embedded_record_data.get("_id") {
+                        Some(ColumnValue::Uuid(Some(id))) => *id,
+                        _ => continue,
+                    };
+                    let mut embedded_record =
+                        serde_json::Map::with_capacity(embedded_record_data.len());
+                    for (key, value) in embedded_record_data.data() {
+                        let value = match value.to_serde_json() {
+                            Ok(value) => value,
+                            Err(err) => {
+                                return Response::error_raw(
+                                    &StatusCode::INTERNAL_SERVER_ERROR,
+                                    &err.to_string(),
+                                )
+                            }
+                        };
+                        embedded_record.insert(key.to_owned(), value);
+                    }
+                    embedded_records.insert(id, Value::Object(embedded_record));
+                }
+            }
+
+            for (record_data, record) in records_data.iter().zip(records.iter_mut()) {
+                let embedded_record = match record_data.get(embed.field()) {
+                    Some(ColumnValue::Uuid(Some(id))) => {
+                        embedded_records.get(id).cloned().unwrap_or(Value::Null)
+                    }
+                    _ => Value::Null,
+                };
+                if let Value::Object(embedded) = record
+                    .entry("_embed")
+                    .or_insert_with(|| Value::Object(serde_json::Map::new()))
+                {
+                    embedded.insert(embed.field().to_owned(), embedded_record);
+                }
+            }
+        }
+    }
+
+    let group_counts = match groups.is_empty() {
+        true => None,
+        false => {
+            let group_counts_data = match RecordDao::db_count_groups(
+                ctx.dao().db(),
+                &collection_data,
+                &groups,
+                &filters,
+            )
+            .await
+            {
+                Ok(data) => data,
+                Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+            };
+            let mut group_counts = Vec::with_capacity(group_counts_data.len());
+            for (values_data, count) in &group_counts_data {
+                let mut values = serde_json::Map::with_capacity(values_data.len());
+                for (key, value) in values_data {
+                    let value = match value.to_serde_json() {
+                        Ok(value) => value,
+                        Err(err) => {
+                            return Response::error_raw(
+                                &StatusCode::INTERNAL_SERVER_ERROR,
+                                &err.to_string(),
+                            )
+                        }
+                    };
+                    values.insert(key.to_owned(), value);
+                }
+                group_counts.push(GroupCountRes::new(&values, count));
+            }
+            Some(group_counts)
         }
     };
 
+    let total = match total {
+        Some(total) => match usize::try_from(total) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        },
+        None => None,
+    };
+
+    let next_cursor = match records_data.last() {
+        Some(record_data)
+            if with_cursor
+                && query_data
+                    .limit()
+                    .is_some_and(|limit| records_data.len() as i64 == i64::from(limit)) =>
+        {
+            let mut values = Vec::with_capacity(orders.len());
+            for o in &orders {
+                let value = match record_data.get(o.field()) {
+                    Some(value) => match value.to_serde_json() {
+                        Ok(value) => value,
+                        Err(err) => {
+                            return Response::error_raw(
+                                &StatusCode::INTERNAL_SERVER_ERROR,
+                                &err.to_string(),
+                            )
+                        }
+                    },
+                    None => Value::Null,
+                };
+                values.push(value);
+            }
+            match ctx.token().jwt().encode_cursor(RecordCursorJson::new(
+                collection_data.id(),
+                &orders
+                    .iter()
+                    .map(|o| (o.field().to_owned(), o.kind().to_uppercase()))
+                    .collect::<Vec<_>>(),
+                &values,
+            )) {
+                Ok(cursor) => Some(cursor),
+                Err(err) => {
+                    return Response::error_raw(
+                        &StatusCode::INTERNAL_SERVER_ERROR,
+                        &err.to_string(),
+                    )
+                }
+            }
+        }
+        _ => None,
+    };
+
     Response::data(
         &StatusCode::OK,
-        &Some(PaginationRes::new(&records_data.len(), &total)),
+        &Some(PaginationRes::new(
+            &records_data.len(),
+            &total,
+            &next_cursor,
+            &group_counts,
+        )),
         &records,
     )
 }
+
+async fn count_many(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<CountManyRecordReqPath>,
+    query_data: web::Json<CountManyRecordReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let (admin_id, token_data) = match token_claim.kind() {
+        JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => (*data.id(), None),
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get user data: {err}"),
+                )
+            }
+        },
+        JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:read") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:read' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get token data: {err}"),
+                )
+            }
+        },
+    };
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    let mut rule_filters = RecordFilters::new(&Vec::new());
+    if let Some(token_data) = &token_data {
+        if !token_data.is_allow_find_many(path.collection_id()) {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This token doesn't have permission to read these records",
+            );
+        }
+        rule_filters =
+            match rule::check_find_many(&req, token_data, &token_claim.user_id(), &collection_data)
+            {
+                Ok(filters) => filters,
+                Err(err) => return err.to_response(),
+            };
+    }
+
+    let filters = match query_data.filter() {
+        Some(filter) => match filter.to_dao(&collection_data) {
+            Ok(filter) => filter.and(&rule_filters),
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        },
+        None => rule_filters,
+    };
+    ctx.index_advisor()
+        .record(collection_data.id(), &filters.fields(), &[]);
+    let total = match RecordDao::db_count(ctx.dao().db(), collection_data.id(), &filters).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let total = match usize::try_from(total) {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    Response::data(&StatusCode::OK, &None, &CountRecordResJson::new(&total))
+}
+
+async fn distinct_many(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<DistinctManyRecordReqPath>,
+    query_data: web::Json<DistinctManyRecordReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let (admin_id, token_data) = match token_claim.kind() {
+        JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => (*data.id(), None),
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get user data: {err}"),
+                )
+            }
+        },
+        JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:read") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:read' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get token data: {err}"),
+                )
+            }
+        },
+    };
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    let mut rule_filters = RecordFilters::new(&Vec::new());
+    if let Some(token_data) = &token_data {
+        if !token_data.is_allow_find_many(path.collection_id()) {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This token doesn't have permission to read these records",
+            );
+        }
+        rule_filters =
+            match rule::check_find_many(&req, token_data, &token_claim.user_id(), &collection_data)
+            {
+                Ok(filters) => filters,
+                Err(err) => return err.to_response(),
+            };
+    }
+
+    let filters = match query_data.filter() {
+        Some(filter) => match filter.to_dao(&collection_data) {
+            Ok(filter) => filter.and(&rule_filters),
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        },
+        None => rule_filters,
+    };
+    ctx.index_advisor().record(
+        collection_data.id(),
+        &filters.fields(),
+        &[query_data.field()],
+    );
+    let pagination = RecordPagination::new(query_data.limit());
+    let values_data = match RecordDao::db_select_distinct(
+        ctx.dao().db(),
+        &collection_data,
+        query_data.field(),
+        &filters,
+        &pagination,
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let mut values = Vec::with_capacity(values_data.len());
+    for value in &values_data {
+        values.push(match value.to_serde_json() {
+            Ok(value) => value,
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        });
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(&values.len(), &None, &None, &None)),
+        &values,
+    )
+}
+
+async fn import_many(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    content_type: web::Header<ContentType>,
+    path: web::Path<ImportManyRecordReqPath>,
+    data: web::Bytes,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let (admin_id, token_data) = match token_claim.kind() {
+        JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => {
+                if !data.role().can_write_records() {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "Your admin role doesn't allow modifying records",
+                    );
+                }
+                (*data.id(), None)
+            }
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get user data: {err}"),
+                )
+            }
+        },
+        JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:write") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:write' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get token data: {err}"),
+                )
+            }
+        },
+    };
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id())
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(role)) => {
+            if !role.can_write_records() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying records",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if *project_data.archived() {
+        return Response::error_raw(&StatusCode::LOCKED, "This project is archived");
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    if let Some(token_data) = &token_data {
+        if !token_data.is_allow_insert(path.collection_id()) {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This token doesn't have permission to write data to this collection",
+            );
+        }
+    }
+
+    let data = match std::str::from_utf8(&data) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let mut records = Vec::new();
+    let mut record_rows = Vec::new();
+    let mut errors = Vec::new();
+    match content_type.essence_str() {
+        "text/csv" => {
+            let mut rows = match parse_csv(data) {
+                Ok(rows) => rows.into_iter(),
+                Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+            };
+            let header = rows.next().unwrap_or_default();
+            for field_name in &header {
+                if !collection_data.schema_fields().contains_key(field_name) {
+                    return Response::error_raw(
+                        &StatusCode::BAD_REQUEST,
+                        &format!("Field '{field_name}' is not exist in the collection"),
+                    );
+                }
+            }
+            'rows: for (idx, row) in rows.enumerate() {
+                let row_number = idx + 1;
+                if row.len() != header.len() {
+                    errors.push(ImportRecordErrorResJson::new(
+                        &row_number,
+                        &format!("Expected {} columns, but found {}", header.len(), row.len()),
+                    ));
+                    continue;
+                }
+                // Plugins see CSV cells as strings, anything else they return is read as JSON
+                let mut row: HashMap<_, _> = header
+                    .iter()
+                    .cloned()
+                    .zip(row.into_iter().map(Value::String))
+                    .collect();
+                if let Err(err) = plugin::before(
+                    &ctx,
+                    &collection_data,
+                    &RecordEventKind::Insert,
+                    &None,
+                    &mut row,
+                )
+                .await
+                {
+                    errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                    continue;
+                }
+                for field_name in row.keys() {
+                    if !collection_data.schema_fields().contains_key(field_name) {
+                        errors.push(ImportRecordErrorResJson::new(
+                            &row_number,
+                            &format!("Field '{field_name}' is not exist in the collection"),
+                        ));
+                        continue 'rows;
+                    }
+                }
+                let mut record_data = RecordDao::new(
+                    collection_data.id(),
+                    &Some(collection_data.schema_fields().len()),
+                );
+                record_data.upsert("_created_by", &ColumnValue::Uuid(token_claim.user_id()));
+                for (field_name, field_props) in collection_data.schema_fields() {
+                    if field_props.computed().is_some() {
+                        continue;
+                    }
+                    let value = row.get(field_name).unwrap_or(&Value::Null);
+                    if (value.is_null() || value.as_str() == Some("")) && *field_props.required() {
+                        errors.push(ImportRecordErrorResJson::new(
+                            &row_number,
+                            &format!("Value for '{field_name}' is required"),
+                        ));
+                        continue 'rows;
+                    }
+                    let value = match value {
+                        Value::String(value) => {
+                            ColumnValue::from_csv_str(field_props.kind(), value)
+                        }
+                        Value::Null => ColumnValue::from_csv_str(field_props.kind(), ""),
+                        value => ColumnValue::from_serde_json(field_props.kind(), value),
+                    };
+                    match value {
+                        Ok(value) => record_data.upsert(field_name, &value),
+                        Err(err) => {
+                            errors.push(ImportRecordErrorResJson::new(
+                                &row_number,
+                                &format!("Error in field '{}': {}", field_name, err),
+                            ));
+                            continue 'rows;
+                        }
+                    }
+                }
+                if let Err(err) = record_data.compute_fields(&collection_data) {
+                    errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                    continue;
+                }
+                if let Some((field, err)) = record_data.check_validation(&collection_data).first() {
+                    errors.push(ImportRecordErrorResJson::new(
+                        &row_number,
+                        &format!("Error in field '{field}': {}", err.message()),
+                    ));
+                    continue;
+                }
+                if let Err(err) = record_data.check_size(&collection_data) {
+                    errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                    continue;
+                }
+                if let Some(token_data) = &token_data {
+                    if let Err(err) = rule::check(
+                        &req,
+                        token_data,
+                        &token_claim.user_id(),
+                        collection_data.id(),
+                        "insert",
+                        Some(&record_data),
+                        Some(&row),
+                    ) {
+                        errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                        continue;
+                    }
+                }
+                records.push(record_data);
+                record_rows.push(row_number);
+            }
+        }
+        "application/x-ndjson" => {
+            'rows: for (idx, line) in data.lines().enumerate() {
+                let row_number = idx + 1;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let mut row = match serde_json::from_str::<HashMap<String, Value>>(line) {
+                    Ok(row) => row,
+                    Err(err) => {
+                        errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                        continue;
+                    }
+                };
+                if let Err(err) = plugin::before(
+                    &ctx,
+                    &collection_data,
+                    &RecordEventKind::Insert,
+                    &None,
+                    &mut row,
+                )
+                .await
+                {
+                    errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                    continue;
+                }
+                for field_name in row.keys() {
+                    if !collection_data.schema_fields().contains_key(field_name) {
+                        errors.push(ImportRecordErrorResJson::new(
+                            &row_number,
+                            &format!("Field '{field_name}' is not exist in the collection"),
+                        ));
+                        continue 'rows;
+                    }
+                }
+                let mut record_data = RecordDao::new(collection_data.id(), &Some(row.len()));
+                record_data.upsert("_created_by", &ColumnValue::Uuid(token_claim.user_id()));
+                for (field_name, field_props) in collection_data.schema_fields() {
+                    if field_props.computed().is_some() {
+                        continue;
+                    }
+                    let value = row.get(field_name).unwrap_or(&Value::Null);
+                    if value.is_null() && *field_props.required() {
+                        errors.push(ImportRecordErrorResJson::new(
+                            &row_number,
+                            &format!("Value for '{field_name}' is required"),
+                        ));
+                        continue 'rows;
+                    }
+                    match ColumnValue::from_serde_json(field_props.kind(), value) {
+                        Ok(value) => record_data.upsert(field_name, &value),
+                        Err(err) => {
+                            errors.push(ImportRecordErrorResJson::new(
+                                &row_number,
+                                &format!("Error in field '{}': {}", field_name, err),
+                            ));
+                            continue 'rows;
+                        }
+                    }
+                }
+                if let Err(err) = record_data.compute_fields(&collection_data) {
+                    errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                    continue;
+                }
+                if let Some((field, err)) = record_data.check_validation(&collection_data).first() {
+                    errors.push(ImportRecordErrorResJson::new(
+                        &row_number,
+                        &format!("Error in field '{field}': {}", err.message()),
+                    ));
+                    continue;
+                }
+                if let Err(err) = record_data.check_size(&collection_data) {
+                    errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                    continue;
+                }
+                if let Some(token_data) = &token_data {
+                    if let Err(err) = rule::check(
+                        &req,
+                        token_data,
+                        &token_claim.user_id(),
+                        collection_data.id(),
+                        "insert",
+                        Some(&record_data),
+                        Some(&row),
+                    ) {
+                        errors.push(ImportRecordErrorResJson::new(&row_number, &err.to_string()));
+                        continue;
+                    }
+                }
+                records.push(record_data);
+                record_rows.push(row_number);
+            }
+        }
+        _ => {
+            return Response::error_raw(
+                &StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Content type must be 'text/csv' or 'application/x-ndjson'",
+            )
+        }
+    }
+
+    if !records.is_empty() {
+        if let Err(err) = quota::check_records(&ctx, &collection_data, &records.len()).await {
+            return err.to_response();
+        }
+    }
+
+    let mut inserted = 0;
+    for (batch, batch_rows) in records
+        .chunks(IMPORT_BATCH_SIZE)
+        .zip(record_rows.chunks(IMPORT_BATCH_SIZE))
+    {
+        match RecordDao::db_insert_many(ctx.dao().db(), batch).await {
+            Ok(_) => {
+                inserted += batch.len();
+                if ctx.event_bus().has_subscribers()
+                    || plugin::has_after(&ctx, &collection_data).await
+                {
+                    for record_data in batch {
+                        notify_inserted(&ctx, &collection_data, record_data).await;
+                    }
+                }
+            }
+            Err(err) => {
+                for row_number in batch_rows {
+                    errors.push(ImportRecordErrorResJson::new(row_number, &err.to_string()));
+                }
+            }
+        }
+    }
+    errors.sort_by_key(|err| *err.row());
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &ImportRecordResJson::new(&inserted, &errors),
+    )
+}
+
+fn parse_csv(data: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(Error::msg("CSV data has an unterminated quoted field"));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.len() > 1 || !row[0].is_empty());
+    Ok(rows)
+}
+
+async fn export_many(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<ExportManyRecordReqPath>,
+    query_data: web::Json<ExportManyRecordReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let (admin_id, token_data) = match token_claim.kind() {
+        JwtTokenKind::User => match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => (*data.id(), None),
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get user data: {err}"),
+                )
+            }
+        },
+        JwtTokenKind::Token => match TokenDao::db_select(ctx.dao().db(), token_claim.id()).await {
+            Ok(data) => {
+                if !data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token is not allowed from this IP address",
+                    );
+                }
+                if !data.is_allow_scope("records:read") {
+                    return Response::error_raw(
+                        &StatusCode::FORBIDDEN,
+                        "This token doesn't have the 'records:read' scope",
+                    );
+                }
+                (*data.admin_id(), Some(data))
+            }
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get token data: {err}"),
+                )
+            }
+        },
+    };
+
+    let (project_data, collection_data) = match tokio::try_join!(
+        ProjectDao::db_select(ctx.dao().db(), path.project_id()),
+        CollectionDao::db_select(ctx.dao().db(), path.collection_id()),
+    ) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, &admin_id).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if project_data.id() != collection_data.project_id() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "Project ID does not match");
+    }
+
+    let mut rule_filters = RecordFilters::new(&Vec::new());
+    if let Some(token_data) = &token_data {
+        if !token_data.is_allow_find_many(path.collection_id()) {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This token doesn't have permission to read these records",
+            );
+        }
+        rule_filters =
+            match rule::check_find_many(&req, token_data, &token_claim.user_id(), &collection_data)
+            {
+                Ok(filters) => filters,
+                Err(err) => return err.to_response(),
+            };
+    }
+
+    let format = query_data.format().as_deref().unwrap_or("ndjson");
+    let content_type = match format {
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "ndjson" => "application/x-ndjson",
+        _ => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Export format '{format}' is not supported"),
+            )
+        }
+    };
+    let format = format.to_owned();
+
+    let filters = match query_data.filter() {
+        Some(filter) => match filter.to_dao(&collection_data) {
+            Ok(filter) => filter.and(&rule_filters),
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        },
+        None => rule_filters,
+    };
+
+    let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+    columns.push("_id".to_owned());
+    columns.append(&mut collection_data.schema_fields().keys().cloned().collect());
+    columns[1..].sort();
+
+    let mut records_data = match export_page(&ctx, &collection_data, &filters, &None).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let (mut tx, rx) = mpsc::channel::<Result<web::Bytes, actix_web::Error>>(1);
+    actix_web::rt::spawn(async move {
+        let mut chunk = match format.as_str() {
+            "csv" => {
+                columns
+                    .iter()
+                    .map(|col| csv_escape(col))
+                    .collect::<Vec<_>>()
+                    .join(",")
+                    + "\n"
+            }
+            "json" => "[".to_owned(),
+            _ => String::new(),
+        };
+        let mut is_first = true;
+        loop {
+            let last_id = match records_data.last().and_then(|record| record.get("_id")) {
+                Some(ColumnValue::Uuid(Some(id))) => Some(*id),
+                _ => None,
+            };
+            for record_data in &records_data {
+                let mut record = serde_json::Map::with_capacity(record_data.len());
+                for (key, value) in record_data.data() {
+                    match value.to_serde_json() {
+                        Ok(value) => record.insert(key.to_owned(), value),
+                        Err(err) => {
+                            hb_log::error(None, format!("Failed to export record: {err}"));
+                            return;
+                        }
+                    };
+                }
+                match format.as_str() {
+                    "csv" => {
+                        chunk += &columns
+                            .iter()
+                            .map(|col| match record.get(col) {
+                                Some(Value::Null) | None => String::new(),
+                                Some(Value::String(value)) => csv_escape(value),
+                                Some(value) => csv_escape(&value.to_string()),
+                            })
+                            .collect::<Vec<_>>()
+                            .join(",");
+                        chunk += "\n";
+                    }
+                    "json" => {
+                        if !is_first {
+                            chunk += ",";
+                        }
+                        chunk += &Value::Object(record).to_string();
+                    }
+                    _ => {
+                        chunk += &Value::Object(record).to_string();
+                        chunk += "\n";
+                    }
+                }
+                is_first = false;
+            }
+            if last_id.is_none() || records_data.len() < EXPORT_BATCH_SIZE {
+                break;
+            }
+            if tx
+                .send(Ok(web::Bytes::from(std::mem::take(&mut chunk))))
+                .await
+                .is_err()
+            {
+                return;
+            }
+            records_data = match export_page(&ctx, &collection_data, &filters, &last_id).await {
+                Ok(data) => data,
+                Err(err) => {
+                    hb_log::error(None, format!("Failed to export records: {err}"));
+                    return;
+                }
+            };
+        }
+        if format == "json" {
+            chunk += "]";
+        }
+        let _ = tx.send(Ok(web::Bytes::from(chunk))).await;
+    });
+
+    HttpResponse::Ok().content_type(content_type).streaming(rx)
+}
+
+async fn export_page(
+    ctx: &ApiRestCtx,
+    collection_data: &CollectionDao,
+    filters: &RecordFilters,
+    last_id: &Option<Uuid>,
+) -> Result<Vec<RecordDao>> {
+    let mut children = Vec::with_capacity(2);
+    if !filters.is_empty() {
+        children.push(RecordFilter::new(
+            &None,
+            "AND",
+            &None,
+            &Some(filters.clone()),
+            &false,
+        ));
+    }
+    if let Some(last_id) = last_id {
+        children.push(RecordFilter::new(
+            &Some("_id".to_owned()),
+            ">",
+            &Some(RecordFilterValue::Single(ColumnValue::Uuid(Some(*last_id)))),
+            &None,
+            &false,
+        ));
+    }
+    let filters = if children.is_empty() {
+        RecordFilters::new(&Vec::new())
+    } else {
+        RecordFilters::new(&vec![RecordFilter::new(
+            &None,
+            "AND",
+            &None,
+            &Some(RecordFilters::new(&children)),
+            &false,
+        )])
+    };
+
+    let (records_data, _) = RecordDao::db_select_many(
+        ctx.dao().db(),
+        collection_data,
+        &filters,
+        &Vec::new(),
+        &vec![RecordOrder::new("_id", "ASC")],
+        &RecordPagination::new(&Some(EXPORT_BATCH_SIZE as i32)),
+        &false,
+    )
+    .await?;
+    Ok(records_data)
+}
+
+fn cursor_filters(
+    collection_data: &CollectionDao,
+    filters: &RecordFilters,
+    orders: &[RecordOrder],
+    values: &[Value],
+) -> Result<RecordFilters> {
+    let mut cursor_values = Vec::with_capacity(values.len());
+    for (o, value) in orders.iter().zip(values) {
+        let kind = match collection_data.schema_fields().get(o.field()) {
+            Some(field) => field.kind(),
+            None => &ColumnKind::Uuid,
+        };
+        cursor_values.push(ColumnValue::from_serde_json(kind, value)?);
+    }
+
+    let mut keyset = Vec::with_capacity(orders.len());
+    for (idx, (o, value)) in orders.iter().zip(&cursor_values).enumerate() {
+        let mut children = Vec::with_capacity(idx + 1);
+        for (o, value) in orders.iter().zip(&cursor_values).take(idx) {
+            children.push(RecordFilter::new(
+                &Some(o.field().to_owned()),
+                "=",
+                &Some(RecordFilterValue::Single(value.clone())),
+                &None,
+                &false,
+            ));
+        }
+        children.push(RecordFilter::new(
+            &Some(o.field().to_owned()),
+            if o.kind().eq_ignore_ascii_case("DESC") {
+                "<"
+            } else {
+                ">"
+            },
+            &Some(RecordFilterValue::Single(value.clone())),
+            &None,
+            &false,
+        ));
+        keyset.push(RecordFilter::new(
+            &None,
+            "AND",
+            &None,
+            &Some(RecordFilters::new(&children)),
+            &false,
+        ));
+    }
+
+    let mut children = Vec::with_capacity(2);
+    if !filters.is_empty() {
+        children.push(RecordFilter::new(
+            &None,
+            "AND",
+            &None,
+            &Some(filters.clone()),
+            &false,
+        ));
+    }
+    children.push(RecordFilter::new(
+        &None,
+        "OR",
+        &None,
+        &Some(RecordFilters::new(&keyset)),
+        &false,
+    ));
+
+    Ok(RecordFilters::new(&vec![RecordFilter::new(
+        &None,
+        "AND",
+        &None,
+        &Some(RecordFilters::new(&children)),
+        &false,
+    )]))
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn parse_if_match(if_match: &Option<web::Header<IfMatch>>) -> Result<Option<i64>> {
+    match if_match.as_ref().map(|if_match| &**if_match) {
+        None | Some(IfMatch::Any) => Ok(None),
+        Some(IfMatch::Items(tags)) => match tags.as_slice() {
+            // Actix parses an absent If-Match header as an empty list
+            [] => Ok(None),
+            [tag] => match tag.tag().parse() {
+                Ok(version) => Ok(Some(version)),
+                Err(_) => Err(Error::msg("If-Match header must contain a record version")),
+            },
+            _ => Err(Error::msg(
+                "If-Match header must contain exactly one record version",
+            )),
+        },
+    }
+}
+
+fn is_not_modified(if_none_match: &Option<web::Header<IfNoneMatch>>, etag: &EntityTag) -> bool {
+    match if_none_match.as_ref().map(|if_none_match| &**if_none_match) {
+        None => false,
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(etag)),
+    }
+}
+
+async fn notify_inserted(
+    ctx: &ApiRestCtx,
+    collection_data: &CollectionDao,
+    record_data: &RecordDao,
+) {
+    let record_id = match record_data.get("_id") {
+        Some(ColumnValue::Uuid(Some(record_id))) => *record_id,
+        _ => return,
+    };
+    let mut record = serde_json::Map::with_capacity(record_data.len());
+    for (key, value) in record_data.data() {
+        match value.to_serde_json() {
+            Ok(value) => record.insert(key.to_owned(), value),
+            Err(err) => {
+                hb_log::error(None, &err);
+                return;
+            }
+        };
+    }
+    ctx.event_bus().publish_record(
+        &RecordEventKind::Insert,
+        collection_data.project_id(),
+        collection_data.id(),
+        &record_id,
+        Some(&record),
+    );
+    plugin::after(
+        ctx,
+        collection_data,
+        &RecordEventKind::Insert,
+        &record_id,
+        Some(&record),
+    )
+    .await;
+}
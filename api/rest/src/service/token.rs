@@ -1,22 +1,32 @@
-use actix_web::{http::StatusCode, web, HttpResponse};
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use anyhow::{Error, Result};
 use chrono::{Duration, Utc};
 use futures::future;
 use hb_dao::{
-    admin::AdminDao, collection::CollectionDao, project::ProjectDao, record::RecordDao,
-    token::TokenDao,
+    admin::{AdminDao, AdminRole},
+    audit_log::AuditLogDao,
+    collection::CollectionDao,
+    jwt_revocation::JwtRevocationDao,
+    project::ProjectDao,
+    record::RecordDao,
+    token::{TokenDao, TokenRuleMethod},
 };
 use hb_token_jwt::kind::JwtTokenKind;
+use uuid::Uuid;
 
 use crate::{
     context::ApiRestCtx,
     model::{
         token::{
-            DeleteOneTokenReqPath, DeleteTokenResJson, FindOneTokenReqPath, InsertOneTokenReqJson,
-            TokenResJson, TokenRuleMethodJson, UpdateOneTokenReqJson, UpdateOneTokenReqPath,
+            ApplyRulesTokenReqJson, ApplyRulesTokenReqPath, DeleteOneTokenReqPath,
+            DeleteTokenResJson, FindOneTokenReqPath, InsertOneTokenReqJson, RotateTokenReqJson,
+            RotateTokenReqPath, TokenResJson, TokenRuleMethodJson, UpdateOneTokenReqJson,
+            UpdateOneTokenReqPath,
         },
         PaginationRes, Response, TokenReqHeader,
     },
+    project_access, quota,
 };
 
 pub fn token_api(cfg: &mut web::ServiceConfig) {
@@ -24,11 +34,14 @@ pub fn token_api(cfg: &mut web::ServiceConfig) {
         .route("/admin/token/{token_id}", web::get().to(find_one))
         .route("/admin/token/{token_id}", web::put().to(update_one))
         .route("/admin/token/{token_id}", web::delete().to(delete_one))
+        .route("/admin/token/{token_id}/rules", web::put().to(apply_rules))
+        .route("/admin/token/{token_id}/rotate", web::post().to(rotate))
         .route("/admin/tokens", web::get().to(find_many));
 }
 
 async fn insert_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     data: web::Json<InsertOneTokenReqJson>,
 ) -> HttpResponse {
@@ -37,7 +50,7 @@ async fn insert_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -49,10 +62,20 @@ async fn insert_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_tokens() {
         return Response::error_raw(
-            &StatusCode::BAD_REQUEST,
-            &format!("Failed to get user data: {err}"),
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying tokens",
         );
     }
 
@@ -69,6 +92,18 @@ async fn insert_one(
         }
     }
 
+    if let Some(allowed_cidrs) = data.allowed_cidrs() {
+        if let Err(err) = TokenDao::check_allowed_cidrs(allowed_cidrs) {
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
+    }
+
+    if let Some(scopes) = data.scopes() {
+        if let Err(err) = TokenDao::check_scopes(scopes) {
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
+    }
+
     let mut collections_data_fut = Vec::with_capacity(data.rules().len());
     let mut check_tables_must_exist_fut = Vec::with_capacity(data.rules().len());
     for collection_id in data.rules().keys() {
@@ -79,10 +114,12 @@ async fn insert_one(
         ));
     }
     let mut project_ids = HashSet::new();
+    let mut collection_project_ids = HashMap::with_capacity(data.rules().len());
     match future::try_join_all(collections_data_fut).await {
         Ok(collections_data) => {
             for collection_data in collections_data {
                 project_ids.insert(*collection_data.project_id());
+                collection_project_ids.insert(*collection_data.id(), *collection_data.project_id());
             }
         }
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
@@ -91,14 +128,27 @@ async fn insert_one(
     for project_id in &project_ids {
         projects_data_fut.push(ProjectDao::db_select(ctx.dao().db(), project_id));
     }
+    let mut project_roles = HashMap::with_capacity(project_ids.len());
     match future::try_join_all(projects_data_fut).await {
         Ok(projects_data) => {
             for project_data in projects_data {
-                if project_data.admin_id() != token_claim.id() {
-                    return Response::error_raw(
-                        &StatusCode::FORBIDDEN,
-                        "This collection does not belong to you",
-                    );
+                match project_access::role(&ctx, &project_data, token_claim.id()).await {
+                    Ok(Some(role)) if role.can_write_tokens() => {
+                        project_roles.insert(*project_data.id(), role);
+                    }
+                    Ok(Some(_)) => {
+                        return Response::error_raw(
+                            &StatusCode::FORBIDDEN,
+                            "Your project role doesn't allow modifying tokens",
+                        )
+                    }
+                    Ok(None) => {
+                        return Response::error_raw(
+                            &StatusCode::FORBIDDEN,
+                            "This collection does not belong to you",
+                        )
+                    }
+                    Err(err) => return err.to_response(),
                 }
             }
         }
@@ -108,12 +158,24 @@ async fn insert_one(
         return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
     }
 
+    if let Err(err) = quota::check_tokens(&ctx, token_claim.id(), &project_ids).await {
+        return err.to_response();
+    }
+
     let mut data_rules = HashMap::with_capacity(data.rules().len());
     for (collection_id, rules) in data.rules() {
         let rules = match rules.to_dao() {
             Ok(rules) => rules,
             Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
         };
+        if let Err(err) = check_rule_role(
+            collection_id,
+            &rules,
+            &collection_project_ids,
+            &project_roles,
+        ) {
+            return Response::error_raw(&StatusCode::FORBIDDEN, &err.to_string());
+        }
         data_rules.insert(*collection_id, rules);
     }
 
@@ -122,6 +184,8 @@ async fn insert_one(
         ctx.access_token_length(),
         &data_rules,
         data.expired_at(),
+        data.allowed_cidrs(),
+        data.scopes(),
     );
     if let Err(err) = token_data.db_insert(ctx.dao().db()).await {
         return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
@@ -136,18 +200,34 @@ async fn insert_one(
         token_data_rules.insert(*collection_id, rules);
     }
 
-    Response::data(
-        &StatusCode::CREATED,
+    let token_res = TokenResJson::new(
+        token_data.id(),
+        token_data.created_at(),
+        token_data.updated_at(),
+        token_data.token(),
+        &token_data_rules,
+        token_data.expired_at(),
+        token_data.allowed_cidrs(),
+        token_data.scopes(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
         &None,
-        &TokenResJson::new(
-            token_data.id(),
-            token_data.created_at(),
-            token_data.updated_at(),
-            token_data.token(),
-            &token_data_rules,
-            token_data.expired_at(),
-        ),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "insert",
+        "token",
+        token_data.id(),
+        &None,
+        &serde_json::to_value(&token_res).ok(),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::CREATED, &None, &token_res)
 }
 
 async fn find_one(
@@ -160,7 +240,7 @@ async fn find_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -207,12 +287,15 @@ async fn find_one(
             token_data.token(),
             &token_data_rules,
             token_data.expired_at(),
+            token_data.allowed_cidrs(),
+            token_data.scopes(),
         ),
     )
 }
 
 async fn update_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     path: web::Path<UpdateOneTokenReqPath>,
     data: web::Json<UpdateOneTokenReqJson>,
@@ -222,7 +305,7 @@ async fn update_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -234,10 +317,20 @@ async fn update_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_tokens() {
         return Response::error_raw(
-            &StatusCode::BAD_REQUEST,
-            &format!("Failed to get user data: {err}"),
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying tokens",
         );
     }
 
@@ -255,6 +348,11 @@ async fn update_one(
         return Response::error_raw(&StatusCode::FORBIDDEN, "This token does not belong to you");
     }
 
+    let token_before = match TokenResJson::from_dao(&token_data) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
     if let Some(rules) = data.rules() {
         let mut collections_data_fut = Vec::with_capacity(rules.len());
         let mut check_tables_must_exist_fut = Vec::with_capacity(rules.len());
@@ -266,10 +364,13 @@ async fn update_one(
             ));
         }
         let mut project_ids = HashSet::new();
+        let mut collection_project_ids = HashMap::with_capacity(rules.len());
         match future::try_join_all(collections_data_fut).await {
             Ok(collections_data) => {
                 for collection_data in collections_data {
                     project_ids.insert(*collection_data.project_id());
+                    collection_project_ids
+                        .insert(*collection_data.id(), *collection_data.project_id());
                 }
             }
             Err(err) => {
@@ -283,14 +384,27 @@ async fn update_one(
         for project_id in &project_ids {
             projects_data_fut.push(ProjectDao::db_select(ctx.dao().db(), project_id));
         }
+        let mut project_roles = HashMap::with_capacity(project_ids.len());
         match future::try_join_all(projects_data_fut).await {
             Ok(projects_data) => {
                 for project_data in projects_data {
-                    if project_data.admin_id() != token_claim.id() {
-                        return Response::error_raw(
-                            &StatusCode::FORBIDDEN,
-                            "This collection does not belong to you",
-                        );
+                    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+                        Ok(Some(role)) if role.can_write_tokens() => {
+                            project_roles.insert(*project_data.id(), role);
+                        }
+                        Ok(Some(_)) => {
+                            return Response::error_raw(
+                                &StatusCode::FORBIDDEN,
+                                "Your project role doesn't allow modifying tokens",
+                            )
+                        }
+                        Ok(None) => {
+                            return Response::error_raw(
+                                &StatusCode::FORBIDDEN,
+                                "This collection does not belong to you",
+                            )
+                        }
+                        Err(err) => return err.to_response(),
                     }
                 }
             }
@@ -306,6 +420,14 @@ async fn update_one(
                 Ok(rules) => rules,
                 Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
             };
+            if let Err(err) = check_rule_role(
+                collection_id,
+                &rules,
+                &collection_project_ids,
+                &project_roles,
+            ) {
+                return Response::error_raw(&StatusCode::FORBIDDEN, &err.to_string());
+            }
             data_rules.insert(*collection_id, rules);
         }
 
@@ -324,6 +446,24 @@ async fn update_one(
         token_data.set_expired_at(expired_at);
     }
 
+    if let Some(allowed_cidrs) = data.allowed_cidrs() {
+        if let Some(allowed_cidrs) = allowed_cidrs {
+            if let Err(err) = TokenDao::check_allowed_cidrs(allowed_cidrs) {
+                return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+            }
+        }
+        token_data.set_allowed_cidrs(allowed_cidrs);
+    }
+
+    if let Some(scopes) = data.scopes() {
+        if let Some(scopes) = scopes {
+            if let Err(err) = TokenDao::check_scopes(scopes) {
+                return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+            }
+        }
+        token_data.set_scopes(scopes);
+    }
+
     if !data.is_all_none() {
         if let Err(err) = token_data.db_update(ctx.dao().db()).await {
             return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
@@ -339,22 +479,370 @@ async fn update_one(
         token_data_rules.insert(*collection_id, rules);
     }
 
-    Response::data(
-        &StatusCode::OK,
+    let token_res = TokenResJson::new(
+        token_data.id(),
+        token_data.created_at(),
+        token_data.updated_at(),
+        token_data.token(),
+        &token_data_rules,
+        token_data.expired_at(),
+        token_data.allowed_cidrs(),
+        token_data.scopes(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
         &None,
-        &TokenResJson::new(
-            token_data.id(),
-            token_data.created_at(),
-            token_data.updated_at(),
-            token_data.token(),
-            &token_data_rules,
-            token_data.expired_at(),
-        ),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "update",
+        "token",
+        token_data.id(),
+        &serde_json::to_value(&token_before).ok(),
+        &serde_json::to_value(&token_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &token_res)
+}
+
+async fn apply_rules(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<ApplyRulesTokenReqPath>,
+    data: web::Json<ApplyRulesTokenReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_tokens() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying tokens",
+        );
+    }
+
+    let mut token_data = match TokenDao::db_select(ctx.dao().db(), path.token_id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get token data: {err}"),
+            )
+        }
+    };
+
+    if token_data.admin_id() != token_claim.id() {
+        return Response::error_raw(&StatusCode::FORBIDDEN, "This token does not belong to you");
+    }
+
+    if data.project_id().is_none() && data.collection_ids().is_none() {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Either 'project_id' or 'collection_ids' must be specified",
+        );
+    }
+
+    let mut collection_ids = HashSet::new();
+    let mut collection_project_ids = HashMap::new();
+    let mut project_roles = HashMap::new();
+
+    if let Some(project_id) = data.project_id() {
+        let (project_data, collections_data) = match tokio::try_join!(
+            ProjectDao::db_select(ctx.dao().db(), project_id),
+            CollectionDao::db_select_many_by_project_id(ctx.dao().db(), project_id)
+        ) {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+        match project_access::role(&ctx, &project_data, token_claim.id()).await {
+            Ok(Some(role)) if role.can_write_tokens() => {
+                project_roles.insert(*project_data.id(), role);
+            }
+            Ok(Some(_)) => {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying tokens",
+                )
+            }
+            Ok(None) => {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "This project does not belong to you",
+                )
+            }
+            Err(err) => return err.to_response(),
+        }
+        for collection_data in &collections_data {
+            collection_ids.insert(*collection_data.id());
+            collection_project_ids.insert(*collection_data.id(), *collection_data.project_id());
+        }
+    }
+
+    if let Some(ids) = data.collection_ids() {
+        let mut collections_data_fut = Vec::with_capacity(ids.len());
+        for collection_id in ids {
+            collections_data_fut.push(CollectionDao::db_select(ctx.dao().db(), collection_id));
+        }
+        let mut project_ids = HashSet::new();
+        match future::try_join_all(collections_data_fut).await {
+            Ok(collections_data) => {
+                for collection_data in collections_data {
+                    project_ids.insert(*collection_data.project_id());
+                    collection_ids.insert(*collection_data.id());
+                    collection_project_ids
+                        .insert(*collection_data.id(), *collection_data.project_id());
+                }
+            }
+            Err(err) => {
+                return Response::error_raw(
+                    &StatusCode::BAD_REQUEST,
+                    &format!("Failed to get collection: {err}"),
+                )
+            }
+        }
+        let mut projects_data_fut = Vec::with_capacity(project_ids.len());
+        for project_id in &project_ids {
+            projects_data_fut.push(ProjectDao::db_select(ctx.dao().db(), project_id));
+        }
+        match future::try_join_all(projects_data_fut).await {
+            Ok(projects_data) => {
+                for project_data in projects_data {
+                    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+                        Ok(Some(role)) if role.can_write_tokens() => {
+                            project_roles.insert(*project_data.id(), role);
+                        }
+                        Ok(Some(_)) => {
+                            return Response::error_raw(
+                                &StatusCode::FORBIDDEN,
+                                "Your project role doesn't allow modifying tokens",
+                            )
+                        }
+                        Ok(None) => {
+                            return Response::error_raw(
+                                &StatusCode::FORBIDDEN,
+                                "This collection does not belong to you",
+                            )
+                        }
+                        Err(err) => return err.to_response(),
+                    }
+                }
+            }
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        }
+    }
+
+    let rule = match data.rule().to_dao() {
+        Ok(rule) => rule,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let token_before = match TokenResJson::from_dao(&token_data) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    for collection_id in &collection_ids {
+        if let Err(err) = check_rule_role(
+            collection_id,
+            &rule,
+            &collection_project_ids,
+            &project_roles,
+        ) {
+            return Response::error_raw(&StatusCode::FORBIDDEN, &err.to_string());
+        }
+    }
+
+    let mut data_rules = token_data.rules().clone();
+    for collection_id in &collection_ids {
+        data_rules.insert(*collection_id, rule.clone());
+    }
+    token_data.set_rules(&data_rules);
+
+    if !collection_ids.is_empty() {
+        if let Err(err) = token_data.db_update(ctx.dao().db()).await {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+    }
+
+    let mut token_data_rules = HashMap::with_capacity(token_data.rules().len());
+    for (collection_id, rules) in token_data.rules() {
+        let rules = match TokenRuleMethodJson::from_dao(rules) {
+            Ok(rules) => rules,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+        token_data_rules.insert(*collection_id, rules);
+    }
+
+    let token_res = TokenResJson::new(
+        token_data.id(),
+        token_data.created_at(),
+        token_data.updated_at(),
+        token_data.token(),
+        &token_data_rules,
+        token_data.expired_at(),
+        token_data.allowed_cidrs(),
+        token_data.scopes(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &None,
+        &req.peer_addr().map(|addr| addr.ip()),
+        "apply_rules",
+        "token",
+        token_data.id(),
+        &serde_json::to_value(&token_before).ok(),
+        &serde_json::to_value(&token_res).ok(),
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &token_res)
+}
+
+async fn rotate(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<RotateTokenReqPath>,
+    data: web::Json<RotateTokenReqJson>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_tokens() {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying tokens",
+        );
+    }
+
+    let grace_period = data.grace_period().unwrap_or(0);
+    if grace_period < 0 {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Grace period must be greater than or equal to 0",
+        );
+    }
+
+    let mut token_data = match TokenDao::db_select(ctx.dao().db(), path.token_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_data.admin_id() != token_claim.id() {
+        return Response::error_raw(&StatusCode::FORBIDDEN, "This token does not belong to you");
+    }
+
+    let token_before = match TokenResJson::from_dao(&token_data) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    token_data.rotate(ctx.access_token_length(), &grace_period);
+
+    if let Err(err) = token_data.db_update_token(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let mut token_data_rules = HashMap::with_capacity(token_data.rules().len());
+    for (collection_id, rules) in token_data.rules() {
+        let rules = match TokenRuleMethodJson::from_dao(rules) {
+            Ok(rules) => rules,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+        token_data_rules.insert(*collection_id, rules);
+    }
+
+    let token_res = TokenResJson::new(
+        token_data.id(),
+        token_data.created_at(),
+        token_data.updated_at(),
+        token_data.token(),
+        &token_data_rules,
+        token_data.expired_at(),
+        token_data.allowed_cidrs(),
+        token_data.scopes(),
+    );
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &None,
+        &req.peer_addr().map(|addr| addr.ip()),
+        "rotate",
+        "token",
+        token_data.id(),
+        &serde_json::to_value(&token_before).ok(),
+        &serde_json::to_value(&token_res).ok(),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &token_res)
 }
 
 async fn delete_one(
     ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
     token: web::Header<TokenReqHeader>,
     path: web::Path<DeleteOneTokenReqPath>,
 ) -> HttpResponse {
@@ -363,7 +851,7 @@ async fn delete_one(
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -375,10 +863,20 @@ async fn delete_one(
         );
     }
 
-    if let Err(err) = AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+    let admin_data = match AdminDao::db_select(ctx.dao().db(), token_claim.id()).await {
+        Ok(data) => data,
+        Err(err) => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                &format!("Failed to get user data: {err}"),
+            )
+        }
+    };
+
+    if !admin_data.role().can_write_tokens() {
         return Response::error_raw(
-            &StatusCode::BAD_REQUEST,
-            &format!("Failed to get user data: {err}"),
+            &StatusCode::FORBIDDEN,
+            "Your admin role doesn't allow modifying tokens",
         );
     }
 
@@ -391,15 +889,47 @@ async fn delete_one(
         return Response::error_raw(&StatusCode::FORBIDDEN, "This token does not belong to you");
     }
 
+    let token_before = match TokenResJson::from_dao(&token_data) {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
     if let Err(err) = TokenDao::db_delete(ctx.dao().db(), path.token_id()).await {
         return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
     }
 
-    Response::data(
-        &StatusCode::OK,
+    let revocation_expired_at = match i64::try_from(*ctx.token().jwt().expiry_duration()) {
+        Ok(expiry_duration) => Utc::now() + Duration::seconds(expiry_duration),
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+    if let Err(err) = JwtRevocationDao::new(path.token_id(), &revocation_expired_at)
+        .db_insert(ctx.dao().db())
+        .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let token_res = DeleteTokenResJson::new(token_data.id());
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &None,
+        &req.peer_addr().map(|addr| addr.ip()),
+        "delete",
+        "token",
+        token_data.id(),
+        &serde_json::to_value(&token_before).ok(),
         &None,
-        &DeleteTokenResJson::new(token_data.id()),
     )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &token_res)
 }
 
 async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader>) -> HttpResponse {
@@ -408,7 +938,7 @@ async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader
         None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
     };
 
-    let token_claim = match ctx.token().jwt().decode(token) {
+    let token_claim = match ctx.decode_token(token).await {
         Ok(token) => token,
         Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
     };
@@ -450,12 +980,38 @@ async fn find_many(ctx: web::Data<ApiRestCtx>, token: web::Header<TokenReqHeader
             token_data.token(),
             &token_data_rules,
             token_data.expired_at(),
+            token_data.allowed_cidrs(),
+            token_data.scopes(),
         ));
     }
 
     Response::data(
         &StatusCode::OK,
-        &Some(PaginationRes::new(&tokens_data.len(), &tokens_data.len())),
+        &Some(PaginationRes::new(
+            &tokens_data.len(),
+            &Some(tokens_data.len()),
+            &None,
+            &None,
+        )),
         &tokens_res,
     )
 }
+
+// A token can't grant more on a collection than its creator's role in the collection's project
+fn check_rule_role(
+    collection_id: &Uuid,
+    rule: &TokenRuleMethod,
+    collection_project_ids: &HashMap<Uuid, Uuid>,
+    project_roles: &HashMap<Uuid, AdminRole>,
+) -> Result<()> {
+    let can_write_records = collection_project_ids
+        .get(collection_id)
+        .and_then(|project_id| project_roles.get(project_id))
+        .is_some_and(|role| role.can_write_records());
+    if rule.is_write() && !can_write_records {
+        return Err(Error::msg(format!(
+            "Your project role doesn't allow granting write access to collection {collection_id}"
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,721 @@
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use hb_dao::{
+    audit_log::AuditLogDao, jwt_revocation::JwtRevocationDao, project::ProjectDao, token::TokenDao,
+    user::UserDao, user_password_reset::UserPasswordResetDao,
+};
+use hb_mailer::MailPayload;
+use hb_token_jwt::{claim::USER_ID_CLAIM, kind::JwtTokenKind};
+use serde_json::Value;
+use validator::Validate;
+
+use super::auth::PASSWORD_RESET_RESEND_INTERVAL;
+use crate::{
+    context::ApiRestCtx,
+    model::{
+        auth::AuthTokenResJson,
+        user::{
+            AnonymousUserReqJson, ConfirmUserPasswordResetReqJson, ConfirmUserPasswordResetResJson,
+            DeleteOneUserReqPath, DeleteUserResJson, FindManyUserReqPath, RegisterUserReqJson,
+            RequestUserPasswordResetReqJson, RequestUserPasswordResetResJson, UpgradeUserReqJson,
+            UserPasswordBasedReqJson, UserResJson,
+        },
+        PaginationRes, Response, TokenReqHeader,
+    },
+    project_access,
+};
+
+pub fn user_api(cfg: &mut web::ServiceConfig) {
+    cfg.route("/auth/user/register", web::post().to(register))
+        .route("/auth/user/password-based", web::post().to(password_based))
+        .route("/auth/user/anonymous", web::post().to(anonymous))
+        .route("/auth/user/upgrade", web::post().to(upgrade))
+        .route(
+            "/auth/user/request-password-reset",
+            web::post().to(request_password_reset),
+        )
+        .route(
+            "/auth/user/confirm-password-reset",
+            web::post().to(confirm_password_reset),
+        )
+        .route("/project/{project_id}/users", web::get().to(find_many))
+        .route(
+            "/project/{project_id}/user/{user_id}",
+            web::delete().to(delete_one),
+        );
+}
+
+async fn register(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    data: web::Json<RegisterUserReqJson>,
+) -> HttpResponse {
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    }
+
+    let token_data = match TokenDao::db_select_by_token(ctx.dao().db(), data.token()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !token_data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This token is not allowed from this IP address",
+        );
+    }
+
+    if !token_data.is_allow_scope("users:auth") {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This token doesn't have the 'users:auth' scope",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), data.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_data.admin_id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This token doesn't belong to this project",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    if UserDao::db_select_by_project_id_and_email(ctx.dao().db(), project_data.id(), data.email())
+        .await
+        .is_ok()
+    {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "User with this email already exists",
+        );
+    }
+
+    let password_hash = match ctx
+        .hash()
+        .argon2()
+        .hash_password(data.password().as_bytes())
+    {
+        Ok(hash) => hash,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    let user_data = UserDao::new(project_data.id(), data.email(), &password_hash.to_string());
+
+    if let Err(err) = user_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::CREATED,
+        &None,
+        &UserResJson::new(
+            user_data.id(),
+            user_data.created_at(),
+            user_data.updated_at(),
+            user_data.project_id(),
+            user_data.email(),
+        ),
+    )
+}
+
+async fn password_based(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    data: web::Json<UserPasswordBasedReqJson>,
+) -> HttpResponse {
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    }
+
+    let ip = req.peer_addr().map(|addr| addr.ip());
+
+    // Users of different projects may share an email, so lockouts are kept per project
+    let throttle_key = format!("{}:{}", data.project_id(), data.email());
+
+    if let Err(err) = ctx.login_throttle().check(&throttle_key, &ip) {
+        return Response::error_raw(&StatusCode::TOO_MANY_REQUESTS, &err.to_string());
+    }
+
+    let token_data = match TokenDao::db_select_by_token(ctx.dao().db(), data.token()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !token_data.is_allow_ip(&ip) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This token is not allowed from this IP address",
+        );
+    }
+
+    if !token_data.is_allow_scope("users:auth") {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This token doesn't have the 'users:auth' scope",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), data.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_data.admin_id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This token doesn't belong to this project",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let user_data = match UserDao::db_select_by_project_id_and_email(
+        ctx.dao().db(),
+        project_data.id(),
+        data.email(),
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => {
+            ctx.login_throttle().record_failure(&throttle_key, &ip);
+            return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+        }
+    };
+
+    let password_hash = match user_data.password_hash() {
+        Some(password_hash) => password_hash,
+        None => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                "This user has not registered a password",
+            )
+        }
+    };
+
+    if let Err(err) = ctx
+        .hash()
+        .argon2()
+        .verify_password(data.password(), password_hash)
+    {
+        // Users have no unlock endpoint, so the lockout simply runs out
+        ctx.login_throttle().record_failure(&throttle_key, &ip);
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    ctx.login_throttle().record_success(&throttle_key);
+
+    let mut custom_claims = project_data.custom_token_claims();
+    custom_claims.insert(
+        USER_ID_CLAIM.to_owned(),
+        Value::String(user_data.id().to_string()),
+    );
+
+    let token = match ctx.token().jwt().encode_with_claims(
+        token_data.id(),
+        &JwtTokenKind::Token,
+        &custom_claims,
+    ) {
+        Ok(token) => token,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &AuthTokenResJson::new(&token, &None),
+    )
+}
+
+async fn anonymous(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    data: web::Json<AnonymousUserReqJson>,
+) -> HttpResponse {
+    let token_data = match TokenDao::db_select_by_token(ctx.dao().db(), data.token()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !token_data.is_allow_ip(&req.peer_addr().map(|addr| addr.ip())) {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This token is not allowed from this IP address",
+        );
+    }
+
+    if !token_data.is_allow_scope("users:anonymous") {
+        return Response::error_raw(
+            &StatusCode::FORBIDDEN,
+            "This token doesn't have the 'users:anonymous' scope",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), data.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_data.admin_id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This token doesn't belong to this project",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let user_data = UserDao::new_anonymous(project_data.id());
+
+    if let Err(err) = user_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let mut custom_claims = project_data.custom_token_claims();
+    custom_claims.insert(
+        USER_ID_CLAIM.to_owned(),
+        Value::String(user_data.id().to_string()),
+    );
+
+    let token = match ctx.token().jwt().encode_with_claims(
+        token_data.id(),
+        &JwtTokenKind::Token,
+        &custom_claims,
+    ) {
+        Ok(token) => token,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    Response::data(
+        &StatusCode::CREATED,
+        &None,
+        &AuthTokenResJson::new(&token, &None),
+    )
+}
+
+// Keeps the user id of the anonymous identity, so everything it created stays owned by it
+async fn upgrade(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    data: web::Json<UpgradeUserReqJson>,
+) -> HttpResponse {
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    }
+
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let user_id = match token_claim.user_id() {
+        Some(user_id) if token_claim.kind() == &JwtTokenKind::Token => user_id,
+        _ => {
+            return Response::error_raw(
+                &StatusCode::BAD_REQUEST,
+                "Must be logged in as an anonymous user",
+            )
+        }
+    };
+
+    let mut user_data = match UserDao::db_select(ctx.dao().db(), &user_id).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if !user_data.is_anonymous() {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, "This user is already registered");
+    }
+
+    if UserDao::db_select_by_project_id_and_email(
+        ctx.dao().db(),
+        user_data.project_id(),
+        data.email(),
+    )
+    .await
+    .is_ok()
+    {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "User with this email already exists",
+        );
+    }
+
+    let password_hash = match ctx
+        .hash()
+        .argon2()
+        .hash_password(data.password().as_bytes())
+    {
+        Ok(hash) => hash,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    user_data.set_email(data.email());
+    user_data.set_password_hash(&password_hash.to_string());
+
+    if let Err(err) = user_data.db_update(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &UserResJson::new(
+            user_data.id(),
+            user_data.created_at(),
+            user_data.updated_at(),
+            user_data.project_id(),
+            user_data.email(),
+        ),
+    )
+}
+
+async fn request_password_reset(
+    ctx: web::Data<ApiRestCtx>,
+    data: web::Json<RequestUserPasswordResetReqJson>,
+) -> HttpResponse {
+    if let Err(err) = data.validate() {
+        return Response::error_validation(&err);
+    };
+
+    let user_data = match UserDao::db_select_by_project_id_and_email(
+        ctx.dao().db(),
+        data.project_id(),
+        data.email(),
+    )
+    .await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let password_resets_data =
+        match UserPasswordResetDao::db_select_many_by_user_id(ctx.dao().db(), user_data.id()).await
+        {
+            Ok(data) => data,
+            Err(err) => {
+                return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+
+    let resend_after = Utc::now() - Duration::seconds(PASSWORD_RESET_RESEND_INTERVAL);
+    if password_resets_data
+        .iter()
+        .any(|password_reset_data| *password_reset_data.created_at() > resend_after)
+    {
+        return Response::error_raw(
+            &StatusCode::TOO_MANY_REQUESTS,
+            "A password reset code was sent recently. Please wait before requesting another one",
+        );
+    }
+
+    // A fresh reset replaces the old ones so each new code doesn't bring a fresh attempt budget
+    // alongside codes that are still guessable
+    if let Err(err) =
+        UserPasswordResetDao::db_delete_many_by_user_id(ctx.dao().db(), user_data.id()).await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let password_reset_data = UserPasswordResetDao::new(user_data.id());
+
+    if let Err(err) = password_reset_data.db_insert(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    if let Err(err) = ctx.mailer().sender().send(MailPayload::new(
+        data.email(),
+        "Request Password Reset Verification Code",
+        &format!(
+            "Your request password reset verification code is {}. This code will expire in {} seconds",
+            password_reset_data.code(),
+            ctx.reset_password_ttl()
+        ),
+    )) {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &RequestUserPasswordResetResJson::new(password_reset_data.id()),
+    )
+}
+
+async fn confirm_password_reset(
+    ctx: web::Data<ApiRestCtx>,
+    data: web::Json<ConfirmUserPasswordResetReqJson>,
+) -> HttpResponse {
+    let password_reset_data = match UserPasswordResetDao::db_select(ctx.dao().db(), data.id()).await
+    {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if let Err(err) = ctx.login_throttle().verify_reset_code(
+        password_reset_data.id(),
+        password_reset_data.code(),
+        data.code(),
+    ) {
+        if ctx
+            .login_throttle()
+            .is_reset_exhausted(password_reset_data.id())
+        {
+            if let Err(err) = UserPasswordResetDao::db_delete_many_by_user_id(
+                ctx.dao().db(),
+                password_reset_data.user_id(),
+            )
+            .await
+            {
+                hb_log::error(None, &err);
+            }
+        }
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    if let Err(err) = UserPasswordResetDao::db_delete_many_by_user_id(
+        ctx.dao().db(),
+        password_reset_data.user_id(),
+    )
+    .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let mut user_data =
+        match UserDao::db_select(ctx.dao().db(), password_reset_data.user_id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    let password_hash = match ctx
+        .hash()
+        .argon2()
+        .hash_password(data.password().as_bytes())
+    {
+        Ok(hash) => hash,
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+
+    user_data.set_password_hash(&password_hash.to_string());
+
+    if let Err(err) = user_data.db_update(ctx.dao().db()).await {
+        return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string());
+    }
+
+    if let Some(email) = user_data.email() {
+        if let Err(err) = ctx.mailer().sender().send(MailPayload::new(
+            email,
+            "Your Password Has Been Reset Successfully",
+            "Your account password has been successfully changed",
+        )) {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+        }
+    }
+
+    Response::data(
+        &StatusCode::OK,
+        &None,
+        &ConfirmUserPasswordResetResJson::new(user_data.id()),
+    )
+}
+
+async fn find_many(
+    ctx: web::Data<ApiRestCtx>,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<FindManyUserReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(_)) => (),
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let users_data =
+        match UserDao::db_select_many_by_project_id(ctx.dao().db(), project_data.id()).await {
+            Ok(data) => data,
+            Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    Response::data(
+        &StatusCode::OK,
+        &Some(PaginationRes::new(
+            &users_data.len(),
+            &Some(users_data.len()),
+            &None,
+            &None,
+        )),
+        &users_data
+            .iter()
+            .map(|data| {
+                UserResJson::new(
+                    data.id(),
+                    data.created_at(),
+                    data.updated_at(),
+                    data.project_id(),
+                    data.email(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+async fn delete_one(
+    ctx: web::Data<ApiRestCtx>,
+    req: HttpRequest,
+    token: web::Header<TokenReqHeader>,
+    path: web::Path<DeleteOneUserReqPath>,
+) -> HttpResponse {
+    let token = match token.get() {
+        Some(token) => token,
+        None => return Response::error_raw(&StatusCode::BAD_REQUEST, "Invalid token"),
+    };
+
+    let token_claim = match ctx.decode_token(token).await {
+        Ok(token) => token,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if token_claim.kind() != &JwtTokenKind::User {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "Must be logged in using password-based login",
+        );
+    }
+
+    let project_data = match ProjectDao::db_select(ctx.dao().db(), path.project_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    match project_access::role(&ctx, &project_data, token_claim.id()).await {
+        Ok(Some(role)) => {
+            if !role.can_write_projects() {
+                return Response::error_raw(
+                    &StatusCode::FORBIDDEN,
+                    "Your project role doesn't allow modifying project users",
+                );
+            }
+        }
+        Ok(None) => {
+            return Response::error_raw(
+                &StatusCode::FORBIDDEN,
+                "This project does not belong to you",
+            )
+        }
+        Err(err) => return err.to_response(),
+    }
+
+    let user_data = match UserDao::db_select(ctx.dao().db(), path.user_id()).await {
+        Ok(data) => data,
+        Err(err) => return Response::error_raw(&StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    if user_data.project_id() != project_data.id() {
+        return Response::error_raw(
+            &StatusCode::BAD_REQUEST,
+            "This user does not belong to the project",
+        );
+    }
+
+    let user_before = UserResJson::new(
+        user_data.id(),
+        user_data.created_at(),
+        user_data.updated_at(),
+        user_data.project_id(),
+        user_data.email(),
+    );
+
+    if let Err(err) = UserDao::db_delete(ctx.dao().db(), user_data.id()).await {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    // Tokens already issued to the deleted user stay valid until they expire unless revoked
+    let revocation_expired_at = match i64::try_from(*ctx.token().jwt().expiry_duration()) {
+        Ok(expiry_duration) => Utc::now() + Duration::seconds(expiry_duration),
+        Err(err) => {
+            return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+        }
+    };
+    if let Err(err) = JwtRevocationDao::new(user_data.id(), &revocation_expired_at)
+        .db_insert(ctx.dao().db())
+        .await
+    {
+        return Response::error_raw(&StatusCode::INTERNAL_SERVER_ERROR, &err.to_string());
+    }
+
+    let user_res = DeleteUserResJson::new(user_data.id());
+
+    if let Err(err) = AuditLogDao::new(
+        token_claim.id(),
+        &Some(*user_data.project_id()),
+        &req.peer_addr().map(|addr| addr.ip()),
+        "delete",
+        "user",
+        user_data.id(),
+        &serde_json::to_value(&user_before).ok(),
+        &None,
+    )
+    .db_insert(ctx.dao().db())
+    .await
+    {
+        hb_log::error(None, &err);
+    }
+
+    Response::data(&StatusCode::OK, &None, &user_res)
+}
@@ -0,0 +1,103 @@
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+
+const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+const TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+
+#[derive(Clone)]
+pub struct TraceContext {
+    trace_id: String,
+    parent_id: Option<String>,
+    span_id: String,
+    flags: String,
+    state: Option<String>,
+}
+
+impl TraceContext {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let state = headers
+            .get(TRACESTATE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty() && value.len() <= 512);
+
+        if let Some((trace_id, parent_id, flags)) = headers
+            .get(TRACEPARENT)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Self::parse_traceparent)
+        {
+            return Self {
+                trace_id,
+                parent_id: Some(parent_id),
+                span_id: Self::random_hex(8),
+                flags,
+                state,
+            };
+        }
+
+        Self {
+            trace_id: Self::random_hex(16),
+            parent_id: None,
+            span_id: Self::random_hex(8),
+            flags: "01".to_owned(),
+            state: None,
+        }
+    }
+
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    pub fn parent_id(&self) -> &Option<String> {
+        &self.parent_id
+    }
+
+    pub fn span_id(&self) -> &str {
+        &self.span_id
+    }
+
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-{}", self.trace_id, self.span_id, self.flags)
+    }
+
+    pub fn insert_headers(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.traceparent()) {
+            headers.insert(TRACEPARENT, value);
+        }
+        if let Some(state) = &self.state {
+            if let Ok(value) = HeaderValue::from_str(state) {
+                headers.insert(TRACESTATE, value);
+            }
+        }
+    }
+
+    fn parse_traceparent(value: &str) -> Option<(String, String, String)> {
+        let parts = value.trim().split('-').collect::<Vec<_>>();
+        if parts.len() < 4 {
+            return None;
+        }
+        let (version, trace_id, parent_id, flags) = (parts[0], parts[1], parts[2], parts[3]);
+        if !Self::is_hex(version, 2) || version == "ff" || (version == "00" && parts.len() != 4) {
+            return None;
+        }
+        if !Self::is_hex(trace_id, 32) || trace_id.chars().all(|c| c == '0') {
+            return None;
+        }
+        if !Self::is_hex(parent_id, 16) || parent_id.chars().all(|c| c == '0') {
+            return None;
+        }
+        if !Self::is_hex(flags, 2) {
+            return None;
+        }
+        Some((trace_id.to_owned(), parent_id.to_owned(), flags.to_owned()))
+    }
+
+    fn is_hex(value: &str, len: usize) -> bool {
+        value.len() == len && value.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+    }
+
+    fn random_hex(len: usize) -> String {
+        (0..len)
+            .map(|_| format!("{:02x}", rand::random::<u8>()))
+            .collect()
+    }
+}
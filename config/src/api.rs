@@ -15,6 +15,7 @@ impl ApiConfig {
 pub struct ApiRestConfig {
     host: String,
     port: String,
+    compression: Option<bool>,
 }
 
 impl ApiRestConfig {
@@ -25,4 +26,8 @@ impl ApiRestConfig {
     pub fn port(&self) -> &str {
         &self.port
     }
+
+    pub fn compression(&self) -> &Option<bool> {
+        &self.compression
+    }
 }
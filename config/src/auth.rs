@@ -6,6 +6,8 @@ pub struct AuthConfig {
     access_token_length: usize,
     registration_ttl: u32,
     reset_password_ttl: u32,
+    operator_emails: Option<Vec<String>>,
+    bootstrap_admin: Option<BootstrapAdminConfig>,
 }
 
 impl AuthConfig {
@@ -24,4 +26,28 @@ impl AuthConfig {
     pub fn reset_password_ttl(&self) -> &u32 {
         &self.reset_password_ttl
     }
+
+    pub fn operator_emails(&self) -> &Option<Vec<String>> {
+        &self.operator_emails
+    }
+
+    pub fn bootstrap_admin(&self) -> &Option<BootstrapAdminConfig> {
+        &self.bootstrap_admin
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BootstrapAdminConfig {
+    email: String,
+    password_hash: String,
+}
+
+impl BootstrapAdminConfig {
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn password_hash(&self) -> &str {
+        &self.password_hash
+    }
 }
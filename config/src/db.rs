@@ -16,6 +16,11 @@ pub struct DbConfig {
     postgres: Option<DbPostgresConfig>,
     mysql: Option<DbMysqlConfig>,
     sqlite: Option<DbSqliteConfig>,
+    record_sweep_interval: Option<u64>,
+    usage_meter_interval: Option<u64>,
+    project_purge_interval: Option<u64>,
+    project_deletion_grace_period: Option<u64>,
+    slow_query_threshold: Option<u64>,
 }
 
 impl DbConfig {
@@ -34,4 +39,24 @@ impl DbConfig {
     pub fn sqlite(&self) -> &Option<DbSqliteConfig> {
         &self.sqlite
     }
+
+    pub fn record_sweep_interval(&self) -> &Option<u64> {
+        &self.record_sweep_interval
+    }
+
+    pub fn usage_meter_interval(&self) -> &Option<u64> {
+        &self.usage_meter_interval
+    }
+
+    pub fn project_purge_interval(&self) -> &Option<u64> {
+        &self.project_purge_interval
+    }
+
+    pub fn project_deletion_grace_period(&self) -> &Option<u64> {
+        &self.project_deletion_grace_period
+    }
+
+    pub fn slow_query_threshold(&self) -> &Option<u64> {
+        &self.slow_query_threshold
+    }
 }
@@ -6,6 +6,8 @@ pub struct DbScyllaConfig {
     port: String,
     replication_factor: i64,
     prepared_statement_cache_size: usize,
+    read_consistency: Option<String>,
+    write_consistency: Option<String>,
 }
 
 impl DbScyllaConfig {
@@ -24,4 +26,12 @@ impl DbScyllaConfig {
     pub fn prepared_statement_cache_size(&self) -> &usize {
         &self.prepared_statement_cache_size
     }
+
+    pub fn read_consistency(&self) -> &Option<String> {
+        &self.read_consistency
+    }
+
+    pub fn write_consistency(&self) -> &Option<String> {
+        &self.write_consistency
+    }
 }
@@ -0,0 +1,54 @@
+use serde_yaml::{Mapping, Value};
+
+const PREFIX: &str = "HB_";
+const SEPARATOR: &str = "__";
+const IGNORED: [&str; 1] = ["HB_CONFIG_PATH"];
+
+// Applies `HB_<SECTION>__<KEY>...` variables over the config file, e.g. `HB_DB__POSTGRES__PASSWORD`
+// sets db.postgres.password. Values are parsed as YAML so numbers, booleans, and lists such as
+// `[a@example.com, b@example.com]` work, except that a value replacing a string in the file is
+// always kept as a string.
+pub fn overlay(config: &mut Value, vars: impl Iterator<Item = (String, String)>) {
+    let mut vars = vars
+        .filter(|(key, _)| key.starts_with(PREFIX) && !IGNORED.contains(&key.as_str()))
+        .collect::<Vec<_>>();
+    vars.sort();
+
+    for (key, value) in vars {
+        let path = key[PREFIX.len()..]
+            .split(SEPARATOR)
+            .map(|segment| segment.to_lowercase())
+            .collect::<Vec<_>>();
+        if path.iter().any(|segment| segment.is_empty()) {
+            continue;
+        }
+        set(config, &path, &value);
+    }
+}
+
+fn set(config: &mut Value, path: &[String], value: &str) {
+    if !config.is_mapping() {
+        *config = Value::Mapping(Mapping::new());
+    }
+    let mapping = match config.as_mapping_mut() {
+        Some(mapping) => mapping,
+        None => return,
+    };
+    let key = Value::String(path[0].clone());
+
+    if path.len() > 1 {
+        let child = mapping
+            .entry(key)
+            .or_insert_with(|| Value::Mapping(Mapping::new()));
+        set(child, &path[1..], value);
+        return;
+    }
+
+    let value = match mapping.get(&key) {
+        Some(Value::String(_)) => Value::String(value.to_owned()),
+        _ => {
+            serde_yaml::from_str::<Value>(value).unwrap_or_else(|_| Value::String(value.to_owned()))
+        }
+    };
+    mapping.insert(key, value);
+}
@@ -6,16 +6,26 @@ use db::DbConfig;
 use hash::HashConfig;
 use log::LogConfig;
 use mailer::MailerConfig;
+use plugin::PluginConfig;
+use publisher::PublisherConfig;
+use quota::QuotaConfig;
+use scheduler::SchedulerConfig;
 use serde::Deserialize;
 use token::TokenConfig;
 
 pub mod api;
 pub mod auth;
 pub mod db;
+mod env;
 pub mod hash;
 pub mod log;
 pub mod mailer;
+pub mod plugin;
+pub mod publisher;
+pub mod quota;
+pub mod scheduler;
 pub mod token;
+mod validation;
 
 #[derive(Deserialize)]
 pub struct Config {
@@ -26,6 +36,10 @@ pub struct Config {
     db: DbConfig,
     api: ApiConfig,
     auth: AuthConfig,
+    quota: Option<QuotaConfig>,
+    publisher: Option<PublisherConfig>,
+    scheduler: Option<SchedulerConfig>,
+    plugin: Option<PluginConfig>,
 }
 
 impl Config {
@@ -56,9 +70,33 @@ impl Config {
     pub fn auth(&self) -> &AuthConfig {
         &self.auth
     }
+
+    pub fn quota(&self) -> &Option<QuotaConfig> {
+        &self.quota
+    }
+
+    pub fn publisher(&self) -> &Option<PublisherConfig> {
+        &self.publisher
+    }
+
+    pub fn scheduler(&self) -> &Option<SchedulerConfig> {
+        &self.scheduler
+    }
+
+    pub fn plugin(&self) -> &Option<PluginConfig> {
+        &self.plugin
+    }
 }
 
-pub fn new(path: &str) -> Config {
-    let file = File::open(path).expect("");
-    serde_yaml::from_reader::<_, Config>(file).unwrap()
+pub fn new(path: &Option<String>) -> Result<Config, String> {
+    let mut config = match path {
+        Some(path) => {
+            let file = File::open(path).map_err(|err| format!("Failed to open {path}: {err}"))?;
+            serde_yaml::from_reader::<_, serde_yaml::Value>(file)
+                .map_err(|err| format!("Failed to parse {path}: {err}"))?
+        }
+        None => serde_yaml::Value::Null,
+    };
+    env::overlay(&mut config, std::env::vars());
+    serde_yaml::from_value::<Config>(config).map_err(|err| err.to_string())
 }
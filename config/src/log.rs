@@ -4,6 +4,9 @@ use serde::Deserialize;
 pub struct LogConfig {
     display_level: bool,
     level_filter: String,
+    span_timings: Option<bool>,
+    file: Option<LogFileConfig>,
+    otlp: Option<LogOtlpConfig>,
 }
 
 impl LogConfig {
@@ -14,4 +17,58 @@ impl LogConfig {
     pub fn level_filter(&self) -> &str {
         &self.level_filter
     }
+
+    pub fn span_timings(&self) -> &Option<bool> {
+        &self.span_timings
+    }
+
+    pub fn file(&self) -> &Option<LogFileConfig> {
+        &self.file
+    }
+
+    pub fn otlp(&self) -> &Option<LogOtlpConfig> {
+        &self.otlp
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LogFileConfig {
+    path: String,
+    rotation: Option<String>,
+    max_size: Option<u64>,
+    max_files: Option<usize>,
+}
+
+impl LogFileConfig {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn rotation(&self) -> &Option<String> {
+        &self.rotation
+    }
+
+    pub fn max_size(&self) -> &Option<u64> {
+        &self.max_size
+    }
+
+    pub fn max_files(&self) -> &Option<usize> {
+        &self.max_files
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LogOtlpConfig {
+    endpoint: String,
+    service_name: Option<String>,
+}
+
+impl LogOtlpConfig {
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn service_name(&self) -> &Option<String> {
+        &self.service_name
+    }
 }
@@ -0,0 +1,22 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PluginConfig {
+    fuel: Option<u64>,
+    max_memory_bytes: Option<usize>,
+    max_module_bytes: Option<usize>,
+}
+
+impl PluginConfig {
+    pub fn fuel(&self) -> &Option<u64> {
+        &self.fuel
+    }
+
+    pub fn max_memory_bytes(&self) -> &Option<usize> {
+        &self.max_memory_bytes
+    }
+
+    pub fn max_module_bytes(&self) -> &Option<usize> {
+        &self.max_module_bytes
+    }
+}
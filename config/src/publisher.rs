@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+use self::{
+    amqp::PublisherAmqpConfig, kafka::PublisherKafkaConfig, mqtt::PublisherMqttConfig,
+    nats::PublisherNatsConfig,
+};
+
+pub mod amqp;
+pub mod kafka;
+pub mod mqtt;
+pub mod nats;
+
+#[derive(Deserialize)]
+pub struct PublisherConfig {
+    mqtt: Option<PublisherMqttConfig>,
+    kafka: Option<PublisherKafkaConfig>,
+    nats: Option<PublisherNatsConfig>,
+    amqp: Option<PublisherAmqpConfig>,
+}
+
+impl PublisherConfig {
+    pub fn mqtt(&self) -> &Option<PublisherMqttConfig> {
+        &self.mqtt
+    }
+
+    pub fn kafka(&self) -> &Option<PublisherKafkaConfig> {
+        &self.kafka
+    }
+
+    pub fn nats(&self) -> &Option<PublisherNatsConfig> {
+        &self.nats
+    }
+
+    pub fn amqp(&self) -> &Option<PublisherAmqpConfig> {
+        &self.amqp
+    }
+}
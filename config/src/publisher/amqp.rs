@@ -0,0 +1,27 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PublisherAmqpConfig {
+    uri: String,
+    exchange: Option<String>,
+    routing_key: Option<String>,
+    format: Option<String>,
+}
+
+impl PublisherAmqpConfig {
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    pub fn exchange(&self) -> &Option<String> {
+        &self.exchange
+    }
+
+    pub fn routing_key(&self) -> &Option<String> {
+        &self.routing_key
+    }
+
+    pub fn format(&self) -> &Option<String> {
+        &self.format
+    }
+}
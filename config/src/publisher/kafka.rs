@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PublisherKafkaConfig {
+    brokers: String,
+    client_id: Option<String>,
+    topic: Option<String>,
+    format: Option<String>,
+    properties: Option<HashMap<String, String>>,
+}
+
+impl PublisherKafkaConfig {
+    pub fn brokers(&self) -> &str {
+        &self.brokers
+    }
+
+    pub fn client_id(&self) -> &Option<String> {
+        &self.client_id
+    }
+
+    pub fn topic(&self) -> &Option<String> {
+        &self.topic
+    }
+
+    pub fn format(&self) -> &Option<String> {
+        &self.format
+    }
+
+    pub fn properties(&self) -> &Option<HashMap<String, String>> {
+        &self.properties
+    }
+}
@@ -0,0 +1,47 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PublisherMqttConfig {
+    host: String,
+    port: u16,
+    client_id: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    topic: Option<String>,
+    qos: Option<u8>,
+    retain: Option<bool>,
+}
+
+impl PublisherMqttConfig {
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> &u16 {
+        &self.port
+    }
+
+    pub fn client_id(&self) -> &Option<String> {
+        &self.client_id
+    }
+
+    pub fn username(&self) -> &Option<String> {
+        &self.username
+    }
+
+    pub fn password(&self) -> &Option<String> {
+        &self.password
+    }
+
+    pub fn topic(&self) -> &Option<String> {
+        &self.topic
+    }
+
+    pub fn qos(&self) -> &Option<u8> {
+        &self.qos
+    }
+
+    pub fn retain(&self) -> &Option<bool> {
+        &self.retain
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PublisherNatsConfig {
+    url: String,
+    client_name: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    subject: Option<String>,
+    format: Option<String>,
+}
+
+impl PublisherNatsConfig {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn client_name(&self) -> &Option<String> {
+        &self.client_name
+    }
+
+    pub fn username(&self) -> &Option<String> {
+        &self.username
+    }
+
+    pub fn password(&self) -> &Option<String> {
+        &self.password
+    }
+
+    pub fn subject(&self) -> &Option<String> {
+        &self.subject
+    }
+
+    pub fn format(&self) -> &Option<String> {
+        &self.format
+    }
+}
@@ -0,0 +1,27 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct QuotaConfig {
+    max_collections: Option<u64>,
+    max_records_per_collection: Option<u64>,
+    max_storage_bytes: Option<u64>,
+    max_tokens: Option<u64>,
+}
+
+impl QuotaConfig {
+    pub fn max_collections(&self) -> &Option<u64> {
+        &self.max_collections
+    }
+
+    pub fn max_records_per_collection(&self) -> &Option<u64> {
+        &self.max_records_per_collection
+    }
+
+    pub fn max_storage_bytes(&self) -> &Option<u64> {
+        &self.max_storage_bytes
+    }
+
+    pub fn max_tokens(&self) -> &Option<u64> {
+        &self.max_tokens
+    }
+}
@@ -0,0 +1,48 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct SchedulerConfig {
+    record_sweep: Option<String>,
+    project_purge: Option<String>,
+    auth_cleanup: Option<String>,
+    backup: Option<SchedulerBackupConfig>,
+}
+
+impl SchedulerConfig {
+    pub fn record_sweep(&self) -> &Option<String> {
+        &self.record_sweep
+    }
+
+    pub fn project_purge(&self) -> &Option<String> {
+        &self.project_purge
+    }
+
+    pub fn auth_cleanup(&self) -> &Option<String> {
+        &self.auth_cleanup
+    }
+
+    pub fn backup(&self) -> &Option<SchedulerBackupConfig> {
+        &self.backup
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SchedulerBackupConfig {
+    schedule: Option<String>,
+    dir: String,
+    keep: Option<usize>,
+}
+
+impl SchedulerBackupConfig {
+    pub fn schedule(&self) -> &Option<String> {
+        &self.schedule
+    }
+
+    pub fn dir(&self) -> &str {
+        &self.dir
+    }
+
+    pub fn keep(&self) -> &Option<usize> {
+        &self.keep
+    }
+}
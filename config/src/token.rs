@@ -3,26 +3,124 @@ use serde::Deserialize;
 #[derive(Deserialize)]
 pub struct TokenConfig {
     jwt: JwtTokenConfig,
+    totp: TotpTokenConfig,
+    webauthn: Option<WebauthnTokenConfig>,
 }
 
 impl TokenConfig {
     pub fn jwt(&self) -> &JwtTokenConfig {
         &self.jwt
     }
+
+    pub fn totp(&self) -> &TotpTokenConfig {
+        &self.totp
+    }
+
+    pub fn webauthn(&self) -> &Option<WebauthnTokenConfig> {
+        &self.webauthn
+    }
 }
 
 #[derive(Deserialize)]
 pub struct JwtTokenConfig {
-    secret: String,
+    algorithm: Option<String>,
+    secret: Option<String>,
+    private_key_path: Option<String>,
+    public_key_path: Option<String>,
+    keys: Option<Vec<JwtKeyConfig>>,
     expiry_duration: u64,
+    refresh_expiry_duration: u64,
 }
 
 impl JwtTokenConfig {
-    pub fn secret(&self) -> &str {
+    pub fn algorithm(&self) -> &Option<String> {
+        &self.algorithm
+    }
+
+    pub fn secret(&self) -> &Option<String> {
         &self.secret
     }
 
+    pub fn private_key_path(&self) -> &Option<String> {
+        &self.private_key_path
+    }
+
+    pub fn public_key_path(&self) -> &Option<String> {
+        &self.public_key_path
+    }
+
+    pub fn keys(&self) -> &Option<Vec<JwtKeyConfig>> {
+        &self.keys
+    }
+
     pub fn expiry_duration(&self) -> &u64 {
         &self.expiry_duration
     }
+
+    pub fn refresh_expiry_duration(&self) -> &u64 {
+        &self.refresh_expiry_duration
+    }
+}
+
+#[derive(Deserialize)]
+pub struct JwtKeyConfig {
+    kid: String,
+    secret: Option<String>,
+    private_key_path: Option<String>,
+    public_key_path: Option<String>,
+}
+
+impl JwtKeyConfig {
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    pub fn secret(&self) -> &Option<String> {
+        &self.secret
+    }
+
+    pub fn private_key_path(&self) -> &Option<String> {
+        &self.private_key_path
+    }
+
+    pub fn public_key_path(&self) -> &Option<String> {
+        &self.public_key_path
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TotpTokenConfig {
+    issuer: String,
+}
+
+impl TotpTokenConfig {
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+}
+
+#[derive(Deserialize)]
+pub struct WebauthnTokenConfig {
+    rp_id: String,
+    rp_origin: String,
+    rp_name: String,
+    ceremony_ttl: u32,
+}
+
+impl WebauthnTokenConfig {
+    pub fn rp_id(&self) -> &str {
+        &self.rp_id
+    }
+
+    pub fn rp_origin(&self) -> &str {
+        &self.rp_origin
+    }
+
+    pub fn rp_name(&self) -> &str {
+        &self.rp_name
+    }
+
+    pub fn ceremony_ttl(&self) -> &u32 {
+        &self.ceremony_ttl
+    }
 }
@@ -0,0 +1,447 @@
+use std::path::Path;
+
+use crate::Config;
+
+const JWT_ALGORITHMS: [&str; 7] = [
+    "HS256", "HS384", "HS512", "RS256", "RS384", "RS512", "EdDSA",
+];
+const ARGON2_ALGORITHMS: [&str; 3] = ["Argon2d", "Argon2i", "Argon2id"];
+const ARGON2_VERSIONS: [&str; 2] = ["V0x10", "V0x13"];
+const SCYLLA_CONSISTENCIES: [&str; 9] = [
+    "ANY",
+    "ONE",
+    "TWO",
+    "THREE",
+    "QUORUM",
+    "ALL",
+    "LOCAL_QUORUM",
+    "EACH_QUORUM",
+    "LOCAL_ONE",
+];
+const EVENT_FORMATS: [&str; 2] = ["json", "cbor"];
+const MIN_ACCESS_TOKEN_LENGTH: usize = 16;
+
+impl Config {
+    // Collects every problem instead of stopping at the first so they can be fixed in one pass
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if let Some(file) = self.log.file() {
+            if file.path().is_empty() {
+                problems.push("log.file.path must not be empty".to_owned());
+            }
+            if file.max_size() == &Some(0) {
+                problems.push("log.file.max_size must be greater than 0".to_owned());
+            }
+        }
+        if let Some(otlp) = self.log.otlp() {
+            if otlp.endpoint().is_empty() {
+                problems.push("log.otlp.endpoint must not be empty".to_owned());
+            }
+        }
+
+        let argon2 = self.hash.argon2();
+        if !ARGON2_ALGORITHMS.contains(&argon2.algorithm()) {
+            problems.push(format!(
+                "hash.argon2.algorithm '{}' is invalid; use one of {}",
+                argon2.algorithm(),
+                ARGON2_ALGORITHMS.join(", ")
+            ));
+        }
+        if !ARGON2_VERSIONS.contains(&argon2.version()) {
+            problems.push(format!(
+                "hash.argon2.version '{}' is invalid; use one of {}",
+                argon2.version(),
+                ARGON2_VERSIONS.join(", ")
+            ));
+        }
+        if !(4..=64).contains(&argon2.salt().len())
+            || !argon2
+                .salt()
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/')
+        {
+            problems.push(
+                "hash.argon2.salt must be 4 to 64 unpadded base64 characters (A-Z, a-z, 0-9, +, /)"
+                    .to_owned(),
+            );
+        }
+
+        self.validate_jwt(&mut problems);
+
+        if self.token.totp().issuer().is_empty() {
+            problems.push("token.totp.issuer must not be empty".to_owned());
+        }
+        if let Some(webauthn) = self.token.webauthn() {
+            if webauthn.rp_id().is_empty() {
+                problems.push("token.webauthn.rp_id must not be empty".to_owned());
+            }
+            let rp_id = webauthn.rp_id();
+            let origin_host = webauthn
+                .rp_origin()
+                .strip_prefix("https://")
+                .or_else(|| webauthn.rp_origin().strip_prefix("http://"))
+                .and_then(|origin| origin.split([':', '/']).next());
+            if !origin_host
+                .is_some_and(|host| host == rp_id || host.ends_with(&format!(".{rp_id}")))
+            {
+                problems.push(format!(
+                    "token.webauthn.rp_origin '{}' must be an http(s) origin on token.webauthn.rp_id or one of its subdomains",
+                    webauthn.rp_origin()
+                ));
+            }
+            if webauthn.ceremony_ttl() == &0 {
+                problems.push("token.webauthn.ceremony_ttl must be greater than 0".to_owned());
+            }
+        }
+
+        if self.mailer.smtp_host().is_empty() {
+            problems.push("mailer.smtp_host must not be empty".to_owned());
+        }
+        if !self.mailer.sender_email().contains('@') {
+            problems.push(format!(
+                "mailer.sender_email '{}' is not an email address",
+                self.mailer.sender_email()
+            ));
+        }
+
+        self.validate_db(&mut problems);
+
+        if self.api.rest().port().parse::<u16>().is_err() {
+            problems.push(format!(
+                "api.rest.port '{}' must be a number between 0 and 65535",
+                self.api.rest().port()
+            ));
+        }
+
+        if self.auth.access_token_length() < &MIN_ACCESS_TOKEN_LENGTH {
+            problems.push(format!(
+                "auth.access_token_length must be at least {MIN_ACCESS_TOKEN_LENGTH}"
+            ));
+        }
+        if self.auth.registration_ttl() == &0 {
+            problems.push("auth.registration_ttl must be greater than 0".to_owned());
+        }
+        if self.auth.reset_password_ttl() == &0 {
+            problems.push("auth.reset_password_ttl must be greater than 0".to_owned());
+        }
+        if let Some(operator_emails) = self.auth.operator_emails() {
+            for email in operator_emails {
+                if !email.contains('@') {
+                    problems.push(format!(
+                        "auth.operator_emails entry '{email}' is not an email address"
+                    ));
+                }
+            }
+        }
+
+        if let Some(bootstrap_admin) = self.auth.bootstrap_admin() {
+            if !bootstrap_admin.email().contains('@') {
+                problems.push(format!(
+                    "auth.bootstrap_admin.email '{}' is not an email address",
+                    bootstrap_admin.email()
+                ));
+            }
+        }
+
+        if let Some(quota) = &self.quota {
+            for (name, value) in [
+                ("max_collections", quota.max_collections()),
+                (
+                    "max_records_per_collection",
+                    quota.max_records_per_collection(),
+                ),
+                ("max_storage_bytes", quota.max_storage_bytes()),
+                ("max_tokens", quota.max_tokens()),
+            ] {
+                if value == &Some(0) {
+                    problems.push(format!("quota.{name} must be greater than 0"));
+                }
+            }
+        }
+
+        if let Some(mqtt) = self
+            .publisher
+            .as_ref()
+            .and_then(|publisher| publisher.mqtt().as_ref())
+        {
+            if mqtt.host().is_empty() {
+                problems.push("publisher.mqtt.host must not be empty".to_owned());
+            }
+            if let Some(topic) = mqtt.topic() {
+                if topic.is_empty() || topic.contains(['+', '#']) {
+                    problems.push(
+                        "publisher.mqtt.topic must not be empty or contain the wildcards '+' and '#'"
+                            .to_owned(),
+                    );
+                }
+            }
+            if let Some(qos) = mqtt.qos() {
+                if *qos > 2 {
+                    problems.push(format!("publisher.mqtt.qos must be 0, 1, or 2, got {qos}"));
+                }
+            }
+        }
+
+        if let Some(kafka) = self
+            .publisher
+            .as_ref()
+            .and_then(|publisher| publisher.kafka().as_ref())
+        {
+            if kafka.brokers().is_empty() {
+                problems.push("publisher.kafka.brokers must not be empty".to_owned());
+            }
+            if kafka.topic().as_ref().is_some_and(|topic| topic.is_empty()) {
+                problems.push("publisher.kafka.topic must not be empty".to_owned());
+            }
+            if let Some(format) = kafka.format() {
+                if !EVENT_FORMATS.contains(&format.as_str()) {
+                    problems.push(format!(
+                        "publisher.kafka.format '{format}' is invalid; use one of {}",
+                        EVENT_FORMATS.join(", ")
+                    ));
+                }
+            }
+        }
+
+        if let Some(nats) = self
+            .publisher
+            .as_ref()
+            .and_then(|publisher| publisher.nats().as_ref())
+        {
+            if nats.url().is_empty() {
+                problems.push("publisher.nats.url must not be empty".to_owned());
+            }
+            if let Some(subject) = nats.subject() {
+                if subject.is_empty() || subject.contains(['*', '>', ' ']) {
+                    problems.push(
+                        "publisher.nats.subject must not be empty or contain spaces and the wildcards '*' and '>'"
+                            .to_owned(),
+                    );
+                }
+            }
+            if let Some(format) = nats.format() {
+                if !EVENT_FORMATS.contains(&format.as_str()) {
+                    problems.push(format!(
+                        "publisher.nats.format '{format}' is invalid; use one of {}",
+                        EVENT_FORMATS.join(", ")
+                    ));
+                }
+            }
+        }
+
+        if let Some(amqp) = self
+            .publisher
+            .as_ref()
+            .and_then(|publisher| publisher.amqp().as_ref())
+        {
+            if amqp.uri().is_empty() {
+                problems.push("publisher.amqp.uri must not be empty".to_owned());
+            }
+            if let Some(format) = amqp.format() {
+                if !EVENT_FORMATS.contains(&format.as_str()) {
+                    problems.push(format!(
+                        "publisher.amqp.format '{format}' is invalid; use one of {}",
+                        EVENT_FORMATS.join(", ")
+                    ));
+                }
+            }
+        }
+
+        if let Some(backup) = self
+            .scheduler
+            .as_ref()
+            .and_then(|scheduler| scheduler.backup().as_ref())
+        {
+            if backup.dir().is_empty() {
+                problems.push("scheduler.backup.dir must not be empty".to_owned());
+            }
+            if backup.keep() == &Some(0) {
+                problems.push("scheduler.backup.keep must be greater than 0".to_owned());
+            }
+        }
+
+        if let Some(plugin) = &self.plugin {
+            if plugin.fuel() == &Some(0) {
+                problems.push("plugin.fuel must be greater than 0".to_owned());
+            }
+            for (name, value) in [
+                ("max_memory_bytes", plugin.max_memory_bytes()),
+                ("max_module_bytes", plugin.max_module_bytes()),
+            ] {
+                if value == &Some(0) {
+                    problems.push(format!("plugin.{name} must be greater than 0"));
+                }
+            }
+        }
+
+        problems
+    }
+
+    fn validate_jwt(&self, problems: &mut Vec<String>) {
+        let jwt = self.token.jwt();
+        let algorithm = jwt.algorithm().as_deref().unwrap_or("HS256");
+        if !JWT_ALGORITHMS.contains(&algorithm) {
+            problems.push(format!(
+                "token.jwt.algorithm '{algorithm}' is invalid; use one of {}",
+                JWT_ALGORITHMS.join(", ")
+            ));
+            return;
+        }
+
+        let mut keys = Vec::new();
+        match jwt.keys() {
+            Some(jwt_keys) => {
+                if jwt_keys.is_empty() {
+                    problems.push("token.jwt.keys must contain at least one key".to_owned());
+                }
+                for (idx, key) in jwt_keys.iter().enumerate() {
+                    if jwt_keys[..idx].iter().any(|other| other.kid() == key.kid()) {
+                        problems.push(format!("token.jwt.keys kid '{}' is duplicated", key.kid()));
+                    }
+                    keys.push((
+                        format!("token.jwt.keys[{idx}]"),
+                        key.secret(),
+                        key.private_key_path(),
+                        key.public_key_path(),
+                    ));
+                }
+            }
+            None => keys.push((
+                "token.jwt".to_owned(),
+                jwt.secret(),
+                jwt.private_key_path(),
+                jwt.public_key_path(),
+            )),
+        }
+
+        for (name, secret, private_key_path, public_key_path) in keys {
+            if algorithm.starts_with("HS") {
+                match secret {
+                    Some(secret) if !secret.is_empty() => (),
+                    _ => problems.push(format!(
+                        "{name}.secret must be set when token.jwt.algorithm is {algorithm}"
+                    )),
+                }
+            } else {
+                for (field, path) in [
+                    ("private_key_path", private_key_path),
+                    ("public_key_path", public_key_path),
+                ] {
+                    match path {
+                        Some(path) if Path::new(path).is_file() => (),
+                        Some(path) => problems.push(format!(
+                            "{name}.{field} '{path}' does not exist or is not a file"
+                        )),
+                        None => problems.push(format!(
+                            "{name}.{field} must be set when token.jwt.algorithm is {algorithm}"
+                        )),
+                    }
+                }
+            }
+        }
+
+        if jwt.expiry_duration() == &0 {
+            problems.push("token.jwt.expiry_duration must be greater than 0".to_owned());
+        }
+        if jwt.refresh_expiry_duration() <= jwt.expiry_duration() {
+            problems.push(
+                "token.jwt.refresh_expiry_duration must be greater than token.jwt.expiry_duration"
+                    .to_owned(),
+            );
+        }
+    }
+
+    fn validate_db(&self, problems: &mut Vec<String>) {
+        let configured = [
+            ("scylla", self.db.scylla().is_some()),
+            ("postgres", self.db.postgres().is_some()),
+            ("mysql", self.db.mysql().is_some()),
+            ("sqlite", self.db.sqlite().is_some()),
+        ]
+        .iter()
+        .filter(|(_, is_some)| *is_some)
+        .map(|(name, _)| format!("db.{name}"))
+        .collect::<Vec<_>>();
+        match configured.len() {
+            0 => problems.push(
+                "No database is configured; set one of db.scylla, db.postgres, db.mysql, or db.sqlite"
+                    .to_owned(),
+            ),
+            1 => (),
+            _ => problems.push(format!(
+                "Only one database can be configured, but {} are set",
+                configured.join(", ")
+            )),
+        }
+
+        if let Some(scylla) = self.db.scylla() {
+            if scylla.port().parse::<u16>().is_err() {
+                problems.push(format!(
+                    "db.scylla.port '{}' is not a valid port",
+                    scylla.port()
+                ));
+            }
+            if scylla.replication_factor() < &1 {
+                problems.push("db.scylla.replication_factor must be at least 1".to_owned());
+            }
+            if scylla.prepared_statement_cache_size() == &0 {
+                problems.push(
+                    "db.scylla.prepared_statement_cache_size must be greater than 0".to_owned(),
+                );
+            }
+            for (field, consistency) in [
+                ("read_consistency", scylla.read_consistency()),
+                ("write_consistency", scylla.write_consistency()),
+            ] {
+                if let Some(consistency) = consistency {
+                    if !SCYLLA_CONSISTENCIES.contains(&consistency.to_uppercase().as_str()) {
+                        problems.push(format!(
+                            "db.scylla.{field} '{consistency}' is invalid; use one of {}",
+                            SCYLLA_CONSISTENCIES.join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some(postgres) = self.db.postgres() {
+            if postgres.port().parse::<u16>().is_err() {
+                problems.push(format!(
+                    "db.postgres.port '{}' is not a valid port",
+                    postgres.port()
+                ));
+            }
+            if postgres.max_connections() == &0 {
+                problems.push("db.postgres.max_connections must be greater than 0".to_owned());
+            }
+        }
+        if let Some(mysql) = self.db.mysql() {
+            if mysql.port().parse::<u16>().is_err() {
+                problems.push(format!(
+                    "db.mysql.port '{}' is not a valid port",
+                    mysql.port()
+                ));
+            }
+            if mysql.max_connections() == &0 {
+                problems.push("db.mysql.max_connections must be greater than 0".to_owned());
+            }
+        }
+        if let Some(sqlite) = self.db.sqlite() {
+            if sqlite.path().is_empty() {
+                problems.push("db.sqlite.path must not be empty".to_owned());
+            }
+            if sqlite.max_connections() == &0 {
+                problems.push("db.sqlite.max_connections must be greater than 0".to_owned());
+            }
+        }
+        if self.db.record_sweep_interval() == &Some(0) {
+            problems.push("db.record_sweep_interval must be greater than 0".to_owned());
+        }
+        if self.db.usage_meter_interval() == &Some(0) {
+            problems.push("db.usage_meter_interval must be greater than 0".to_owned());
+        }
+        if self.db.project_purge_interval() == &Some(0) {
+            problems.push("db.project_purge_interval must be greater than 0".to_owned());
+        }
+    }
+}
@@ -1,11 +1,14 @@
-use anyhow::Result;
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
 use chrono::{DateTime, Utc};
 use hb_db_mysql::{
     db::MysqlDb,
     model::admin::AdminModel as AdminMysqlModel,
     query::admin::{
         DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
-        SELECT_BY_EMAIL as MYSQL_SELECT_BY_EMAIL, UPDATE as MYSQL_UPDATE,
+        SELECT_BY_EMAIL as MYSQL_SELECT_BY_EMAIL, SELECT_MANY as MYSQL_SELECT_MANY,
+        UPDATE as MYSQL_UPDATE,
     },
 };
 use hb_db_postgresql::{
@@ -13,7 +16,8 @@ use hb_db_postgresql::{
     model::admin::AdminModel as AdminPostgresModel,
     query::admin::{
         DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
-        SELECT_BY_EMAIL as POSTGRES_SELECT_BY_EMAIL, UPDATE as POSTGRES_UPDATE,
+        SELECT_BY_EMAIL as POSTGRES_SELECT_BY_EMAIL, SELECT_MANY as POSTGRES_SELECT_MANY,
+        UPDATE as POSTGRES_UPDATE,
     },
 };
 use hb_db_scylladb::{
@@ -21,7 +25,8 @@ use hb_db_scylladb::{
     model::admin::AdminModel as AdminScyllaModel,
     query::admin::{
         DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
-        SELECT_BY_EMAIL as SCYLLA_SELECT_BY_EMAIL, UPDATE as SCYLLA_UPDATE,
+        SELECT_BY_EMAIL as SCYLLA_SELECT_BY_EMAIL, SELECT_MANY as SCYLLA_SELECT_MANY,
+        UPDATE as SCYLLA_UPDATE,
     },
 };
 use hb_db_sqlite::{
@@ -29,20 +34,32 @@ use hb_db_sqlite::{
     model::admin::AdminModel as AdminSqliteModel,
     query::admin::{
         DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
-        SELECT_BY_EMAIL as SQLITE_SELECT_BY_EMAIL, UPDATE as SQLITE_UPDATE,
+        SELECT_BY_EMAIL as SQLITE_SELECT_BY_EMAIL, SELECT_MANY as SQLITE_SELECT_MANY,
+        UPDATE as SQLITE_UPDATE,
     },
 };
-use scylla::frame::value::CqlTimestamp as ScyllaCqlTimestamp;
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
 use crate::{util::conversion, Db};
 
+#[derive(Serialize, Deserialize)]
 pub struct AdminDao {
     id: Uuid,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     email: String,
     password_hash: String,
+    mfa_secret: Option<String>,
+    mfa_enabled: bool,
+    role: AdminRole,
+    #[serde(default)]
+    passkeys: Vec<Value>,
 }
 
 impl AdminDao {
@@ -54,6 +71,10 @@ impl AdminDao {
             updated_at: now,
             email: email.to_owned(),
             password_hash: password_hash.to_owned(),
+            mfa_secret: None,
+            mfa_enabled: false,
+            role: AdminRole::Owner,
+            passkeys: Vec::new(),
         }
     }
 
@@ -77,6 +98,23 @@ impl AdminDao {
         &self.password_hash
     }
 
+    pub fn mfa_secret(&self) -> &Option<String> {
+        &self.mfa_secret
+    }
+
+    pub fn mfa_enabled(&self) -> &bool {
+        &self.mfa_enabled
+    }
+
+    pub fn role(&self) -> &AdminRole {
+        &self.role
+    }
+
+    // Registered WebAuthn credentials, kept in the serialized form the authenticator library uses
+    pub fn passkeys(&self) -> &Vec<Value> {
+        &self.passkeys
+    }
+
     pub fn set_email(&mut self, email: &str) {
         self.email = email.to_owned()
     }
@@ -85,6 +123,22 @@ impl AdminDao {
         self.password_hash = password_hash.to_owned();
     }
 
+    pub fn set_mfa_secret(&mut self, mfa_secret: &Option<String>) {
+        self.mfa_secret.clone_from(mfa_secret);
+    }
+
+    pub fn set_mfa_enabled(&mut self, mfa_enabled: &bool) {
+        self.mfa_enabled = *mfa_enabled;
+    }
+
+    pub fn set_role(&mut self, role: &AdminRole) {
+        self.role = *role;
+    }
+
+    pub fn set_passkeys(&mut self, passkeys: &[Value]) {
+        self.passkeys = passkeys.to_vec();
+    }
+
     pub async fn db_insert(&self, db: &Db) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
@@ -128,6 +182,43 @@ impl AdminDao {
         }
     }
 
+    pub async fn db_select_many(db: &Db) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut admins_data = Vec::new();
+                let admins = Self::scylladb_select_many(db).await?;
+                for admin in admins {
+                    admins_data.push(Self::from_scylladb_model(&admin?)?);
+                }
+                Ok(admins_data)
+            }
+            Db::PostgresqlDb(db) => {
+                let admins = Self::postgresdb_select_many(db).await?;
+                let mut admins_data = Vec::with_capacity(admins.len());
+                for admin in &admins {
+                    admins_data.push(Self::from_postgresdb_model(admin)?);
+                }
+                Ok(admins_data)
+            }
+            Db::MysqlDb(db) => {
+                let admins = Self::mysqldb_select_many(db).await?;
+                let mut admins_data = Vec::with_capacity(admins.len());
+                for admin in &admins {
+                    admins_data.push(Self::from_mysqldb_model(admin)?);
+                }
+                Ok(admins_data)
+            }
+            Db::SqliteDb(db) => {
+                let admins = Self::sqlitedb_select_many(db).await?;
+                let mut admins_data = Vec::with_capacity(admins.len());
+                for admin in &admins {
+                    admins_data.push(Self::from_sqlitedb_model(admin)?);
+                }
+                Ok(admins_data)
+            }
+        }
+    }
+
     pub async fn db_update(&mut self, db: &Db) -> Result<()> {
         self.updated_at = Utc::now();
         match db {
@@ -148,7 +239,7 @@ impl AdminDao {
     }
 
     async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
-        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()?).await?;
         Ok(())
     }
 
@@ -166,6 +257,13 @@ impl AdminDao {
             .first_row_typed::<AdminScyllaModel>()?)
     }
 
+    async fn scylladb_select_many(db: &ScyllaDb) -> Result<ScyllaTypedRowIter<AdminScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY, &[])
+            .await?
+            .rows_typed::<AdminScyllaModel>()?)
+    }
+
     async fn scylladb_update(&self, db: &ScyllaDb) -> Result<()> {
         db.execute(
             SCYLLA_UPDATE,
@@ -173,6 +271,10 @@ impl AdminDao {
                 &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
                 &self.email,
                 &self.password_hash,
+                &self.mfa_secret,
+                &self.mfa_enabled,
+                self.role.to_str(),
+                &self.passkeys_string()?,
                 &self.id,
             ),
         )
@@ -192,7 +294,11 @@ impl AdminDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.email)
-                .bind(&self.password_hash),
+                .bind(&self.password_hash)
+                .bind(&self.mfa_secret)
+                .bind(self.mfa_enabled)
+                .bind(self.role.to_str())
+                .bind(self.passkeys_string()?),
         )
         .await?;
         Ok(())
@@ -213,12 +319,20 @@ impl AdminDao {
             .await?)
     }
 
+    async fn postgresdb_select_many(db: &PostgresDb) -> Result<Vec<AdminPostgresModel>> {
+        Ok(db.fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY)).await?)
+    }
+
     async fn postgresdb_update(&self, db: &PostgresDb) -> Result<()> {
         db.execute(
             sqlx::query(POSTGRES_UPDATE)
                 .bind(&self.updated_at)
                 .bind(&self.email)
                 .bind(&self.password_hash)
+                .bind(&self.mfa_secret)
+                .bind(self.mfa_enabled)
+                .bind(self.role.to_str())
+                .bind(self.passkeys_string()?)
                 .bind(&self.id),
         )
         .await?;
@@ -237,7 +351,11 @@ impl AdminDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.email)
-                .bind(&self.password_hash),
+                .bind(&self.password_hash)
+                .bind(&self.mfa_secret)
+                .bind(self.mfa_enabled)
+                .bind(self.role.to_str())
+                .bind(self.passkeys_string()?),
         )
         .await?;
         Ok(())
@@ -253,12 +371,20 @@ impl AdminDao {
             .await?)
     }
 
+    async fn mysqldb_select_many(db: &MysqlDb) -> Result<Vec<AdminMysqlModel>> {
+        Ok(db.fetch_all(sqlx::query_as(MYSQL_SELECT_MANY)).await?)
+    }
+
     async fn mysqldb_update(&self, db: &MysqlDb) -> Result<()> {
         db.execute(
             sqlx::query(MYSQL_UPDATE)
                 .bind(&self.updated_at)
                 .bind(&self.email)
                 .bind(&self.password_hash)
+                .bind(&self.mfa_secret)
+                .bind(self.mfa_enabled)
+                .bind(self.role.to_str())
+                .bind(self.passkeys_string()?)
                 .bind(&self.id),
         )
         .await?;
@@ -277,7 +403,11 @@ impl AdminDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.email)
-                .bind(&self.password_hash),
+                .bind(&self.password_hash)
+                .bind(&self.mfa_secret)
+                .bind(self.mfa_enabled)
+                .bind(self.role.to_str())
+                .bind(self.passkeys_string()?),
         )
         .await?;
         Ok(())
@@ -293,12 +423,20 @@ impl AdminDao {
             .await?)
     }
 
+    async fn sqlitedb_select_many(db: &SqliteDb) -> Result<Vec<AdminSqliteModel>> {
+        Ok(db.fetch_all(sqlx::query_as(SQLITE_SELECT_MANY)).await?)
+    }
+
     async fn sqlitedb_update(&self, db: &SqliteDb) -> Result<()> {
         db.execute(
             sqlx::query(SQLITE_UPDATE)
                 .bind(&self.updated_at)
                 .bind(&self.email)
                 .bind(&self.password_hash)
+                .bind(&self.mfa_secret)
+                .bind(self.mfa_enabled)
+                .bind(self.role.to_str())
+                .bind(self.passkeys_string()?)
                 .bind(&self.id),
         )
         .await?;
@@ -317,17 +455,25 @@ impl AdminDao {
             updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
             email: model.email().to_owned(),
             password_hash: model.password_hash().to_owned(),
+            mfa_secret: model.mfa_secret().clone(),
+            mfa_enabled: *model.mfa_enabled(),
+            role: model.role().parse()?,
+            passkeys: Self::passkeys_from_string(model.passkeys())?,
         })
     }
 
-    fn to_scylladb_model(&self) -> AdminScyllaModel {
-        AdminScyllaModel::new(
+    fn to_scylladb_model(&self) -> Result<AdminScyllaModel> {
+        Ok(AdminScyllaModel::new(
             &self.id,
             &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
             &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
             &self.email,
             &self.password_hash,
-        )
+            &self.mfa_secret,
+            &self.mfa_enabled,
+            self.role.to_str(),
+            &self.passkeys_string()?,
+        ))
     }
 
     fn from_postgresdb_model(model: &AdminPostgresModel) -> Result<Self> {
@@ -337,6 +483,10 @@ impl AdminDao {
             updated_at: *model.updated_at(),
             email: model.email().to_owned(),
             password_hash: model.password_hash().to_owned(),
+            mfa_secret: model.mfa_secret().clone(),
+            mfa_enabled: *model.mfa_enabled(),
+            role: model.role().parse()?,
+            passkeys: Self::passkeys_from_string(model.passkeys())?,
         })
     }
 
@@ -347,6 +497,10 @@ impl AdminDao {
             updated_at: *model.updated_at(),
             email: model.email().to_owned(),
             password_hash: model.password_hash().to_owned(),
+            mfa_secret: model.mfa_secret().clone(),
+            mfa_enabled: *model.mfa_enabled(),
+            role: model.role().parse()?,
+            passkeys: Self::passkeys_from_string(model.passkeys())?,
         })
     }
 
@@ -357,6 +511,111 @@ impl AdminDao {
             updated_at: *model.updated_at(),
             email: model.email().to_owned(),
             password_hash: model.password_hash().to_owned(),
+            mfa_secret: model.mfa_secret().clone(),
+            mfa_enabled: *model.mfa_enabled(),
+            role: model.role().parse()?,
+            passkeys: Self::passkeys_from_string(model.passkeys())?,
         })
     }
+
+    fn passkeys_string(&self) -> Result<Option<String>> {
+        match self.passkeys.is_empty() {
+            true => Ok(None),
+            false => Ok(Some(serde_json::to_string(&self.passkeys)?)),
+        }
+    }
+
+    fn passkeys_from_string(passkeys: &Option<String>) -> Result<Vec<Value>> {
+        match passkeys {
+            Some(passkeys) => Ok(serde_json::from_str(passkeys)?),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, PartialOrd, Clone, Copy)]
+pub enum AdminRole {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl FromStr for AdminRole {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self> {
+        match str {
+            "viewer" => Ok(Self::Viewer),
+            "editor" => Ok(Self::Editor),
+            "owner" => Ok(Self::Owner),
+            _ => Err(Error::msg(format!("Unknown admin role '{str}'"))),
+        }
+    }
+}
+
+impl AdminRole {
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Editor => "editor",
+            Self::Owner => "owner",
+        }
+    }
+
+    pub fn can_write_records(&self) -> bool {
+        *self >= Self::Editor
+    }
+
+    pub fn can_write_schemas(&self) -> bool {
+        *self >= Self::Editor
+    }
+
+    pub fn can_write_tokens(&self) -> bool {
+        *self >= Self::Editor
+    }
+
+    pub fn can_write_projects(&self) -> bool {
+        *self == Self::Owner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdminRole;
+
+    #[test]
+    fn role_parses_its_own_name() {
+        for role in [AdminRole::Viewer, AdminRole::Editor, AdminRole::Owner] {
+            assert!(role.to_str().parse::<AdminRole>().unwrap() == role);
+        }
+        assert!("admin".parse::<AdminRole>().is_err());
+        assert!("Owner".parse::<AdminRole>().is_err());
+    }
+
+    #[test]
+    fn viewer_is_read_only() {
+        let role = AdminRole::Viewer;
+        assert!(!role.can_write_records());
+        assert!(!role.can_write_schemas());
+        assert!(!role.can_write_tokens());
+        assert!(!role.can_write_projects());
+    }
+
+    #[test]
+    fn editor_writes_data_but_not_the_project() {
+        let role = AdminRole::Editor;
+        assert!(role.can_write_records());
+        assert!(role.can_write_schemas());
+        assert!(role.can_write_tokens());
+        assert!(!role.can_write_projects());
+    }
+
+    #[test]
+    fn owner_writes_everything() {
+        let role = AdminRole::Owner;
+        assert!(role.can_write_records());
+        assert!(role.can_write_schemas());
+        assert!(role.can_write_tokens());
+        assert!(role.can_write_projects());
+    }
 }
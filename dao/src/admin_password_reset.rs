@@ -1,27 +1,45 @@
-use anyhow::Result;
-use chrono::{DateTime, Utc};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Duration, Utc};
 use hb_db_mysql::{
     db::MysqlDb,
     model::admin_password_reset::AdminPasswordResetModel as AdminPasswordResetMysqlModel,
-    query::admin_password_reset::{INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT},
+    query::admin_password_reset::{
+        DELETE_EXPIRED as MYSQL_DELETE_EXPIRED,
+        DELETE_MANY_BY_ADMIN_ID as MYSQL_DELETE_MANY_BY_ADMIN_ID, INSERT as MYSQL_INSERT,
+        SELECT as MYSQL_SELECT, SELECT_MANY_BY_ADMIN_ID as MYSQL_SELECT_MANY_BY_ADMIN_ID,
+    },
 };
 use hb_db_postgresql::{
     db::PostgresDb,
     model::admin_password_reset::AdminPasswordResetModel as AdminPasswordResetPostgresModel,
-    query::admin_password_reset::{INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT},
+    query::admin_password_reset::{
+        DELETE_EXPIRED as POSTGRES_DELETE_EXPIRED,
+        DELETE_MANY_BY_ADMIN_ID as POSTGRES_DELETE_MANY_BY_ADMIN_ID, INSERT as POSTGRES_INSERT,
+        SELECT as POSTGRES_SELECT, SELECT_MANY_BY_ADMIN_ID as POSTGRES_SELECT_MANY_BY_ADMIN_ID,
+    },
 };
 use hb_db_scylladb::{
     db::ScyllaDb,
     model::admin_password_reset::AdminPasswordResetModel as AdminPasswordResetScyllaModel,
-    query::admin_password_reset::{INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT},
+    query::admin_password_reset::{
+        DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as SCYLLA_SELECT_MANY_BY_ADMIN_ID,
+    },
 };
 use hb_db_sqlite::{
     db::SqliteDb,
     model::admin_password_reset::AdminPasswordResetModel as AdminPasswordResetSqliteModel,
-    query::admin_password_reset::{INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT},
+    query::admin_password_reset::{
+        DELETE_EXPIRED as SQLITE_DELETE_EXPIRED,
+        DELETE_MANY_BY_ADMIN_ID as SQLITE_DELETE_MANY_BY_ADMIN_ID, INSERT as SQLITE_INSERT,
+        SELECT as SQLITE_SELECT, SELECT_MANY_BY_ADMIN_ID as SQLITE_SELECT_MANY_BY_ADMIN_ID,
+    },
 };
 use rand::{thread_rng, Rng};
-use scylla::frame::value::CqlTimestamp as ScyllaCqlTimestamp;
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
 use uuid::Uuid;
 
 use crate::{util::conversion, Db};
@@ -32,10 +50,11 @@ pub struct AdminPasswordResetDao {
     updated_at: DateTime<Utc>,
     admin_id: Uuid,
     code: String,
+    expired_at: DateTime<Utc>,
 }
 
 impl AdminPasswordResetDao {
-    pub fn new(admin_id: &Uuid) -> Self {
+    pub fn new(admin_id: &Uuid, expiry_duration: &u32) -> Self {
         let now = Utc::now();
         Self {
             id: Uuid::now_v7(),
@@ -43,6 +62,7 @@ impl AdminPasswordResetDao {
             updated_at: now,
             admin_id: *admin_id,
             code: thread_rng().gen_range(100000..=999999).to_string(),
+            expired_at: now + Duration::seconds(i64::from(*expiry_duration)),
         }
     }
 
@@ -66,6 +86,10 @@ impl AdminPasswordResetDao {
         &self.code
     }
 
+    pub fn expired_at(&self) -> &DateTime<Utc> {
+        &self.expired_at
+    }
+
     pub async fn db_insert(&self, db: &Db) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
@@ -76,19 +100,71 @@ impl AdminPasswordResetDao {
     }
 
     pub async fn db_select(db: &Db, id: &Uuid) -> Result<Self> {
+        let data = match db {
+            Db::ScyllaDb(db) => Self::from_scylladb_model(&Self::scylladb_select(db, id).await?)?,
+            Db::PostgresqlDb(db) => {
+                Self::from_postgresdb_model(&Self::postgresdb_select(db, id).await?)
+            }
+            Db::MysqlDb(db) => Self::from_mysqldb_model(&Self::mysqldb_select(db, id).await?),
+            Db::SqliteDb(db) => Self::from_sqlitedb_model(&Self::sqlitedb_select(db, id).await?),
+        };
+        if data.expired_at <= Utc::now() {
+            return Err(Error::msg("Password reset code has expired"));
+        }
+        Ok(data)
+    }
+
+    pub async fn db_select_many_by_admin_id(db: &Db, admin_id: &Uuid) -> Result<Vec<Self>> {
         match db {
-            Db::ScyllaDb(db) => Ok(Self::from_scylladb_model(
-                &Self::scylladb_select(db, id).await?,
-            )?),
-            Db::PostgresqlDb(db) => Ok(Self::from_postgresdb_model(
-                &Self::postgresdb_select(db, id).await?,
-            )?),
-            Db::MysqlDb(db) => Ok(Self::from_mysqldb_model(
-                &Self::mysqldb_select(db, id).await?,
-            )?),
-            Db::SqliteDb(db) => Ok(Self::from_sqlitedb_model(
-                &Self::sqlitedb_select(db, id).await?,
-            )?),
+            Db::ScyllaDb(db) => {
+                let mut password_resets_data = Vec::new();
+                let password_resets = Self::scylladb_select_many_by_admin_id(db, admin_id).await?;
+                for password_reset in password_resets {
+                    password_resets_data.push(Self::from_scylladb_model(&password_reset?)?);
+                }
+                Ok(password_resets_data)
+            }
+            Db::PostgresqlDb(db) => Ok(Self::postgresdb_select_many_by_admin_id(db, admin_id)
+                .await?
+                .iter()
+                .map(Self::from_postgresdb_model)
+                .collect()),
+            Db::MysqlDb(db) => Ok(Self::mysqldb_select_many_by_admin_id(db, admin_id)
+                .await?
+                .iter()
+                .map(Self::from_mysqldb_model)
+                .collect()),
+            Db::SqliteDb(db) => Ok(Self::sqlitedb_select_many_by_admin_id(db, admin_id)
+                .await?
+                .iter()
+                .map(Self::from_sqlitedb_model)
+                .collect()),
+        }
+    }
+
+    // Invalidates every code issued to the admin, whether used, pending, or expired
+    pub async fn db_delete_many_by_admin_id(db: &Db, admin_id: &Uuid) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let password_resets = Self::scylladb_select_many_by_admin_id(db, admin_id).await?;
+                for password_reset in password_resets {
+                    Self::scylladb_delete(db, password_reset?.id()).await?;
+                }
+                Ok(())
+            }
+            Db::PostgresqlDb(db) => Self::postgresdb_delete_many_by_admin_id(db, admin_id).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete_many_by_admin_id(db, admin_id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete_many_by_admin_id(db, admin_id).await,
+        }
+    }
+
+    pub async fn db_delete_expired(db: &Db) -> Result<u64> {
+        match db {
+            // ScyllaDB expires password resets itself through the table's default TTL
+            Db::ScyllaDb(_) => Ok(0),
+            Db::PostgresqlDb(db) => Self::postgresdb_delete_expired(db).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete_expired(db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete_expired(db).await,
         }
     }
 
@@ -104,6 +180,21 @@ impl AdminPasswordResetDao {
             .first_row_typed::<AdminPasswordResetScyllaModel>()?)
     }
 
+    async fn scylladb_select_many_by_admin_id(
+        db: &ScyllaDb,
+        admin_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<AdminPasswordResetScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_ADMIN_ID, [admin_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn scylladb_delete(db: &ScyllaDb, id: &Uuid) -> Result<()> {
+        db.execute(SCYLLA_DELETE, [id].as_ref()).await?;
+        Ok(())
+    }
+
     async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
         db.execute(
             sqlx::query(POSTGRES_INSERT)
@@ -111,7 +202,8 @@ impl AdminPasswordResetDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.admin_id)
-                .bind(&self.code),
+                .bind(&self.code)
+                .bind(&self.expired_at),
         )
         .await?;
         Ok(())
@@ -122,17 +214,32 @@ impl AdminPasswordResetDao {
         id: &Uuid,
     ) -> Result<AdminPasswordResetPostgresModel> {
         Ok(db
-            .fetch_one(sqlx::query_as(POSTGRES_SELECT).bind(id).bind(&{
-                let now = Utc::now();
-                DateTime::from_timestamp(
-                    now.timestamp() - db.table_reset_password_ttl(),
-                    now.timestamp_subsec_nanos(),
-                )
-                .unwrap()
-            }))
+            .fetch_one(sqlx::query_as(POSTGRES_SELECT).bind(id))
+            .await?)
+    }
+
+    async fn postgresdb_select_many_by_admin_id(
+        db: &PostgresDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AdminPasswordResetPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
             .await?)
     }
 
+    async fn postgresdb_delete_many_by_admin_id(db: &PostgresDb, admin_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(POSTGRES_DELETE_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_delete_expired(db: &PostgresDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(POSTGRES_DELETE_EXPIRED).bind(&Utc::now()))
+            .await?
+            .rows_affected())
+    }
+
     async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
         db.execute(
             sqlx::query(MYSQL_INSERT)
@@ -140,25 +247,39 @@ impl AdminPasswordResetDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.admin_id)
-                .bind(&self.code),
+                .bind(&self.code)
+                .bind(&self.expired_at),
         )
         .await?;
         Ok(())
     }
 
     async fn mysqldb_select(db: &MysqlDb, id: &Uuid) -> Result<AdminPasswordResetMysqlModel> {
+        Ok(db.fetch_one(sqlx::query_as(MYSQL_SELECT).bind(id)).await?)
+    }
+
+    async fn mysqldb_select_many_by_admin_id(
+        db: &MysqlDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AdminPasswordResetMysqlModel>> {
         Ok(db
-            .fetch_one(sqlx::query_as(MYSQL_SELECT).bind(id).bind(&{
-                let now = Utc::now();
-                DateTime::from_timestamp(
-                    now.timestamp() - db.table_reset_password_ttl(),
-                    now.timestamp_subsec_nanos(),
-                )
-                .unwrap()
-            }))
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
             .await?)
     }
 
+    async fn mysqldb_delete_many_by_admin_id(db: &MysqlDb, admin_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(MYSQL_DELETE_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_delete_expired(db: &MysqlDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(MYSQL_DELETE_EXPIRED).bind(&Utc::now()))
+            .await?
+            .rows_affected())
+    }
+
     async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
         db.execute(
             sqlx::query(SQLITE_INSERT)
@@ -166,25 +287,39 @@ impl AdminPasswordResetDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.admin_id)
-                .bind(&self.code),
+                .bind(&self.code)
+                .bind(&self.expired_at),
         )
         .await?;
         Ok(())
     }
 
     async fn sqlitedb_select(db: &SqliteDb, id: &Uuid) -> Result<AdminPasswordResetSqliteModel> {
+        Ok(db.fetch_one(sqlx::query_as(SQLITE_SELECT).bind(id)).await?)
+    }
+
+    async fn sqlitedb_select_many_by_admin_id(
+        db: &SqliteDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AdminPasswordResetSqliteModel>> {
         Ok(db
-            .fetch_one(sqlx::query_as(SQLITE_SELECT).bind(id).bind(&{
-                let now = Utc::now();
-                DateTime::from_timestamp(
-                    now.timestamp() - db.table_reset_password_ttl(),
-                    now.timestamp_subsec_nanos(),
-                )
-                .unwrap()
-            }))
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
             .await?)
     }
 
+    async fn sqlitedb_delete_many_by_admin_id(db: &SqliteDb, admin_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(SQLITE_DELETE_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_delete_expired(db: &SqliteDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(SQLITE_DELETE_EXPIRED).bind(&Utc::now()))
+            .await?
+            .rows_affected())
+    }
+
     fn from_scylladb_model(model: &AdminPasswordResetScyllaModel) -> Result<Self> {
         Ok(Self {
             id: *model.id(),
@@ -192,6 +327,7 @@ impl AdminPasswordResetDao {
             updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
             admin_id: *model.admin_id(),
             code: model.code().to_owned(),
+            expired_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.expired_at())?,
         })
     }
 
@@ -202,36 +338,126 @@ impl AdminPasswordResetDao {
             &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
             &self.admin_id,
             &self.code,
+            &ScyllaCqlTimestamp(self.expired_at.timestamp_millis()),
         )
     }
 
-    fn from_postgresdb_model(model: &AdminPasswordResetPostgresModel) -> Result<Self> {
-        Ok(Self {
+    fn from_postgresdb_model(model: &AdminPasswordResetPostgresModel) -> Self {
+        Self {
             id: *model.id(),
             created_at: *model.created_at(),
             updated_at: *model.updated_at(),
             admin_id: *model.admin_id(),
             code: model.code().to_owned(),
-        })
+            expired_at: *model.expired_at(),
+        }
     }
 
-    fn from_mysqldb_model(model: &AdminPasswordResetMysqlModel) -> Result<Self> {
-        Ok(Self {
+    fn from_mysqldb_model(model: &AdminPasswordResetMysqlModel) -> Self {
+        Self {
             id: *model.id(),
             created_at: *model.created_at(),
             updated_at: *model.updated_at(),
             admin_id: *model.admin_id(),
             code: model.code().to_owned(),
-        })
+            expired_at: *model.expired_at(),
+        }
     }
 
-    fn from_sqlitedb_model(model: &AdminPasswordResetSqliteModel) -> Result<Self> {
-        Ok(Self {
+    fn from_sqlitedb_model(model: &AdminPasswordResetSqliteModel) -> Self {
+        Self {
             id: *model.id(),
             created_at: *model.created_at(),
             updated_at: *model.updated_at(),
             admin_id: *model.admin_id(),
             code: model.code().to_owned(),
-        })
+            expired_at: *model.expired_at(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hb_db_sqlite::db::SqliteDb;
+    use uuid::Uuid;
+
+    use super::AdminPasswordResetDao;
+    use crate::Db;
+
+    async fn db() -> Db {
+        Db::SqliteDb(SqliteDb::new(":memory:", &1, &600, &600, &None).await)
+    }
+
+    #[test]
+    fn new_issues_a_six_digit_code_with_an_expiry() {
+        let password_reset_data = AdminPasswordResetDao::new(&Uuid::now_v7(), &300);
+        assert_eq!(password_reset_data.code().len(), 6);
+        assert!(password_reset_data
+            .code()
+            .chars()
+            .all(|c| c.is_ascii_digit()));
+        assert_eq!(
+            (*password_reset_data.expired_at() - *password_reset_data.created_at()).num_seconds(),
+            300
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_code_is_rejected_and_cleaned_up() {
+        let db = db().await;
+        let admin_id = Uuid::now_v7();
+        let expired_data = AdminPasswordResetDao::new(&admin_id, &0);
+        let pending_data = AdminPasswordResetDao::new(&admin_id, &300);
+        expired_data.db_insert(&db).await.unwrap();
+        pending_data.db_insert(&db).await.unwrap();
+
+        assert!(AdminPasswordResetDao::db_select(&db, expired_data.id())
+            .await
+            .is_err());
+        assert_eq!(
+            AdminPasswordResetDao::db_select(&db, pending_data.id())
+                .await
+                .unwrap()
+                .code(),
+            pending_data.code()
+        );
+
+        assert_eq!(
+            AdminPasswordResetDao::db_delete_expired(&db).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            AdminPasswordResetDao::db_select_many_by_admin_id(&db, &admin_id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn issuing_again_invalidates_earlier_codes() {
+        let db = db().await;
+        let admin_id = Uuid::now_v7();
+        let earlier_data = AdminPasswordResetDao::new(&admin_id, &300);
+        let other_data = AdminPasswordResetDao::new(&Uuid::now_v7(), &300);
+        earlier_data.db_insert(&db).await.unwrap();
+        other_data.db_insert(&db).await.unwrap();
+
+        AdminPasswordResetDao::db_delete_many_by_admin_id(&db, &admin_id)
+            .await
+            .unwrap();
+        let latest_data = AdminPasswordResetDao::new(&admin_id, &300);
+        latest_data.db_insert(&db).await.unwrap();
+
+        assert!(AdminPasswordResetDao::db_select(&db, earlier_data.id())
+            .await
+            .is_err());
+        assert!(AdminPasswordResetDao::db_select(&db, latest_data.id())
+            .await
+            .is_ok());
+        assert!(AdminPasswordResetDao::db_select(&db, other_data.id())
+            .await
+            .is_ok());
     }
 }
@@ -0,0 +1,358 @@
+use anyhow::{Error, Result};
+use chrono::{DateTime, Duration, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::admin_refresh_token::AdminRefreshTokenModel as AdminRefreshTokenMysqlModel,
+    query::admin_refresh_token::{
+        DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT_BY_TOKEN as MYSQL_SELECT_BY_TOKEN,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::admin_refresh_token::AdminRefreshTokenModel as AdminRefreshTokenPostgresModel,
+    query::admin_refresh_token::{
+        DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT,
+        SELECT_BY_TOKEN as POSTGRES_SELECT_BY_TOKEN,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::admin_refresh_token::AdminRefreshTokenModel as AdminRefreshTokenScyllaModel,
+    query::admin_refresh_token::{
+        DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT_BY_TOKEN as SCYLLA_SELECT_BY_TOKEN,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::admin_refresh_token::AdminRefreshTokenModel as AdminRefreshTokenSqliteModel,
+    query::admin_refresh_token::{
+        DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT_BY_TOKEN as SQLITE_SELECT_BY_TOKEN,
+    },
+};
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use scylla::frame::value::CqlTimestamp as ScyllaCqlTimestamp;
+use uuid::Uuid;
+
+use crate::{util::conversion, Db};
+
+const REFRESH_TOKEN_LENGTH: usize = 64;
+
+pub struct AdminRefreshTokenDao {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    admin_id: Uuid,
+    session_id: Uuid,
+    token: String,
+    expired_at: DateTime<Utc>,
+}
+
+impl AdminRefreshTokenDao {
+    pub fn new(admin_id: &Uuid, session_id: &Uuid, expiry_duration: &u64) -> Result<Self> {
+        let now = Utc::now();
+        Ok(Self {
+            id: Uuid::now_v7(),
+            created_at: now,
+            admin_id: *admin_id,
+            session_id: *session_id,
+            token: thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(REFRESH_TOKEN_LENGTH)
+                .map(char::from)
+                .collect(),
+            expired_at: now + Duration::seconds(i64::try_from(*expiry_duration)?),
+        })
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn session_id(&self) -> &Uuid {
+        &self.session_id
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn expired_at(&self) -> &DateTime<Utc> {
+        &self.expired_at
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert(self, db).await,
+        }
+    }
+
+    pub async fn db_select_by_token(db: &Db, token: &str) -> Result<Self> {
+        let data = match db {
+            Db::ScyllaDb(db) => {
+                Self::from_scylladb_model(&Self::scylladb_select_by_token(db, token).await?)?
+            }
+            Db::PostgresqlDb(db) => {
+                Self::from_postgresdb_model(&Self::postgresdb_select_by_token(db, token).await?)
+            }
+            Db::MysqlDb(db) => {
+                Self::from_mysqldb_model(&Self::mysqldb_select_by_token(db, token).await?)
+            }
+            Db::SqliteDb(db) => {
+                Self::from_sqlitedb_model(&Self::sqlitedb_select_by_token(db, token).await?)
+            }
+        };
+        if data.expired_at <= Utc::now() {
+            return Err(Error::msg("Refresh token has expired"));
+        }
+        Ok(data)
+    }
+
+    pub async fn db_delete(db: &Db, id: &Uuid) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_delete(db, id).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_delete(db, id).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete(db, id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete(db, id).await,
+        }
+    }
+
+    async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        Ok(())
+    }
+
+    async fn scylladb_select_by_token(
+        db: &ScyllaDb,
+        token: &str,
+    ) -> Result<AdminRefreshTokenScyllaModel> {
+        Ok(db
+            .execute(SCYLLA_SELECT_BY_TOKEN, [token].as_ref())
+            .await?
+            .first_row_typed::<AdminRefreshTokenScyllaModel>()?)
+    }
+
+    async fn scylladb_delete(db: &ScyllaDb, id: &Uuid) -> Result<()> {
+        db.execute(SCYLLA_DELETE, [id].as_ref()).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.admin_id)
+                .bind(&self.session_id)
+                .bind(&self.token)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select_by_token(
+        db: &PostgresDb,
+        token: &str,
+    ) -> Result<AdminRefreshTokenPostgresModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(POSTGRES_SELECT_BY_TOKEN).bind(token))
+            .await?)
+    }
+
+    async fn postgresdb_delete(db: &PostgresDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(POSTGRES_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.admin_id)
+                .bind(&self.session_id)
+                .bind(&self.token)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select_by_token(
+        db: &MysqlDb,
+        token: &str,
+    ) -> Result<AdminRefreshTokenMysqlModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(MYSQL_SELECT_BY_TOKEN).bind(token))
+            .await?)
+    }
+
+    async fn mysqldb_delete(db: &MysqlDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(MYSQL_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.admin_id)
+                .bind(&self.session_id)
+                .bind(&self.token)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select_by_token(
+        db: &SqliteDb,
+        token: &str,
+    ) -> Result<AdminRefreshTokenSqliteModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(SQLITE_SELECT_BY_TOKEN).bind(token))
+            .await?)
+    }
+
+    async fn sqlitedb_delete(db: &SqliteDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(SQLITE_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    fn from_scylladb_model(model: &AdminRefreshTokenScyllaModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.created_at())?,
+            admin_id: *model.admin_id(),
+            session_id: *model.session_id(),
+            token: model.token().to_owned(),
+            expired_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.expired_at())?,
+        })
+    }
+
+    fn to_scylladb_model(&self) -> AdminRefreshTokenScyllaModel {
+        AdminRefreshTokenScyllaModel::new(
+            &self.id,
+            &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+            &self.admin_id,
+            &self.session_id,
+            &self.token,
+            &ScyllaCqlTimestamp(self.expired_at.timestamp_millis()),
+        )
+    }
+
+    fn from_postgresdb_model(model: &AdminRefreshTokenPostgresModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            admin_id: *model.admin_id(),
+            session_id: *model.session_id(),
+            token: model.token().to_owned(),
+            expired_at: *model.expired_at(),
+        }
+    }
+
+    fn from_mysqldb_model(model: &AdminRefreshTokenMysqlModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            admin_id: *model.admin_id(),
+            session_id: *model.session_id(),
+            token: model.token().to_owned(),
+            expired_at: *model.expired_at(),
+        }
+    }
+
+    fn from_sqlitedb_model(model: &AdminRefreshTokenSqliteModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            admin_id: *model.admin_id(),
+            session_id: *model.session_id(),
+            token: model.token().to_owned(),
+            expired_at: *model.expired_at(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hb_db_sqlite::db::SqliteDb;
+    use uuid::Uuid;
+
+    use super::{AdminRefreshTokenDao, REFRESH_TOKEN_LENGTH};
+    use crate::Db;
+
+    async fn db() -> Db {
+        Db::SqliteDb(SqliteDb::new(":memory:", &1, &600, &600, &None).await)
+    }
+
+    #[test]
+    fn new_issues_a_random_token_with_an_expiry() {
+        let (admin_id, session_id) = (Uuid::now_v7(), Uuid::now_v7());
+        let refresh_token_data = AdminRefreshTokenDao::new(&admin_id, &session_id, &3600).unwrap();
+        assert_eq!(refresh_token_data.token().len(), REFRESH_TOKEN_LENGTH);
+        assert!(refresh_token_data
+            .token()
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric()));
+        assert_eq!(
+            (*refresh_token_data.expired_at() - *refresh_token_data.created_at()).num_seconds(),
+            3600
+        );
+        assert_ne!(
+            refresh_token_data.token(),
+            AdminRefreshTokenDao::new(&admin_id, &session_id, &3600)
+                .unwrap()
+                .token()
+        );
+        assert!(AdminRefreshTokenDao::new(&admin_id, &session_id, &u64::MAX).is_err());
+    }
+
+    #[tokio::test]
+    async fn deleted_token_can_no_longer_be_used() {
+        let db = db().await;
+        let refresh_token_data =
+            AdminRefreshTokenDao::new(&Uuid::now_v7(), &Uuid::now_v7(), &3600).unwrap();
+        refresh_token_data.db_insert(&db).await.unwrap();
+
+        let selected = AdminRefreshTokenDao::db_select_by_token(&db, refresh_token_data.token())
+            .await
+            .unwrap();
+        assert_eq!(selected.id(), refresh_token_data.id());
+        assert_eq!(selected.session_id(), refresh_token_data.session_id());
+
+        AdminRefreshTokenDao::db_delete(&db, refresh_token_data.id())
+            .await
+            .unwrap();
+        assert!(
+            AdminRefreshTokenDao::db_select_by_token(&db, refresh_token_data.token())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_token_is_rejected() {
+        let db = db().await;
+        let refresh_token_data =
+            AdminRefreshTokenDao::new(&Uuid::now_v7(), &Uuid::now_v7(), &0).unwrap();
+        refresh_token_data.db_insert(&db).await.unwrap();
+        assert!(
+            AdminRefreshTokenDao::db_select_by_token(&db, refresh_token_data.token())
+                .await
+                .is_err()
+        );
+    }
+}
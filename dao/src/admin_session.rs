@@ -0,0 +1,427 @@
+use std::{cmp::Reverse, net::IpAddr};
+
+use anyhow::{Error, Result};
+use chrono::{DateTime, Duration, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::admin_session::AdminSessionModel as AdminSessionMysqlModel,
+    query::admin_session::{
+        DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as MYSQL_SELECT_MANY_BY_ADMIN_ID,
+        UPDATE_EXPIRED_AT as MYSQL_UPDATE_EXPIRED_AT,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::admin_session::AdminSessionModel as AdminSessionPostgresModel,
+    query::admin_session::{
+        DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as POSTGRES_SELECT_MANY_BY_ADMIN_ID,
+        UPDATE_EXPIRED_AT as POSTGRES_UPDATE_EXPIRED_AT,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::admin_session::AdminSessionModel as AdminSessionScyllaModel,
+    query::admin_session::{
+        DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as SCYLLA_SELECT_MANY_BY_ADMIN_ID,
+        UPDATE_EXPIRED_AT as SCYLLA_UPDATE_EXPIRED_AT,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::admin_session::AdminSessionModel as AdminSessionSqliteModel,
+    query::admin_session::{
+        DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as SQLITE_SELECT_MANY_BY_ADMIN_ID,
+        UPDATE_EXPIRED_AT as SQLITE_UPDATE_EXPIRED_AT,
+    },
+};
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
+use uuid::Uuid;
+
+use crate::{jwt_revocation::JwtRevocationDao, util::conversion, Db};
+
+pub struct AdminSessionDao {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    admin_id: Uuid,
+    user_agent: Option<String>,
+    ip: Option<String>,
+    expired_at: DateTime<Utc>,
+}
+
+impl AdminSessionDao {
+    pub fn new(
+        admin_id: &Uuid,
+        user_agent: &Option<String>,
+        ip: &Option<IpAddr>,
+        expiry_duration: &u64,
+    ) -> Result<Self> {
+        let now = Utc::now();
+        Ok(Self {
+            id: Uuid::now_v7(),
+            created_at: now,
+            updated_at: now,
+            admin_id: *admin_id,
+            user_agent: user_agent.clone(),
+            ip: ip.map(|ip| ip.to_string()),
+            expired_at: now + Duration::seconds(i64::try_from(*expiry_duration)?),
+        })
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn user_agent(&self) -> &Option<String> {
+        &self.user_agent
+    }
+
+    pub fn ip(&self) -> &Option<String> {
+        &self.ip
+    }
+
+    pub fn expired_at(&self) -> &DateTime<Utc> {
+        &self.expired_at
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert(self, db).await,
+        }
+    }
+
+    pub async fn db_select(db: &Db, id: &Uuid) -> Result<Self> {
+        let data = match db {
+            Db::ScyllaDb(db) => Self::from_scylladb_model(&Self::scylladb_select(db, id).await?)?,
+            Db::PostgresqlDb(db) => {
+                Self::from_postgresdb_model(&Self::postgresdb_select(db, id).await?)
+            }
+            Db::MysqlDb(db) => Self::from_mysqldb_model(&Self::mysqldb_select(db, id).await?),
+            Db::SqliteDb(db) => Self::from_sqlitedb_model(&Self::sqlitedb_select(db, id).await?),
+        };
+        if data.expired_at <= Utc::now() {
+            return Err(Error::msg("Session has expired"));
+        }
+        Ok(data)
+    }
+
+    pub async fn db_select_many_by_admin_id(db: &Db, admin_id: &Uuid) -> Result<Vec<Self>> {
+        let mut sessions_data = match db {
+            Db::ScyllaDb(db) => {
+                let mut sessions_data = Vec::new();
+                let sessions = Self::scylladb_select_many_by_admin_id(db, admin_id).await?;
+                for session in sessions {
+                    sessions_data.push(Self::from_scylladb_model(&session?)?);
+                }
+                sessions_data.sort_by_key(|session| Reverse(session.created_at));
+                sessions_data
+            }
+            Db::PostgresqlDb(db) => Self::postgresdb_select_many_by_admin_id(db, admin_id)
+                .await?
+                .iter()
+                .map(Self::from_postgresdb_model)
+                .collect(),
+            Db::MysqlDb(db) => Self::mysqldb_select_many_by_admin_id(db, admin_id)
+                .await?
+                .iter()
+                .map(Self::from_mysqldb_model)
+                .collect(),
+            Db::SqliteDb(db) => Self::sqlitedb_select_many_by_admin_id(db, admin_id)
+                .await?
+                .iter()
+                .map(Self::from_sqlitedb_model)
+                .collect(),
+        };
+        let now = Utc::now();
+        sessions_data.retain(|session| session.expired_at > now);
+        Ok(sessions_data)
+    }
+
+    // Pushes the session expiry forward, called whenever its refresh token is rotated
+    pub async fn db_extend(&mut self, db: &Db, expiry_duration: &u64) -> Result<()> {
+        self.updated_at = Utc::now();
+        self.expired_at = self.updated_at + Duration::seconds(i64::try_from(*expiry_duration)?);
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_update_expired_at(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_update_expired_at(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_update_expired_at(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_update_expired_at(self, db).await,
+        }
+    }
+
+    // Revokes every access token issued for this session and removes it, so its refresh
+    // token can no longer be used either
+    pub async fn db_revoke(&self, db: &Db, access_expiry_duration: &u64) -> Result<()> {
+        let revocation_expired_at =
+            Utc::now() + Duration::seconds(i64::try_from(*access_expiry_duration)?);
+        JwtRevocationDao::new(&self.id, &revocation_expired_at)
+            .db_insert(db)
+            .await?;
+        Self::db_delete(db, &self.id).await
+    }
+
+    pub async fn db_delete(db: &Db, id: &Uuid) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_delete(db, id).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_delete(db, id).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete(db, id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete(db, id).await,
+        }
+    }
+
+    async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        Ok(())
+    }
+
+    async fn scylladb_select(db: &ScyllaDb, id: &Uuid) -> Result<AdminSessionScyllaModel> {
+        Ok(db
+            .execute(SCYLLA_SELECT, [id].as_ref())
+            .await?
+            .first_row_typed::<AdminSessionScyllaModel>()?)
+    }
+
+    async fn scylladb_select_many_by_admin_id(
+        db: &ScyllaDb,
+        admin_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<AdminSessionScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_ADMIN_ID, [admin_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn scylladb_update_expired_at(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(
+            SCYLLA_UPDATE_EXPIRED_AT,
+            &(
+                &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+                &ScyllaCqlTimestamp(self.expired_at.timestamp_millis()),
+                &self.id,
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn scylladb_delete(db: &ScyllaDb, id: &Uuid) -> Result<()> {
+        db.execute(SCYLLA_DELETE, [id].as_ref()).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.admin_id)
+                .bind(&self.user_agent)
+                .bind(&self.ip)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select(db: &PostgresDb, id: &Uuid) -> Result<AdminSessionPostgresModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(POSTGRES_SELECT).bind(id))
+            .await?)
+    }
+
+    async fn postgresdb_select_many_by_admin_id(
+        db: &PostgresDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AdminSessionPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn postgresdb_update_expired_at(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_UPDATE_EXPIRED_AT)
+                .bind(&self.updated_at)
+                .bind(&self.expired_at)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_delete(db: &PostgresDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(POSTGRES_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.admin_id)
+                .bind(&self.user_agent)
+                .bind(&self.ip)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select(db: &MysqlDb, id: &Uuid) -> Result<AdminSessionMysqlModel> {
+        Ok(db.fetch_one(sqlx::query_as(MYSQL_SELECT).bind(id)).await?)
+    }
+
+    async fn mysqldb_select_many_by_admin_id(
+        db: &MysqlDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AdminSessionMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn mysqldb_update_expired_at(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_UPDATE_EXPIRED_AT)
+                .bind(&self.updated_at)
+                .bind(&self.expired_at)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_delete(db: &MysqlDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(MYSQL_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.admin_id)
+                .bind(&self.user_agent)
+                .bind(&self.ip)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select(db: &SqliteDb, id: &Uuid) -> Result<AdminSessionSqliteModel> {
+        Ok(db.fetch_one(sqlx::query_as(SQLITE_SELECT).bind(id)).await?)
+    }
+
+    async fn sqlitedb_select_many_by_admin_id(
+        db: &SqliteDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AdminSessionSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn sqlitedb_update_expired_at(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_UPDATE_EXPIRED_AT)
+                .bind(&self.updated_at)
+                .bind(&self.expired_at)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_delete(db: &SqliteDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(SQLITE_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    fn from_scylladb_model(model: &AdminSessionScyllaModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.created_at())?,
+            updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
+            admin_id: *model.admin_id(),
+            user_agent: model.user_agent().clone(),
+            ip: model.ip().clone(),
+            expired_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.expired_at())?,
+        })
+    }
+
+    fn to_scylladb_model(&self) -> AdminSessionScyllaModel {
+        AdminSessionScyllaModel::new(
+            &self.id,
+            &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+            &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+            &self.admin_id,
+            &self.user_agent,
+            &self.ip,
+            &ScyllaCqlTimestamp(self.expired_at.timestamp_millis()),
+        )
+    }
+
+    fn from_postgresdb_model(model: &AdminSessionPostgresModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            admin_id: *model.admin_id(),
+            user_agent: model.user_agent().clone(),
+            ip: model.ip().clone(),
+            expired_at: *model.expired_at(),
+        }
+    }
+
+    fn from_mysqldb_model(model: &AdminSessionMysqlModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            admin_id: *model.admin_id(),
+            user_agent: model.user_agent().clone(),
+            ip: model.ip().clone(),
+            expired_at: *model.expired_at(),
+        }
+    }
+
+    fn from_sqlitedb_model(model: &AdminSessionSqliteModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            admin_id: *model.admin_id(),
+            user_agent: model.user_agent().clone(),
+            ip: model.ip().clone(),
+            expired_at: *model.expired_at(),
+        }
+    }
+}
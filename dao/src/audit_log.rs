@@ -0,0 +1,458 @@
+use std::{cmp::Reverse, net::IpAddr};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::audit_log::AuditLogModel as AuditLogMysqlModel,
+    query::audit_log::{
+        INSERT as MYSQL_INSERT, SELECT_MANY_BY_ADMIN_ID as MYSQL_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_BY_PROJECT_ID as MYSQL_SELECT_MANY_BY_PROJECT_ID,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::audit_log::AuditLogModel as AuditLogPostgresModel,
+    query::audit_log::{
+        INSERT as POSTGRES_INSERT, SELECT_MANY_BY_ADMIN_ID as POSTGRES_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_BY_PROJECT_ID as POSTGRES_SELECT_MANY_BY_PROJECT_ID,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::audit_log::AuditLogModel as AuditLogScyllaModel,
+    query::audit_log::{
+        INSERT as SCYLLA_INSERT, SELECT_MANY_BY_ADMIN_ID as SCYLLA_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_BY_PROJECT_ID as SCYLLA_SELECT_MANY_BY_PROJECT_ID,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::audit_log::AuditLogModel as AuditLogSqliteModel,
+    query::audit_log::{
+        INSERT as SQLITE_INSERT, SELECT_MANY_BY_ADMIN_ID as SQLITE_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_BY_PROJECT_ID as SQLITE_SELECT_MANY_BY_PROJECT_ID,
+    },
+};
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{util::conversion, Db};
+
+const REDACTED_FIELDS: [&str; 5] = [
+    "token",
+    "previous_token",
+    "password",
+    "password_hash",
+    "mfa_secret",
+];
+
+pub struct AuditLogDao {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    admin_id: Uuid,
+    project_id: Option<Uuid>,
+    ip: Option<String>,
+    action: String,
+    resource: String,
+    resource_id: Uuid,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+impl AuditLogDao {
+    pub fn new(
+        admin_id: &Uuid,
+        project_id: &Option<Uuid>,
+        ip: &Option<IpAddr>,
+        action: &str,
+        resource: &str,
+        resource_id: &Uuid,
+        before: &Option<Value>,
+        after: &Option<Value>,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            created_at: Utc::now(),
+            admin_id: *admin_id,
+            project_id: *project_id,
+            ip: ip.map(|ip| ip.to_string()),
+            action: action.to_owned(),
+            resource: resource.to_owned(),
+            resource_id: *resource_id,
+            before: before.as_ref().map(Self::redact),
+            after: after.as_ref().map(Self::redact),
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn project_id(&self) -> &Option<Uuid> {
+        &self.project_id
+    }
+
+    pub fn ip(&self) -> &Option<String> {
+        &self.ip
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+
+    pub fn resource_id(&self) -> &Uuid {
+        &self.resource_id
+    }
+
+    pub fn before(&self) -> &Option<Value> {
+        &self.before
+    }
+
+    pub fn after(&self) -> &Option<Value> {
+        &self.after
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert(self, db).await,
+        }
+    }
+
+    pub async fn db_select_many_by_admin_id(db: &Db, admin_id: &Uuid) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut audit_logs_data = Vec::new();
+                let audit_logs = Self::scylladb_select_many_by_admin_id(db, admin_id).await?;
+                for audit_log in audit_logs {
+                    audit_logs_data.push(Self::from_scylladb_model(&audit_log?)?);
+                }
+                audit_logs_data.sort_by_key(|audit_log| Reverse(audit_log.created_at));
+                Ok(audit_logs_data)
+            }
+            Db::PostgresqlDb(db) => {
+                let audit_logs = Self::postgresdb_select_many_by_admin_id(db, admin_id).await?;
+                let mut audit_logs_data = Vec::with_capacity(audit_logs.len());
+                for audit_log in &audit_logs {
+                    audit_logs_data.push(Self::from_postgresdb_model(audit_log)?);
+                }
+                Ok(audit_logs_data)
+            }
+            Db::MysqlDb(db) => {
+                let audit_logs = Self::mysqldb_select_many_by_admin_id(db, admin_id).await?;
+                let mut audit_logs_data = Vec::with_capacity(audit_logs.len());
+                for audit_log in &audit_logs {
+                    audit_logs_data.push(Self::from_mysqldb_model(audit_log)?);
+                }
+                Ok(audit_logs_data)
+            }
+            Db::SqliteDb(db) => {
+                let audit_logs = Self::sqlitedb_select_many_by_admin_id(db, admin_id).await?;
+                let mut audit_logs_data = Vec::with_capacity(audit_logs.len());
+                for audit_log in &audit_logs {
+                    audit_logs_data.push(Self::from_sqlitedb_model(audit_log)?);
+                }
+                Ok(audit_logs_data)
+            }
+        }
+    }
+
+    pub async fn db_select_many_by_project_id(db: &Db, project_id: &Uuid) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut audit_logs_data = Vec::new();
+                let audit_logs = Self::scylladb_select_many_by_project_id(db, project_id).await?;
+                for audit_log in audit_logs {
+                    audit_logs_data.push(Self::from_scylladb_model(&audit_log?)?);
+                }
+                audit_logs_data.sort_by_key(|audit_log| Reverse(audit_log.created_at));
+                Ok(audit_logs_data)
+            }
+            Db::PostgresqlDb(db) => {
+                let audit_logs = Self::postgresdb_select_many_by_project_id(db, project_id).await?;
+                let mut audit_logs_data = Vec::with_capacity(audit_logs.len());
+                for audit_log in &audit_logs {
+                    audit_logs_data.push(Self::from_postgresdb_model(audit_log)?);
+                }
+                Ok(audit_logs_data)
+            }
+            Db::MysqlDb(db) => {
+                let audit_logs = Self::mysqldb_select_many_by_project_id(db, project_id).await?;
+                let mut audit_logs_data = Vec::with_capacity(audit_logs.len());
+                for audit_log in &audit_logs {
+                    audit_logs_data.push(Self::from_mysqldb_model(audit_log)?);
+                }
+                Ok(audit_logs_data)
+            }
+            Db::SqliteDb(db) => {
+                let audit_logs = Self::sqlitedb_select_many_by_project_id(db, project_id).await?;
+                let mut audit_logs_data = Vec::with_capacity(audit_logs.len());
+                for audit_log in &audit_logs {
+                    audit_logs_data.push(Self::from_sqlitedb_model(audit_log)?);
+                }
+                Ok(audit_logs_data)
+            }
+        }
+    }
+
+    async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        Ok(())
+    }
+
+    async fn scylladb_select_many_by_admin_id(
+        db: &ScyllaDb,
+        admin_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<AuditLogScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_ADMIN_ID, [admin_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn scylladb_select_many_by_project_id(
+        db: &ScyllaDb,
+        project_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<AuditLogScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_PROJECT_ID, [project_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.admin_id)
+                .bind(&self.project_id)
+                .bind(&self.ip)
+                .bind(&self.action)
+                .bind(&self.resource)
+                .bind(&self.resource_id)
+                .bind(self.before_string())
+                .bind(self.after_string()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select_many_by_admin_id(
+        db: &PostgresDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AuditLogPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn postgresdb_select_many_by_project_id(
+        db: &PostgresDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<AuditLogPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.admin_id)
+                .bind(&self.project_id)
+                .bind(&self.ip)
+                .bind(&self.action)
+                .bind(&self.resource)
+                .bind(&self.resource_id)
+                .bind(self.before_string())
+                .bind(self.after_string()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select_many_by_admin_id(
+        db: &MysqlDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AuditLogMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn mysqldb_select_many_by_project_id(
+        db: &MysqlDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<AuditLogMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.admin_id)
+                .bind(&self.project_id)
+                .bind(&self.ip)
+                .bind(&self.action)
+                .bind(&self.resource)
+                .bind(&self.resource_id)
+                .bind(self.before_string())
+                .bind(self.after_string()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select_many_by_admin_id(
+        db: &SqliteDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<AuditLogSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn sqlitedb_select_many_by_project_id(
+        db: &SqliteDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<AuditLogSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    fn before_string(&self) -> Option<String> {
+        self.before.as_ref().map(|before| before.to_string())
+    }
+
+    fn after_string(&self) -> Option<String> {
+        self.after.as_ref().map(|after| after.to_string())
+    }
+
+    fn snapshot_from_string(snapshot: &Option<String>) -> Result<Option<Value>> {
+        match snapshot {
+            Some(snapshot) => Ok(Some(serde_json::from_str(snapshot)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn redact(snapshot: &Value) -> Value {
+        match snapshot {
+            Value::Object(snapshot) => Value::Object(
+                snapshot
+                    .iter()
+                    .map(|(key, value)| {
+                        if REDACTED_FIELDS.contains(&key.as_str()) {
+                            (key.to_owned(), Value::String("[REDACTED]".to_owned()))
+                        } else {
+                            (key.to_owned(), Self::redact(value))
+                        }
+                    })
+                    .collect(),
+            ),
+            Value::Array(snapshot) => Value::Array(snapshot.iter().map(Self::redact).collect()),
+            _ => snapshot.clone(),
+        }
+    }
+
+    fn from_scylladb_model(model: &AuditLogScyllaModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.created_at())?,
+            admin_id: *model.admin_id(),
+            project_id: *model.project_id(),
+            ip: model.ip().clone(),
+            action: model.action().to_owned(),
+            resource: model.resource().to_owned(),
+            resource_id: *model.resource_id(),
+            before: Self::snapshot_from_string(model.before())?,
+            after: Self::snapshot_from_string(model.after())?,
+        })
+    }
+
+    fn to_scylladb_model(&self) -> AuditLogScyllaModel {
+        AuditLogScyllaModel::new(
+            &self.id,
+            &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+            &self.admin_id,
+            &self.project_id,
+            &self.ip,
+            &self.action,
+            &self.resource,
+            &self.resource_id,
+            &self.before_string(),
+            &self.after_string(),
+        )
+    }
+
+    fn from_postgresdb_model(model: &AuditLogPostgresModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            admin_id: *model.admin_id(),
+            project_id: *model.project_id(),
+            ip: model.ip().clone(),
+            action: model.action().to_owned(),
+            resource: model.resource().to_owned(),
+            resource_id: *model.resource_id(),
+            before: Self::snapshot_from_string(model.before())?,
+            after: Self::snapshot_from_string(model.after())?,
+        })
+    }
+
+    fn from_mysqldb_model(model: &AuditLogMysqlModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            admin_id: *model.admin_id(),
+            project_id: *model.project_id(),
+            ip: model.ip().clone(),
+            action: model.action().to_owned(),
+            resource: model.resource().to_owned(),
+            resource_id: *model.resource_id(),
+            before: Self::snapshot_from_string(model.before())?,
+            after: Self::snapshot_from_string(model.after())?,
+        })
+    }
+
+    fn from_sqlitedb_model(model: &AuditLogSqliteModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            admin_id: *model.admin_id(),
+            project_id: *model.project_id(),
+            ip: model.ip().clone(),
+            action: model.action().to_owned(),
+            resource: model.resource().to_owned(),
+            resource_id: *model.resource_id(),
+            before: Self::snapshot_from_string(model.before())?,
+            after: Self::snapshot_from_string(model.after())?,
+        })
+    }
+}
@@ -0,0 +1,405 @@
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::{
+    admin::AdminDao,
+    collection::CollectionDao,
+    project::ProjectDao,
+    project_member::ProjectMemberDao,
+    record::{
+        RecordDao, RecordFilter, RecordFilterValue, RecordFilters, RecordOrder, RecordPagination,
+    },
+    token::TokenDao,
+    user::UserDao,
+    value::{ColumnKind, ColumnValue},
+    Db,
+};
+
+pub const BACKUP_FORMAT: &str = "hyperbase-backup";
+pub const BACKUP_VERSION: u32 = 1;
+const RECORD_BATCH_SIZE: usize = 1000;
+
+// The archive is NDJSON: a manifest line carrying every collection schema, one line per row, and
+// an end line with the row counts so a truncated archive is rejected on restore. Sessions,
+// password resets, registrations, revocations, transfers, and audit logs are not included.
+#[derive(Serialize, Deserialize)]
+struct BackupManifest {
+    format: String,
+    version: u32,
+    created_at: DateTime<Utc>,
+    collections: Vec<CollectionDao>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+enum BackupEntry {
+    Admin(AdminDao),
+    Project(ProjectDao),
+    ProjectMember(ProjectMemberDao),
+    Token(TokenDao),
+    User(UserDao),
+    Record {
+        collection_id: Uuid,
+        fields: Map<String, Value>,
+    },
+    End(BackupSummary),
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct BackupSummary {
+    admins: usize,
+    projects: usize,
+    project_members: usize,
+    tokens: usize,
+    collections: usize,
+    users: usize,
+    records: usize,
+    skipped: usize,
+}
+
+impl BackupSummary {
+    pub fn admins(&self) -> &usize {
+        &self.admins
+    }
+
+    pub fn projects(&self) -> &usize {
+        &self.projects
+    }
+
+    pub fn project_members(&self) -> &usize {
+        &self.project_members
+    }
+
+    pub fn tokens(&self) -> &usize {
+        &self.tokens
+    }
+
+    pub fn collections(&self) -> &usize {
+        &self.collections
+    }
+
+    pub fn users(&self) -> &usize {
+        &self.users
+    }
+
+    pub fn records(&self) -> &usize {
+        &self.records
+    }
+
+    // Rows restored over existing data are counted here instead of in their own kind
+    pub fn skipped(&self) -> &usize {
+        &self.skipped
+    }
+}
+
+// Sends the archive line by line so callers can stream it to a file or an HTTP response
+pub async fn db_backup(db: &Db, tx: &mpsc::Sender<String>) -> Result<BackupSummary> {
+    let mut summary = BackupSummary::default();
+
+    let collections_data = CollectionDao::db_select_many(db).await?;
+    summary.collections = collections_data.len();
+    send_line(
+        tx,
+        &BackupManifest {
+            format: BACKUP_FORMAT.to_owned(),
+            version: BACKUP_VERSION,
+            created_at: Utc::now(),
+            collections: collections_data,
+        },
+    )
+    .await?;
+
+    let admins_data = AdminDao::db_select_many(db).await?;
+    let mut projects_data = Vec::new();
+    let mut tokens_data = Vec::new();
+    for admin_data in &admins_data {
+        projects_data
+            .append(&mut ProjectDao::db_select_many_by_admin_id(db, admin_data.id()).await?);
+        tokens_data.append(&mut TokenDao::db_select_many_by_admin_id(db, admin_data.id()).await?);
+    }
+
+    for admin_data in admins_data {
+        send_line(tx, &BackupEntry::Admin(admin_data)).await?;
+        summary.admins += 1;
+    }
+    let mut project_ids = Vec::with_capacity(projects_data.len());
+    for project_data in projects_data {
+        project_ids.push(*project_data.id());
+        send_line(tx, &BackupEntry::Project(project_data)).await?;
+        summary.projects += 1;
+    }
+    for project_id in &project_ids {
+        for project_member_data in
+            ProjectMemberDao::db_select_many_by_project_id(db, project_id).await?
+        {
+            send_line(tx, &BackupEntry::ProjectMember(project_member_data)).await?;
+            summary.project_members += 1;
+        }
+        for user_data in UserDao::db_select_many_by_project_id(db, project_id).await? {
+            send_line(tx, &BackupEntry::User(user_data)).await?;
+            summary.users += 1;
+        }
+    }
+    for token_data in tokens_data {
+        send_line(tx, &BackupEntry::Token(token_data)).await?;
+        summary.tokens += 1;
+    }
+
+    for collection_data in CollectionDao::db_select_many(db).await? {
+        let mut last_id = None;
+        loop {
+            let records_data = select_records_after(db, &collection_data, &last_id).await?;
+            for record_data in &records_data {
+                let mut fields = Map::with_capacity(record_data.len());
+                for (key, value) in record_data.data() {
+                    fields.insert(key.to_owned(), value.to_serde_json()?);
+                }
+                send_line(
+                    tx,
+                    &BackupEntry::Record {
+                        collection_id: *collection_data.id(),
+                        fields,
+                    },
+                )
+                .await?;
+                summary.records += 1;
+            }
+            last_id = match records_data.last().and_then(|record| record.get("_id")) {
+                Some(ColumnValue::Uuid(Some(id))) => Some(*id),
+                _ => None,
+            };
+            if last_id.is_none() || records_data.len() < RECORD_BATCH_SIZE {
+                break;
+            }
+        }
+    }
+
+    send_line(tx, &BackupEntry::End(summary.clone())).await?;
+
+    Ok(summary)
+}
+
+// Rows whose id already exists are skipped so the restoring operator's own account and any
+// newer internal state are kept; records in collections that already exist are upserted
+pub async fn db_restore(
+    db: &Db,
+    mut lines: impl Stream<Item = Result<String>> + Unpin,
+) -> Result<BackupSummary> {
+    let manifest = match lines.next().await {
+        Some(line) => serde_json::from_str::<BackupManifest>(&line?)
+            .map_err(|err| Error::msg(format!("Invalid backup manifest: {err}")))?,
+        None => return Err(Error::msg("Backup is empty")),
+    };
+    if manifest.format != BACKUP_FORMAT {
+        return Err(Error::msg(format!(
+            "Unknown backup format '{}'",
+            manifest.format
+        )));
+    }
+    if manifest.version > BACKUP_VERSION {
+        return Err(Error::msg(format!(
+            "Backup version {} is newer than the supported version {BACKUP_VERSION}",
+            manifest.version
+        )));
+    }
+
+    let mut summary = BackupSummary::default();
+
+    let mut collections_kinds = HashMap::with_capacity(manifest.collections.len());
+    let mut new_collection_ids = HashSet::with_capacity(manifest.collections.len());
+    for collection_data in &manifest.collections {
+        let mut kinds = HashMap::with_capacity(collection_data.schema_fields().len() + 3);
+        kinds.insert("_id".to_owned(), ColumnKind::Uuid);
+        kinds.insert("_version".to_owned(), ColumnKind::BigInt);
+        kinds.insert("_created_by".to_owned(), ColumnKind::Uuid);
+        for (field, props) in collection_data.schema_fields() {
+            kinds.insert(field.to_owned(), props.kind().clone());
+        }
+        collections_kinds.insert(*collection_data.id(), kinds);
+
+        if CollectionDao::db_select(db, collection_data.id())
+            .await
+            .is_ok()
+        {
+            summary.skipped += 1;
+            continue;
+        }
+        collection_data.db_insert(db).await?;
+        new_collection_ids.insert(*collection_data.id());
+        summary.collections += 1;
+    }
+
+    let mut pending_records: Vec<RecordDao> = Vec::new();
+    let mut end = None;
+    while let Some(line) = lines.next().await {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if end.is_some() {
+            return Err(Error::msg("Backup has data after its end marker"));
+        }
+        let entry = serde_json::from_str::<BackupEntry>(&line)
+            .map_err(|err| Error::msg(format!("Invalid backup entry: {err}")))?;
+
+        if !matches!(entry, BackupEntry::Record { .. }) {
+            flush_records(db, &mut pending_records, &new_collection_ids).await?;
+        }
+
+        match entry {
+            BackupEntry::Admin(admin_data) => {
+                if AdminDao::db_select(db, admin_data.id()).await.is_ok() {
+                    summary.skipped += 1;
+                } else {
+                    admin_data.db_insert(db).await?;
+                    summary.admins += 1;
+                }
+            }
+            BackupEntry::Project(project_data) => {
+                if ProjectDao::db_select(db, project_data.id()).await.is_ok() {
+                    summary.skipped += 1;
+                } else {
+                    project_data.db_insert(db).await?;
+                    summary.projects += 1;
+                }
+            }
+            BackupEntry::ProjectMember(project_member_data) => {
+                if ProjectMemberDao::db_select(db, project_member_data.id())
+                    .await
+                    .is_ok()
+                {
+                    summary.skipped += 1;
+                } else {
+                    project_member_data.db_insert(db).await?;
+                    summary.project_members += 1;
+                }
+            }
+            BackupEntry::Token(token_data) => {
+                if TokenDao::db_select(db, token_data.id()).await.is_ok() {
+                    summary.skipped += 1;
+                } else {
+                    token_data.db_insert(db).await?;
+                    summary.tokens += 1;
+                }
+            }
+            BackupEntry::User(user_data) => {
+                if UserDao::db_select(db, user_data.id()).await.is_ok() {
+                    summary.skipped += 1;
+                } else {
+                    user_data.db_insert(db).await?;
+                    summary.users += 1;
+                }
+            }
+            BackupEntry::Record {
+                collection_id,
+                fields,
+            } => {
+                let kinds = match collections_kinds.get(&collection_id) {
+                    Some(kinds) => kinds,
+                    None => {
+                        return Err(Error::msg(format!(
+                            "Record belongs to collection '{collection_id}' which is not in the backup manifest"
+                        )))
+                    }
+                };
+                let mut record_data = RecordDao::new(&collection_id, &Some(kinds.len()));
+                for (field, kind) in kinds {
+                    let value = match fields.get(field) {
+                        Some(value) => ColumnValue::from_serde_json(kind, value)?,
+                        None if field.starts_with('_') => continue,
+                        None => ColumnValue::none(kind),
+                    };
+                    record_data.upsert(field, &value);
+                }
+                if let Some(field) = fields.keys().find(|field| !kinds.contains_key(*field)) {
+                    return Err(Error::msg(format!(
+                        "Record field '{field}' is not in the schema of collection '{collection_id}'"
+                    )));
+                }
+                if pending_records.first().is_some_and(|record| {
+                    record.table_name() != record_data.table_name()
+                        || record.len() != record_data.len()
+                        || record.keys().any(|key| record_data.get(key).is_none())
+                }) || pending_records.len() >= RECORD_BATCH_SIZE
+                {
+                    flush_records(db, &mut pending_records, &new_collection_ids).await?;
+                }
+                pending_records.push(record_data);
+                summary.records += 1;
+            }
+            BackupEntry::End(backup_summary) => end = Some(backup_summary),
+        }
+    }
+    flush_records(db, &mut pending_records, &new_collection_ids).await?;
+
+    match end {
+        Some(_) => Ok(summary),
+        None => Err(Error::msg(
+            "Backup is truncated; its end marker is missing. Rows read before the truncation were restored",
+        )),
+    }
+}
+
+async fn flush_records(
+    db: &Db,
+    records_data: &mut Vec<RecordDao>,
+    new_collection_ids: &HashSet<Uuid>,
+) -> Result<()> {
+    let is_new_collection = match records_data.first() {
+        Some(record_data) => new_collection_ids
+            .iter()
+            .any(|id| RecordDao::new_table_name(id) == record_data.table_name()),
+        None => return Ok(()),
+    };
+    if is_new_collection {
+        RecordDao::db_insert_many(db, records_data).await?;
+    } else {
+        for record_data in records_data.iter() {
+            record_data.db_upsert(db).await?;
+        }
+    }
+    records_data.clear();
+    Ok(())
+}
+
+async fn select_records_after(
+    db: &Db,
+    collection_data: &CollectionDao,
+    last_id: &Option<Uuid>,
+) -> Result<Vec<RecordDao>> {
+    let filters = match last_id {
+        Some(last_id) => RecordFilters::new(&vec![RecordFilter::new(
+            &Some("_id".to_owned()),
+            ">",
+            &Some(RecordFilterValue::Single(ColumnValue::Uuid(Some(*last_id)))),
+            &None,
+            &false,
+        )]),
+        None => RecordFilters::new(&Vec::new()),
+    };
+    let (records_data, _) = RecordDao::db_select_many(
+        db,
+        collection_data,
+        &filters,
+        &Vec::new(),
+        &vec![RecordOrder::new("_id", "ASC")],
+        &RecordPagination::new(&Some(RECORD_BATCH_SIZE as i32)),
+        &false,
+    )
+    .await?;
+    Ok(records_data)
+}
+
+async fn send_line<T: Serialize>(tx: &mpsc::Sender<String>, value: &T) -> Result<()> {
+    let mut line = serde_json::to_string(value)?;
+    line.push('\n');
+    tx.send(line)
+        .await
+        .map_err(|_| Error::msg("Backup receiver was closed"))
+}
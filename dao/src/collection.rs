@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
 use anyhow::{Error, Result};
 use chrono::{DateTime, Utc};
@@ -5,23 +7,28 @@ use futures::future;
 use hb_db_mysql::{
     db::MysqlDb,
     model::collection::{
-        CollectionModel as CollectionMysqlModel,
+        CollectionModel as CollectionMysqlModel, PartialIndexModel as PartialIndexMysqlModel,
         SchemaFieldPropsModel as SchemaFieldPropsMysqlModel,
     },
     query::collection::{
         DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
-        SELECT_MANY_BY_PROJECT_ID as MYSQL_SELECT_MANY_BY_PROJECT_ID, UPDATE as MYSQL_UPDATE,
+        SELECT_MANY as MYSQL_SELECT_MANY,
+        SELECT_MANY_BY_PROJECT_ID as MYSQL_SELECT_MANY_BY_PROJECT_ID,
+        SELECT_MANY_WITH_AUTO_DELETE as MYSQL_SELECT_MANY_WITH_AUTO_DELETE, UPDATE as MYSQL_UPDATE,
     },
 };
 use hb_db_postgresql::{
     db::PostgresDb,
     model::collection::{
-        CollectionModel as CollectionPostgresModel,
+        CollectionModel as CollectionPostgresModel, PartialIndexModel as PartialIndexPostgresModel,
         SchemaFieldPropsModel as SchemaFieldPropsPostgresModel,
     },
     query::collection::{
         DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
-        SELECT_MANY_BY_PROJECT_ID as POSTGRES_SELECT_MANY_BY_PROJECT_ID, UPDATE as POSTGRES_UPDATE,
+        SELECT_MANY as POSTGRES_SELECT_MANY,
+        SELECT_MANY_BY_PROJECT_ID as POSTGRES_SELECT_MANY_BY_PROJECT_ID,
+        SELECT_MANY_WITH_AUTO_DELETE as POSTGRES_SELECT_MANY_WITH_AUTO_DELETE,
+        UPDATE as POSTGRES_UPDATE,
     },
 };
 use hb_db_scylladb::{
@@ -32,29 +39,42 @@ use hb_db_scylladb::{
     },
     query::collection::{
         DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
+        SELECT_MANY as SCYLLA_SELECT_MANY,
         SELECT_MANY_BY_PROJECT_ID as SCYLLA_SELECT_MANY_BY_PROJECT_ID, UPDATE as SCYLLA_UPDATE,
     },
 };
 use hb_db_sqlite::{
     db::SqliteDb,
     model::collection::{
-        CollectionModel as CollectionSqliteModel,
+        CollectionModel as CollectionSqliteModel, PartialIndexModel as PartialIndexSqliteModel,
         SchemaFieldPropsModel as SchemaFieldPropsSqliteModel,
     },
     query::collection::{
         DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
-        SELECT_MANY_BY_PROJECT_ID as SQLITE_SELECT_MANY_BY_PROJECT_ID, UPDATE as SQLITE_UPDATE,
+        SELECT_MANY as SQLITE_SELECT_MANY,
+        SELECT_MANY_BY_PROJECT_ID as SQLITE_SELECT_MANY_BY_PROJECT_ID,
+        SELECT_MANY_WITH_AUTO_DELETE as SQLITE_SELECT_MANY_WITH_AUTO_DELETE,
+        UPDATE as SQLITE_UPDATE,
     },
 };
+use regex::Regex;
 use scylla::{
     frame::value::CqlTimestamp as ScyllaCqlTimestamp,
     transport::session::TypedRowIter as ScyllaTypedRowIter,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use crate::{record::RecordDao, util::conversion, value::ColumnKind, Db};
+use crate::{
+    collection_plugin::CollectionPluginDao,
+    record::RecordDao,
+    util::conversion,
+    value::{ColumnKind, ColumnValue},
+    Db,
+};
 
+#[derive(Serialize, Deserialize)]
 pub struct CollectionDao {
     id: Uuid,
     created_at: DateTime<Utc>,
@@ -63,6 +83,11 @@ pub struct CollectionDao {
     name: String,
     schema_fields: HashMap<String, SchemaFieldProps>,
     indexes: HashSet<String>,
+    partial_indexes: HashMap<String, PartialIndex>,
+    max_field_size: Option<i64>,
+    max_record_size: Option<i64>,
+    auto_delete_after_seconds: Option<i64>,
+    #[serde(skip)]
     _preserve: Option<Preserve>,
 }
 
@@ -72,6 +97,10 @@ impl CollectionDao {
         name: &str,
         schema_fields: &HashMap<String, SchemaFieldProps>,
         indexes: &HashSet<String>,
+        partial_indexes: &HashMap<String, PartialIndex>,
+        max_field_size: &Option<i64>,
+        max_record_size: &Option<i64>,
+        auto_delete_after_seconds: &Option<i64>,
     ) -> Result<Self> {
         let now = Utc::now();
 
@@ -83,6 +112,10 @@ impl CollectionDao {
             name: name.to_string(),
             schema_fields: schema_fields.clone(),
             indexes: indexes.clone(),
+            partial_indexes: partial_indexes.clone(),
+            max_field_size: *max_field_size,
+            max_record_size: *max_record_size,
+            auto_delete_after_seconds: *auto_delete_after_seconds,
             _preserve: None,
         })
     }
@@ -115,15 +148,60 @@ impl CollectionDao {
         &self.indexes
     }
 
+    pub fn partial_indexes(&self) -> &HashMap<String, PartialIndex> {
+        &self.partial_indexes
+    }
+
+    pub fn max_field_size(&self) -> &Option<i64> {
+        &self.max_field_size
+    }
+
+    pub fn max_record_size(&self) -> &Option<i64> {
+        &self.max_record_size
+    }
+
+    pub fn auto_delete_after_seconds(&self) -> &Option<i64> {
+        &self.auto_delete_after_seconds
+    }
+
     pub fn set_name(&mut self, name: &str) {
         self.name = name.to_owned();
     }
 
+    pub fn set_max_field_size(&mut self, max_field_size: &Option<i64>) {
+        self.max_field_size = *max_field_size;
+    }
+
+    pub fn set_max_record_size(&mut self, max_record_size: &Option<i64>) {
+        self.max_record_size = *max_record_size;
+    }
+
+    pub fn update_auto_delete_after_seconds(&mut self, auto_delete_after_seconds: &Option<i64>) {
+        if self._preserve.is_none() {
+            self._preserve = Some(Preserve {
+                schema_fields: None,
+                indexes: None,
+                partial_indexes: None,
+                renamed_fields: None,
+                auto_delete_after_seconds: Some(self.auto_delete_after_seconds),
+                transform_schema_fields: false,
+            });
+        } else {
+            self._preserve.as_mut().unwrap().auto_delete_after_seconds =
+                Some(self.auto_delete_after_seconds);
+        }
+        self.auto_delete_after_seconds = *auto_delete_after_seconds;
+    }
+
     pub fn update_schema_fields(&mut self, schema_fields: &HashMap<String, SchemaFieldProps>) {
         if self._preserve.is_none() {
             self._preserve = Some(Preserve {
                 schema_fields: Some(self.schema_fields.clone()),
                 indexes: None,
+                partial_indexes: None,
+                renamed_fields: None,
+                auto_delete_after_seconds: None,
+                transform_schema_fields: false,
             });
         } else {
             self._preserve.as_mut().unwrap().schema_fields = Some(self.schema_fields.clone());
@@ -136,6 +214,10 @@ impl CollectionDao {
             self._preserve = Some(Preserve {
                 schema_fields: None,
                 indexes: Some(self.indexes.clone()),
+                partial_indexes: None,
+                renamed_fields: None,
+                auto_delete_after_seconds: None,
+                transform_schema_fields: false,
             });
         } else {
             self._preserve.as_mut().unwrap().indexes = Some(self.indexes.clone());
@@ -143,28 +225,110 @@ impl CollectionDao {
         self.indexes = indexes.to_owned();
     }
 
-    pub async fn db_insert(&self, db: &Db) -> Result<()> {
-        if let Db::MysqlDb(_) = db {
-            for index in &self.indexes {
-                if let Some(field) = self.schema_fields.get(index) {
-                    match &field.kind {
-                        ColumnKind::Binary
-                        | ColumnKind::Varint
-                        | ColumnKind::Decimal
-                        | ColumnKind::String
-                        | ColumnKind::Json => {
-                            return Err(Error::msg(format!(
-                                "Field '{}' has type '{}' that doesn't support indexing in the data type implementation of Hyperbase for MySQL",
-                                index,
-                                field.kind.to_str()
-                            )))
-                        }
-                        _ => (),
-                    };
+    pub fn update_partial_indexes(&mut self, partial_indexes: &HashMap<String, PartialIndex>) {
+        if self._preserve.is_none() {
+            self._preserve = Some(Preserve {
+                schema_fields: None,
+                indexes: None,
+                partial_indexes: Some(self.partial_indexes.clone()),
+                renamed_fields: None,
+                auto_delete_after_seconds: None,
+                transform_schema_fields: false,
+            });
+        } else {
+            self._preserve.as_mut().unwrap().partial_indexes = Some(self.partial_indexes.clone());
+        }
+        self.partial_indexes = partial_indexes.clone();
+    }
+
+    pub fn rename_schema_fields(&mut self, renamed_fields: &HashMap<String, String>) -> Result<()> {
+        let mut targets = HashSet::with_capacity(renamed_fields.len());
+        for (from, to) in renamed_fields {
+            if !targets.insert(to) {
+                return Err(Error::msg(format!(
+                    "Field '{to}' is the target of more than one rename"
+                )));
+            }
+            if !self.schema_fields.contains_key(from) {
+                return Err(Error::msg(format!(
+                    "Field '{from}' is not exist in the schema fields"
+                )));
+            }
+            if self.schema_fields.contains_key(to) {
+                return Err(Error::msg(format!(
+                    "Field '{to}' already exists in the schema fields"
+                )));
+            }
+            if self.indexes.contains(from) {
+                return Err(Error::msg(format!(
+                    "Field '{from}' can't be renamed because it is in the indexes"
+                )));
+            }
+            for (name, partial_index) in &self.partial_indexes {
+                if partial_index.fields().contains(from)
+                    || partial_index.predicate().contains_key(from)
+                {
+                    return Err(Error::msg(format!(
+                        "Field '{from}' can't be renamed because it is in partial index '{name}'"
+                    )));
+                }
+            }
+            for (name, field_props) in &self.schema_fields {
+                if let Some(computed) = field_props.computed() {
+                    if computed.fields().contains(from) {
+                        return Err(Error::msg(format!(
+                            "Field '{from}' can't be renamed because it is used by computed field '{name}'"
+                        )));
+                    }
                 }
             }
         }
 
+        if self._preserve.is_none() {
+            self._preserve = Some(Preserve {
+                schema_fields: None,
+                indexes: None,
+                partial_indexes: None,
+                renamed_fields: None,
+                auto_delete_after_seconds: None,
+                transform_schema_fields: false,
+            });
+        }
+        let preserve = self._preserve.as_mut().unwrap();
+        for (from, to) in renamed_fields {
+            let field = self.schema_fields.remove(from).unwrap();
+            if let Some(schema_fields) = &mut preserve.schema_fields {
+                if let Some(field) = schema_fields.remove(from) {
+                    schema_fields.insert(to.to_owned(), field);
+                }
+            }
+            preserve.renamed_fields.get_or_insert_with(Vec::new).push((
+                from.to_owned(),
+                to.to_owned(),
+                field.clone(),
+            ));
+            self.schema_fields.insert(to.to_owned(), field);
+        }
+        Ok(())
+    }
+
+    pub fn set_transform_schema_fields(&mut self, transform_schema_fields: &bool) {
+        if self._preserve.is_none() {
+            self._preserve = Some(Preserve {
+                schema_fields: None,
+                indexes: None,
+                partial_indexes: None,
+                renamed_fields: None,
+                auto_delete_after_seconds: None,
+                transform_schema_fields: false,
+            });
+        }
+        self._preserve.as_mut().unwrap().transform_schema_fields = *transform_schema_fields;
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        self.check_indexes(db)?;
+
         RecordDao::db_create_table(db, self).await?;
 
         let mut create_indexes_fut = Vec::with_capacity(self.indexes.len());
@@ -173,6 +337,21 @@ impl CollectionDao {
         }
         future::try_join_all(create_indexes_fut).await?;
 
+        let mut create_partial_indexes_fut = Vec::with_capacity(self.partial_indexes.len());
+        for (index, partial_index) in &self.partial_indexes {
+            create_partial_indexes_fut.push(RecordDao::db_create_partial_index(
+                db,
+                self,
+                index,
+                partial_index,
+            ));
+        }
+        future::try_join_all(create_partial_indexes_fut).await?;
+
+        if self.auto_delete_after_seconds.is_some() {
+            RecordDao::db_set_default_ttl(db, &self.id, &self.auto_delete_after_seconds).await?;
+        }
+
         match db {
             Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
             Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
@@ -198,6 +377,43 @@ impl CollectionDao {
         }
     }
 
+    pub async fn db_select_many(db: &Db) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut collections_data = Vec::new();
+                let collections = Self::scylladb_select_many(db).await?;
+                for collection in collections {
+                    collections_data.push(Self::from_scylladb_model(&collection?)?)
+                }
+                Ok(collections_data)
+            }
+            Db::PostgresqlDb(db) => {
+                let collections = Self::postgresdb_select_many(db).await?;
+                let mut collections_data = Vec::with_capacity(collections.len());
+                for collection in &collections {
+                    collections_data.push(Self::from_postgresdb_model(collection)?);
+                }
+                Ok(collections_data)
+            }
+            Db::MysqlDb(db) => {
+                let collections = Self::mysqldb_select_many(db).await?;
+                let mut collections_data = Vec::with_capacity(collections.len());
+                for collection in &collections {
+                    collections_data.push(Self::from_mysqldb_model(collection)?);
+                }
+                Ok(collections_data)
+            }
+            Db::SqliteDb(db) => {
+                let collections = Self::sqlitedb_select_many(db).await?;
+                let mut collections_data = Vec::with_capacity(collections.len());
+                for collection in &collections {
+                    collections_data.push(Self::from_sqlitedb_model(collection)?);
+                }
+                Ok(collections_data)
+            }
+        }
+    }
+
     pub async fn db_select_many_by_project_id(db: &Db, project_id: &Uuid) -> Result<Vec<Self>> {
         match db {
             Db::ScyllaDb(db) => {
@@ -236,27 +452,74 @@ impl CollectionDao {
         }
     }
 
-    pub async fn db_update(&mut self, db: &Db) -> Result<()> {
-        if let Db::MysqlDb(_) = db {
-            for index in &self.indexes {
-                if let Some(field) = self.schema_fields.get(index) {
-                    match &field.kind {
-                        ColumnKind::Binary
-                        | ColumnKind::Varint
-                        | ColumnKind::Decimal
-                        | ColumnKind::String
-                        | ColumnKind::Json => {
-                            return Err(Error::msg(format!(
-                                "Field '{}' has type '{}' that doesn't support indexing in the data type implementation of Hyperbase for MySQL",
-                                index,
-                                field.kind.to_str()
-                            )))
-                        }
-                        _ => (),
-                    };
+    pub async fn db_select_many_with_auto_delete(db: &Db) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(_) => Ok(Vec::new()),
+            Db::PostgresqlDb(db) => {
+                let collections = Self::postgresdb_select_many_with_auto_delete(db).await?;
+                let mut collections_data = Vec::with_capacity(collections.len());
+                for collection in &collections {
+                    collections_data.push(Self::from_postgresdb_model(collection)?);
+                }
+                Ok(collections_data)
+            }
+            Db::MysqlDb(db) => {
+                let collections = Self::mysqldb_select_many_with_auto_delete(db).await?;
+                let mut collections_data = Vec::with_capacity(collections.len());
+                for collection in &collections {
+                    collections_data.push(Self::from_mysqldb_model(collection)?);
+                }
+                Ok(collections_data)
+            }
+            Db::SqliteDb(db) => {
+                let collections = Self::sqlitedb_select_many_with_auto_delete(db).await?;
+                let mut collections_data = Vec::with_capacity(collections.len());
+                for collection in &collections {
+                    collections_data.push(Self::from_sqlitedb_model(collection)?);
+                }
+                Ok(collections_data)
+            }
+        }
+    }
+
+    pub async fn db_check_schema_fields_transform(
+        &self,
+        db: &Db,
+    ) -> Result<Vec<(String, Uuid, String)>> {
+        let mut failures = Vec::new();
+        let preserve = match &self._preserve {
+            Some(preserve) => preserve,
+            None => return Ok(failures),
+        };
+        let preserve_schema_fields = match &preserve.schema_fields {
+            Some(schema_fields) => schema_fields,
+            None => return Ok(failures),
+        };
+        for (field_name, field_props) in preserve_schema_fields {
+            if let Some(field) = self.schema_fields.get(field_name) {
+                if field.kind() == field_props.kind() {
+                    continue;
+                }
+                let column = preserve
+                    .renamed_fields
+                    .as_ref()
+                    .and_then(|renamed_fields| {
+                        renamed_fields.iter().find(|(_, to, _)| to == field_name)
+                    })
+                    .map_or(field_name.as_str(), |(from, _, _)| from.as_str());
+                for (id, message) in
+                    RecordDao::db_check_column_transform(db, &self.id, column, field_props, field)
+                        .await?
+                {
+                    failures.push((field_name.to_owned(), id, message));
                 }
             }
         }
+        Ok(failures)
+    }
+
+    pub async fn db_update(&mut self, db: &Db) -> Result<()> {
+        self.check_indexes(db)?;
 
         let is_preserve_schema_fields_exist = self
             ._preserve
@@ -266,6 +529,32 @@ impl CollectionDao {
             ._preserve
             .as_ref()
             .is_some_and(|preserve| preserve.indexes.as_ref().is_some());
+        let is_preserve_partial_indexes_exist = self
+            ._preserve
+            .as_ref()
+            .is_some_and(|preserve| preserve.partial_indexes.as_ref().is_some());
+
+        if is_preserve_partial_indexes_exist {
+            let mut drop_partial_indexes_fut = Vec::new();
+            for (index, partial_index) in self
+                ._preserve
+                .as_ref()
+                .unwrap()
+                .partial_indexes
+                .as_ref()
+                .unwrap()
+            {
+                if self.partial_indexes.get(index) != Some(partial_index) {
+                    drop_partial_indexes_fut.push(RecordDao::db_drop_partial_index(
+                        db,
+                        &self.id,
+                        index,
+                        partial_index,
+                    ));
+                }
+            }
+            future::try_join_all(drop_partial_indexes_fut).await?;
+        }
 
         if is_preserve_indexes_exist {
             let mut drop_indexes_fut = Vec::new();
@@ -277,6 +566,16 @@ impl CollectionDao {
             future::try_join_all(drop_indexes_fut).await?;
         }
 
+        if let Some(renamed_fields) = self
+            ._preserve
+            .as_ref()
+            .and_then(|preserve| preserve.renamed_fields.as_ref())
+        {
+            for (from, to, field) in renamed_fields {
+                RecordDao::db_rename_column(db, &self.id, from, to, field).await?;
+            }
+        }
+
         if is_preserve_schema_fields_exist {
             let mut columns_change_type = HashMap::new();
             let mut columns_drop = HashSet::new();
@@ -291,7 +590,7 @@ impl CollectionDao {
                 match self.schema_fields.get(field_name) {
                     Some(field) => {
                         if field.kind() != field_props.kind() {
-                            columns_change_type.insert(field_name.to_owned(), *field);
+                            columns_change_type.insert(field_name.to_owned(), field.clone());
                         }
                     }
                     None => {
@@ -300,7 +599,11 @@ impl CollectionDao {
                 };
             }
             if !columns_change_type.is_empty() {
-                RecordDao::db_change_columns_type(db, &self.id, &columns_change_type).await?;
+                if self._preserve.as_ref().unwrap().transform_schema_fields {
+                    self.db_transform_columns(db, &columns_change_type).await?;
+                } else {
+                    RecordDao::db_change_columns_type(db, &self.id, &columns_change_type).await?;
+                }
             }
             if !columns_drop.is_empty() {
                 RecordDao::db_drop_columns(db, &self.id, &columns_drop).await?;
@@ -317,7 +620,7 @@ impl CollectionDao {
                     .unwrap()
                     .contains_key(field_name)
                 {
-                    columns_add.insert(field_name.to_owned(), *field_props);
+                    columns_add.insert(field_name.to_owned(), field_props.clone());
                 }
             }
             if !columns_add.is_empty() {
@@ -343,6 +646,41 @@ impl CollectionDao {
             future::try_join_all(create_indexes_fut).await?;
         }
 
+        if is_preserve_partial_indexes_exist {
+            let mut create_partial_indexes_fut = Vec::new();
+            for (index, partial_index) in &self.partial_indexes {
+                if self
+                    ._preserve
+                    .as_ref()
+                    .unwrap()
+                    .partial_indexes
+                    .as_ref()
+                    .unwrap()
+                    .get(index)
+                    != Some(partial_index)
+                {
+                    create_partial_indexes_fut.push(RecordDao::db_create_partial_index(
+                        db,
+                        self,
+                        index,
+                        partial_index,
+                    ));
+                }
+            }
+            future::try_join_all(create_partial_indexes_fut).await?;
+        }
+
+        if let Some(auto_delete_after_seconds) = self
+            ._preserve
+            .as_ref()
+            .and_then(|preserve| preserve.auto_delete_after_seconds)
+        {
+            if auto_delete_after_seconds != self.auto_delete_after_seconds {
+                RecordDao::db_set_default_ttl(db, &self.id, &self.auto_delete_after_seconds)
+                    .await?;
+            }
+        }
+
         self.updated_at = Utc::now();
 
         match db {
@@ -355,6 +693,7 @@ impl CollectionDao {
 
     pub async fn db_delete(db: &Db, id: &Uuid) -> Result<()> {
         RecordDao::db_drop_table(db, id).await?;
+        CollectionPluginDao::db_delete(db, id).await?;
 
         match db {
             Db::ScyllaDb(db) => Self::scylladb_delete(db, id).await,
@@ -364,6 +703,100 @@ impl CollectionDao {
         }
     }
 
+    fn check_indexes(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(_) if !self.partial_indexes.is_empty() => {
+                return Err(Error::msg(
+                    "Partial indexes are not supported in the data type implementation of Hyperbase for ScyllaDB",
+                ));
+            }
+            Db::MysqlDb(_) => {
+                for index in self.indexes.iter().chain(
+                    self.partial_indexes
+                        .values()
+                        .flat_map(|partial_index| partial_index.fields()),
+                ) {
+                    if let Some(field) = self.schema_fields.get(index) {
+                        match &field.kind {
+                            ColumnKind::Binary
+                            | ColumnKind::Varint
+                            | ColumnKind::Decimal
+                            | ColumnKind::String
+                            | ColumnKind::Json => {
+                                return Err(Error::msg(format!(
+                                    "Field '{}' has type '{}' that doesn't support indexing in the data type implementation of Hyperbase for MySQL",
+                                    index,
+                                    field.kind.to_str()
+                                )))
+                            }
+                            _ => (),
+                        };
+                    }
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    async fn db_transform_columns(
+        &self,
+        db: &Db,
+        columns: &HashMap<String, SchemaFieldProps>,
+    ) -> Result<()> {
+        let preserve = self._preserve.as_ref().unwrap();
+        let preserve_schema_fields = preserve.schema_fields.as_ref().unwrap();
+        let preserve_indexes = preserve.indexes.as_ref().unwrap_or(&self.indexes);
+        let preserve_partial_indexes = preserve
+            .partial_indexes
+            .as_ref()
+            .unwrap_or(&self.partial_indexes);
+
+        let indexes = columns
+            .keys()
+            .filter(|column| self.indexes.contains(*column) && preserve_indexes.contains(*column))
+            .collect::<Vec<_>>();
+        let partial_indexes = self
+            .partial_indexes
+            .iter()
+            .filter(|(index, partial_index)| {
+                preserve_partial_indexes.get(*index) == Some(partial_index)
+                    && columns.keys().any(|column| {
+                        partial_index.fields().contains(column)
+                            || partial_index.predicate().contains_key(column)
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        for index in &indexes {
+            RecordDao::db_drop_index(db, &self.id, index).await?;
+        }
+        for (index, partial_index) in &partial_indexes {
+            RecordDao::db_drop_partial_index(db, &self.id, index, partial_index).await?;
+        }
+
+        for (column, props) in columns {
+            let preserve_props = match preserve_schema_fields.get(column) {
+                Some(props) => props,
+                None => {
+                    return Err(Error::msg(format!(
+                        "Field '{column}' is not exist in the previous schema fields"
+                    )))
+                }
+            };
+            RecordDao::db_transform_column(db, &self.id, column, preserve_props, props).await?;
+        }
+
+        for index in &indexes {
+            RecordDao::db_create_index(db, &self.id, index).await?;
+        }
+        for (index, partial_index) in &partial_indexes {
+            RecordDao::db_create_partial_index(db, self, index, partial_index).await?;
+        }
+
+        Ok(())
+    }
+
     async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
         db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
         Ok(())
@@ -376,6 +809,15 @@ impl CollectionDao {
             .first_row_typed::<CollectionScyllaModel>()?)
     }
 
+    async fn scylladb_select_many(
+        db: &ScyllaDb,
+    ) -> Result<ScyllaTypedRowIter<CollectionScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY, &[])
+            .await?
+            .rows_typed::<CollectionScyllaModel>()?)
+    }
+
     async fn scylladb_select_many_by_project_id(
         db: &ScyllaDb,
         project_id: &Uuid,
@@ -398,6 +840,9 @@ impl CollectionDao {
                     .map(|(key, value)| (key.to_owned(), value.to_scylladb_model()))
                     .collect::<HashMap<_, _>>(),
                 &self.indexes,
+                &self.max_field_size,
+                &self.max_record_size,
+                &self.auto_delete_after_seconds,
                 &self.id,
             ),
         )
@@ -420,7 +865,11 @@ impl CollectionDao {
                 .bind(model.project_id())
                 .bind(model.name())
                 .bind(model.schema_fields())
-                .bind(model.indexes()),
+                .bind(model.indexes())
+                .bind(model.partial_indexes())
+                .bind(model.max_field_size())
+                .bind(model.max_record_size())
+                .bind(model.auto_delete_after_seconds()),
         )
         .await?;
         Ok(())
@@ -432,6 +881,10 @@ impl CollectionDao {
             .await?)
     }
 
+    async fn postgresdb_select_many(db: &PostgresDb) -> Result<Vec<CollectionPostgresModel>> {
+        Ok(db.fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY)).await?)
+    }
+
     async fn postgresdb_select_many_by_project_id(
         db: &PostgresDb,
         project_id: &Uuid,
@@ -441,6 +894,14 @@ impl CollectionDao {
             .await?)
     }
 
+    async fn postgresdb_select_many_with_auto_delete(
+        db: &PostgresDb,
+    ) -> Result<Vec<CollectionPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_WITH_AUTO_DELETE))
+            .await?)
+    }
+
     async fn postgresdb_update(&self, db: &PostgresDb) -> Result<()> {
         let model = self.to_postgresdb_model();
         db.execute(
@@ -449,6 +910,10 @@ impl CollectionDao {
                 .bind(model.name())
                 .bind(model.schema_fields())
                 .bind(model.indexes())
+                .bind(model.partial_indexes())
+                .bind(model.max_field_size())
+                .bind(model.max_record_size())
+                .bind(model.auto_delete_after_seconds())
                 .bind(model.id()),
         )
         .await?;
@@ -470,7 +935,11 @@ impl CollectionDao {
                 .bind(model.project_id())
                 .bind(model.name())
                 .bind(model.schema_fields())
-                .bind(model.indexes()),
+                .bind(model.indexes())
+                .bind(model.partial_indexes())
+                .bind(model.max_field_size())
+                .bind(model.max_record_size())
+                .bind(model.auto_delete_after_seconds()),
         )
         .await?;
         Ok(())
@@ -480,6 +949,10 @@ impl CollectionDao {
         Ok(db.fetch_one(sqlx::query_as(MYSQL_SELECT).bind(id)).await?)
     }
 
+    async fn mysqldb_select_many(db: &MysqlDb) -> Result<Vec<CollectionMysqlModel>> {
+        Ok(db.fetch_all(sqlx::query_as(MYSQL_SELECT_MANY)).await?)
+    }
+
     async fn mysqldb_select_many_by_project_id(
         db: &MysqlDb,
         project_id: &Uuid,
@@ -489,6 +962,14 @@ impl CollectionDao {
             .await?)
     }
 
+    async fn mysqldb_select_many_with_auto_delete(
+        db: &MysqlDb,
+    ) -> Result<Vec<CollectionMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_WITH_AUTO_DELETE))
+            .await?)
+    }
+
     async fn mysqldb_update(&self, db: &MysqlDb) -> Result<()> {
         let model = self.to_mysqldb_model();
         db.execute(
@@ -497,6 +978,10 @@ impl CollectionDao {
                 .bind(model.name())
                 .bind(model.schema_fields())
                 .bind(model.indexes())
+                .bind(model.partial_indexes())
+                .bind(model.max_field_size())
+                .bind(model.max_record_size())
+                .bind(model.auto_delete_after_seconds())
                 .bind(model.id()),
         )
         .await?;
@@ -518,7 +1003,11 @@ impl CollectionDao {
                 .bind(model.project_id())
                 .bind(model.name())
                 .bind(model.schema_fields())
-                .bind(model.indexes()),
+                .bind(model.indexes())
+                .bind(model.partial_indexes())
+                .bind(model.max_field_size())
+                .bind(model.max_record_size())
+                .bind(model.auto_delete_after_seconds()),
         )
         .await?;
         Ok(())
@@ -528,6 +1017,10 @@ impl CollectionDao {
         Ok(db.fetch_one(sqlx::query_as(SQLITE_SELECT).bind(id)).await?)
     }
 
+    async fn sqlitedb_select_many(db: &SqliteDb) -> Result<Vec<CollectionSqliteModel>> {
+        Ok(db.fetch_all(sqlx::query_as(SQLITE_SELECT_MANY)).await?)
+    }
+
     async fn sqlitedb_select_many_by_project_id(
         db: &SqliteDb,
         project_id: &Uuid,
@@ -537,6 +1030,14 @@ impl CollectionDao {
             .await?)
     }
 
+    async fn sqlitedb_select_many_with_auto_delete(
+        db: &SqliteDb,
+    ) -> Result<Vec<CollectionSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_WITH_AUTO_DELETE))
+            .await?)
+    }
+
     async fn sqlitedb_update(&self, db: &SqliteDb) -> Result<()> {
         let model = self.to_sqlitedb_model();
         db.execute(
@@ -545,6 +1046,10 @@ impl CollectionDao {
                 .bind(model.name())
                 .bind(model.schema_fields())
                 .bind(model.indexes())
+                .bind(model.partial_indexes())
+                .bind(model.max_field_size())
+                .bind(model.max_record_size())
+                .bind(model.auto_delete_after_seconds())
                 .bind(model.id()),
         )
         .await?;
@@ -576,6 +1081,10 @@ impl CollectionDao {
                 Some(indexes) => indexes.to_owned(),
                 None => HashSet::new(),
             },
+            partial_indexes: HashMap::new(),
+            max_field_size: *model.max_field_size(),
+            max_record_size: *model.max_record_size(),
+            auto_delete_after_seconds: *model.auto_delete_after_seconds(),
             _preserve: None,
         })
     }
@@ -597,6 +1106,9 @@ impl CollectionDao {
             } else {
                 None
             },
+            &self.max_field_size,
+            &self.max_record_size,
+            &self.auto_delete_after_seconds,
         )
     }
 
@@ -609,6 +1121,10 @@ impl CollectionDao {
             };
             schema_fields.insert(key.to_owned(), value);
         }
+        let mut partial_indexes = HashMap::with_capacity(model.partial_indexes().len());
+        for (key, value) in &model.partial_indexes().0 {
+            partial_indexes.insert(key.to_owned(), PartialIndex::from_postgresdb_model(value)?);
+        }
         Ok(Self {
             id: *model.id(),
             created_at: *model.created_at(),
@@ -617,6 +1133,10 @@ impl CollectionDao {
             name: model.name().to_owned(),
             schema_fields,
             indexes: HashSet::from_iter(model.indexes().to_owned()),
+            partial_indexes,
+            max_field_size: *model.max_field_size(),
+            max_record_size: *model.max_record_size(),
+            auto_delete_after_seconds: *model.auto_delete_after_seconds(),
             _preserve: None,
         })
     }
@@ -635,6 +1155,15 @@ impl CollectionDao {
                     .collect(),
             ),
             &Vec::from_iter(self.indexes.to_owned()),
+            &sqlx::types::Json(
+                self.partial_indexes
+                    .iter()
+                    .map(|(key, value)| (key.to_owned(), value.to_postgresdb_model()))
+                    .collect(),
+            ),
+            &self.max_field_size,
+            &self.max_record_size,
+            &self.auto_delete_after_seconds,
         )
     }
 
@@ -647,6 +1176,10 @@ impl CollectionDao {
             };
             schema_fields.insert(key.to_owned(), value);
         }
+        let mut partial_indexes = HashMap::with_capacity(model.partial_indexes().len());
+        for (key, value) in &model.partial_indexes().0 {
+            partial_indexes.insert(key.to_owned(), PartialIndex::from_mysqldb_model(value)?);
+        }
         Ok(Self {
             id: *model.id(),
             created_at: *model.created_at(),
@@ -655,6 +1188,10 @@ impl CollectionDao {
             name: model.name().to_owned(),
             schema_fields,
             indexes: model.indexes().0.to_owned(),
+            partial_indexes,
+            max_field_size: *model.max_field_size(),
+            max_record_size: *model.max_record_size(),
+            auto_delete_after_seconds: *model.auto_delete_after_seconds(),
             _preserve: None,
         })
     }
@@ -673,6 +1210,15 @@ impl CollectionDao {
                     .collect(),
             ),
             &sqlx::types::Json(self.indexes.to_owned()),
+            &sqlx::types::Json(
+                self.partial_indexes
+                    .iter()
+                    .map(|(key, value)| (key.to_owned(), value.to_mysqldb_model()))
+                    .collect(),
+            ),
+            &self.max_field_size,
+            &self.max_record_size,
+            &self.auto_delete_after_seconds,
         )
     }
 
@@ -685,6 +1231,10 @@ impl CollectionDao {
             };
             schema_fields.insert(key.to_owned(), value);
         }
+        let mut partial_indexes = HashMap::with_capacity(model.partial_indexes().len());
+        for (key, value) in &model.partial_indexes().0 {
+            partial_indexes.insert(key.to_owned(), PartialIndex::from_sqlitedb_model(value)?);
+        }
         Ok(Self {
             id: *model.id(),
             created_at: *model.created_at(),
@@ -693,6 +1243,10 @@ impl CollectionDao {
             name: model.name().to_owned(),
             schema_fields,
             indexes: model.indexes().0.to_owned(),
+            partial_indexes,
+            max_field_size: *model.max_field_size(),
+            max_record_size: *model.max_record_size(),
+            auto_delete_after_seconds: *model.auto_delete_after_seconds(),
             _preserve: None,
         })
     }
@@ -711,21 +1265,39 @@ impl CollectionDao {
                     .collect(),
             ),
             &sqlx::types::Json(self.indexes.to_owned()),
+            &sqlx::types::Json(
+                self.partial_indexes
+                    .iter()
+                    .map(|(key, value)| (key.to_owned(), value.to_sqlitedb_model()))
+                    .collect(),
+            ),
+            &self.max_field_size,
+            &self.max_record_size,
+            &self.auto_delete_after_seconds,
         )
     }
 }
 
-#[derive(Serialize, Clone, Copy)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct SchemaFieldProps {
     kind: ColumnKind,
     required: bool,
+    validation: SchemaFieldValidation,
+    computed: Option<SchemaFieldComputed>,
 }
 
 impl SchemaFieldProps {
-    pub fn new(kind: &ColumnKind, required: &bool) -> Self {
+    pub fn new(
+        kind: &ColumnKind,
+        required: &bool,
+        validation: &SchemaFieldValidation,
+        computed: &Option<SchemaFieldComputed>,
+    ) -> Self {
         Self {
-            kind: *kind,
+            kind: kind.to_owned(),
             required: *required,
+            validation: validation.clone(),
+            computed: computed.clone(),
         }
     }
 
@@ -737,14 +1309,34 @@ impl SchemaFieldProps {
         &self.required
     }
 
+    pub fn validation(&self) -> &SchemaFieldValidation {
+        &self.validation
+    }
+
+    pub fn computed(&self) -> &Option<SchemaFieldComputed> {
+        &self.computed
+    }
+
     fn from_scylladb_model(model: &SchemaFieldPropsScyllaModel) -> Result<Self> {
-        let kind = match ColumnKind::from_str(model.kind()) {
+        let kind = match ColumnKind::from_str_with_variants(model.kind(), model.enum_variants()) {
             Ok(kind) => kind,
             Err(err) => return Err(err.into()),
         };
         Ok(Self {
             kind,
             required: *model.required(),
+            validation: SchemaFieldValidation {
+                min_length: *model.min_length(),
+                max_length: *model.max_length(),
+                min: *model.min(),
+                max: *model.max(),
+                pattern: model.pattern().clone(),
+            },
+            computed: SchemaFieldComputed::from_model(
+                model.computed_op(),
+                model.computed_fields(),
+                model.computed_separator(),
+            )?,
         })
     }
 
@@ -753,17 +1345,38 @@ impl SchemaFieldProps {
             self.kind.to_str(),
             &self.kind.to_scylladb_model(),
             &self.required,
+            &self.kind.enum_variants().cloned(),
+            &self.validation.min_length,
+            &self.validation.max_length,
+            &self.validation.min,
+            &self.validation.max,
+            &self.validation.pattern,
+            &self.computed.as_ref().map(|c| c.op.to_str().to_owned()),
+            &self.computed.as_ref().map(|c| c.fields.clone()),
+            &self.computed.as_ref().and_then(|c| c.separator.clone()),
         )
     }
 
     fn from_postgresdb_model(model: &SchemaFieldPropsPostgresModel) -> Result<Self> {
-        let kind = match ColumnKind::from_str(model.kind()) {
+        let kind = match ColumnKind::from_str_with_variants(model.kind(), model.enum_variants()) {
             Ok(kind) => kind,
             Err(err) => return Err(err.into()),
         };
         Ok(Self {
             kind,
             required: *model.required(),
+            validation: SchemaFieldValidation {
+                min_length: *model.min_length(),
+                max_length: *model.max_length(),
+                min: *model.min(),
+                max: *model.max(),
+                pattern: model.pattern().clone(),
+            },
+            computed: SchemaFieldComputed::from_model(
+                model.computed_op(),
+                model.computed_fields(),
+                model.computed_separator(),
+            )?,
         })
     }
 
@@ -772,17 +1385,38 @@ impl SchemaFieldProps {
             self.kind.to_str(),
             &self.kind.to_postgresdb_model(),
             &self.required,
+            &self.kind.enum_variants().cloned(),
+            &self.validation.min_length,
+            &self.validation.max_length,
+            &self.validation.min,
+            &self.validation.max,
+            &self.validation.pattern,
+            &self.computed.as_ref().map(|c| c.op.to_str().to_owned()),
+            &self.computed.as_ref().map(|c| c.fields.clone()),
+            &self.computed.as_ref().and_then(|c| c.separator.clone()),
         )
     }
 
     fn from_mysqldb_model(model: &SchemaFieldPropsMysqlModel) -> Result<Self> {
-        let kind = match ColumnKind::from_str(model.kind()) {
+        let kind = match ColumnKind::from_str_with_variants(model.kind(), model.enum_variants()) {
             Ok(kind) => kind,
             Err(err) => return Err(err.into()),
         };
         Ok(Self {
             kind,
             required: *model.required(),
+            validation: SchemaFieldValidation {
+                min_length: *model.min_length(),
+                max_length: *model.max_length(),
+                min: *model.min(),
+                max: *model.max(),
+                pattern: model.pattern().clone(),
+            },
+            computed: SchemaFieldComputed::from_model(
+                model.computed_op(),
+                model.computed_fields(),
+                model.computed_separator(),
+            )?,
         })
     }
 
@@ -791,17 +1425,38 @@ impl SchemaFieldProps {
             self.kind.to_str(),
             &self.kind.to_mysqldb_model(),
             &self.required,
+            &self.kind.enum_variants().cloned(),
+            &self.validation.min_length,
+            &self.validation.max_length,
+            &self.validation.min,
+            &self.validation.max,
+            &self.validation.pattern,
+            &self.computed.as_ref().map(|c| c.op.to_str().to_owned()),
+            &self.computed.as_ref().map(|c| c.fields.clone()),
+            &self.computed.as_ref().and_then(|c| c.separator.clone()),
         )
     }
 
     fn from_sqlitedb_model(model: &SchemaFieldPropsSqliteModel) -> Result<Self> {
-        let kind = match ColumnKind::from_str(model.kind()) {
+        let kind = match ColumnKind::from_str_with_variants(model.kind(), model.enum_variants()) {
             Ok(kind) => kind,
             Err(err) => return Err(err.into()),
         };
         Ok(Self {
             kind,
             required: *model.required(),
+            validation: SchemaFieldValidation {
+                min_length: *model.min_length(),
+                max_length: *model.max_length(),
+                min: *model.min(),
+                max: *model.max(),
+                pattern: model.pattern().clone(),
+            },
+            computed: SchemaFieldComputed::from_model(
+                model.computed_op(),
+                model.computed_fields(),
+                model.computed_separator(),
+            )?,
         })
     }
 
@@ -810,11 +1465,781 @@ impl SchemaFieldProps {
             self.kind.to_str(),
             &self.kind.to_sqlitedb_model(),
             &self.required,
+            &self.kind.enum_variants().cloned(),
+            &self.validation.min_length,
+            &self.validation.max_length,
+            &self.validation.min,
+            &self.validation.max,
+            &self.validation.pattern,
+            &self.computed.as_ref().map(|c| c.op.to_str().to_owned()),
+            &self.computed.as_ref().map(|c| c.fields.clone()),
+            &self.computed.as_ref().and_then(|c| c.separator.clone()),
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct SchemaFieldValidation {
+    min_length: Option<i64>,
+    max_length: Option<i64>,
+    min: Option<f64>,
+    max: Option<f64>,
+    pattern: Option<String>,
+}
+
+impl SchemaFieldValidation {
+    pub fn new(
+        kind: &ColumnKind,
+        min_length: &Option<i64>,
+        max_length: &Option<i64>,
+        min: &Option<f64>,
+        max: &Option<f64>,
+        pattern: &Option<String>,
+    ) -> Result<Self> {
+        if min_length.is_some() || max_length.is_some() {
+            match kind {
+                ColumnKind::String
+                | ColumnKind::Enum(_)
+                | ColumnKind::Binary
+                | ColumnKind::Json => (),
+                _ => {
+                    return Err(Error::msg(format!(
+                        "Length validation is not supported for kind '{}'",
+                        kind.to_str()
+                    )))
+                }
+            }
+        }
+        if min_length.is_some_and(|min_length| min_length < 0)
+            || max_length.is_some_and(|max_length| max_length < 0)
+        {
+            return Err(Error::msg("Length validation can't be negative"));
+        }
+        if let (Some(min_length), Some(max_length)) = (min_length, max_length) {
+            if min_length > max_length {
+                return Err(Error::msg(
+                    "Value of 'min_length' can't be greater than 'max_length'",
+                ));
+            }
+        }
+
+        if min.is_some() || max.is_some() {
+            match kind {
+                ColumnKind::TinyInt
+                | ColumnKind::SmallInt
+                | ColumnKind::Int
+                | ColumnKind::BigInt
+                | ColumnKind::Varint
+                | ColumnKind::Float
+                | ColumnKind::Double
+                | ColumnKind::Decimal => (),
+                _ => {
+                    return Err(Error::msg(format!(
+                        "Range validation is not supported for kind '{}'",
+                        kind.to_str()
+                    )))
+                }
+            }
+        }
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err(Error::msg("Value of 'min' can't be greater than 'max'"));
+            }
+        }
+
+        if let Some(pattern) = pattern {
+            match kind {
+                ColumnKind::String | ColumnKind::Enum(_) => (),
+                _ => {
+                    return Err(Error::msg(format!(
+                        "Pattern validation is not supported for kind '{}'",
+                        kind.to_str()
+                    )))
+                }
+            }
+            if let Err(err) = Regex::new(pattern) {
+                return Err(Error::msg(format!("Pattern '{pattern}' is invalid: {err}")));
+            }
+        }
+
+        Ok(Self {
+            min_length: *min_length,
+            max_length: *max_length,
+            min: *min,
+            max: *max,
+            pattern: pattern.clone(),
+        })
+    }
+
+    pub fn min_length(&self) -> &Option<i64> {
+        &self.min_length
+    }
+
+    pub fn max_length(&self) -> &Option<i64> {
+        &self.max_length
+    }
+
+    pub fn min(&self) -> &Option<f64> {
+        &self.min
+    }
+
+    pub fn max(&self) -> &Option<f64> {
+        &self.max
+    }
+
+    pub fn pattern(&self) -> &Option<String> {
+        &self.pattern
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min_length.is_none()
+            && self.max_length.is_none()
+            && self.min.is_none()
+            && self.max.is_none()
+            && self.pattern.is_none()
+    }
+
+    pub fn validate(&self, value: &ColumnValue) -> Result<(), FieldValidationError> {
+        let length = match value {
+            ColumnValue::String(Some(value)) | ColumnValue::Json(Some(value)) => {
+                Some(value.chars().count())
+            }
+            ColumnValue::Binary(Some(value)) => Some(value.len()),
+            _ => None,
+        };
+        if let Some(length) = length {
+            if let Some(min_length) = self.min_length {
+                if (length as i64) < min_length {
+                    return Err(FieldValidationError::new(
+                        "min_length",
+                        &format!("Length must be at least {min_length}, but found {length}"),
+                    ));
+                }
+            }
+            if let Some(max_length) = self.max_length {
+                if length as i64 > max_length {
+                    return Err(FieldValidationError::new(
+                        "max_length",
+                        &format!("Length must be at most {max_length}, but found {length}"),
+                    ));
+                }
+            }
+        }
+
+        let number = value
+            .to_f64()
+            .map_err(|err| FieldValidationError::new("invalid_value", &err.to_string()))?;
+        if let Some(number) = number {
+            if let Some(min) = self.min {
+                if number < min {
+                    return Err(FieldValidationError::new(
+                        "min",
+                        &format!(
+                            "Value must be greater than or equal to {min}, but found {number}"
+                        ),
+                    ));
+                }
+            }
+            if let Some(max) = self.max {
+                if number > max {
+                    return Err(FieldValidationError::new(
+                        "max",
+                        &format!("Value must be less than or equal to {max}, but found {number}"),
+                    ));
+                }
+            }
+        }
+
+        if let (Some(pattern), ColumnValue::String(Some(value))) = (&self.pattern, value) {
+            let regex = Regex::new(pattern)
+                .map_err(|err| FieldValidationError::new("invalid_pattern", &err.to_string()))?;
+            if !regex.is_match(value) {
+                return Err(FieldValidationError::new(
+                    "pattern",
+                    &format!("Value must match pattern '{pattern}'"),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct FieldValidationError {
+    code: String,
+    message: String,
+}
+
+impl FieldValidationError {
+    pub fn new(code: &str, message: &str) -> Self {
+        Self {
+            code: code.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ComputedOp {
+    Concat,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Sha256,
+}
+
+impl FromStr for ComputedOp {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self> {
+        match str {
+            "concat" => Ok(Self::Concat),
+            "add" => Ok(Self::Add),
+            "subtract" => Ok(Self::Subtract),
+            "multiply" => Ok(Self::Multiply),
+            "divide" => Ok(Self::Divide),
+            "sha256" => Ok(Self::Sha256),
+            _ => Err(Error::msg(format!("Unknown computed operation '{str}'"))),
+        }
+    }
+}
+
+impl ComputedOp {
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Concat => "concat",
+            Self::Add => "add",
+            Self::Subtract => "subtract",
+            Self::Multiply => "multiply",
+            Self::Divide => "divide",
+            Self::Sha256 => "sha256",
+        }
+    }
+
+    fn is_arithmetic(&self) -> bool {
+        matches!(
+            self,
+            Self::Add | Self::Subtract | Self::Multiply | Self::Divide
         )
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct SchemaFieldComputed {
+    op: ComputedOp,
+    fields: Vec<String>,
+    separator: Option<String>,
+}
+
+impl SchemaFieldComputed {
+    pub fn new(
+        kind: &ColumnKind,
+        op: &str,
+        fields: &[String],
+        separator: &Option<String>,
+    ) -> Result<Self> {
+        let op = op.parse::<ComputedOp>()?;
+        match op {
+            ComputedOp::Concat | ComputedOp::Sha256 => {
+                if *kind != ColumnKind::String {
+                    return Err(Error::msg(format!(
+                        "Computed operation '{}' must be stored in a field of kind 'string'",
+                        op.to_str()
+                    )));
+                }
+            }
+            _ => {
+                if !matches!(
+                    kind,
+                    ColumnKind::TinyInt
+                        | ColumnKind::SmallInt
+                        | ColumnKind::Int
+                        | ColumnKind::BigInt
+                        | ColumnKind::Float
+                        | ColumnKind::Double
+                ) {
+                    return Err(Error::msg(format!(
+                        "Computed operation '{}' is not supported for kind '{}'",
+                        op.to_str(),
+                        kind.to_str()
+                    )));
+                }
+            }
+        }
+        if fields.is_empty() {
+            return Err(Error::msg(
+                "Computed field must have at least one source field",
+            ));
+        }
+        if op == ComputedOp::Sha256 && fields.len() != 1 {
+            return Err(Error::msg(
+                "Computed operation 'sha256' must have exactly one source field",
+            ));
+        }
+        if op.is_arithmetic() && fields.len() < 2 {
+            return Err(Error::msg(format!(
+                "Computed operation '{}' must have at least two source fields",
+                op.to_str()
+            )));
+        }
+        if separator.is_some() && op != ComputedOp::Concat {
+            return Err(Error::msg(
+                "Separator is only supported for computed operation 'concat'",
+            ));
+        }
+
+        Ok(Self {
+            op,
+            fields: fields.to_vec(),
+            separator: separator.clone(),
+        })
+    }
+
+    fn from_model(
+        op: &Option<String>,
+        fields: &Option<Vec<String>>,
+        separator: &Option<String>,
+    ) -> Result<Option<Self>> {
+        match op {
+            Some(op) => Ok(Some(Self {
+                op: op.parse()?,
+                fields: fields.clone().unwrap_or_default(),
+                separator: separator.clone(),
+            })),
+            None => Ok(None),
+        }
+    }
+
+    pub fn op(&self) -> &ComputedOp {
+        &self.op
+    }
+
+    pub fn fields(&self) -> &Vec<String> {
+        &self.fields
+    }
+
+    pub fn separator(&self) -> &Option<String> {
+        &self.separator
+    }
+
+    pub fn check_schema_fields(schema_fields: &HashMap<String, SchemaFieldProps>) -> Result<()> {
+        for (field_name, field_props) in schema_fields {
+            let computed = match field_props.computed() {
+                Some(computed) => computed,
+                None => continue,
+            };
+            for source in &computed.fields {
+                let source_props = match schema_fields.get(source) {
+                    Some(source_props) => source_props,
+                    None => {
+                        return Err(Error::msg(format!(
+                            "Field '{source}' used by computed field '{field_name}' is not exist in the collection"
+                        )))
+                    }
+                };
+                if source_props.computed().is_some() {
+                    return Err(Error::msg(format!(
+                        "Computed field '{field_name}' can't use another computed field '{source}'"
+                    )));
+                }
+                if computed.op.is_arithmetic()
+                    && !matches!(
+                        source_props.kind(),
+                        ColumnKind::TinyInt
+                            | ColumnKind::SmallInt
+                            | ColumnKind::Int
+                            | ColumnKind::BigInt
+                            | ColumnKind::Varint
+                            | ColumnKind::Float
+                            | ColumnKind::Double
+                            | ColumnKind::Decimal
+                    )
+                {
+                    return Err(Error::msg(format!(
+                        "Field '{source}' used by computed field '{field_name}' must be numeric"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn compute(
+        &self,
+        kind: &ColumnKind,
+        data: &HashMap<String, ColumnValue>,
+    ) -> Result<ColumnValue> {
+        let mut values = Vec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            match data.get(field) {
+                Some(value) if !value.is_null() => values.push(value),
+                _ => return Ok(ColumnValue::none(kind)),
+            }
+        }
+
+        match self.op {
+            ComputedOp::Concat => {
+                let mut strs = Vec::with_capacity(values.len());
+                for value in &values {
+                    strs.push(Self::value_to_string(value)?);
+                }
+                Ok(ColumnValue::String(Some(
+                    strs.join(self.separator.as_deref().unwrap_or_default()),
+                )))
+            }
+            ComputedOp::Sha256 => {
+                let bytes = match values[0] {
+                    ColumnValue::Binary(Some(value)) => value.clone(),
+                    value => Self::value_to_string(value)?.into_bytes(),
+                };
+                Ok(ColumnValue::String(Some(format!(
+                    "{:x}",
+                    Sha256::digest(bytes)
+                ))))
+            }
+            _ => {
+                let mut result = None;
+                for value in &values {
+                    let number = match value.to_f64()? {
+                        Some(number) => number,
+                        None => return Err(Error::msg("Value is not a number")),
+                    };
+                    result = Some(match result {
+                        None => number,
+                        Some(result) => match self.op {
+                            ComputedOp::Add => result + number,
+                            ComputedOp::Subtract => result - number,
+                            ComputedOp::Multiply => result * number,
+                            _ => {
+                                if number == 0.0 {
+                                    return Err(Error::msg("Division by zero"));
+                                }
+                                result / number
+                            }
+                        },
+                    });
+                }
+                let result = result.unwrap_or_default();
+                if !result.is_finite() {
+                    return Err(Error::msg("Computed value is not a finite number"));
+                }
+                match kind {
+                    ColumnKind::Float | ColumnKind::Double => {
+                        ColumnValue::from_serde_json(kind, &serde_json::json!(result))
+                    }
+                    _ => {
+                        if result.fract() != 0.0 {
+                            return Err(Error::msg(format!(
+                                "Computed value {result} is not an integer"
+                            )));
+                        }
+                        ColumnValue::from_serde_json(kind, &serde_json::json!(result as i64))
+                    }
+                }
+            }
+        }
+    }
+
+    fn value_to_string(value: &ColumnValue) -> Result<String> {
+        match value.to_serde_json()? {
+            serde_json::Value::String(value) => Ok(value),
+            value => Ok(value.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct PartialIndex {
+    fields: Vec<String>,
+    unique: bool,
+    predicate: HashMap<String, String>,
+}
+
+impl PartialIndex {
+    pub fn new(
+        fields: &[String],
+        unique: &bool,
+        predicate: &HashMap<String, String>,
+    ) -> Result<Self> {
+        if fields.is_empty() {
+            return Err(Error::msg("Partial index must have at least one field"));
+        }
+
+        let mut normalized_predicate = HashMap::with_capacity(predicate.len());
+        for (field, op) in predicate {
+            let op = op.to_uppercase();
+            if !PARTIAL_INDEX_PREDICATE_OPERATOR.contains(&op.as_str()) {
+                return Err(Error::msg(format!(
+                    "Predicate operator '{op}' for field '{field}' is not supported, must be one of: {}",
+                    PARTIAL_INDEX_PREDICATE_OPERATOR.join(", ")
+                )));
+            }
+            normalized_predicate.insert(field.to_owned(), op);
+        }
+
+        Ok(Self {
+            fields: fields.to_vec(),
+            unique: *unique,
+            predicate: normalized_predicate,
+        })
+    }
+
+    pub fn fields(&self) -> &Vec<String> {
+        &self.fields
+    }
+
+    pub fn unique(&self) -> &bool {
+        &self.unique
+    }
+
+    pub fn predicate(&self) -> &HashMap<String, String> {
+        &self.predicate
+    }
+
+    fn from_postgresdb_model(model: &PartialIndexPostgresModel) -> Result<Self> {
+        Self::new(model.fields(), model.unique(), model.predicate())
+    }
+
+    pub fn to_postgresdb_model(&self) -> PartialIndexPostgresModel {
+        PartialIndexPostgresModel::new(&self.fields, &self.unique, &self.predicate)
+    }
+
+    fn from_mysqldb_model(model: &PartialIndexMysqlModel) -> Result<Self> {
+        Self::new(model.fields(), model.unique(), model.predicate())
+    }
+
+    pub fn to_mysqldb_model(&self) -> PartialIndexMysqlModel {
+        PartialIndexMysqlModel::new(&self.fields, &self.unique, &self.predicate)
+    }
+
+    fn from_sqlitedb_model(model: &PartialIndexSqliteModel) -> Result<Self> {
+        Self::new(model.fields(), model.unique(), model.predicate())
+    }
+
+    pub fn to_sqlitedb_model(&self) -> PartialIndexSqliteModel {
+        PartialIndexSqliteModel::new(&self.fields, &self.unique, &self.predicate)
+    }
+}
+
+const PARTIAL_INDEX_PREDICATE_OPERATOR: [&str; 2] = ["IS NULL", "IS NOT NULL"];
+
 struct Preserve {
     schema_fields: Option<HashMap<String, SchemaFieldProps>>,
     indexes: Option<HashSet<String>>,
+    partial_indexes: Option<HashMap<String, PartialIndex>>,
+    renamed_fields: Option<Vec<(String, String, SchemaFieldProps)>>,
+    auto_delete_after_seconds: Option<Option<i64>>,
+    transform_schema_fields: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::{HashMap, HashMapExt};
+
+    use crate::value::{ColumnKind, ColumnValue};
+
+    use super::{SchemaFieldComputed, SchemaFieldProps, SchemaFieldValidation};
+
+    fn validation(
+        kind: &ColumnKind,
+        min_length: Option<i64>,
+        max_length: Option<i64>,
+        min: Option<f64>,
+        max: Option<f64>,
+        pattern: Option<&str>,
+    ) -> anyhow::Result<SchemaFieldValidation> {
+        SchemaFieldValidation::new(
+            kind,
+            &min_length,
+            &max_length,
+            &min,
+            &max,
+            &pattern.map(str::to_owned),
+        )
+    }
+
+    fn code(validation: &SchemaFieldValidation, value: &ColumnValue) -> Option<String> {
+        validation
+            .validate(value)
+            .err()
+            .map(|err| err.code().to_owned())
+    }
+
+    #[test]
+    fn validation_rejects_rules_that_dont_fit_the_kind() {
+        assert!(validation(&ColumnKind::Int, Some(1), None, None, None, None).is_err());
+        assert!(validation(&ColumnKind::String, None, None, Some(1.0), None, None).is_err());
+        assert!(validation(&ColumnKind::Int, None, None, None, None, Some("a")).is_err());
+        assert!(validation(&ColumnKind::String, Some(-1), None, None, None, None).is_err());
+        assert!(validation(&ColumnKind::String, Some(3), Some(2), None, None, None).is_err());
+        assert!(validation(&ColumnKind::Int, None, None, Some(3.0), Some(2.0), None).is_err());
+        assert!(validation(&ColumnKind::String, None, None, None, None, Some("(")).is_err());
+        assert!(
+            validation(&ColumnKind::String, None, None, None, None, None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn validation_checks_length_range_and_pattern() {
+        let string = |value: &str| ColumnValue::String(Some(value.to_owned()));
+
+        let length = validation(&ColumnKind::String, Some(2), Some(3), None, None, None).unwrap();
+        assert_eq!(code(&length, &string("a")).as_deref(), Some("min_length"));
+        assert_eq!(code(&length, &string("ab")), None);
+        assert_eq!(code(&length, &string("äöü")), None);
+        assert_eq!(
+            code(&length, &string("abcd")).as_deref(),
+            Some("max_length")
+        );
+
+        let range = validation(&ColumnKind::Int, None, None, Some(0.0), Some(10.0), None).unwrap();
+        assert_eq!(
+            code(&range, &ColumnValue::Integer(Some(-1))).as_deref(),
+            Some("min")
+        );
+        assert_eq!(code(&range, &ColumnValue::Integer(Some(10))), None);
+        assert_eq!(
+            code(&range, &ColumnValue::Integer(Some(11))).as_deref(),
+            Some("max")
+        );
+
+        let pattern = validation(
+            &ColumnKind::String,
+            None,
+            None,
+            None,
+            None,
+            Some("^[a-z]+$"),
+        )
+        .unwrap();
+        assert_eq!(code(&pattern, &string("abc")), None);
+        assert_eq!(code(&pattern, &string("Abc")).as_deref(), Some("pattern"));
+    }
+
+    #[test]
+    fn validation_skips_null_values() {
+        let validation =
+            validation(&ColumnKind::String, Some(1), None, None, None, Some("^a")).unwrap();
+        assert_eq!(code(&validation, &ColumnValue::String(None)), None);
+    }
+
+    fn computed(kind: &ColumnKind, op: &str, fields: &[&str]) -> SchemaFieldComputed {
+        let fields: Vec<_> = fields.iter().map(|field| field.to_string()).collect();
+        SchemaFieldComputed::new(kind, op, &fields, &None).unwrap()
+    }
+
+    #[test]
+    fn computed_rejects_invalid_definitions() {
+        let fields = |fields: &[&str]| fields.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+        let new = |kind: &ColumnKind, op: &str, sources: &[&str], separator: Option<&str>| {
+            SchemaFieldComputed::new(kind, op, &fields(sources), &separator.map(str::to_owned))
+        };
+        assert!(new(&ColumnKind::String, "unknown", &["a"], None).is_err());
+        assert!(new(&ColumnKind::Int, "concat", &["a"], None).is_err());
+        assert!(new(&ColumnKind::String, "add", &["a", "b"], None).is_err());
+        assert!(new(&ColumnKind::String, "concat", &[], None).is_err());
+        assert!(new(&ColumnKind::String, "sha256", &["a", "b"], None).is_err());
+        assert!(new(&ColumnKind::Int, "add", &["a"], None).is_err());
+        assert!(new(&ColumnKind::Int, "add", &["a", "b"], Some("-")).is_err());
+        assert!(new(&ColumnKind::String, "concat", &["a", "b"], Some("-")).is_ok());
+    }
+
+    #[test]
+    fn computed_checks_its_source_fields() {
+        let props = |kind: ColumnKind, computed: Option<SchemaFieldComputed>| {
+            SchemaFieldProps::new(&kind, &false, &SchemaFieldValidation::default(), &computed)
+        };
+        let mut schema_fields = HashMap::new();
+        schema_fields.insert("a".to_owned(), props(ColumnKind::Int, None));
+        schema_fields.insert("s".to_owned(), props(ColumnKind::String, None));
+        schema_fields.insert(
+            "sum".to_owned(),
+            props(
+                ColumnKind::Int,
+                Some(computed(&ColumnKind::Int, "add", &["a", "a"])),
+            ),
+        );
+        assert!(SchemaFieldComputed::check_schema_fields(&schema_fields).is_ok());
+
+        for sources in [["a", "missing"], ["a", "s"], ["a", "sum"]] {
+            let mut schema_fields = schema_fields.clone();
+            schema_fields.insert(
+                "other".to_owned(),
+                props(
+                    ColumnKind::Int,
+                    Some(computed(&ColumnKind::Int, "add", &sources)),
+                ),
+            );
+            assert!(
+                SchemaFieldComputed::check_schema_fields(&schema_fields).is_err(),
+                "{sources:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn computed_derives_values() {
+        let mut data = HashMap::new();
+        data.insert(
+            "first".to_owned(),
+            ColumnValue::String(Some("Ada".to_owned())),
+        );
+        data.insert(
+            "last".to_owned(),
+            ColumnValue::String(Some("Lovelace".to_owned())),
+        );
+        data.insert("price".to_owned(), ColumnValue::Integer(Some(7)));
+        data.insert("qty".to_owned(), ColumnValue::Integer(Some(3)));
+        data.insert("zero".to_owned(), ColumnValue::Integer(Some(0)));
+        data.insert("none".to_owned(), ColumnValue::Integer(None));
+
+        let concat = SchemaFieldComputed::new(
+            &ColumnKind::String,
+            "concat",
+            &["first".to_owned(), "last".to_owned()],
+            &Some(" ".to_owned()),
+        )
+        .unwrap();
+        assert!(
+            concat.compute(&ColumnKind::String, &data).unwrap()
+                == ColumnValue::String(Some("Ada Lovelace".to_owned()))
+        );
+        assert!(
+            computed(&ColumnKind::Int, "multiply", &["price", "qty"])
+                .compute(&ColumnKind::Int, &data)
+                .unwrap()
+                == ColumnValue::Integer(Some(21))
+        );
+        assert!(
+            computed(&ColumnKind::String, "sha256", &["first"])
+                .compute(&ColumnKind::String, &data)
+                .unwrap()
+                == ColumnValue::String(Some(
+                    "99a563ab2f6e21e96998f9fddd2a2bab82b70ac019579502b8d7fc0032ff62bb".to_owned()
+                ))
+        );
+        assert!(
+            computed(&ColumnKind::Int, "add", &["price", "none"])
+                .compute(&ColumnKind::Int, &data)
+                .unwrap()
+                == ColumnValue::Integer(None)
+        );
+        assert!(computed(&ColumnKind::Int, "divide", &["price", "zero"])
+            .compute(&ColumnKind::Int, &data)
+            .is_err());
+        assert!(computed(&ColumnKind::Int, "divide", &["price", "qty"])
+            .compute(&ColumnKind::Int, &data)
+            .is_err());
+    }
 }
@@ -0,0 +1,264 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::collection_plugin::CollectionPluginModel as CollectionPluginMysqlModel,
+    query::collection_plugin::{
+        DELETE as MYSQL_DELETE, SELECT as MYSQL_SELECT, UPSERT as MYSQL_UPSERT,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::collection_plugin::CollectionPluginModel as CollectionPluginPostgresModel,
+    query::collection_plugin::{
+        DELETE as POSTGRES_DELETE, SELECT as POSTGRES_SELECT, UPSERT as POSTGRES_UPSERT,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::collection_plugin::CollectionPluginModel as CollectionPluginScyllaModel,
+    query::collection_plugin::{
+        DELETE as SCYLLA_DELETE, SELECT as SCYLLA_SELECT, UPSERT as SCYLLA_UPSERT,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::collection_plugin::CollectionPluginModel as CollectionPluginSqliteModel,
+    query::collection_plugin::{
+        DELETE as SQLITE_DELETE, SELECT as SQLITE_SELECT, UPSERT as SQLITE_UPSERT,
+    },
+};
+use scylla::frame::value::CqlTimestamp as ScyllaCqlTimestamp;
+use uuid::Uuid;
+
+use crate::{util::conversion, Db};
+
+// The WASM module whose hooks run around record writes of a collection, at most one per collection
+pub struct CollectionPluginDao {
+    collection_id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    module: Vec<u8>,
+}
+
+impl CollectionPluginDao {
+    pub fn new(collection_id: &Uuid, module: &[u8]) -> Self {
+        let now = Utc::now();
+
+        Self {
+            collection_id: *collection_id,
+            created_at: now,
+            updated_at: now,
+            module: module.to_vec(),
+        }
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn module(&self) -> &[u8] {
+        &self.module
+    }
+
+    pub fn set_module(&mut self, module: &[u8]) {
+        self.module = module.to_vec();
+        self.updated_at = Utc::now();
+    }
+
+    pub async fn db_upsert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_upsert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_upsert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_upsert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_upsert(self, db).await,
+        }
+    }
+
+    pub async fn db_select(db: &Db, collection_id: &Uuid) -> Result<Option<Self>> {
+        match db {
+            Db::ScyllaDb(db) => match Self::scylladb_select(db, collection_id).await? {
+                Some(model) => Ok(Some(Self::from_scylladb_model(&model)?)),
+                None => Ok(None),
+            },
+            Db::PostgresqlDb(db) => Ok(Self::postgresdb_select(db, collection_id)
+                .await?
+                .as_ref()
+                .map(Self::from_postgresdb_model)),
+            Db::MysqlDb(db) => Ok(Self::mysqldb_select(db, collection_id)
+                .await?
+                .as_ref()
+                .map(Self::from_mysqldb_model)),
+            Db::SqliteDb(db) => Ok(Self::sqlitedb_select(db, collection_id)
+                .await?
+                .as_ref()
+                .map(Self::from_sqlitedb_model)),
+        }
+    }
+
+    pub async fn db_delete(db: &Db, collection_id: &Uuid) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_delete(db, collection_id).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_delete(db, collection_id).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete(db, collection_id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete(db, collection_id).await,
+        }
+    }
+
+    async fn scylladb_upsert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(
+            SCYLLA_UPSERT,
+            &CollectionPluginScyllaModel::new(
+                &self.collection_id,
+                &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+                &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+                &self.module,
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn scylladb_select(
+        db: &ScyllaDb,
+        collection_id: &Uuid,
+    ) -> Result<Option<CollectionPluginScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT, [collection_id].as_ref())
+            .await?
+            .maybe_first_row_typed::<CollectionPluginScyllaModel>()?)
+    }
+
+    async fn scylladb_delete(db: &ScyllaDb, collection_id: &Uuid) -> Result<()> {
+        db.execute(SCYLLA_DELETE, [collection_id].as_ref()).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_upsert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_UPSERT)
+                .bind(&self.collection_id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.module),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select(
+        db: &PostgresDb,
+        collection_id: &Uuid,
+    ) -> Result<Option<CollectionPluginPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT).bind(collection_id))
+            .await?
+            .pop())
+    }
+
+    async fn postgresdb_delete(db: &PostgresDb, collection_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(POSTGRES_DELETE).bind(collection_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_upsert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_UPSERT)
+                .bind(&self.collection_id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.module),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select(
+        db: &MysqlDb,
+        collection_id: &Uuid,
+    ) -> Result<Option<CollectionPluginMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT).bind(collection_id))
+            .await?
+            .pop())
+    }
+
+    async fn mysqldb_delete(db: &MysqlDb, collection_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(MYSQL_DELETE).bind(collection_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_upsert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_UPSERT)
+                .bind(&self.collection_id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.module),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select(
+        db: &SqliteDb,
+        collection_id: &Uuid,
+    ) -> Result<Option<CollectionPluginSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT).bind(collection_id))
+            .await?
+            .pop())
+    }
+
+    async fn sqlitedb_delete(db: &SqliteDb, collection_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(SQLITE_DELETE).bind(collection_id))
+            .await?;
+        Ok(())
+    }
+
+    fn from_scylladb_model(model: &CollectionPluginScyllaModel) -> Result<Self> {
+        Ok(Self {
+            collection_id: *model.collection_id(),
+            created_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.created_at())?,
+            updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
+            module: model.module().to_vec(),
+        })
+    }
+
+    fn from_postgresdb_model(model: &CollectionPluginPostgresModel) -> Self {
+        Self {
+            collection_id: *model.collection_id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            module: model.module().to_vec(),
+        }
+    }
+
+    fn from_mysqldb_model(model: &CollectionPluginMysqlModel) -> Self {
+        Self {
+            collection_id: *model.collection_id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            module: model.module().to_vec(),
+        }
+    }
+
+    fn from_sqlitedb_model(model: &CollectionPluginSqliteModel) -> Self {
+        Self {
+            collection_id: *model.collection_id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            module: model.module().to_vec(),
+        }
+    }
+}
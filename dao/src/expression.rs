@@ -0,0 +1,1118 @@
+use std::cmp::Ordering;
+
+use anyhow::{Error, Result};
+use regex::RegexBuilder;
+use serde_json::{Map, Number, Value};
+
+const MAX_LENGTH: usize = 1024;
+const MAX_DEPTH: usize = 32;
+
+// A small CEL-like language for conditions. It has null, booleans, numbers, strings, and lists,
+// field access with `.` and `[]`, the operators `!`, `-`, `*`, `/`, `%`, `+`, `==`, `!=`, `<`,
+// `<=`, `>`, `>=`, `in`, `&&`, `||`, and `?:`, the functions `size(x)` and `has(x.field)`, and the
+// string methods `startsWith`, `endsWith`, `contains`, and `matches`. Reading a field that doesn't
+// exist gives null instead of an error, so conditions on optional fields stay short.
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+impl Expression {
+    // Only the given variables may be referenced, so typos are caught when the expression is saved
+    // rather than when it is first evaluated
+    pub fn parse(source: &str, variables: &[&str]) -> Result<Self> {
+        if source.len() > MAX_LENGTH {
+            return Err(Error::msg(format!(
+                "Expression is longer than {MAX_LENGTH} characters"
+            )));
+        }
+
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+            depth: 0,
+            variables,
+        };
+        let root = parser.parse_conditional()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(Error::msg(format!(
+                "Unexpected {} in expression",
+                token.describe()
+            )));
+        }
+
+        Ok(Self {
+            source: source.to_owned(),
+            root,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn references(&self, variable: &str) -> bool {
+        references(&self.root, variable)
+    }
+
+    // Parts that don't read the variable are evaluated against the context, and the rest must
+    // compare one of its fields with such a part, joined by `&&` and `||`. Without a context only
+    // the shape is checked, which is enough to reject an expression when it is saved.
+    pub fn to_filter(
+        &self,
+        variable: &str,
+        context: Option<&Map<String, Value>>,
+    ) -> Result<Filter> {
+        filter(&self.root, variable, context)
+    }
+
+    pub fn evaluate(&self, context: &Map<String, Value>) -> Result<bool> {
+        match eval(&self.root, context)? {
+            Value::Bool(result) => Ok(result),
+            value => Err(Error::msg(format!(
+                "Expression must evaluate to a boolean, but got {}",
+                type_name(&value)
+            ))),
+        }
+    }
+}
+
+// An expression turned into a filter on the fields of one variable, for requests that read many
+// records at once and so have no single record to evaluate it against. The operators are the ones
+// record filters use.
+#[derive(Debug, PartialEq)]
+pub enum Filter {
+    Constant(bool),
+    Compare(String, &'static str, Value),
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+}
+
+impl Filter {
+    fn and(left: Self, right: Self) -> Self {
+        match (left, right) {
+            (Self::Constant(false), _) | (_, Self::Constant(false)) => Self::Constant(false),
+            (Self::Constant(true), filter) | (filter, Self::Constant(true)) => filter,
+            (Self::And(mut left), Self::And(right)) => {
+                left.extend(right);
+                Self::And(left)
+            }
+            (Self::And(mut filters), filter) | (filter, Self::And(mut filters)) => {
+                filters.push(filter);
+                Self::And(filters)
+            }
+            (left, right) => Self::And(vec![left, right]),
+        }
+    }
+
+    fn or(left: Self, right: Self) -> Self {
+        match (left, right) {
+            (Self::Constant(true), _) | (_, Self::Constant(true)) => Self::Constant(true),
+            (Self::Constant(false), filter) | (filter, Self::Constant(false)) => filter,
+            (Self::Or(mut left), Self::Or(right)) => {
+                left.extend(right);
+                Self::Or(left)
+            }
+            (Self::Or(mut filters), filter) | (filter, Self::Or(mut filters)) => {
+                filters.push(filter);
+                Self::Or(filters)
+            }
+            (left, right) => Self::Or(vec![left, right]),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    Literal(Value),
+    Ident(String),
+    Punct(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Self::Literal(value) => format!("'{value}'"),
+            Self::Ident(ident) => format!("'{ident}'"),
+            Self::Punct(punct) => format!("'{punct}'"),
+        }
+    }
+}
+
+// Two-character operators come first so `<=` isn't read as `<` followed by `=`
+const PUNCTS: [&str; 22] = [
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "+", "-", "*", "/", "%", "(", ")", "[", "]",
+    ".", ",", "?", ":",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(idx, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, ch)) if ch == c => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((_, 'r')) => value.push('\r'),
+                        Some((_, ch)) if ch == '\\' || ch == '"' || ch == '\'' => value.push(ch),
+                        Some((_, ch)) => {
+                            return Err(Error::msg(format!("Unknown escape '\\{ch}' in string")))
+                        }
+                        None => return Err(Error::msg("Unterminated string in expression")),
+                    },
+                    Some((_, ch)) => value.push(ch),
+                    None => return Err(Error::msg("Unterminated string in expression")),
+                }
+            }
+            tokens.push(Token::Literal(Value::String(value)));
+        } else if c.is_ascii_digit() {
+            let mut end = idx;
+            let mut is_float = false;
+            while let Some(&(i, ch)) = chars.peek() {
+                if ch.is_ascii_digit() {
+                    end = i + 1;
+                    chars.next();
+                } else if ch == '.'
+                    && !is_float
+                    && source[i + 1..].starts_with(|ch: char| ch.is_ascii_digit())
+                {
+                    is_float = true;
+                    end = i + 1;
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let number = &source[idx..end];
+            let value = if is_float {
+                number
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .map(Value::Number)
+            } else {
+                number.parse::<i64>().ok().map(Value::from)
+            };
+            match value {
+                Some(value) => tokens.push(Token::Literal(value)),
+                None => return Err(Error::msg(format!("Invalid number '{number}'"))),
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = idx;
+            while let Some(&(i, ch)) = chars.peek() {
+                if ch.is_ascii_alphanumeric() || ch == '_' {
+                    end = i + ch.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(match &source[idx..end] {
+                "null" => Token::Literal(Value::Null),
+                "true" => Token::Literal(Value::Bool(true)),
+                "false" => Token::Literal(Value::Bool(false)),
+                ident => Token::Ident(ident.to_owned()),
+            });
+        } else {
+            let punct = match PUNCTS
+                .iter()
+                .find(|punct| source[idx..].starts_with(**punct))
+            {
+                Some(punct) => *punct,
+                None => {
+                    return Err(Error::msg(format!(
+                        "Unexpected character '{c}' in expression"
+                    )))
+                }
+            };
+            for _ in 0..punct.len() {
+                chars.next();
+            }
+            tokens.push(Token::Punct(punct));
+        }
+    }
+    Ok(tokens)
+}
+
+enum Node {
+    Literal(Value),
+    List(Vec<Node>),
+    Variable(String),
+    Member(Box<Node>, String),
+    Index(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Negate(Box<Node>),
+    Binary(&'static str, Box<Node>, Box<Node>),
+    Conditional(Box<Node>, Box<Node>, Box<Node>),
+    Call(String, Vec<Node>),
+    Method(Box<Node>, String, Vec<Node>),
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+    variables: &'a [&'a str],
+}
+
+impl Parser<'_> {
+    fn peek_punct(&self, punct: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        if self.peek_punct(punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<()> {
+        if self.eat_punct(punct) {
+            return Ok(());
+        }
+        match self.tokens.get(self.pos) {
+            Some(token) => Err(Error::msg(format!(
+                "Expected '{punct}' but found {} in expression",
+                token.describe()
+            ))),
+            None => Err(Error::msg(format!(
+                "Expected '{punct}' but the expression ended"
+            ))),
+        }
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(Error::msg(format!(
+                "Expression is nested deeper than {MAX_DEPTH} levels"
+            )));
+        }
+        Ok(())
+    }
+
+    fn parse_conditional(&mut self) -> Result<Node> {
+        self.enter()?;
+        let condition = self.parse_binary(0)?;
+        let node = if self.eat_punct("?") {
+            let then = self.parse_conditional()?;
+            self.expect_punct(":")?;
+            let otherwise = self.parse_conditional()?;
+            Node::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise))
+        } else {
+            condition
+        };
+        self.depth -= 1;
+        Ok(node)
+    }
+
+    // Binary operators from the loosest to the tightest binding
+    fn parse_binary(&mut self, level: usize) -> Result<Node> {
+        const LEVELS: [&[&str]; 5] = [
+            &["||"],
+            &["&&"],
+            &["==", "!=", "<=", ">=", "<", ">", "in"],
+            &["+", "-"],
+            &["*", "/", "%"],
+        ];
+
+        let operators = match LEVELS.get(level) {
+            Some(operators) => *operators,
+            None => return self.parse_unary(),
+        };
+        let mut left = self.parse_binary(level + 1)?;
+        loop {
+            let op = match self.tokens.get(self.pos) {
+                Some(Token::Punct(punct)) => operators.iter().find(|op| *op == punct),
+                Some(Token::Ident(ident)) if ident == "in" => {
+                    operators.iter().find(|op| **op == "in")
+                }
+                _ => None,
+            };
+            let Some(op) = op else {
+                break;
+            };
+            self.pos += 1;
+            let right = self.parse_binary(level + 1)?;
+            left = Node::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Node> {
+        if self.eat_punct("!") {
+            self.enter()?;
+            let node = Node::Not(Box::new(self.parse_unary()?));
+            self.depth -= 1;
+            Ok(node)
+        } else if self.eat_punct("-") {
+            self.enter()?;
+            let node = Node::Negate(Box::new(self.parse_unary()?));
+            self.depth -= 1;
+            Ok(node)
+        } else {
+            self.parse_postfix()
+        }
+    }
+
+    fn parse_postfix(&mut self) -> Result<Node> {
+        let mut node = self.parse_primary()?;
+        loop {
+            if self.eat_punct(".") {
+                let name = match self.tokens.get(self.pos) {
+                    Some(Token::Ident(name)) => name.to_owned(),
+                    _ => return Err(Error::msg("Expected a field name after '.' in expression")),
+                };
+                self.pos += 1;
+                node = if self.eat_punct("(") {
+                    Node::Method(Box::new(node), name, self.parse_args(")")?)
+                } else {
+                    Node::Member(Box::new(node), name)
+                };
+            } else if self.eat_punct("[") {
+                let index = self.parse_conditional()?;
+                self.expect_punct("]")?;
+                node = Node::Index(Box::new(node), Box::new(index));
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Node> {
+        let token = match self.tokens.get(self.pos) {
+            Some(token) => token.clone(),
+            None => return Err(Error::msg("Expression ended unexpectedly")),
+        };
+        self.pos += 1;
+        match token {
+            Token::Literal(value) => Ok(Node::Literal(value)),
+            Token::Ident(name) => {
+                if self.eat_punct("(") {
+                    if !["size", "has"].contains(&name.as_str()) {
+                        return Err(Error::msg(format!("Unknown function '{name}'")));
+                    }
+                    let args = self.parse_args(")")?;
+                    if args.len() != 1 {
+                        return Err(Error::msg(format!(
+                            "Function '{name}' takes exactly one argument"
+                        )));
+                    }
+                    if name == "has" && !matches!(args[0], Node::Member(..) | Node::Index(..)) {
+                        return Err(Error::msg(
+                            "Function 'has' takes a field, like has(record.field)",
+                        ));
+                    }
+                    Ok(Node::Call(name, args))
+                } else if self.variables.contains(&name.as_str()) {
+                    Ok(Node::Variable(name))
+                } else {
+                    Err(Error::msg(format!(
+                        "Unknown variable '{name}', expected one of: {}",
+                        self.variables.join(", ")
+                    )))
+                }
+            }
+            Token::Punct("(") => {
+                let node = self.parse_conditional()?;
+                self.expect_punct(")")?;
+                Ok(node)
+            }
+            Token::Punct("[") => Ok(Node::List(self.parse_args("]")?)),
+            token => Err(Error::msg(format!(
+                "Unexpected {} in expression",
+                token.describe()
+            ))),
+        }
+    }
+
+    fn parse_args(&mut self, close: &str) -> Result<Vec<Node>> {
+        let mut args = Vec::new();
+        if self.eat_punct(close) {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_conditional()?);
+            if self.eat_punct(close) {
+                return Ok(args);
+            }
+            self.expect_punct(",")?;
+        }
+    }
+}
+
+fn eval(node: &Node, context: &Map<String, Value>) -> Result<Value> {
+    match node {
+        Node::Literal(value) => Ok(value.clone()),
+        Node::List(items) => Ok(Value::Array(
+            items
+                .iter()
+                .map(|item| eval(item, context))
+                .collect::<Result<_>>()?,
+        )),
+        Node::Variable(name) => Ok(context.get(name).cloned().unwrap_or(Value::Null)),
+        Node::Member(object, field) => member(&eval(object, context)?, field),
+        Node::Index(object, index) => match (eval(object, context)?, eval(index, context)?) {
+            (Value::Null, _) => Ok(Value::Null),
+            (Value::Object(object), Value::String(key)) => {
+                Ok(object.get(&key).cloned().unwrap_or(Value::Null))
+            }
+            (Value::Array(items), Value::Number(idx)) => Ok(idx
+                .as_u64()
+                .and_then(|idx| items.get(usize::try_from(idx).ok()?).cloned())
+                .unwrap_or(Value::Null)),
+            (object, index) => Err(Error::msg(format!(
+                "Can't index {} with {}",
+                type_name(&object),
+                type_name(&index)
+            ))),
+        },
+        Node::Not(operand) => match eval(operand, context)? {
+            Value::Bool(value) => Ok(Value::Bool(!value)),
+            value => Err(Error::msg(format!(
+                "Operator '!' needs a boolean, but got {}",
+                type_name(&value)
+            ))),
+        },
+        Node::Negate(operand) => arithmetic("-", &Value::from(0), &eval(operand, context)?),
+        Node::Binary(op, left, right) => match *op {
+            "&&" | "||" => {
+                let left = boolean(op, &eval(left, context)?)?;
+                if (*op == "&&" && !left) || (*op == "||" && left) {
+                    return Ok(Value::Bool(left));
+                }
+                Ok(Value::Bool(boolean(op, &eval(right, context)?)?))
+            }
+            _ => binary(op, &eval(left, context)?, &eval(right, context)?),
+        },
+        Node::Conditional(condition, then, otherwise) => {
+            if boolean("?:", &eval(condition, context)?)? {
+                eval(then, context)
+            } else {
+                eval(otherwise, context)
+            }
+        }
+        Node::Call(name, args) => match (name.as_str(), &args[0]) {
+            ("has", Node::Member(object, field)) => Ok(Value::Bool(
+                !member(&eval(object, context)?, field)?.is_null(),
+            )),
+            ("has", index @ Node::Index(..)) => Ok(Value::Bool(!eval(index, context)?.is_null())),
+            (_, arg) => match eval(arg, context)? {
+                Value::String(value) => Ok(Value::from(value.chars().count())),
+                Value::Array(items) => Ok(Value::from(items.len())),
+                Value::Object(object) => Ok(Value::from(object.len())),
+                value => Err(Error::msg(format!(
+                    "Function 'size' needs a string, list, or map, but got {}",
+                    type_name(&value)
+                ))),
+            },
+        },
+        Node::Method(object, name, args) => {
+            let object = eval(object, context)?;
+            let args = args
+                .iter()
+                .map(|arg| eval(arg, context))
+                .collect::<Result<Vec<_>>>()?;
+            let (Value::String(object), [Value::String(arg)]) = (&object, args.as_slice()) else {
+                if object.is_null() {
+                    return Ok(Value::Bool(false));
+                }
+                return Err(Error::msg(format!(
+                    "Method '{name}' needs a string and one string argument"
+                )));
+            };
+            match name.as_str() {
+                "startsWith" => Ok(Value::Bool(object.starts_with(arg.as_str()))),
+                "endsWith" => Ok(Value::Bool(object.ends_with(arg.as_str()))),
+                "contains" => Ok(Value::Bool(object.contains(arg.as_str()))),
+                "matches" => Ok(Value::Bool(
+                    RegexBuilder::new(arg)
+                        .size_limit(1 << 20)
+                        .build()?
+                        .is_match(object),
+                )),
+                _ => Err(Error::msg(format!("Unknown method '{name}'"))),
+            }
+        }
+    }
+}
+
+fn references(node: &Node, variable: &str) -> bool {
+    match node {
+        Node::Literal(_) => false,
+        Node::Variable(name) => name == variable,
+        Node::List(items) | Node::Call(_, items) => {
+            items.iter().any(|item| references(item, variable))
+        }
+        Node::Member(object, _) | Node::Not(object) | Node::Negate(object) => {
+            references(object, variable)
+        }
+        Node::Index(left, right) | Node::Binary(_, left, right) => {
+            references(left, variable) || references(right, variable)
+        }
+        Node::Conditional(condition, then, otherwise) => {
+            references(condition, variable)
+                || references(then, variable)
+                || references(otherwise, variable)
+        }
+        Node::Method(object, _, args) => {
+            references(object, variable) || args.iter().any(|arg| references(arg, variable))
+        }
+    }
+}
+
+fn filter(node: &Node, variable: &str, context: Option<&Map<String, Value>>) -> Result<Filter> {
+    if !references(node, variable) {
+        let Some(context) = context else {
+            return Ok(Filter::Constant(true));
+        };
+        return match eval(node, context)? {
+            Value::Bool(value) => Ok(Filter::Constant(value)),
+            value => Err(Error::msg(format!(
+                "Expression must evaluate to a boolean, but got {}",
+                type_name(&value)
+            ))),
+        };
+    }
+
+    let value = |node: &Node| match context {
+        Some(context) => eval(node, context).map(Some),
+        None => Ok(None),
+    };
+    let unsupported = || {
+        Error::msg(format!(
+            "Only comparisons of {variable} fields, has({variable}.field), and the methods startsWith, endsWith, and contains on {variable} fields, joined by '&&' and '||', can be used to filter many records"
+        ))
+    };
+
+    match node {
+        Node::Binary("&&", left, right) => Ok(Filter::and(
+            filter(left, variable, context)?,
+            filter(right, variable, context)?,
+        )),
+        Node::Binary("||", left, right) => Ok(Filter::or(
+            filter(left, variable, context)?,
+            filter(right, variable, context)?,
+        )),
+        Node::Binary(op @ ("==" | "!=" | "<" | "<=" | ">" | ">=" | "in"), left, right) => {
+            match (field(left, variable), field(right, variable)) {
+                (Some(field), None) if !references(right, variable) => {
+                    compare(field, op, value(right)?)
+                }
+                (None, Some(field)) if *op != "in" && !references(left, variable) => {
+                    let op = match *op {
+                        "<" => ">",
+                        "<=" => ">=",
+                        ">" => "<",
+                        ">=" => "<=",
+                        op => op,
+                    };
+                    compare(field, op, value(left)?)
+                }
+                _ => Err(unsupported()),
+            }
+        }
+        Node::Call(name, args) if name == "has" => match field(&args[0], variable) {
+            Some(field) => Ok(Filter::Compare(field, "IS NOT NULL", Value::Null)),
+            None => Err(unsupported()),
+        },
+        Node::Not(operand) => match operand.as_ref() {
+            Node::Call(name, args) if name == "has" => match field(&args[0], variable) {
+                Some(field) => Ok(Filter::Compare(field, "IS NULL", Value::Null)),
+                None => Err(unsupported()),
+            },
+            _ => Err(unsupported()),
+        },
+        Node::Method(object, name, args) if args.len() == 1 && !references(&args[0], variable) => {
+            let op = match name.as_str() {
+                "startsWith" => "STARTS_WITH",
+                "endsWith" => "ENDS_WITH",
+                "contains" => "SUBSTRING",
+                _ => return Err(unsupported()),
+            };
+            let Some(field) = field(object, variable) else {
+                return Err(unsupported());
+            };
+            match value(&args[0])? {
+                Some(Value::String(arg)) => Ok(Filter::Compare(field, op, Value::String(arg))),
+                Some(_) => Err(Error::msg(format!(
+                    "Method '{name}' needs a string and one string argument"
+                ))),
+                None => Ok(Filter::Compare(field, op, Value::Null)),
+            }
+        }
+        Node::Member(..) | Node::Index(..) => match field(node, variable) {
+            Some(field) => Ok(Filter::Compare(field, "=", Value::Bool(true))),
+            None => Err(unsupported()),
+        },
+        _ => Err(unsupported()),
+    }
+}
+
+// A field read directly off the variable, like `record.field` or `record['field']`
+fn field(node: &Node, variable: &str) -> Option<String> {
+    match node {
+        Node::Member(object, field) => match object.as_ref() {
+            Node::Variable(name) if name == variable => Some(field.to_owned()),
+            _ => None,
+        },
+        Node::Index(object, index) => match (object.as_ref(), index.as_ref()) {
+            (Node::Variable(name), Node::Literal(Value::String(field))) if name == variable => {
+                Some(field.to_owned())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// A missing field reads as null in an expression, so `!=` also matches rows where the column is
+// null, and comparing with null becomes a null check
+fn compare(field: String, op: &'static str, value: Option<Value>) -> Result<Filter> {
+    let Some(value) = value else {
+        return Ok(Filter::Compare(field, "=", Value::Null));
+    };
+    match (op, value) {
+        ("==", Value::Null) => Ok(Filter::Compare(field, "IS NULL", Value::Null)),
+        ("!=", Value::Null) => Ok(Filter::Compare(field, "IS NOT NULL", Value::Null)),
+        ("==", value) => Ok(Filter::Compare(field, "=", value)),
+        ("!=", value) => Ok(Filter::Or(vec![
+            Filter::Compare(field.to_owned(), "!=", value),
+            Filter::Compare(field, "IS NULL", Value::Null),
+        ])),
+        ("in", Value::Null) => Ok(Filter::Constant(false)),
+        ("in", Value::Array(items)) if items.is_empty() => Ok(Filter::Constant(false)),
+        ("in", Value::Array(items)) => Ok(Filter::Compare(field, "IN", Value::Array(items))),
+        ("in", value) => Err(Error::msg(format!(
+            "Operator 'in' on field '{field}' needs a list, but got {}",
+            type_name(&value)
+        ))),
+        (op, value @ (Value::Number(_) | Value::String(_))) => {
+            Ok(Filter::Compare(field, op, value))
+        }
+        (op, value) => Err(Error::msg(format!(
+            "Can't compare field '{field}' with {} using '{op}'",
+            type_name(&value)
+        ))),
+    }
+}
+
+fn member(object: &Value, field: &str) -> Result<Value> {
+    match object {
+        Value::Null => Ok(Value::Null),
+        Value::Object(object) => Ok(object.get(field).cloned().unwrap_or(Value::Null)),
+        object => Err(Error::msg(format!(
+            "Can't read field '{field}' of {}",
+            type_name(object)
+        ))),
+    }
+}
+
+fn boolean(op: &str, value: &Value) -> Result<bool> {
+    match value {
+        Value::Bool(value) => Ok(*value),
+        value => Err(Error::msg(format!(
+            "Operator '{op}' needs a boolean, but got {}",
+            type_name(value)
+        ))),
+    }
+}
+
+fn binary(op: &str, left: &Value, right: &Value) -> Result<Value> {
+    match op {
+        "==" => Ok(Value::Bool(equals(left, right))),
+        "!=" => Ok(Value::Bool(!equals(left, right))),
+        "<" | "<=" | ">" | ">=" => {
+            let ordering = match (left, right) {
+                (Value::Number(left), Value::Number(right)) => {
+                    left.as_f64().partial_cmp(&right.as_f64())
+                }
+                (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+                _ => None,
+            };
+            let Some(ordering) = ordering else {
+                return Err(Error::msg(format!(
+                    "Can't compare {} with {}",
+                    type_name(left),
+                    type_name(right)
+                )));
+            };
+            Ok(Value::Bool(match op {
+                "<" => ordering == Ordering::Less,
+                "<=" => ordering != Ordering::Greater,
+                ">" => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }))
+        }
+        "in" => match right {
+            Value::Null => Ok(Value::Bool(false)),
+            Value::Array(items) => Ok(Value::Bool(items.iter().any(|item| equals(left, item)))),
+            Value::Object(object) => match left {
+                Value::String(key) => Ok(Value::Bool(object.contains_key(key))),
+                _ => Ok(Value::Bool(false)),
+            },
+            Value::String(value) => match left {
+                Value::String(part) => Ok(Value::Bool(value.contains(part.as_str()))),
+                _ => Ok(Value::Bool(false)),
+            },
+            right => Err(Error::msg(format!(
+                "Operator 'in' needs a list, map, or string, but got {}",
+                type_name(right)
+            ))),
+        },
+        "+" => match (left, right) {
+            (Value::String(left), Value::String(right)) => {
+                Ok(Value::String(left.to_owned() + right))
+            }
+            (Value::Array(left), Value::Array(right)) => {
+                Ok(Value::Array(left.iter().chain(right).cloned().collect()))
+            }
+            _ => arithmetic(op, left, right),
+        },
+        _ => arithmetic(op, left, right),
+    }
+}
+
+fn arithmetic(op: &str, left: &Value, right: &Value) -> Result<Value> {
+    let (Value::Number(left), Value::Number(right)) = (left, right) else {
+        return Err(Error::msg(format!(
+            "Operator '{op}' can't be used on {} and {}",
+            type_name(left),
+            type_name(right)
+        )));
+    };
+
+    if let (Some(left), Some(right)) = (left.as_i64(), right.as_i64()) {
+        let result = match op {
+            "+" => left.checked_add(right),
+            "-" => left.checked_sub(right),
+            "*" => left.checked_mul(right),
+            "/" => left.checked_div(right),
+            _ => left.checked_rem(right),
+        };
+        return match result {
+            Some(result) => Ok(Value::from(result)),
+            None => Err(Error::msg(format!(
+                "Operator '{op}' overflowed or divided by zero"
+            ))),
+        };
+    }
+
+    let (left, right) = (
+        left.as_f64().unwrap_or_default(),
+        right.as_f64().unwrap_or_default(),
+    );
+    let result = match op {
+        "+" => left + right,
+        "-" => left - right,
+        "*" => left * right,
+        "/" => left / right,
+        _ => left % right,
+    };
+    match Number::from_f64(result) {
+        Some(result) => Ok(Value::Number(result)),
+        None => Err(Error::msg(format!(
+            "Operator '{op}' didn't produce a finite number"
+        ))),
+    }
+}
+
+// Integers and floats compare by value, so `1 == 1.0`
+fn equals(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => match (left.as_i64(), right.as_i64()) {
+            (Some(left), Some(right)) => left == right,
+            _ => left.as_f64() == right.as_f64(),
+        },
+        _ => left == right,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "a map",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Map, Value};
+
+    use super::{Expression, Filter, MAX_DEPTH, MAX_LENGTH};
+
+    const VARIABLES: [&str; 3] = ["request", "token", "record"];
+
+    fn context() -> Map<String, Value> {
+        json!({
+            "request": { "method": "update" },
+            "token": { "user_id": "u1", "roles": ["editor"] },
+            "record": {
+                "owner_id": "u1",
+                "status": "draft",
+                "score": 7,
+                "ratio": 0.5,
+                "tags": ["a", "b"],
+                "note": null,
+            },
+        })
+        .as_object()
+        .cloned()
+        .unwrap()
+    }
+
+    fn eval(source: &str) -> anyhow::Result<bool> {
+        Expression::parse(source, &VARIABLES)?.evaluate(&context())
+    }
+
+    #[test]
+    fn parse_rejects_invalid_syntax() {
+        for source in [
+            "",
+            "record.",
+            "record.score ==",
+            "(true",
+            "true)",
+            "[1, 2",
+            "true ? 1",
+            "'unterminated",
+            "'bad \\q escape'",
+            "record.score # 1",
+            "true true",
+        ] {
+            assert!(Expression::parse(source, &VARIABLES).is_err(), "{source}");
+        }
+    }
+
+    #[test]
+    fn parse_rejects_unknown_names() {
+        assert!(Expression::parse("user.id == 1", &VARIABLES).is_err());
+        assert!(Expression::parse("length(record.tags) == 2", &VARIABLES).is_err());
+        assert!(Expression::parse("size(record.tags, 1) == 2", &VARIABLES).is_err());
+        assert!(Expression::parse("has(record)", &VARIABLES).is_err());
+    }
+
+    #[test]
+    fn parse_keeps_the_source() {
+        let source = "record.owner_id == token.user_id";
+        assert_eq!(
+            Expression::parse(source, &VARIABLES).unwrap().source(),
+            source
+        );
+    }
+
+    #[test]
+    fn evaluate_follows_precedence() {
+        for source in [
+            "1 + 2 * 3 == 7",
+            "(1 + 2) * 3 == 9",
+            "10 - 4 - 3 == 3",
+            "7 % 4 * 2 == 6",
+            "-record.score + 10 == 3",
+            "!false && true",
+            "true || false && false",
+            "!(true || false) == false",
+            "1 < 2 == true",
+            "record.score > 5 ? record.status == 'draft' : false",
+            "false ? false : true ? true : false",
+            "1 == 1.0",
+            "record.ratio * 4 == 2",
+            "'ab' + 'c' == 'abc'",
+            "[1] + [2] == [1, 2]",
+        ] {
+            assert!(eval(source).unwrap(), "{source}");
+        }
+    }
+
+    #[test]
+    fn evaluate_reads_fields_and_lists() {
+        for source in [
+            "record.owner_id == token.user_id && record.status != 'locked'",
+            "record['status'] == 'draft'",
+            "record.tags[1] == 'b'",
+            "'editor' in token.roles",
+            "'status' in record",
+            "'ra' in record.status",
+            "size(record.tags) == 2",
+            "size(record.status) == 5",
+            "has(record.status)",
+            "!has(record.note)",
+            "record.status.startsWith('dr')",
+            "record.status.endsWith('ft')",
+            "record.status.contains('af')",
+            "record.status.matches('^d.a')",
+            "request.method in ['insert', 'update']",
+        ] {
+            assert!(eval(source).unwrap(), "{source}");
+        }
+    }
+
+    #[test]
+    fn evaluate_treats_missing_fields_as_null() {
+        for source in [
+            "record.missing == null",
+            "record.missing.deeper == null",
+            "record.note.deeper == null",
+            "record['missing'] == null",
+            "record.tags[5] == null",
+            "!has(record.missing)",
+            "!has(record.missing.deeper)",
+            "!('a' in record.missing)",
+            "!record.missing.startsWith('a')",
+        ] {
+            assert!(eval(source).unwrap(), "{source}");
+        }
+        let context = Map::new();
+        assert!(Expression::parse("record == null", &VARIABLES)
+            .unwrap()
+            .evaluate(&context)
+            .unwrap());
+    }
+
+    #[test]
+    fn evaluate_short_circuits() {
+        // The right side would fail, so these only pass if it's never evaluated
+        assert!(!eval("false && record.status > 1").unwrap());
+        assert!(eval("true || record.status > 1").unwrap());
+        assert!(eval("true ? true : record.status > 1").unwrap());
+        assert!(eval("true && record.status > 1").is_err());
+        assert!(eval("false || record.status > 1").is_err());
+    }
+
+    #[test]
+    fn evaluate_rejects_type_mismatches() {
+        for source in [
+            "record.score",
+            "record.status > 1",
+            "record.status + 1 == 1",
+            "!record.score",
+            "record.score && true",
+            "true && record.score",
+            "record.score ? true : false",
+            "-record.status == 0",
+            "record.score.field == 1",
+            "record.tags['a'] == null",
+            "size(record.score) == 0",
+            "1 in record.score",
+            "record.score.startsWith('a')",
+            "record.status.matches('(')",
+            "1 / 0 == 0",
+            "9223372036854775807 + 1 == 0",
+        ] {
+            assert!(eval(source).is_err(), "{source}");
+        }
+    }
+
+    #[test]
+    fn parse_enforces_limits() {
+        let long = format!("'{}' == ''", "a".repeat(MAX_LENGTH));
+        assert!(Expression::parse(&long, &VARIABLES).is_err());
+
+        let nested = |depth: usize| format!("{}true{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(Expression::parse(&nested(MAX_DEPTH - 1), &VARIABLES).is_ok());
+        assert!(Expression::parse(&nested(MAX_DEPTH), &VARIABLES).is_err());
+        let negated = format!("{}true", "!".repeat(MAX_DEPTH));
+        assert!(Expression::parse(&negated, &VARIABLES).is_err());
+    }
+
+    fn filter(source: &str) -> anyhow::Result<Filter> {
+        let mut context = context();
+        context.remove("record");
+        Expression::parse(source, &VARIABLES)?.to_filter("record", Some(&context))
+    }
+
+    fn compare(field: &str, op: &'static str, value: Value) -> Filter {
+        Filter::Compare(field.to_owned(), op, value)
+    }
+
+    #[test]
+    fn to_filter_turns_record_comparisons_into_filters() {
+        assert_eq!(
+            filter("record.owner_id == token.user_id && record.status != 'locked'").unwrap(),
+            Filter::And(vec![
+                compare("owner_id", "=", json!("u1")),
+                Filter::Or(vec![
+                    compare("status", "!=", json!("locked")),
+                    compare("status", "IS NULL", Value::Null),
+                ]),
+            ])
+        );
+        assert_eq!(
+            filter("5 < record.score || record['note'] == null").unwrap(),
+            Filter::Or(vec![
+                compare("score", ">", json!(5)),
+                compare("note", "IS NULL", Value::Null),
+            ])
+        );
+        assert_eq!(
+            filter("record.status in ['draft', 'review'] && !has(record.note)").unwrap(),
+            Filter::And(vec![
+                compare("status", "IN", json!(["draft", "review"])),
+                compare("note", "IS NULL", Value::Null),
+            ])
+        );
+        assert_eq!(
+            filter("record.status.startsWith(token.user_id)").unwrap(),
+            compare("status", "STARTS_WITH", json!("u1"))
+        );
+    }
+
+    #[test]
+    fn to_filter_evaluates_the_rest_up_front() {
+        assert_eq!(
+            filter("request.method == 'update' && record.score > 1").unwrap(),
+            compare("score", ">", json!(1))
+        );
+        assert_eq!(
+            filter("request.method == 'delete' && record.score > 1").unwrap(),
+            Filter::Constant(false)
+        );
+        assert_eq!(
+            filter("'editor' in token.roles || record.score > 1").unwrap(),
+            Filter::Constant(true)
+        );
+        assert_eq!(
+            filter("record.status in token.missing").unwrap(),
+            Filter::Constant(false)
+        );
+    }
+
+    #[test]
+    fn to_filter_rejects_what_a_filter_cannot_express() {
+        for source in [
+            "record.score + 1 > 2",
+            "record.owner_id == record.status",
+            "!(record.score > 1)",
+            "record.score > 1 ? true : false",
+            "size(record.tags) > 0",
+            "token.user_id in record.tags",
+            "record.status.matches('d.*')",
+            "record.score < null",
+            "record.score < [1]",
+        ] {
+            assert!(filter(source).is_err(), "{source}");
+        }
+
+        let shape = |source: &str| {
+            Expression::parse(source, &VARIABLES)
+                .unwrap()
+                .to_filter("record", None)
+        };
+        assert!(shape("record.owner_id == token.user_id && request.method != 'x'").is_ok());
+        assert!(shape("record.score + 1 > 2").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    query::jwt_revocation::{COUNT as MYSQL_COUNT, INSERT as MYSQL_INSERT},
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    query::jwt_revocation::{COUNT as POSTGRES_COUNT, INSERT as POSTGRES_INSERT},
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::jwt_revocation::JwtRevocationModel as JwtRevocationScyllaModel,
+    query::jwt_revocation::{COUNT as SCYLLA_COUNT, INSERT as SCYLLA_INSERT},
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    query::jwt_revocation::{COUNT as SQLITE_COUNT, INSERT as SQLITE_INSERT},
+};
+use scylla::frame::value::CqlTimestamp as ScyllaCqlTimestamp;
+use uuid::Uuid;
+
+use crate::Db;
+
+// The id is either the jti of a single JWT or the subject id of every JWT issued to it
+pub struct JwtRevocationDao {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    expired_at: DateTime<Utc>,
+}
+
+impl JwtRevocationDao {
+    pub fn new(id: &Uuid, expired_at: &DateTime<Utc>) -> Self {
+        Self {
+            id: *id,
+            created_at: Utc::now(),
+            expired_at: *expired_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn expired_at(&self) -> &DateTime<Utc> {
+        &self.expired_at
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert(self, db).await,
+        }
+    }
+
+    pub async fn db_is_revoked(db: &Db, id: &Uuid) -> Result<bool> {
+        let count = match db {
+            Db::ScyllaDb(db) => Self::scylladb_count(db, id).await?,
+            Db::PostgresqlDb(db) => Self::postgresdb_count(db, id).await?,
+            Db::MysqlDb(db) => Self::mysqldb_count(db, id).await?,
+            Db::SqliteDb(db) => Self::sqlitedb_count(db, id).await?,
+        };
+        Ok(count > 0)
+    }
+
+    async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(
+            SCYLLA_INSERT,
+            &JwtRevocationScyllaModel::new(
+                &self.id,
+                &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+                &ScyllaCqlTimestamp(self.expired_at.timestamp_millis()),
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn scylladb_count(db: &ScyllaDb, id: &Uuid) -> Result<i64> {
+        Ok(db
+            .execute(SCYLLA_COUNT, [id].as_ref())
+            .await?
+            .first_row_typed::<(i64,)>()?
+            .0)
+    }
+
+    async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_count(db: &PostgresDb, id: &Uuid) -> Result<i64> {
+        Ok(db
+            .fetch_one::<(i64,)>(sqlx::query_as(POSTGRES_COUNT).bind(id))
+            .await?
+            .0)
+    }
+
+    async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_count(db: &MysqlDb, id: &Uuid) -> Result<i64> {
+        Ok(db
+            .fetch_one::<(i64,)>(sqlx::query_as(MYSQL_COUNT).bind(id))
+            .await?
+            .0)
+    }
+
+    async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.expired_at),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_count(db: &SqliteDb, id: &Uuid) -> Result<i64> {
+        Ok(db
+            .fetch_one::<(i64,)>(sqlx::query_as(SQLITE_COUNT).bind(id))
+            .await?
+            .0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use hb_db_sqlite::db::SqliteDb;
+    use uuid::Uuid;
+
+    use super::JwtRevocationDao;
+    use crate::Db;
+
+    #[tokio::test]
+    async fn revoked_ids_are_reported() {
+        let db = Db::SqliteDb(SqliteDb::new(":memory:", &1, &600, &600, &None).await);
+        let (revoked_id, other_id) = (Uuid::now_v7(), Uuid::now_v7());
+        JwtRevocationDao::new(&revoked_id, &(Utc::now() + Duration::hours(1)))
+            .db_insert(&db)
+            .await
+            .unwrap();
+
+        assert!(JwtRevocationDao::db_is_revoked(&db, &revoked_id)
+            .await
+            .unwrap());
+        assert!(!JwtRevocationDao::db_is_revoked(&db, &other_id)
+            .await
+            .unwrap());
+    }
+}
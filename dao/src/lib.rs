@@ -1,3 +1,4 @@
+use anyhow::Result;
 use hb_db_mysql::db::MysqlDb;
 use hb_db_postgresql::db::PostgresDb;
 use hb_db_scylladb::db::ScyllaDb;
@@ -5,11 +6,27 @@ use hb_db_sqlite::db::SqliteDb;
 
 pub mod admin;
 pub mod admin_password_reset;
+pub mod admin_refresh_token;
+pub mod admin_session;
+pub mod audit_log;
+pub mod backup;
 pub mod collection;
+pub mod collection_plugin;
+pub mod expression;
+pub mod jwt_revocation;
 pub mod project;
+pub mod project_member;
+pub mod project_purger;
+pub mod project_transfer;
+pub mod project_usage;
 pub mod record;
+pub mod record_sweeper;
 pub mod register;
+pub mod scheduler;
 pub mod token;
+pub mod usage_meter;
+pub mod user;
+pub mod user_password_reset;
 mod util;
 pub mod value;
 
@@ -19,3 +36,35 @@ pub enum Db {
     MysqlDb(MysqlDb),
     SqliteDb(SqliteDb),
 }
+
+impl Db {
+    pub async fn ping(&self) -> Result<()> {
+        match self {
+            Self::ScyllaDb(db) => {
+                db.session_query("SELECT \"release_version\" FROM \"system\".\"local\"", &[])
+                    .await?;
+            }
+            Self::PostgresqlDb(db) => {
+                db.execute_unprepared(sqlx::query("SELECT 1")).await?;
+            }
+            Self::MysqlDb(db) => {
+                db.execute_unprepared(sqlx::query("SELECT 1")).await?;
+            }
+            Self::SqliteDb(db) => {
+                db.execute_unprepared(sqlx::query("SELECT 1")).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // Waits for checked-out connections to be returned; the ScyllaDB session closes its
+    // connections when it is dropped
+    pub async fn close(&self) {
+        match self {
+            Self::ScyllaDb(_) => (),
+            Self::PostgresqlDb(db) => db.close().await,
+            Self::MysqlDb(db) => db.close().await,
+            Self::SqliteDb(db) => db.close().await,
+        }
+    }
+}
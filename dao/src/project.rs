@@ -1,11 +1,13 @@
-use anyhow::Result;
+use anyhow::{Error, Result};
 use chrono::{DateTime, Utc};
 use hb_db_mysql::{
     db::MysqlDb,
     model::project::ProjectModel as ProjectMysqlModel,
     query::project::{
         DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
-        SELECT_MANY_BY_ADMIN_ID as MYSQL_SELECT_MANY_BY_ADMIN_ID, UPDATE as MYSQL_UPDATE,
+        SELECT_MANY_BY_ADMIN_ID as MYSQL_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_DELETED_BEFORE as MYSQL_SELECT_MANY_DELETED_BEFORE, UPDATE as MYSQL_UPDATE,
+        UPDATE_DELETED_AT as MYSQL_UPDATE_DELETED_AT,
     },
 };
 use hb_db_postgresql::{
@@ -13,7 +15,9 @@ use hb_db_postgresql::{
     model::project::ProjectModel as ProjectPostgresModel,
     query::project::{
         DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
-        SELECT_MANY_BY_ADMIN_ID as POSTGRES_SELECT_MANY_BY_ADMIN_ID, UPDATE as POSTGRES_UPDATE,
+        SELECT_MANY_BY_ADMIN_ID as POSTGRES_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_DELETED_BEFORE as POSTGRES_SELECT_MANY_DELETED_BEFORE,
+        UPDATE as POSTGRES_UPDATE, UPDATE_DELETED_AT as POSTGRES_UPDATE_DELETED_AT,
     },
 };
 use hb_db_scylladb::{
@@ -21,7 +25,9 @@ use hb_db_scylladb::{
     model::project::ProjectModel as ProjectScyllaModel,
     query::project::{
         DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
-        SELECT_MANY_BY_ADMIN_ID as SCYLLA_SELECT_MANY_BY_ADMIN_ID, UPDATE as SCYLLA_UPDATE,
+        SELECT_MANY_BY_ADMIN_ID as SCYLLA_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_DELETED_BEFORE as SCYLLA_SELECT_MANY_DELETED_BEFORE, UPDATE as SCYLLA_UPDATE,
+        UPDATE_DELETED_AT as SCYLLA_UPDATE_DELETED_AT,
     },
 };
 use hb_db_sqlite::{
@@ -29,23 +35,34 @@ use hb_db_sqlite::{
     model::project::ProjectModel as ProjectSqliteModel,
     query::project::{
         DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
-        SELECT_MANY_BY_ADMIN_ID as SQLITE_SELECT_MANY_BY_ADMIN_ID, UPDATE as SQLITE_UPDATE,
+        SELECT_MANY_BY_ADMIN_ID as SQLITE_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_DELETED_BEFORE as SQLITE_SELECT_MANY_DELETED_BEFORE, UPDATE as SQLITE_UPDATE,
+        UPDATE_DELETED_AT as SQLITE_UPDATE_DELETED_AT,
     },
 };
+use hb_token_jwt::claim::RESERVED_CLAIMS;
 use scylla::{
     frame::value::CqlTimestamp as ScyllaCqlTimestamp,
     transport::session::TypedRowIter as ScyllaTypedRowIter,
 };
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
 use crate::{util::conversion, Db};
 
+#[derive(Serialize, Deserialize)]
 pub struct ProjectDao {
     id: Uuid,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     admin_id: Uuid,
     name: String,
+    suspended: bool,
+    #[serde(default)]
+    archived: bool,
+    token_claims: Option<Map<String, Value>>,
+    deleted_at: Option<DateTime<Utc>>,
 }
 
 impl ProjectDao {
@@ -57,6 +74,10 @@ impl ProjectDao {
             updated_at: now,
             admin_id: *admin_id,
             name: name.to_owned(),
+            suspended: false,
+            archived: false,
+            token_claims: None,
+            deleted_at: None,
         }
     }
 
@@ -80,10 +101,61 @@ impl ProjectDao {
         &self.name
     }
 
+    pub fn suspended(&self) -> &bool {
+        &self.suspended
+    }
+
+    pub fn archived(&self) -> &bool {
+        &self.archived
+    }
+
+    pub fn token_claims(&self) -> &Option<Map<String, Value>> {
+        &self.token_claims
+    }
+
+    // Claims stored before the reserved list grew may still hold reserved keys, so they're dropped
+    // here as well as rejected when set
+    pub fn custom_token_claims(&self) -> Map<String, Value> {
+        match &self.token_claims {
+            Some(token_claims) => token_claims
+                .iter()
+                .filter(|(key, _)| !RESERVED_CLAIMS.contains(&key.as_str()))
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+            None => Map::new(),
+        }
+    }
+
+    pub fn deleted_at(&self) -> &Option<DateTime<Utc>> {
+        &self.deleted_at
+    }
+
     pub fn set_name(&mut self, name: &str) {
         self.name = name.to_owned();
     }
 
+    pub fn set_suspended(&mut self, suspended: &bool) {
+        self.suspended = *suspended;
+    }
+
+    pub fn set_archived(&mut self, archived: &bool) {
+        self.archived = *archived;
+    }
+
+    pub fn set_token_claims(&mut self, token_claims: &Option<Map<String, Value>>) -> Result<()> {
+        if let Some(token_claims) = token_claims {
+            for key in token_claims.keys() {
+                if RESERVED_CLAIMS.contains(&key.as_str()) {
+                    return Err(Error::msg(format!(
+                        "Token claim '{key}' is reserved and can't be overridden"
+                    )));
+                }
+            }
+        }
+        self.token_claims = token_claims.clone();
+        Ok(())
+    }
+
     pub async fn db_insert(&self, db: &Db) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
@@ -93,7 +165,16 @@ impl ProjectDao {
         }
     }
 
+    // A deleted project waiting to be purged is treated as missing
     pub async fn db_select(db: &Db, id: &Uuid) -> Result<Self> {
+        let project_data = Self::db_select_including_deleted(db, id).await?;
+        if project_data.deleted_at.is_some() {
+            return Err(Error::msg(format!("Project '{id}' is deleted")));
+        }
+        Ok(project_data)
+    }
+
+    pub async fn db_select_including_deleted(db: &Db, id: &Uuid) -> Result<Self> {
         match db {
             Db::ScyllaDb(db) => Ok(Self::from_scylladb_model(
                 &Self::scylladb_select(db, id).await?,
@@ -147,6 +228,49 @@ impl ProjectDao {
         }
     }
 
+    pub async fn db_select_many_deleted_before(
+        db: &Db,
+        deleted_before: &DateTime<Utc>,
+    ) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut projects_data = Vec::new();
+                let projects =
+                    Self::scylladb_select_many_deleted_before(db, deleted_before).await?;
+                for project in projects {
+                    projects_data.push(Self::from_scylladb_model(&project?)?);
+                }
+                Ok(projects_data)
+            }
+            Db::PostgresqlDb(db) => {
+                let projects =
+                    Self::postgresdb_select_many_deleted_before(db, deleted_before).await?;
+                let mut projects_data = Vec::with_capacity(projects.len());
+                for project in &projects {
+                    projects_data.push(Self::from_postgresdb_model(project)?);
+                }
+                Ok(projects_data)
+            }
+            Db::MysqlDb(db) => {
+                let projects = Self::mysqldb_select_many_deleted_before(db, deleted_before).await?;
+                let mut projects_data = Vec::with_capacity(projects.len());
+                for project in &projects {
+                    projects_data.push(Self::from_mysqldb_model(project)?);
+                }
+                Ok(projects_data)
+            }
+            Db::SqliteDb(db) => {
+                let projects =
+                    Self::sqlitedb_select_many_deleted_before(db, deleted_before).await?;
+                let mut projects_data = Vec::with_capacity(projects.len());
+                for project in &projects {
+                    projects_data.push(Self::from_sqlitedb_model(project)?);
+                }
+                Ok(projects_data)
+            }
+        }
+    }
+
     pub async fn db_update(&mut self, db: &Db) -> Result<()> {
         self.updated_at = Utc::now();
         match db {
@@ -157,6 +281,23 @@ impl ProjectDao {
         }
     }
 
+    // Marks the project as deleted, or restores it when deleted_at is None, without touching its
+    // collections
+    pub async fn db_update_deleted_at(
+        &mut self,
+        db: &Db,
+        deleted_at: &Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        self.updated_at = Utc::now();
+        self.deleted_at = *deleted_at;
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_update_deleted_at(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_update_deleted_at(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_update_deleted_at(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_update_deleted_at(self, db).await,
+        }
+    }
+
     pub async fn db_delete(db: &Db, id: &Uuid) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => Self::scylladb_delete(db, id).await,
@@ -167,7 +308,8 @@ impl ProjectDao {
     }
 
     async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
-        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()?)
+            .await?;
         Ok(())
     }
 
@@ -188,12 +330,43 @@ impl ProjectDao {
             .rows_typed()?)
     }
 
+    async fn scylladb_select_many_deleted_before(
+        db: &ScyllaDb,
+        deleted_before: &DateTime<Utc>,
+    ) -> Result<ScyllaTypedRowIter<ProjectScyllaModel>> {
+        Ok(db
+            .execute(
+                SCYLLA_SELECT_MANY_DELETED_BEFORE,
+                [ScyllaCqlTimestamp(deleted_before.timestamp_millis())].as_ref(),
+            )
+            .await?
+            .rows_typed()?)
+    }
+
     async fn scylladb_update(&self, db: &ScyllaDb) -> Result<()> {
         db.execute(
             SCYLLA_UPDATE,
             &(
                 &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
                 &self.name,
+                &self.suspended,
+                &self.archived,
+                &self.token_claims_string()?,
+                &self.id,
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn scylladb_update_deleted_at(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(
+            SCYLLA_UPDATE_DELETED_AT,
+            &(
+                &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+                &self
+                    .deleted_at
+                    .map(|deleted_at| ScyllaCqlTimestamp(deleted_at.timestamp_millis())),
                 &self.id,
             ),
         )
@@ -213,7 +386,11 @@ impl ProjectDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.admin_id)
-                .bind(&self.name),
+                .bind(&self.name)
+                .bind(self.suspended)
+                .bind(self.archived)
+                .bind(self.token_claims_string()?)
+                .bind(&self.deleted_at),
         )
         .await?;
         Ok(())
@@ -234,11 +411,34 @@ impl ProjectDao {
             .await?)
     }
 
+    async fn postgresdb_select_many_deleted_before(
+        db: &PostgresDb,
+        deleted_before: &DateTime<Utc>,
+    ) -> Result<Vec<ProjectPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_DELETED_BEFORE).bind(deleted_before))
+            .await?)
+    }
+
     async fn postgresdb_update(&self, db: &PostgresDb) -> Result<()> {
         db.execute(
             sqlx::query(POSTGRES_UPDATE)
                 .bind(&self.updated_at)
                 .bind(&self.name)
+                .bind(self.suspended)
+                .bind(self.archived)
+                .bind(self.token_claims_string()?)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_update_deleted_at(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_UPDATE_DELETED_AT)
+                .bind(&self.updated_at)
+                .bind(&self.deleted_at)
                 .bind(&self.id),
         )
         .await?;
@@ -257,7 +457,11 @@ impl ProjectDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.admin_id)
-                .bind(&self.name),
+                .bind(&self.name)
+                .bind(self.suspended)
+                .bind(self.archived)
+                .bind(self.token_claims_string()?)
+                .bind(&self.deleted_at),
         )
         .await?;
         Ok(())
@@ -276,11 +480,34 @@ impl ProjectDao {
             .await?)
     }
 
+    async fn mysqldb_select_many_deleted_before(
+        db: &MysqlDb,
+        deleted_before: &DateTime<Utc>,
+    ) -> Result<Vec<ProjectMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_DELETED_BEFORE).bind(deleted_before))
+            .await?)
+    }
+
     async fn mysqldb_update(&self, db: &MysqlDb) -> Result<()> {
         db.execute(
             sqlx::query(MYSQL_UPDATE)
                 .bind(&self.updated_at)
                 .bind(&self.name)
+                .bind(self.suspended)
+                .bind(self.archived)
+                .bind(self.token_claims_string()?)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_update_deleted_at(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_UPDATE_DELETED_AT)
+                .bind(&self.updated_at)
+                .bind(&self.deleted_at)
                 .bind(&self.id),
         )
         .await?;
@@ -299,7 +526,11 @@ impl ProjectDao {
                 .bind(&self.created_at)
                 .bind(&self.updated_at)
                 .bind(&self.admin_id)
-                .bind(&self.name),
+                .bind(&self.name)
+                .bind(self.suspended)
+                .bind(self.archived)
+                .bind(self.token_claims_string()?)
+                .bind(&self.deleted_at),
         )
         .await?;
         Ok(())
@@ -318,11 +549,34 @@ impl ProjectDao {
             .await?)
     }
 
+    async fn sqlitedb_select_many_deleted_before(
+        db: &SqliteDb,
+        deleted_before: &DateTime<Utc>,
+    ) -> Result<Vec<ProjectSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_DELETED_BEFORE).bind(deleted_before))
+            .await?)
+    }
+
     async fn sqlitedb_update(&self, db: &SqliteDb) -> Result<()> {
         db.execute(
             sqlx::query(SQLITE_UPDATE)
                 .bind(&self.updated_at)
                 .bind(&self.name)
+                .bind(self.suspended)
+                .bind(self.archived)
+                .bind(self.token_claims_string()?)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_update_deleted_at(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_UPDATE_DELETED_AT)
+                .bind(&self.updated_at)
+                .bind(&self.deleted_at)
                 .bind(&self.id),
         )
         .await?;
@@ -341,17 +595,32 @@ impl ProjectDao {
             updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
             admin_id: *model.admin_id(),
             name: model.name().to_owned(),
+            suspended: *model.suspended(),
+            archived: *model.archived(),
+            token_claims: Self::token_claims_from_string(model.token_claims())?,
+            deleted_at: match model.deleted_at() {
+                Some(deleted_at) => Some(conversion::scylla_cql_timestamp_to_datetime_utc(
+                    deleted_at,
+                )?),
+                None => None,
+            },
         })
     }
 
-    fn to_scylladb_model(&self) -> ProjectScyllaModel {
-        ProjectScyllaModel::new(
+    fn to_scylladb_model(&self) -> Result<ProjectScyllaModel> {
+        Ok(ProjectScyllaModel::new(
             &self.id,
             &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
             &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
             &self.admin_id,
             &self.name,
-        )
+            &self.suspended,
+            &self.archived,
+            &self.token_claims_string()?,
+            &self
+                .deleted_at
+                .map(|deleted_at| ScyllaCqlTimestamp(deleted_at.timestamp_millis())),
+        ))
     }
 
     fn from_postgresdb_model(model: &ProjectPostgresModel) -> Result<Self> {
@@ -361,6 +630,10 @@ impl ProjectDao {
             updated_at: *model.updated_at(),
             admin_id: *model.admin_id(),
             name: model.name().to_owned(),
+            suspended: *model.suspended(),
+            archived: *model.archived(),
+            token_claims: Self::token_claims_from_string(model.token_claims())?,
+            deleted_at: *model.deleted_at(),
         })
     }
 
@@ -371,6 +644,10 @@ impl ProjectDao {
             updated_at: *model.updated_at(),
             admin_id: *model.admin_id(),
             name: model.name().to_owned(),
+            suspended: *model.suspended(),
+            archived: *model.archived(),
+            token_claims: Self::token_claims_from_string(model.token_claims())?,
+            deleted_at: *model.deleted_at(),
         })
     }
 
@@ -381,6 +658,75 @@ impl ProjectDao {
             updated_at: *model.updated_at(),
             admin_id: *model.admin_id(),
             name: model.name().to_owned(),
+            suspended: *model.suspended(),
+            archived: *model.archived(),
+            token_claims: Self::token_claims_from_string(model.token_claims())?,
+            deleted_at: *model.deleted_at(),
         })
     }
+
+    fn token_claims_string(&self) -> Result<Option<String>> {
+        match &self.token_claims {
+            Some(token_claims) => Ok(Some(serde_json::to_string(token_claims)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn token_claims_from_string(
+        token_claims: &Option<String>,
+    ) -> Result<Option<Map<String, Value>>> {
+        match token_claims {
+            Some(token_claims) => Ok(Some(serde_json::from_str(token_claims)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Map, Value};
+    use uuid::Uuid;
+
+    use super::ProjectDao;
+
+    fn claims(value: Value) -> Option<Map<String, Value>> {
+        value.as_object().cloned()
+    }
+
+    #[test]
+    fn set_token_claims_rejects_reserved_claims() {
+        let mut project_data = ProjectDao::new(&Uuid::now_v7(), "project");
+        for key in ["jti", "id", "kind", "iat", "exp", "nbf", "sid", "uid"] {
+            assert!(
+                project_data
+                    .set_token_claims(&claims(json!({ key: Uuid::now_v7().to_string() })))
+                    .is_err(),
+                "claim '{key}' should be rejected"
+            );
+        }
+        assert!(project_data.token_claims().is_none());
+    }
+
+    #[test]
+    fn set_token_claims_accepts_custom_claims() {
+        let mut project_data = ProjectDao::new(&Uuid::now_v7(), "project");
+        assert!(project_data
+            .set_token_claims(&claims(json!({ "tenant": "acme" })))
+            .is_ok());
+        assert_eq!(
+            project_data.custom_token_claims(),
+            *claims(json!({ "tenant": "acme" })).as_ref().unwrap()
+        );
+    }
+
+    #[test]
+    fn custom_token_claims_drops_reserved_claims() {
+        let mut project_data = ProjectDao::new(&Uuid::now_v7(), "project");
+        project_data.token_claims =
+            claims(json!({ "uid": Uuid::now_v7().to_string(), "tenant": "acme" }));
+        assert_eq!(
+            project_data.custom_token_claims(),
+            *claims(json!({ "tenant": "acme" })).as_ref().unwrap()
+        );
+    }
 }
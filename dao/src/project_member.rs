@@ -0,0 +1,557 @@
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::project_member::ProjectMemberModel as ProjectMemberMysqlModel,
+    query::project_member::{
+        DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as MYSQL_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_BY_PROJECT_ID as MYSQL_SELECT_MANY_BY_PROJECT_ID, UPDATE as MYSQL_UPDATE,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::project_member::ProjectMemberModel as ProjectMemberPostgresModel,
+    query::project_member::{
+        DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as POSTGRES_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_BY_PROJECT_ID as POSTGRES_SELECT_MANY_BY_PROJECT_ID, UPDATE as POSTGRES_UPDATE,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::project_member::ProjectMemberModel as ProjectMemberScyllaModel,
+    query::project_member::{
+        DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as SCYLLA_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_BY_PROJECT_ID as SCYLLA_SELECT_MANY_BY_PROJECT_ID, UPDATE as SCYLLA_UPDATE,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::project_member::ProjectMemberModel as ProjectMemberSqliteModel,
+    query::project_member::{
+        DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
+        SELECT_MANY_BY_ADMIN_ID as SQLITE_SELECT_MANY_BY_ADMIN_ID,
+        SELECT_MANY_BY_PROJECT_ID as SQLITE_SELECT_MANY_BY_PROJECT_ID, UPDATE as SQLITE_UPDATE,
+    },
+};
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{admin::AdminRole, project::ProjectDao, util::conversion, Db};
+
+#[derive(Serialize, Deserialize)]
+pub struct ProjectMemberDao {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    project_id: Uuid,
+    admin_id: Uuid,
+    role: AdminRole,
+}
+
+impl ProjectMemberDao {
+    pub fn new(project_id: &Uuid, admin_id: &Uuid, role: &AdminRole) -> Result<Self> {
+        Self::check_role(role)?;
+
+        let now = Utc::now();
+        Ok(Self {
+            id: Uuid::now_v7(),
+            created_at: now,
+            updated_at: now,
+            project_id: *project_id,
+            admin_id: *admin_id,
+            role: *role,
+        })
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn role(&self) -> &AdminRole {
+        &self.role
+    }
+
+    pub fn set_role(&mut self, role: &AdminRole) -> Result<()> {
+        Self::check_role(role)?;
+        self.role = *role;
+        Ok(())
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert(self, db).await,
+        }
+    }
+
+    pub async fn db_select(db: &Db, id: &Uuid) -> Result<Self> {
+        match db {
+            Db::ScyllaDb(db) => Ok(Self::from_scylladb_model(
+                &Self::scylladb_select(db, id).await?,
+            )?),
+            Db::PostgresqlDb(db) => Ok(Self::from_postgresdb_model(
+                &Self::postgresdb_select(db, id).await?,
+            )?),
+            Db::MysqlDb(db) => Ok(Self::from_mysqldb_model(
+                &Self::mysqldb_select(db, id).await?,
+            )?),
+            Db::SqliteDb(db) => Ok(Self::from_sqlitedb_model(
+                &Self::sqlitedb_select(db, id).await?,
+            )?),
+        }
+    }
+
+    pub async fn db_select_many_by_project_id(db: &Db, project_id: &Uuid) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut members_data = Vec::new();
+                let members = Self::scylladb_select_many_by_project_id(db, project_id).await?;
+                for member in members {
+                    members_data.push(Self::from_scylladb_model(&member?)?);
+                }
+                Ok(members_data)
+            }
+            Db::PostgresqlDb(db) => {
+                let members = Self::postgresdb_select_many_by_project_id(db, project_id).await?;
+                let mut members_data = Vec::with_capacity(members.len());
+                for member in &members {
+                    members_data.push(Self::from_postgresdb_model(member)?);
+                }
+                Ok(members_data)
+            }
+            Db::MysqlDb(db) => {
+                let members = Self::mysqldb_select_many_by_project_id(db, project_id).await?;
+                let mut members_data = Vec::with_capacity(members.len());
+                for member in &members {
+                    members_data.push(Self::from_mysqldb_model(member)?);
+                }
+                Ok(members_data)
+            }
+            Db::SqliteDb(db) => {
+                let members = Self::sqlitedb_select_many_by_project_id(db, project_id).await?;
+                let mut members_data = Vec::with_capacity(members.len());
+                for member in &members {
+                    members_data.push(Self::from_sqlitedb_model(member)?);
+                }
+                Ok(members_data)
+            }
+        }
+    }
+
+    pub async fn db_select_many_by_admin_id(db: &Db, admin_id: &Uuid) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut members_data = Vec::new();
+                let members = Self::scylladb_select_many_by_admin_id(db, admin_id).await?;
+                for member in members {
+                    members_data.push(Self::from_scylladb_model(&member?)?);
+                }
+                Ok(members_data)
+            }
+            Db::PostgresqlDb(db) => {
+                let members = Self::postgresdb_select_many_by_admin_id(db, admin_id).await?;
+                let mut members_data = Vec::with_capacity(members.len());
+                for member in &members {
+                    members_data.push(Self::from_postgresdb_model(member)?);
+                }
+                Ok(members_data)
+            }
+            Db::MysqlDb(db) => {
+                let members = Self::mysqldb_select_many_by_admin_id(db, admin_id).await?;
+                let mut members_data = Vec::with_capacity(members.len());
+                for member in &members {
+                    members_data.push(Self::from_mysqldb_model(member)?);
+                }
+                Ok(members_data)
+            }
+            Db::SqliteDb(db) => {
+                let members = Self::sqlitedb_select_many_by_admin_id(db, admin_id).await?;
+                let mut members_data = Vec::with_capacity(members.len());
+                for member in &members {
+                    members_data.push(Self::from_sqlitedb_model(member)?);
+                }
+                Ok(members_data)
+            }
+        }
+    }
+
+    // The project owner always has the owner role, other admins get the role of
+    // their membership or None if they are not a member of the project
+    pub async fn db_select_role(
+        db: &Db,
+        project_data: &ProjectDao,
+        admin_id: &Uuid,
+    ) -> Result<Option<AdminRole>> {
+        if project_data.admin_id() == admin_id {
+            return Ok(Some(AdminRole::Owner));
+        }
+        Ok(Self::db_select_many_by_project_id(db, project_data.id())
+            .await?
+            .iter()
+            .find(|member| member.admin_id() == admin_id)
+            .map(|member| *member.role()))
+    }
+
+    pub async fn db_update(&mut self, db: &Db) -> Result<()> {
+        self.updated_at = Utc::now();
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_update(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_update(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_update(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_update(self, db).await,
+        }
+    }
+
+    pub async fn db_delete(db: &Db, id: &Uuid) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_delete(db, id).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_delete(db, id).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete(db, id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete(db, id).await,
+        }
+    }
+
+    async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        Ok(())
+    }
+
+    async fn scylladb_select(db: &ScyllaDb, id: &Uuid) -> Result<ProjectMemberScyllaModel> {
+        Ok(db
+            .execute(SCYLLA_SELECT, [id].as_ref())
+            .await?
+            .first_row_typed::<ProjectMemberScyllaModel>()?)
+    }
+
+    async fn scylladb_select_many_by_project_id(
+        db: &ScyllaDb,
+        project_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<ProjectMemberScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_PROJECT_ID, [project_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn scylladb_select_many_by_admin_id(
+        db: &ScyllaDb,
+        admin_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<ProjectMemberScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_ADMIN_ID, [admin_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn scylladb_update(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(
+            SCYLLA_UPDATE,
+            &(
+                &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+                self.role.to_str(),
+                &self.id,
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn scylladb_delete(db: &ScyllaDb, id: &Uuid) -> Result<()> {
+        db.execute(SCYLLA_DELETE, [id].as_ref()).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.admin_id)
+                .bind(self.role.to_str()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select(db: &PostgresDb, id: &Uuid) -> Result<ProjectMemberPostgresModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(POSTGRES_SELECT).bind(id))
+            .await?)
+    }
+
+    async fn postgresdb_select_many_by_project_id(
+        db: &PostgresDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<ProjectMemberPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    async fn postgresdb_select_many_by_admin_id(
+        db: &PostgresDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<ProjectMemberPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn postgresdb_update(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_UPDATE)
+                .bind(&self.updated_at)
+                .bind(self.role.to_str())
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_delete(db: &PostgresDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(POSTGRES_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.admin_id)
+                .bind(self.role.to_str()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select(db: &MysqlDb, id: &Uuid) -> Result<ProjectMemberMysqlModel> {
+        Ok(db.fetch_one(sqlx::query_as(MYSQL_SELECT).bind(id)).await?)
+    }
+
+    async fn mysqldb_select_many_by_project_id(
+        db: &MysqlDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<ProjectMemberMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    async fn mysqldb_select_many_by_admin_id(
+        db: &MysqlDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<ProjectMemberMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn mysqldb_update(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_UPDATE)
+                .bind(&self.updated_at)
+                .bind(self.role.to_str())
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_delete(db: &MysqlDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(MYSQL_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.admin_id)
+                .bind(self.role.to_str()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select(db: &SqliteDb, id: &Uuid) -> Result<ProjectMemberSqliteModel> {
+        Ok(db.fetch_one(sqlx::query_as(SQLITE_SELECT).bind(id)).await?)
+    }
+
+    async fn sqlitedb_select_many_by_project_id(
+        db: &SqliteDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<ProjectMemberSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    async fn sqlitedb_select_many_by_admin_id(
+        db: &SqliteDb,
+        admin_id: &Uuid,
+    ) -> Result<Vec<ProjectMemberSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_ADMIN_ID).bind(admin_id))
+            .await?)
+    }
+
+    async fn sqlitedb_update(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_UPDATE)
+                .bind(&self.updated_at)
+                .bind(self.role.to_str())
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_delete(db: &SqliteDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(SQLITE_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    fn from_scylladb_model(model: &ProjectMemberScyllaModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.created_at())?,
+            updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
+            project_id: *model.project_id(),
+            admin_id: *model.admin_id(),
+            role: model.role().parse()?,
+        })
+    }
+
+    fn to_scylladb_model(&self) -> ProjectMemberScyllaModel {
+        ProjectMemberScyllaModel::new(
+            &self.id,
+            &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+            &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+            &self.project_id,
+            &self.admin_id,
+            self.role.to_str(),
+        )
+    }
+
+    fn from_postgresdb_model(model: &ProjectMemberPostgresModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            admin_id: *model.admin_id(),
+            role: model.role().parse()?,
+        })
+    }
+
+    fn from_mysqldb_model(model: &ProjectMemberMysqlModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            admin_id: *model.admin_id(),
+            role: model.role().parse()?,
+        })
+    }
+
+    fn from_sqlitedb_model(model: &ProjectMemberSqliteModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            admin_id: *model.admin_id(),
+            role: model.role().parse()?,
+        })
+    }
+
+    fn check_role(role: &AdminRole) -> Result<()> {
+        if *role == AdminRole::Owner {
+            return Err(Error::msg(
+                "The owner role can't be given to a project member",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hb_db_sqlite::db::SqliteDb;
+    use uuid::Uuid;
+
+    use super::ProjectMemberDao;
+    use crate::{admin::AdminRole, project::ProjectDao, Db};
+
+    #[test]
+    fn owner_role_is_reserved_for_the_project_owner() {
+        assert!(
+            ProjectMemberDao::new(&Uuid::now_v7(), &Uuid::now_v7(), &AdminRole::Owner).is_err()
+        );
+
+        let mut member_data =
+            ProjectMemberDao::new(&Uuid::now_v7(), &Uuid::now_v7(), &AdminRole::Viewer).unwrap();
+        assert!(member_data.set_role(&AdminRole::Owner).is_err());
+        assert!(*member_data.role() == AdminRole::Viewer);
+    }
+
+    #[tokio::test]
+    async fn select_role_follows_the_membership() {
+        let db = Db::SqliteDb(SqliteDb::new(":memory:", &1, &600, &600, &None).await);
+        let (owner_id, member_id, stranger_id) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let project_data = ProjectDao::new(&owner_id, "project");
+        project_data.db_insert(&db).await.unwrap();
+
+        let mut member_data =
+            ProjectMemberDao::new(project_data.id(), &member_id, &AdminRole::Viewer).unwrap();
+        member_data.db_insert(&db).await.unwrap();
+
+        let role = |admin_id| ProjectMemberDao::db_select_role(&db, &project_data, admin_id);
+        assert!(role(&owner_id).await.unwrap() == Some(AdminRole::Owner));
+        assert!(role(&member_id).await.unwrap() == Some(AdminRole::Viewer));
+        assert!(role(&stranger_id).await.unwrap().is_none());
+
+        member_data.set_role(&AdminRole::Editor).unwrap();
+        member_data.db_update(&db).await.unwrap();
+        assert!(role(&member_id).await.unwrap() == Some(AdminRole::Editor));
+
+        ProjectMemberDao::db_delete(&db, member_data.id())
+            .await
+            .unwrap();
+        assert!(role(&member_id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::{
+    collection::CollectionDao, project::ProjectDao, project_member::ProjectMemberDao,
+    user::UserDao, Db,
+};
+
+// Hard deletes projects once they have been soft deleted for longer than the grace period. A
+// failing project doesn't stop the others from being purged, but the first error is returned
+// afterwards.
+pub async fn purge(db: &Db, grace_period: &Duration) -> Result<String> {
+    let deleted_before = Utc::now() - chrono::Duration::from_std(*grace_period)?;
+
+    let mut purged = 0;
+    let mut first_err = None;
+    for project_data in ProjectDao::db_select_many_deleted_before(db, &deleted_before).await? {
+        match purge_project(db, &project_data).await {
+            Ok(_) => {
+                hb_log::info(
+                    Some("🧹"),
+                    format!("ProjectPurger: Purged project '{}'", project_data.id()),
+                );
+                purged += 1;
+            }
+            Err(err) => {
+                hb_log::error(None, &err);
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+
+    match first_err {
+        Some(err) => Err(err),
+        None => Ok(format!("Purged {purged} projects")),
+    }
+}
+
+// The project row goes last so a failed purge is retried on the next run
+async fn purge_project(db: &Db, project_data: &ProjectDao) -> Result<()> {
+    for collection_data in CollectionDao::db_select_many_by_project_id(db, project_data.id()).await?
+    {
+        CollectionDao::db_delete(db, collection_data.id()).await?;
+    }
+
+    for member_data in ProjectMemberDao::db_select_many_by_project_id(db, project_data.id()).await?
+    {
+        ProjectMemberDao::db_delete(db, member_data.id()).await?;
+    }
+
+    for user_data in UserDao::db_select_many_by_project_id(db, project_data.id()).await? {
+        UserDao::db_delete(db, user_data.id()).await?;
+    }
+
+    ProjectDao::db_delete(db, project_data.id()).await
+}
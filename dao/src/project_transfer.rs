@@ -0,0 +1,436 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::project_transfer::ProjectTransferModel as ProjectTransferMysqlModel,
+    query::{
+        project::UPDATE_ADMIN_ID as MYSQL_PROJECT_UPDATE_ADMIN_ID,
+        project_transfer::{
+            DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
+            SELECT_MANY_BY_TO_ADMIN_ID as MYSQL_SELECT_MANY_BY_TO_ADMIN_ID,
+        },
+        token::UPDATE_ADMIN_ID as MYSQL_TOKEN_UPDATE_ADMIN_ID,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::project_transfer::ProjectTransferModel as ProjectTransferPostgresModel,
+    query::{
+        project::UPDATE_ADMIN_ID as POSTGRES_PROJECT_UPDATE_ADMIN_ID,
+        project_transfer::{
+            DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
+            SELECT_MANY_BY_TO_ADMIN_ID as POSTGRES_SELECT_MANY_BY_TO_ADMIN_ID,
+        },
+        token::UPDATE_ADMIN_ID as POSTGRES_TOKEN_UPDATE_ADMIN_ID,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::project_transfer::ProjectTransferModel as ProjectTransferScyllaModel,
+    query::{
+        project::UPDATE_ADMIN_ID as SCYLLA_PROJECT_UPDATE_ADMIN_ID,
+        project_transfer::{
+            DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
+            SELECT_MANY_BY_TO_ADMIN_ID as SCYLLA_SELECT_MANY_BY_TO_ADMIN_ID,
+        },
+        token::UPDATE_ADMIN_ID as SCYLLA_TOKEN_UPDATE_ADMIN_ID,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::project_transfer::ProjectTransferModel as ProjectTransferSqliteModel,
+    query::{
+        project::UPDATE_ADMIN_ID as SQLITE_PROJECT_UPDATE_ADMIN_ID,
+        project_transfer::{
+            DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
+            SELECT_MANY_BY_TO_ADMIN_ID as SQLITE_SELECT_MANY_BY_TO_ADMIN_ID,
+        },
+        token::UPDATE_ADMIN_ID as SQLITE_TOKEN_UPDATE_ADMIN_ID,
+    },
+};
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
+use uuid::Uuid;
+
+use crate::{util::conversion, Db};
+
+pub struct ProjectTransferDao {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    project_id: Uuid,
+    from_admin_id: Uuid,
+    to_admin_id: Uuid,
+}
+
+impl ProjectTransferDao {
+    pub fn new(project_id: &Uuid, from_admin_id: &Uuid, to_admin_id: &Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::now_v7(),
+            created_at: now,
+            updated_at: now,
+            project_id: *project_id,
+            from_admin_id: *from_admin_id,
+            to_admin_id: *to_admin_id,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn from_admin_id(&self) -> &Uuid {
+        &self.from_admin_id
+    }
+
+    pub fn to_admin_id(&self) -> &Uuid {
+        &self.to_admin_id
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert(self, db).await,
+        }
+    }
+
+    pub async fn db_select(db: &Db, id: &Uuid) -> Result<Self> {
+        match db {
+            Db::ScyllaDb(db) => Self::from_scylladb_model(&Self::scylladb_select(db, id).await?),
+            Db::PostgresqlDb(db) => Ok(Self::from_postgresdb_model(
+                &Self::postgresdb_select(db, id).await?,
+            )),
+            Db::MysqlDb(db) => Ok(Self::from_mysqldb_model(
+                &Self::mysqldb_select(db, id).await?,
+            )),
+            Db::SqliteDb(db) => Ok(Self::from_sqlitedb_model(
+                &Self::sqlitedb_select(db, id).await?,
+            )),
+        }
+    }
+
+    pub async fn db_select_many_by_to_admin_id(db: &Db, to_admin_id: &Uuid) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut transfers_data = Vec::new();
+                let transfers = Self::scylladb_select_many_by_to_admin_id(db, to_admin_id).await?;
+                for transfer in transfers {
+                    transfers_data.push(Self::from_scylladb_model(&transfer?)?);
+                }
+                Ok(transfers_data)
+            }
+            Db::PostgresqlDb(db) => {
+                Ok(Self::postgresdb_select_many_by_to_admin_id(db, to_admin_id)
+                    .await?
+                    .iter()
+                    .map(Self::from_postgresdb_model)
+                    .collect())
+            }
+            Db::MysqlDb(db) => Ok(Self::mysqldb_select_many_by_to_admin_id(db, to_admin_id)
+                .await?
+                .iter()
+                .map(Self::from_mysqldb_model)
+                .collect()),
+            Db::SqliteDb(db) => Ok(Self::sqlitedb_select_many_by_to_admin_id(db, to_admin_id)
+                .await?
+                .iter()
+                .map(Self::from_sqlitedb_model)
+                .collect()),
+        }
+    }
+
+    // Moves the project and the given tokens to the receiving admin in a single
+    // transaction (a logged batch in ScyllaDB), then removes the transfer request
+    pub async fn db_accept(&self, db: &Db, token_ids: &[Uuid]) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_accept(self, db, token_ids).await?,
+            Db::PostgresqlDb(db) => Self::postgresdb_accept(self, db, token_ids).await?,
+            Db::MysqlDb(db) => Self::mysqldb_accept(self, db, token_ids).await?,
+            Db::SqliteDb(db) => Self::sqlitedb_accept(self, db, token_ids).await?,
+        }
+        Self::db_delete(db, &self.id).await
+    }
+
+    pub async fn db_delete(db: &Db, id: &Uuid) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_delete(db, id).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_delete(db, id).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete(db, id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete(db, id).await,
+        }
+    }
+
+    async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        Ok(())
+    }
+
+    async fn scylladb_select(db: &ScyllaDb, id: &Uuid) -> Result<ProjectTransferScyllaModel> {
+        Ok(db
+            .execute(SCYLLA_SELECT, [id].as_ref())
+            .await?
+            .first_row_typed::<ProjectTransferScyllaModel>()?)
+    }
+
+    async fn scylladb_select_many_by_to_admin_id(
+        db: &ScyllaDb,
+        to_admin_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<ProjectTransferScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_TO_ADMIN_ID, [to_admin_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn scylladb_accept(&self, db: &ScyllaDb, token_ids: &[Uuid]) -> Result<()> {
+        let updated_at = ScyllaCqlTimestamp(Utc::now().timestamp_millis());
+        let mut queries = Vec::with_capacity(token_ids.len() + 1);
+        let mut values = Vec::with_capacity(token_ids.len() + 1);
+        queries.push(SCYLLA_PROJECT_UPDATE_ADMIN_ID);
+        values.push((updated_at, self.to_admin_id, self.project_id));
+        for token_id in token_ids {
+            queries.push(SCYLLA_TOKEN_UPDATE_ADMIN_ID);
+            values.push((updated_at, self.to_admin_id, *token_id));
+        }
+        db.batch(&queries, values).await?;
+        Ok(())
+    }
+
+    async fn scylladb_delete(db: &ScyllaDb, id: &Uuid) -> Result<()> {
+        db.execute(SCYLLA_DELETE, [id].as_ref()).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.from_admin_id)
+                .bind(&self.to_admin_id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select(db: &PostgresDb, id: &Uuid) -> Result<ProjectTransferPostgresModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(POSTGRES_SELECT).bind(id))
+            .await?)
+    }
+
+    async fn postgresdb_select_many_by_to_admin_id(
+        db: &PostgresDb,
+        to_admin_id: &Uuid,
+    ) -> Result<Vec<ProjectTransferPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_TO_ADMIN_ID).bind(to_admin_id))
+            .await?)
+    }
+
+    async fn postgresdb_accept(&self, db: &PostgresDb, token_ids: &[Uuid]) -> Result<()> {
+        let updated_at = Utc::now();
+        let mut queries = Vec::with_capacity(token_ids.len() + 1);
+        queries.push(
+            sqlx::query(POSTGRES_PROJECT_UPDATE_ADMIN_ID)
+                .bind(updated_at)
+                .bind(self.to_admin_id)
+                .bind(self.project_id),
+        );
+        for token_id in token_ids {
+            queries.push(
+                sqlx::query(POSTGRES_TOKEN_UPDATE_ADMIN_ID)
+                    .bind(updated_at)
+                    .bind(self.to_admin_id)
+                    .bind(*token_id),
+            );
+        }
+        db.execute_transaction(queries).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_delete(db: &PostgresDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(POSTGRES_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.from_admin_id)
+                .bind(&self.to_admin_id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select(db: &MysqlDb, id: &Uuid) -> Result<ProjectTransferMysqlModel> {
+        Ok(db.fetch_one(sqlx::query_as(MYSQL_SELECT).bind(id)).await?)
+    }
+
+    async fn mysqldb_select_many_by_to_admin_id(
+        db: &MysqlDb,
+        to_admin_id: &Uuid,
+    ) -> Result<Vec<ProjectTransferMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_TO_ADMIN_ID).bind(to_admin_id))
+            .await?)
+    }
+
+    async fn mysqldb_accept(&self, db: &MysqlDb, token_ids: &[Uuid]) -> Result<()> {
+        let updated_at = Utc::now();
+        let mut queries = Vec::with_capacity(token_ids.len() + 1);
+        queries.push(
+            sqlx::query(MYSQL_PROJECT_UPDATE_ADMIN_ID)
+                .bind(updated_at)
+                .bind(self.to_admin_id)
+                .bind(self.project_id),
+        );
+        for token_id in token_ids {
+            queries.push(
+                sqlx::query(MYSQL_TOKEN_UPDATE_ADMIN_ID)
+                    .bind(updated_at)
+                    .bind(self.to_admin_id)
+                    .bind(*token_id),
+            );
+        }
+        db.execute_transaction(queries).await?;
+        Ok(())
+    }
+
+    async fn mysqldb_delete(db: &MysqlDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(MYSQL_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.from_admin_id)
+                .bind(&self.to_admin_id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select(db: &SqliteDb, id: &Uuid) -> Result<ProjectTransferSqliteModel> {
+        Ok(db.fetch_one(sqlx::query_as(SQLITE_SELECT).bind(id)).await?)
+    }
+
+    async fn sqlitedb_select_many_by_to_admin_id(
+        db: &SqliteDb,
+        to_admin_id: &Uuid,
+    ) -> Result<Vec<ProjectTransferSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_TO_ADMIN_ID).bind(to_admin_id))
+            .await?)
+    }
+
+    async fn sqlitedb_accept(&self, db: &SqliteDb, token_ids: &[Uuid]) -> Result<()> {
+        let updated_at = Utc::now();
+        let mut queries = Vec::with_capacity(token_ids.len() + 1);
+        queries.push(
+            sqlx::query(SQLITE_PROJECT_UPDATE_ADMIN_ID)
+                .bind(updated_at)
+                .bind(self.to_admin_id)
+                .bind(self.project_id),
+        );
+        for token_id in token_ids {
+            queries.push(
+                sqlx::query(SQLITE_TOKEN_UPDATE_ADMIN_ID)
+                    .bind(updated_at)
+                    .bind(self.to_admin_id)
+                    .bind(*token_id),
+            );
+        }
+        db.execute_transaction(queries).await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_delete(db: &SqliteDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(SQLITE_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    fn from_scylladb_model(model: &ProjectTransferScyllaModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.created_at())?,
+            updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
+            project_id: *model.project_id(),
+            from_admin_id: *model.from_admin_id(),
+            to_admin_id: *model.to_admin_id(),
+        })
+    }
+
+    fn to_scylladb_model(&self) -> ProjectTransferScyllaModel {
+        ProjectTransferScyllaModel::new(
+            &self.id,
+            &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+            &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+            &self.project_id,
+            &self.from_admin_id,
+            &self.to_admin_id,
+        )
+    }
+
+    fn from_postgresdb_model(model: &ProjectTransferPostgresModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            from_admin_id: *model.from_admin_id(),
+            to_admin_id: *model.to_admin_id(),
+        }
+    }
+
+    fn from_mysqldb_model(model: &ProjectTransferMysqlModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            from_admin_id: *model.from_admin_id(),
+            to_admin_id: *model.to_admin_id(),
+        }
+    }
+
+    fn from_sqlitedb_model(model: &ProjectTransferSqliteModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            from_admin_id: *model.from_admin_id(),
+            to_admin_id: *model.to_admin_id(),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::project_usage::ProjectUsageModel as ProjectUsageMysqlModel,
+    query::project_usage::{
+        SELECT_MANY_BY_PROJECT_ID as MYSQL_SELECT_MANY_BY_PROJECT_ID, UPSERT as MYSQL_UPSERT,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::project_usage::ProjectUsageModel as ProjectUsagePostgresModel,
+    query::project_usage::{
+        SELECT_MANY_BY_PROJECT_ID as POSTGRES_SELECT_MANY_BY_PROJECT_ID, UPSERT as POSTGRES_UPSERT,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::project_usage::ProjectUsageModel as ProjectUsageScyllaModel,
+    query::project_usage::{
+        SELECT_MANY_BY_PROJECT_ID as SCYLLA_SELECT_MANY_BY_PROJECT_ID, UPSERT as SCYLLA_UPSERT,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::project_usage::ProjectUsageModel as ProjectUsageSqliteModel,
+    query::project_usage::{
+        SELECT_MANY_BY_PROJECT_ID as SQLITE_SELECT_MANY_BY_PROJECT_ID, UPSERT as SQLITE_UPSERT,
+    },
+};
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
+use uuid::Uuid;
+
+use crate::{util::conversion, Db};
+
+// One row per project per UTC day. Requests accumulate over the day while the record count and
+// storage bytes hold the latest snapshot.
+pub struct ProjectUsageDao {
+    project_id: Uuid,
+    period: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    request_count: i64,
+    record_count: i64,
+    storage_bytes: i64,
+}
+
+impl ProjectUsageDao {
+    pub fn new(project_id: &Uuid, period: &DateTime<Utc>) -> Self {
+        Self {
+            project_id: *project_id,
+            period: *period,
+            updated_at: Utc::now(),
+            request_count: 0,
+            record_count: 0,
+            storage_bytes: 0,
+        }
+    }
+
+    pub fn current_period() -> DateTime<Utc> {
+        Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn period(&self) -> &DateTime<Utc> {
+        &self.period
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn request_count(&self) -> &i64 {
+        &self.request_count
+    }
+
+    pub fn record_count(&self) -> &i64 {
+        &self.record_count
+    }
+
+    pub fn storage_bytes(&self) -> &i64 {
+        &self.storage_bytes
+    }
+
+    pub fn add_requests(&mut self, request_count: &i64) {
+        self.request_count += request_count;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn set_storage(&mut self, record_count: &i64, storage_bytes: &i64) {
+        self.record_count = *record_count;
+        self.storage_bytes = *storage_bytes;
+        self.updated_at = Utc::now();
+    }
+
+    pub async fn db_upsert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_upsert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_upsert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_upsert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_upsert(self, db).await,
+        }
+    }
+
+    // Newest period first
+    pub async fn db_select_many_by_project_id(
+        db: &Db,
+        project_id: &Uuid,
+        limit: &i64,
+    ) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut project_usages_data = Vec::new();
+                let project_usages =
+                    Self::scylladb_select_many_by_project_id(db, project_id, limit).await?;
+                for project_usage in project_usages {
+                    project_usages_data.push(Self::from_scylladb_model(&project_usage?)?);
+                }
+                Ok(project_usages_data)
+            }
+            Db::PostgresqlDb(db) => {
+                let project_usages =
+                    Self::postgresdb_select_many_by_project_id(db, project_id, limit).await?;
+                let mut project_usages_data = Vec::with_capacity(project_usages.len());
+                for project_usage in &project_usages {
+                    project_usages_data.push(Self::from_postgresdb_model(project_usage)?);
+                }
+                Ok(project_usages_data)
+            }
+            Db::MysqlDb(db) => {
+                let project_usages =
+                    Self::mysqldb_select_many_by_project_id(db, project_id, limit).await?;
+                let mut project_usages_data = Vec::with_capacity(project_usages.len());
+                for project_usage in &project_usages {
+                    project_usages_data.push(Self::from_mysqldb_model(project_usage)?);
+                }
+                Ok(project_usages_data)
+            }
+            Db::SqliteDb(db) => {
+                let project_usages =
+                    Self::sqlitedb_select_many_by_project_id(db, project_id, limit).await?;
+                let mut project_usages_data = Vec::with_capacity(project_usages.len());
+                for project_usage in &project_usages {
+                    project_usages_data.push(Self::from_sqlitedb_model(project_usage)?);
+                }
+                Ok(project_usages_data)
+            }
+        }
+    }
+
+    async fn scylladb_upsert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(SCYLLA_UPSERT, &self.to_scylladb_model()).await?;
+        Ok(())
+    }
+
+    async fn scylladb_select_many_by_project_id(
+        db: &ScyllaDb,
+        project_id: &Uuid,
+        limit: &i64,
+    ) -> Result<ScyllaTypedRowIter<ProjectUsageScyllaModel>> {
+        Ok(db
+            .execute(
+                SCYLLA_SELECT_MANY_BY_PROJECT_ID,
+                &(project_id, &i32::try_from(*limit)?),
+            )
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn postgresdb_upsert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_UPSERT)
+                .bind(&self.project_id)
+                .bind(&self.period)
+                .bind(&self.updated_at)
+                .bind(&self.request_count)
+                .bind(&self.record_count)
+                .bind(&self.storage_bytes),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select_many_by_project_id(
+        db: &PostgresDb,
+        project_id: &Uuid,
+        limit: &i64,
+    ) -> Result<Vec<ProjectUsagePostgresModel>> {
+        Ok(db
+            .fetch_all(
+                sqlx::query_as(POSTGRES_SELECT_MANY_BY_PROJECT_ID)
+                    .bind(project_id)
+                    .bind(limit),
+            )
+            .await?)
+    }
+
+    async fn mysqldb_upsert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_UPSERT)
+                .bind(&self.project_id)
+                .bind(&self.period)
+                .bind(&self.updated_at)
+                .bind(&self.request_count)
+                .bind(&self.record_count)
+                .bind(&self.storage_bytes),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select_many_by_project_id(
+        db: &MysqlDb,
+        project_id: &Uuid,
+        limit: &i64,
+    ) -> Result<Vec<ProjectUsageMysqlModel>> {
+        Ok(db
+            .fetch_all(
+                sqlx::query_as(MYSQL_SELECT_MANY_BY_PROJECT_ID)
+                    .bind(project_id)
+                    .bind(limit),
+            )
+            .await?)
+    }
+
+    async fn sqlitedb_upsert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_UPSERT)
+                .bind(&self.project_id)
+                .bind(&self.period)
+                .bind(&self.updated_at)
+                .bind(&self.request_count)
+                .bind(&self.record_count)
+                .bind(&self.storage_bytes),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select_many_by_project_id(
+        db: &SqliteDb,
+        project_id: &Uuid,
+        limit: &i64,
+    ) -> Result<Vec<ProjectUsageSqliteModel>> {
+        Ok(db
+            .fetch_all(
+                sqlx::query_as(SQLITE_SELECT_MANY_BY_PROJECT_ID)
+                    .bind(project_id)
+                    .bind(limit),
+            )
+            .await?)
+    }
+
+    fn from_scylladb_model(model: &ProjectUsageScyllaModel) -> Result<Self> {
+        Ok(Self {
+            project_id: *model.project_id(),
+            period: conversion::scylla_cql_timestamp_to_datetime_utc(model.period())?,
+            updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
+            request_count: *model.request_count(),
+            record_count: *model.record_count(),
+            storage_bytes: *model.storage_bytes(),
+        })
+    }
+
+    fn to_scylladb_model(&self) -> ProjectUsageScyllaModel {
+        ProjectUsageScyllaModel::new(
+            &self.project_id,
+            &ScyllaCqlTimestamp(self.period.timestamp_millis()),
+            &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+            &self.request_count,
+            &self.record_count,
+            &self.storage_bytes,
+        )
+    }
+
+    fn from_postgresdb_model(model: &ProjectUsagePostgresModel) -> Result<Self> {
+        Ok(Self {
+            project_id: *model.project_id(),
+            period: *model.period(),
+            updated_at: *model.updated_at(),
+            request_count: *model.request_count(),
+            record_count: *model.record_count(),
+            storage_bytes: *model.storage_bytes(),
+        })
+    }
+
+    fn from_mysqldb_model(model: &ProjectUsageMysqlModel) -> Result<Self> {
+        Ok(Self {
+            project_id: *model.project_id(),
+            period: *model.period(),
+            updated_at: *model.updated_at(),
+            request_count: *model.request_count(),
+            record_count: *model.record_count(),
+            storage_bytes: *model.storage_bytes(),
+        })
+    }
+
+    fn from_sqlitedb_model(model: &ProjectUsageSqliteModel) -> Result<Self> {
+        Ok(Self {
+            project_id: *model.project_id(),
+            period: *model.period(),
+            updated_at: *model.updated_at(),
+            request_count: *model.request_count(),
+            record_count: *model.record_count(),
+            storage_bytes: *model.storage_bytes(),
+        })
+    }
+}
@@ -2,27 +2,49 @@ use std::collections::hash_map::Keys;
 
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
 use anyhow::{Error, Result};
+use chrono::Utc;
+use futures::StreamExt;
 use hb_db_mysql::{
     db::MysqlDb,
     model::{
-        collection::SchemaFieldPropsModel as SchemaFieldPropsMysqlModel,
+        collection::{
+            PartialIndexModel as PartialIndexMysqlModel,
+            SchemaFieldPropsModel as SchemaFieldPropsMysqlModel,
+        },
         system::{
             COMPARISON_OPERATOR as MYSQL_COMPARISON_OPERATOR,
-            LOGICAL_OPERATOR as MYSQL_LOGICAL_OPERATOR, ORDER_TYPE as MYSQL_ORDER_TYPE,
+            LOGICAL_OPERATOR as MYSQL_LOGICAL_OPERATOR, NULLARY_OPERATOR as MYSQL_NULLARY_OPERATOR,
+            ORDER_TYPE as MYSQL_ORDER_TYPE,
+        },
+    },
+    query::{
+        record as mysql_record,
+        system::{
+            COUNT_COLUMN as MYSQL_COUNT_COLUMN, COUNT_TABLE as MYSQL_COUNT_TABLE,
+            TABLE_SIZE as MYSQL_TABLE_SIZE,
         },
     },
-    query::{record as mysql_record, system::COUNT_TABLE as MYSQL_COUNT_TABLE},
 };
 use hb_db_postgresql::{
     db::PostgresDb,
     model::{
-        collection::SchemaFieldPropsModel as SchemaFieldPropsPostgresModel,
+        collection::{
+            PartialIndexModel as PartialIndexPostgresModel,
+            SchemaFieldPropsModel as SchemaFieldPropsPostgresModel,
+        },
         system::{
             COMPARISON_OPERATOR as POSTGRES_COMPARISON_OPERATOR,
-            LOGICAL_OPERATOR as POSTGRES_LOGICAL_OPERATOR, ORDER_TYPE as POSTGRES_ORDER_TYPE,
+            LOGICAL_OPERATOR as POSTGRES_LOGICAL_OPERATOR,
+            NULLARY_OPERATOR as POSTGRES_NULLARY_OPERATOR, ORDER_TYPE as POSTGRES_ORDER_TYPE,
+        },
+    },
+    query::{
+        record as postgres_record,
+        system::{
+            COUNT_COLUMN as POSTGRES_COUNT_COLUMN, COUNT_TABLE as POSTGRES_COUNT_TABLE,
+            TABLE_SIZE as POSTGRES_TABLE_SIZE,
         },
     },
-    query::{record as postgres_record, system::COUNT_TABLE as POSTGRES_COUNT_TABLE},
 };
 use hb_db_scylladb::{
     db::ScyllaDb,
@@ -30,27 +52,49 @@ use hb_db_scylladb::{
         collection::SchemaFieldPropsModel as SchemaFieldPropsScyllaModel,
         system::{
             COMPARISON_OPERATOR as SCYLLA_COMPARISON_OPERATOR,
-            LOGICAL_OPERATOR as SCYLLA_LOGICAL_OPERATOR, ORDER_TYPE as SCYLLA_ORDER_TYPE,
+            LOGICAL_OPERATOR as SCYLLA_LOGICAL_OPERATOR,
+            NULLARY_OPERATOR as SCYLLA_NULLARY_OPERATOR, ORDER_TYPE as SCYLLA_ORDER_TYPE,
+        },
+    },
+    query::{
+        record as scylla_record,
+        system::{
+            COUNT_COLUMN as SCYLLA_COUNT_COLUMN, COUNT_TABLE as SCYLLA_COUNT_TABLE,
+            SELECT_SIZE_ESTIMATES as SCYLLA_SELECT_SIZE_ESTIMATES,
         },
     },
-    query::{record as scylla_record, system::COUNT_TABLE as SCYLLA_COUNT_TABLE},
 };
 use hb_db_sqlite::{
     db::SqliteDb,
     model::{
-        collection::SchemaFieldPropsModel as SchemaFieldPropsSqliteModel,
+        collection::{
+            PartialIndexModel as PartialIndexSqliteModel,
+            SchemaFieldPropsModel as SchemaFieldPropsSqliteModel,
+        },
         system::{
             COMPARISON_OPERATOR as SQLITE_COMPARISON_OPERATOR,
-            LOGICAL_OPERATOR as SQLITE_LOGICAL_OPERATOR, ORDER_TYPE as SQLITE_ORDER_TYPE,
+            LOGICAL_OPERATOR as SQLITE_LOGICAL_OPERATOR,
+            NULLARY_OPERATOR as SQLITE_NULLARY_OPERATOR, ORDER_TYPE as SQLITE_ORDER_TYPE,
         },
     },
-    query::{record as sqlite_record, system::COUNT_TABLE as SQLITE_COUNT_TABLE},
+    query::{
+        record as sqlite_record,
+        system::{
+            COUNT_COLUMN as SQLITE_COUNT_COLUMN, COUNT_TABLE as SQLITE_COUNT_TABLE,
+            TABLE_SIZE as SQLITE_TABLE_SIZE,
+        },
+    },
+};
+use scylla::{
+    frame::response::result::CqlValue as ScyllaCqlValue, serialize::value::SerializeCql,
+    QueryResult,
 };
-use scylla::{frame::response::result::CqlValue as ScyllaCqlValue, serialize::value::SerializeCql};
 use uuid::Uuid;
 
 use crate::{
-    collection::{CollectionDao, SchemaFieldProps},
+    collection::{
+        CollectionDao, FieldValidationError, PartialIndex, SchemaFieldProps, SchemaFieldValidation,
+    },
     value::{ColumnKind, ColumnValue},
     Db,
 };
@@ -102,6 +146,65 @@ impl RecordDao {
         self.data.insert(key.to_owned(), value.to_owned());
     }
 
+    pub fn check_size(&self, collection_data: &CollectionDao) -> Result<()> {
+        let mut record_size = 0;
+        for (field, value) in &self.data {
+            let size = value.size();
+            if let Some(max_field_size) = collection_data.max_field_size() {
+                if size as i64 > *max_field_size {
+                    return Err(Error::msg(format!(
+                        "Field '{field}' is {size} bytes, which exceeds the limit of {max_field_size} bytes"
+                    )));
+                }
+            }
+            record_size += size;
+        }
+        if let Some(max_record_size) = collection_data.max_record_size() {
+            if record_size as i64 > *max_record_size {
+                return Err(Error::msg(format!(
+                    "Record is {record_size} bytes, which exceeds the limit of {max_record_size} bytes"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn compute_fields(&mut self, collection_data: &CollectionDao) -> Result<()> {
+        for (field, field_props) in collection_data.schema_fields() {
+            if let Some(computed) = field_props.computed() {
+                let value = match computed.compute(field_props.kind(), &self.data) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        return Err(Error::msg(format!(
+                            "Failed to compute field '{field}': {err}"
+                        )))
+                    }
+                };
+                if value.is_null() && *field_props.required() {
+                    return Err(Error::msg(format!("Value for '{field}' is required")));
+                }
+                self.data.insert(field.to_owned(), value);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_validation(
+        &self,
+        collection_data: &CollectionDao,
+    ) -> Vec<(String, FieldValidationError)> {
+        let mut errors = Vec::new();
+        for (field, value) in &self.data {
+            if let Some(field_props) = collection_data.schema_fields().get(field) {
+                if let Err(err) = field_props.validation().validate(value) {
+                    errors.push((field.to_owned(), err));
+                }
+            }
+        }
+        errors.sort_by(|a, b| a.0.cmp(&b.0));
+        errors
+    }
+
     pub async fn db_create_table(db: &Db, collection: &CollectionDao) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => {
@@ -218,6 +321,17 @@ impl RecordDao {
         }
     }
 
+    // Sizes come from each database's table statistics, so they are estimates that may lag behind
+    // the latest writes
+    pub async fn db_table_size(db: &Db, collection_id: &Uuid) -> Result<i64> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_table_size(db, collection_id).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_table_size(db, collection_id).await,
+            Db::MysqlDb(db) => Self::mysqldb_table_size(db, collection_id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_table_size(db, collection_id).await,
+        }
+    }
+
     pub async fn db_add_columns(
         db: &Db,
         collection_id: &Uuid,
@@ -339,6 +453,205 @@ impl RecordDao {
         }
     }
 
+    pub async fn db_check_column_transform(
+        db: &Db,
+        collection_id: &Uuid,
+        column: &str,
+        from: &SchemaFieldProps,
+        to: &SchemaFieldProps,
+    ) -> Result<Vec<(Uuid, String)>> {
+        let mut failures = Vec::new();
+        for (id, value) in Self::db_select_column(db, collection_id, column, from.kind()).await? {
+            if let Err(err) = value.transform(to.kind()) {
+                failures.push((id, err.to_string()));
+            }
+        }
+        Ok(failures)
+    }
+
+    pub async fn db_transform_column(
+        db: &Db,
+        collection_id: &Uuid,
+        column: &str,
+        from: &SchemaFieldProps,
+        to: &SchemaFieldProps,
+    ) -> Result<()> {
+        if let Db::ScyllaDb(_) = db {
+            return Err(Error::msg(
+                "Transforming the kind of a field is not supported in ScyllaDB",
+            ));
+        }
+
+        let values = Self::db_select_column(db, collection_id, column, from.kind()).await?;
+        let mut transformed_values = Vec::with_capacity(values.len());
+        for (id, value) in &values {
+            match value.transform(to.kind()) {
+                Ok(value) => transformed_values.push((id, value)),
+                Err(err) => {
+                    return Err(Error::msg(format!(
+                        "Failed to transform field '{column}' of record '{id}': {err}"
+                    )))
+                }
+            }
+        }
+
+        let column_tmp = format!("_tmp_{column}");
+        let mut columns_add = HashMap::with_capacity(1);
+        columns_add.insert(column_tmp.to_owned(), to.clone());
+        Self::db_add_columns(db, collection_id, &columns_add).await?;
+
+        for (id, value) in &transformed_values {
+            let mut record_data = Self::new(collection_id, &Some(2));
+            record_data.upsert("_id", &ColumnValue::Uuid(Some(**id)));
+            record_data.upsert(&column_tmp, value);
+            record_data.db_update(db, &HashMap::new(), &None).await?;
+        }
+
+        let mut columns_drop = HashSet::with_capacity(1);
+        columns_drop.insert(column.to_owned());
+        Self::db_drop_columns(db, collection_id, &columns_drop).await?;
+
+        Self::db_rename_column(db, collection_id, &column_tmp, column, to).await
+    }
+
+    async fn db_select_column(
+        db: &Db,
+        collection_id: &Uuid,
+        column: &str,
+        kind: &ColumnKind,
+    ) -> Result<Vec<(Uuid, ColumnValue)>> {
+        let table_name = Self::new_table_name(collection_id);
+        let columns = vec!["_id", column];
+        let filters = RecordFilters::new(&Vec::new());
+        let pagination = RecordPagination::new(&None);
+
+        match db {
+            Db::ScyllaDb(db) => {
+                let rows = Self::scylladb_select_many(
+                    db,
+                    &table_name,
+                    &columns,
+                    &filters,
+                    &Vec::new(),
+                    &Vec::new(),
+                    &pagination,
+                )
+                .await?;
+                let mut values = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    let id = match row.first() {
+                        Some(Some(id)) => {
+                            ColumnValue::from_scylladb_model(&ColumnKind::Uuid, id)?.to_uuid()?
+                        }
+                        _ => return Err(Error::msg("Id is undefined")),
+                    };
+                    let value = match row.get(1) {
+                        Some(Some(value)) => ColumnValue::from_scylladb_model(kind, value)?,
+                        _ => ColumnValue::none(kind),
+                    };
+                    values.push((id, value));
+                }
+                Ok(values)
+            }
+            Db::PostgresqlDb(db) => {
+                let rows = Self::postgresdb_select_many(
+                    db,
+                    &table_name,
+                    &columns,
+                    &filters,
+                    &Vec::new(),
+                    &Vec::new(),
+                    &pagination,
+                )
+                .await?;
+                let mut values = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    values.push((
+                        ColumnValue::from_postgresdb_model(&ColumnKind::Uuid, "_id", row)?
+                            .to_uuid()?,
+                        ColumnValue::from_postgresdb_model(kind, column, row)?,
+                    ));
+                }
+                Ok(values)
+            }
+            Db::MysqlDb(db) => {
+                let rows = Self::mysqldb_select_many(
+                    db,
+                    &table_name,
+                    &columns,
+                    &filters,
+                    &Vec::new(),
+                    &Vec::new(),
+                    &pagination,
+                )
+                .await?;
+                let mut values = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    values.push((
+                        ColumnValue::from_mysqldb_model(&ColumnKind::Uuid, "_id", row)?
+                            .to_uuid()?,
+                        ColumnValue::from_mysqldb_model(kind, column, row)?,
+                    ));
+                }
+                Ok(values)
+            }
+            Db::SqliteDb(db) => {
+                let rows = Self::sqlitedb_select_many(
+                    db,
+                    &table_name,
+                    &columns,
+                    &filters,
+                    &Vec::new(),
+                    &Vec::new(),
+                    &pagination,
+                )
+                .await?;
+                let mut values = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    values.push((
+                        ColumnValue::from_sqlitedb_model(&ColumnKind::Uuid, "_id", row)?
+                            .to_uuid()?,
+                        ColumnValue::from_sqlitedb_model(kind, column, row)?,
+                    ));
+                }
+                Ok(values)
+            }
+        }
+    }
+
+    pub async fn db_rename_column(
+        db: &Db,
+        collection_id: &Uuid,
+        from: &str,
+        to: &str,
+        column: &SchemaFieldProps,
+    ) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => {
+                Self::scylladb_rename_column(
+                    db,
+                    collection_id,
+                    from,
+                    to,
+                    &column.to_scylladb_model(),
+                )
+                .await
+            }
+            Db::PostgresqlDb(db) => {
+                Self::postgresdb_rename_column(
+                    db,
+                    collection_id,
+                    from,
+                    to,
+                    &column.to_postgresdb_model(),
+                )
+                .await
+            }
+            Db::MysqlDb(db) => Self::mysqldb_rename_column(db, collection_id, from, to).await,
+            Db::SqliteDb(db) => Self::sqlitedb_rename_column(db, collection_id, from, to).await,
+        }
+    }
+
     pub async fn db_create_index(db: &Db, collection_id: &Uuid, index: &str) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => Self::scylladb_create_index(db, collection_id, index).await,
@@ -357,6 +670,76 @@ impl RecordDao {
         }
     }
 
+    pub async fn db_create_partial_index(
+        db: &Db,
+        collection_data: &CollectionDao,
+        index: &str,
+        partial_index: &PartialIndex,
+    ) -> Result<()> {
+        match db {
+            Db::ScyllaDb(_) => Err(Error::msg(
+                "Partial indexes are not supported in the data type implementation of Hyperbase for ScyllaDB",
+            )),
+            Db::PostgresqlDb(db) => {
+                Self::postgresdb_create_partial_index(
+                    db,
+                    collection_data.id(),
+                    index,
+                    &partial_index.to_postgresdb_model(),
+                )
+                .await
+            }
+            Db::MysqlDb(db) => {
+                Self::mysqldb_create_partial_index(
+                    db,
+                    collection_data.id(),
+                    index,
+                    &partial_index.to_mysqldb_model(),
+                    &collection_data
+                        .schema_fields()
+                        .iter()
+                        .map(|(col, col_props)| (col.to_owned(), col_props.to_mysqldb_model()))
+                        .collect(),
+                )
+                .await
+            }
+            Db::SqliteDb(db) => {
+                Self::sqlitedb_create_partial_index(
+                    db,
+                    collection_data.id(),
+                    index,
+                    &partial_index.to_sqlitedb_model(),
+                )
+                .await
+            }
+        }
+    }
+
+    pub async fn db_drop_partial_index(
+        db: &Db,
+        collection_id: &Uuid,
+        index: &str,
+        partial_index: &PartialIndex,
+    ) -> Result<()> {
+        match db {
+            Db::ScyllaDb(_) => Err(Error::msg(
+                "Partial indexes are not supported in the data type implementation of Hyperbase for ScyllaDB",
+            )),
+            Db::PostgresqlDb(db) => Self::postgresdb_drop_index(db, collection_id, index).await,
+            Db::MysqlDb(db) => {
+                Self::mysqldb_drop_partial_index(
+                    db,
+                    collection_id,
+                    index,
+                    &partial_index.to_mysqldb_model(),
+                )
+                .await
+            }
+            Db::SqliteDb(db) => Self::sqlitedb_drop_index(db, collection_id, index).await,
+        }
+    }
+
+    #[tracing::instrument(level = "debug", name = "record.insert", skip_all, fields(table = %self.table_name))]
     pub async fn db_insert(&self, db: &Db) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
@@ -366,21 +749,81 @@ impl RecordDao {
         }
     }
 
+    #[tracing::instrument(level = "debug", name = "record.insert_many", skip_all, fields(records = records.len()))]
+    pub async fn db_insert_many(db: &Db, records: &[Self]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        for record in &records[1..] {
+            if record.table_name != records[0].table_name
+                || record.data.len() != records[0].data.len()
+                || record.keys().any(|key| !records[0].data.contains_key(key))
+            {
+                return Err(Error::msg(
+                    "All records inserted in one batch must have the same table and columns",
+                ));
+            }
+        }
+        match db {
+            Db::ScyllaDb(db) => {
+                for record in records {
+                    Self::scylladb_insert(record, db).await?;
+                }
+                Ok(())
+            }
+            Db::PostgresqlDb(db) => Self::postgresdb_insert_many(db, records).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert_many(db, records).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert_many(db, records).await,
+        }
+    }
+
+    #[tracing::instrument(level = "debug", name = "record.upsert", skip_all, fields(table = %self.table_name))]
+    pub async fn db_upsert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_upsert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_upsert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_upsert(self, db).await,
+        }
+    }
+
+    #[tracing::instrument(level = "debug", name = "record.select", skip_all, fields(collection_id = %collection_data.id(), id = %id))]
     pub async fn db_select(db: &Db, collection_data: &CollectionDao, id: &Uuid) -> Result<Self> {
         match db {
             Db::ScyllaDb(db) => {
                 let table_name = Self::new_table_name(collection_data.id());
 
-                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 3);
                 let mut columns_props =
-                    Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                    Vec::with_capacity(collection_data.schema_fields().len() + 3);
 
                 columns.push("_id");
-                columns_props.push(SchemaFieldProps::new(&ColumnKind::Uuid, &true));
+                columns_props.push(SchemaFieldProps::new(
+                    &ColumnKind::Uuid,
+                    &true,
+                    &SchemaFieldValidation::default(),
+                    &None,
+                ));
+
+                columns.push("_version");
+                columns_props.push(SchemaFieldProps::new(
+                    &ColumnKind::BigInt,
+                    &false,
+                    &SchemaFieldValidation::default(),
+                    &None,
+                ));
+
+                columns.push("_created_by");
+                columns_props.push(SchemaFieldProps::new(
+                    &ColumnKind::Uuid,
+                    &false,
+                    &SchemaFieldValidation::default(),
+                    &None,
+                ));
 
                 for (column, props) in collection_data.schema_fields() {
                     columns.push(column);
-                    columns_props.push(*props)
+                    columns_props.push(props.clone())
                 }
 
                 let scylladb_data = Self::scylladb_select(db, &table_name, &columns, id).await?;
@@ -402,13 +845,19 @@ impl RecordDao {
                     };
                 }
 
+                if let Some(ColumnValue::BigInteger(version @ None)) = data.get_mut("_version") {
+                    *version = Some(0);
+                }
+
                 Ok(Self { table_name, data })
             }
             Db::PostgresqlDb(db) => {
                 let table_name = Self::new_table_name(collection_data.id());
 
-                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 3);
                 columns.push("_id");
+                columns.push("_version");
+                columns.push("_created_by");
                 for column in collection_data.schema_fields().keys() {
                     columns.push(column);
                 }
@@ -421,6 +870,22 @@ impl RecordDao {
                     "_id".to_owned(),
                     ColumnValue::from_postgresdb_model(&ColumnKind::Uuid, "_id", &postgresdb_data)?,
                 );
+                data.insert(
+                    "_version".to_owned(),
+                    ColumnValue::from_postgresdb_model(
+                        &ColumnKind::BigInt,
+                        "_version",
+                        &postgresdb_data,
+                    )?,
+                );
+                data.insert(
+                    "_created_by".to_owned(),
+                    ColumnValue::from_postgresdb_model(
+                        &ColumnKind::Uuid,
+                        "_created_by",
+                        &postgresdb_data,
+                    )?,
+                );
                 for (field, field_props) in collection_data.schema_fields() {
                     data.insert(
                         field.to_owned(),
@@ -432,13 +897,19 @@ impl RecordDao {
                     );
                 }
 
+                if let Some(ColumnValue::BigInteger(version @ None)) = data.get_mut("_version") {
+                    *version = Some(0);
+                }
+
                 Ok(Self { table_name, data })
             }
             Db::MysqlDb(db) => {
                 let table_name = Self::new_table_name(collection_data.id());
 
-                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 3);
                 columns.push("_id");
+                columns.push("_version");
+                columns.push("_created_by");
                 for column in collection_data.schema_fields().keys() {
                     columns.push(column);
                 }
@@ -450,6 +921,22 @@ impl RecordDao {
                     "_id".to_owned(),
                     ColumnValue::from_mysqldb_model(&ColumnKind::Uuid, "_id", &mysqldb_data)?,
                 );
+                data.insert(
+                    "_version".to_owned(),
+                    ColumnValue::from_mysqldb_model(
+                        &ColumnKind::BigInt,
+                        "_version",
+                        &mysqldb_data,
+                    )?,
+                );
+                data.insert(
+                    "_created_by".to_owned(),
+                    ColumnValue::from_mysqldb_model(
+                        &ColumnKind::Uuid,
+                        "_created_by",
+                        &mysqldb_data,
+                    )?,
+                );
                 for (field, field_props) in collection_data.schema_fields() {
                     data.insert(
                         field.to_owned(),
@@ -457,13 +944,19 @@ impl RecordDao {
                     );
                 }
 
+                if let Some(ColumnValue::BigInteger(version @ None)) = data.get_mut("_version") {
+                    *version = Some(0);
+                }
+
                 Ok(Self { table_name, data })
             }
             Db::SqliteDb(db) => {
                 let table_name = Self::new_table_name(collection_data.id());
 
-                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 3);
                 columns.push("_id");
+                columns.push("_version");
+                columns.push("_created_by");
                 for column in collection_data.schema_fields().keys() {
                     columns.push(column);
                 }
@@ -475,6 +968,22 @@ impl RecordDao {
                     "_id".to_owned(),
                     ColumnValue::from_sqlitedb_model(&ColumnKind::Uuid, "_id", &sqlitedb_data)?,
                 );
+                data.insert(
+                    "_version".to_owned(),
+                    ColumnValue::from_sqlitedb_model(
+                        &ColumnKind::BigInt,
+                        "_version",
+                        &sqlitedb_data,
+                    )?,
+                );
+                data.insert(
+                    "_created_by".to_owned(),
+                    ColumnValue::from_sqlitedb_model(
+                        &ColumnKind::Uuid,
+                        "_created_by",
+                        &sqlitedb_data,
+                    )?,
+                );
                 for (field, field_props) in collection_data.schema_fields() {
                     data.insert(
                         field.to_owned(),
@@ -486,11 +995,16 @@ impl RecordDao {
                     );
                 }
 
+                if let Some(ColumnValue::BigInteger(version @ None)) = data.get_mut("_version") {
+                    *version = Some(0);
+                }
+
                 Ok(Self { table_name, data })
             }
         }
     }
 
+    #[tracing::instrument(level = "debug", name = "record.select_many", skip_all, fields(collection_id = %collection_data.id()))]
     pub async fn db_select_many(
         db: &Db,
         collection_data: &CollectionDao,
@@ -498,33 +1012,57 @@ impl RecordDao {
         groups: &Vec<&str>,
         orders: &Vec<RecordOrder>,
         pagination: &RecordPagination,
-    ) -> Result<(Vec<Self>, i64)> {
+        with_total: &bool,
+    ) -> Result<(Vec<Self>, Option<i64>)> {
         match db {
             Db::ScyllaDb(db) => {
                 let table_name = Self::new_table_name(collection_data.id());
 
-                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 2);
                 let mut columns_props =
-                    Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                    Vec::with_capacity(collection_data.schema_fields().len() + 2);
 
                 columns.push("_id");
-                columns_props.push(SchemaFieldProps::new(&ColumnKind::Uuid, &true));
+                columns_props.push(SchemaFieldProps::new(
+                    &ColumnKind::Uuid,
+                    &true,
+                    &SchemaFieldValidation::default(),
+                    &None,
+                ));
+
+                columns.push("_created_by");
+                columns_props.push(SchemaFieldProps::new(
+                    &ColumnKind::Uuid,
+                    &false,
+                    &SchemaFieldValidation::default(),
+                    &None,
+                ));
 
                 for (column, props) in collection_data.schema_fields() {
                     columns.push(column);
-                    columns_props.push(*props)
+                    columns_props.push(props.clone())
                 }
 
-                let (scylladb_data_many, total) = Self::scylladb_select_many(
-                    db,
-                    &table_name,
-                    &columns,
-                    filters,
-                    groups,
-                    orders,
-                    pagination,
-                )
-                .await?;
+                let (scylladb_data_many, total) = tokio::try_join!(
+                    Self::scylladb_select_many(
+                        db,
+                        &table_name,
+                        &columns,
+                        filters,
+                        groups,
+                        orders,
+                        pagination,
+                    ),
+                    async {
+                        if *with_total {
+                            Self::scylladb_count(db, &table_name, filters)
+                                .await
+                                .map(Some)
+                        } else {
+                            Ok(None)
+                        }
+                    }
+                )?;
 
                 let mut data_many = Vec::with_capacity(scylladb_data_many.len());
                 for scylladb_data in scylladb_data_many {
@@ -555,22 +1093,33 @@ impl RecordDao {
             Db::PostgresqlDb(db) => {
                 let table_name = Self::new_table_name(collection_data.id());
 
-                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 2);
                 columns.push("_id");
+                columns.push("_created_by");
                 for column in collection_data.schema_fields().keys() {
                     columns.push(column);
                 }
 
-                let (postgres_data_many, total) = Self::postgresdb_select_many(
-                    db,
-                    &table_name,
-                    &columns,
-                    filters,
-                    groups,
-                    orders,
-                    pagination,
-                )
-                .await?;
+                let (postgres_data_many, total) = tokio::try_join!(
+                    Self::postgresdb_select_many(
+                        db,
+                        &table_name,
+                        &columns,
+                        filters,
+                        groups,
+                        orders,
+                        pagination,
+                    ),
+                    async {
+                        if *with_total {
+                            Self::postgresdb_count(db, &table_name, filters)
+                                .await
+                                .map(Some)
+                        } else {
+                            Ok(None)
+                        }
+                    }
+                )?;
 
                 let mut data_many = Vec::with_capacity(postgres_data_many.len());
                 for postgres_data in &postgres_data_many {
@@ -584,6 +1133,14 @@ impl RecordDao {
                             postgres_data,
                         )?,
                     );
+                    data.insert(
+                        "_created_by".to_owned(),
+                        ColumnValue::from_postgresdb_model(
+                            &ColumnKind::Uuid,
+                            "_created_by",
+                            postgres_data,
+                        )?,
+                    );
                     for (field, field_props) in collection_data.schema_fields() {
                         data.insert(
                             field.to_owned(),
@@ -605,22 +1162,33 @@ impl RecordDao {
             Db::MysqlDb(db) => {
                 let table_name = Self::new_table_name(collection_data.id());
 
-                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 2);
                 columns.push("_id");
+                columns.push("_created_by");
                 for column in collection_data.schema_fields().keys() {
                     columns.push(column);
                 }
 
-                let (mysql_data_many, total) = Self::mysqldb_select_many(
-                    db,
-                    &table_name,
-                    &columns,
-                    filters,
-                    groups,
-                    orders,
-                    pagination,
-                )
-                .await?;
+                let (mysql_data_many, total) = tokio::try_join!(
+                    Self::mysqldb_select_many(
+                        db,
+                        &table_name,
+                        &columns,
+                        filters,
+                        groups,
+                        orders,
+                        pagination,
+                    ),
+                    async {
+                        if *with_total {
+                            Self::mysqldb_count(db, &table_name, filters)
+                                .await
+                                .map(Some)
+                        } else {
+                            Ok(None)
+                        }
+                    }
+                )?;
 
                 let mut data_many = Vec::with_capacity(mysql_data_many.len());
                 for mysql_data in &mysql_data_many {
@@ -630,6 +1198,14 @@ impl RecordDao {
                         "_id".to_owned(),
                         ColumnValue::from_mysqldb_model(&ColumnKind::Uuid, "_id", mysql_data)?,
                     );
+                    data.insert(
+                        "_created_by".to_owned(),
+                        ColumnValue::from_mysqldb_model(
+                            &ColumnKind::Uuid,
+                            "_created_by",
+                            mysql_data,
+                        )?,
+                    );
                     for (field, field_props) in collection_data.schema_fields() {
                         data.insert(
                             field.to_owned(),
@@ -647,22 +1223,33 @@ impl RecordDao {
             Db::SqliteDb(db) => {
                 let table_name = Self::new_table_name(collection_data.id());
 
-                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 1);
+                let mut columns = Vec::with_capacity(collection_data.schema_fields().len() + 2);
                 columns.push("_id");
+                columns.push("_created_by");
                 for column in collection_data.schema_fields().keys() {
                     columns.push(column);
                 }
 
-                let (sqlite_data_many, total) = Self::sqlitedb_select_many(
-                    db,
-                    &table_name,
-                    &columns,
-                    filters,
-                    groups,
-                    orders,
-                    pagination,
-                )
-                .await?;
+                let (sqlite_data_many, total) = tokio::try_join!(
+                    Self::sqlitedb_select_many(
+                        db,
+                        &table_name,
+                        &columns,
+                        filters,
+                        groups,
+                        orders,
+                        pagination,
+                    ),
+                    async {
+                        if *with_total {
+                            Self::sqlitedb_count(db, &table_name, filters)
+                                .await
+                                .map(Some)
+                        } else {
+                            Ok(None)
+                        }
+                    }
+                )?;
 
                 let mut data_many = Vec::with_capacity(sqlite_data_many.len());
                 for sqlite_data in &sqlite_data_many {
@@ -672,6 +1259,14 @@ impl RecordDao {
                         "_id".to_owned(),
                         ColumnValue::from_sqlitedb_model(&ColumnKind::Uuid, "_id", sqlite_data)?,
                     );
+                    data.insert(
+                        "_created_by".to_owned(),
+                        ColumnValue::from_sqlitedb_model(
+                            &ColumnKind::Uuid,
+                            "_created_by",
+                            sqlite_data,
+                        )?,
+                    );
                     for (field, field_props) in collection_data.schema_fields() {
                         data.insert(
                             field.to_owned(),
@@ -693,23 +1288,276 @@ impl RecordDao {
         }
     }
 
-    pub async fn db_update(&self, db: &Db) -> Result<()> {
+    #[tracing::instrument(level = "debug", name = "record.count", skip_all, fields(collection_id = %collection_id))]
+    pub async fn db_count(db: &Db, collection_id: &Uuid, filters: &RecordFilters) -> Result<i64> {
+        let table_name = Self::new_table_name(collection_id);
         match db {
-            Db::ScyllaDb(db) => Self::scylladb_update(self, db).await,
-            Db::PostgresqlDb(db) => Self::postgresdb_update(self, db).await,
-            Db::MysqlDb(db) => Self::mysqldb_update(self, db).await,
-            Db::SqliteDb(db) => Self::sqlitedb_update(self, db).await,
+            Db::ScyllaDb(db) => Self::scylladb_count(db, &table_name, filters).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_count(db, &table_name, filters).await,
+            Db::MysqlDb(db) => Self::mysqldb_count(db, &table_name, filters).await,
+            Db::SqliteDb(db) => Self::sqlitedb_count(db, &table_name, filters).await,
         }
     }
 
-    pub async fn db_delete(db: &Db, collection_id: &Uuid, id: &Uuid) -> Result<()> {
-        match db {
-            Db::ScyllaDb(db) => Self::scylladb_delete(db, collection_id, id).await,
-            Db::PostgresqlDb(db) => Self::postgresdb_delete(db, collection_id, id).await,
-            Db::MysqlDb(db) => Self::mysqldb_delete(db, collection_id, id).await,
-            Db::SqliteDb(db) => Self::sqlitedb_delete(db, collection_id, id).await,
-        }
-    }
+    #[tracing::instrument(level = "debug", name = "record.count_groups", skip_all, fields(collection_id = %collection_data.id()))]
+    pub async fn db_count_groups(
+        db: &Db,
+        collection_data: &CollectionDao,
+        groups: &Vec<&str>,
+        filters: &RecordFilters,
+    ) -> Result<Vec<(HashMap<String, ColumnValue>, i64)>> {
+        let table_name = Self::new_table_name(collection_data.id());
+        let mut kinds = Vec::with_capacity(groups.len());
+        for group in groups {
+            kinds.push(match *group {
+                "_id" => &ColumnKind::Uuid,
+                _ => match collection_data.schema_fields().get(*group) {
+                    Some(props) => props.kind(),
+                    None => {
+                        return Err(Error::msg(format!(
+                            "Field '{group}' is not exist in the collection"
+                        )))
+                    }
+                },
+            });
+        }
+
+        let mut group_counts = Vec::new();
+        match db {
+            Db::ScyllaDb(db) => {
+                for row in Self::scylladb_count_groups(db, &table_name, groups, filters).await? {
+                    let mut values = HashMap::with_capacity(groups.len());
+                    for (idx, group) in groups.iter().enumerate() {
+                        values.insert(
+                            (*group).to_owned(),
+                            match &row[idx] {
+                                Some(value) => ColumnValue::from_scylladb_model(kinds[idx], value)?,
+                                None => ColumnValue::none(kinds[idx]),
+                            },
+                        );
+                    }
+                    let count = match row.last() {
+                        Some(Some(ScyllaCqlValue::BigInt(count))) => *count,
+                        _ => return Err(Error::msg("Failed to read the group count")),
+                    };
+                    group_counts.push((values, count));
+                }
+            }
+            Db::PostgresqlDb(db) => {
+                for row in &Self::postgresdb_count_groups(db, &table_name, groups, filters).await? {
+                    let mut values = HashMap::with_capacity(groups.len());
+                    for (idx, group) in groups.iter().enumerate() {
+                        values.insert(
+                            (*group).to_owned(),
+                            ColumnValue::from_postgresdb_model(kinds[idx], group, row)?,
+                        );
+                    }
+                    group_counts.push((values, sqlx::Row::try_get(row, "_count")?));
+                }
+            }
+            Db::MysqlDb(db) => {
+                for row in &Self::mysqldb_count_groups(db, &table_name, groups, filters).await? {
+                    let mut values = HashMap::with_capacity(groups.len());
+                    for (idx, group) in groups.iter().enumerate() {
+                        values.insert(
+                            (*group).to_owned(),
+                            ColumnValue::from_mysqldb_model(kinds[idx], group, row)?,
+                        );
+                    }
+                    group_counts.push((values, sqlx::Row::try_get(row, "_count")?));
+                }
+            }
+            Db::SqliteDb(db) => {
+                for row in &Self::sqlitedb_count_groups(db, &table_name, groups, filters).await? {
+                    let mut values = HashMap::with_capacity(groups.len());
+                    for (idx, group) in groups.iter().enumerate() {
+                        values.insert(
+                            (*group).to_owned(),
+                            ColumnValue::from_sqlitedb_model(kinds[idx], group, row)?,
+                        );
+                    }
+                    group_counts.push((values, sqlx::Row::try_get(row, "_count")?));
+                }
+            }
+        }
+        Ok(group_counts)
+    }
+
+    #[tracing::instrument(level = "debug", name = "record.select_distinct", skip_all, fields(collection_id = %collection_data.id(), field = field))]
+    pub async fn db_select_distinct(
+        db: &Db,
+        collection_data: &CollectionDao,
+        field: &str,
+        filters: &RecordFilters,
+        pagination: &RecordPagination,
+    ) -> Result<Vec<ColumnValue>> {
+        let table_name = Self::new_table_name(collection_data.id());
+        let kind = match field {
+            "_id" => &ColumnKind::Uuid,
+            _ => match collection_data.schema_fields().get(field) {
+                Some(props) => props.kind(),
+                None => {
+                    return Err(Error::msg(format!(
+                        "Field '{field}' is not exist in the collection"
+                    )))
+                }
+            },
+        };
+
+        match db {
+            Db::ScyllaDb(_) => Err(Error::msg(
+                "Selecting distinct values is not supported in the data type implementation of Hyperbase for ScyllaDB",
+            )),
+            Db::PostgresqlDb(db) => {
+                let rows =
+                    Self::postgresdb_select_distinct(db, &table_name, field, filters, pagination)
+                        .await?;
+                let mut values = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    values.push(ColumnValue::from_postgresdb_model(kind, field, row)?);
+                }
+                Ok(values)
+            }
+            Db::MysqlDb(db) => {
+                let rows =
+                    Self::mysqldb_select_distinct(db, &table_name, field, filters, pagination)
+                        .await?;
+                let mut values = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    values.push(ColumnValue::from_mysqldb_model(kind, field, row)?);
+                }
+                Ok(values)
+            }
+            Db::SqliteDb(db) => {
+                let rows =
+                    Self::sqlitedb_select_distinct(db, &table_name, field, filters, pagination)
+                        .await?;
+                let mut values = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    values.push(ColumnValue::from_sqlitedb_model(kind, field, row)?);
+                }
+                Ok(values)
+            }
+        }
+    }
+
+    #[tracing::instrument(level = "debug", name = "record.update", skip_all, fields(table = %self.table_name))]
+    pub async fn db_update(
+        &self,
+        db: &Db,
+        increments: &HashMap<String, ColumnValue>,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        match db {
+            Db::ScyllaDb(_) if !increments.is_empty() => Err(Error::msg(
+                "Atomic increments are not supported in the data type implementation of Hyperbase for ScyllaDB",
+            )),
+            Db::ScyllaDb(db) => Self::scylladb_update(self, db, version).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_update(self, db, increments, version).await,
+            Db::MysqlDb(db) => Self::mysqldb_update(self, db, increments, version).await,
+            Db::SqliteDb(db) => Self::sqlitedb_update(self, db, increments, version).await,
+        }
+    }
+
+    #[tracing::instrument(level = "debug", name = "record.delete", skip_all, fields(collection_id = %collection_id, id = %id))]
+    pub async fn db_delete(
+        db: &Db,
+        collection_id: &Uuid,
+        id: &Uuid,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_delete(db, collection_id, id, version).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_delete(db, collection_id, id, version).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete(db, collection_id, id, version).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete(db, collection_id, id, version).await,
+        }
+    }
+
+    pub async fn db_migrate_version_columns(db: &Db) -> Result<()> {
+        for collection in CollectionDao::db_select_many(db).await? {
+            let table_name = Self::new_table_name(collection.id());
+            let has_version_column = match db {
+                Db::ScyllaDb(db) => Self::scylladb_check_version_column(db, &table_name).await?,
+                Db::PostgresqlDb(db) => {
+                    Self::postgresdb_check_version_column(db, &table_name).await?
+                }
+                Db::MysqlDb(db) => Self::mysqldb_check_version_column(db, &table_name).await?,
+                Db::SqliteDb(db) => Self::sqlitedb_check_version_column(db, &table_name).await?,
+            };
+            if has_version_column {
+                continue;
+            }
+            match db {
+                Db::ScyllaDb(db) => Self::scylladb_add_version_column(db, &table_name).await?,
+                Db::PostgresqlDb(db) => {
+                    Self::postgresdb_add_version_column(db, &table_name).await?
+                }
+                Db::MysqlDb(db) => Self::mysqldb_add_version_column(db, &table_name).await?,
+                Db::SqliteDb(db) => Self::sqlitedb_add_version_column(db, &table_name).await?,
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn db_migrate_created_by_columns(db: &Db) -> Result<()> {
+        for collection in CollectionDao::db_select_many(db).await? {
+            let table_name = Self::new_table_name(collection.id());
+            let has_created_by_column = match db {
+                Db::ScyllaDb(db) => Self::scylladb_check_created_by_column(db, &table_name).await?,
+                Db::PostgresqlDb(db) => {
+                    Self::postgresdb_check_created_by_column(db, &table_name).await?
+                }
+                Db::MysqlDb(db) => Self::mysqldb_check_created_by_column(db, &table_name).await?,
+                Db::SqliteDb(db) => Self::sqlitedb_check_created_by_column(db, &table_name).await?,
+            };
+            if has_created_by_column {
+                continue;
+            }
+            match db {
+                Db::ScyllaDb(db) => Self::scylladb_add_created_by_column(db, &table_name).await?,
+                Db::PostgresqlDb(db) => {
+                    Self::postgresdb_add_created_by_column(db, &table_name).await?
+                }
+                Db::MysqlDb(db) => Self::mysqldb_add_created_by_column(db, &table_name).await?,
+                Db::SqliteDb(db) => Self::sqlitedb_add_created_by_column(db, &table_name).await?,
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn db_set_default_ttl(
+        db: &Db,
+        collection_id: &Uuid,
+        auto_delete_after_seconds: &Option<i64>,
+    ) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => {
+                Self::scylladb_set_default_ttl(db, collection_id, auto_delete_after_seconds).await
+            }
+            Db::PostgresqlDb(_) | Db::MysqlDb(_) | Db::SqliteDb(_) => Ok(()),
+        }
+    }
+
+    pub async fn db_delete_expired(
+        db: &Db,
+        collection_id: &Uuid,
+        auto_delete_after_seconds: &i64,
+    ) -> Result<u64> {
+        let cutoff = Utc::now().timestamp_millis() - auto_delete_after_seconds * 1000;
+        if cutoff <= 0 {
+            return Ok(0);
+        }
+        let cutoff_id = Uuid::from_u128((cutoff as u128) << 80);
+        match db {
+            Db::ScyllaDb(_) => Ok(0),
+            Db::PostgresqlDb(db) => {
+                Self::postgresdb_delete_expired(db, collection_id, &cutoff_id).await
+            }
+            Db::MysqlDb(db) => Self::mysqldb_delete_expired(db, collection_id, &cutoff_id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete_expired(db, collection_id, &cutoff_id).await,
+        }
+    }
 
     async fn scylladb_create_table(
         db: &ScyllaDb,
@@ -745,6 +1593,22 @@ impl RecordDao {
             > 0)
     }
 
+    async fn scylladb_table_size(db: &ScyllaDb, collection_id: &Uuid) -> Result<i64> {
+        let mut size = 0;
+        for row in db
+            .session_query(
+                SCYLLA_SELECT_SIZE_ESTIMATES,
+                [&RecordDao::new_table_name(collection_id)].as_ref(),
+            )
+            .await?
+            .rows_typed::<(i64, i64)>()?
+        {
+            let (mean_partition_size, partitions_count) = row?;
+            size += mean_partition_size * partitions_count;
+        }
+        Ok(size)
+    }
+
     async fn scylladb_add_columns(
         db: &ScyllaDb,
         collection_id: &Uuid,
@@ -784,6 +1648,42 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn scylladb_rename_column(
+        db: &ScyllaDb,
+        collection_id: &Uuid,
+        from: &str,
+        to: &str,
+        column: &SchemaFieldPropsScyllaModel,
+    ) -> Result<()> {
+        let record_table = Self::new_table_name(collection_id);
+
+        let mut columns_add = HashMap::with_capacity(1);
+        columns_add.insert(to.to_owned(), column.clone());
+        db.session_query(
+            &scylla_record::add_columns(&record_table, &columns_add),
+            &[],
+        )
+        .await?;
+
+        let query_update = scylla_record::update(&record_table, &vec![to]);
+        let mut rows = db
+            .execute_iter(&scylla_record::select_column(&record_table, from), &[])
+            .await?;
+        while let Some(row) = rows.next().await {
+            let (id, value) = row?.into_typed::<(Uuid, Option<ScyllaCqlValue>)>()?;
+            db.execute(&query_update, (value, id)).await?;
+        }
+
+        let mut columns_drop = HashSet::with_capacity(1);
+        columns_drop.insert(from.to_owned());
+        db.session_query(
+            &scylla_record::drop_columns(&record_table, &columns_drop),
+            &[],
+        )
+        .await?;
+        Ok(())
+    }
+
     async fn scylladb_create_index(db: &ScyllaDb, collection_id: &Uuid, index: &str) -> Result<()> {
         db.session_query(
             &scylla_record::create_index(&Self::new_table_name(collection_id), index),
@@ -835,9 +1735,46 @@ impl RecordDao {
         groups: &Vec<&str>,
         orders: &Vec<RecordOrder>,
         pagination: &RecordPagination,
-    ) -> Result<(Vec<Vec<Option<ScyllaCqlValue>>>, i64)> {
+    ) -> Result<Vec<Vec<Option<ScyllaCqlValue>>>> {
         let filter = filters.scylladb_filter_query(&None, 0)?;
 
+        if let (true, Some(limit)) = (pagination.random(), pagination.limit()) {
+            let token = Uuid::new_v4().as_u64_pair().0 as i64;
+
+            let mut values = filters.scylladb_values()?;
+            values.push(Box::new(token));
+            values.push(Box::new(limit));
+            let mut rows: Vec<_> = db
+                .execute(
+                    &scylla_record::select_many_from_token(table_name, columns, &filter, &false),
+                    &values,
+                )
+                .await?
+                .rows()?
+                .iter()
+                .map(|row| row.columns.to_owned())
+                .collect();
+
+            let remaining = *limit - rows.len() as i32;
+            if remaining > 0 {
+                let mut values = filters.scylladb_values()?;
+                values.push(Box::new(token));
+                values.push(Box::new(remaining));
+                rows.extend(
+                    db.execute(
+                        &scylla_record::select_many_from_token(table_name, columns, &filter, &true),
+                        &values,
+                    )
+                    .await?
+                    .rows()?
+                    .iter()
+                    .map(|row| row.columns.to_owned()),
+                );
+            }
+
+            return Ok(rows);
+        }
+
         let mut order = Vec::with_capacity(orders.len());
         for o in orders {
             if SCYLLA_ORDER_TYPE.contains(&o.kind.to_uppercase().as_str()) {
@@ -854,7 +1791,6 @@ impl RecordDao {
         if let Some(limit) = pagination.limit() {
             values.push(Box::new(limit))
         }
-        let total_values = filters.scylladb_values()?;
 
         let query_select_many = scylla_record::select_many(
             table_name,
@@ -864,46 +1800,187 @@ impl RecordDao {
             &order,
             &pagination.limit().is_some(),
         );
-        let query_total = scylla_record::count(table_name, &filter);
 
-        let (data, total) = tokio::try_join!(
-            db.execute(&query_select_many, &values),
-            db.execute(&query_total, &total_values)
-        )?;
+        Ok(db
+            .execute(&query_select_many, &values)
+            .await?
+            .rows()?
+            .iter()
+            .map(|row| row.columns.to_owned())
+            .collect())
+    }
 
-        Ok((
-            data.rows()?
-                .iter()
-                .map(|row| row.columns.to_owned())
-                .collect(),
-            total.first_row_typed::<(i64,)>()?.0,
-        ))
+    async fn scylladb_count(
+        db: &ScyllaDb,
+        table_name: &str,
+        filters: &RecordFilters,
+    ) -> Result<i64> {
+        let filter = filters.scylladb_filter_query(&None, 0)?;
+        let values = filters.scylladb_values()?;
+
+        Ok(db
+            .execute(&scylla_record::count(table_name, &filter), &values)
+            .await?
+            .first_row_typed::<(i64,)>()?
+            .0)
     }
 
-    async fn scylladb_update(&self, db: &ScyllaDb) -> Result<()> {
+    async fn scylladb_count_groups(
+        db: &ScyllaDb,
+        table_name: &str,
+        groups: &Vec<&str>,
+        filters: &RecordFilters,
+    ) -> Result<Vec<Vec<Option<ScyllaCqlValue>>>> {
+        let filter = filters.scylladb_filter_query(&None, 0)?;
+        let values = filters.scylladb_values()?;
+
+        Ok(db
+            .execute(
+                &scylla_record::count_groups(table_name, groups, &filter),
+                &values,
+            )
+            .await?
+            .rows()?
+            .iter()
+            .map(|row| row.columns.to_owned())
+            .collect())
+    }
+
+    async fn scylladb_update(&self, db: &ScyllaDb, version: &Option<i64>) -> Result<bool> {
         let mut columns = Vec::with_capacity(self.data.len());
-        let mut values = Vec::with_capacity(self.data.len());
-        for (col, val) in &self.data {
-            if col != "_id" {
+        for col in self.data.keys() {
+            if col != "_id" && col != "_version" {
                 columns.push(col.as_str());
-                values.push(val.to_scylladb_model()?);
             }
         }
-        match self.data.get("_id") {
-            Some(id) => values.push(id.to_scylladb_model()?),
+        columns.push("_version");
+        let id = match self.data.get("_id") {
+            Some(id) => id,
             None => return Err(Error::msg("Id is undefined")),
+        };
+        let query = scylla_record::update_with_version(&self.table_name, &columns);
+
+        // Without If-Match the update still goes through, so a lost race is retried against the
+        // version the concurrent writer left behind.
+        let mut expected_version = match (version, self.data.get("_version")) {
+            (Some(version), _) => *version,
+            (None, Some(ColumnValue::BigInteger(version))) => version.unwrap_or(0),
+            (None, _) => 0,
+        };
+        for _ in 0..SCYLLA_UPDATE_ATTEMPTS {
+            let mut values = Vec::with_capacity(columns.len() + 3);
+            for col in &columns[..columns.len() - 1] {
+                values.push(self.data[*col].to_scylladb_model()?);
+            }
+            values.push(ColumnValue::BigInteger(Some(expected_version + 1)).to_scylladb_model()?);
+            values.push(id.to_scylladb_model()?);
+            Self::scylladb_push_version_condition(&mut values, &expected_version);
+
+            match Self::scylladb_lwt_applied(db.execute(&query, &values).await?)? {
+                None => return Ok(true),
+                Some(current_version) if version.is_none() => expected_version = current_version,
+                Some(_) => return Ok(false),
+            }
+        }
+        Ok(false)
+    }
+
+    async fn scylladb_delete(
+        db: &ScyllaDb,
+        collection_id: &Uuid,
+        id: &Uuid,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        let table_name = Self::new_table_name(collection_id);
+        match version {
+            Some(version) => {
+                let mut values: Vec<Box<dyn SerializeCql>> = vec![Box::new(*id)];
+                Self::scylladb_push_version_condition(&mut values, version);
+                Ok(Self::scylladb_lwt_applied(
+                    db.execute(&scylla_record::delete_with_version(&table_name), &values)
+                        .await?,
+                )?
+                .is_none())
+            }
+            None => {
+                let mut column = HashSet::<String>::with_capacity(1);
+                column.insert("_id".to_owned());
+                db.execute(&scylla_record::delete(&table_name, &column), [id].as_ref())
+                    .await?;
+                Ok(true)
+            }
         }
-        db.execute(&scylla_record::update(&self.table_name, &columns), &values)
+    }
+
+    fn scylladb_push_version_condition(values: &mut Vec<Box<dyn SerializeCql>>, version: &i64) {
+        values.push(Box::new(Some(*version)));
+        values.push(Box::new((*version != 0).then_some(*version)));
+    }
+
+    // None when the lightweight transaction was applied, otherwise the version the record
+    // currently holds.
+    fn scylladb_lwt_applied(result: QueryResult) -> Result<Option<i64>> {
+        let version_idx = result
+            .col_specs
+            .iter()
+            .position(|spec| spec.name == "_version");
+        let row = result.first_row()?;
+        match row.columns.first() {
+            Some(Some(ScyllaCqlValue::Boolean(true))) => Ok(None),
+            Some(Some(ScyllaCqlValue::Boolean(false))) => Ok(Some(
+                version_idx
+                    .and_then(|idx| row.columns.get(idx))
+                    .and_then(|version| version.as_ref())
+                    .and_then(ScyllaCqlValue::as_bigint)
+                    .unwrap_or(0),
+            )),
+            _ => Err(Error::msg(
+                "Lightweight transaction result is missing the [applied] column",
+            )),
+        }
+    }
+
+    async fn scylladb_check_version_column(db: &ScyllaDb, table_name: &str) -> Result<bool> {
+        Ok(db
+            .session_query(SCYLLA_COUNT_COLUMN, [table_name, "_version"].as_ref())
+            .await?
+            .first_row_typed::<(i64,)>()?
+            .0
+            > 0)
+    }
+
+    async fn scylladb_check_created_by_column(db: &ScyllaDb, table_name: &str) -> Result<bool> {
+        Ok(db
+            .session_query(SCYLLA_COUNT_COLUMN, [table_name, "_created_by"].as_ref())
+            .await?
+            .first_row_typed::<(i64,)>()?
+            .0
+            > 0)
+    }
+
+    async fn scylladb_add_version_column(db: &ScyllaDb, table_name: &str) -> Result<()> {
+        db.session_query(&scylla_record::add_version_column(table_name), &[])
+            .await?;
+        Ok(())
+    }
+
+    async fn scylladb_add_created_by_column(db: &ScyllaDb, table_name: &str) -> Result<()> {
+        db.session_query(&scylla_record::add_created_by_column(table_name), &[])
             .await?;
         Ok(())
     }
 
-    async fn scylladb_delete(db: &ScyllaDb, collection_id: &Uuid, id: &Uuid) -> Result<()> {
-        let mut column = HashSet::<String>::with_capacity(1);
-        column.insert("_id".to_owned());
-        db.execute(
-            &scylla_record::delete(&Self::new_table_name(collection_id), &column),
-            [id].as_ref(),
+    async fn scylladb_set_default_ttl(
+        db: &ScyllaDb,
+        collection_id: &Uuid,
+        auto_delete_after_seconds: &Option<i64>,
+    ) -> Result<()> {
+        db.session_query(
+            &scylla_record::set_default_ttl(
+                &Self::new_table_name(collection_id),
+                &auto_delete_after_seconds.unwrap_or(0),
+            ),
+            &[],
         )
         .await?;
         Ok(())
@@ -944,6 +2021,15 @@ impl RecordDao {
             > 0)
     }
 
+    async fn postgresdb_table_size(db: &PostgresDb, collection_id: &Uuid) -> Result<i64> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(POSTGRES_TABLE_SIZE).bind(&RecordDao::new_table_name(collection_id)),
+            )
+            .await?
+            .0)
+    }
+
     async fn postgresdb_add_columns(
         db: &PostgresDb,
         collection_id: &Uuid,
@@ -983,6 +2069,31 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn postgresdb_rename_column(
+        db: &PostgresDb,
+        collection_id: &Uuid,
+        from: &str,
+        to: &str,
+        column: &SchemaFieldPropsPostgresModel,
+    ) -> Result<()> {
+        let record_table = Self::new_table_name(collection_id);
+        db.execute_unprepared(sqlx::query(&postgres_record::rename_column(
+            &record_table,
+            from,
+            to,
+        )))
+        .await?;
+        if column.enum_variants().is_some() {
+            db.execute_unprepared(sqlx::query(&postgres_record::rename_enum_constraint(
+                &record_table,
+                from,
+                to,
+            )))
+            .await?;
+        }
+        Ok(())
+    }
+
     async fn postgresdb_create_index(
         db: &PostgresDb,
         collection_id: &Uuid,
@@ -1009,6 +2120,21 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn postgresdb_create_partial_index(
+        db: &PostgresDb,
+        collection_id: &Uuid,
+        index: &str,
+        partial_index: &PartialIndexPostgresModel,
+    ) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&postgres_record::create_partial_index(
+            &Self::new_table_name(collection_id),
+            index,
+            partial_index,
+        )))
+        .await?;
+        Ok(())
+    }
+
     async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
         let mut columns = Vec::with_capacity(self.data.len());
         let mut values = Vec::with_capacity(self.data.len());
@@ -1025,6 +2151,40 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn postgresdb_insert_many(db: &PostgresDb, records: &[Self]) -> Result<()> {
+        let columns = records[0]
+            .keys()
+            .map(|col| col.as_str())
+            .collect::<Vec<_>>();
+        let query = postgres_record::insert_many(&records[0].table_name, &columns, &records.len());
+        let mut query = sqlx::query(&query);
+        for record in records {
+            for col in &columns {
+                if let Some(val) = record.get(col) {
+                    query = val.to_postgresdb_model(query)?;
+                }
+            }
+        }
+        db.execute(query).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_upsert(&self, db: &PostgresDb) -> Result<()> {
+        let mut columns = Vec::with_capacity(self.data.len());
+        let mut values = Vec::with_capacity(self.data.len());
+        for (col, val) in &self.data {
+            columns.push(col.as_str());
+            values.push(val);
+        }
+        let query = postgres_record::upsert(&self.table_name, &columns);
+        let mut query = sqlx::query(&query);
+        for val in values {
+            query = val.to_postgresdb_model(query)?;
+        }
+        db.execute(query).await?;
+        Ok(())
+    }
+
     async fn postgresdb_select(
         db: &PostgresDb,
         table_name: &str,
@@ -1044,7 +2204,7 @@ impl RecordDao {
         groups: &Vec<&str>,
         orders: &Vec<RecordOrder>,
         pagination: &RecordPagination,
-    ) -> Result<(Vec<sqlx::postgres::PgRow>, i64)> {
+    ) -> Result<Vec<sqlx::postgres::PgRow>> {
         let mut argument_idx = 1;
         let filter = filters.postgresdb_filter_query(&None, 0, &mut argument_idx)?;
 
@@ -1066,63 +2226,201 @@ impl RecordDao {
             &filter,
             groups,
             &order,
+            pagination.random(),
             &pagination.limit().is_some(),
             &argument_idx,
         );
         let mut query_select_many = sqlx::query(&query_select_many);
-        let query_total = postgres_record::count(table_name, &filter);
-        let mut query_total = sqlx::query_as(&query_total);
 
         query_select_many = filters.postgresdb_values(query_select_many)?;
         if let Some(limit) = pagination.limit() {
             query_select_many = query_select_many.bind(limit);
         }
+
+        Ok(db.fetch_all_rows_cancellable(query_select_many).await?)
+    }
+
+    async fn postgresdb_count(
+        db: &PostgresDb,
+        table_name: &str,
+        filters: &RecordFilters,
+    ) -> Result<i64> {
+        let mut argument_idx = 1;
+        let filter = filters.postgresdb_filter_query(&None, 0, &mut argument_idx)?;
+
+        let query_total = postgres_record::count(table_name, &filter);
+        let mut query_total = sqlx::query_as(&query_total);
         query_total = filters.postgresdb_values_as(query_total)?;
 
-        let (rows, total) = tokio::try_join!(
-            db.fetch_all_rows(query_select_many),
-            db.fetch_one::<(i64,)>(query_total)
-        )?;
+        Ok(db.fetch_one::<(i64,)>(query_total).await?.0)
+    }
 
-        Ok((rows, total.0))
+    async fn postgresdb_select_distinct(
+        db: &PostgresDb,
+        table_name: &str,
+        column: &str,
+        filters: &RecordFilters,
+        pagination: &RecordPagination,
+    ) -> Result<Vec<sqlx::postgres::PgRow>> {
+        let mut argument_idx = 1;
+        let filter = filters.postgresdb_filter_query(&None, 0, &mut argument_idx)?;
+
+        let query = postgres_record::select_distinct(
+            table_name,
+            column,
+            &filter,
+            &pagination.limit().is_some(),
+            &argument_idx,
+        );
+        let mut query = sqlx::query(&query);
+        query = filters.postgresdb_values(query)?;
+        if let Some(limit) = pagination.limit() {
+            query = query.bind(limit);
+        }
+
+        Ok(db.fetch_all_rows_cancellable(query).await?)
+    }
+
+    async fn postgresdb_count_groups(
+        db: &PostgresDb,
+        table_name: &str,
+        groups: &Vec<&str>,
+        filters: &RecordFilters,
+    ) -> Result<Vec<sqlx::postgres::PgRow>> {
+        let mut argument_idx = 1;
+        let filter = filters.postgresdb_filter_query(&None, 0, &mut argument_idx)?;
+
+        let query = postgres_record::count_groups(table_name, groups, &filter);
+        let mut query = sqlx::query(&query);
+        query = filters.postgresdb_values(query)?;
+
+        Ok(db.fetch_all_rows_cancellable(query).await?)
     }
 
-    async fn postgresdb_update(&self, db: &PostgresDb) -> Result<()> {
+    async fn postgresdb_update(
+        &self,
+        db: &PostgresDb,
+        increments: &HashMap<String, ColumnValue>,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        let version_increment = ColumnValue::BigInteger(Some(1));
         let mut columns = Vec::with_capacity(self.data.len());
-        let mut values = Vec::with_capacity(self.data.len());
+        let mut values = Vec::with_capacity(self.data.len() + 2);
         for (col, val) in &self.data {
-            if col != "_id" {
+            if col != "_id" && col != "_version" && !increments.contains_key(col) {
                 columns.push(col.as_str());
                 values.push(val);
             }
         }
+        let mut increment_columns = Vec::with_capacity(increments.len() + 1);
+        for (col, val) in increments {
+            increment_columns.push(col.as_str());
+            values.push(val);
+        }
+        increment_columns.push("_version");
+        values.push(&version_increment);
         match self.data.get("_id") {
             Some(id) => values.push(id),
             None => return Err(Error::msg("Id is undefined")),
         }
-        let query = postgres_record::update(&self.table_name, &columns);
+        let query = postgres_record::update(
+            &self.table_name,
+            &columns,
+            &increment_columns,
+            &version.is_some(),
+        );
         let mut query = sqlx::query(&query);
         for val in values {
             query = val.to_postgresdb_model(query)?;
         }
-        db.execute(query).await?;
+        if let Some(version) = version {
+            query = query.bind(version);
+        }
+        Ok(db.execute(query).await?.rows_affected() > 0)
+    }
+
+    async fn postgresdb_delete(
+        db: &PostgresDb,
+        collection_id: &Uuid,
+        id: &Uuid,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        let table_name = Self::new_table_name(collection_id);
+        let result = match version {
+            Some(version) => {
+                db.execute(
+                    sqlx::query(&postgres_record::delete_with_version(&table_name))
+                        .bind(id)
+                        .bind(version),
+                )
+                .await?
+            }
+            None => {
+                let mut column = HashSet::<String>::with_capacity(1);
+                column.insert("_id".to_owned());
+                db.execute(sqlx::query(&postgres_record::delete(&table_name, &column)).bind(id))
+                    .await?
+            }
+        };
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn postgresdb_check_version_column(db: &PostgresDb, table_name: &str) -> Result<bool> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(POSTGRES_COUNT_COLUMN)
+                    .bind(table_name)
+                    .bind("_version"),
+            )
+            .await?
+            .0
+            > 0)
+    }
+
+    async fn postgresdb_check_created_by_column(db: &PostgresDb, table_name: &str) -> Result<bool> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(POSTGRES_COUNT_COLUMN)
+                    .bind(table_name)
+                    .bind("_created_by"),
+            )
+            .await?
+            .0
+            > 0)
+    }
+
+    async fn postgresdb_add_version_column(db: &PostgresDb, table_name: &str) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&postgres_record::add_version_column(
+            table_name,
+        )))
+        .await?;
         Ok(())
     }
 
-    async fn postgresdb_delete(db: &PostgresDb, collection_id: &Uuid, id: &Uuid) -> Result<()> {
-        let mut column = HashSet::<String>::with_capacity(1);
-        column.insert("_id".to_owned());
-        db.execute(
-            sqlx::query(&postgres_record::delete(
-                &Self::new_table_name(collection_id),
-                &column,
-            ))
-            .bind(id),
-        )
+    async fn postgresdb_add_created_by_column(db: &PostgresDb, table_name: &str) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&postgres_record::add_created_by_column(
+            table_name,
+        )))
         .await?;
         Ok(())
     }
 
+    async fn postgresdb_delete_expired(
+        db: &PostgresDb,
+        collection_id: &Uuid,
+        cutoff_id: &Uuid,
+    ) -> Result<u64> {
+        Ok(db
+            .execute(
+                sqlx::query(&postgres_record::delete_before_id(&Self::new_table_name(
+                    collection_id,
+                )))
+                .bind(cutoff_id),
+            )
+            .await?
+            .rows_affected())
+    }
+
     async fn mysqldb_create_table(
         db: &MysqlDb,
         collection_id: &Uuid,
@@ -1154,6 +2452,15 @@ impl RecordDao {
             > 0)
     }
 
+    async fn mysqldb_table_size(db: &MysqlDb, collection_id: &Uuid) -> Result<i64> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(MYSQL_TABLE_SIZE).bind(&RecordDao::new_table_name(collection_id)),
+            )
+            .await?
+            .0)
+    }
+
     async fn mysqldb_add_columns(
         db: &MysqlDb,
         collection_id: &Uuid,
@@ -1193,6 +2500,21 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn mysqldb_rename_column(
+        db: &MysqlDb,
+        collection_id: &Uuid,
+        from: &str,
+        to: &str,
+    ) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&mysql_record::rename_column(
+            &Self::new_table_name(collection_id),
+            from,
+            to,
+        )))
+        .await?;
+        Ok(())
+    }
+
     async fn mysqldb_create_index(db: &MysqlDb, collection_id: &Uuid, index: &str) -> Result<()> {
         let record_table = Self::new_table_name(collection_id);
 
@@ -1237,6 +2559,49 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn mysqldb_create_partial_index(
+        db: &MysqlDb,
+        collection_id: &Uuid,
+        index: &str,
+        partial_index: &PartialIndexMysqlModel,
+        columns: &HashMap<String, SchemaFieldPropsMysqlModel>,
+    ) -> Result<()> {
+        let record_table = Self::new_table_name(collection_id);
+
+        if !partial_index.predicate().is_empty() {
+            db.execute_unprepared(sqlx::query(&mysql_record::add_partial_index_columns(
+                &record_table,
+                index,
+                partial_index,
+                columns,
+            )))
+            .await?;
+        }
+
+        db.execute_unprepared(sqlx::query(&mysql_record::create_partial_index(
+            &record_table,
+            index,
+            partial_index,
+        )))
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_drop_partial_index(
+        db: &MysqlDb,
+        collection_id: &Uuid,
+        index: &str,
+        partial_index: &PartialIndexMysqlModel,
+    ) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&mysql_record::drop_partial_index(
+            &Self::new_table_name(collection_id),
+            index,
+            partial_index,
+        )))
+        .await?;
+        Ok(())
+    }
+
     async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
         let mut columns = Vec::with_capacity(self.data.len());
         let mut values = Vec::with_capacity(self.data.len());
@@ -1253,6 +2618,40 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn mysqldb_insert_many(db: &MysqlDb, records: &[Self]) -> Result<()> {
+        let columns = records[0]
+            .keys()
+            .map(|col| col.as_str())
+            .collect::<Vec<_>>();
+        let query = mysql_record::insert_many(&records[0].table_name, &columns, &records.len());
+        let mut query = sqlx::query(&query);
+        for record in records {
+            for col in &columns {
+                if let Some(val) = record.get(col) {
+                    query = val.to_mysqldb_model(query)?;
+                }
+            }
+        }
+        db.execute(query).await?;
+        Ok(())
+    }
+
+    async fn mysqldb_upsert(&self, db: &MysqlDb) -> Result<()> {
+        let mut columns = Vec::with_capacity(self.data.len());
+        let mut values = Vec::with_capacity(self.data.len());
+        for (col, val) in &self.data {
+            columns.push(col.as_str());
+            values.push(val);
+        }
+        let query = mysql_record::upsert(&self.table_name, &columns);
+        let mut query = sqlx::query(&query);
+        for val in values {
+            query = val.to_mysqldb_model(query)?;
+        }
+        db.execute(query).await?;
+        Ok(())
+    }
+
     async fn mysqldb_select(
         db: &MysqlDb,
         table_name: &str,
@@ -1272,7 +2671,7 @@ impl RecordDao {
         groups: &Vec<&str>,
         orders: &Vec<RecordOrder>,
         pagination: &RecordPagination,
-    ) -> Result<(Vec<sqlx::mysql::MySqlRow>, i64)> {
+    ) -> Result<Vec<sqlx::mysql::MySqlRow>> {
         let filter = filters.mysqldb_filter_query(&None, 0)?;
 
         let mut order = Vec::with_capacity(orders.len());
@@ -1293,62 +2692,190 @@ impl RecordDao {
             &filter,
             groups,
             &order,
+            pagination.random(),
             &pagination.limit().is_some(),
         );
         let mut query_select_many = sqlx::query(&query_select_many);
-        let query_total = mysql_record::count(table_name, &filter);
-        let mut query_total = sqlx::query_as(&query_total);
 
         query_select_many = filters.mysqldb_values(query_select_many)?;
         if let Some(limit) = pagination.limit() {
             query_select_many = query_select_many.bind(limit);
         }
+
+        Ok(db.fetch_all_rows_cancellable(query_select_many).await?)
+    }
+
+    async fn mysqldb_count(db: &MysqlDb, table_name: &str, filters: &RecordFilters) -> Result<i64> {
+        let filter = filters.mysqldb_filter_query(&None, 0)?;
+
+        let query_total = mysql_record::count(table_name, &filter);
+        let mut query_total = sqlx::query_as(&query_total);
         query_total = filters.mysqldb_values_as(query_total)?;
 
-        let (rows, total) = tokio::try_join!(
-            db.fetch_all_rows(query_select_many),
-            db.fetch_one::<(i64,)>(query_total)
-        )?;
+        Ok(db.fetch_one::<(i64,)>(query_total).await?.0)
+    }
+
+    async fn mysqldb_select_distinct(
+        db: &MysqlDb,
+        table_name: &str,
+        column: &str,
+        filters: &RecordFilters,
+        pagination: &RecordPagination,
+    ) -> Result<Vec<sqlx::mysql::MySqlRow>> {
+        let filter = filters.mysqldb_filter_query(&None, 0)?;
+
+        let query = mysql_record::select_distinct(
+            table_name,
+            column,
+            &filter,
+            &pagination.limit().is_some(),
+        );
+        let mut query = sqlx::query(&query);
+        query = filters.mysqldb_values(query)?;
+        if let Some(limit) = pagination.limit() {
+            query = query.bind(limit);
+        }
+
+        Ok(db.fetch_all_rows_cancellable(query).await?)
+    }
 
-        Ok((rows, total.0))
+    async fn mysqldb_count_groups(
+        db: &MysqlDb,
+        table_name: &str,
+        groups: &Vec<&str>,
+        filters: &RecordFilters,
+    ) -> Result<Vec<sqlx::mysql::MySqlRow>> {
+        let filter = filters.mysqldb_filter_query(&None, 0)?;
+
+        let query = mysql_record::count_groups(table_name, groups, &filter);
+        let mut query = sqlx::query(&query);
+        query = filters.mysqldb_values(query)?;
+
+        Ok(db.fetch_all_rows_cancellable(query).await?)
     }
 
-    async fn mysqldb_update(&self, db: &MysqlDb) -> Result<()> {
+    async fn mysqldb_update(
+        &self,
+        db: &MysqlDb,
+        increments: &HashMap<String, ColumnValue>,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        let version_increment = ColumnValue::BigInteger(Some(1));
         let mut columns = Vec::with_capacity(self.data.len());
-        let mut values = Vec::with_capacity(self.data.len());
+        let mut values = Vec::with_capacity(self.data.len() + 2);
         for (col, val) in &self.data {
-            if col != "_id" {
+            if col != "_id" && col != "_version" && !increments.contains_key(col) {
                 columns.push(col.as_str());
                 values.push(val);
             }
         }
+        let mut increment_columns = Vec::with_capacity(increments.len() + 1);
+        for (col, val) in increments {
+            increment_columns.push(col.as_str());
+            values.push(val);
+        }
+        increment_columns.push("_version");
+        values.push(&version_increment);
         match self.data.get("_id") {
             Some(id) => values.push(id),
             None => return Err(Error::msg("Id is undefined")),
         }
-        let query = mysql_record::update(&self.table_name, &columns);
+        let query = mysql_record::update(
+            &self.table_name,
+            &columns,
+            &increment_columns,
+            &version.is_some(),
+        );
         let mut query = sqlx::query(&query);
         for val in values {
             query = val.to_mysqldb_model(query)?;
         }
-        db.execute(query).await?;
+        if let Some(version) = version {
+            query = query.bind(version);
+        }
+        Ok(db.execute(query).await?.rows_affected() > 0)
+    }
+
+    async fn mysqldb_delete(
+        db: &MysqlDb,
+        collection_id: &Uuid,
+        id: &Uuid,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        let table_name = Self::new_table_name(collection_id);
+        let result = match version {
+            Some(version) => {
+                db.execute(
+                    sqlx::query(&mysql_record::delete_with_version(&table_name))
+                        .bind(id)
+                        .bind(version),
+                )
+                .await?
+            }
+            None => {
+                let mut column = HashSet::<String>::with_capacity(1);
+                column.insert("_id".to_owned());
+                db.execute(sqlx::query(&mysql_record::delete(&table_name, &column)).bind(id))
+                    .await?
+            }
+        };
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mysqldb_check_version_column(db: &MysqlDb, table_name: &str) -> Result<bool> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(MYSQL_COUNT_COLUMN)
+                    .bind(table_name)
+                    .bind("_version"),
+            )
+            .await?
+            .0
+            > 0)
+    }
+
+    async fn mysqldb_check_created_by_column(db: &MysqlDb, table_name: &str) -> Result<bool> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(MYSQL_COUNT_COLUMN)
+                    .bind(table_name)
+                    .bind("_created_by"),
+            )
+            .await?
+            .0
+            > 0)
+    }
+
+    async fn mysqldb_add_version_column(db: &MysqlDb, table_name: &str) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&mysql_record::add_version_column(table_name)))
+            .await?;
         Ok(())
     }
 
-    async fn mysqldb_delete(db: &MysqlDb, collection_id: &Uuid, id: &Uuid) -> Result<()> {
-        let mut column = HashSet::<String>::with_capacity(1);
-        column.insert("_id".to_owned());
-        db.execute(
-            sqlx::query(&mysql_record::delete(
-                &Self::new_table_name(collection_id),
-                &column,
-            ))
-            .bind(id),
-        )
+    async fn mysqldb_add_created_by_column(db: &MysqlDb, table_name: &str) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&mysql_record::add_created_by_column(
+            table_name,
+        )))
         .await?;
         Ok(())
     }
 
+    async fn mysqldb_delete_expired(
+        db: &MysqlDb,
+        collection_id: &Uuid,
+        cutoff_id: &Uuid,
+    ) -> Result<u64> {
+        Ok(db
+            .execute(
+                sqlx::query(&mysql_record::delete_before_id(&Self::new_table_name(
+                    collection_id,
+                )))
+                .bind(cutoff_id),
+            )
+            .await?
+            .rows_affected())
+    }
+
     async fn sqlitedb_create_table(
         db: &SqliteDb,
         collection_id: &Uuid,
@@ -1380,6 +2907,15 @@ impl RecordDao {
             > 0)
     }
 
+    async fn sqlitedb_table_size(db: &SqliteDb, collection_id: &Uuid) -> Result<i64> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(SQLITE_TABLE_SIZE).bind(&RecordDao::new_table_name(collection_id)),
+            )
+            .await?
+            .0)
+    }
+
     async fn sqlitedb_add_columns(
         db: &SqliteDb,
         collection_id: &Uuid,
@@ -1419,6 +2955,21 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn sqlitedb_rename_column(
+        db: &SqliteDb,
+        collection_id: &Uuid,
+        from: &str,
+        to: &str,
+    ) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&sqlite_record::rename_column(
+            &Self::new_table_name(collection_id),
+            from,
+            to,
+        )))
+        .await?;
+        Ok(())
+    }
+
     async fn sqlitedb_create_index(db: &SqliteDb, collection_id: &Uuid, index: &str) -> Result<()> {
         db.execute_unprepared(sqlx::query(&sqlite_record::create_index(
             &Self::new_table_name(collection_id),
@@ -1437,6 +2988,21 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn sqlitedb_create_partial_index(
+        db: &SqliteDb,
+        collection_id: &Uuid,
+        index: &str,
+        partial_index: &PartialIndexSqliteModel,
+    ) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&sqlite_record::create_partial_index(
+            &Self::new_table_name(collection_id),
+            index,
+            partial_index,
+        )))
+        .await?;
+        Ok(())
+    }
+
     async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
         let mut columns = Vec::with_capacity(self.data.len());
         let mut values = Vec::with_capacity(self.data.len());
@@ -1453,6 +3019,40 @@ impl RecordDao {
         Ok(())
     }
 
+    async fn sqlitedb_insert_many(db: &SqliteDb, records: &[Self]) -> Result<()> {
+        let columns = records[0]
+            .keys()
+            .map(|col| col.as_str())
+            .collect::<Vec<_>>();
+        let query = sqlite_record::insert_many(&records[0].table_name, &columns, &records.len());
+        let mut query = sqlx::query(&query);
+        for record in records {
+            for col in &columns {
+                if let Some(val) = record.get(col) {
+                    query = val.to_sqlitedb_model(query)?;
+                }
+            }
+        }
+        db.execute(query).await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_upsert(&self, db: &SqliteDb) -> Result<()> {
+        let mut columns = Vec::with_capacity(self.data.len());
+        let mut values = Vec::with_capacity(self.data.len());
+        for (col, val) in &self.data {
+            columns.push(col.as_str());
+            values.push(val);
+        }
+        let query = sqlite_record::upsert(&self.table_name, &columns);
+        let mut query = sqlx::query(&query);
+        for val in values {
+            query = val.to_sqlitedb_model(query)?;
+        }
+        db.execute(query).await?;
+        Ok(())
+    }
+
     async fn sqlitedb_select(
         db: &SqliteDb,
         table_name: &str,
@@ -1472,7 +3072,7 @@ impl RecordDao {
         groups: &Vec<&str>,
         orders: &Vec<RecordOrder>,
         pagination: &RecordPagination,
-    ) -> Result<(Vec<sqlx::sqlite::SqliteRow>, i64)> {
+    ) -> Result<Vec<sqlx::sqlite::SqliteRow>> {
         let filter = filters.sqlitedb_filter_query(&None, 0)?;
 
         let mut order = Vec::with_capacity(orders.len());
@@ -1493,61 +3093,193 @@ impl RecordDao {
             &filter,
             groups,
             &order,
+            pagination.random(),
             &pagination.limit().is_some(),
         );
         let mut query_select_many = sqlx::query(&query_select_many);
-        let query_total = sqlite_record::count(table_name, &filter);
-        let mut query_total = sqlx::query_as(&query_total);
 
         query_select_many = filters.sqlitedb_values(query_select_many)?;
         if let Some(limit) = pagination.limit() {
             query_select_many = query_select_many.bind(limit);
         }
+
+        Ok(db.fetch_all_rows(query_select_many).await?)
+    }
+
+    async fn sqlitedb_count(
+        db: &SqliteDb,
+        table_name: &str,
+        filters: &RecordFilters,
+    ) -> Result<i64> {
+        let filter = filters.sqlitedb_filter_query(&None, 0)?;
+
+        let query_total = sqlite_record::count(table_name, &filter);
+        let mut query_total = sqlx::query_as(&query_total);
         query_total = filters.sqlitedb_values_as(query_total)?;
 
-        let (rows, total) = tokio::try_join!(
-            db.fetch_all_rows(query_select_many),
-            db.fetch_one::<(i64,)>(query_total)
-        )?;
+        Ok(db.fetch_one::<(i64,)>(query_total).await?.0)
+    }
+
+    async fn sqlitedb_select_distinct(
+        db: &SqliteDb,
+        table_name: &str,
+        column: &str,
+        filters: &RecordFilters,
+        pagination: &RecordPagination,
+    ) -> Result<Vec<sqlx::sqlite::SqliteRow>> {
+        let filter = filters.sqlitedb_filter_query(&None, 0)?;
+
+        let query = sqlite_record::select_distinct(
+            table_name,
+            column,
+            &filter,
+            &pagination.limit().is_some(),
+        );
+        let mut query = sqlx::query(&query);
+        query = filters.sqlitedb_values(query)?;
+        if let Some(limit) = pagination.limit() {
+            query = query.bind(limit);
+        }
+
+        Ok(db.fetch_all_rows(query).await?)
+    }
+
+    async fn sqlitedb_count_groups(
+        db: &SqliteDb,
+        table_name: &str,
+        groups: &Vec<&str>,
+        filters: &RecordFilters,
+    ) -> Result<Vec<sqlx::sqlite::SqliteRow>> {
+        let filter = filters.sqlitedb_filter_query(&None, 0)?;
+
+        let query = sqlite_record::count_groups(table_name, groups, &filter);
+        let mut query = sqlx::query(&query);
+        query = filters.sqlitedb_values(query)?;
 
-        Ok((rows, total.0))
+        Ok(db.fetch_all_rows(query).await?)
     }
 
-    async fn sqlitedb_update(&self, db: &SqliteDb) -> Result<()> {
+    async fn sqlitedb_update(
+        &self,
+        db: &SqliteDb,
+        increments: &HashMap<String, ColumnValue>,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        let version_increment = ColumnValue::BigInteger(Some(1));
         let mut columns = Vec::with_capacity(self.data.len());
-        let mut values = Vec::with_capacity(self.data.len());
+        let mut values = Vec::with_capacity(self.data.len() + 2);
         for (col, val) in &self.data {
-            if col != "_id" {
+            if col != "_id" && col != "_version" && !increments.contains_key(col) {
                 columns.push(col.as_str());
                 values.push(val);
             }
         }
+        let mut increment_columns = Vec::with_capacity(increments.len() + 1);
+        for (col, val) in increments {
+            increment_columns.push(col.as_str());
+            values.push(val);
+        }
+        increment_columns.push("_version");
+        values.push(&version_increment);
         match self.data.get("_id") {
             Some(id) => values.push(id),
             None => return Err(Error::msg("Id is undefined")),
         }
-        let query = sqlite_record::update(&self.table_name, &columns);
+        let query = sqlite_record::update(
+            &self.table_name,
+            &columns,
+            &increment_columns,
+            &version.is_some(),
+        );
         let mut query = sqlx::query(&query);
         for val in values {
             query = val.to_sqlitedb_model(query)?;
         }
-        db.execute(query).await?;
+        if let Some(version) = version {
+            query = query.bind(version);
+        }
+        Ok(db.execute(query).await?.rows_affected() > 0)
+    }
+
+    async fn sqlitedb_delete(
+        db: &SqliteDb,
+        collection_id: &Uuid,
+        id: &Uuid,
+        version: &Option<i64>,
+    ) -> Result<bool> {
+        let table_name = Self::new_table_name(collection_id);
+        let result = match version {
+            Some(version) => {
+                db.execute(
+                    sqlx::query(&sqlite_record::delete_with_version(&table_name))
+                        .bind(id)
+                        .bind(version),
+                )
+                .await?
+            }
+            None => {
+                let mut column = HashSet::<String>::with_capacity(1);
+                column.insert("_id".to_owned());
+                db.execute(sqlx::query(&sqlite_record::delete(&table_name, &column)).bind(id))
+                    .await?
+            }
+        };
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn sqlitedb_check_version_column(db: &SqliteDb, table_name: &str) -> Result<bool> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(SQLITE_COUNT_COLUMN)
+                    .bind(table_name)
+                    .bind("_version"),
+            )
+            .await?
+            .0
+            > 0)
+    }
+
+    async fn sqlitedb_check_created_by_column(db: &SqliteDb, table_name: &str) -> Result<bool> {
+        Ok(db
+            .fetch_one_unprepared::<(i64,)>(
+                sqlx::query_as(SQLITE_COUNT_COLUMN)
+                    .bind(table_name)
+                    .bind("_created_by"),
+            )
+            .await?
+            .0
+            > 0)
+    }
+
+    async fn sqlitedb_add_version_column(db: &SqliteDb, table_name: &str) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&sqlite_record::add_version_column(table_name)))
+            .await?;
         Ok(())
     }
 
-    async fn sqlitedb_delete(db: &SqliteDb, collection_id: &Uuid, id: &Uuid) -> Result<()> {
-        let mut column = HashSet::<String>::with_capacity(1);
-        column.insert("_id".to_owned());
-        db.execute(
-            sqlx::query(&sqlite_record::delete(
-                &Self::new_table_name(collection_id),
-                &column,
-            ))
-            .bind(id),
-        )
+    async fn sqlitedb_add_created_by_column(db: &SqliteDb, table_name: &str) -> Result<()> {
+        db.execute_unprepared(sqlx::query(&sqlite_record::add_created_by_column(
+            table_name,
+        )))
         .await?;
         Ok(())
     }
+
+    async fn sqlitedb_delete_expired(
+        db: &SqliteDb,
+        collection_id: &Uuid,
+        cutoff_id: &Uuid,
+    ) -> Result<u64> {
+        Ok(db
+            .execute(
+                sqlx::query(&sqlite_record::delete_before_id(&Self::new_table_name(
+                    collection_id,
+                )))
+                .bind(cutoff_id),
+            )
+            .await?
+            .rows_affected())
+    }
 }
 
 #[derive(Clone)]
@@ -1558,6 +3290,47 @@ impl RecordFilters {
         Self(data.to_vec())
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Both filters must hold. Top-level ANDs are merged rather than nested, since ScyllaDB only
+    // takes two levels of filters.
+    pub fn and(&self, other: &Self) -> Self {
+        let mut children = Vec::with_capacity(self.0.len() + other.0.len());
+        for f in self.0.iter().chain(&other.0) {
+            match &f.child {
+                Some(child) if f.op.eq_ignore_ascii_case("AND") => {
+                    children.extend(child.0.iter().cloned())
+                }
+                _ => children.push(f.clone()),
+            }
+        }
+        match children.len() {
+            0 | 1 => Self(children),
+            _ => Self(vec![RecordFilter::new(
+                &None,
+                "AND",
+                &None,
+                &Some(Self(children)),
+                &false,
+            )]),
+        }
+    }
+
+    pub fn fields(&self) -> HashSet<String> {
+        let mut fields = HashSet::new();
+        for f in &self.0 {
+            if let Some(field) = &f.field {
+                fields.insert(field.to_owned());
+            }
+            if let Some(child) = &f.child {
+                fields.extend(child.fields());
+            }
+        }
+        fields
+    }
+
     pub fn scylladb_filter_query(
         &self,
         logical_operator: &Option<&str>,
@@ -1589,10 +3362,27 @@ impl RecordFilters {
                 }
             } else {
                 let field = f.field.as_ref().unwrap();
+                if f.insensitive {
+                    return Err(Error::msg(
+                        "Case-insensitive comparison is not supported in ScyllaDB",
+                    ));
+                }
                 if SCYLLA_COMPARISON_OPERATOR.contains(&op.as_str()) {
+                    if SCYLLA_SUBSTRING_OPERATOR.contains(&op.as_str()) {
+                        f.check_values(&SCYLLA_NULLARY_OPERATOR)?;
+                        filter += &format!("\"{field}\" LIKE ?");
+                        continue;
+                    }
+                    if op == "BETWEEN" {
+                        f.check_values(&SCYLLA_NULLARY_OPERATOR)?;
+                        filter += &format!("\"{field}\" >= ? AND \"{field}\" <= ?");
+                        continue;
+                    }
                     filter += &format!("\"{}\" {}", field, &op);
-                    if f.value.is_some() {
-                        filter += " ?";
+                    if let Some(placeholders) =
+                        f.placeholders(&SCYLLA_NULLARY_OPERATOR, &mut || "?".to_owned())?
+                    {
+                        filter += &format!(" {placeholders}");
                     }
                 } else {
                     return Err(Error::msg(format!(
@@ -1607,7 +3397,9 @@ impl RecordFilters {
     pub fn scylladb_values(&self) -> Result<Vec<Box<dyn SerializeCql>>> {
         let mut values = Vec::with_capacity(self.values_capacity());
         for f in &self.0 {
-            if let Some(value) = &f.value {
+            for value in
+                f.values_to_bind(&SCYLLA_NULLARY_OPERATOR, &SCYLLA_SUBSTRING_OPERATOR, false)?
+            {
                 values.push(value.to_scylladb_model()?)
             }
             if let Some(child) = &f.child {
@@ -1638,7 +3430,16 @@ impl RecordFilters {
             }
             let op = f.op.to_uppercase();
             if let Some(child) = &f.child {
-                if POSTGRES_LOGICAL_OPERATOR.contains(&op.as_str()) {
+                if op == "NOT" {
+                    filter += &format!(
+                        "NOT ({})",
+                        child.postgresdb_filter_query(
+                            &Some("AND"),
+                            level + 1,
+                            first_argument_idx,
+                        )?
+                    );
+                } else if POSTGRES_LOGICAL_OPERATOR.contains(&op.as_str()) {
                     filter += &child.postgresdb_filter_query(
                         &Some(&op),
                         level + 1,
@@ -1651,10 +3452,30 @@ impl RecordFilters {
                 }
             } else {
                 if POSTGRES_COMPARISON_OPERATOR.contains(&op.as_str()) {
-                    filter += &format!("\"{}\" {}", f.field.as_ref().unwrap(), &op);
-                    if f.value.is_some() {
-                        filter += &format!(" ${}", first_argument_idx);
+                    if SUBSTRING_OPERATOR.contains(&op.as_str()) {
+                        f.check_values(&POSTGRES_NULLARY_OPERATOR)?;
+                        filter += &format!(
+                            "\"{}\" {} ${}",
+                            f.field.as_ref().unwrap(),
+                            if f.insensitive { "ILIKE" } else { "LIKE" },
+                            first_argument_idx
+                        );
                         *first_argument_idx += 1;
+                        continue;
+                    }
+                    filter += &format!(
+                        "{} {}",
+                        f.lower(format!("\"{}\"", f.field.as_ref().unwrap())),
+                        &op
+                    );
+                    if let Some(placeholders) =
+                        f.placeholders(&POSTGRES_NULLARY_OPERATOR, &mut || {
+                            let argument_idx = *first_argument_idx;
+                            *first_argument_idx += 1;
+                            f.lower(format!("${argument_idx}"))
+                        })?
+                    {
+                        filter += &format!(" {placeholders}");
                     }
                 } else {
                     return Err(Error::msg(format!(
@@ -1675,7 +3496,7 @@ impl RecordFilters {
     ) -> Result<sqlx::query::Query<'a, sqlx::Postgres, sqlx::postgres::PgArguments>> {
         let mut query = query;
         for f in &self.0 {
-            if let Some(value) = &f.value {
+            for value in f.values_to_bind(&POSTGRES_NULLARY_OPERATOR, &SUBSTRING_OPERATOR, true)? {
                 query = value.to_postgresdb_model(query)?
             }
             if let Some(child) = &f.child {
@@ -1691,7 +3512,7 @@ impl RecordFilters {
     ) -> Result<sqlx::query::QueryAs<'a, sqlx::Postgres, T, sqlx::postgres::PgArguments>> {
         let mut query = query;
         for f in &self.0 {
-            if let Some(value) = &f.value {
+            for value in f.values_to_bind(&POSTGRES_NULLARY_OPERATOR, &SUBSTRING_OPERATOR, true)? {
                 query = value.to_postgresdb_model_as(query)?
             }
             if let Some(child) = &f.child {
@@ -1721,7 +3542,12 @@ impl RecordFilters {
             }
             let op = f.op.to_uppercase();
             if let Some(child) = &f.child {
-                if MYSQL_LOGICAL_OPERATOR.contains(&op.as_str()) {
+                if op == "NOT" {
+                    filter += &format!(
+                        "NOT ({})",
+                        child.mysqldb_filter_query(&Some("AND"), level + 1)?
+                    );
+                } else if MYSQL_LOGICAL_OPERATOR.contains(&op.as_str()) {
                     filter += &child.mysqldb_filter_query(&Some(&op), level + 1)?;
                 } else {
                     return Err(Error::msg(format!(
@@ -1730,9 +3556,24 @@ impl RecordFilters {
                 }
             } else {
                 if MYSQL_COMPARISON_OPERATOR.contains(&op.as_str()) {
-                    filter += &format!("`{}` {}", f.field.as_ref().unwrap(), &op);
-                    if f.value.is_some() {
-                        filter += " ?";
+                    if SUBSTRING_OPERATOR.contains(&op.as_str()) {
+                        f.check_values(&MYSQL_NULLARY_OPERATOR)?;
+                        filter += &format!(
+                            "{} LIKE {}",
+                            f.lower(format!("`{}`", f.field.as_ref().unwrap())),
+                            f.lower("?".to_owned())
+                        );
+                        continue;
+                    }
+                    filter += &format!(
+                        "{} {}",
+                        f.lower(format!("`{}`", f.field.as_ref().unwrap())),
+                        &op
+                    );
+                    if let Some(placeholders) =
+                        f.placeholders(&MYSQL_NULLARY_OPERATOR, &mut || f.lower("?".to_owned()))?
+                    {
+                        filter += &format!(" {placeholders}");
                     }
                 } else {
                     return Err(Error::msg(format!(
@@ -1753,7 +3594,7 @@ impl RecordFilters {
     ) -> Result<sqlx::query::Query<'a, sqlx::MySql, sqlx::mysql::MySqlArguments>> {
         let mut query = query;
         for f in &self.0 {
-            if let Some(value) = &f.value {
+            for value in f.values_to_bind(&MYSQL_NULLARY_OPERATOR, &SUBSTRING_OPERATOR, true)? {
                 query = value.to_mysqldb_model(query)?
             }
             if let Some(child) = &f.child {
@@ -1769,7 +3610,7 @@ impl RecordFilters {
     ) -> Result<sqlx::query::QueryAs<'a, sqlx::MySql, T, sqlx::mysql::MySqlArguments>> {
         let mut query = query;
         for f in &self.0 {
-            if let Some(value) = &f.value {
+            for value in f.values_to_bind(&MYSQL_NULLARY_OPERATOR, &SUBSTRING_OPERATOR, true)? {
                 query = value.to_mysqldb_model_as(query)?
             }
             if let Some(child) = &f.child {
@@ -1799,7 +3640,12 @@ impl RecordFilters {
             }
             let op = f.op.to_uppercase();
             if let Some(child) = &f.child {
-                if SQLITE_LOGICAL_OPERATOR.contains(&op.as_str()) {
+                if op == "NOT" {
+                    filter += &format!(
+                        "NOT ({})",
+                        child.sqlitedb_filter_query(&Some("AND"), level + 1)?
+                    );
+                } else if SQLITE_LOGICAL_OPERATOR.contains(&op.as_str()) {
                     filter += &child.sqlitedb_filter_query(&Some(&op), level + 1)?;
                 } else {
                     return Err(Error::msg(format!(
@@ -1808,9 +3654,24 @@ impl RecordFilters {
                 }
             } else {
                 if SQLITE_COMPARISON_OPERATOR.contains(&op.as_str()) {
-                    filter += &format!("`{}` {}", f.field.as_ref().unwrap(), &op);
-                    if f.value.is_some() {
-                        filter += " ?";
+                    if SUBSTRING_OPERATOR.contains(&op.as_str()) {
+                        f.check_values(&SQLITE_NULLARY_OPERATOR)?;
+                        filter += &format!(
+                            "{} LIKE {} ESCAPE '\\'",
+                            f.lower(format!("`{}`", f.field.as_ref().unwrap())),
+                            f.lower("?".to_owned())
+                        );
+                        continue;
+                    }
+                    filter += &format!(
+                        "{} {}",
+                        f.lower(format!("`{}`", f.field.as_ref().unwrap())),
+                        &op
+                    );
+                    if let Some(placeholders) =
+                        f.placeholders(&SQLITE_NULLARY_OPERATOR, &mut || f.lower("?".to_owned()))?
+                    {
+                        filter += &format!(" {placeholders}");
                     }
                 } else {
                     return Err(Error::msg(format!(
@@ -1831,7 +3692,7 @@ impl RecordFilters {
     ) -> Result<sqlx::query::Query<'a, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'a>>> {
         let mut query = query;
         for f in &self.0 {
-            if let Some(value) = &f.value {
+            for value in f.values_to_bind(&SQLITE_NULLARY_OPERATOR, &SUBSTRING_OPERATOR, true)? {
                 query = value.to_sqlitedb_model(query)?
             }
             if let Some(child) = &f.child {
@@ -1847,7 +3708,7 @@ impl RecordFilters {
     ) -> Result<sqlx::query::QueryAs<'a, sqlx::Sqlite, T, sqlx::sqlite::SqliteArguments<'a>>> {
         let mut query = query;
         for f in &self.0 {
-            if let Some(value) = &f.value {
+            for value in f.values_to_bind(&SQLITE_NULLARY_OPERATOR, &SUBSTRING_OPERATOR, true)? {
                 query = value.to_sqlitedb_model_as(query)?
             }
             if let Some(child) = &f.child {
@@ -1868,26 +3729,43 @@ impl RecordFilters {
     }
 }
 
+const SCYLLA_UPDATE_ATTEMPTS: usize = 3;
+
+const SUBSTRING_OPERATOR: [&str; 4] = ["CONTAINS", "SUBSTRING", "STARTS_WITH", "ENDS_WITH"];
+// CQL already has CONTAINS for collection membership, so ScyllaDB keeps it and only turns the
+// other substring operators into LIKE
+const SCYLLA_SUBSTRING_OPERATOR: [&str; 3] = ["SUBSTRING", "STARTS_WITH", "ENDS_WITH"];
+const LIST_OPERATOR: [&str; 2] = ["IN", "NOT IN"];
+const RANGE_OPERATOR: [&str; 4] = [
+    "BETWEEN",
+    "NOT BETWEEN",
+    "BETWEEN SYMMETRIC",
+    "NOT BETWEEN SYMMETRIC",
+];
+
 #[derive(Clone)]
 pub struct RecordFilter {
     field: Option<String>,
     op: String,
-    value: Option<ColumnValue>,
+    value: Option<RecordFilterValue>,
     child: Option<RecordFilters>,
+    insensitive: bool,
 }
 
 impl RecordFilter {
     pub fn new(
         field: &Option<String>,
         op: &str,
-        value: &Option<ColumnValue>,
+        value: &Option<RecordFilterValue>,
         child: &Option<RecordFilters>,
+        insensitive: &bool,
     ) -> Self {
         Self {
             field: field.to_owned(),
             op: op.to_owned(),
             value: value.clone(),
             child: child.clone(),
+            insensitive: *insensitive,
         }
     }
 
@@ -1899,13 +3777,151 @@ impl RecordFilter {
         &self.op
     }
 
-    pub fn value(&self) -> &Option<ColumnValue> {
+    pub fn value(&self) -> &Option<RecordFilterValue> {
         &self.value
     }
 
     pub fn child(&self) -> &Option<RecordFilters> {
         &self.child
     }
+
+    pub fn insensitive(&self) -> &bool {
+        &self.insensitive
+    }
+
+    pub fn is_substring_operator(op: &str) -> bool {
+        SUBSTRING_OPERATOR.contains(&op.to_uppercase().as_str())
+    }
+
+    pub fn is_list_operator(op: &str) -> bool {
+        LIST_OPERATOR.contains(&op.to_uppercase().as_str())
+    }
+
+    pub fn is_range_operator(op: &str) -> bool {
+        RANGE_OPERATOR.contains(&op.to_uppercase().as_str())
+    }
+
+    // Whether any backend treats the operator as taking no value
+    pub fn is_null_operator(op: &str) -> bool {
+        let op = op.to_uppercase();
+        [
+            &SCYLLA_NULLARY_OPERATOR[..],
+            &POSTGRES_NULLARY_OPERATOR,
+            &MYSQL_NULLARY_OPERATOR,
+            &SQLITE_NULLARY_OPERATOR,
+        ]
+        .iter()
+        .any(|nullary| nullary.contains(&op.as_str()))
+    }
+
+    fn lower(&self, expr: String) -> String {
+        match self.insensitive {
+            true => format!("LOWER({expr})"),
+            false => expr,
+        }
+    }
+
+    fn check_values(&self, nullary: &[&str]) -> Result<()> {
+        let op = self.op.to_uppercase();
+        if nullary.contains(&op.as_str()) {
+            return Ok(());
+        } else if self.value.is_none() {
+            return Err(Error::msg(format!("Operator '{op}' requires a value")));
+        } else if RANGE_OPERATOR.contains(&op.as_str()) {
+            match &self.value {
+                Some(RecordFilterValue::Multiple(values)) if values.len() == 2 => (),
+                _ => {
+                    return Err(Error::msg(format!(
+                        "Operator '{op}' requires exactly two values"
+                    )))
+                }
+            }
+        } else if let Some(RecordFilterValue::Multiple(values)) = &self.value {
+            if values.is_empty() {
+                return Err(Error::msg(format!(
+                    "Operator '{op}' requires at least one value"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn placeholders(
+        &self,
+        nullary: &[&str],
+        placeholder: &mut impl FnMut() -> String,
+    ) -> Result<Option<String>> {
+        self.check_values(nullary)?;
+        if nullary.contains(&self.op.to_uppercase().as_str()) {
+            return Ok(None);
+        }
+        match &self.value {
+            Some(RecordFilterValue::Single(_)) => Ok(Some(placeholder())),
+            Some(RecordFilterValue::Multiple(_)) if Self::is_range_operator(&self.op) => {
+                Ok(Some(format!("{} AND {}", placeholder(), placeholder())))
+            }
+            Some(RecordFilterValue::Multiple(values)) => Ok(Some(format!(
+                "({})",
+                (0..values.len())
+                    .map(|_| placeholder())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    // Values of substring operators become LIKE patterns. SQL escapes the wildcards in them, while
+    // ScyllaDB's LIKE has no escape, so values with wildcards are rejected there.
+    fn values_to_bind(
+        &self,
+        nullary: &[&str],
+        substring: &[&str],
+        escape: bool,
+    ) -> Result<Vec<ColumnValue>> {
+        if nullary.contains(&self.op.to_uppercase().as_str()) {
+            return Ok(Vec::new());
+        }
+        let value = match &self.value {
+            Some(RecordFilterValue::Single(value)) => value,
+            Some(RecordFilterValue::Multiple(values)) => return Ok(values.to_vec()),
+            None => return Ok(Vec::new()),
+        };
+        let op = self.op.to_uppercase();
+        if !substring.contains(&op.as_str()) {
+            return Ok(vec![value.clone()]);
+        }
+        let pattern = match value {
+            ColumnValue::String(Some(value)) => match escape {
+                true => value
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_"),
+                false if value.contains(['%', '_']) => {
+                    return Err(Error::msg(format!(
+                        "Operator '{op}' doesn't accept '%' or '_' in ScyllaDB"
+                    )))
+                }
+                false => value.to_owned(),
+            },
+            _ => {
+                return Err(Error::msg(format!(
+                    "Operator '{op}' only accepts a string value"
+                )))
+            }
+        };
+        Ok(vec![ColumnValue::String(Some(match op.as_str() {
+            "CONTAINS" | "SUBSTRING" => format!("%{pattern}%"),
+            "STARTS_WITH" => format!("{pattern}%"),
+            _ => format!("%{pattern}"),
+        }))])
+    }
+}
+
+#[derive(Clone)]
+pub enum RecordFilterValue {
+    Single(ColumnValue),
+    Multiple(Vec<ColumnValue>),
 }
 
 pub struct RecordOrder {
@@ -1932,14 +3948,172 @@ impl RecordOrder {
 
 pub struct RecordPagination {
     limit: Option<i32>,
+    random: bool,
 }
 
 impl RecordPagination {
     pub fn new(limit: &Option<i32>) -> Self {
-        Self { limit: *limit }
+        Self {
+            limit: *limit,
+            random: false,
+        }
+    }
+
+    pub fn sample(size: &i32) -> Self {
+        Self {
+            limit: Some(*size),
+            random: true,
+        }
     }
 
     pub fn limit(&self) -> &Option<i32> {
         &self.limit
     }
+
+    pub fn random(&self) -> &bool {
+        &self.random
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ColumnValue, RecordFilter, RecordFilterValue, RecordFilters, POSTGRES_NULLARY_OPERATOR,
+        SUBSTRING_OPERATOR,
+    };
+
+    fn filter(op: &str) -> RecordFilters {
+        RecordFilters::new(&vec![RecordFilter::new(
+            &Some("x".to_owned()),
+            op,
+            &None,
+            &None,
+            &false,
+        )])
+    }
+
+    #[test]
+    fn postgresdb_accepts_operators_without_value() {
+        for op in POSTGRES_NULLARY_OPERATOR {
+            let filters = filter(op);
+            assert_eq!(
+                filters.postgresdb_filter_query(&None, 0, &mut 1).unwrap(),
+                format!("\"x\" {op}")
+            );
+            assert!(filters.0[0]
+                .values_to_bind(&POSTGRES_NULLARY_OPERATOR, &SUBSTRING_OPERATOR, true)
+                .unwrap()
+                .is_empty());
+        }
+        assert!(filter("=")
+            .postgresdb_filter_query(&None, 0, &mut 1)
+            .is_err());
+    }
+
+    #[test]
+    fn mysqldb_accepts_operators_without_value() {
+        for op in ["IS NULL", "IS NOT NULL"] {
+            assert_eq!(
+                filter(op).mysqldb_filter_query(&None, 0).unwrap(),
+                format!("`x` {op}")
+            );
+        }
+        assert!(filter("ISNULL").mysqldb_filter_query(&None, 0).is_err());
+        assert!(filter("=").mysqldb_filter_query(&None, 0).is_err());
+    }
+
+    #[test]
+    fn sqlitedb_accepts_operators_without_value() {
+        for op in ["IS NULL", "IS NOT NULL"] {
+            assert_eq!(
+                filter(op).sqlitedb_filter_query(&None, 0).unwrap(),
+                format!("`x` {op}")
+            );
+        }
+        assert!(filter("=").sqlitedb_filter_query(&None, 0).is_err());
+    }
+
+    #[test]
+    fn scylladb_requires_values() {
+        assert!(filter("=").scylladb_filter_query(&None, 0).is_err());
+        assert!(filter("IS NULL").scylladb_filter_query(&None, 0).is_err());
+    }
+
+    #[test]
+    fn scylladb_keeps_cql_contains() {
+        let with_value = |op: &str, value: &str| {
+            RecordFilters::new(&vec![RecordFilter::new(
+                &Some("x".to_owned()),
+                op,
+                &Some(RecordFilterValue::Single(ColumnValue::String(Some(
+                    value.to_owned(),
+                )))),
+                &None,
+                &false,
+            )])
+        };
+
+        assert_eq!(
+            with_value("CONTAINS", "a")
+                .scylladb_filter_query(&None, 0)
+                .unwrap(),
+            "\"x\" CONTAINS ?"
+        );
+        assert_eq!(
+            with_value("CONTAINS", "a%")
+                .scylladb_values()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            with_value("SUBSTRING", "a")
+                .scylladb_filter_query(&None, 0)
+                .unwrap(),
+            "\"x\" LIKE ?"
+        );
+        for value in ["a%", "a_b"] {
+            assert!(with_value("SUBSTRING", value).scylladb_values().is_err());
+            assert!(with_value("STARTS_WITH", value).scylladb_values().is_err());
+        }
+        assert!(matches!(
+            &with_value("SUBSTRING", "a_b").0[0]
+                .values_to_bind(&POSTGRES_NULLARY_OPERATOR, &SUBSTRING_OPERATOR, true)
+                .unwrap()[..],
+            [ColumnValue::String(Some(pattern))] if pattern == "%a\\_b%"
+        ));
+    }
+
+    #[test]
+    fn and_merges_top_level_ands() {
+        let and = |filters: RecordFilters| {
+            RecordFilters::new(&vec![RecordFilter::new(
+                &None,
+                "AND",
+                &None,
+                &Some(filters),
+                &false,
+            )])
+        };
+        let empty = RecordFilters::new(&Vec::new());
+
+        assert!(empty.and(&empty).is_empty());
+        assert_eq!(
+            empty
+                .and(&filter("IS NULL"))
+                .postgresdb_filter_query(&None, 0, &mut 1)
+                .unwrap(),
+            "\"x\" IS NULL"
+        );
+        let merged = and(filter("IS NULL")).and(&and(filter("IS NOT NULL")));
+        assert_eq!(merged.0.len(), 1);
+        assert_eq!(merged.0[0].child.as_ref().unwrap().0.len(), 2);
+        assert_eq!(
+            and(filter("IS NULL"))
+                .and(&filter("IS NOT NULL"))
+                .postgresdb_filter_query(&None, 0, &mut 1)
+                .unwrap(),
+            "\"x\" IS NULL AND \"x\" IS NOT NULL"
+        );
+    }
 }
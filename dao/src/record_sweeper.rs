@@ -0,0 +1,40 @@
+use anyhow::Result;
+
+use crate::{collection::CollectionDao, record::RecordDao, Db};
+
+// Deletes records that have outlived their collection's auto delete period. A failing collection
+// doesn't stop the others from being swept, but the first error is returned afterwards.
+pub async fn sweep(db: &Db) -> Result<String> {
+    let mut deleted = 0;
+    let mut first_err = None;
+    for collection_data in CollectionDao::db_select_many_with_auto_delete(db).await? {
+        let auto_delete_after_seconds = match collection_data.auto_delete_after_seconds() {
+            Some(auto_delete_after_seconds) => auto_delete_after_seconds,
+            None => continue,
+        };
+        match RecordDao::db_delete_expired(db, collection_data.id(), auto_delete_after_seconds)
+            .await
+        {
+            Ok(0) => (),
+            Ok(count) => {
+                hb_log::info(
+                    Some("🧹"),
+                    format!(
+                        "RecordSweeper: Deleted {count} expired records from collection '{}'",
+                        collection_data.id()
+                    ),
+                );
+                deleted += count;
+            }
+            Err(err) => {
+                hb_log::error(None, &err);
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+
+    match first_err {
+        Some(err) => Err(err),
+        None => Ok(format!("Deleted {deleted} expired records")),
+    }
+}
@@ -4,15 +4,16 @@ use hb_db_mysql::{
     db::MysqlDb,
     model::registration::RegistrationModel as RegistrationMysqlModel,
     query::registration::{
-        DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
-        SELECT_BY_EMAIL as MYSQL_SELECT_BY_EMAIL, UPDATE as MYSQL_UPDATE,
+        DELETE as MYSQL_DELETE, DELETE_EXPIRED as MYSQL_DELETE_EXPIRED, INSERT as MYSQL_INSERT,
+        SELECT as MYSQL_SELECT, SELECT_BY_EMAIL as MYSQL_SELECT_BY_EMAIL, UPDATE as MYSQL_UPDATE,
     },
 };
 use hb_db_postgresql::{
     db::PostgresDb,
     model::registration::RegistrationModel as RegistrationPostgresModel,
     query::registration::{
-        DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
+        DELETE as POSTGRES_DELETE, DELETE_EXPIRED as POSTGRES_DELETE_EXPIRED,
+        INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
         SELECT_BY_EMAIL as POSTGRES_SELECT_BY_EMAIL, UPDATE as POSTGRES_UPDATE,
     },
 };
@@ -28,8 +29,9 @@ use hb_db_sqlite::{
     db::SqliteDb,
     model::registration::RegistrationModel as RegistrationSqliteModel,
     query::registration::{
-        DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
-        SELECT_BY_EMAIL as SQLITE_SELECT_BY_EMAIL, UPDATE as SQLITE_UPDATE,
+        DELETE as SQLITE_DELETE, DELETE_EXPIRED as SQLITE_DELETE_EXPIRED, INSERT as SQLITE_INSERT,
+        SELECT as SQLITE_SELECT, SELECT_BY_EMAIL as SQLITE_SELECT_BY_EMAIL,
+        UPDATE as SQLITE_UPDATE,
     },
 };
 use rand::{thread_rng, Rng};
@@ -150,6 +152,16 @@ impl RegistrationDao {
         }
     }
 
+    pub async fn db_delete_expired(db: &Db) -> Result<u64> {
+        match db {
+            // ScyllaDB expires registrations itself through the table's default TTL
+            Db::ScyllaDb(_) => Ok(0),
+            Db::PostgresqlDb(db) => Self::postgresdb_delete_expired(db).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete_expired(db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete_expired(db).await,
+        }
+    }
+
     async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
         db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
         Ok(())
@@ -250,6 +262,20 @@ impl RegistrationDao {
         Ok(())
     }
 
+    async fn postgresdb_delete_expired(db: &PostgresDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(POSTGRES_DELETE_EXPIRED).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_registration_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?
+            .rows_affected())
+    }
+
     async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
         db.execute(
             sqlx::query(MYSQL_INSERT)
@@ -306,6 +332,20 @@ impl RegistrationDao {
         Ok(())
     }
 
+    async fn mysqldb_delete_expired(db: &MysqlDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(MYSQL_DELETE_EXPIRED).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_registration_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?
+            .rows_affected())
+    }
+
     async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
         db.execute(
             sqlx::query(SQLITE_INSERT)
@@ -366,6 +406,20 @@ impl RegistrationDao {
         Ok(())
     }
 
+    async fn sqlitedb_delete_expired(db: &SqliteDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(SQLITE_DELETE_EXPIRED).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_registration_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?
+            .rows_affected())
+    }
+
     fn from_scylladb_model(model: &RegistrationScyllaModel) -> Result<Self> {
         Ok(Self {
             id: *model.id(),
@@ -0,0 +1,323 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use tokio::sync::{mpsc, watch};
+
+use crate::{
+    admin_password_reset::AdminPasswordResetDao, backup, project_purger, record_sweeper,
+    register::RegistrationDao, user_password_reset::UserPasswordResetDao, Db,
+};
+
+const BACKUP_FILE_PREFIX: &str = "hyperbase-backup-";
+const BACKUP_FILE_EXTENSION: &str = ".ndjson";
+
+pub enum Job {
+    RecordSweep,
+    ProjectPurge { grace_period: Duration },
+    AuthCleanup,
+    Backup { dir: PathBuf, keep: usize },
+}
+
+impl Job {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::RecordSweep => "record_sweep",
+            Self::ProjectPurge { .. } => "project_purge",
+            Self::AuthCleanup => "auth_cleanup",
+            Self::Backup { .. } => "backup",
+        }
+    }
+
+    async fn run(&self, db: &Db) -> Result<String> {
+        match self {
+            Self::RecordSweep => record_sweeper::sweep(db).await,
+            Self::ProjectPurge { grace_period } => project_purger::purge(db, grace_period).await,
+            Self::AuthCleanup => {
+                let (registrations, admin_password_resets, user_password_resets) = tokio::try_join!(
+                    RegistrationDao::db_delete_expired(db),
+                    AdminPasswordResetDao::db_delete_expired(db),
+                    UserPasswordResetDao::db_delete_expired(db),
+                )?;
+                Ok(format!(
+                    "Deleted {registrations} registrations and {} password resets",
+                    admin_password_resets + user_password_resets
+                ))
+            }
+            Self::Backup { dir, keep } => backup_to_dir(db, dir, keep).await,
+        }
+    }
+}
+
+pub enum JobSchedule {
+    Every(Duration),
+    Cron(Box<Schedule>),
+}
+
+impl JobSchedule {
+    pub fn from_cron(expression: &str) -> Result<Self> {
+        match Schedule::from_str(expression) {
+            Ok(schedule) => Ok(Self::Cron(Box::new(schedule))),
+            Err(err) => Err(Error::msg(format!(
+                "Invalid cron expression '{expression}': {err}"
+            ))),
+        }
+    }
+
+    fn next_after(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(interval) => Some(*after + chrono::Duration::from_std(*interval).ok()?),
+            Self::Cron(schedule) => schedule.after(after).next(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Every(interval) => format!("every {}s", interval.as_secs()),
+            Self::Cron(schedule) => schedule.to_string(),
+        }
+    }
+}
+
+// Shared with the admin API so job progress can be inspected while the scheduler runs
+#[derive(Clone, Default)]
+pub struct JobStatuses {
+    statuses: Arc<Mutex<Vec<JobStatus>>>,
+}
+
+impl JobStatuses {
+    pub fn all(&self) -> Vec<JobStatus> {
+        self.statuses.lock().unwrap().clone()
+    }
+
+    fn update(&self, idx: &usize, f: impl FnOnce(&mut JobStatus)) {
+        if let Some(status) = self.statuses.lock().unwrap().get_mut(*idx) {
+            f(status);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct JobStatus {
+    name: String,
+    schedule: String,
+    running: bool,
+    next_run_at: Option<DateTime<Utc>>,
+    last_started_at: Option<DateTime<Utc>>,
+    last_finished_at: Option<DateTime<Utc>>,
+    last_succeeded: Option<bool>,
+    last_message: Option<String>,
+    run_count: u64,
+    failure_count: u64,
+}
+
+impl JobStatus {
+    fn new(name: &str, schedule: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            schedule: schedule.to_owned(),
+            running: false,
+            next_run_at: None,
+            last_started_at: None,
+            last_finished_at: None,
+            last_succeeded: None,
+            last_message: None,
+            run_count: 0,
+            failure_count: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn schedule(&self) -> &str {
+        &self.schedule
+    }
+
+    pub fn running(&self) -> &bool {
+        &self.running
+    }
+
+    pub fn next_run_at(&self) -> &Option<DateTime<Utc>> {
+        &self.next_run_at
+    }
+
+    pub fn last_started_at(&self) -> &Option<DateTime<Utc>> {
+        &self.last_started_at
+    }
+
+    pub fn last_finished_at(&self) -> &Option<DateTime<Utc>> {
+        &self.last_finished_at
+    }
+
+    pub fn last_succeeded(&self) -> &Option<bool> {
+        &self.last_succeeded
+    }
+
+    pub fn last_message(&self) -> &Option<String> {
+        &self.last_message
+    }
+
+    pub fn run_count(&self) -> &u64 {
+        &self.run_count
+    }
+
+    pub fn failure_count(&self) -> &u64 {
+        &self.failure_count
+    }
+}
+
+pub struct Scheduler {
+    db: Arc<Db>,
+    jobs: Vec<(Job, JobSchedule)>,
+    statuses: JobStatuses,
+}
+
+impl Scheduler {
+    pub fn new(db: Arc<Db>, jobs: Vec<(Job, JobSchedule)>, statuses: JobStatuses) -> Self {
+        hb_log::info(Some("⚡"), "Scheduler: Initializing component");
+
+        *statuses.statuses.lock().unwrap() = jobs
+            .iter()
+            .map(|(job, schedule)| JobStatus::new(job.name(), &schedule.describe()))
+            .collect();
+
+        Self { db, jobs, statuses }
+    }
+
+    // Runs on the caller's task rather than a spawned one because record queries aren't Send. Jobs
+    // run one at a time, so a job that becomes due while another is running starts right after it.
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        hb_log::info(Some("💫"), "Scheduler: Running component");
+
+        // Interval jobs run once at startup, cron jobs wait for their first match
+        let now = Utc::now();
+        let mut next_runs: Vec<_> = self
+            .jobs
+            .iter()
+            .map(|(_, schedule)| match schedule {
+                JobSchedule::Every(_) => Some(now),
+                JobSchedule::Cron(_) => schedule.next_after(&now),
+            })
+            .collect();
+
+        loop {
+            for (idx, next_run_at) in next_runs.iter().enumerate() {
+                self.statuses
+                    .update(&idx, |status| status.next_run_at = *next_run_at);
+            }
+
+            let due = next_runs
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, next_run_at)| next_run_at.map(|next_run_at| (idx, next_run_at)))
+                .min_by_key(|(_, next_run_at)| *next_run_at);
+            let (idx, next_run_at) = match due {
+                Some(due) => due,
+                None => {
+                    let _ = shutdown.wait_for(|shutdown| *shutdown).await;
+                    break;
+                }
+            };
+
+            let wait = (next_run_at - Utc::now()).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => (),
+                _ = shutdown.wait_for(|shutdown| *shutdown) => break,
+            }
+
+            let (job, schedule) = &self.jobs[idx];
+            self.statuses.update(&idx, |status| {
+                status.running = true;
+                status.last_started_at = Some(Utc::now());
+            });
+
+            let result = job.run(&self.db).await;
+            match &result {
+                Ok(message) => {
+                    hb_log::debug(None, format!("Scheduler: Job '{}': {message}", job.name()))
+                }
+                Err(err) => hb_log::error(
+                    None,
+                    format!("Scheduler: Job '{}' failed: {err}", job.name()),
+                ),
+            }
+
+            let finished_at = Utc::now();
+            self.statuses.update(&idx, |status| {
+                status.running = false;
+                status.last_finished_at = Some(finished_at);
+                status.last_succeeded = Some(result.is_ok());
+                status.run_count += 1;
+                match result {
+                    Ok(message) => status.last_message = Some(message),
+                    Err(err) => {
+                        status.last_message = Some(err.to_string());
+                        status.failure_count += 1;
+                    }
+                }
+            });
+            next_runs[idx] = schedule.next_after(&finished_at);
+        }
+
+        hb_log::info(Some("👋"), "Scheduler: Stopped component");
+        Ok(())
+    }
+}
+
+async fn backup_to_dir(db: &Db, dir: &Path, keep: &usize) -> Result<String> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "{BACKUP_FILE_PREFIX}{}{BACKUP_FILE_EXTENSION}",
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    let mut writer = BufWriter::new(File::create(&path)?);
+
+    let (tx, mut rx) = mpsc::channel::<String>(64);
+    let (summary, written) = tokio::join!(async move { backup::db_backup(db, &tx).await }, async {
+        while let Some(line) = rx.recv().await {
+            writer.write_all(line.as_bytes())?;
+        }
+        writer.flush()
+    });
+    let summary = match summary.and_then(|summary| Ok(written.map(|_| summary)?)) {
+        Ok(summary) => summary,
+        Err(err) => {
+            let _ = fs::remove_file(&path);
+            return Err(err);
+        }
+    };
+
+    // File names sort by their timestamps, so everything before the newest `keep` files is pruned
+    let mut backups: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(BACKUP_FILE_PREFIX) && name.ends_with(BACKUP_FILE_EXTENSION)
+                })
+        })
+        .collect();
+    backups.sort();
+    for old_backup in backups.iter().rev().skip(*keep) {
+        fs::remove_file(old_backup)?;
+    }
+
+    Ok(format!(
+        "Backed up {} collections and {} records to {}",
+        summary.collections(),
+        summary.records(),
+        path.display()
+    ))
+}
@@ -1,5 +1,7 @@
+use std::net::IpAddr;
+
 use ahash::{HashMap, HashMapExt};
-use anyhow::Result;
+use anyhow::{Error, Result};
 use chrono::{DateTime, Utc};
 use hb_db_mysql::{
     db::MysqlDb,
@@ -8,8 +10,10 @@ use hb_db_mysql::{
     },
     query::token::{
         DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
+        SELECT_BY_PREVIOUS_TOKEN as MYSQL_SELECT_BY_PREVIOUS_TOKEN,
         SELECT_BY_TOKEN as MYSQL_SELECT_BY_TOKEN,
         SELECT_MANY_BY_ADMIN_ID as MYSQL_SELECT_MANY_BY_ADMIN_ID, UPDATE as MYSQL_UPDATE,
+        UPDATE_TOKEN as MYSQL_UPDATE_TOKEN,
     },
 };
 use hb_db_postgresql::{
@@ -19,8 +23,10 @@ use hb_db_postgresql::{
     },
     query::token::{
         DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
+        SELECT_BY_PREVIOUS_TOKEN as POSTGRES_SELECT_BY_PREVIOUS_TOKEN,
         SELECT_BY_TOKEN as POSTGRES_SELECT_BY_TOKEN,
         SELECT_MANY_BY_ADMIN_ID as POSTGRES_SELECT_MANY_BY_ADMIN_ID, UPDATE as POSTGRES_UPDATE,
+        UPDATE_TOKEN as POSTGRES_UPDATE_TOKEN,
     },
 };
 use hb_db_scylladb::{
@@ -30,8 +36,10 @@ use hb_db_scylladb::{
     },
     query::token::{
         DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
+        SELECT_BY_PREVIOUS_TOKEN as SCYLLA_SELECT_BY_PREVIOUS_TOKEN,
         SELECT_BY_TOKEN as SCYLLA_SELECT_BY_TOKEN,
         SELECT_MANY_BY_ADMIN_ID as SCYLLA_SELECT_MANY_BY_ADMIN_ID, UPDATE as SCYLLA_UPDATE,
+        UPDATE_TOKEN as SCYLLA_UPDATE_TOKEN,
     },
 };
 use hb_db_sqlite::{
@@ -41,8 +49,10 @@ use hb_db_sqlite::{
     },
     query::token::{
         DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
+        SELECT_BY_PREVIOUS_TOKEN as SQLITE_SELECT_BY_PREVIOUS_TOKEN,
         SELECT_BY_TOKEN as SQLITE_SELECT_BY_TOKEN,
         SELECT_MANY_BY_ADMIN_ID as SQLITE_SELECT_MANY_BY_ADMIN_ID, UPDATE as SQLITE_UPDATE,
+        UPDATE_TOKEN as SQLITE_UPDATE_TOKEN,
     },
 };
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
@@ -50,11 +60,28 @@ use scylla::{
     frame::value::CqlTimestamp as ScyllaCqlTimestamp,
     transport::session::TypedRowIter as ScyllaTypedRowIter,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
-use crate::{util::conversion, Db};
+use crate::{
+    expression::{Expression, Filter},
+    util::{cidr, conversion},
+    Db,
+};
+
+pub const TOKEN_SCOPES: [&str; 4] = [
+    "records:read",
+    "records:write",
+    "users:auth",
+    "users:anonymous",
+];
 
+// What a rule condition can reference: the request being made, the token making it, and the
+// record it touches
+pub const RULE_CONDITION_VARIABLES: [&str; 3] = ["request", "token", "record"];
+
+#[derive(Serialize, Deserialize)]
 pub struct TokenDao {
     id: Uuid,
     created_at: DateTime<Utc>,
@@ -63,6 +90,10 @@ pub struct TokenDao {
     token: String,
     rules: HashMap<Uuid, TokenRuleMethod>,
     expired_at: Option<DateTime<Utc>>,
+    allowed_cidrs: Option<Vec<String>>,
+    scopes: Option<Vec<String>>,
+    previous_token: Option<String>,
+    previous_token_expired_at: Option<DateTime<Utc>>,
 }
 
 impl TokenDao {
@@ -71,6 +102,8 @@ impl TokenDao {
         token_length: &usize,
         rules: &HashMap<Uuid, TokenRuleMethod>,
         expired_at: &Option<DateTime<Utc>>,
+        allowed_cidrs: &Option<Vec<String>>,
+        scopes: &Option<Vec<String>>,
     ) -> Self {
         let now = Utc::now();
         Self {
@@ -78,13 +111,13 @@ impl TokenDao {
             created_at: now,
             updated_at: now,
             admin_id: *admin_id,
-            token: thread_rng()
-                .sample_iter(&Alphanumeric)
-                .take(*token_length)
-                .map(char::from)
-                .collect(),
+            token: Self::generate_token(token_length),
             rules: rules.clone(),
             expired_at: *expired_at,
+            allowed_cidrs: allowed_cidrs.clone(),
+            scopes: scopes.clone(),
+            previous_token: None,
+            previous_token_expired_at: None,
         }
     }
 
@@ -116,6 +149,22 @@ impl TokenDao {
         &self.expired_at
     }
 
+    pub fn allowed_cidrs(&self) -> &Option<Vec<String>> {
+        &self.allowed_cidrs
+    }
+
+    pub fn scopes(&self) -> &Option<Vec<String>> {
+        &self.scopes
+    }
+
+    pub fn previous_token(&self) -> &Option<String> {
+        &self.previous_token
+    }
+
+    pub fn previous_token_expired_at(&self) -> &Option<DateTime<Utc>> {
+        &self.previous_token_expired_at
+    }
+
     pub fn set_rules(&mut self, rules: &HashMap<Uuid, TokenRuleMethod>) {
         self.rules = rules.clone();
     }
@@ -124,6 +173,67 @@ impl TokenDao {
         self.expired_at = *expired_at;
     }
 
+    pub fn set_allowed_cidrs(&mut self, allowed_cidrs: &Option<Vec<String>>) {
+        self.allowed_cidrs = allowed_cidrs.clone();
+    }
+
+    pub fn set_scopes(&mut self, scopes: &Option<Vec<String>>) {
+        self.scopes = scopes.clone();
+    }
+
+    pub fn rotate(&mut self, token_length: &usize, grace_period: &i64) {
+        if *grace_period > 0 {
+            self.previous_token = Some(self.token.clone());
+            self.previous_token_expired_at =
+                Some(Utc::now() + chrono::Duration::seconds(*grace_period));
+        } else {
+            self.previous_token = None;
+            self.previous_token_expired_at = None;
+        }
+        self.token = Self::generate_token(token_length);
+    }
+
+    fn generate_token(token_length: &usize) -> String {
+        thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(*token_length)
+            .map(char::from)
+            .collect()
+    }
+
+    pub fn check_scopes(scopes: &[String]) -> Result<()> {
+        for scope in scopes {
+            if !TOKEN_SCOPES.contains(&scope.as_str()) {
+                return Err(Error::msg(format!("Scope '{scope}' is not supported")));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_allow_scope(&self, scope: &str) -> bool {
+        match &self.scopes {
+            Some(scopes) => scopes.iter().any(|s| s == scope),
+            None => true,
+        }
+    }
+
+    pub fn check_allowed_cidrs(allowed_cidrs: &[String]) -> Result<()> {
+        for allowed_cidr in allowed_cidrs {
+            cidr::parse(allowed_cidr)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_allow_ip(&self, ip: &Option<IpAddr>) -> bool {
+        match &self.allowed_cidrs {
+            Some(allowed_cidrs) => match ip {
+                Some(ip) => allowed_cidrs.iter().any(|cidr| cidr::contains(cidr, ip)),
+                None => false,
+            },
+            None => true,
+        }
+    }
+
     pub fn is_allow_find_one(&self, collection_id: &Uuid) -> bool {
         match self.rules.get(collection_id) {
             Some(rules) => rules.find_one,
@@ -159,6 +269,50 @@ impl TokenDao {
         }
     }
 
+    pub fn has_condition(&self, collection_id: &Uuid) -> bool {
+        self.rules
+            .get(collection_id)
+            .is_some_and(|rules| rules.condition.is_some())
+    }
+
+    // Checked on top of the method flags, so a condition can only narrow what a rule allows
+    pub fn is_allow_condition(
+        &self,
+        collection_id: &Uuid,
+        context: &Map<String, Value>,
+    ) -> Result<bool> {
+        match self
+            .rules
+            .get(collection_id)
+            .and_then(|rules| rules.condition.as_ref())
+        {
+            Some(condition) => {
+                Expression::parse(condition, &RULE_CONDITION_VARIABLES)?.evaluate(context)
+            }
+            None => Ok(true),
+        }
+    }
+
+    // The condition as a filter on the records, for requests that read many of them at once.
+    // The context has no record, the rest of it is evaluated up front.
+    pub fn condition_filter(
+        &self,
+        collection_id: &Uuid,
+        context: &Map<String, Value>,
+    ) -> Result<Option<Filter>> {
+        match self
+            .rules
+            .get(collection_id)
+            .and_then(|rules| rules.condition.as_ref())
+        {
+            Some(condition) => Ok(Some(
+                Expression::parse(condition, &RULE_CONDITION_VARIABLES)?
+                    .to_filter("record", Some(context))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
     pub async fn db_insert(&self, db: &Db) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
@@ -186,6 +340,21 @@ impl TokenDao {
     }
 
     pub async fn db_select_by_token(db: &Db, token: &str) -> Result<Self> {
+        match Self::db_select_by_current_token(db, token).await {
+            Ok(data) => Ok(data),
+            Err(err) => match Self::db_select_by_previous_token(db, token).await {
+                Ok(data) => match data.previous_token_expired_at {
+                    Some(previous_token_expired_at) if previous_token_expired_at > Utc::now() => {
+                        Ok(data)
+                    }
+                    _ => Err(err),
+                },
+                Err(_) => Err(err),
+            },
+        }
+    }
+
+    async fn db_select_by_current_token(db: &Db, token: &str) -> Result<Self> {
         match db {
             Db::ScyllaDb(scylla_db) => Ok(Self::from_scylladb_model(
                 &Self::scylladb_select_by_token(scylla_db, token).await?,
@@ -202,6 +371,23 @@ impl TokenDao {
         }
     }
 
+    async fn db_select_by_previous_token(db: &Db, token: &str) -> Result<Self> {
+        match db {
+            Db::ScyllaDb(scylla_db) => Ok(Self::from_scylladb_model(
+                &Self::scylladb_select_by_previous_token(scylla_db, token).await?,
+            )?),
+            Db::PostgresqlDb(postgres_db) => Ok(Self::from_postgresdb_model(
+                &Self::postgresdb_select_by_previous_token(postgres_db, token).await?,
+            )),
+            Db::MysqlDb(mysql_db) => Ok(Self::from_mysqldb_model(
+                &Self::mysqldb_select_by_previous_token(mysql_db, token).await?,
+            )),
+            Db::SqliteDb(sqlite_db) => Ok(Self::from_sqlitedb_model(
+                &Self::sqlitedb_select_by_previous_token(sqlite_db, token).await?,
+            )),
+        }
+    }
+
     pub async fn db_select_many_by_admin_id(db: &Db, admin_id: &Uuid) -> Result<Vec<Self>> {
         match db {
             Db::ScyllaDb(scylla_db) => {
@@ -244,6 +430,16 @@ impl TokenDao {
         }
     }
 
+    pub async fn db_update_token(&mut self, db: &Db) -> Result<()> {
+        self.updated_at = Utc::now();
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_update_token(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_update_token(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_update_token(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_update_token(self, db).await,
+        }
+    }
+
     pub async fn db_delete(db: &Db, id: &Uuid) -> Result<()> {
         match db {
             Db::ScyllaDb(db) => Self::scylladb_delete(db, id).await,
@@ -272,6 +468,16 @@ impl TokenDao {
             .first_row_typed::<TokenScyllaModel>()?)
     }
 
+    async fn scylladb_select_by_previous_token(
+        db: &ScyllaDb,
+        token: &str,
+    ) -> Result<TokenScyllaModel> {
+        Ok(db
+            .execute(SCYLLA_SELECT_BY_PREVIOUS_TOKEN, [token].as_ref())
+            .await?
+            .first_row_typed::<TokenScyllaModel>()?)
+    }
+
     async fn scylladb_select_many_by_admin_id(
         db: &ScyllaDb,
         admin_id: &Uuid,
@@ -296,6 +502,27 @@ impl TokenDao {
                     Some(expired_at) => Some(ScyllaCqlTimestamp(expired_at.timestamp_millis())),
                     None => None,
                 },
+                &self.allowed_cidrs,
+                &self.scopes,
+                &self.id,
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn scylladb_update_token(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(
+            SCYLLA_UPDATE_TOKEN,
+            &(
+                &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+                &self.token,
+                &self.previous_token,
+                &self
+                    .previous_token_expired_at
+                    .map(|previous_token_expired_at| {
+                        ScyllaCqlTimestamp(previous_token_expired_at.timestamp_millis())
+                    }),
                 &self.id,
             ),
         )
@@ -317,7 +544,11 @@ impl TokenDao {
                 .bind(&self.admin_id)
                 .bind(&self.token)
                 .bind(&sqlx::types::Json(&self.rules))
-                .bind(&self.expired_at),
+                .bind(&self.expired_at)
+                .bind(&self.allowed_cidrs)
+                .bind(&self.scopes)
+                .bind(&self.previous_token)
+                .bind(&self.previous_token_expired_at),
         )
         .await?;
         Ok(())
@@ -338,6 +569,15 @@ impl TokenDao {
             .await?)
     }
 
+    async fn postgresdb_select_by_previous_token(
+        db: &PostgresDb,
+        token: &str,
+    ) -> Result<TokenPostgresModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(POSTGRES_SELECT_BY_PREVIOUS_TOKEN).bind(token))
+            .await?)
+    }
+
     async fn postgresdb_select_many_by_admin_id(
         db: &PostgresDb,
         admin_id: &Uuid,
@@ -353,6 +593,21 @@ impl TokenDao {
                 .bind(&self.updated_at)
                 .bind(&sqlx::types::Json(&self.rules))
                 .bind(&self.expired_at)
+                .bind(&self.allowed_cidrs)
+                .bind(&self.scopes)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_update_token(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_UPDATE_TOKEN)
+                .bind(&self.updated_at)
+                .bind(&self.token)
+                .bind(&self.previous_token)
+                .bind(&self.previous_token_expired_at)
                 .bind(&self.id),
         )
         .await?;
@@ -373,7 +628,11 @@ impl TokenDao {
                 .bind(&self.admin_id)
                 .bind(&self.token)
                 .bind(&sqlx::types::Json(&self.rules))
-                .bind(&self.expired_at),
+                .bind(&self.expired_at)
+                .bind(&self.allowed_cidrs.as_ref().map(sqlx::types::Json))
+                .bind(&self.scopes.as_ref().map(sqlx::types::Json))
+                .bind(&self.previous_token)
+                .bind(&self.previous_token_expired_at),
         )
         .await?;
         Ok(())
@@ -389,6 +648,15 @@ impl TokenDao {
             .await?)
     }
 
+    async fn mysqldb_select_by_previous_token(
+        db: &MysqlDb,
+        token: &str,
+    ) -> Result<TokenMysqlModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(MYSQL_SELECT_BY_PREVIOUS_TOKEN).bind(token))
+            .await?)
+    }
+
     async fn mysqldb_select_many_by_admin_id(
         db: &MysqlDb,
         admin_id: &Uuid,
@@ -404,6 +672,21 @@ impl TokenDao {
                 .bind(&self.updated_at)
                 .bind(&sqlx::types::Json(&self.rules))
                 .bind(&self.expired_at)
+                .bind(&self.allowed_cidrs.as_ref().map(sqlx::types::Json))
+                .bind(&self.scopes.as_ref().map(sqlx::types::Json))
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_update_token(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_UPDATE_TOKEN)
+                .bind(&self.updated_at)
+                .bind(&self.token)
+                .bind(&self.previous_token)
+                .bind(&self.previous_token_expired_at)
                 .bind(&self.id),
         )
         .await?;
@@ -424,7 +707,11 @@ impl TokenDao {
                 .bind(&self.admin_id)
                 .bind(&self.token)
                 .bind(&sqlx::types::Json(&self.rules))
-                .bind(&self.expired_at),
+                .bind(&self.expired_at)
+                .bind(&self.allowed_cidrs.as_ref().map(sqlx::types::Json))
+                .bind(&self.scopes.as_ref().map(sqlx::types::Json))
+                .bind(&self.previous_token)
+                .bind(&self.previous_token_expired_at),
         )
         .await?;
         Ok(())
@@ -440,6 +727,15 @@ impl TokenDao {
             .await?)
     }
 
+    async fn sqlitedb_select_by_previous_token(
+        db: &SqliteDb,
+        token: &str,
+    ) -> Result<TokenSqliteModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(SQLITE_SELECT_BY_PREVIOUS_TOKEN).bind(token))
+            .await?)
+    }
+
     async fn sqlitedb_select_many_by_admin_id(
         db: &SqliteDb,
         admin_id: &Uuid,
@@ -455,6 +751,21 @@ impl TokenDao {
                 .bind(&self.updated_at)
                 .bind(&sqlx::types::Json(&self.rules))
                 .bind(&self.expired_at)
+                .bind(&self.allowed_cidrs.as_ref().map(sqlx::types::Json))
+                .bind(&self.scopes.as_ref().map(sqlx::types::Json))
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_update_token(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_UPDATE_TOKEN)
+                .bind(&self.updated_at)
+                .bind(&self.token)
+                .bind(&self.previous_token)
+                .bind(&self.previous_token_expired_at)
                 .bind(&self.id),
         )
         .await?;
@@ -488,6 +799,15 @@ impl TokenDao {
                 )?),
                 None => None,
             },
+            allowed_cidrs: model.allowed_cidrs().clone(),
+            scopes: model.scopes().clone(),
+            previous_token: model.previous_token().clone(),
+            previous_token_expired_at: match &model.previous_token_expired_at() {
+                Some(previous_token_expired_at) => Some(
+                    conversion::scylla_cql_timestamp_to_datetime_utc(previous_token_expired_at)?,
+                ),
+                None => None,
+            },
         })
     }
 
@@ -508,6 +828,14 @@ impl TokenDao {
                 Some(expired_at) => Some(ScyllaCqlTimestamp(expired_at.timestamp_millis())),
                 None => None,
             },
+            &self.allowed_cidrs,
+            &self.scopes,
+            &self.previous_token,
+            &self
+                .previous_token_expired_at
+                .map(|previous_token_expired_at| {
+                    ScyllaCqlTimestamp(previous_token_expired_at.timestamp_millis())
+                }),
         )
     }
 
@@ -529,6 +857,10 @@ impl TokenDao {
                 })
                 .collect(),
             expired_at: *model.expired_at(),
+            allowed_cidrs: model.allowed_cidrs().clone(),
+            scopes: model.scopes().clone(),
+            previous_token: model.previous_token().clone(),
+            previous_token_expired_at: *model.previous_token_expired_at(),
         }
     }
 
@@ -547,6 +879,10 @@ impl TokenDao {
                 })
                 .collect(),
             expired_at: *model.expired_at(),
+            allowed_cidrs: model.allowed_cidrs().as_ref().map(|cidrs| cidrs.0.clone()),
+            scopes: model.scopes().as_ref().map(|scopes| scopes.0.clone()),
+            previous_token: model.previous_token().clone(),
+            previous_token_expired_at: *model.previous_token_expired_at(),
         }
     }
 
@@ -568,17 +904,25 @@ impl TokenDao {
                 Some(expired_at) => Some(expired_at.0),
                 None => None,
             },
+            allowed_cidrs: model.allowed_cidrs().as_ref().map(|cidrs| cidrs.0.clone()),
+            scopes: model.scopes().as_ref().map(|scopes| scopes.0.clone()),
+            previous_token: model.previous_token().clone(),
+            previous_token_expired_at: model
+                .previous_token_expired_at()
+                .as_ref()
+                .map(|previous_token_expired_at| previous_token_expired_at.0),
         }
     }
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct TokenRuleMethod {
     find_one: bool,
     find_many: bool,
     insert: bool,
     update: bool,
     delete: bool,
+    condition: Option<String>,
 }
 
 impl TokenRuleMethod {
@@ -588,6 +932,7 @@ impl TokenRuleMethod {
         insert: &bool,
         update: &bool,
         delete: &bool,
+        condition: &Option<String>,
     ) -> Self {
         Self {
             find_one: *find_one,
@@ -595,6 +940,7 @@ impl TokenRuleMethod {
             insert: *insert,
             update: *update,
             delete: *delete,
+            condition: condition.clone(),
         }
     }
 
@@ -618,6 +964,14 @@ impl TokenRuleMethod {
         &self.delete
     }
 
+    pub fn condition(&self) -> &Option<String> {
+        &self.condition
+    }
+
+    pub fn is_write(&self) -> bool {
+        self.insert || self.update || self.delete
+    }
+
     pub fn from_scylladb_model(model: &TokenRuleMethodScyllaModel) -> Self {
         Self {
             find_one: *model.find_one(),
@@ -625,6 +979,7 @@ impl TokenRuleMethod {
             insert: *model.insert(),
             update: *model.update(),
             delete: *model.delete(),
+            condition: model.condition().clone(),
         }
     }
 
@@ -635,6 +990,7 @@ impl TokenRuleMethod {
             &self.insert,
             &self.update,
             &self.delete,
+            &self.condition,
         )
     }
 
@@ -645,6 +1001,7 @@ impl TokenRuleMethod {
             insert: *model.insert(),
             update: *model.update(),
             delete: *model.delete(),
+            condition: model.condition().clone(),
         }
     }
 
@@ -655,6 +1012,7 @@ impl TokenRuleMethod {
             insert: *model.insert(),
             update: *model.update(),
             delete: *model.delete(),
+            condition: model.condition().clone(),
         }
     }
 
@@ -665,6 +1023,170 @@ impl TokenRuleMethod {
             insert: *model.insert(),
             update: *model.update(),
             delete: *model.delete(),
+            condition: model.condition().clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ahash::{HashMap, HashMapExt};
+    use chrono::{Duration, Utc};
+    use serde_json::{json, Map, Value};
+    use uuid::Uuid;
+
+    use super::{TokenDao, TokenRuleMethod};
+    use crate::expression::Filter;
+
+    fn context(owner_id: &str) -> Map<String, Value> {
+        json!({
+            "request": { "method": "update" },
+            "token": { "user_id": "u1" },
+            "record": { "owner_id": owner_id, "status": "draft" },
+        })
+        .as_object()
+        .cloned()
+        .unwrap()
+    }
+
+    #[test]
+    fn condition_denies_access() {
+        let collection_id = Uuid::now_v7();
+        let mut rules = HashMap::new();
+        rules.insert(
+            collection_id,
+            TokenRuleMethod::new(
+                &true,
+                &true,
+                &true,
+                &true,
+                &true,
+                &Some("record.owner_id == token.user_id && record.status != 'locked'".to_owned()),
+            ),
+        );
+        let token_data = TokenDao::new(&Uuid::now_v7(), &16, &rules, &None, &None, &None);
+
+        assert!(token_data.is_allow_update(&collection_id));
+        assert!(token_data.has_condition(&collection_id));
+        assert!(token_data
+            .is_allow_condition(&collection_id, &context("u1"))
+            .unwrap());
+        assert!(!token_data
+            .is_allow_condition(&collection_id, &context("u2"))
+            .unwrap());
+        assert!(token_data
+            .is_allow_condition(&Uuid::now_v7(), &context("u2"))
+            .unwrap());
+    }
+
+    #[test]
+    fn condition_filters_many_records() {
+        let collection_id = Uuid::now_v7();
+        let mut rules = HashMap::new();
+        rules.insert(
+            collection_id,
+            TokenRuleMethod::new(
+                &true,
+                &true,
+                &false,
+                &false,
+                &false,
+                &Some("record.owner_id == token.user_id".to_owned()),
+            ),
+        );
+        let token_data = TokenDao::new(&Uuid::now_v7(), &16, &rules, &None, &None, &None);
+
+        let mut context = context("u2");
+        context.remove("record");
+        assert_eq!(
+            token_data
+                .condition_filter(&collection_id, &context)
+                .unwrap(),
+            Some(Filter::Compare(
+                "owner_id".to_owned(),
+                "=",
+                Value::from("u1")
+            ))
+        );
+        assert_eq!(
+            token_data
+                .condition_filter(&Uuid::now_v7(), &context)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn allowed_cidrs_restrict_the_caller_ip() {
+        assert!(
+            TokenDao::check_allowed_cidrs(&["10.0.0.0/8".to_owned(), "::1".to_owned()]).is_ok()
+        );
+        for cidr in ["10.0.0.0/33", "10.0.0", "fe80::/129", ""] {
+            assert!(
+                TokenDao::check_allowed_cidrs(&[cidr.to_owned()]).is_err(),
+                "{cidr}"
+            );
+        }
+
+        let ip = |ip: &str| Some(ip.parse().unwrap());
+        let mut token_data =
+            TokenDao::new(&Uuid::now_v7(), &16, &HashMap::new(), &None, &None, &None);
+        assert!(token_data.is_allow_ip(&ip("203.0.113.7")));
+        assert!(token_data.is_allow_ip(&None));
+
+        token_data.set_allowed_cidrs(&Some(vec![
+            "10.1.0.0/16".to_owned(),
+            "2001:db8::/32".to_owned(),
+            "192.0.2.1".to_owned(),
+        ]));
+        assert!(token_data.is_allow_ip(&ip("10.1.200.3")));
+        assert!(token_data.is_allow_ip(&ip("::ffff:10.1.0.1")));
+        assert!(token_data.is_allow_ip(&ip("2001:db8:1::1")));
+        assert!(token_data.is_allow_ip(&ip("192.0.2.1")));
+        assert!(!token_data.is_allow_ip(&ip("10.2.0.1")));
+        assert!(!token_data.is_allow_ip(&ip("192.0.2.2")));
+        assert!(!token_data.is_allow_ip(&ip("2001:db9::1")));
+        assert!(!token_data.is_allow_ip(&None));
+    }
+
+    #[test]
+    fn scopes_limit_what_the_token_can_do() {
+        assert!(
+            TokenDao::check_scopes(&["records:read".to_owned(), "users:auth".to_owned()]).is_ok()
+        );
+        assert!(TokenDao::check_scopes(&["records:admin".to_owned()]).is_err());
+
+        let mut token_data =
+            TokenDao::new(&Uuid::now_v7(), &16, &HashMap::new(), &None, &None, &None);
+        assert!(token_data.is_allow_scope("records:write"));
+
+        token_data.set_scopes(&Some(vec!["records:read".to_owned()]));
+        assert!(token_data.is_allow_scope("records:read"));
+        assert!(!token_data.is_allow_scope("records:write"));
+
+        token_data.set_scopes(&Some(Vec::new()));
+        assert!(!token_data.is_allow_scope("records:read"));
+    }
+
+    #[test]
+    fn rotate_keeps_the_previous_token_for_the_grace_period() {
+        let mut token_data =
+            TokenDao::new(&Uuid::now_v7(), &16, &HashMap::new(), &None, &None, &None);
+        let original = token_data.token().to_owned();
+
+        token_data.rotate(&24, &60);
+        assert_eq!(token_data.token().len(), 24);
+        assert_ne!(token_data.token(), original);
+        assert_eq!(
+            token_data.previous_token().as_deref(),
+            Some(original.as_str())
+        );
+        let expired_at = token_data.previous_token_expired_at().unwrap();
+        assert!(expired_at > Utc::now() + Duration::seconds(50));
+        assert!(expired_at <= Utc::now() + Duration::seconds(60));
+
+        token_data.rotate(&16, &0);
+        assert!(token_data.previous_token().is_none());
+        assert!(token_data.previous_token_expired_at().is_none());
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ahash::{HashMap, HashMapExt};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::{
+    collection::CollectionDao,
+    project_usage::ProjectUsageDao,
+    record::{RecordDao, RecordFilters},
+    Db,
+};
+
+// Counts requests per project in memory until the usage meter adds them to the stored totals
+#[derive(Clone, Default)]
+pub struct RequestCounter {
+    counts: Arc<Mutex<HashMap<Uuid, i64>>>,
+}
+
+impl RequestCounter {
+    pub fn increment(&self, project_id: &Uuid) {
+        *self.counts.lock().unwrap().entry(*project_id).or_insert(0) += 1;
+    }
+
+    fn take(&self) -> HashMap<Uuid, i64> {
+        std::mem::take(&mut *self.counts.lock().unwrap())
+    }
+
+    // Puts counts back when they couldn't be stored so the next run retries them
+    fn restore(&self, project_id: &Uuid, count: &i64) {
+        *self.counts.lock().unwrap().entry(*project_id).or_insert(0) += count;
+    }
+}
+
+pub struct UsageMeter {
+    db: Arc<Db>,
+    interval: Duration,
+    request_counter: RequestCounter,
+}
+
+impl UsageMeter {
+    pub fn new(db: Arc<Db>, interval: &Duration, request_counter: RequestCounter) -> Self {
+        Self {
+            db,
+            interval: *interval,
+            request_counter,
+        }
+    }
+
+    // Runs on the caller's task rather than a spawned one because record count futures aren't Send
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        hb_log::info(Some("💫"), "UsageMeter: Running component");
+
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            let is_shutdown = tokio::select! {
+                _ = interval.tick() => false,
+                _ = shutdown.wait_for(|shutdown| *shutdown) => true,
+            };
+
+            // Requests counted since the last run are stored once more before stopping
+            if let Err(err) = self.aggregate(!is_shutdown).await {
+                hb_log::error(None, &err);
+            }
+
+            if is_shutdown {
+                break;
+            }
+        }
+
+        hb_log::info(Some("👋"), "UsageMeter: Stopped component");
+        Ok(())
+    }
+
+    async fn aggregate(&self, with_storage: bool) -> Result<()> {
+        let period = ProjectUsageDao::current_period();
+        let mut request_counts = self.request_counter.take();
+
+        let mut collection_ids = HashMap::<Uuid, Vec<Uuid>>::new();
+        if with_storage {
+            for collection_data in CollectionDao::db_select_many(&self.db).await? {
+                collection_ids
+                    .entry(*collection_data.project_id())
+                    .or_default()
+                    .push(*collection_data.id());
+            }
+        }
+        for project_id in request_counts.keys() {
+            collection_ids.entry(*project_id).or_default();
+        }
+
+        for (project_id, collection_ids) in &collection_ids {
+            let request_count = request_counts.remove(project_id).unwrap_or(0);
+            if let Err(err) = self
+                .aggregate_project(
+                    project_id,
+                    collection_ids,
+                    &period,
+                    &request_count,
+                    with_storage,
+                )
+                .await
+            {
+                self.request_counter.restore(project_id, &request_count);
+                hb_log::error(None, &err);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn aggregate_project(
+        &self,
+        project_id: &Uuid,
+        collection_ids: &[Uuid],
+        period: &DateTime<Utc>,
+        request_count: &i64,
+        with_storage: bool,
+    ) -> Result<()> {
+        let mut project_usage_data =
+            match ProjectUsageDao::db_select_many_by_project_id(&self.db, project_id, &1)
+                .await?
+                .pop()
+            {
+                Some(data) if data.period() == period => data,
+                Some(data) => {
+                    // A new period starts with the last known storage until it is measured again
+                    let mut project_usage_data = ProjectUsageDao::new(project_id, period);
+                    project_usage_data.set_storage(data.record_count(), data.storage_bytes());
+                    project_usage_data
+                }
+                None => ProjectUsageDao::new(project_id, period),
+            };
+
+        project_usage_data.add_requests(request_count);
+
+        if with_storage {
+            let mut record_count = 0;
+            let mut storage_bytes = 0;
+            for collection_id in collection_ids {
+                record_count +=
+                    RecordDao::db_count(&self.db, collection_id, &RecordFilters::new(&Vec::new()))
+                        .await?;
+                storage_bytes += RecordDao::db_table_size(&self.db, collection_id).await?;
+            }
+            project_usage_data.set_storage(&record_count, &storage_bytes);
+        }
+
+        project_usage_data.db_upsert(&self.db).await
+    }
+}
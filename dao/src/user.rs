@@ -0,0 +1,588 @@
+use std::cmp::Reverse;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::user::UserModel as UserMysqlModel,
+    query::user::{
+        DELETE as MYSQL_DELETE, INSERT as MYSQL_INSERT, SELECT as MYSQL_SELECT,
+        SELECT_BY_PROJECT_ID_AND_EMAIL as MYSQL_SELECT_BY_PROJECT_ID_AND_EMAIL,
+        SELECT_MANY_BY_PROJECT_ID as MYSQL_SELECT_MANY_BY_PROJECT_ID, UPDATE as MYSQL_UPDATE,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::user::UserModel as UserPostgresModel,
+    query::user::{
+        DELETE as POSTGRES_DELETE, INSERT as POSTGRES_INSERT, SELECT as POSTGRES_SELECT,
+        SELECT_BY_PROJECT_ID_AND_EMAIL as POSTGRES_SELECT_BY_PROJECT_ID_AND_EMAIL,
+        SELECT_MANY_BY_PROJECT_ID as POSTGRES_SELECT_MANY_BY_PROJECT_ID, UPDATE as POSTGRES_UPDATE,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::user::UserModel as UserScyllaModel,
+    query::user::{
+        DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
+        SELECT_BY_PROJECT_ID_AND_EMAIL as SCYLLA_SELECT_BY_PROJECT_ID_AND_EMAIL,
+        SELECT_MANY_BY_PROJECT_ID as SCYLLA_SELECT_MANY_BY_PROJECT_ID, UPDATE as SCYLLA_UPDATE,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::user::UserModel as UserSqliteModel,
+    query::user::{
+        DELETE as SQLITE_DELETE, INSERT as SQLITE_INSERT, SELECT as SQLITE_SELECT,
+        SELECT_BY_PROJECT_ID_AND_EMAIL as SQLITE_SELECT_BY_PROJECT_ID_AND_EMAIL,
+        SELECT_MANY_BY_PROJECT_ID as SQLITE_SELECT_MANY_BY_PROJECT_ID, UPDATE as SQLITE_UPDATE,
+    },
+};
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{util::conversion, Db};
+
+#[derive(Serialize, Deserialize)]
+pub struct UserDao {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    project_id: Uuid,
+    email: Option<String>,
+    password_hash: Option<String>,
+}
+
+impl UserDao {
+    pub fn new(project_id: &Uuid, email: &str, password_hash: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::now_v7(),
+            created_at: now,
+            updated_at: now,
+            project_id: *project_id,
+            email: Some(email.to_owned()),
+            password_hash: Some(password_hash.to_owned()),
+        }
+    }
+
+    pub fn new_anonymous(project_id: &Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::now_v7(),
+            created_at: now,
+            updated_at: now,
+            project_id: *project_id,
+            email: None,
+            password_hash: None,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn email(&self) -> &Option<String> {
+        &self.email
+    }
+
+    pub fn password_hash(&self) -> &Option<String> {
+        &self.password_hash
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        self.email.is_none()
+    }
+
+    pub fn set_email(&mut self, email: &str) {
+        self.email = Some(email.to_owned());
+    }
+
+    pub fn set_password_hash(&mut self, password_hash: &str) {
+        self.password_hash = Some(password_hash.to_owned());
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert(self, db).await,
+        }
+    }
+
+    pub async fn db_select(db: &Db, id: &Uuid) -> Result<Self> {
+        match db {
+            Db::ScyllaDb(db) => Self::from_scylladb_model(&Self::scylladb_select(db, id).await?),
+            Db::PostgresqlDb(db) => Ok(Self::from_postgresdb_model(
+                &Self::postgresdb_select(db, id).await?,
+            )),
+            Db::MysqlDb(db) => Ok(Self::from_mysqldb_model(
+                &Self::mysqldb_select(db, id).await?,
+            )),
+            Db::SqliteDb(db) => Ok(Self::from_sqlitedb_model(
+                &Self::sqlitedb_select(db, id).await?,
+            )),
+        }
+    }
+
+    pub async fn db_select_by_project_id_and_email(
+        db: &Db,
+        project_id: &Uuid,
+        email: &str,
+    ) -> Result<Self> {
+        match db {
+            Db::ScyllaDb(db) => Self::from_scylladb_model(
+                &Self::scylladb_select_by_project_id_and_email(db, project_id, email).await?,
+            ),
+            Db::PostgresqlDb(db) => Ok(Self::from_postgresdb_model(
+                &Self::postgresdb_select_by_project_id_and_email(db, project_id, email).await?,
+            )),
+            Db::MysqlDb(db) => Ok(Self::from_mysqldb_model(
+                &Self::mysqldb_select_by_project_id_and_email(db, project_id, email).await?,
+            )),
+            Db::SqliteDb(db) => Ok(Self::from_sqlitedb_model(
+                &Self::sqlitedb_select_by_project_id_and_email(db, project_id, email).await?,
+            )),
+        }
+    }
+
+    pub async fn db_select_many_by_project_id(db: &Db, project_id: &Uuid) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut users_data = Vec::new();
+                let users = Self::scylladb_select_many_by_project_id(db, project_id).await?;
+                for user in users {
+                    users_data.push(Self::from_scylladb_model(&user?)?);
+                }
+                users_data.sort_by_key(|user| Reverse(user.created_at));
+                Ok(users_data)
+            }
+            Db::PostgresqlDb(db) => Ok(Self::postgresdb_select_many_by_project_id(db, project_id)
+                .await?
+                .iter()
+                .map(Self::from_postgresdb_model)
+                .collect()),
+            Db::MysqlDb(db) => Ok(Self::mysqldb_select_many_by_project_id(db, project_id)
+                .await?
+                .iter()
+                .map(Self::from_mysqldb_model)
+                .collect()),
+            Db::SqliteDb(db) => Ok(Self::sqlitedb_select_many_by_project_id(db, project_id)
+                .await?
+                .iter()
+                .map(Self::from_sqlitedb_model)
+                .collect()),
+        }
+    }
+
+    pub async fn db_update(&mut self, db: &Db) -> Result<()> {
+        self.updated_at = Utc::now();
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_update(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_update(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_update(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_update(self, db).await,
+        }
+    }
+
+    pub async fn db_delete(db: &Db, id: &Uuid) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_delete(db, id).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_delete(db, id).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete(db, id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete(db, id).await,
+        }
+    }
+
+    async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        Ok(())
+    }
+
+    async fn scylladb_select(db: &ScyllaDb, id: &Uuid) -> Result<UserScyllaModel> {
+        Ok(db
+            .execute(SCYLLA_SELECT, [id].as_ref())
+            .await?
+            .first_row_typed::<UserScyllaModel>()?)
+    }
+
+    async fn scylladb_select_by_project_id_and_email(
+        db: &ScyllaDb,
+        project_id: &Uuid,
+        email: &str,
+    ) -> Result<UserScyllaModel> {
+        Ok(db
+            .execute(SCYLLA_SELECT_BY_PROJECT_ID_AND_EMAIL, &(project_id, email))
+            .await?
+            .first_row_typed::<UserScyllaModel>()?)
+    }
+
+    async fn scylladb_select_many_by_project_id(
+        db: &ScyllaDb,
+        project_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<UserScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_PROJECT_ID, [project_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn scylladb_update(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(
+            SCYLLA_UPDATE,
+            &(
+                &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+                &self.email,
+                &self.password_hash,
+                &self.id,
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn scylladb_delete(db: &ScyllaDb, id: &Uuid) -> Result<()> {
+        db.execute(SCYLLA_DELETE, [id].as_ref()).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.email)
+                .bind(&self.password_hash),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select(db: &PostgresDb, id: &Uuid) -> Result<UserPostgresModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(POSTGRES_SELECT).bind(id))
+            .await?)
+    }
+
+    async fn postgresdb_select_by_project_id_and_email(
+        db: &PostgresDb,
+        project_id: &Uuid,
+        email: &str,
+    ) -> Result<UserPostgresModel> {
+        Ok(db
+            .fetch_one(
+                sqlx::query_as(POSTGRES_SELECT_BY_PROJECT_ID_AND_EMAIL)
+                    .bind(project_id)
+                    .bind(email),
+            )
+            .await?)
+    }
+
+    async fn postgresdb_select_many_by_project_id(
+        db: &PostgresDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<UserPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    async fn postgresdb_update(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_UPDATE)
+                .bind(&self.updated_at)
+                .bind(&self.email)
+                .bind(&self.password_hash)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_delete(db: &PostgresDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(POSTGRES_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.email)
+                .bind(&self.password_hash),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select(db: &MysqlDb, id: &Uuid) -> Result<UserMysqlModel> {
+        Ok(db.fetch_one(sqlx::query_as(MYSQL_SELECT).bind(id)).await?)
+    }
+
+    async fn mysqldb_select_by_project_id_and_email(
+        db: &MysqlDb,
+        project_id: &Uuid,
+        email: &str,
+    ) -> Result<UserMysqlModel> {
+        Ok(db
+            .fetch_one(
+                sqlx::query_as(MYSQL_SELECT_BY_PROJECT_ID_AND_EMAIL)
+                    .bind(project_id)
+                    .bind(email),
+            )
+            .await?)
+    }
+
+    async fn mysqldb_select_many_by_project_id(
+        db: &MysqlDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<UserMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    async fn mysqldb_update(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_UPDATE)
+                .bind(&self.updated_at)
+                .bind(&self.email)
+                .bind(&self.password_hash)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_delete(db: &MysqlDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(MYSQL_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.project_id)
+                .bind(&self.email)
+                .bind(&self.password_hash),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select(db: &SqliteDb, id: &Uuid) -> Result<UserSqliteModel> {
+        Ok(db.fetch_one(sqlx::query_as(SQLITE_SELECT).bind(id)).await?)
+    }
+
+    async fn sqlitedb_select_by_project_id_and_email(
+        db: &SqliteDb,
+        project_id: &Uuid,
+        email: &str,
+    ) -> Result<UserSqliteModel> {
+        Ok(db
+            .fetch_one(
+                sqlx::query_as(SQLITE_SELECT_BY_PROJECT_ID_AND_EMAIL)
+                    .bind(project_id)
+                    .bind(email),
+            )
+            .await?)
+    }
+
+    async fn sqlitedb_select_many_by_project_id(
+        db: &SqliteDb,
+        project_id: &Uuid,
+    ) -> Result<Vec<UserSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_PROJECT_ID).bind(project_id))
+            .await?)
+    }
+
+    async fn sqlitedb_update(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_UPDATE)
+                .bind(&self.updated_at)
+                .bind(&self.email)
+                .bind(&self.password_hash)
+                .bind(&self.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_delete(db: &SqliteDb, id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(SQLITE_DELETE).bind(id)).await?;
+        Ok(())
+    }
+
+    fn from_scylladb_model(model: &UserScyllaModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.created_at())?,
+            updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
+            project_id: *model.project_id(),
+            email: model.email().clone(),
+            password_hash: model.password_hash().clone(),
+        })
+    }
+
+    fn to_scylladb_model(&self) -> UserScyllaModel {
+        UserScyllaModel::new(
+            &self.id,
+            &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+            &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+            &self.project_id,
+            &self.email,
+            &self.password_hash,
+        )
+    }
+
+    fn from_postgresdb_model(model: &UserPostgresModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            email: model.email().clone(),
+            password_hash: model.password_hash().clone(),
+        }
+    }
+
+    fn from_mysqldb_model(model: &UserMysqlModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            email: model.email().clone(),
+            password_hash: model.password_hash().clone(),
+        }
+    }
+
+    fn from_sqlitedb_model(model: &UserSqliteModel) -> Self {
+        Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            project_id: *model.project_id(),
+            email: model.email().clone(),
+            password_hash: model.password_hash().clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hb_db_sqlite::db::SqliteDb;
+    use uuid::Uuid;
+
+    use super::UserDao;
+    use crate::{user_password_reset::UserPasswordResetDao, Db};
+
+    async fn db() -> Db {
+        Db::SqliteDb(SqliteDb::new(":memory:", &1, &600, &600, &None).await)
+    }
+
+    #[tokio::test]
+    async fn users_are_scoped_to_their_project() {
+        let db = db().await;
+        let (project_id, other_project_id) = (Uuid::now_v7(), Uuid::now_v7());
+        let user_data = UserDao::new(&project_id, "ada@example.com", "hash");
+        user_data.db_insert(&db).await.unwrap();
+
+        let selected =
+            UserDao::db_select_by_project_id_and_email(&db, &project_id, "ada@example.com")
+                .await
+                .unwrap();
+        assert_eq!(selected.id(), user_data.id());
+        assert!(!selected.is_anonymous());
+        assert!(UserDao::db_select_by_project_id_and_email(
+            &db,
+            &other_project_id,
+            "ada@example.com"
+        )
+        .await
+        .is_err());
+        assert!(
+            UserDao::db_select_many_by_project_id(&db, &other_project_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn password_reset_codes_are_dropped_together() {
+        let db = db().await;
+        let user_data = UserDao::new(&Uuid::now_v7(), "ada@example.com", "hash");
+        user_data.db_insert(&db).await.unwrap();
+        for _ in 0..2 {
+            UserPasswordResetDao::new(user_data.id())
+                .db_insert(&db)
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            UserPasswordResetDao::db_select_many_by_user_id(&db, user_data.id())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        UserPasswordResetDao::db_delete_many_by_user_id(&db, user_data.id())
+            .await
+            .unwrap();
+        assert!(
+            UserPasswordResetDao::db_select_many_by_user_id(&db, user_data.id())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn upgrade_keeps_the_anonymous_user_id() {
+        let db = db().await;
+        let project_id = Uuid::now_v7();
+        let mut user_data = UserDao::new_anonymous(&project_id);
+        assert!(user_data.is_anonymous());
+        user_data.db_insert(&db).await.unwrap();
+
+        user_data.set_email("ada@example.com");
+        user_data.set_password_hash("hash");
+        user_data.db_update(&db).await.unwrap();
+
+        let selected =
+            UserDao::db_select_by_project_id_and_email(&db, &project_id, "ada@example.com")
+                .await
+                .unwrap();
+        assert_eq!(selected.id(), user_data.id());
+        assert_eq!(selected.password_hash().as_deref(), Some("hash"));
+        assert!(!selected.is_anonymous());
+    }
+}
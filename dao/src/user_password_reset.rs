@@ -0,0 +1,411 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hb_db_mysql::{
+    db::MysqlDb,
+    model::user_password_reset::UserPasswordResetModel as UserPasswordResetMysqlModel,
+    query::user_password_reset::{
+        DELETE_EXPIRED as MYSQL_DELETE_EXPIRED,
+        DELETE_MANY_BY_USER_ID as MYSQL_DELETE_MANY_BY_USER_ID, INSERT as MYSQL_INSERT,
+        SELECT as MYSQL_SELECT, SELECT_MANY_BY_USER_ID as MYSQL_SELECT_MANY_BY_USER_ID,
+    },
+};
+use hb_db_postgresql::{
+    db::PostgresDb,
+    model::user_password_reset::UserPasswordResetModel as UserPasswordResetPostgresModel,
+    query::user_password_reset::{
+        DELETE_EXPIRED as POSTGRES_DELETE_EXPIRED,
+        DELETE_MANY_BY_USER_ID as POSTGRES_DELETE_MANY_BY_USER_ID, INSERT as POSTGRES_INSERT,
+        SELECT as POSTGRES_SELECT, SELECT_MANY_BY_USER_ID as POSTGRES_SELECT_MANY_BY_USER_ID,
+    },
+};
+use hb_db_scylladb::{
+    db::ScyllaDb,
+    model::user_password_reset::UserPasswordResetModel as UserPasswordResetScyllaModel,
+    query::user_password_reset::{
+        DELETE as SCYLLA_DELETE, INSERT as SCYLLA_INSERT, SELECT as SCYLLA_SELECT,
+        SELECT_MANY_BY_USER_ID as SCYLLA_SELECT_MANY_BY_USER_ID,
+    },
+};
+use hb_db_sqlite::{
+    db::SqliteDb,
+    model::user_password_reset::UserPasswordResetModel as UserPasswordResetSqliteModel,
+    query::user_password_reset::{
+        DELETE_EXPIRED as SQLITE_DELETE_EXPIRED,
+        DELETE_MANY_BY_USER_ID as SQLITE_DELETE_MANY_BY_USER_ID, INSERT as SQLITE_INSERT,
+        SELECT as SQLITE_SELECT, SELECT_MANY_BY_USER_ID as SQLITE_SELECT_MANY_BY_USER_ID,
+    },
+};
+use rand::{thread_rng, Rng};
+use scylla::{
+    frame::value::CqlTimestamp as ScyllaCqlTimestamp,
+    transport::session::TypedRowIter as ScyllaTypedRowIter,
+};
+use uuid::Uuid;
+
+use crate::{util::conversion, Db};
+
+pub struct UserPasswordResetDao {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    user_id: Uuid,
+    code: String,
+}
+
+impl UserPasswordResetDao {
+    pub fn new(user_id: &Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::now_v7(),
+            created_at: now,
+            updated_at: now,
+            user_id: *user_id,
+            code: thread_rng().gen_range(100000..=999999).to_string(),
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub async fn db_insert(&self, db: &Db) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => Self::scylladb_insert(self, db).await,
+            Db::PostgresqlDb(db) => Self::postgresdb_insert(self, db).await,
+            Db::MysqlDb(db) => Self::mysqldb_insert(self, db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_insert(self, db).await,
+        }
+    }
+
+    pub async fn db_select(db: &Db, id: &Uuid) -> Result<Self> {
+        match db {
+            Db::ScyllaDb(db) => Ok(Self::from_scylladb_model(
+                &Self::scylladb_select(db, id).await?,
+            )?),
+            Db::PostgresqlDb(db) => Ok(Self::from_postgresdb_model(
+                &Self::postgresdb_select(db, id).await?,
+            )?),
+            Db::MysqlDb(db) => Ok(Self::from_mysqldb_model(
+                &Self::mysqldb_select(db, id).await?,
+            )?),
+            Db::SqliteDb(db) => Ok(Self::from_sqlitedb_model(
+                &Self::sqlitedb_select(db, id).await?,
+            )?),
+        }
+    }
+
+    pub async fn db_select_many_by_user_id(db: &Db, user_id: &Uuid) -> Result<Vec<Self>> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let mut password_resets_data = Vec::new();
+                let password_resets = Self::scylladb_select_many_by_user_id(db, user_id).await?;
+                for password_reset in password_resets {
+                    password_resets_data.push(Self::from_scylladb_model(&password_reset?)?);
+                }
+                Ok(password_resets_data)
+            }
+            Db::PostgresqlDb(db) => Self::postgresdb_select_many_by_user_id(db, user_id)
+                .await?
+                .iter()
+                .map(Self::from_postgresdb_model)
+                .collect(),
+            Db::MysqlDb(db) => Self::mysqldb_select_many_by_user_id(db, user_id)
+                .await?
+                .iter()
+                .map(Self::from_mysqldb_model)
+                .collect(),
+            Db::SqliteDb(db) => Self::sqlitedb_select_many_by_user_id(db, user_id)
+                .await?
+                .iter()
+                .map(Self::from_sqlitedb_model)
+                .collect(),
+        }
+    }
+
+    // Invalidates every code issued to the user, whether used, pending, or expired
+    pub async fn db_delete_many_by_user_id(db: &Db, user_id: &Uuid) -> Result<()> {
+        match db {
+            Db::ScyllaDb(db) => {
+                let password_resets = Self::scylladb_select_many_by_user_id(db, user_id).await?;
+                for password_reset in password_resets {
+                    Self::scylladb_delete(db, password_reset?.id()).await?;
+                }
+                Ok(())
+            }
+            Db::PostgresqlDb(db) => Self::postgresdb_delete_many_by_user_id(db, user_id).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete_many_by_user_id(db, user_id).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete_many_by_user_id(db, user_id).await,
+        }
+    }
+
+    pub async fn db_delete_expired(db: &Db) -> Result<u64> {
+        match db {
+            // ScyllaDB expires password resets itself through the table's default TTL
+            Db::ScyllaDb(_) => Ok(0),
+            Db::PostgresqlDb(db) => Self::postgresdb_delete_expired(db).await,
+            Db::MysqlDb(db) => Self::mysqldb_delete_expired(db).await,
+            Db::SqliteDb(db) => Self::sqlitedb_delete_expired(db).await,
+        }
+    }
+
+    async fn scylladb_insert(&self, db: &ScyllaDb) -> Result<()> {
+        db.execute(SCYLLA_INSERT, &self.to_scylladb_model()).await?;
+        Ok(())
+    }
+
+    async fn scylladb_select(db: &ScyllaDb, id: &Uuid) -> Result<UserPasswordResetScyllaModel> {
+        Ok(db
+            .execute(SCYLLA_SELECT, [id].as_ref())
+            .await?
+            .first_row_typed::<UserPasswordResetScyllaModel>()?)
+    }
+
+    async fn scylladb_select_many_by_user_id(
+        db: &ScyllaDb,
+        user_id: &Uuid,
+    ) -> Result<ScyllaTypedRowIter<UserPasswordResetScyllaModel>> {
+        Ok(db
+            .execute(SCYLLA_SELECT_MANY_BY_USER_ID, [user_id].as_ref())
+            .await?
+            .rows_typed()?)
+    }
+
+    async fn scylladb_delete(db: &ScyllaDb, id: &Uuid) -> Result<()> {
+        db.execute(SCYLLA_DELETE, [id].as_ref()).await?;
+        Ok(())
+    }
+
+    async fn postgresdb_insert(&self, db: &PostgresDb) -> Result<()> {
+        db.execute(
+            sqlx::query(POSTGRES_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.user_id)
+                .bind(&self.code),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_select(
+        db: &PostgresDb,
+        id: &Uuid,
+    ) -> Result<UserPasswordResetPostgresModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(POSTGRES_SELECT).bind(id).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_reset_password_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?)
+    }
+
+    async fn postgresdb_select_many_by_user_id(
+        db: &PostgresDb,
+        user_id: &Uuid,
+    ) -> Result<Vec<UserPasswordResetPostgresModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(POSTGRES_SELECT_MANY_BY_USER_ID).bind(user_id))
+            .await?)
+    }
+
+    async fn postgresdb_delete_many_by_user_id(db: &PostgresDb, user_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(POSTGRES_DELETE_MANY_BY_USER_ID).bind(user_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn postgresdb_delete_expired(db: &PostgresDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(POSTGRES_DELETE_EXPIRED).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_reset_password_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?
+            .rows_affected())
+    }
+
+    async fn mysqldb_insert(&self, db: &MysqlDb) -> Result<()> {
+        db.execute(
+            sqlx::query(MYSQL_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.user_id)
+                .bind(&self.code),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_select(db: &MysqlDb, id: &Uuid) -> Result<UserPasswordResetMysqlModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(MYSQL_SELECT).bind(id).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_reset_password_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?)
+    }
+
+    async fn mysqldb_select_many_by_user_id(
+        db: &MysqlDb,
+        user_id: &Uuid,
+    ) -> Result<Vec<UserPasswordResetMysqlModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(MYSQL_SELECT_MANY_BY_USER_ID).bind(user_id))
+            .await?)
+    }
+
+    async fn mysqldb_delete_many_by_user_id(db: &MysqlDb, user_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(MYSQL_DELETE_MANY_BY_USER_ID).bind(user_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn mysqldb_delete_expired(db: &MysqlDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(MYSQL_DELETE_EXPIRED).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_reset_password_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?
+            .rows_affected())
+    }
+
+    async fn sqlitedb_insert(&self, db: &SqliteDb) -> Result<()> {
+        db.execute(
+            sqlx::query(SQLITE_INSERT)
+                .bind(&self.id)
+                .bind(&self.created_at)
+                .bind(&self.updated_at)
+                .bind(&self.user_id)
+                .bind(&self.code),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_select(db: &SqliteDb, id: &Uuid) -> Result<UserPasswordResetSqliteModel> {
+        Ok(db
+            .fetch_one(sqlx::query_as(SQLITE_SELECT).bind(id).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_reset_password_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?)
+    }
+
+    async fn sqlitedb_select_many_by_user_id(
+        db: &SqliteDb,
+        user_id: &Uuid,
+    ) -> Result<Vec<UserPasswordResetSqliteModel>> {
+        Ok(db
+            .fetch_all(sqlx::query_as(SQLITE_SELECT_MANY_BY_USER_ID).bind(user_id))
+            .await?)
+    }
+
+    async fn sqlitedb_delete_many_by_user_id(db: &SqliteDb, user_id: &Uuid) -> Result<()> {
+        db.execute(sqlx::query(SQLITE_DELETE_MANY_BY_USER_ID).bind(user_id))
+            .await?;
+        Ok(())
+    }
+
+    async fn sqlitedb_delete_expired(db: &SqliteDb) -> Result<u64> {
+        Ok(db
+            .execute(sqlx::query(SQLITE_DELETE_EXPIRED).bind(&{
+                let now = Utc::now();
+                DateTime::from_timestamp(
+                    now.timestamp() - db.table_reset_password_ttl(),
+                    now.timestamp_subsec_nanos(),
+                )
+                .unwrap()
+            }))
+            .await?
+            .rows_affected())
+    }
+
+    fn from_scylladb_model(model: &UserPasswordResetScyllaModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.created_at())?,
+            updated_at: conversion::scylla_cql_timestamp_to_datetime_utc(model.updated_at())?,
+            user_id: *model.user_id(),
+            code: model.code().to_owned(),
+        })
+    }
+
+    fn to_scylladb_model(&self) -> UserPasswordResetScyllaModel {
+        UserPasswordResetScyllaModel::new(
+            &self.id,
+            &ScyllaCqlTimestamp(self.created_at.timestamp_millis()),
+            &ScyllaCqlTimestamp(self.updated_at.timestamp_millis()),
+            &self.user_id,
+            &self.code,
+        )
+    }
+
+    fn from_postgresdb_model(model: &UserPasswordResetPostgresModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            user_id: *model.user_id(),
+            code: model.code().to_owned(),
+        })
+    }
+
+    fn from_mysqldb_model(model: &UserPasswordResetMysqlModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            user_id: *model.user_id(),
+            code: model.code().to_owned(),
+        })
+    }
+
+    fn from_sqlitedb_model(model: &UserPasswordResetSqliteModel) -> Result<Self> {
+        Ok(Self {
+            id: *model.id(),
+            created_at: *model.created_at(),
+            updated_at: *model.updated_at(),
+            user_id: *model.user_id(),
+            code: model.code().to_owned(),
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use std::net::IpAddr;
+
+use anyhow::{Error, Result};
+
+pub fn parse(cidr: &str) -> Result<(IpAddr, u32)> {
+    let (addr, prefix) = match cidr.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (cidr, None),
+    };
+    let addr = match addr.parse::<IpAddr>() {
+        Ok(addr) => addr,
+        Err(_) => return Err(Error::msg(format!("'{cidr}' is not a valid CIDR"))),
+    };
+    let max_prefix = match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+    let prefix = match prefix {
+        Some(prefix) => match prefix.parse::<u32>() {
+            Ok(prefix) if prefix <= max_prefix => prefix,
+            _ => return Err(Error::msg(format!("'{cidr}' is not a valid CIDR"))),
+        },
+        None => max_prefix,
+    };
+    Ok((addr, prefix))
+}
+
+pub fn contains(cidr: &str, ip: &IpAddr) -> bool {
+    let (network, prefix) = match parse(cidr) {
+        Ok(cidr) => cidr,
+        Err(_) => return false,
+    };
+    match (network, ip.to_canonical()) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            u32::from(network).checked_shr(32 - prefix).unwrap_or(0)
+                == u32::from(ip).checked_shr(32 - prefix).unwrap_or(0)
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            u128::from(network).checked_shr(128 - prefix).unwrap_or(0)
+                == u128::from(ip).checked_shr(128 - prefix).unwrap_or(0)
+        }
+        _ => false,
+    }
+}
@@ -14,30 +14,32 @@ use scylla::{
     },
     serialize::value::SerializeCql as ScyllaSerializeCql,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sqlx::ValueRef;
 use uuid::Uuid;
 
 use crate::util::conversion;
 
-#[derive(Serialize, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
 pub enum ColumnKind {
-    Boolean,   // boolean
-    TinyInt,   // 8-bit signed int
-    SmallInt,  // 16-bit signed int
-    Int,       // 32-bit signed int
-    BigInt,    // 64-bit signed long
-    Varint,    // Arbitrary-precision integer
-    Float,     // 32-bit IEEE-754 floating point
-    Double,    // 64-bit IEEE-754 floating point
-    Decimal,   // Variable-precision decimal
-    String,    // UTF8 encoded string
-    Binary,    // Arbitrary bytes
-    Uuid,      // A UUID (of any version)
-    Date,      // A date (with no corresponding time value)
-    Time,      // A time (with no corresponding date value)
-    DateTime,  // A datetime
-    Timestamp, // A timestamp (date and time)
-    Json,      // A json data format
+    Boolean,           // boolean
+    TinyInt,           // 8-bit signed int
+    SmallInt,          // 16-bit signed int
+    Int,               // 32-bit signed int
+    BigInt,            // 64-bit signed long
+    Varint,            // Arbitrary-precision integer
+    Float,             // 32-bit IEEE-754 floating point
+    Double,            // 64-bit IEEE-754 floating point
+    Decimal,           // Variable-precision decimal
+    String,            // UTF8 encoded string
+    Binary,            // Arbitrary bytes
+    Uuid,              // A UUID (of any version)
+    Date,              // A date (with no corresponding time value)
+    Time,              // A time (with no corresponding date value)
+    DateTime,          // A datetime
+    Timestamp,         // A timestamp (date and time)
+    Json,              // A json data format
+    Enum(Vec<String>), // A string restricted to a set of variants
 }
 
 impl ColumnKind {
@@ -60,10 +62,15 @@ impl ColumnKind {
             Self::DateTime => "datetime",
             Self::Timestamp => "timestamp",
             Self::Json => "json",
+            Self::Enum(_) => "enum",
         }
     }
 
     pub fn from_str(str: &str) -> Result<Self> {
+        Self::from_str_with_variants(str, &None)
+    }
+
+    pub fn from_str_with_variants(str: &str, variants: &Option<Vec<String>>) -> Result<Self> {
         match str {
             "boolean" => Ok(Self::Boolean),
             "tinyint" => Ok(Self::TinyInt),
@@ -82,10 +89,42 @@ impl ColumnKind {
             "datetime" => Ok(Self::DateTime),
             "timestamp" => Ok(Self::Timestamp),
             "json" => Ok(Self::Json),
+            "enum" => match variants {
+                Some(variants) if !variants.is_empty() => Ok(Self::Enum(variants.to_owned())),
+                _ => Err(Error::msg(
+                    "Schema field kind 'enum' must have at least one variant",
+                )),
+            },
             _ => Err(Error::msg(format!("Unknown schema field kind '{str}'"))),
         }
     }
 
+    // Variants end up inside the DDL of the MySQL ENUM type, so new schemas hold them to a charset
+    // that never needs quoting. Stored schemas are not checked, so older collections still load.
+    pub fn check_enum_variants(&self) -> Result<()> {
+        if let Some(variant) = self.enum_variants().and_then(|variants| {
+            variants.iter().find(|variant| {
+                variant.is_empty()
+                    || variant.len() > 64
+                    || !variant
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ' '))
+            })
+        }) {
+            return Err(Error::msg(format!(
+                "Enum variant '{variant}' must be 1 to 64 letters, digits, spaces, '_', '-', or '.'"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn enum_variants(&self) -> Option<&Vec<String>> {
+        match self {
+            Self::Enum(variants) => Some(variants),
+            _ => None,
+        }
+    }
+
     pub fn to_scylladb_model(&self) -> ColumnKindScylla {
         match self {
             Self::Boolean => ColumnKindScylla::Boolean,
@@ -97,7 +136,7 @@ impl ColumnKind {
             Self::Float => ColumnKindScylla::Float,
             Self::Double => ColumnKindScylla::Double,
             Self::Decimal => ColumnKindScylla::Decimal,
-            Self::String => ColumnKindScylla::Text,
+            Self::String | Self::Enum(_) => ColumnKindScylla::Text,
             Self::Binary | Self::Json => ColumnKindScylla::Blob,
             Self::Uuid => ColumnKindScylla::Uuid,
             Self::Date => ColumnKindScylla::Date,
@@ -117,7 +156,7 @@ impl ColumnKind {
             Self::Float => ColumnKindPostgres::Real,
             Self::Double => ColumnKindPostgres::DoublePrecision,
             Self::Decimal => ColumnKindPostgres::Numeric,
-            Self::String => ColumnKindPostgres::Varchar,
+            Self::String | Self::Enum(_) => ColumnKindPostgres::Varchar,
             Self::Binary => ColumnKindPostgres::Bytea,
             Self::Uuid => ColumnKindPostgres::Uuid,
             Self::Date => ColumnKindPostgres::Date,
@@ -138,6 +177,7 @@ impl ColumnKind {
             Self::Float => ColumnKindMysql::Float,
             Self::Double => ColumnKindMysql::Double,
             Self::String => ColumnKindMysql::Text,
+            Self::Enum(_) => ColumnKindMysql::Enum,
             Self::Uuid => ColumnKindMysql::Binary16,
             Self::Date => ColumnKindMysql::Date,
             Self::Time => ColumnKindMysql::Time,
@@ -156,7 +196,7 @@ impl ColumnKind {
                 ColumnKindSqlite::Blob
             }
             Self::Float | Self::Double => ColumnKindSqlite::Real,
-            Self::String => ColumnKindSqlite::Text,
+            Self::String | Self::Enum(_) => ColumnKindSqlite::Text,
             Self::Date => ColumnKindSqlite::Date,
             Self::Time => ColumnKindSqlite::Time,
             Self::DateTime | Self::Timestamp => ColumnKindSqlite::Datetime,
@@ -164,7 +204,7 @@ impl ColumnKind {
     }
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, PartialEq)]
 pub enum ColumnValue {
     Boolean(Option<bool>),
     TinyInteger(Option<i8>),
@@ -197,7 +237,7 @@ impl ColumnValue {
             ColumnKind::Float => Self::Float(None),
             ColumnKind::Double => Self::Double(None),
             ColumnKind::Decimal => Self::Decimal(None),
-            ColumnKind::String => Self::String(None),
+            ColumnKind::String | ColumnKind::Enum(_) => Self::String(None),
             ColumnKind::Binary => Self::Binary(None),
             ColumnKind::Uuid => Self::Uuid(None),
             ColumnKind::Date => Self::Date(None),
@@ -318,7 +358,9 @@ impl ColumnValue {
             ColumnKind::Decimal => Ok(Self::Decimal(Some(BigDecimal::from_str(
                 std::str::from_utf8(data)?,
             )?))),
-            ColumnKind::String => Ok(Self::String(Some(std::str::from_utf8(data)?.to_owned()))),
+            ColumnKind::String | ColumnKind::Enum(_) => {
+                Ok(Self::String(Some(std::str::from_utf8(data)?.to_owned())))
+            }
             ColumnKind::Binary => Ok(Self::Binary(Some(data.to_vec()))),
             ColumnKind::Uuid => Ok(Self::Uuid(Some(Uuid::from_bytes(<[u8; 16]>::try_from(
                 data,
@@ -401,6 +443,13 @@ impl ColumnValue {
                     &value.to_string(),
                 )?))),
                 ColumnKind::String => Ok(Self::String(Some(value.to_owned()))),
+                ColumnKind::Enum(variants) => match variants.contains(value) {
+                    true => Ok(Self::String(Some(value.to_owned()))),
+                    false => Err(Error::msg(format!(
+                        "Value '{value}' is not one of the enum variants: {}",
+                        variants.join(", ")
+                    ))),
+                },
                 ColumnKind::Binary => Ok(Self::Binary(Some(value.as_bytes().to_vec()))),
                 ColumnKind::Uuid => match Uuid::from_str(value) {
                     Ok(uuid) => Ok(Self::Uuid(Some(uuid))),
@@ -449,6 +498,124 @@ impl ColumnValue {
         }
     }
 
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Boolean(data) => data.map_or(0, |_| 1),
+            Self::TinyInteger(data) => data.map_or(0, |_| 1),
+            Self::SmallInteger(data) => data.map_or(0, |_| 2),
+            Self::Integer(data) => data.map_or(0, |_| 4),
+            Self::BigInteger(data) => data.map_or(0, |_| 8),
+            Self::VarInteger(data) => data
+                .as_ref()
+                .map_or(0, |data| data.to_signed_bytes_be().len()),
+            Self::Float(data) => data.map_or(0, |_| 4),
+            Self::Double(data) => data.map_or(0, |_| 8),
+            Self::Decimal(data) => data.as_ref().map_or(0, |data| data.to_string().len()),
+            Self::String(data) => data.as_ref().map_or(0, |data| data.len()),
+            Self::Binary(data) => data.as_ref().map_or(0, |data| data.len()),
+            Self::Uuid(data) => data.map_or(0, |_| 16),
+            Self::Date(data) => data.map_or(0, |_| 4),
+            Self::Time(data) => data.map_or(0, |_| 8),
+            Self::DateTime(data) => data.map_or(0, |_| 8),
+            Self::Timestamp(data) => data.map_or(0, |_| 8),
+            Self::Json(data) => data.as_ref().map_or(0, |data| data.len()),
+        }
+    }
+
+    pub fn from_csv_str(kind: &ColumnKind, value: &str) -> Result<Self> {
+        if value.is_empty() {
+            return Ok(Self::none(kind));
+        }
+        match kind {
+            ColumnKind::Boolean
+            | ColumnKind::TinyInt
+            | ColumnKind::SmallInt
+            | ColumnKind::Int
+            | ColumnKind::BigInt
+            | ColumnKind::Float
+            | ColumnKind::Double => Self::from_serde_json(kind, &serde_json::from_str(value)?),
+            _ => Self::from_serde_json(kind, &serde_json::Value::String(value.to_owned())),
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(
+            self,
+            Self::Boolean(None)
+                | Self::TinyInteger(None)
+                | Self::SmallInteger(None)
+                | Self::Integer(None)
+                | Self::BigInteger(None)
+                | Self::VarInteger(None)
+                | Self::Float(None)
+                | Self::Double(None)
+                | Self::Decimal(None)
+                | Self::String(None)
+                | Self::Binary(None)
+                | Self::Uuid(None)
+                | Self::Date(None)
+                | Self::Time(None)
+                | Self::DateTime(None)
+                | Self::Timestamp(None)
+                | Self::Json(None)
+        )
+    }
+
+    pub fn to_f64(&self) -> Result<Option<f64>> {
+        match self {
+            Self::TinyInteger(Some(value)) => Ok(Some(f64::from(*value))),
+            Self::SmallInteger(Some(value)) => Ok(Some(f64::from(*value))),
+            Self::Integer(Some(value)) => Ok(Some(f64::from(*value))),
+            Self::BigInteger(Some(value)) => Ok(Some(*value as f64)),
+            Self::VarInteger(Some(value)) => Ok(Some(value.to_string().parse::<f64>()?)),
+            Self::Float(Some(value)) => Ok(Some(f64::from(*value))),
+            Self::Double(Some(value)) => Ok(Some(*value)),
+            Self::Decimal(Some(value)) => Ok(Some(value.to_string().parse::<f64>()?)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn to_uuid(&self) -> Result<Uuid> {
+        match self {
+            Self::Uuid(Some(value)) => Ok(*value),
+            _ => Err(Error::msg("Value is not a uuid")),
+        }
+    }
+
+    pub fn transform(&self, kind: &ColumnKind) -> Result<Self> {
+        match (self, kind) {
+            (Self::DateTime(Some(value)) | Self::Timestamp(Some(value)), ColumnKind::Date) => {
+                Ok(Self::Date(Some(value.date_naive())))
+            }
+            (Self::DateTime(Some(value)) | Self::Timestamp(Some(value)), ColumnKind::Time) => {
+                Ok(Self::Time(Some(value.time())))
+            }
+            (Self::Date(Some(value)), ColumnKind::DateTime) => Ok(Self::DateTime(Some(
+                DateTime::from_naive_utc_and_offset(value.and_time(NaiveTime::default()), Utc),
+            ))),
+            (Self::Date(Some(value)), ColumnKind::Timestamp) => Ok(Self::Timestamp(Some(
+                DateTime::from_naive_utc_and_offset(value.and_time(NaiveTime::default()), Utc),
+            ))),
+            _ => match self.to_serde_json()? {
+                serde_json::Value::String(value) => Self::from_csv_str(kind, &value),
+                value @ serde_json::Value::Number(_)
+                    if matches!(kind, ColumnKind::Varint | ColumnKind::Decimal) =>
+                {
+                    Self::from_serde_json(kind, &serde_json::Value::String(value.to_string()))
+                }
+                value @ (serde_json::Value::Bool(_)
+                | serde_json::Value::Number(_)
+                | serde_json::Value::Array(_)
+                | serde_json::Value::Object(_))
+                    if matches!(kind, ColumnKind::String | ColumnKind::Enum(_)) =>
+                {
+                    Self::from_serde_json(kind, &serde_json::Value::String(value.to_string()))
+                }
+                value => Self::from_serde_json(kind, &value),
+            },
+        }
+    }
+
     pub fn to_serde_json(&self) -> Result<serde_json::Value> {
         match self {
             Self::Boolean(data) => match data {
@@ -526,6 +693,9 @@ impl ColumnValue {
     }
 
     pub fn from_scylladb_model(kind: &ColumnKind, value: &ScyllaCqlValue) -> Result<Self> {
+        if let ScyllaCqlValue::Empty = value {
+            return Ok(Self::none(kind));
+        }
         match kind {
             ColumnKind::Boolean => Ok(Self::Boolean(Some(value.as_boolean().ok_or_else(
                 || {
@@ -586,7 +756,7 @@ impl ColumnValue {
                     })?
                     .to_string(),
             )?))),
-            ColumnKind::String => Ok(Self::String(Some(
+            ColumnKind::String | ColumnKind::Enum(_) => Ok(Self::String(Some(
                 value
                     .as_text()
                     .ok_or_else(|| {
@@ -710,6 +880,9 @@ impl ColumnValue {
         index: &str,
         value: &sqlx::postgres::PgRow,
     ) -> Result<Self> {
+        if sqlx::Row::try_get_raw(value, index)?.is_null() {
+            return Ok(Self::none(kind));
+        }
         match kind {
             ColumnKind::Boolean => Ok(Self::Boolean(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::TinyInt => Ok(Self::TinyInteger(Some(sqlx::Row::try_get(value, index)?))),
@@ -724,7 +897,9 @@ impl ColumnValue {
             ColumnKind::Decimal => Ok(Self::Decimal(Some(BigDecimal::from_str(
                 &sqlx::Row::try_get::<sqlx::types::BigDecimal, _>(value, index)?.to_string(),
             )?))),
-            ColumnKind::String => Ok(Self::String(Some(sqlx::Row::try_get(value, index)?))),
+            ColumnKind::String | ColumnKind::Enum(_) => {
+                Ok(Self::String(Some(sqlx::Row::try_get(value, index)?)))
+            }
             ColumnKind::Binary => Ok(Self::Binary(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::Uuid => Ok(Self::Uuid(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::Date => Ok(Self::Date(Some(sqlx::Row::try_get(value, index)?))),
@@ -813,6 +988,9 @@ impl ColumnValue {
         index: &str,
         value: &sqlx::mysql::MySqlRow,
     ) -> Result<Self> {
+        if sqlx::Row::try_get_raw(value, index)?.is_null() {
+            return Ok(Self::none(kind));
+        }
         match kind {
             ColumnKind::Boolean => Ok(Self::Boolean(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::TinyInt => Ok(Self::TinyInteger(Some(sqlx::Row::try_get(value, index)?))),
@@ -827,7 +1005,9 @@ impl ColumnValue {
             ColumnKind::Decimal => Ok(Self::Decimal(Some(BigDecimal::from_str(
                 std::str::from_utf8(sqlx::Row::try_get::<&[u8], _>(value, index)?)?,
             )?))),
-            ColumnKind::String => Ok(Self::String(Some(sqlx::Row::try_get(value, index)?))),
+            ColumnKind::String | ColumnKind::Enum(_) => {
+                Ok(Self::String(Some(sqlx::Row::try_get(value, index)?)))
+            }
             ColumnKind::Binary => Ok(Self::Binary(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::Uuid => Ok(Self::Uuid(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::Date => Ok(Self::Date(Some(sqlx::Row::try_get(value, index)?))),
@@ -920,6 +1100,9 @@ impl ColumnValue {
         index: &str,
         value: &sqlx::sqlite::SqliteRow,
     ) -> Result<Self> {
+        if sqlx::Row::try_get_raw(value, index)?.is_null() {
+            return Ok(Self::none(kind));
+        }
         match kind {
             ColumnKind::Boolean => Ok(Self::Boolean(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::TinyInt => Ok(Self::TinyInteger(Some(sqlx::Row::try_get(value, index)?))),
@@ -934,7 +1117,9 @@ impl ColumnValue {
             ColumnKind::Decimal => Ok(Self::Decimal(Some(BigDecimal::from_str(
                 std::str::from_utf8(sqlx::Row::try_get::<&[u8], _>(value, index)?)?,
             )?))),
-            ColumnKind::String => Ok(Self::String(Some(sqlx::Row::try_get(value, index)?))),
+            ColumnKind::String | ColumnKind::Enum(_) => {
+                Ok(Self::String(Some(sqlx::Row::try_get(value, index)?)))
+            }
             ColumnKind::Binary => Ok(Self::Binary(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::Uuid => Ok(Self::Uuid(Some(sqlx::Row::try_get(value, index)?))),
             ColumnKind::Date => Ok(Self::Date(Some(sqlx::Row::try_get(value, index)?))),
@@ -1015,3 +1200,114 @@ impl ColumnValue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use scylla::frame::response::result::CqlValue as ScyllaCqlValue;
+
+    use super::{ColumnKind, ColumnValue};
+
+    fn kinds() -> Vec<ColumnKind> {
+        vec![
+            ColumnKind::Boolean,
+            ColumnKind::TinyInt,
+            ColumnKind::SmallInt,
+            ColumnKind::Int,
+            ColumnKind::BigInt,
+            ColumnKind::Varint,
+            ColumnKind::Float,
+            ColumnKind::Double,
+            ColumnKind::Decimal,
+            ColumnKind::String,
+            ColumnKind::Binary,
+            ColumnKind::Uuid,
+            ColumnKind::Date,
+            ColumnKind::Time,
+            ColumnKind::DateTime,
+            ColumnKind::Timestamp,
+            ColumnKind::Json,
+            ColumnKind::Enum(vec!["a".to_owned()]),
+        ]
+    }
+
+    fn assert_none(kind: &ColumnKind, value: anyhow::Result<ColumnValue>) {
+        match value {
+            Ok(value) => assert!(value == ColumnValue::none(kind), "{}", kind.to_str()),
+            Err(err) => panic!("{}: {err}", kind.to_str()),
+        }
+    }
+
+    #[test]
+    fn scylladb_empty_is_none() {
+        for kind in kinds() {
+            assert_none(
+                &kind,
+                ColumnValue::from_scylladb_model(&kind, &ScyllaCqlValue::Empty),
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn sqlitedb_null_is_none() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let row = sqlx::query("SELECT NULL AS v")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        for kind in kinds() {
+            assert_none(&kind, ColumnValue::from_sqlitedb_model(&kind, "v", &row));
+        }
+    }
+
+    // PostgreSQL and MySQL rows can only come from a server, so these run when one is given
+    #[tokio::test]
+    async fn postgresdb_null_is_none() {
+        let url = match std::env::var("HB_TEST_POSTGRES_URL") {
+            Ok(url) => url,
+            Err(_) => return,
+        };
+        let pool = sqlx::PgPool::connect(&url).await.unwrap();
+        let row = sqlx::query("SELECT NULL AS v")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        for kind in kinds() {
+            assert_none(&kind, ColumnValue::from_postgresdb_model(&kind, "v", &row));
+        }
+    }
+
+    #[tokio::test]
+    async fn mysqldb_null_is_none() {
+        let url = match std::env::var("HB_TEST_MYSQL_URL") {
+            Ok(url) => url,
+            Err(_) => return,
+        };
+        let pool = sqlx::MySqlPool::connect(&url).await.unwrap();
+        let row = sqlx::query("SELECT NULL AS v")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        for kind in kinds() {
+            assert_none(&kind, ColumnValue::from_mysqldb_model(&kind, "v", &row));
+        }
+    }
+
+    #[test]
+    fn enum_variants_are_held_to_a_safe_charset() {
+        let enum_kind =
+            |variant: &str| ColumnKind::Enum(vec!["draft".to_owned(), variant.to_owned()]);
+        for variant in ["published", "In review", "v1.2", "top-level_item"] {
+            assert!(
+                enum_kind(variant).check_enum_variants().is_ok(),
+                "{variant}"
+            );
+        }
+        for variant in ["", "it's", "back\\slash", "50%", &"a".repeat(65)] {
+            assert!(
+                enum_kind(variant).check_enum_variants().is_err(),
+                "{variant}"
+            );
+        }
+        assert!(ColumnKind::String.check_enum_variants().is_ok());
+    }
+}
@@ -1,15 +1,28 @@
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
 use sqlx::{
-    mysql::{MySqlArguments, MySqlPoolOptions, MySqlQueryResult, MySqlRow},
+    mysql::{MySqlArguments, MySqlConnection, MySqlPoolOptions, MySqlQueryResult, MySqlRow},
+    pool::PoolConnection,
     query::{Query, QueryAs},
-    Error, MySql, Pool,
+    Connection, Error, Execute, MySql, Pool,
 };
 
-use crate::query::{admin, admin_password_reset, collection, project, registration, token};
+use tracing::{Instrument, Span};
+
+use crate::query::{
+    admin, admin_password_reset, admin_refresh_token, admin_session, audit_log, collection,
+    collection_plugin, jwt_revocation, migration, project, project_member, project_transfer,
+    project_usage, registration, token, user, user_password_reset,
+};
 
 pub struct MysqlDb {
     pool: Pool<MySql>,
     table_registration_ttl: i64,
     table_reset_password_ttl: i64,
+    slow_query_threshold: Option<Duration>,
 }
 
 impl MysqlDb {
@@ -22,6 +35,7 @@ impl MysqlDb {
         max_connections: &u32,
         table_registration_ttl: &i64,
         table_reset_password_ttl: &i64,
+        slow_query_threshold: &Option<Duration>,
     ) -> Self {
         hb_log::info(Some("⚡"), "MySQL: Initializing component");
 
@@ -38,6 +52,7 @@ impl MysqlDb {
             pool,
             table_registration_ttl: *table_registration_ttl,
             table_reset_password_ttl: *table_reset_password_ttl,
+            slow_query_threshold: *slow_query_threshold,
         }
     }
 
@@ -45,49 +60,97 @@ impl MysqlDb {
         &self,
         query: Query<'_, MySql, MySqlArguments>,
     ) -> Result<MySqlQueryResult, Error> {
-        query.persistent(false).execute(&self.pool).await
+        let statement = query.sql();
+        self.observe(statement, query.persistent(false).execute(&self.pool))
+            .await
     }
 
     pub async fn execute(
         &self,
         query: Query<'_, MySql, MySqlArguments>,
     ) -> Result<MySqlQueryResult, Error> {
-        query.execute(&self.pool).await
+        let statement = query.sql();
+        self.observe(statement, query.execute(&self.pool)).await
+    }
+
+    pub async fn execute_transaction(
+        &self,
+        queries: Vec<Query<'_, MySql, MySqlArguments>>,
+    ) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+        for query in queries {
+            let statement = query.sql();
+            self.observe(statement, query.execute(&mut *tx)).await?;
+        }
+        tx.commit().await
     }
 
     pub async fn fetch_one_unprepared<T: Send + Unpin + for<'r> sqlx::FromRow<'r, MySqlRow>>(
         &self,
         query: QueryAs<'_, MySql, T, MySqlArguments>,
     ) -> Result<T, Error> {
-        Ok(query.persistent(false).fetch_one(&self.pool).await?)
+        let statement = query.sql();
+        Ok(self
+            .observe(statement, query.persistent(false).fetch_one(&self.pool))
+            .await?)
     }
 
     pub async fn fetch_one<T: Send + Unpin + for<'r> sqlx::FromRow<'r, MySqlRow>>(
         &self,
         query: QueryAs<'_, MySql, T, MySqlArguments>,
     ) -> Result<T, Error> {
-        Ok(query.fetch_one(&self.pool).await?)
+        let statement = query.sql();
+        Ok(self.observe(statement, query.fetch_one(&self.pool)).await?)
     }
 
     pub async fn fetch_one_row(
         &self,
         query: Query<'_, MySql, MySqlArguments>,
     ) -> Result<MySqlRow, Error> {
-        Ok(query.fetch_one(&self.pool).await?)
+        let statement = query.sql();
+        Ok(self.observe(statement, query.fetch_one(&self.pool)).await?)
     }
 
     pub async fn fetch_all<T: Send + Unpin + for<'r> sqlx::FromRow<'r, MySqlRow>>(
         &self,
         query: QueryAs<'_, MySql, T, MySqlArguments>,
     ) -> Result<Vec<T>, Error> {
-        query.fetch_all(&self.pool).await
+        let statement = query.sql();
+        self.observe(statement, query.fetch_all(&self.pool)).await
     }
 
     pub async fn fetch_all_rows(
         &self,
         query: Query<'_, MySql, MySqlArguments>,
     ) -> Result<Vec<MySqlRow>, Error> {
-        query.fetch_all(&self.pool).await
+        let statement = query.sql();
+        self.observe(statement, query.fetch_all(&self.pool)).await
+    }
+
+    pub async fn fetch_all_rows_cancellable(
+        &self,
+        query: Query<'_, MySql, MySqlArguments>,
+    ) -> Result<Vec<MySqlRow>, Error> {
+        let mut conn = self.pool.acquire().await?;
+        let (id,) = sqlx::query_as::<_, (u64,)>("SELECT CONNECTION_ID()")
+            .fetch_one(&mut *conn)
+            .await?;
+        let mut cancel_on_drop = CancelOnDrop {
+            pool: self.pool.clone(),
+            conn: Some(conn),
+            query: format!("KILL QUERY {id}"),
+        };
+        let statement = query.sql();
+        let rows = self
+            .observe(statement, query.fetch_all(cancel_on_drop.conn()))
+            .await;
+        cancel_on_drop.disarm();
+        rows
+    }
+
+    pub async fn close(&self) {
+        hb_log::info(Some("🔌"), "MySQL: Closing connection pool");
+        self.pool.close().await;
     }
 
     pub fn table_registration_ttl(&self) -> &i64 {
@@ -98,12 +161,115 @@ impl MysqlDb {
         &self.table_reset_password_ttl
     }
 
+    async fn observe<T>(&self, statement: &str, query: impl Future<Output = T>) -> T {
+        let started_at = Instant::now();
+        let output = query.instrument(query_span(statement)).await;
+        if let Some(slow_query_threshold) = &self.slow_query_threshold {
+            let elapsed = started_at.elapsed();
+            if elapsed >= *slow_query_threshold {
+                hb_log::warn(
+                    None,
+                    format!(
+                        "MySQL: Slow query on table '{}' took {} ms with {} bound parameters: {statement}",
+                        table_name(statement).unwrap_or("-"),
+                        elapsed.as_millis(),
+                        bound_parameter_count(statement),
+                    ),
+                );
+            }
+        }
+        output
+    }
+
     async fn init(pool: &Pool<MySql>) {
+        migration::init(pool).await;
         admin::init(pool).await;
         token::init(pool).await;
         project::init(pool).await;
         collection::init(pool).await;
+        collection_plugin::init(pool).await;
         registration::init(pool).await;
         admin_password_reset::init(pool).await;
+        admin_refresh_token::init(pool).await;
+        jwt_revocation::init(pool).await;
+        project_member::init(pool).await;
+        project_transfer::init(pool).await;
+        project_usage::init(pool).await;
+        audit_log::init(pool).await;
+        admin_session::init(pool).await;
+        user::init(pool).await;
+        user_password_reset::init(pool).await;
     }
 }
+
+// Holds the connection running the query so it can't go back to the pool, and be handed to
+// another request, before the cancel for the abandoned query has run
+struct CancelOnDrop {
+    pool: Pool<MySql>,
+    conn: Option<PoolConnection<MySql>>,
+    query: String,
+}
+
+impl CancelOnDrop {
+    fn conn(&mut self) -> &mut MySqlConnection {
+        self.conn.as_mut().unwrap()
+    }
+
+    fn disarm(mut self) {
+        self.conn = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let conn = conn.detach();
+            let pool = self.pool.clone();
+            let query = std::mem::take(&mut self.query);
+            tokio::spawn(async move {
+                if let Err(err) = sqlx::query(&query).execute(&pool).await {
+                    hb_log::warn(
+                        None,
+                        format!("MySQL: Failed to cancel abandoned query: {err}"),
+                    );
+                }
+                // The abandoned connection may still have unread results, so it is closed
+                // rather than reused
+                if let Err(err) = conn.close().await {
+                    hb_log::warn(
+                        None,
+                        format!("MySQL: Failed to close abandoned connection: {err}"),
+                    );
+                }
+            });
+        }
+    }
+}
+
+fn query_span(statement: &str) -> Span {
+    tracing::debug_span!("db.query", db.system = "mysql", db.statement = statement)
+}
+
+// Takes the identifier following the first FROM, INTO, UPDATE, or TABLE keyword
+fn table_name(statement: &str) -> Option<&str> {
+    let mut words = statement.split_whitespace();
+    while let Some(word) = words.next() {
+        if ["FROM", "INTO", "UPDATE", "TABLE"]
+            .iter()
+            .any(|keyword| word.eq_ignore_ascii_case(keyword))
+        {
+            return words
+                .find(|word| {
+                    !["IF", "NOT", "EXISTS"]
+                        .iter()
+                        .any(|keyword| word.eq_ignore_ascii_case(keyword))
+                })
+                .map(|word| word.trim_matches(|c| matches!(c, '"' | '`' | '(' | ')' | ';')));
+        }
+    }
+    None
+}
+
+fn bound_parameter_count(statement: &str) -> usize {
+    statement.matches('?').count()
+}
@@ -1,6 +1,6 @@
-pub const LOGICAL_OPERATOR: [&str; 2] = ["AND", "OR"];
+pub const LOGICAL_OPERATOR: [&str; 3] = ["AND", "OR", "NOT"];
 
-pub const COMPARISON_OPERATOR: [&str; 19] = [
+pub const COMPARISON_OPERATOR: [&str; 22] = [
     ">",
     ">=",
     "<",
@@ -15,11 +15,17 @@ pub const COMPARISON_OPERATOR: [&str; 19] = [
     "IS NOT",
     "IS NULL",
     "IS NOT NULL",
-    "ISNULL",
     "IN",
     "NOT IN",
     "LIKE",
     "NOT LIKE",
+    "CONTAINS",
+    "SUBSTRING",
+    "STARTS_WITH",
+    "ENDS_WITH",
 ];
 
+// Comparison operators that take no value
+pub const NULLARY_OPERATOR: [&str; 2] = ["IS NULL", "IS NOT NULL"];
+
 pub const ORDER_TYPE: [&str; 2] = ["ASC", "DESC"];
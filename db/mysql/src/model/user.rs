@@ -0,0 +1,59 @@
+use sqlx::{
+    types::chrono::{DateTime, Utc},
+    FromRow,
+};
+use uuid::Uuid;
+
+#[derive(FromRow)]
+pub struct UserModel {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    project_id: Uuid,
+    email: Option<String>,
+    password_hash: Option<String>,
+}
+
+impl UserModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &DateTime<Utc>,
+        updated_at: &DateTime<Utc>,
+        project_id: &Uuid,
+        email: &Option<String>,
+        password_hash: &Option<String>,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            project_id: *project_id,
+            email: email.clone(),
+            password_hash: password_hash.clone(),
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn email(&self) -> &Option<String> {
+        &self.email
+    }
+
+    pub fn password_hash(&self) -> &Option<String> {
+        &self.password_hash
+    }
+}
@@ -30,6 +30,7 @@ pub enum ColumnKind {
     Char36,
     Char32,
     Json,
+    Enum,
 }
 
 impl ColumnKind {
@@ -63,6 +64,7 @@ impl ColumnKind {
             Self::Char36 => "char(36)",
             Self::Char32 => "char(32)",
             Self::Json => "json",
+            Self::Enum => "enum",
         }
     }
 
@@ -96,6 +98,7 @@ impl ColumnKind {
             "char(36)" => Ok(Self::Char36),
             "char(32)" => Ok(Self::Char32),
             "json" => Ok(Self::Json),
+            "enum" => Ok(Self::Enum),
             _ => Err(format!("Unknown schema field kind '{str}'")),
         }
     }
@@ -0,0 +1,13 @@
+use sqlx::{Executor, MySql, Pool};
+
+pub const INSERT: &str = "INSERT INTO `admin_refresh_tokens` (`id`, `created_at`, `admin_id`, `session_id`, `token`, `expired_at`) VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT_BY_TOKEN: &str = "SELECT `id`, `created_at`, `admin_id`, `session_id`, `token`, `expired_at` FROM `admin_refresh_tokens` WHERE `token` = ?";
+pub const DELETE: &str = "DELETE FROM `admin_refresh_tokens` WHERE `id` = ?";
+
+pub async fn init(pool: &Pool<MySql>) {
+    hb_log::info(Some("🔧"), "MySQL: Setting up admin_refresh_tokens table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT_BY_TOKEN).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
@@ -0,0 +1,13 @@
+use sqlx::{Executor, MySql, Pool};
+
+pub const INSERT: &str = "INSERT INTO `audit_logs` (`id`, `created_at`, `admin_id`, `project_id`, `ip`, `action`, `resource`, `resource_id`, `before`, `after`) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT `id`, `created_at`, `admin_id`, `project_id`, `ip`, `action`, `resource`, `resource_id`, `before`, `after` FROM `audit_logs` WHERE `admin_id` = ? ORDER BY `created_at` DESC";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT `id`, `created_at`, `admin_id`, `project_id`, `ip`, `action`, `resource`, `resource_id`, `before`, `after` FROM `audit_logs` WHERE `project_id` = ? ORDER BY `created_at` DESC";
+
+pub async fn init(pool: &Pool<MySql>) {
+    hb_log::info(Some("🔧"), "MySQL: Setting up audit_logs table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+}
@@ -0,0 +1,13 @@
+use sqlx::{Executor, MySql, Pool};
+
+pub const UPSERT: &str = "INSERT INTO `collection_plugins` (`collection_id`, `created_at`, `updated_at`, `module`) VALUES (?, ?, ?, ?) ON DUPLICATE KEY UPDATE `updated_at` = VALUES(`updated_at`), `module` = VALUES(`module`)";
+pub const SELECT: &str = "SELECT `collection_id`, `created_at`, `updated_at`, `module` FROM `collection_plugins` WHERE `collection_id` = ?";
+pub const DELETE: &str = "DELETE FROM `collection_plugins` WHERE `collection_id` = ?";
+
+pub async fn init(pool: &Pool<MySql>) {
+    hb_log::info(Some("🔧"), "MySQL: Setting up collection_plugins table");
+
+    pool.prepare(UPSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
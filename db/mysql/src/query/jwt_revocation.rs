@@ -0,0 +1,11 @@
+use sqlx::{Executor, MySql, Pool};
+
+pub const INSERT: &str = "INSERT INTO `jwt_revocations` (`id`, `created_at`, `expired_at`) VALUES (?, ?, ?)";
+pub const COUNT: &str = "SELECT COUNT(1) FROM `jwt_revocations` WHERE `id` = ?";
+
+pub async fn init(pool: &Pool<MySql>) {
+    hb_log::info(Some("🔧"), "MySQL: Setting up jwt_revocations table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(COUNT).await.unwrap();
+}
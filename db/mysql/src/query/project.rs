@@ -1,19 +1,23 @@
 use sqlx::{Executor, MySql, Pool};
 
-pub const INSERT: &str = "INSERT INTO `projects` (`id`, `created_at`, `updated_at`, `admin_id`, `name`) VALUES (?, ?, ?, ?, ?)";
-pub const SELECT: &str = "SELECT `id`, `created_at`, `updated_at`, `admin_id`, `name` FROM `projects` WHERE `id` = ?";
-pub const SELECT_MANY_BY_ADMIN_ID :  &str = "SELECT `id`, `created_at`, `updated_at`, `admin_id`, `name` FROM `projects` WHERE `admin_id` = ?";
-pub const UPDATE: &str = "UPDATE `projects` SET `updated_at` = ?, `name` = ? WHERE `id` = ?";
+pub const INSERT: &str = "INSERT INTO `projects` (`id`, `created_at`, `updated_at`, `admin_id`, `name`, `suspended`, `archived`, `token_claims`, `deleted_at`) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT `id`, `created_at`, `updated_at`, `admin_id`, `name`, `suspended`, `archived`, `token_claims`, `deleted_at` FROM `projects` WHERE `id` = ?";
+pub const SELECT_MANY_BY_ADMIN_ID :  &str = "SELECT `id`, `created_at`, `updated_at`, `admin_id`, `name`, `suspended`, `archived`, `token_claims`, `deleted_at` FROM `projects` WHERE `admin_id` = ?";
+pub const SELECT_MANY_DELETED_BEFORE: &str = "SELECT `id`, `created_at`, `updated_at`, `admin_id`, `name`, `suspended`, `archived`, `token_claims`, `deleted_at` FROM `projects` WHERE `deleted_at` < ?";
+pub const UPDATE: &str = "UPDATE `projects` SET `updated_at` = ?, `name` = ?, `suspended` = ?, `archived` = ?, `token_claims` = ? WHERE `id` = ?";
+pub const UPDATE_ADMIN_ID: &str = "UPDATE `projects` SET `updated_at` = ?, `admin_id` = ? WHERE `id` = ?";
+pub const UPDATE_DELETED_AT: &str = "UPDATE `projects` SET `updated_at` = ?, `deleted_at` = ? WHERE `id` = ?";
 pub const DELETE: &str = "DELETE FROM `projects` WHERE `id` = ?";
 
 pub async fn init(pool: &Pool<MySql>) {
     hb_log::info(Some("🔧"), "MySQL: Setting up projects table");
 
-    pool.execute("CREATE TABLE IF NOT EXISTS `projects` (`id` binary(16)	, `created_at` timestamp, `updated_at` timestamp, `admin_id` binary(16)	, `name` text, PRIMARY KEY (`id`))").await.unwrap();
-
     pool.prepare(INSERT).await.unwrap();
     pool.prepare(SELECT).await.unwrap();
     pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
+    pool.prepare(SELECT_MANY_DELETED_BEFORE).await.unwrap();
     pool.prepare(UPDATE).await.unwrap();
+    pool.prepare(UPDATE_ADMIN_ID).await.unwrap();
+    pool.prepare(UPDATE_DELETED_AT).await.unwrap();
     pool.prepare(DELETE).await.unwrap();
 }
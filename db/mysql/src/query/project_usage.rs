@@ -0,0 +1,11 @@
+use sqlx::{Executor, MySql, Pool};
+
+pub const UPSERT: &str = "INSERT INTO `project_usages` (`project_id`, `period`, `updated_at`, `request_count`, `record_count`, `storage_bytes`) VALUES (?, ?, ?, ?, ?, ?) ON DUPLICATE KEY UPDATE `updated_at` = VALUES(`updated_at`), `request_count` = VALUES(`request_count`), `record_count` = VALUES(`record_count`), `storage_bytes` = VALUES(`storage_bytes`)";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT `project_id`, `period`, `updated_at`, `request_count`, `record_count`, `storage_bytes` FROM `project_usages` WHERE `project_id` = ? ORDER BY `period` DESC LIMIT ?";
+
+pub async fn init(pool: &Pool<MySql>) {
+    hb_log::info(Some("🔧"), "MySQL: Setting up project_usages table");
+
+    pool.prepare(UPSERT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+}
@@ -1,18 +1,30 @@
 use ahash::{HashMap, HashSet};
+use hb_db_query_builder::{self as query_builder, Dialect};
 use itertools::Itertools;
 
-use crate::model::collection::SchemaFieldPropsModel;
+use crate::model::collection::{PartialIndexModel, SchemaFieldPropsModel};
+
+pub struct MysqlDialect;
+
+impl Dialect for MysqlDialect {
+    const IDENT_QUOTE: char = '`';
+    const RANDOM: &'static str = "RAND()";
+
+    fn placeholder(_idx: usize) -> String {
+        "?".to_owned()
+    }
+}
 
 pub fn create_table(
     record_table: &str,
     columns: &HashMap<String, SchemaFieldPropsModel>,
 ) -> String {
     format!(
-        "CREATE TABLE IF NOT EXISTS `{}` (`_id` binary(16), {}, PRIMARY KEY (`_id`)) ",
+        "CREATE TABLE IF NOT EXISTS `{}` (`_id` binary(16), `_version` bigint, `_created_by` binary(16), {}, PRIMARY KEY (`_id`)) ",
         record_table,
         columns
             .iter()
-            .map(|(col, col_props)| format!("`{}` {}", col, col_props.internal_kind().to_str()))
+            .map(|(col, col_props)| format!("`{}` {}", col, column_kind(col_props)))
             .join(", ")
     )
 }
@@ -21,17 +33,21 @@ pub fn drop_table(record_table: &str) -> String {
     format!("DROP TABLE IF EXISTS `{record_table}`")
 }
 
+pub fn add_version_column(record_table: &str) -> String {
+    format!("ALTER TABLE `{record_table}` ADD COLUMN `_version` bigint")
+}
+
+pub fn add_created_by_column(record_table: &str) -> String {
+    format!("ALTER TABLE `{record_table}` ADD COLUMN `_created_by` binary(16)")
+}
+
 pub fn add_columns(record_table: &str, columns: &HashMap<String, SchemaFieldPropsModel>) -> String {
     format!(
         "ALTER TABLE `{}` {}",
         record_table,
         columns
             .iter()
-            .map(|(col, col_props)| format!(
-                "ADD COLUMN `{}` {}",
-                col,
-                col_props.internal_kind().to_str()
-            ))
+            .map(|(col, col_props)| format!("ADD COLUMN `{}` {}", col, column_kind(col_props)))
             .join(", ")
     )
 }
@@ -47,6 +63,10 @@ pub fn drop_columns(record_table: &str, column_names: &HashSet<String>) -> Strin
     )
 }
 
+pub fn rename_column(record_table: &str, from: &str, to: &str) -> String {
+    format!("ALTER TABLE `{record_table}` RENAME COLUMN `{from}` TO `{to}`")
+}
+
 pub fn change_columns_type(
     record_table: &str,
     columns: &HashMap<String, SchemaFieldPropsModel>,
@@ -56,15 +76,25 @@ pub fn change_columns_type(
         record_table,
         columns
             .iter()
-            .map(|(col, col_props)| format!(
-                "ALTER `{}` TYPE {}",
-                col,
-                col_props.internal_kind().to_str()
-            ))
+            .map(|(col, col_props)| format!("ALTER `{}` TYPE {}", col, column_kind(col_props)))
             .join(", ")
     )
 }
 
+fn column_kind(col_props: &SchemaFieldPropsModel) -> String {
+    match col_props.enum_variants() {
+        Some(variants) => format!(
+            "{}({})",
+            col_props.internal_kind().to_str(),
+            variants
+                .iter()
+                .map(|variant| format!("'{}'", variant.replace('\\', "\\\\").replace('\'', "''")))
+                .join(", ")
+        ),
+        None => col_props.internal_kind().to_str().to_owned(),
+    }
+}
+
 pub fn count_index(record_table: &str, index: &str) -> String {
     format!("SELECT COUNT(1) FROM `information_schema`.`STATISTICS` WHERE `TABLE_NAME` = '{record_table}' and `INDEX_NAME` = '{index}'")
 }
@@ -77,92 +107,163 @@ pub fn drop_index(record_table: &str, index: &str) -> String {
     format!("DROP INDEX `{record_table}_{index}`")
 }
 
-pub fn insert(record_table: &str, columns: &Vec<&str>) -> String {
-    let mut cols = "".to_owned();
-    let mut vals = "".to_owned();
-    for (idx, col) in columns.iter().enumerate() {
-        cols += &format!("`{col}`");
-        vals += "?";
-        if idx < columns.len() - 1 {
-            cols += ", ";
-            vals += ", ";
-        }
+pub fn add_partial_index_columns(
+    record_table: &str,
+    index: &str,
+    partial_index: &PartialIndexModel,
+    columns: &HashMap<String, SchemaFieldPropsModel>,
+) -> String {
+    let predicate = partial_index
+        .predicate()
+        .iter()
+        .sorted()
+        .map(|(field, op)| format!("`{field}` {op}"))
+        .join(" AND ");
+    format!(
+        "ALTER TABLE `{}` {}",
+        record_table,
+        partial_index
+            .fields()
+            .iter()
+            .filter_map(|field| columns.get(field).map(|col_props| (field, col_props)))
+            .map(|(field, col_props)| format!(
+                "ADD COLUMN `_{index}_{field}` {} AS (IF({predicate}, `{field}`, NULL)) VIRTUAL",
+                column_kind(col_props)
+            ))
+            .join(", ")
+    )
+}
+
+pub fn create_partial_index(
+    record_table: &str,
+    index: &str,
+    partial_index: &PartialIndexModel,
+) -> String {
+    format!(
+        "CREATE {}INDEX `{record_table}_{index}` ON `{record_table}` ({})",
+        if *partial_index.unique() {
+            "UNIQUE "
+        } else {
+            ""
+        },
+        partial_index
+            .fields()
+            .iter()
+            .map(|field| if partial_index.predicate().is_empty() {
+                format!("`{field}`")
+            } else {
+                format!("`_{index}_{field}`")
+            })
+            .join(", ")
+    )
+}
+
+pub fn drop_partial_index(
+    record_table: &str,
+    index: &str,
+    partial_index: &PartialIndexModel,
+) -> String {
+    if partial_index.predicate().is_empty() {
+        format!("DROP INDEX `{record_table}_{index}` ON `{record_table}`")
+    } else {
+        format!(
+            "ALTER TABLE `{}` {}",
+            record_table,
+            partial_index
+                .fields()
+                .iter()
+                .map(|field| format!("DROP COLUMN `_{index}_{field}`"))
+                .join(", ")
+        )
     }
-    format!("INSERT INTO `{record_table}` ({cols}) VALUES ({vals})")
 }
 
-pub fn select(record_table: &str, columns: &Vec<&str>) -> String {
+pub fn insert(record_table: &str, columns: &Vec<&str>) -> String {
+    query_builder::insert::<MysqlDialect>(record_table, columns)
+}
+
+pub fn insert_many(record_table: &str, columns: &Vec<&str>, rows: &usize) -> String {
+    query_builder::insert_many::<MysqlDialect>(record_table, columns, rows)
+}
+
+pub fn upsert(record_table: &str, columns: &Vec<&str>) -> String {
     format!(
-        "SELECT {} FROM `{}` WHERE `_id` = ?",
-        columns.iter().map(|col| format!("`{col}`")).join(", "),
-        record_table
+        "{} ON DUPLICATE KEY UPDATE {}, `_version` = COALESCE(`_version`, 0) + 1",
+        insert(record_table, columns),
+        columns
+            .iter()
+            .filter(|col| **col != "_created_by")
+            .map(|col| format!("`{col}` = VALUES(`{col}`)"))
+            .join(", ")
     )
 }
 
+pub fn select(record_table: &str, columns: &Vec<&str>) -> String {
+    query_builder::select::<MysqlDialect>(record_table, columns)
+}
+
 pub fn select_many(
     record_table: &str,
     columns: &Vec<&str>,
     filter: &str,
     groups: &Vec<&str>,
     orders: &Vec<(&str, &str)>,
+    random: &bool,
     with_query_limit: &bool,
 ) -> String {
-    let mut query = format!(
-        "SELECT {} FROM `{}`",
-        columns.iter().map(|col| format!("`{col}`")).join(", "),
+    query_builder::select_many::<MysqlDialect>(
         record_table,
-    );
-    if filter.len() > 0 {
-        query += &format!(" WHERE {filter}")
-    }
-    if groups.len() > 0 {
-        query += " GROUP BY";
-        let mut count = 0;
-        for group in groups {
-            if count > 0 {
-                query += ",";
-            }
-            query += &format!(" `{group}`");
-            count += 1;
-        }
-    }
-    if orders.len() > 0 {
-        query += " ORDER BY";
-        let mut count = 0;
-        for (field, kind) in orders {
-            if count > 0 {
-                query += ","
-            }
-            query += &format!(" `{field}` {kind}");
-            count += 1;
-        }
-    }
-    if *with_query_limit {
-        query += " LIMIT ?"
-    }
-    query
+        columns,
+        filter,
+        groups,
+        orders,
+        *random,
+        &with_query_limit.then_some(0),
+    )
 }
 
-pub fn update(record_table: &str, columns: &Vec<&str>) -> String {
-    format!(
-        "UPDATE `{}` SET {} WHERE `_id` = ?",
-        record_table,
-        columns.iter().map(|col| format!("`{col}` = ?")).join(", ")
-    )
+pub fn update(
+    record_table: &str,
+    columns: &Vec<&str>,
+    increment_columns: &Vec<&str>,
+    versioned: &bool,
+) -> String {
+    query_builder::update::<MysqlDialect>(record_table, columns, increment_columns, *versioned)
 }
 
 pub fn delete(record_table: &str, columns: &HashSet<String>) -> String {
-    format!(
-        "DELETE FROM `{}` WHERE {}",
+    query_builder::delete::<MysqlDialect>(
         record_table,
-        columns.iter().map(|col| format!("`{col}` = ?")).join(", ")
+        &columns.iter().map(String::as_str).collect::<Vec<_>>(),
     )
 }
 
+pub fn delete_with_version(record_table: &str) -> String {
+    query_builder::delete_with_version::<MysqlDialect>(record_table)
+}
+
+pub fn delete_before_id(record_table: &str) -> String {
+    query_builder::delete_before_id::<MysqlDialect>(record_table)
+}
+
 pub fn count(record_table: &str, filter: &str) -> String {
-    let mut query = format!("SELECT COUNT(1) FROM `{}`", record_table);
-    if filter.len() > 0 {
-        query += &format!(" WHERE {filter}")
-    }
-    query
+    query_builder::count::<MysqlDialect>(record_table, filter)
+}
+
+pub fn count_groups(record_table: &str, groups: &Vec<&str>, filter: &str) -> String {
+    query_builder::count_groups::<MysqlDialect>(record_table, groups, filter)
+}
+
+pub fn select_distinct(
+    record_table: &str,
+    column: &str,
+    filter: &str,
+    with_query_limit: &bool,
+) -> String {
+    query_builder::select_distinct::<MysqlDialect>(
+        record_table,
+        column,
+        filter,
+        &with_query_limit.then_some(0),
+    )
 }
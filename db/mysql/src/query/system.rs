@@ -1 +1,3 @@
 pub const COUNT_TABLE: &str = "SELECT COUNT(1) FROM `information_schema`.`tables` WHERE `table_name` = ?";
+pub const COUNT_COLUMN: &str = "SELECT COUNT(1) FROM `information_schema`.`columns` WHERE `table_name` = ? AND `column_name` = ?";
+pub const TABLE_SIZE: &str = "SELECT CAST(COALESCE(SUM(`data_length` + `index_length`), 0) AS SIGNED) FROM `information_schema`.`tables` WHERE `table_schema` = DATABASE() AND `table_name` = ?";
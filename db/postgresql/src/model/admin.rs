@@ -11,6 +11,10 @@ pub struct AdminModel {
     updated_at: DateTime<Utc>,
     email: String,
     password_hash: String,
+    mfa_secret: Option<String>,
+    mfa_enabled: bool,
+    role: String,
+    passkeys: Option<String>,
 }
 
 impl AdminModel {
@@ -20,6 +24,10 @@ impl AdminModel {
         updated_at: &DateTime<Utc>,
         email: &str,
         password_hash: &str,
+        mfa_secret: &Option<String>,
+        mfa_enabled: &bool,
+        role: &str,
+        passkeys: &Option<String>,
     ) -> Self {
         Self {
             id: *id,
@@ -27,6 +35,10 @@ impl AdminModel {
             updated_at: *updated_at,
             email: email.to_owned(),
             password_hash: password_hash.to_owned(),
+            mfa_secret: mfa_secret.clone(),
+            mfa_enabled: *mfa_enabled,
+            role: role.to_owned(),
+            passkeys: passkeys.clone(),
         }
     }
 
@@ -49,4 +61,20 @@ impl AdminModel {
     pub fn password_hash(&self) -> &str {
         &self.password_hash
     }
+
+    pub fn mfa_secret(&self) -> &Option<String> {
+        &self.mfa_secret
+    }
+
+    pub fn mfa_enabled(&self) -> &bool {
+        &self.mfa_enabled
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    pub fn passkeys(&self) -> &Option<String> {
+        &self.passkeys
+    }
 }
@@ -11,6 +11,7 @@ pub struct AdminPasswordResetModel {
     updated_at: DateTime<Utc>,
     admin_id: Uuid,
     code: String,
+    expired_at: DateTime<Utc>,
 }
 
 impl AdminPasswordResetModel {
@@ -20,6 +21,7 @@ impl AdminPasswordResetModel {
         updated_at: &DateTime<Utc>,
         admin_id: &Uuid,
         code: &str,
+        expired_at: &DateTime<Utc>,
     ) -> Self {
         Self {
             id: *id,
@@ -27,6 +29,7 @@ impl AdminPasswordResetModel {
             updated_at: *updated_at,
             admin_id: *admin_id,
             code: code.to_owned(),
+            expired_at: *expired_at,
         }
     }
 
@@ -49,4 +52,8 @@ impl AdminPasswordResetModel {
     pub fn code(&self) -> &str {
         &self.code
     }
+
+    pub fn expired_at(&self) -> &DateTime<Utc> {
+        &self.expired_at
+    }
 }
@@ -0,0 +1,59 @@
+use sqlx::{
+    types::chrono::{DateTime, Utc},
+    FromRow,
+};
+use uuid::Uuid;
+
+#[derive(FromRow)]
+pub struct AdminRefreshTokenModel {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    admin_id: Uuid,
+    session_id: Uuid,
+    token: String,
+    expired_at: DateTime<Utc>,
+}
+
+impl AdminRefreshTokenModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &DateTime<Utc>,
+        admin_id: &Uuid,
+        session_id: &Uuid,
+        token: &str,
+        expired_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            admin_id: *admin_id,
+            session_id: *session_id,
+            token: token.to_owned(),
+            expired_at: *expired_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn session_id(&self) -> &Uuid {
+        &self.session_id
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn expired_at(&self) -> &DateTime<Utc> {
+        &self.expired_at
+    }
+}
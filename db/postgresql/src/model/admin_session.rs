@@ -0,0 +1,66 @@
+use sqlx::{
+    types::chrono::{DateTime, Utc},
+    FromRow,
+};
+use uuid::Uuid;
+
+#[derive(FromRow)]
+pub struct AdminSessionModel {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    admin_id: Uuid,
+    user_agent: Option<String>,
+    ip: Option<String>,
+    expired_at: DateTime<Utc>,
+}
+
+impl AdminSessionModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &DateTime<Utc>,
+        updated_at: &DateTime<Utc>,
+        admin_id: &Uuid,
+        user_agent: &Option<String>,
+        ip: &Option<String>,
+        expired_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            admin_id: *admin_id,
+            user_agent: user_agent.clone(),
+            ip: ip.clone(),
+            expired_at: *expired_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn user_agent(&self) -> &Option<String> {
+        &self.user_agent
+    }
+
+    pub fn ip(&self) -> &Option<String> {
+        &self.ip
+    }
+
+    pub fn expired_at(&self) -> &DateTime<Utc> {
+        &self.expired_at
+    }
+}
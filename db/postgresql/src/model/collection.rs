@@ -20,6 +20,10 @@ pub struct CollectionModel {
     name: String,
     schema_fields: Json<HashMap<String, SchemaFieldPropsModel>>,
     indexes: Vec<String>,
+    partial_indexes: Json<HashMap<String, PartialIndexModel>>,
+    max_field_size: Option<i64>,
+    max_record_size: Option<i64>,
+    auto_delete_after_seconds: Option<i64>,
 }
 
 impl CollectionModel {
@@ -31,6 +35,10 @@ impl CollectionModel {
         name: &str,
         schema_fields: &Json<HashMap<String, SchemaFieldPropsModel>>,
         indexes: &Vec<String>,
+        partial_indexes: &Json<HashMap<String, PartialIndexModel>>,
+        max_field_size: &Option<i64>,
+        max_record_size: &Option<i64>,
+        auto_delete_after_seconds: &Option<i64>,
     ) -> Self {
         Self {
             id: *id,
@@ -40,6 +48,10 @@ impl CollectionModel {
             name: name.to_owned(),
             schema_fields: schema_fields.clone(),
             indexes: indexes.clone(),
+            partial_indexes: partial_indexes.clone(),
+            max_field_size: *max_field_size,
+            max_record_size: *max_record_size,
+            auto_delete_after_seconds: *auto_delete_after_seconds,
         }
     }
 
@@ -70,6 +82,22 @@ impl CollectionModel {
     pub fn indexes(&self) -> &Vec<String> {
         &self.indexes
     }
+
+    pub fn partial_indexes(&self) -> &Json<HashMap<String, PartialIndexModel>> {
+        &self.partial_indexes
+    }
+
+    pub fn max_field_size(&self) -> &Option<i64> {
+        &self.max_field_size
+    }
+
+    pub fn max_record_size(&self) -> &Option<i64> {
+        &self.max_record_size
+    }
+
+    pub fn auto_delete_after_seconds(&self) -> &Option<i64> {
+        &self.auto_delete_after_seconds
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -77,14 +105,45 @@ pub struct SchemaFieldPropsModel {
     kind: String,
     internal_kind: ColumnKind,
     required: bool,
+    enum_variants: Option<Vec<String>>,
+    min_length: Option<i64>,
+    max_length: Option<i64>,
+    min: Option<f64>,
+    max: Option<f64>,
+    pattern: Option<String>,
+    computed_op: Option<String>,
+    computed_fields: Option<Vec<String>>,
+    computed_separator: Option<String>,
 }
 
 impl SchemaFieldPropsModel {
-    pub fn new(kind: &str, internal_kind: &ColumnKind, required: &bool) -> Self {
+    pub fn new(
+        kind: &str,
+        internal_kind: &ColumnKind,
+        required: &bool,
+        enum_variants: &Option<Vec<String>>,
+        min_length: &Option<i64>,
+        max_length: &Option<i64>,
+        min: &Option<f64>,
+        max: &Option<f64>,
+        pattern: &Option<String>,
+        computed_op: &Option<String>,
+        computed_fields: &Option<Vec<String>>,
+        computed_separator: &Option<String>,
+    ) -> Self {
         Self {
             kind: kind.to_owned(),
             internal_kind: *internal_kind,
             required: *required,
+            enum_variants: enum_variants.clone(),
+            min_length: *min_length,
+            max_length: *max_length,
+            min: *min,
+            max: *max,
+            pattern: pattern.clone(),
+            computed_op: computed_op.clone(),
+            computed_fields: computed_fields.clone(),
+            computed_separator: computed_separator.clone(),
         }
     }
 
@@ -99,4 +158,69 @@ impl SchemaFieldPropsModel {
     pub fn required(&self) -> &bool {
         &self.required
     }
+
+    pub fn enum_variants(&self) -> &Option<Vec<String>> {
+        &self.enum_variants
+    }
+
+    pub fn min_length(&self) -> &Option<i64> {
+        &self.min_length
+    }
+
+    pub fn max_length(&self) -> &Option<i64> {
+        &self.max_length
+    }
+
+    pub fn min(&self) -> &Option<f64> {
+        &self.min
+    }
+
+    pub fn max(&self) -> &Option<f64> {
+        &self.max
+    }
+
+    pub fn pattern(&self) -> &Option<String> {
+        &self.pattern
+    }
+
+    pub fn computed_op(&self) -> &Option<String> {
+        &self.computed_op
+    }
+
+    pub fn computed_fields(&self) -> &Option<Vec<String>> {
+        &self.computed_fields
+    }
+
+    pub fn computed_separator(&self) -> &Option<String> {
+        &self.computed_separator
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct PartialIndexModel {
+    fields: Vec<String>,
+    unique: bool,
+    predicate: HashMap<String, String>,
+}
+
+impl PartialIndexModel {
+    pub fn new(fields: &[String], unique: &bool, predicate: &HashMap<String, String>) -> Self {
+        Self {
+            fields: fields.to_vec(),
+            unique: *unique,
+            predicate: predicate.clone(),
+        }
+    }
+
+    pub fn fields(&self) -> &Vec<String> {
+        &self.fields
+    }
+
+    pub fn unique(&self) -> &bool {
+        &self.unique
+    }
+
+    pub fn predicate(&self) -> &HashMap<String, String> {
+        &self.predicate
+    }
 }
@@ -1,6 +1,6 @@
-pub const LOGICAL_OPERATOR: [&str; 2] = ["AND", "OR"];
+pub const LOGICAL_OPERATOR: [&str; 3] = ["AND", "OR", "NOT"];
 
-pub const COMPARISON_OPERATOR: [&str; 29] = [
+pub const COMPARISON_OPERATOR: [&str; 35] = [
     "<",
     ">",
     "<=",
@@ -30,6 +30,26 @@ pub const COMPARISON_OPERATOR: [&str; 29] = [
     "NOT IN",
     "LIKE",
     "NOT LIKE",
+    "ILIKE",
+    "NOT ILIKE",
+    "CONTAINS",
+    "SUBSTRING",
+    "STARTS_WITH",
+    "ENDS_WITH",
+];
+
+// Comparison operators that take no value
+pub const NULLARY_OPERATOR: [&str; 10] = [
+    "IS NULL",
+    "IS NOT NULL",
+    "ISNULL",
+    "NOTNULL",
+    "IS TRUE",
+    "IS NOT TRUE",
+    "IS FALSE",
+    "IS NOT FALSE",
+    "IS UNKNOWN",
+    "IS NOT UNKNOWN",
 ];
 
 pub const ORDER_TYPE: [&str; 2] = ["ASC", "DESC"];
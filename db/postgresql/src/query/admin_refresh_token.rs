@@ -0,0 +1,13 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const INSERT: &str = "INSERT INTO \"admin_refresh_tokens\" (\"id\", \"created_at\", \"admin_id\", \"session_id\", \"token\", \"expired_at\") VALUES ($1, $2, $3, $4, $5, $6)";
+pub const SELECT_BY_TOKEN: &str = "SELECT \"id\", \"created_at\", \"admin_id\", \"session_id\", \"token\", \"expired_at\" FROM \"admin_refresh_tokens\" WHERE \"token\" = $1";
+pub const DELETE: &str = "DELETE FROM \"admin_refresh_tokens\" WHERE \"id\" = $1";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up admin_refresh_tokens table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT_BY_TOKEN).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
@@ -0,0 +1,17 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const INSERT: &str = "INSERT INTO \"admin_sessions\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\") VALUES ($1, $2, $3, $4, $5, $6, $7)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\" FROM \"admin_sessions\" WHERE \"id\" = $1";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\" FROM \"admin_sessions\" WHERE \"admin_id\" = $1 ORDER BY \"created_at\" DESC";
+pub const UPDATE_EXPIRED_AT: &str = "UPDATE \"admin_sessions\" SET \"updated_at\" = $1, \"expired_at\" = $2 WHERE \"id\" = $3";
+pub const DELETE: &str = "DELETE FROM \"admin_sessions\" WHERE \"id\" = $1";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up admin_sessions table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
+    pool.prepare(UPDATE_EXPIRED_AT).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
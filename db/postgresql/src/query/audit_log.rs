@@ -0,0 +1,13 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const INSERT: &str = "INSERT INTO \"audit_logs\" (\"id\", \"created_at\", \"admin_id\", \"project_id\", \"ip\", \"action\", \"resource\", \"resource_id\", \"before\", \"after\") VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"admin_id\", \"project_id\", \"ip\", \"action\", \"resource\", \"resource_id\", \"before\", \"after\" FROM \"audit_logs\" WHERE \"admin_id\" = $1 ORDER BY \"created_at\" DESC";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"admin_id\", \"project_id\", \"ip\", \"action\", \"resource\", \"resource_id\", \"before\", \"after\" FROM \"audit_logs\" WHERE \"project_id\" = $1 ORDER BY \"created_at\" DESC";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up audit_logs table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+}
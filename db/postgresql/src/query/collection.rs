@@ -1,19 +1,21 @@
 use sqlx::{Executor, Pool, Postgres};
 
-pub const INSERT: &str = "INSERT INTO \"collections\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\") VALUES ($1, $2, $3, $4, $5, $6, $7)";
-pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\" FROM \"collections\" WHERE \"id\" = $1";
-pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\" FROM \"collections\" WHERE \"project_id\" = $1";
-pub const UPDATE: &str = "UPDATE \"collections\" SET \"updated_at\" = $1, \"name\" = $2, \"schema_fields\" = $3, \"indexes\" = $4 WHERE \"id\" = $5";
+pub const INSERT: &str = "INSERT INTO \"collections\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\") VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\" FROM \"collections\" WHERE \"id\" = $1";
+pub const SELECT_MANY: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\" FROM \"collections\"";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\" FROM \"collections\" WHERE \"project_id\" = $1";
+pub const SELECT_MANY_WITH_AUTO_DELETE: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\" FROM \"collections\" WHERE \"auto_delete_after_seconds\" IS NOT NULL";
+pub const UPDATE: &str = "UPDATE \"collections\" SET \"updated_at\" = $1, \"name\" = $2, \"schema_fields\" = $3, \"indexes\" = $4, \"partial_indexes\" = $5, \"max_field_size\" = $6, \"max_record_size\" = $7, \"auto_delete_after_seconds\" = $8 WHERE \"id\" = $9";
 pub const DELETE: &str = "DELETE FROM \"collections\" WHERE \"id\" = $1";
 
 pub async fn init(pool: &Pool<Postgres>) {
     hb_log::info(Some("🔧"), "PostgreSQL: Setting up collections table");
 
-    pool.execute("CREATE TABLE IF NOT EXISTS \"collections\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"project_id\" uuid, \"name\" text, \"schema_fields\" jsonb, \"indexes\" text[], PRIMARY KEY (\"id\"))").await.unwrap();
-
     pool.prepare(INSERT).await.unwrap();
     pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY).await.unwrap();
     pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+    pool.prepare(SELECT_MANY_WITH_AUTO_DELETE).await.unwrap();
     pool.prepare(UPDATE).await.unwrap();
     pool.prepare(DELETE).await.unwrap();
 }
@@ -0,0 +1,13 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const UPSERT: &str = "INSERT INTO \"collection_plugins\" (\"collection_id\", \"created_at\", \"updated_at\", \"module\") VALUES ($1, $2, $3, $4) ON CONFLICT (\"collection_id\") DO UPDATE SET \"updated_at\" = excluded.\"updated_at\", \"module\" = excluded.\"module\"";
+pub const SELECT: &str = "SELECT \"collection_id\", \"created_at\", \"updated_at\", \"module\" FROM \"collection_plugins\" WHERE \"collection_id\" = $1";
+pub const DELETE: &str = "DELETE FROM \"collection_plugins\" WHERE \"collection_id\" = $1";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up collection_plugins table");
+
+    pool.prepare(UPSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
@@ -0,0 +1,11 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const INSERT: &str = "INSERT INTO \"jwt_revocations\" (\"id\", \"created_at\", \"expired_at\") VALUES ($1, $2, $3)";
+pub const COUNT: &str = "SELECT COUNT(1) FROM \"jwt_revocations\" WHERE \"id\" = $1";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up jwt_revocations table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(COUNT).await.unwrap();
+}
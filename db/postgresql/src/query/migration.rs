@@ -0,0 +1,251 @@
+use sqlx::{Executor, Pool, Postgres};
+
+use crate::query::system::COUNT_COLUMN;
+
+pub const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS \"schema_migrations\" (\"version\" bigint, \"name\" text, \"applied_at\" timestamptz, PRIMARY KEY (\"version\"))";
+pub const INSERT: &str = "INSERT INTO \"schema_migrations\" (\"version\", \"name\", \"applied_at\") VALUES ($1, $2, CURRENT_TIMESTAMP) ON CONFLICT (\"version\") DO NOTHING";
+pub const SELECT_MANY: &str = "SELECT \"version\" FROM \"schema_migrations\"";
+
+pub enum Step {
+    Execute(&'static str),
+    // Skipped when the column already exists; existing rows are set to `default` when it is added
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        kind: &'static str,
+        default: Option<&'static str>,
+    },
+}
+
+pub struct Migration {
+    version: i64,
+    name: &'static str,
+    steps: &'static [Step],
+}
+
+// Migrations are applied in order and recorded in schema_migrations. Append new ones to the end
+// and never change one that has been released.
+pub const MIGRATIONS: [Migration; 6] = [
+    Migration {
+        version: 1,
+        name: "create_internal_tables",
+        steps: &[
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"admins\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"email\" text, \"password_hash\" text, \"mfa_secret\" text, \"mfa_enabled\" boolean, \"role\" text, \"passkeys\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"tokens\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"admin_id\" uuid, \"token\" text, \"rules\" jsonb, \"expired_at\" timestamptz, \"allowed_cidrs\" text[], \"scopes\" text[], \"previous_token\" text, \"previous_token_expired_at\" timestamptz, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"projects\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"admin_id\" uuid, \"name\" text, \"suspended\" boolean, \"token_claims\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"collections\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"project_id\" uuid, \"name\" text, \"schema_fields\" jsonb, \"indexes\" text[], \"partial_indexes\" jsonb, \"max_field_size\" bigint, \"max_record_size\" bigint, \"auto_delete_after_seconds\" bigint, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"registrations\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"email\" text, \"password_hash\" text, \"code\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"admin_password_resets\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"admin_id\" uuid, \"code\" text, \"expired_at\" timestamptz, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"admin_refresh_tokens\" (\"id\" uuid, \"created_at\" timestamptz, \"admin_id\" uuid, \"session_id\" uuid, \"token\" text, \"expired_at\" timestamptz, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"jwt_revocations\" (\"id\" uuid, \"created_at\" timestamptz, \"expired_at\" timestamptz, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"project_members\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"project_id\" uuid, \"admin_id\" uuid, \"role\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"project_transfers\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"project_id\" uuid, \"from_admin_id\" uuid, \"to_admin_id\" uuid, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"audit_logs\" (\"id\" uuid, \"created_at\" timestamptz, \"admin_id\" uuid, \"project_id\" uuid, \"ip\" text, \"action\" text, \"resource\" text, \"resource_id\" uuid, \"before\" text, \"after\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"admin_sessions\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"admin_id\" uuid, \"user_agent\" text, \"ip\" text, \"expired_at\" timestamptz, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"users\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"project_id\" uuid, \"email\" text, \"password_hash\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"user_password_resets\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"user_id\" uuid, \"code\" text, PRIMARY KEY (\"id\"))"),
+        ],
+    },
+    // Deployments created before migrations existed have tables without these columns
+    Migration {
+        version: 2,
+        name: "add_columns_to_early_tables",
+        steps: &[
+            Step::AddColumn {
+                table: "admins",
+                column: "mfa_secret",
+                kind: "text",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "admins",
+                column: "mfa_enabled",
+                kind: "boolean",
+                default: Some("false"),
+            },
+            Step::AddColumn {
+                table: "admins",
+                column: "role",
+                kind: "text",
+                default: Some("'owner'"),
+            },
+            Step::AddColumn {
+                table: "admins",
+                column: "passkeys",
+                kind: "text",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "admin_password_resets",
+                column: "expired_at",
+                kind: "timestamptz",
+                default: Some("\"created_at\""),
+            },
+            Step::AddColumn {
+                table: "collections",
+                column: "partial_indexes",
+                kind: "jsonb",
+                default: Some("'{}'"),
+            },
+            Step::AddColumn {
+                table: "collections",
+                column: "max_field_size",
+                kind: "bigint",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "collections",
+                column: "max_record_size",
+                kind: "bigint",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "collections",
+                column: "auto_delete_after_seconds",
+                kind: "bigint",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "projects",
+                column: "suspended",
+                kind: "boolean",
+                default: Some("false"),
+            },
+            Step::AddColumn {
+                table: "projects",
+                column: "token_claims",
+                kind: "text",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "tokens",
+                column: "allowed_cidrs",
+                kind: "text[]",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "tokens",
+                column: "scopes",
+                kind: "text[]",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "tokens",
+                column: "previous_token",
+                kind: "text",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "tokens",
+                column: "previous_token_expired_at",
+                kind: "timestamptz",
+                default: None,
+            },
+        ],
+    },
+    Migration {
+        version: 3,
+        name: "create_project_usages",
+        steps: &[
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"project_usages\" (\"project_id\" uuid, \"period\" timestamptz, \"updated_at\" timestamptz, \"request_count\" bigint, \"record_count\" bigint, \"storage_bytes\" bigint, PRIMARY KEY (\"project_id\", \"period\"))"),
+        ],
+    },
+    Migration {
+        version: 4,
+        name: "add_project_deleted_at",
+        steps: &[Step::AddColumn {
+            table: "projects",
+            column: "deleted_at",
+            kind: "timestamptz",
+            default: None,
+        }],
+    },
+    Migration {
+        version: 5,
+        name: "add_project_archived",
+        steps: &[Step::AddColumn {
+            table: "projects",
+            column: "archived",
+            kind: "boolean",
+            default: Some("false"),
+        }],
+    },
+    Migration {
+        version: 6,
+        name: "create_collection_plugins",
+        steps: &[
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"collection_plugins\" (\"collection_id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"module\" bytea, PRIMARY KEY (\"collection_id\"))"),
+        ],
+    },
+];
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Applying schema migrations");
+
+    pool.execute(CREATE_TABLE).await.unwrap();
+
+    let applied_versions = sqlx::query_as::<_, (i64,)>(SELECT_MANY)
+        .fetch_all(pool)
+        .await
+        .unwrap();
+
+    for migration in &MIGRATIONS {
+        if applied_versions
+            .iter()
+            .any(|(version,)| *version == migration.version)
+        {
+            continue;
+        }
+
+        hb_log::info(
+            Some("🔧"),
+            format!(
+                "PostgreSQL: Applying migration {} ({})",
+                migration.version, migration.name
+            ),
+        );
+
+        for step in migration.steps {
+            match step {
+                Step::Execute(statement) => {
+                    pool.execute(*statement).await.unwrap();
+                }
+                Step::AddColumn {
+                    table,
+                    column,
+                    kind,
+                    default,
+                } => {
+                    let (count,) = sqlx::query_as::<_, (i64,)>(COUNT_COLUMN)
+                        .bind(table)
+                        .bind(column)
+                        .fetch_one(pool)
+                        .await
+                        .unwrap();
+                    if count > 0 {
+                        continue;
+                    }
+                    pool.execute(
+                        format!("ALTER TABLE \"{table}\" ADD COLUMN \"{column}\" {kind}").as_str(),
+                    )
+                    .await
+                    .unwrap();
+                    if let Some(default) = default {
+                        pool.execute(
+                            format!("UPDATE \"{table}\" SET \"{column}\" = {default}").as_str(),
+                        )
+                        .await
+                        .unwrap();
+                    }
+                }
+            }
+        }
+
+        sqlx::query(INSERT)
+            .bind(migration.version)
+            .bind(migration.name)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,19 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const INSERT: &str = "INSERT INTO \"project_members\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\") VALUES ($1, $2, $3, $4, $5, $6)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"project_members\" WHERE \"id\" = $1";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"project_members\" WHERE \"project_id\" = $1";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"project_members\" WHERE \"admin_id\" = $1";
+pub const UPDATE: &str = "UPDATE \"project_members\" SET \"updated_at\" = $1, \"role\" = $2 WHERE \"id\" = $3";
+pub const DELETE: &str = "DELETE FROM \"project_members\" WHERE \"id\" = $1";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up project_members table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
+    pool.prepare(UPDATE).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
@@ -0,0 +1,15 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const INSERT: &str = "INSERT INTO \"project_transfers\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\") VALUES ($1, $2, $3, $4, $5, $6)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\" FROM \"project_transfers\" WHERE \"id\" = $1";
+pub const SELECT_MANY_BY_TO_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\" FROM \"project_transfers\" WHERE \"to_admin_id\" = $1";
+pub const DELETE: &str = "DELETE FROM \"project_transfers\" WHERE \"id\" = $1";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up project_transfers table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_TO_ADMIN_ID).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
@@ -0,0 +1,11 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const UPSERT: &str = "INSERT INTO \"project_usages\" (\"project_id\", \"period\", \"updated_at\", \"request_count\", \"record_count\", \"storage_bytes\") VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (\"project_id\", \"period\") DO UPDATE SET \"updated_at\" = EXCLUDED.\"updated_at\", \"request_count\" = EXCLUDED.\"request_count\", \"record_count\" = EXCLUDED.\"record_count\", \"storage_bytes\" = EXCLUDED.\"storage_bytes\"";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"project_id\", \"period\", \"updated_at\", \"request_count\", \"record_count\", \"storage_bytes\" FROM \"project_usages\" WHERE \"project_id\" = $1 ORDER BY \"period\" DESC LIMIT $2";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up project_usages table");
+
+    pool.prepare(UPSERT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+}
@@ -1,18 +1,29 @@
 use ahash::{HashMap, HashSet};
+use hb_db_query_builder::{self as query_builder, Dialect};
 use itertools::Itertools;
 
-use crate::model::collection::SchemaFieldPropsModel;
+use crate::model::collection::{PartialIndexModel, SchemaFieldPropsModel};
+
+pub struct PostgresDialect;
+
+impl Dialect for PostgresDialect {
+    const IDENT_QUOTE: char = '"';
+
+    fn placeholder(idx: usize) -> String {
+        format!("${idx}")
+    }
+}
 
 pub fn create_table(
     record_table: &str,
     columns: &HashMap<String, SchemaFieldPropsModel>,
 ) -> String {
     format!(
-        "CREATE TABLE IF NOT EXISTS \"{}\" (\"_id\" uuid, {}, PRIMARY KEY (\"_id\")) ",
+        "CREATE TABLE IF NOT EXISTS \"{}\" (\"_id\" uuid, \"_version\" bigint, \"_created_by\" uuid, {}, PRIMARY KEY (\"_id\")) ",
         record_table,
         columns
             .iter()
-            .map(|(col, col_props)| format!("\"{}\" {}", col, col_props.internal_kind().to_str()))
+            .map(|(col, col_props)| column_definition(record_table, col, col_props))
             .join(", ")
     )
 }
@@ -21,6 +32,14 @@ pub fn drop_table(record_table: &str) -> String {
     format!("DROP TABLE IF EXISTS \"{record_table}\"")
 }
 
+pub fn add_version_column(record_table: &str) -> String {
+    format!("ALTER TABLE \"{record_table}\" ADD COLUMN \"_version\" bigint")
+}
+
+pub fn add_created_by_column(record_table: &str) -> String {
+    format!("ALTER TABLE \"{record_table}\" ADD COLUMN \"_created_by\" uuid")
+}
+
 pub fn add_columns(record_table: &str, columns: &HashMap<String, SchemaFieldPropsModel>) -> String {
     format!(
         "ALTER TABLE \"{}\" {}",
@@ -28,9 +47,8 @@ pub fn add_columns(record_table: &str, columns: &HashMap<String, SchemaFieldProp
         columns
             .iter()
             .map(|(col, col_props)| format!(
-                "ADD COLUMN \"{}\" {}",
-                col,
-                col_props.internal_kind().to_str()
+                "ADD COLUMN {}",
+                column_definition(record_table, col, col_props)
             ))
             .join(", ")
     )
@@ -47,6 +65,14 @@ pub fn drop_columns(record_table: &str, column_names: &HashSet<String>) -> Strin
     )
 }
 
+pub fn rename_column(record_table: &str, from: &str, to: &str) -> String {
+    format!("ALTER TABLE \"{record_table}\" RENAME COLUMN \"{from}\" TO \"{to}\"")
+}
+
+pub fn rename_enum_constraint(record_table: &str, from: &str, to: &str) -> String {
+    format!("ALTER TABLE \"{record_table}\" RENAME CONSTRAINT \"{record_table}_{from}_enum\" TO \"{record_table}_{to}_enum\"")
+}
+
 pub fn change_columns_type(
     record_table: &str,
     columns: &HashMap<String, SchemaFieldPropsModel>,
@@ -56,15 +82,42 @@ pub fn change_columns_type(
         record_table,
         columns
             .iter()
-            .map(|(col, col_props)| format!(
-                "ALTER \"{}\" TYPE {}",
-                col,
-                col_props.internal_kind().to_str()
-            ))
+            .map(|(col, col_props)| {
+                let mut alter = format!(
+                    "ALTER \"{col}\" TYPE {}, DROP CONSTRAINT IF EXISTS \"{record_table}_{col}_enum\"",
+                    col_props.internal_kind().to_str()
+                );
+                if let Some(check) = enum_check(col, col_props) {
+                    alter += &format!(", ADD CONSTRAINT \"{record_table}_{col}_enum\" {check}");
+                }
+                alter
+            })
             .join(", ")
     )
 }
 
+fn column_definition(record_table: &str, col: &str, col_props: &SchemaFieldPropsModel) -> String {
+    match enum_check(col, col_props) {
+        Some(check) => format!(
+            "\"{col}\" {} CONSTRAINT \"{record_table}_{col}_enum\" {check}",
+            col_props.internal_kind().to_str()
+        ),
+        None => format!("\"{col}\" {}", col_props.internal_kind().to_str()),
+    }
+}
+
+fn enum_check(col: &str, col_props: &SchemaFieldPropsModel) -> Option<String> {
+    col_props.enum_variants().as_ref().map(|variants| {
+        format!(
+            "CHECK (\"{col}\" IN ({}))",
+            variants
+                .iter()
+                .map(|variant| format!("'{}'", variant.replace('\'', "''")))
+                .join(", ")
+        )
+    })
+}
+
 pub fn create_index(record_table: &str, index: &str) -> String {
     format!(
         "CREATE INDEX IF NOT EXISTS \"{record_table}_{index}\" ON \"{record_table}\" (\"{index}\")"
@@ -75,26 +128,69 @@ pub fn drop_index(record_table: &str, index: &str) -> String {
     format!("DROP INDEX IF EXISTS \"{record_table}_{index}\"")
 }
 
+pub fn create_partial_index(
+    record_table: &str,
+    index: &str,
+    partial_index: &PartialIndexModel,
+) -> String {
+    let mut query = format!(
+        "CREATE {}INDEX IF NOT EXISTS \"{record_table}_{index}\" ON \"{record_table}\" ({})",
+        if *partial_index.unique() {
+            "UNIQUE "
+        } else {
+            ""
+        },
+        partial_index
+            .fields()
+            .iter()
+            .map(|field| format!("\"{field}\""))
+            .join(", ")
+    );
+    if !partial_index.predicate().is_empty() {
+        query += &format!(
+            " WHERE {}",
+            partial_index
+                .predicate()
+                .iter()
+                .sorted()
+                .map(|(field, op)| format!("\"{field}\" {op}"))
+                .join(" AND ")
+        );
+    }
+    query
+}
+
 pub fn insert(record_table: &str, columns: &Vec<&str>) -> String {
-    let mut cols = "".to_owned();
-    let mut vals = "".to_owned();
-    for (idx, col) in columns.iter().enumerate() {
-        cols += &format!("\"{col}\"");
-        vals += &format!("${}", idx + 1);
-        if idx < columns.len() - 1 {
-            cols += ", ";
-            vals += ", ";
-        }
+    query_builder::insert::<PostgresDialect>(record_table, columns)
+}
+
+pub fn insert_many(record_table: &str, columns: &Vec<&str>, rows: &usize) -> String {
+    query_builder::insert_many::<PostgresDialect>(record_table, columns, rows)
+}
+
+pub fn upsert(record_table: &str, columns: &Vec<&str>) -> String {
+    let updates = columns
+        .iter()
+        .filter(|col| **col != "_id" && **col != "_created_by")
+        .map(|col| format!("\"{col}\" = EXCLUDED.\"{col}\""))
+        .join(", ");
+    if updates.is_empty() {
+        format!(
+            "{} ON CONFLICT (\"_id\") DO NOTHING",
+            insert(record_table, columns)
+        )
+    } else {
+        format!(
+            "{} ON CONFLICT (\"_id\") DO UPDATE SET {}, \"_version\" = COALESCE(\"{}\".\"_version\", 0) + 1",
+            insert(record_table, columns),
+            updates,
+            record_table
+        )
     }
-    format!("INSERT INTO \"{record_table}\" ({cols}) VALUES ({vals})")
 }
 
 pub fn select(record_table: &str, columns: &Vec<&str>) -> String {
-    format!(
-        "SELECT {} FROM \"{}\" WHERE \"_id\" = $1",
-        columns.iter().map(|col| format!("\"{col}\"")).join(", "),
-        record_table
-    )
+    query_builder::select::<PostgresDialect>(record_table, columns)
 }
 
 pub fn select_many(
@@ -103,74 +199,64 @@ pub fn select_many(
     filter: &str,
     groups: &Vec<&str>,
     orders: &Vec<(&str, &str)>,
+    random: &bool,
     with_query_limit: &bool,
     argument_idx: &usize,
 ) -> String {
-    let mut query = format!(
-        "SELECT {} FROM \"{}\"",
-        columns.iter().map(|col| format!("\"{col}\"")).join(", "),
+    query_builder::select_many::<PostgresDialect>(
         record_table,
-    );
-    if filter.len() > 0 {
-        query += &format!(" WHERE {filter}")
-    }
-    if groups.len() > 0 {
-        query += " GROUP BY";
-        let mut count = 0;
-        for group in groups {
-            if count > 0 {
-                query += ",";
-            }
-            query += &format!(" \"{group}\"");
-            count += 1;
-        }
-    }
-    if orders.len() > 0 {
-        query += " ORDER BY";
-        let mut count = 0;
-        for (field, kind) in orders {
-            if count > 0 {
-                query += ","
-            }
-            query += &format!(" \"{field}\" {kind}");
-            count += 1;
-        }
-    }
-    if *with_query_limit {
-        query += &format!(" LIMIT ${argument_idx}")
-    }
-    query
+        columns,
+        filter,
+        groups,
+        orders,
+        *random,
+        &with_query_limit.then_some(*argument_idx),
+    )
 }
 
-pub fn update(record_table: &str, columns: &Vec<&str>) -> String {
-    format!(
-        "UPDATE \"{}\" SET {} WHERE \"_id\" = ${}",
-        record_table,
-        columns
-            .iter()
-            .enumerate()
-            .map(|(idx, col)| format!("\"{}\" = ${}", col, idx + 1))
-            .join(", "),
-        columns.len() + 1
-    )
+pub fn update(
+    record_table: &str,
+    columns: &Vec<&str>,
+    increment_columns: &Vec<&str>,
+    versioned: &bool,
+) -> String {
+    query_builder::update::<PostgresDialect>(record_table, columns, increment_columns, *versioned)
 }
 
 pub fn delete(record_table: &str, columns: &HashSet<String>) -> String {
-    format!(
-        "DELETE FROM \"{}\" WHERE {}",
+    query_builder::delete::<PostgresDialect>(
         record_table,
-        columns
-            .iter()
-            .enumerate()
-            .map(|(idx, col)| format!("\"{}\" = ${}", col, idx + 1))
-            .join(", ")
+        &columns.iter().map(String::as_str).collect::<Vec<_>>(),
     )
 }
 
+pub fn delete_with_version(record_table: &str) -> String {
+    query_builder::delete_with_version::<PostgresDialect>(record_table)
+}
+
+pub fn delete_before_id(record_table: &str) -> String {
+    query_builder::delete_before_id::<PostgresDialect>(record_table)
+}
+
 pub fn count(record_table: &str, filter: &str) -> String {
-    let mut query = format!("SELECT COUNT(1) FROM \"{}\"", record_table);
-    if filter.len() > 0 {
-        query += &format!(" WHERE {filter}")
-    }
-    query
+    query_builder::count::<PostgresDialect>(record_table, filter)
+}
+
+pub fn count_groups(record_table: &str, groups: &Vec<&str>, filter: &str) -> String {
+    query_builder::count_groups::<PostgresDialect>(record_table, groups, filter)
+}
+
+pub fn select_distinct(
+    record_table: &str,
+    column: &str,
+    filter: &str,
+    with_query_limit: &bool,
+    argument_idx: &usize,
+) -> String {
+    query_builder::select_distinct::<PostgresDialect>(
+        record_table,
+        column,
+        filter,
+        &with_query_limit.then_some(*argument_idx),
+    )
 }
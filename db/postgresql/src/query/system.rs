@@ -1 +1,3 @@
 pub const COUNT_TABLE: &str = "SELECT COUNT(1) FROM \"information_schema\".\"tables\" WHERE \"table_name\" = $1";
+pub const COUNT_COLUMN: &str = "SELECT COUNT(1) FROM \"information_schema\".\"columns\" WHERE \"table_name\" = $1 AND \"column_name\" = $2";
+pub const TABLE_SIZE: &str = "SELECT COALESCE(pg_total_relation_size(to_regclass(quote_ident($1))), 0)::bigint";
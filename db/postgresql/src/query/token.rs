@@ -1,21 +1,25 @@
 use sqlx::{Executor, Pool, Postgres};
 
-pub const INSERT: &str = "INSERT INTO \"tokens\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\") VALUES ($1, $2, $3, $4, $5, $6, $7)";
-pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\" FROM \"tokens\" WHERE \"id\" = $1";
-pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\" FROM \"tokens\" WHERE \"admin_id\" = $1";
-pub const SELECT_BY_TOKEN: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\" FROM \"tokens\" WHERE \"token\" = $1";
-pub const UPDATE: &str = "UPDATE \"tokens\" SET \"updated_at\" = $1, \"rules\" = $2, \"expired_at\" = $3 WHERE \"id\" = $4";
+pub const INSERT: &str = "INSERT INTO \"tokens\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\", \"allowed_cidrs\", \"scopes\", \"previous_token\", \"previous_token_expired_at\") VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\", \"allowed_cidrs\", \"scopes\", \"previous_token\", \"previous_token_expired_at\" FROM \"tokens\" WHERE \"id\" = $1";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\", \"allowed_cidrs\", \"scopes\", \"previous_token\", \"previous_token_expired_at\" FROM \"tokens\" WHERE \"admin_id\" = $1";
+pub const SELECT_BY_TOKEN: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\", \"allowed_cidrs\", \"scopes\", \"previous_token\", \"previous_token_expired_at\" FROM \"tokens\" WHERE \"token\" = $1";
+pub const SELECT_BY_PREVIOUS_TOKEN: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"token\", \"rules\", \"expired_at\", \"allowed_cidrs\", \"scopes\", \"previous_token\", \"previous_token_expired_at\" FROM \"tokens\" WHERE \"previous_token\" = $1";
+pub const UPDATE: &str = "UPDATE \"tokens\" SET \"updated_at\" = $1, \"rules\" = $2, \"expired_at\" = $3, \"allowed_cidrs\" = $4, \"scopes\" = $5 WHERE \"id\" = $6";
+pub const UPDATE_ADMIN_ID: &str = "UPDATE \"tokens\" SET \"updated_at\" = $1, \"admin_id\" = $2 WHERE \"id\" = $3";
+pub const UPDATE_TOKEN: &str = "UPDATE \"tokens\" SET \"updated_at\" = $1, \"token\" = $2, \"previous_token\" = $3, \"previous_token_expired_at\" = $4 WHERE \"id\" = $5";
 pub const DELETE: &str = "DELETE FROM \"tokens\" WHERE \"id\" = $1";
 
 pub async fn init(pool: &Pool<Postgres>) {
     hb_log::info(Some("🔧"), "PostgreSQL: Setting up tokens table");
 
-    pool.execute("CREATE TABLE IF NOT EXISTS \"tokens\" (\"id\" uuid, \"created_at\" timestamptz, \"updated_at\" timestamptz, \"admin_id\" uuid, \"token\" text, \"rules\" jsonb, \"expired_at\" timestamptz, PRIMARY KEY (\"id\"))").await.unwrap();
-
     pool.prepare(INSERT).await.unwrap();
     pool.prepare(SELECT).await.unwrap();
     pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
     pool.prepare(SELECT_BY_TOKEN).await.unwrap();
+    pool.prepare(SELECT_BY_PREVIOUS_TOKEN).await.unwrap();
     pool.prepare(UPDATE).await.unwrap();
+    pool.prepare(UPDATE_ADMIN_ID).await.unwrap();
+    pool.prepare(UPDATE_TOKEN).await.unwrap();
     pool.prepare(DELETE).await.unwrap();
 }
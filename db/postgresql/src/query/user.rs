@@ -0,0 +1,19 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const INSERT: &str = "INSERT INTO \"users\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\") VALUES ($1, $2, $3, $4, $5, $6)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"users\" WHERE \"id\" = $1";
+pub const SELECT_BY_PROJECT_ID_AND_EMAIL: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"users\" WHERE \"project_id\" = $1 AND \"email\" = $2";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"users\" WHERE \"project_id\" = $1 ORDER BY \"created_at\" DESC";
+pub const UPDATE: &str = "UPDATE \"users\" SET \"updated_at\" = $1, \"email\" = $2, \"password_hash\" = $3 WHERE \"id\" = $4";
+pub const DELETE: &str = "DELETE FROM \"users\" WHERE \"id\" = $1";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(Some("🔧"), "PostgreSQL: Setting up users table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_BY_PROJECT_ID_AND_EMAIL).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+    pool.prepare(UPDATE).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
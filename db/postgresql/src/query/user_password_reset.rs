@@ -0,0 +1,24 @@
+use sqlx::{Executor, Pool, Postgres};
+
+pub const INSERT: &str = "INSERT INTO \"user_password_resets\" (\"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\") VALUES ($1, $2, $3, $4, $5)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\" FROM \"user_password_resets\" WHERE \"id\" = $1 AND \"updated_at\" >= $2";
+pub const SELECT_MANY_BY_USER_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\" FROM \"user_password_resets\" WHERE \"user_id\" = $1";
+pub const UPDATE: &str = "UPDATE \"user_password_resets\" SET \"updated_at\" = $1, \"code\" = $2 WHERE \"id\" = $3 AND \"updated_at\" >= $4";
+pub const DELETE: &str = "DELETE FROM \"user_password_resets\" WHERE \"id\" = $1";
+pub const DELETE_EXPIRED: &str = "DELETE FROM \"user_password_resets\" WHERE \"updated_at\" < $1";
+pub const DELETE_MANY_BY_USER_ID: &str = "DELETE FROM \"user_password_resets\" WHERE \"user_id\" = $1";
+
+pub async fn init(pool: &Pool<Postgres>) {
+    hb_log::info(
+        Some("🔧"),
+        "PostgreSQL: Setting up user_password_resets table",
+    );
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_USER_ID).await.unwrap();
+    pool.prepare(UPDATE).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+    pool.prepare(DELETE_EXPIRED).await.unwrap();
+    pool.prepare(DELETE_MANY_BY_USER_ID).await.unwrap();
+}
@@ -0,0 +1,353 @@
+use itertools::Itertools;
+
+pub trait Dialect {
+    const IDENT_QUOTE: char;
+    const RANDOM: &'static str = "RANDOM()";
+
+    // Positional dialects (`?`) ignore the index, numbered dialects (`$1`) start at 1
+    fn placeholder(idx: usize) -> String;
+
+    fn ident(ident: &str) -> String {
+        let quote = Self::IDENT_QUOTE;
+        format!(
+            "{quote}{}{quote}",
+            ident.replace(quote, &format!("{quote}{quote}"))
+        )
+    }
+
+    fn table(record_table: &str) -> String {
+        Self::ident(record_table)
+    }
+}
+
+pub fn columns<D: Dialect>(columns: &[&str]) -> String {
+    columns.iter().map(|col| D::ident(col)).join(", ")
+}
+
+pub fn placeholders<D: Dialect>(offset: usize, count: usize) -> String {
+    (offset + 1..=offset + count)
+        .map(|idx| D::placeholder(idx))
+        .join(", ")
+}
+
+pub fn assignments<D: Dialect>(columns: &[&str], offset: usize, separator: &str) -> String {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(idx, col)| format!("{} = {}", D::ident(col), D::placeholder(offset + idx + 1)))
+        .join(separator)
+}
+
+pub fn insert<D: Dialect>(record_table: &str, columns: &[&str]) -> String {
+    format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        D::table(record_table),
+        self::columns::<D>(columns),
+        placeholders::<D>(0, columns.len())
+    )
+}
+
+pub fn insert_many<D: Dialect>(record_table: &str, columns: &[&str], rows: &usize) -> String {
+    format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        D::table(record_table),
+        self::columns::<D>(columns),
+        (0..*rows)
+            .map(|row| format!(
+                "({})",
+                placeholders::<D>(row * columns.len(), columns.len())
+            ))
+            .join(", ")
+    )
+}
+
+pub fn select<D: Dialect>(record_table: &str, columns: &[&str]) -> String {
+    format!(
+        "SELECT {} FROM {} WHERE {} = {}",
+        self::columns::<D>(columns),
+        D::table(record_table),
+        D::ident("_id"),
+        D::placeholder(1)
+    )
+}
+
+pub fn select_many<D: Dialect>(
+    record_table: &str,
+    columns: &[&str],
+    filter: &str,
+    groups: &[&str],
+    orders: &[(&str, &str)],
+    random: bool,
+    limit_idx: &Option<usize>,
+) -> String {
+    let mut query = format!(
+        "SELECT {} FROM {}",
+        self::columns::<D>(columns),
+        D::table(record_table)
+    );
+    if !filter.is_empty() {
+        query += &format!(" WHERE {filter}");
+    }
+    if !groups.is_empty() {
+        query += &format!(" GROUP BY {}", self::columns::<D>(groups));
+    }
+    if random {
+        query += &format!(" ORDER BY {}", D::RANDOM);
+    } else if !orders.is_empty() {
+        query += &format!(
+            " ORDER BY {}",
+            orders
+                .iter()
+                .map(|(field, kind)| format!("{} {kind}", D::ident(field)))
+                .join(", ")
+        );
+    }
+    if let Some(limit_idx) = limit_idx {
+        query += &format!(" LIMIT {}", D::placeholder(*limit_idx));
+    }
+    query
+}
+
+pub fn update<D: Dialect>(
+    record_table: &str,
+    columns: &[&str],
+    increment_columns: &[&str],
+    versioned: bool,
+) -> String {
+    let mut set = assignments::<D>(columns, 0, ", ");
+    for (idx, col) in increment_columns.iter().enumerate() {
+        if !set.is_empty() {
+            set += ", ";
+        }
+        set += &format!(
+            "{0} = COALESCE({0}, 0) + {1}",
+            D::ident(col),
+            D::placeholder(columns.len() + idx + 1)
+        );
+    }
+    let id_idx = columns.len() + increment_columns.len() + 1;
+    let mut query = format!(
+        "UPDATE {} SET {} WHERE {} = {}",
+        D::table(record_table),
+        set,
+        D::ident("_id"),
+        D::placeholder(id_idx)
+    );
+    if versioned {
+        query += &format!(
+            " AND COALESCE({}, 0) = {}",
+            D::ident("_version"),
+            D::placeholder(id_idx + 1)
+        );
+    }
+    query
+}
+
+pub fn delete<D: Dialect>(record_table: &str, columns: &[&str]) -> String {
+    format!(
+        "DELETE FROM {} WHERE {}",
+        D::table(record_table),
+        assignments::<D>(columns, 0, " AND ")
+    )
+}
+
+pub fn delete_with_version<D: Dialect>(record_table: &str) -> String {
+    format!(
+        "DELETE FROM {} WHERE {} = {} AND COALESCE({}, 0) = {}",
+        D::table(record_table),
+        D::ident("_id"),
+        D::placeholder(1),
+        D::ident("_version"),
+        D::placeholder(2)
+    )
+}
+
+pub fn delete_before_id<D: Dialect>(record_table: &str) -> String {
+    format!(
+        "DELETE FROM {} WHERE {} < {}",
+        D::table(record_table),
+        D::ident("_id"),
+        D::placeholder(1)
+    )
+}
+
+pub fn count<D: Dialect>(record_table: &str, filter: &str) -> String {
+    let mut query = format!("SELECT COUNT(1) FROM {}", D::table(record_table));
+    if !filter.is_empty() {
+        query += &format!(" WHERE {filter}");
+    }
+    query
+}
+
+pub fn count_groups<D: Dialect>(record_table: &str, groups: &[&str], filter: &str) -> String {
+    let mut query = format!(
+        "SELECT {}, COUNT(1) AS {} FROM {}",
+        self::columns::<D>(groups),
+        D::ident("_count"),
+        D::table(record_table)
+    );
+    if !filter.is_empty() {
+        query += &format!(" WHERE {filter}");
+    }
+    query += &format!(" GROUP BY {}", self::columns::<D>(groups));
+    query
+}
+
+pub fn select_distinct<D: Dialect>(
+    record_table: &str,
+    column: &str,
+    filter: &str,
+    limit_idx: &Option<usize>,
+) -> String {
+    let mut query = format!(
+        "SELECT DISTINCT {} FROM {}",
+        D::ident(column),
+        D::table(record_table)
+    );
+    if !filter.is_empty() {
+        query += &format!(" WHERE {filter}");
+    }
+    query += &format!(" ORDER BY {}", D::ident(column));
+    if let Some(limit_idx) = limit_idx {
+        query += &format!(" LIMIT {}", D::placeholder(*limit_idx));
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dialect;
+
+    struct PostgresDialect;
+
+    impl Dialect for PostgresDialect {
+        const IDENT_QUOTE: char = '"';
+
+        fn placeholder(idx: usize) -> String {
+            format!("${idx}")
+        }
+    }
+
+    struct MysqlDialect;
+
+    impl Dialect for MysqlDialect {
+        const IDENT_QUOTE: char = '`';
+        const RANDOM: &'static str = "RAND()";
+
+        fn placeholder(_idx: usize) -> String {
+            "?".to_owned()
+        }
+    }
+
+    struct SqliteDialect;
+
+    impl Dialect for SqliteDialect {
+        const IDENT_QUOTE: char = '"';
+
+        fn placeholder(_idx: usize) -> String {
+            "?".to_owned()
+        }
+    }
+
+    struct ScyllaDialect;
+
+    impl Dialect for ScyllaDialect {
+        const IDENT_QUOTE: char = '"';
+
+        fn placeholder(_idx: usize) -> String {
+            "?".to_owned()
+        }
+
+        fn table(record_table: &str) -> String {
+            format!("\"hyperbase\".{}", Self::ident(record_table))
+        }
+    }
+
+    const COLUMNS: [&str; 5] = ["a", "b\"c", "d`e", "f g", "h"];
+
+    // Numbered placeholders must run from 1 to the number of bound values without gaps or
+    // repeats, positional ones only need the right count
+    fn assert_placeholders<D: Dialect>(query: &str, expected: usize) {
+        if D::placeholder(1) == D::placeholder(2) {
+            assert_eq!(
+                query.matches(&D::placeholder(1)).count(),
+                expected,
+                "{query}"
+            );
+            return;
+        }
+        let mut indexes = Vec::new();
+        let mut rest = query;
+        while let Some(start) = rest.find('$') {
+            rest = &rest[start + 1..];
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            indexes.push(rest[..end].parse::<usize>().unwrap());
+            rest = &rest[end..];
+        }
+        indexes.sort_unstable();
+        assert_eq!(indexes, (1..=expected).collect::<Vec<_>>(), "{query}");
+    }
+
+    fn check_dialect<D: Dialect>() {
+        for len in 1..=COLUMNS.len() {
+            let columns = &COLUMNS[..len];
+
+            assert_placeholders::<D>(&super::insert::<D>("t", columns), len);
+            assert_placeholders::<D>(&super::select::<D>("t", columns), 1);
+            assert_placeholders::<D>(&super::delete::<D>("t", columns), len);
+
+            for rows in 1..=4 {
+                assert_placeholders::<D>(&super::insert_many::<D>("t", columns, &rows), len * rows);
+            }
+
+            for increments in 0..=2 {
+                let increment_columns = &["x", "y"][..increments];
+                assert_placeholders::<D>(
+                    &super::update::<D>("t", columns, increment_columns, false),
+                    len + increments + 1,
+                );
+                assert_placeholders::<D>(
+                    &super::update::<D>("t", columns, increment_columns, true),
+                    len + increments + 2,
+                );
+            }
+
+            let filter = super::assignments::<D>(columns, 0, " AND ");
+            assert_placeholders::<D>(&super::count::<D>("t", &filter), len);
+        }
+
+        assert_placeholders::<D>(&super::count::<D>("t", ""), 0);
+        assert_placeholders::<D>(&super::delete_with_version::<D>("t"), 2);
+        assert_placeholders::<D>(&super::delete_before_id::<D>("t"), 1);
+    }
+
+    #[test]
+    fn postgresql_placeholders_match_bound_values() {
+        check_dialect::<PostgresDialect>();
+    }
+
+    #[test]
+    fn mysql_placeholders_match_bound_values() {
+        check_dialect::<MysqlDialect>();
+    }
+
+    #[test]
+    fn sqlite_placeholders_match_bound_values() {
+        check_dialect::<SqliteDialect>();
+    }
+
+    #[test]
+    fn scylladb_placeholders_match_bound_values() {
+        check_dialect::<ScyllaDialect>();
+    }
+
+    #[test]
+    fn insert_many_numbers_rows_consecutively() {
+        assert_eq!(
+            super::insert_many::<PostgresDialect>("t", &["a", "b"], &2),
+            "INSERT INTO \"t\" (\"a\", \"b\") VALUES ($1, $2), ($3, $4)"
+        );
+    }
+}
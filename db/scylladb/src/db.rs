@@ -1,15 +1,119 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+use ahash::{HashMap, HashMapExt};
 use scylla::{
-    serialize::row::SerializeRow,
+    batch::Batch,
+    prepared_statement::PreparedStatement,
+    serialize::{batch::BatchValues, row::SerializeRow},
+    statement::Consistency,
     transport::{errors::QueryError, iterator::RowIterator},
-    Bytes, CachingSession, QueryResult, SessionBuilder,
+    Bytes, QueryResult, Session, SessionBuilder,
 };
 
+use tracing::{Instrument, Span};
+
 use crate::query::{
-    admin, admin_password_reset, collection, keyspace, project, registration, token,
+    admin, admin_password_reset, admin_refresh_token, admin_session, audit_log, collection,
+    collection_plugin, jwt_revocation, keyspace, migration, project, project_member,
+    project_transfer, project_usage, registration, system, token, user, user_password_reset,
 };
 
+const SYSTEM_STATEMENTS: [&str; 80] = [
+    admin::INSERT,
+    admin::SELECT,
+    admin::SELECT_BY_EMAIL,
+    admin::SELECT_MANY,
+    admin::UPDATE,
+    admin::DELETE,
+    admin_password_reset::INSERT,
+    admin_password_reset::SELECT,
+    admin_password_reset::SELECT_MANY_BY_ADMIN_ID,
+    admin_password_reset::UPDATE,
+    admin_password_reset::DELETE,
+    admin_refresh_token::INSERT,
+    admin_refresh_token::SELECT_BY_TOKEN,
+    admin_refresh_token::DELETE,
+    admin_session::INSERT,
+    admin_session::SELECT,
+    admin_session::SELECT_MANY_BY_ADMIN_ID,
+    admin_session::UPDATE_EXPIRED_AT,
+    admin_session::DELETE,
+    audit_log::INSERT,
+    audit_log::SELECT_MANY_BY_ADMIN_ID,
+    audit_log::SELECT_MANY_BY_PROJECT_ID,
+    collection::INSERT,
+    collection::SELECT,
+    collection::SELECT_MANY,
+    collection::SELECT_MANY_BY_PROJECT_ID,
+    collection::UPDATE,
+    collection::DELETE,
+    collection_plugin::UPSERT,
+    collection_plugin::SELECT,
+    collection_plugin::DELETE,
+    jwt_revocation::INSERT,
+    jwt_revocation::COUNT,
+    project::INSERT,
+    project::SELECT,
+    project::SELECT_MANY_BY_ADMIN_ID,
+    project::SELECT_MANY_DELETED_BEFORE,
+    project::UPDATE,
+    project::UPDATE_ADMIN_ID,
+    project::UPDATE_DELETED_AT,
+    project::DELETE,
+    project_member::INSERT,
+    project_member::SELECT,
+    project_member::SELECT_MANY_BY_PROJECT_ID,
+    project_member::SELECT_MANY_BY_ADMIN_ID,
+    project_member::UPDATE,
+    project_member::DELETE,
+    project_transfer::INSERT,
+    project_transfer::SELECT,
+    project_transfer::SELECT_MANY_BY_TO_ADMIN_ID,
+    project_transfer::DELETE,
+    project_usage::UPSERT,
+    project_usage::SELECT_MANY_BY_PROJECT_ID,
+    registration::INSERT,
+    registration::SELECT,
+    registration::SELECT_BY_EMAIL,
+    registration::UPDATE,
+    registration::DELETE,
+    system::COUNT_TABLE,
+    system::COUNT_COLUMN,
+    token::INSERT,
+    token::SELECT,
+    token::SELECT_MANY_BY_ADMIN_ID,
+    token::SELECT_BY_TOKEN,
+    token::SELECT_BY_PREVIOUS_TOKEN,
+    token::UPDATE,
+    token::UPDATE_ADMIN_ID,
+    token::UPDATE_TOKEN,
+    token::DELETE,
+    user::INSERT,
+    user::SELECT,
+    user::SELECT_BY_PROJECT_ID_AND_EMAIL,
+    user::SELECT_MANY_BY_PROJECT_ID,
+    user::UPDATE,
+    user::DELETE,
+    user_password_reset::INSERT,
+    user_password_reset::SELECT,
+    user_password_reset::SELECT_MANY_BY_USER_ID,
+    user_password_reset::UPDATE,
+    user_password_reset::DELETE,
+];
+
 pub struct ScyllaDb {
-    cached_session: CachingSession,
+    session: Session,
+    statement_cache: Box<StatementCache>,
+    read_consistency: Option<Consistency>,
+    write_consistency: Option<Consistency>,
+    slow_query_threshold: Option<Duration>,
 }
 
 impl ScyllaDb {
@@ -18,30 +122,50 @@ impl ScyllaDb {
         port: &str,
         replication_factor: &i64,
         cache_size: &usize,
+        read_consistency: &Option<String>,
+        write_consistency: &Option<String>,
         table_registration_ttl: &u32,
         table_reset_password_ttl: &u32,
+        slow_query_threshold: &Option<Duration>,
     ) -> Self {
         hb_log::info(Some("⚡"), "ScyllaDB: Initializing component");
 
         let hostname = format!("{host}:{port}");
-        let cached_session: CachingSession = CachingSession::from(
-            SessionBuilder::new()
-                .known_node(&hostname)
-                .build()
-                .await
-                .unwrap(),
-            *cache_size,
-        );
+        let session = SessionBuilder::new()
+            .known_node(&hostname)
+            .build()
+            .await
+            .unwrap();
 
         Self::init(
-            &cached_session,
+            &session,
             replication_factor,
             table_registration_ttl,
             table_reset_password_ttl,
         )
         .await;
 
-        Self { cached_session }
+        let mut scylla_db = Self {
+            session,
+            statement_cache: Box::new(StatementCache {
+                system_statements: HashMap::with_capacity(SYSTEM_STATEMENTS.len()),
+                statements: Mutex::new(HashMap::with_capacity(*cache_size)),
+                capacity: *cache_size,
+                system_hits: AtomicU64::new(0),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                evictions: AtomicU64::new(0),
+            }),
+            read_consistency: read_consistency
+                .as_ref()
+                .map(|consistency| Self::parse_consistency(consistency)),
+            write_consistency: write_consistency
+                .as_ref()
+                .map(|consistency| Self::parse_consistency(consistency)),
+            slow_query_threshold: *slow_query_threshold,
+        };
+        scylla_db.prewarm().await;
+        scylla_db
     }
 
     pub async fn session_query(
@@ -49,7 +173,7 @@ impl ScyllaDb {
         query: &str,
         values: impl SerializeRow,
     ) -> Result<QueryResult, QueryError> {
-        self.cached_session.get_session().query(query, values).await
+        self.observe(query, self.session.query(query, values)).await
     }
 
     pub async fn execute(
@@ -57,7 +181,56 @@ impl ScyllaDb {
         query: &str,
         values: impl SerializeRow,
     ) -> Result<QueryResult, QueryError> {
-        self.cached_session.execute(query, values).await
+        let prepared_statement = self.prepared_statement(query).await?;
+        self.observe(query, self.session.execute(&prepared_statement, values))
+            .await
+    }
+
+    pub async fn batch(
+        &self,
+        queries: &[&str],
+        values: impl BatchValues,
+    ) -> Result<QueryResult, QueryError> {
+        let mut batch = Batch::default();
+        for query in queries {
+            batch.append_statement(self.prepared_statement(query).await?);
+        }
+        if let Some(consistency) = self.write_consistency {
+            batch.set_consistency(consistency);
+        }
+        let started_at = Instant::now();
+        let output = self
+            .session
+            .batch(&batch, values)
+            .instrument(tracing::debug_span!(
+                "db.batch",
+                db.system = "scylladb",
+                db.statements = queries.len()
+            ))
+            .await;
+        if let Some(slow_query_threshold) = &self.slow_query_threshold {
+            let elapsed = started_at.elapsed();
+            if elapsed >= *slow_query_threshold {
+                hb_log::warn(
+                    None,
+                    format!(
+                        "ScyllaDB: Slow batch of {} statements on tables [{}] took {} ms with {} bound parameters",
+                        queries.len(),
+                        queries
+                            .iter()
+                            .map(|query| table_name(query).unwrap_or("-"))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        elapsed.as_millis(),
+                        queries
+                            .iter()
+                            .map(|query| bound_parameter_count(query))
+                            .sum::<usize>(),
+                    ),
+                );
+            }
+        }
+        output
     }
 
     pub async fn execute_iter(
@@ -65,7 +238,9 @@ impl ScyllaDb {
         query: &str,
         values: impl SerializeRow,
     ) -> Result<RowIterator, QueryError> {
-        self.cached_session.execute_iter(query, values).await
+        let prepared_statement = self.prepared_statement(query).await?;
+        self.observe(query, self.session.execute_iter(prepared_statement, values))
+            .await
     }
 
     pub async fn execute_paged(
@@ -74,26 +249,217 @@ impl ScyllaDb {
         values: impl SerializeRow,
         paging_state: Option<Bytes>,
     ) -> Result<QueryResult, QueryError> {
-        self.cached_session
-            .execute_paged(query, values, paging_state)
-            .await
+        let prepared_statement = self.prepared_statement(query).await?;
+        self.observe(
+            query,
+            self.session
+                .execute_paged(&prepared_statement, values, paging_state),
+        )
+        .await
+    }
+
+    async fn observe<T>(&self, statement: &str, query: impl Future<Output = T>) -> T {
+        let started_at = Instant::now();
+        let output = query.instrument(query_span(statement)).await;
+        if let Some(slow_query_threshold) = &self.slow_query_threshold {
+            let elapsed = started_at.elapsed();
+            if elapsed >= *slow_query_threshold {
+                hb_log::warn(
+                    None,
+                    format!(
+                        "ScyllaDB: Slow query on table '{}' took {} ms with {} bound parameters: {statement}",
+                        table_name(statement).unwrap_or("-"),
+                        elapsed.as_millis(),
+                        bound_parameter_count(statement),
+                    ),
+                );
+            }
+        }
+        output
+    }
+
+    pub fn statement_cache_metrics(&self) -> StatementCacheMetrics {
+        let statement_cache = &self.statement_cache;
+        StatementCacheMetrics {
+            system_statements: statement_cache.system_statements.len(),
+            cached_statements: self.lock_statement_cache().len(),
+            capacity: statement_cache.capacity,
+            system_hits: statement_cache.system_hits.load(Ordering::Relaxed),
+            hits: statement_cache.hits.load(Ordering::Relaxed),
+            misses: statement_cache.misses.load(Ordering::Relaxed),
+            evictions: statement_cache.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn prewarm(&mut self) {
+        hb_log::info(Some("🔧"), "ScyllaDB: Preparing system statements");
+
+        for query in SYSTEM_STATEMENTS {
+            let statement = self.prepare(query).await.unwrap();
+            self.statement_cache
+                .system_statements
+                .insert(query.to_owned(), statement);
+        }
+    }
+
+    async fn prepared_statement(&self, query: &str) -> Result<PreparedStatement, QueryError> {
+        let statement_cache = &self.statement_cache;
+        if let Some(statement) = statement_cache.system_statements.get(query) {
+            statement_cache.system_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(statement.clone());
+        }
+
+        if let Some(statement) = self.lock_statement_cache().get(query) {
+            statement_cache.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(statement.clone());
+        }
+        statement_cache.misses.fetch_add(1, Ordering::Relaxed);
+
+        let statement = self.prepare(query).await?;
+
+        let mut statements = self.lock_statement_cache();
+        if !statements.contains_key(query) && statements.len() >= statement_cache.capacity {
+            let evicted = statements.keys().next().cloned();
+            if let Some(evicted) = evicted {
+                statements.remove(&evicted);
+                statement_cache.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        if statement_cache.capacity > 0 {
+            statements.insert(query.to_owned(), statement.clone());
+        }
+
+        Ok(statement)
+    }
+
+    async fn prepare(&self, query: &str) -> Result<PreparedStatement, QueryError> {
+        let mut statement = self.session.prepare(query).await?;
+        let consistency = if query.trim_start().starts_with("SELECT") {
+            self.read_consistency
+        } else {
+            self.write_consistency
+        };
+        if let Some(consistency) = consistency {
+            statement.set_consistency(consistency);
+        }
+        Ok(statement)
+    }
+
+    fn lock_statement_cache(&self) -> MutexGuard<'_, HashMap<String, PreparedStatement>> {
+        match self.statement_cache.statements.lock() {
+            Ok(statement_cache) => statement_cache,
+            Err(err) => err.into_inner(),
+        }
+    }
+
+    fn parse_consistency(consistency: &str) -> Consistency {
+        match consistency.to_uppercase().as_str() {
+            "ANY" => Consistency::Any,
+            "ONE" => Consistency::One,
+            "TWO" => Consistency::Two,
+            "THREE" => Consistency::Three,
+            "QUORUM" => Consistency::Quorum,
+            "ALL" => Consistency::All,
+            "LOCAL_QUORUM" => Consistency::LocalQuorum,
+            "EACH_QUORUM" => Consistency::EachQuorum,
+            "LOCAL_ONE" => Consistency::LocalOne,
+            _ => panic!("ScyllaDB consistency level '{consistency}' is not supported"),
+        }
     }
 
     async fn init(
-        cached_session: &CachingSession,
+        session: &Session,
         replication_factor: &i64,
         table_registration_ttl: &u32,
         table_reset_password_ttl: &u32,
     ) {
         // Create keyspace
-        keyspace::init(cached_session, replication_factor).await;
+        keyspace::init(session, replication_factor).await;
 
-        // Create tables
-        admin::init(cached_session).await;
-        token::init(cached_session).await;
-        project::init(cached_session).await;
-        collection::init(cached_session).await;
-        registration::init(cached_session, table_registration_ttl).await;
-        admin_password_reset::init(cached_session, table_reset_password_ttl).await;
+        // Create and migrate tables
+        migration::init(session).await;
+
+        // Apply the configured TTLs
+        registration::init(session, table_registration_ttl).await;
+        admin_password_reset::init(session, table_reset_password_ttl).await;
+        user_password_reset::init(session, table_reset_password_ttl).await;
     }
 }
+
+struct StatementCache {
+    system_statements: HashMap<String, PreparedStatement>,
+    statements: Mutex<HashMap<String, PreparedStatement>>,
+    capacity: usize,
+    system_hits: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+pub struct StatementCacheMetrics {
+    system_statements: usize,
+    cached_statements: usize,
+    capacity: usize,
+    system_hits: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl StatementCacheMetrics {
+    pub fn system_statements(&self) -> &usize {
+        &self.system_statements
+    }
+
+    pub fn cached_statements(&self) -> &usize {
+        &self.cached_statements
+    }
+
+    pub fn capacity(&self) -> &usize {
+        &self.capacity
+    }
+
+    pub fn system_hits(&self) -> &u64 {
+        &self.system_hits
+    }
+
+    pub fn hits(&self) -> &u64 {
+        &self.hits
+    }
+
+    pub fn misses(&self) -> &u64 {
+        &self.misses
+    }
+
+    pub fn evictions(&self) -> &u64 {
+        &self.evictions
+    }
+}
+
+fn query_span(statement: &str) -> Span {
+    tracing::debug_span!("db.query", db.system = "scylladb", db.statement = statement)
+}
+
+// Takes the identifier following the first FROM, INTO, UPDATE, or TABLE keyword
+fn table_name(statement: &str) -> Option<&str> {
+    let mut words = statement.split_whitespace();
+    while let Some(word) = words.next() {
+        if ["FROM", "INTO", "UPDATE", "TABLE"]
+            .iter()
+            .any(|keyword| word.eq_ignore_ascii_case(keyword))
+        {
+            return words
+                .find(|word| {
+                    !["IF", "NOT", "EXISTS"]
+                        .iter()
+                        .any(|keyword| word.eq_ignore_ascii_case(keyword))
+                })
+                .map(|word| word.trim_matches(|c| matches!(c, '"' | '`' | '(' | ')' | ';')));
+        }
+    }
+    None
+}
+
+fn bound_parameter_count(statement: &str) -> usize {
+    statement.matches('?').count()
+}
@@ -1,8 +1,18 @@
 pub mod admin;
 pub mod admin_password_reset;
+pub mod admin_refresh_token;
+pub mod admin_session;
+pub mod audit_log;
 pub mod collection;
+pub mod collection_plugin;
+pub mod jwt_revocation;
 pub mod project;
+pub mod project_member;
+pub mod project_transfer;
+pub mod project_usage;
 pub mod registration;
 pub mod system;
 pub mod token;
+pub mod user;
+pub mod user_password_reset;
 pub mod value;
@@ -8,6 +8,7 @@ pub struct AdminPasswordResetModel {
     updated_at: CqlTimestamp,
     admin_id: Uuid,
     code: String,
+    expired_at: CqlTimestamp,
 }
 
 impl AdminPasswordResetModel {
@@ -17,6 +18,7 @@ impl AdminPasswordResetModel {
         updated_at: &CqlTimestamp,
         admin_id: &Uuid,
         code: &str,
+        expired_at: &CqlTimestamp,
     ) -> Self {
         Self {
             id: *id,
@@ -24,6 +26,7 @@ impl AdminPasswordResetModel {
             updated_at: *updated_at,
             admin_id: *admin_id,
             code: code.to_owned(),
+            expired_at: *expired_at,
         }
     }
 
@@ -46,4 +49,8 @@ impl AdminPasswordResetModel {
     pub fn code(&self) -> &str {
         &self.code
     }
+
+    pub fn expired_at(&self) -> &CqlTimestamp {
+        &self.expired_at
+    }
 }
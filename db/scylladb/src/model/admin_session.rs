@@ -0,0 +1,63 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct AdminSessionModel {
+    id: Uuid,
+    created_at: CqlTimestamp,
+    updated_at: CqlTimestamp,
+    admin_id: Uuid,
+    user_agent: Option<String>,
+    ip: Option<String>,
+    expired_at: CqlTimestamp,
+}
+
+impl AdminSessionModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &CqlTimestamp,
+        updated_at: &CqlTimestamp,
+        admin_id: &Uuid,
+        user_agent: &Option<String>,
+        ip: &Option<String>,
+        expired_at: &CqlTimestamp,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            admin_id: *admin_id,
+            user_agent: user_agent.clone(),
+            ip: ip.clone(),
+            expired_at: *expired_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &CqlTimestamp {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &CqlTimestamp {
+        &self.updated_at
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn user_agent(&self) -> &Option<String> {
+        &self.user_agent
+    }
+
+    pub fn ip(&self) -> &Option<String> {
+        &self.ip
+    }
+
+    pub fn expired_at(&self) -> &CqlTimestamp {
+        &self.expired_at
+    }
+}
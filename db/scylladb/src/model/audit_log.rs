@@ -0,0 +1,84 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct AuditLogModel {
+    id: Uuid,
+    created_at: CqlTimestamp,
+    admin_id: Uuid,
+    project_id: Option<Uuid>,
+    ip: Option<String>,
+    action: String,
+    resource: String,
+    resource_id: Uuid,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl AuditLogModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &CqlTimestamp,
+        admin_id: &Uuid,
+        project_id: &Option<Uuid>,
+        ip: &Option<String>,
+        action: &str,
+        resource: &str,
+        resource_id: &Uuid,
+        before: &Option<String>,
+        after: &Option<String>,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            admin_id: *admin_id,
+            project_id: *project_id,
+            ip: ip.clone(),
+            action: action.to_owned(),
+            resource: resource.to_owned(),
+            resource_id: *resource_id,
+            before: before.clone(),
+            after: after.clone(),
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &CqlTimestamp {
+        &self.created_at
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn project_id(&self) -> &Option<Uuid> {
+        &self.project_id
+    }
+
+    pub fn ip(&self) -> &Option<String> {
+        &self.ip
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+
+    pub fn resource_id(&self) -> &Uuid {
+        &self.resource_id
+    }
+
+    pub fn before(&self) -> &Option<String> {
+        &self.before
+    }
+
+    pub fn after(&self) -> &Option<String> {
+        &self.after
+    }
+}
@@ -13,6 +13,9 @@ pub struct CollectionModel {
     name: String,
     schema_fields: HashMap<String, SchemaFieldPropsModel>,
     indexes: Option<HashSet<String>>,
+    max_field_size: Option<i64>,
+    max_record_size: Option<i64>,
+    auto_delete_after_seconds: Option<i64>,
 }
 
 impl CollectionModel {
@@ -24,6 +27,9 @@ impl CollectionModel {
         name: &str,
         schema_fields: &HashMap<String, SchemaFieldPropsModel>,
         indexes: &Option<HashSet<String>>,
+        max_field_size: &Option<i64>,
+        max_record_size: &Option<i64>,
+        auto_delete_after_seconds: &Option<i64>,
     ) -> Self {
         Self {
             id: *id,
@@ -33,6 +39,9 @@ impl CollectionModel {
             name: name.to_owned(),
             schema_fields: schema_fields.clone(),
             indexes: indexes.clone(),
+            max_field_size: *max_field_size,
+            max_record_size: *max_record_size,
+            auto_delete_after_seconds: *auto_delete_after_seconds,
         }
     }
 
@@ -63,6 +72,18 @@ impl CollectionModel {
     pub fn indexes(&self) -> &Option<HashSet<String>> {
         &self.indexes
     }
+
+    pub fn max_field_size(&self) -> &Option<i64> {
+        &self.max_field_size
+    }
+
+    pub fn max_record_size(&self) -> &Option<i64> {
+        &self.max_record_size
+    }
+
+    pub fn auto_delete_after_seconds(&self) -> &Option<i64> {
+        &self.auto_delete_after_seconds
+    }
 }
 
 #[derive(FromUserType, SerializeCql, Clone)]
@@ -70,14 +91,45 @@ pub struct SchemaFieldPropsModel {
     kind: String,
     internal_kind: ColumnKind,
     required: bool,
+    enum_variants: Option<Vec<String>>,
+    min_length: Option<i64>,
+    max_length: Option<i64>,
+    min: Option<f64>,
+    max: Option<f64>,
+    pattern: Option<String>,
+    computed_op: Option<String>,
+    computed_fields: Option<Vec<String>>,
+    computed_separator: Option<String>,
 }
 
 impl SchemaFieldPropsModel {
-    pub fn new(kind: &str, internal_kind: &ColumnKind, required: &bool) -> Self {
+    pub fn new(
+        kind: &str,
+        internal_kind: &ColumnKind,
+        required: &bool,
+        enum_variants: &Option<Vec<String>>,
+        min_length: &Option<i64>,
+        max_length: &Option<i64>,
+        min: &Option<f64>,
+        max: &Option<f64>,
+        pattern: &Option<String>,
+        computed_op: &Option<String>,
+        computed_fields: &Option<Vec<String>>,
+        computed_separator: &Option<String>,
+    ) -> Self {
         Self {
             kind: kind.to_owned(),
             internal_kind: *internal_kind,
             required: *required,
+            enum_variants: enum_variants.clone(),
+            min_length: *min_length,
+            max_length: *max_length,
+            min: *min,
+            max: *max,
+            pattern: pattern.clone(),
+            computed_op: computed_op.clone(),
+            computed_fields: computed_fields.clone(),
+            computed_separator: computed_separator.clone(),
         }
     }
 
@@ -92,4 +144,40 @@ impl SchemaFieldPropsModel {
     pub fn required(&self) -> &bool {
         &self.required
     }
+
+    pub fn enum_variants(&self) -> &Option<Vec<String>> {
+        &self.enum_variants
+    }
+
+    pub fn min_length(&self) -> &Option<i64> {
+        &self.min_length
+    }
+
+    pub fn max_length(&self) -> &Option<i64> {
+        &self.max_length
+    }
+
+    pub fn min(&self) -> &Option<f64> {
+        &self.min
+    }
+
+    pub fn max(&self) -> &Option<f64> {
+        &self.max
+    }
+
+    pub fn pattern(&self) -> &Option<String> {
+        &self.pattern
+    }
+
+    pub fn computed_op(&self) -> &Option<String> {
+        &self.computed_op
+    }
+
+    pub fn computed_fields(&self) -> &Option<Vec<String>> {
+        &self.computed_fields
+    }
+
+    pub fn computed_separator(&self) -> &Option<String> {
+        &self.computed_separator
+    }
 }
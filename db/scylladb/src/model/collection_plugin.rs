@@ -0,0 +1,42 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct CollectionPluginModel {
+    collection_id: Uuid,
+    created_at: CqlTimestamp,
+    updated_at: CqlTimestamp,
+    module: Vec<u8>,
+}
+
+impl CollectionPluginModel {
+    pub fn new(
+        collection_id: &Uuid,
+        created_at: &CqlTimestamp,
+        updated_at: &CqlTimestamp,
+        module: &[u8],
+    ) -> Self {
+        Self {
+            collection_id: *collection_id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            module: module.to_vec(),
+        }
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+
+    pub fn created_at(&self) -> &CqlTimestamp {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &CqlTimestamp {
+        &self.updated_at
+    }
+
+    pub fn module(&self) -> &[u8] {
+        &self.module
+    }
+}
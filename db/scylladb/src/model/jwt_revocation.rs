@@ -0,0 +1,31 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct JwtRevocationModel {
+    id: Uuid,
+    created_at: CqlTimestamp,
+    expired_at: CqlTimestamp,
+}
+
+impl JwtRevocationModel {
+    pub fn new(id: &Uuid, created_at: &CqlTimestamp, expired_at: &CqlTimestamp) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            expired_at: *expired_at,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &CqlTimestamp {
+        &self.created_at
+    }
+
+    pub fn expired_at(&self) -> &CqlTimestamp {
+        &self.expired_at
+    }
+}
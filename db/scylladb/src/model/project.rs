@@ -8,6 +8,10 @@ pub struct ProjectModel {
     updated_at: CqlTimestamp,
     admin_id: Uuid,
     name: String,
+    suspended: bool,
+    archived: bool,
+    token_claims: Option<String>,
+    deleted_at: Option<CqlTimestamp>,
 }
 
 impl ProjectModel {
@@ -17,6 +21,10 @@ impl ProjectModel {
         updated_at: &CqlTimestamp,
         admin_id: &Uuid,
         name: &str,
+        suspended: &bool,
+        archived: &bool,
+        token_claims: &Option<String>,
+        deleted_at: &Option<CqlTimestamp>,
     ) -> Self {
         Self {
             id: *id,
@@ -24,6 +32,10 @@ impl ProjectModel {
             updated_at: *updated_at,
             admin_id: *admin_id,
             name: name.to_owned(),
+            suspended: *suspended,
+            archived: *archived,
+            token_claims: token_claims.clone(),
+            deleted_at: *deleted_at,
         }
     }
 
@@ -46,4 +58,20 @@ impl ProjectModel {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn suspended(&self) -> &bool {
+        &self.suspended
+    }
+
+    pub fn archived(&self) -> &bool {
+        &self.archived
+    }
+
+    pub fn token_claims(&self) -> &Option<String> {
+        &self.token_claims
+    }
+
+    pub fn deleted_at(&self) -> &Option<CqlTimestamp> {
+        &self.deleted_at
+    }
 }
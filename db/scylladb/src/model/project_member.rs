@@ -0,0 +1,56 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct ProjectMemberModel {
+    id: Uuid,
+    created_at: CqlTimestamp,
+    updated_at: CqlTimestamp,
+    project_id: Uuid,
+    admin_id: Uuid,
+    role: String,
+}
+
+impl ProjectMemberModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &CqlTimestamp,
+        updated_at: &CqlTimestamp,
+        project_id: &Uuid,
+        admin_id: &Uuid,
+        role: &str,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            project_id: *project_id,
+            admin_id: *admin_id,
+            role: role.to_owned(),
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &CqlTimestamp {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &CqlTimestamp {
+        &self.updated_at
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn admin_id(&self) -> &Uuid {
+        &self.admin_id
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+}
@@ -0,0 +1,56 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct ProjectTransferModel {
+    id: Uuid,
+    created_at: CqlTimestamp,
+    updated_at: CqlTimestamp,
+    project_id: Uuid,
+    from_admin_id: Uuid,
+    to_admin_id: Uuid,
+}
+
+impl ProjectTransferModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &CqlTimestamp,
+        updated_at: &CqlTimestamp,
+        project_id: &Uuid,
+        from_admin_id: &Uuid,
+        to_admin_id: &Uuid,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            project_id: *project_id,
+            from_admin_id: *from_admin_id,
+            to_admin_id: *to_admin_id,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &CqlTimestamp {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &CqlTimestamp {
+        &self.updated_at
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn from_admin_id(&self) -> &Uuid {
+        &self.from_admin_id
+    }
+
+    pub fn to_admin_id(&self) -> &Uuid {
+        &self.to_admin_id
+    }
+}
@@ -0,0 +1,56 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct ProjectUsageModel {
+    project_id: Uuid,
+    period: CqlTimestamp,
+    updated_at: CqlTimestamp,
+    request_count: i64,
+    record_count: i64,
+    storage_bytes: i64,
+}
+
+impl ProjectUsageModel {
+    pub fn new(
+        project_id: &Uuid,
+        period: &CqlTimestamp,
+        updated_at: &CqlTimestamp,
+        request_count: &i64,
+        record_count: &i64,
+        storage_bytes: &i64,
+    ) -> Self {
+        Self {
+            project_id: *project_id,
+            period: *period,
+            updated_at: *updated_at,
+            request_count: *request_count,
+            record_count: *record_count,
+            storage_bytes: *storage_bytes,
+        }
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn period(&self) -> &CqlTimestamp {
+        &self.period
+    }
+
+    pub fn updated_at(&self) -> &CqlTimestamp {
+        &self.updated_at
+    }
+
+    pub fn request_count(&self) -> &i64 {
+        &self.request_count
+    }
+
+    pub fn record_count(&self) -> &i64 {
+        &self.record_count
+    }
+
+    pub fn storage_bytes(&self) -> &i64 {
+        &self.storage_bytes
+    }
+}
@@ -1,6 +1,22 @@
 pub const LOGICAL_OPERATOR: [&str; 1] = ["AND"];
 
-pub const COMPARISON_OPERATOR: [&str; 8] =
-    ["=", "<", ">", "<=", ">=", "IN", "CONTAINS", "CONTAINS KEY"];
+pub const COMPARISON_OPERATOR: [&str; 13] = [
+    "=",
+    "<",
+    ">",
+    "<=",
+    ">=",
+    "BETWEEN",
+    "IN",
+    "CONTAINS",
+    "CONTAINS KEY",
+    "LIKE",
+    "SUBSTRING",
+    "STARTS_WITH",
+    "ENDS_WITH",
+];
+
+// Comparison operators that take no value
+pub const NULLARY_OPERATOR: [&str; 0] = [];
 
 pub const ORDER_TYPE: [&str; 2] = ["ASC", "DESC"];
@@ -11,6 +11,10 @@ pub struct TokenModel {
     token: String,
     rules: Option<HashMap<Uuid, TokenRuleMethodModel>>,
     expired_at: Option<CqlTimestamp>,
+    allowed_cidrs: Option<Vec<String>>,
+    scopes: Option<Vec<String>>,
+    previous_token: Option<String>,
+    previous_token_expired_at: Option<CqlTimestamp>,
 }
 
 impl TokenModel {
@@ -22,6 +26,10 @@ impl TokenModel {
         token: &str,
         rules: &Option<HashMap<Uuid, TokenRuleMethodModel>>,
         expired_at: &Option<CqlTimestamp>,
+        allowed_cidrs: &Option<Vec<String>>,
+        scopes: &Option<Vec<String>>,
+        previous_token: &Option<String>,
+        previous_token_expired_at: &Option<CqlTimestamp>,
     ) -> Self {
         Self {
             id: *id,
@@ -31,6 +39,10 @@ impl TokenModel {
             token: token.to_owned(),
             rules: rules.clone(),
             expired_at: *expired_at,
+            allowed_cidrs: allowed_cidrs.clone(),
+            scopes: scopes.clone(),
+            previous_token: previous_token.clone(),
+            previous_token_expired_at: *previous_token_expired_at,
         }
     }
 
@@ -61,6 +73,22 @@ impl TokenModel {
     pub fn expired_at(&self) -> &Option<CqlTimestamp> {
         &self.expired_at
     }
+
+    pub fn allowed_cidrs(&self) -> &Option<Vec<String>> {
+        &self.allowed_cidrs
+    }
+
+    pub fn scopes(&self) -> &Option<Vec<String>> {
+        &self.scopes
+    }
+
+    pub fn previous_token(&self) -> &Option<String> {
+        &self.previous_token
+    }
+
+    pub fn previous_token_expired_at(&self) -> &Option<CqlTimestamp> {
+        &self.previous_token_expired_at
+    }
 }
 
 #[derive(FromUserType, SerializeCql, Clone)]
@@ -70,6 +98,7 @@ pub struct TokenRuleMethodModel {
     insert: bool,
     update: bool,
     delete: bool,
+    condition: Option<String>,
 }
 
 impl TokenRuleMethodModel {
@@ -79,6 +108,7 @@ impl TokenRuleMethodModel {
         insert: &bool,
         update: &bool,
         delete: &bool,
+        condition: &Option<String>,
     ) -> Self {
         Self {
             find_one: *find_one,
@@ -86,6 +116,7 @@ impl TokenRuleMethodModel {
             insert: *insert,
             update: *update,
             delete: *delete,
+            condition: condition.clone(),
         }
     }
 
@@ -108,4 +139,8 @@ impl TokenRuleMethodModel {
     pub fn delete(&self) -> &bool {
         &self.delete
     }
+
+    pub fn condition(&self) -> &Option<String> {
+        &self.condition
+    }
 }
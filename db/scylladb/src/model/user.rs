@@ -0,0 +1,56 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct UserModel {
+    id: Uuid,
+    created_at: CqlTimestamp,
+    updated_at: CqlTimestamp,
+    project_id: Uuid,
+    email: Option<String>,
+    password_hash: Option<String>,
+}
+
+impl UserModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &CqlTimestamp,
+        updated_at: &CqlTimestamp,
+        project_id: &Uuid,
+        email: &Option<String>,
+        password_hash: &Option<String>,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            project_id: *project_id,
+            email: email.clone(),
+            password_hash: password_hash.clone(),
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &CqlTimestamp {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &CqlTimestamp {
+        &self.updated_at
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn email(&self) -> &Option<String> {
+        &self.email
+    }
+
+    pub fn password_hash(&self) -> &Option<String> {
+        &self.password_hash
+    }
+}
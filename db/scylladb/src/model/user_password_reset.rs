@@ -0,0 +1,49 @@
+use scylla::{frame::value::CqlTimestamp, FromRow, SerializeRow};
+use uuid::Uuid;
+
+#[derive(FromRow, SerializeRow)]
+pub struct UserPasswordResetModel {
+    id: Uuid,
+    created_at: CqlTimestamp,
+    updated_at: CqlTimestamp,
+    user_id: Uuid,
+    code: String,
+}
+
+impl UserPasswordResetModel {
+    pub fn new(
+        id: &Uuid,
+        created_at: &CqlTimestamp,
+        updated_at: &CqlTimestamp,
+        user_id: &Uuid,
+        code: &str,
+    ) -> Self {
+        Self {
+            id: *id,
+            created_at: *created_at,
+            updated_at: *updated_at,
+            user_id: *user_id,
+            code: code.to_owned(),
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn created_at(&self) -> &CqlTimestamp {
+        &self.created_at
+    }
+
+    pub fn updated_at(&self) -> &CqlTimestamp {
+        &self.updated_at
+    }
+
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
@@ -0,0 +1,3 @@
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"admin_refresh_tokens\" (\"id\", \"created_at\", \"admin_id\", \"session_id\", \"token\", \"expired_at\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT_BY_TOKEN: &str = "SELECT \"id\", \"created_at\", \"admin_id\", \"session_id\", \"token\", \"expired_at\" FROM \"hyperbase\".\"admin_refresh_tokens\" WHERE \"token\" = ?";
+pub const DELETE: &str = "DELETE FROM \"hyperbase\".\"admin_refresh_tokens\" WHERE \"id\" = ?";
@@ -0,0 +1,5 @@
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"admin_sessions\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\") VALUES (?, ?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\" FROM \"hyperbase\".\"admin_sessions\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\" FROM \"hyperbase\".\"admin_sessions\" WHERE \"admin_id\" = ?";
+pub const UPDATE_EXPIRED_AT: &str = "UPDATE \"hyperbase\".\"admin_sessions\" SET \"updated_at\" = ?, \"expired_at\" = ? WHERE \"id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"hyperbase\".\"admin_sessions\" WHERE \"id\" = ?";
@@ -0,0 +1,3 @@
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"audit_logs\" (\"id\", \"created_at\", \"admin_id\", \"project_id\", \"ip\", \"action\", \"resource\", \"resource_id\", \"before\", \"after\") VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"admin_id\", \"project_id\", \"ip\", \"action\", \"resource\", \"resource_id\", \"before\", \"after\" FROM \"hyperbase\".\"audit_logs\" WHERE \"admin_id\" = ?";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"admin_id\", \"project_id\", \"ip\", \"action\", \"resource\", \"resource_id\", \"before\", \"after\" FROM \"hyperbase\".\"audit_logs\" WHERE \"project_id\" = ?";
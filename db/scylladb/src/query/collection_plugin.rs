@@ -0,0 +1,3 @@
+pub const UPSERT: &str = "INSERT INTO \"hyperbase\".\"collection_plugins\" (\"collection_id\", \"created_at\", \"updated_at\", \"module\") VALUES (?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"collection_id\", \"created_at\", \"updated_at\", \"module\" FROM \"hyperbase\".\"collection_plugins\" WHERE \"collection_id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"hyperbase\".\"collection_plugins\" WHERE \"collection_id\" = ?";
@@ -0,0 +1,2 @@
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"jwt_revocations\" (\"id\", \"created_at\", \"expired_at\") VALUES (?, ?, ?)";
+pub const COUNT: &str = "SELECT COUNT(1) FROM \"hyperbase\".\"jwt_revocations\" WHERE \"id\" = ?";
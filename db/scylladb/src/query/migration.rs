@@ -0,0 +1,372 @@
+use scylla::Session;
+use uuid::Uuid;
+
+use crate::query::system::{COUNT_COLUMN, SELECT_TYPE_FIELD_NAMES};
+
+pub const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS \"hyperbase\".\"schema_migrations\" (\"version\" bigint, \"name\" text, \"applied_at\" timestamp, PRIMARY KEY (\"version\"))";
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"schema_migrations\" (\"version\", \"name\", \"applied_at\") VALUES (?, ?, toTimestamp(now()))";
+pub const SELECT_MANY: &str = "SELECT \"version\" FROM \"hyperbase\".\"schema_migrations\"";
+
+pub enum Step {
+    Execute(&'static str),
+    // Skipped when the column already exists; existing rows are set to `default` when it is added
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        kind: &'static str,
+        default: Option<&'static str>,
+    },
+    // Skipped when the user-defined type already has the field
+    AddTypeField {
+        type_name: &'static str,
+        field: &'static str,
+        kind: &'static str,
+    },
+}
+
+pub struct Migration {
+    version: i64,
+    name: &'static str,
+    steps: &'static [Step],
+}
+
+// Migrations are applied in order and recorded in schema_migrations. Append new ones to the end
+// and never change one that has been released.
+pub const MIGRATIONS: [Migration; 8] = [
+    Migration {
+        version: 1,
+        name: "create_internal_tables",
+        steps: &[
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"admins\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"email\" text, \"password_hash\" text, \"mfa_secret\" text, \"mfa_enabled\" boolean, \"role\" text, \"passkeys\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"admins\" (\"email\")"),
+            Step::Execute("CREATE TYPE IF NOT EXISTS \"hyperbase\".\"token_rules\" (\"find_one\" boolean, \"find_many\" boolean, \"insert\" boolean, \"update\" boolean, \"delete\" boolean)"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"tokens\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"admin_id\" uuid, \"token\" text, \"rules\" map<uuid, frozen<token_rules>>, \"expired_at\" timestamp, \"allowed_cidrs\" list<text>, \"scopes\" list<text>, \"previous_token\" text, \"previous_token_expired_at\" timestamp, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"tokens\" (\"admin_id\")"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"tokens\" (\"token\")"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"tokens\" (\"previous_token\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"projects\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"admin_id\" uuid, \"name\" text, \"suspended\" boolean, \"token_claims\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"projects\" (\"admin_id\")"),
+            Step::Execute("CREATE TYPE IF NOT EXISTS \"hyperbase\".\"schema_field_props\" (\"kind\" text, \"internal_kind\" text, \"required\" boolean, \"enum_variants\" list<text>, \"min_length\" bigint, \"max_length\" bigint, \"min\" double, \"max\" double, \"pattern\" text, \"computed_op\" text, \"computed_fields\" list<text>, \"computed_separator\" text)"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"collections\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"project_id\" uuid, \"name\" text, \"schema_fields\" map<text, frozen<schema_field_props>>, \"indexes\" set<text>, \"max_field_size\" bigint, \"max_record_size\" bigint, \"auto_delete_after_seconds\" bigint, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"collections\" (\"project_id\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"registrations\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"email\" text, \"password_hash\" text, \"code\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"registrations\" (\"email\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"admin_password_resets\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"admin_id\" uuid, \"code\" text, \"expired_at\" timestamp, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"admin_password_resets\" (\"admin_id\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"admin_refresh_tokens\" (\"id\" uuid, \"created_at\" timestamp, \"admin_id\" uuid, \"session_id\" uuid, \"token\" text, \"expired_at\" timestamp, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"admin_refresh_tokens\" (\"token\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"jwt_revocations\" (\"id\" uuid, \"created_at\" timestamp, \"expired_at\" timestamp, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"project_members\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"project_id\" uuid, \"admin_id\" uuid, \"role\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"project_members\" (\"project_id\")"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"project_members\" (\"admin_id\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"project_transfers\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"project_id\" uuid, \"from_admin_id\" uuid, \"to_admin_id\" uuid, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"project_transfers\" (\"to_admin_id\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"audit_logs\" (\"id\" uuid, \"created_at\" timestamp, \"admin_id\" uuid, \"project_id\" uuid, \"ip\" text, \"action\" text, \"resource\" text, \"resource_id\" uuid, \"before\" text, \"after\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"audit_logs\" (\"admin_id\")"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"audit_logs\" (\"project_id\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"admin_sessions\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"admin_id\" uuid, \"user_agent\" text, \"ip\" text, \"expired_at\" timestamp, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"admin_sessions\" (\"admin_id\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"users\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"project_id\" uuid, \"email\" text, \"password_hash\" text, PRIMARY KEY (\"id\"))"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"users\" (\"project_id\")"),
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"users\" (\"email\")"),
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"user_password_resets\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"user_id\" uuid, \"code\" text, PRIMARY KEY (\"id\"))"),
+        ],
+    },
+    // Deployments created before migrations existed have tables without these columns
+    Migration {
+        version: 2,
+        name: "add_columns_to_early_tables",
+        steps: &[
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "enum_variants",
+                kind: "list<text>",
+            },
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "min_length",
+                kind: "bigint",
+            },
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "max_length",
+                kind: "bigint",
+            },
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "min",
+                kind: "double",
+            },
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "max",
+                kind: "double",
+            },
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "pattern",
+                kind: "text",
+            },
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "computed_op",
+                kind: "text",
+            },
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "computed_fields",
+                kind: "list<text>",
+            },
+            Step::AddTypeField {
+                type_name: "schema_field_props",
+                field: "computed_separator",
+                kind: "text",
+            },
+            Step::AddColumn {
+                table: "admins",
+                column: "mfa_secret",
+                kind: "text",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "admins",
+                column: "mfa_enabled",
+                kind: "boolean",
+                default: Some("false"),
+            },
+            Step::AddColumn {
+                table: "admins",
+                column: "role",
+                kind: "text",
+                default: Some("'owner'"),
+            },
+            Step::AddColumn {
+                table: "admins",
+                column: "passkeys",
+                kind: "text",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "admin_password_resets",
+                column: "expired_at",
+                kind: "timestamp",
+                default: Some("toTimestamp(now())"),
+            },
+            Step::AddColumn {
+                table: "collections",
+                column: "max_field_size",
+                kind: "bigint",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "collections",
+                column: "max_record_size",
+                kind: "bigint",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "collections",
+                column: "auto_delete_after_seconds",
+                kind: "bigint",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "projects",
+                column: "suspended",
+                kind: "boolean",
+                default: Some("false"),
+            },
+            Step::AddColumn {
+                table: "projects",
+                column: "token_claims",
+                kind: "text",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "tokens",
+                column: "allowed_cidrs",
+                kind: "list<text>",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "tokens",
+                column: "scopes",
+                kind: "list<text>",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "tokens",
+                column: "previous_token",
+                kind: "text",
+                default: None,
+            },
+            Step::AddColumn {
+                table: "tokens",
+                column: "previous_token_expired_at",
+                kind: "timestamp",
+                default: None,
+            },
+        ],
+    },
+    Migration {
+        version: 3,
+        name: "create_project_usages",
+        steps: &[
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"project_usages\" (\"project_id\" uuid, \"period\" timestamp, \"updated_at\" timestamp, \"request_count\" bigint, \"record_count\" bigint, \"storage_bytes\" bigint, PRIMARY KEY (\"project_id\", \"period\")) WITH CLUSTERING ORDER BY (\"period\" DESC)"),
+        ],
+    },
+    Migration {
+        version: 4,
+        name: "add_project_deleted_at",
+        steps: &[Step::AddColumn {
+            table: "projects",
+            column: "deleted_at",
+            kind: "timestamp",
+            default: None,
+        }],
+    },
+    Migration {
+        version: 5,
+        name: "add_project_archived",
+        steps: &[Step::AddColumn {
+            table: "projects",
+            column: "archived",
+            kind: "boolean",
+            default: Some("false"),
+        }],
+    },
+    Migration {
+        version: 6,
+        name: "create_collection_plugins",
+        steps: &[
+            Step::Execute("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"collection_plugins\" (\"collection_id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"module\" blob, PRIMARY KEY (\"collection_id\"))"),
+        ],
+    },
+    Migration {
+        version: 7,
+        name: "add_token_rule_condition",
+        steps: &[Step::AddTypeField {
+            type_name: "token_rules",
+            field: "condition",
+            kind: "text",
+        }],
+    },
+    Migration {
+        version: 8,
+        name: "index_user_password_resets_by_user_id",
+        steps: &[
+            Step::Execute("CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"user_password_resets\" (\"user_id\")"),
+        ],
+    },
+];
+
+pub async fn init(session: &Session) {
+    hb_log::info(Some("🔧"), "ScyllaDB: Applying schema migrations");
+
+    session.query(CREATE_TABLE, &[]).await.unwrap();
+
+    let applied_versions = session
+        .query(SELECT_MANY, &[])
+        .await
+        .unwrap()
+        .rows_typed::<(i64,)>()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    for migration in &MIGRATIONS {
+        if applied_versions
+            .iter()
+            .any(|(version,)| *version == migration.version)
+        {
+            continue;
+        }
+
+        hb_log::info(
+            Some("🔧"),
+            format!(
+                "ScyllaDB: Applying migration {} ({})",
+                migration.version, migration.name
+            ),
+        );
+
+        for step in migration.steps {
+            match step {
+                Step::Execute(statement) => {
+                    session.query(*statement, &[]).await.unwrap();
+                }
+                Step::AddColumn {
+                    table,
+                    column,
+                    kind,
+                    default,
+                } => {
+                    let (count,) = session
+                        .query(COUNT_COLUMN, (table, column))
+                        .await
+                        .unwrap()
+                        .first_row_typed::<(i64,)>()
+                        .unwrap();
+                    if count > 0 {
+                        continue;
+                    }
+                    session
+                        .query(
+                            format!(
+                                "ALTER TABLE \"hyperbase\".\"{table}\" ADD \"{column}\" {kind}"
+                            ),
+                            &[],
+                        )
+                        .await
+                        .unwrap();
+                    // CQL has no unkeyed UPDATE, so the default is written row by row
+                    if let Some(default) = default {
+                        let ids = session
+                            .query(format!("SELECT \"id\" FROM \"hyperbase\".\"{table}\""), &[])
+                            .await
+                            .unwrap()
+                            .rows_typed::<(Uuid,)>()
+                            .unwrap()
+                            .collect::<Result<Vec<_>, _>>()
+                            .unwrap();
+                        let update = format!(
+                            "UPDATE \"hyperbase\".\"{table}\" SET \"{column}\" = {default} WHERE \"id\" = ?"
+                        );
+                        for id in ids {
+                            session.query(update.as_str(), id).await.unwrap();
+                        }
+                    }
+                }
+                Step::AddTypeField {
+                    type_name,
+                    field,
+                    kind,
+                } => {
+                    let (field_names,) = session
+                        .query(SELECT_TYPE_FIELD_NAMES, (type_name,))
+                        .await
+                        .unwrap()
+                        .first_row_typed::<(Vec<String>,)>()
+                        .unwrap();
+                    if field_names.iter().any(|name| name == field) {
+                        continue;
+                    }
+                    session
+                        .query(
+                            format!(
+                                "ALTER TYPE \"hyperbase\".\"{type_name}\" ADD \"{field}\" {kind}"
+                            ),
+                            &[],
+                        )
+                        .await
+                        .unwrap();
+                }
+            }
+        }
+
+        session
+            .query(INSERT, (migration.version, migration.name))
+            .await
+            .unwrap();
+    }
+}
@@ -1,42 +1,8 @@
-use scylla::CachingSession;
-
-pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"projects\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"name\") VALUES (?, ?, ?, ?, ?)";
-pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"name\" FROM \"hyperbase\".\"projects\" WHERE \"id\" = ?";
-pub const SELECT_MANY_BY_ADMIN_ID :  &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"name\" FROM \"hyperbase\".\"projects\" WHERE \"admin_id\" = ?";
-pub const UPDATE: &str = "UPDATE \"hyperbase\".\"projects\" SET \"updated_at\" = ?, \"name\" = ? WHERE \"id\" = ?";
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"projects\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"name\", \"suspended\", \"archived\", \"token_claims\", \"deleted_at\") VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"name\", \"suspended\", \"archived\", \"token_claims\", \"deleted_at\" FROM \"hyperbase\".\"projects\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_ADMIN_ID :  &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"name\", \"suspended\", \"archived\", \"token_claims\", \"deleted_at\" FROM \"hyperbase\".\"projects\" WHERE \"admin_id\" = ?";
+pub const SELECT_MANY_DELETED_BEFORE: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"name\", \"suspended\", \"archived\", \"token_claims\", \"deleted_at\" FROM \"hyperbase\".\"projects\" WHERE \"deleted_at\" < ? ALLOW FILTERING";
+pub const UPDATE: &str = "UPDATE \"hyperbase\".\"projects\" SET \"updated_at\" = ?, \"name\" = ?, \"suspended\" = ?, \"archived\" = ?, \"token_claims\" = ? WHERE \"id\" = ?";
+pub const UPDATE_ADMIN_ID: &str = "UPDATE \"hyperbase\".\"projects\" SET \"updated_at\" = ?, \"admin_id\" = ? WHERE \"id\" = ?";
+pub const UPDATE_DELETED_AT: &str = "UPDATE \"hyperbase\".\"projects\" SET \"updated_at\" = ?, \"deleted_at\" = ? WHERE \"id\" = ?";
 pub const DELETE: &str = "DELETE FROM \"hyperbase\".\"projects\" WHERE \"id\" = ?";
-
-pub async fn init(cached_session: &CachingSession) {
-    hb_log::info(Some("🔧"), "ScyllaDB: Setting up projects table");
-
-    cached_session.get_session().query("CREATE TABLE IF NOT EXISTS \"hyperbase\".\"projects\" (\"id\" uuid, \"created_at\" timestamp, \"updated_at\" timestamp, \"admin_id\" uuid, \"name\" text, PRIMARY KEY (\"id\"))", &[]).await.unwrap();
-    cached_session
-        .get_session()
-        .query(
-            "CREATE INDEX IF NOT EXISTS ON \"hyperbase\".\"projects\" (\"admin_id\")",
-            &[],
-        )
-        .await
-        .unwrap();
-
-    cached_session
-        .add_prepared_statement(&INSERT.into())
-        .await
-        .unwrap();
-    cached_session
-        .add_prepared_statement(&SELECT.into())
-        .await
-        .unwrap();
-    cached_session
-        .add_prepared_statement(&SELECT_MANY_BY_ADMIN_ID.into())
-        .await
-        .unwrap();
-    cached_session
-        .add_prepared_statement(&UPDATE.into())
-        .await
-        .unwrap();
-    cached_session
-        .add_prepared_statement(&DELETE.into())
-        .await
-        .unwrap();
-}
@@ -0,0 +1,6 @@
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"project_members\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"hyperbase\".\"project_members\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"hyperbase\".\"project_members\" WHERE \"project_id\" = ?";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"hyperbase\".\"project_members\" WHERE \"admin_id\" = ?";
+pub const UPDATE: &str = "UPDATE \"hyperbase\".\"project_members\" SET \"updated_at\" = ?, \"role\" = ? WHERE \"id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"hyperbase\".\"project_members\" WHERE \"id\" = ?";
@@ -0,0 +1,4 @@
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"project_transfers\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\" FROM \"hyperbase\".\"project_transfers\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_TO_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\" FROM \"hyperbase\".\"project_transfers\" WHERE \"to_admin_id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"hyperbase\".\"project_transfers\" WHERE \"id\" = ?";
@@ -0,0 +1,2 @@
+pub const UPSERT: &str = "INSERT INTO \"hyperbase\".\"project_usages\" (\"project_id\", \"period\", \"updated_at\", \"request_count\", \"record_count\", \"storage_bytes\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"project_id\", \"period\", \"updated_at\", \"request_count\", \"record_count\", \"storage_bytes\" FROM \"hyperbase\".\"project_usages\" WHERE \"project_id\" = ? ORDER BY \"period\" DESC LIMIT ?";
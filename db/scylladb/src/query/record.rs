@@ -1,14 +1,29 @@
 use ahash::{HashMap, HashSet};
+use hb_db_query_builder::{self as query_builder, Dialect};
 use itertools::Itertools;
 
 use crate::model::collection::SchemaFieldPropsModel;
 
+pub struct ScyllaDialect;
+
+impl Dialect for ScyllaDialect {
+    const IDENT_QUOTE: char = '"';
+
+    fn placeholder(_idx: usize) -> String {
+        "?".to_owned()
+    }
+
+    fn table(record_table: &str) -> String {
+        format!("\"hyperbase\".{}", Self::ident(record_table))
+    }
+}
+
 pub fn create_table(
     record_table: &str,
     columns: &HashMap<String, SchemaFieldPropsModel>,
 ) -> String {
     format!(
-        "CREATE TABLE IF NOT EXISTS \"hyperbase\".\"{}\" (\"_id\" uuid, {}, PRIMARY KEY (\"_id\")) ",
+        "CREATE TABLE IF NOT EXISTS \"hyperbase\".\"{}\" (\"_id\" uuid, \"_version\" bigint, \"_created_by\" uuid, {}, PRIMARY KEY (\"_id\")) ",
         record_table,
         columns
             .iter()
@@ -21,6 +36,10 @@ pub fn drop_table(record_table: &str) -> String {
     format!("DROP TABLE IF EXISTS \"hyperbase\".\"{record_table}\"")
 }
 
+pub fn set_default_ttl(record_table: &str, ttl: &i64) -> String {
+    format!("ALTER TABLE \"hyperbase\".\"{record_table}\" WITH default_time_to_live = {ttl}")
+}
+
 pub fn create_index(record_table: &str, index: &str) -> String {
     format!("CREATE INDEX IF NOT EXISTS \"{record_table}_{index}\" ON \"hyperbase\".\"{record_table}\" (\"{index}\")")
 }
@@ -29,6 +48,14 @@ pub fn drop_index(record_table: &str, index: &str) -> String {
     format!("DROP INDEX IF EXISTS \"hyperbase\".\"{record_table}_{index}\"")
 }
 
+pub fn add_version_column(record_table: &str) -> String {
+    format!("ALTER TABLE \"hyperbase\".\"{record_table}\" ADD \"_version\" bigint")
+}
+
+pub fn add_created_by_column(record_table: &str) -> String {
+    format!("ALTER TABLE \"hyperbase\".\"{record_table}\" ADD \"_created_by\" uuid")
+}
+
 pub fn add_columns(record_table: &str, columns: &HashMap<String, SchemaFieldPropsModel>) -> String {
     format!(
         "ALTER TABLE \"hyperbase\".\"{}\" ADD ({})",
@@ -51,6 +78,10 @@ pub fn drop_columns(record_table: &str, column_names: &HashSet<String>) -> Strin
     )
 }
 
+pub fn select_column(record_table: &str, column: &str) -> String {
+    format!("SELECT \"_id\", \"{column}\" FROM \"hyperbase\".\"{record_table}\"")
+}
+
 pub fn change_columns_type(
     record_table: &str,
     columns: &HashMap<String, SchemaFieldPropsModel>,
@@ -70,25 +101,11 @@ pub fn change_columns_type(
 }
 
 pub fn insert(record_table: &str, columns: &Vec<&str>) -> String {
-    let mut cols = "".to_owned();
-    let mut vals = "".to_owned();
-    for (idx, col) in columns.iter().enumerate() {
-        cols += &format!("\"{col}\"");
-        vals += "?";
-        if idx < columns.len() - 1 {
-            cols += ", ";
-            vals += ", ";
-        }
-    }
-    format!("INSERT INTO \"hyperbase\".\"{record_table}\" ({cols}) VALUES ({vals})")
+    query_builder::insert::<ScyllaDialect>(record_table, columns)
 }
 
 pub fn select(record_table: &str, columns: &Vec<&str>) -> String {
-    format!(
-        "SELECT {} FROM \"hyperbase\".\"{}\" WHERE \"_id\" = ?",
-        columns.iter().map(|col| format!("\"{col}\"")).join(", "),
-        record_table
-    )
+    query_builder::select::<ScyllaDialect>(record_table, columns)
 }
 
 pub fn select_many(
@@ -99,68 +116,72 @@ pub fn select_many(
     orders: &Vec<(&str, &str)>,
     with_query_limit: &bool,
 ) -> String {
-    let mut query = format!(
-        "SELECT {} FROM \"hyperbase\".\"{}\"",
-        columns.iter().map(|col| format!("\"{col}\"")).join(", "),
+    query_builder::select_many::<ScyllaDialect>(
         record_table,
-    );
-    if filter.len() > 0 {
-        query += &format!(" WHERE {filter}")
-    }
-    if groups.len() > 0 {
-        query += " GROUP BY";
-        let mut count = 0;
-        for group in groups {
-            if count > 0 {
-                query += ",";
-            }
-            query += &format!(" \"{group}\"");
-            count += 1;
-        }
-    }
-    if orders.len() > 0 {
-        query += " ORDER BY";
-        let mut count = 0;
-        for (field, kind) in orders {
-            if count > 0 {
-                query += ","
-            }
-            query += &format!(" \"{field}\" {kind}");
-            count += 1;
-        }
-    }
-    if *with_query_limit {
-        query += " LIMIT ?"
-    }
-    query + " ALLOW FILTERING"
+        columns,
+        filter,
+        groups,
+        orders,
+        false,
+        &with_query_limit.then_some(0),
+    ) + " ALLOW FILTERING"
 }
 
-pub fn update(record_table: &str, columns: &Vec<&str>) -> String {
-    format!(
-        "UPDATE \"hyperbase\".\"{}\" SET {} WHERE \"_id\" = ?",
+pub fn select_many_from_token(
+    record_table: &str,
+    columns: &Vec<&str>,
+    filter: &str,
+    before: &bool,
+) -> String {
+    let token = match before {
+        true => "token(\"_id\") < ?",
+        false => "token(\"_id\") >= ?",
+    };
+    let filter = match filter.is_empty() {
+        true => token.to_owned(),
+        false => format!("{filter} AND {token}"),
+    };
+    query_builder::select_many::<ScyllaDialect>(
         record_table,
-        columns
-            .iter()
-            .map(|col| format!("\"{col}\" = ?"))
-            .join(", ")
-    )
+        columns,
+        &filter,
+        &[],
+        &[],
+        false,
+        &Some(0),
+    ) + " ALLOW FILTERING"
+}
+
+pub fn update(record_table: &str, columns: &Vec<&str>) -> String {
+    query_builder::update::<ScyllaDialect>(record_table, columns, &[], false)
 }
 
 pub fn delete(record_table: &str, columns: &HashSet<String>) -> String {
-    format!(
-        "DELETE FROM \"hyperbase\".\"{}\" WHERE {}",
+    query_builder::delete::<ScyllaDialect>(
         record_table,
-        columns
-            .iter()
-            .map(|col| format!("\"{col}\" = ?"))
-            .join(", ")
+        &columns.iter().map(String::as_str).collect::<Vec<_>>(),
     )
 }
 
+// Lightweight transactions guarding a write on the record version. Rows written before the
+// version column existed hold null there and count as version 0, so the expected version is
+// bound twice: once as is and once as null when it is 0.
+pub fn update_with_version(record_table: &str, columns: &Vec<&str>) -> String {
+    update(record_table, columns) + &version_condition()
+}
+
+pub fn delete_with_version(record_table: &str) -> String {
+    query_builder::delete::<ScyllaDialect>(record_table, &["_id"]) + &version_condition()
+}
+
+fn version_condition() -> String {
+    format!(" IF {} IN (?, ?)", ScyllaDialect::ident("_version"))
+}
+
 pub fn count(record_table: &str, filter: &str) -> String {
-    let mut query = format!("SELECT COUNT(1) FROM \"hyperbase\".\"{}\"", record_table);
-    if filter.len() > 0 {
-        query += &format!(" WHERE {filter}")
-    }
-    query + " ALLOW FILTERING"
+    query_builder::count::<ScyllaDialect>(record_table, filter) + " ALLOW FILTERING"
+}
+
+pub fn count_groups(record_table: &str, groups: &Vec<&str>, filter: &str) -> String {
+    query_builder::count_groups::<ScyllaDialect>(record_table, groups, filter) + " ALLOW FILTERING"
 }
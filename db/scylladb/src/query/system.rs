@@ -1 +1,4 @@
 pub const COUNT_TABLE: &str = "SELECT COUNT(1) FROM \"system_schema\".\"tables\" WHERE \"keyspace_name\" = 'hyperbase' AND \"table_name\" = ?";
+pub const COUNT_COLUMN: &str = "SELECT COUNT(1) FROM \"system_schema\".\"columns\" WHERE \"keyspace_name\" = 'hyperbase' AND \"table_name\" = ? AND \"column_name\" = ?";
+pub const SELECT_SIZE_ESTIMATES: &str = "SELECT \"mean_partition_size\", \"partitions_count\" FROM \"system\".\"size_estimates\" WHERE \"keyspace_name\" = 'hyperbase' AND \"table_name\" = ?";
+pub const SELECT_TYPE_FIELD_NAMES: &str = "SELECT \"field_names\" FROM \"system_schema\".\"types\" WHERE \"keyspace_name\" = 'hyperbase' AND \"type_name\" = ?";
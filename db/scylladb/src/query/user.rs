@@ -0,0 +1,6 @@
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"users\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"hyperbase\".\"users\" WHERE \"id\" = ?";
+pub const SELECT_BY_PROJECT_ID_AND_EMAIL: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"hyperbase\".\"users\" WHERE \"project_id\" = ? AND \"email\" = ? ALLOW FILTERING";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"hyperbase\".\"users\" WHERE \"project_id\" = ?";
+pub const UPDATE: &str = "UPDATE \"hyperbase\".\"users\" SET \"updated_at\" = ?, \"email\" = ?, \"password_hash\" = ? WHERE \"id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"hyperbase\".\"users\" WHERE \"id\" = ?";
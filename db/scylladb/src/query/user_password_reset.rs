@@ -0,0 +1,13 @@
+use scylla::Session;
+
+pub const INSERT: &str = "INSERT INTO \"hyperbase\".\"user_password_resets\" (\"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\") VALUES (?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\" FROM \"hyperbase\".\"user_password_resets\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_USER_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\" FROM \"hyperbase\".\"user_password_resets\" WHERE \"user_id\" = ?";
+pub const UPDATE: &str = "UPDATE \"hyperbase\".\"user_password_resets\" SET \"updated_at\" = ?, \"code\" = ? WHERE \"id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"hyperbase\".\"user_password_resets\" WHERE \"id\" = ?";
+
+pub async fn init(session: &Session, ttl: &u32) {
+    hb_log::info(Some("🔧"), "ScyllaDB: Setting up user_password_resets table");
+
+    session.query("ALTER TABLE \"hyperbase\".\"user_password_resets\" WITH default_time_to_live = ".to_owned() + &ttl.to_string(), &[]).await.unwrap();
+}
@@ -1,15 +1,27 @@
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
 use sqlx::{
     query::{Query, QueryAs},
     sqlite::{SqliteArguments, SqlitePoolOptions, SqliteQueryResult, SqliteRow},
-    Error, Pool, Sqlite,
+    Error, Execute, Pool, Sqlite,
 };
 
-use crate::query::{admin, admin_password_reset, collection, project, registration, token};
+use tracing::{Instrument, Span};
+
+use crate::query::{
+    admin, admin_password_reset, admin_refresh_token, admin_session, audit_log, collection,
+    collection_plugin, jwt_revocation, migration, project, project_member, project_transfer,
+    project_usage, registration, token, user, user_password_reset,
+};
 
 pub struct SqliteDb {
     pool: Pool<Sqlite>,
     table_registration_ttl: i64,
     table_reset_password_ttl: i64,
+    slow_query_threshold: Option<Duration>,
 }
 
 impl SqliteDb {
@@ -18,6 +30,7 @@ impl SqliteDb {
         max_connections: &u32,
         table_registration_ttl: &i64,
         table_reset_password_ttl: &i64,
+        slow_query_threshold: &Option<Duration>,
     ) -> Self {
         hb_log::info(Some("⚡"), "SQLite: Initializing component");
 
@@ -34,6 +47,7 @@ impl SqliteDb {
             pool,
             table_registration_ttl: *table_registration_ttl,
             table_reset_password_ttl: *table_reset_password_ttl,
+            slow_query_threshold: *slow_query_threshold,
         }
     }
 
@@ -41,14 +55,29 @@ impl SqliteDb {
         &self,
         query: Query<'a, Sqlite, SqliteArguments<'a>>,
     ) -> Result<SqliteQueryResult, Error> {
-        query.persistent(false).execute(&self.pool).await
+        let statement = query.sql();
+        self.observe(statement, query.persistent(false).execute(&self.pool))
+            .await
     }
 
     pub async fn execute<'a>(
         &self,
         query: Query<'a, Sqlite, SqliteArguments<'a>>,
     ) -> Result<SqliteQueryResult, Error> {
-        query.execute(&self.pool).await
+        let statement = query.sql();
+        self.observe(statement, query.execute(&self.pool)).await
+    }
+
+    pub async fn execute_transaction<'a>(
+        &self,
+        queries: Vec<Query<'a, Sqlite, SqliteArguments<'a>>>,
+    ) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+        for query in queries {
+            let statement = query.sql();
+            self.observe(statement, query.execute(&mut *tx)).await?;
+        }
+        tx.commit().await
     }
 
     pub async fn fetch_one_unprepared<
@@ -58,35 +87,47 @@ impl SqliteDb {
         &self,
         query: QueryAs<'a, Sqlite, T, SqliteArguments<'a>>,
     ) -> Result<T, Error> {
-        Ok(query.persistent(false).fetch_one(&self.pool).await?)
+        let statement = query.sql();
+        Ok(self
+            .observe(statement, query.persistent(false).fetch_one(&self.pool))
+            .await?)
     }
 
     pub async fn fetch_one<'a, T: Send + Unpin + for<'r> sqlx::FromRow<'r, SqliteRow>>(
         &self,
         query: QueryAs<'a, Sqlite, T, SqliteArguments<'a>>,
     ) -> Result<T, Error> {
-        Ok(query.fetch_one(&self.pool).await?)
+        let statement = query.sql();
+        Ok(self.observe(statement, query.fetch_one(&self.pool)).await?)
     }
 
     pub async fn fetch_one_row<'a>(
         &self,
         query: Query<'a, Sqlite, SqliteArguments<'a>>,
     ) -> Result<SqliteRow, Error> {
-        Ok(query.fetch_one(&self.pool).await?)
+        let statement = query.sql();
+        Ok(self.observe(statement, query.fetch_one(&self.pool)).await?)
     }
 
     pub async fn fetch_all<'a, T: Send + Unpin + for<'r> sqlx::FromRow<'r, SqliteRow>>(
         &self,
         query: QueryAs<'a, Sqlite, T, SqliteArguments<'a>>,
     ) -> Result<Vec<T>, Error> {
-        query.fetch_all(&self.pool).await
+        let statement = query.sql();
+        self.observe(statement, query.fetch_all(&self.pool)).await
     }
 
     pub async fn fetch_all_rows<'a>(
         &self,
         query: Query<'a, Sqlite, SqliteArguments<'a>>,
     ) -> Result<Vec<SqliteRow>, Error> {
-        query.fetch_all(&self.pool).await
+        let statement = query.sql();
+        self.observe(statement, query.fetch_all(&self.pool)).await
+    }
+
+    pub async fn close(&self) {
+        hb_log::info(Some("🔌"), "SQLite: Closing connection pool");
+        self.pool.close().await;
     }
 
     pub fn table_registration_ttl(&self) -> &i64 {
@@ -97,12 +138,71 @@ impl SqliteDb {
         &self.table_reset_password_ttl
     }
 
+    async fn observe<T>(&self, statement: &str, query: impl Future<Output = T>) -> T {
+        let started_at = Instant::now();
+        let output = query.instrument(query_span(statement)).await;
+        if let Some(slow_query_threshold) = &self.slow_query_threshold {
+            let elapsed = started_at.elapsed();
+            if elapsed >= *slow_query_threshold {
+                hb_log::warn(
+                    None,
+                    format!(
+                        "SQLite: Slow query on table '{}' took {} ms with {} bound parameters: {statement}",
+                        table_name(statement).unwrap_or("-"),
+                        elapsed.as_millis(),
+                        bound_parameter_count(statement),
+                    ),
+                );
+            }
+        }
+        output
+    }
+
     async fn init(pool: &Pool<Sqlite>) {
+        migration::init(pool).await;
         admin::init(pool).await;
         token::init(pool).await;
         project::init(pool).await;
         collection::init(pool).await;
+        collection_plugin::init(pool).await;
         registration::init(pool).await;
         admin_password_reset::init(pool).await;
+        admin_refresh_token::init(pool).await;
+        jwt_revocation::init(pool).await;
+        project_member::init(pool).await;
+        project_transfer::init(pool).await;
+        project_usage::init(pool).await;
+        audit_log::init(pool).await;
+        admin_session::init(pool).await;
+        user::init(pool).await;
+        user_password_reset::init(pool).await;
     }
 }
+
+fn query_span(statement: &str) -> Span {
+    tracing::debug_span!("db.query", db.system = "sqlite", db.statement = statement)
+}
+
+// Takes the identifier following the first FROM, INTO, UPDATE, or TABLE keyword
+fn table_name(statement: &str) -> Option<&str> {
+    let mut words = statement.split_whitespace();
+    while let Some(word) = words.next() {
+        if ["FROM", "INTO", "UPDATE", "TABLE"]
+            .iter()
+            .any(|keyword| word.eq_ignore_ascii_case(keyword))
+        {
+            return words
+                .find(|word| {
+                    !["IF", "NOT", "EXISTS"]
+                        .iter()
+                        .any(|keyword| word.eq_ignore_ascii_case(keyword))
+                })
+                .map(|word| word.trim_matches(|c| matches!(c, '"' | '`' | '(' | ')' | ';')));
+        }
+    }
+    None
+}
+
+fn bound_parameter_count(statement: &str) -> usize {
+    statement.matches('?').count()
+}
@@ -11,6 +11,10 @@ pub struct ProjectModel {
     updated_at: DateTime<Utc>,
     admin_id: Uuid,
     name: String,
+    suspended: bool,
+    archived: bool,
+    token_claims: Option<String>,
+    deleted_at: Option<DateTime<Utc>>,
 }
 
 impl ProjectModel {
@@ -20,6 +24,10 @@ impl ProjectModel {
         updated_at: &DateTime<Utc>,
         admin_id: &Uuid,
         name: &str,
+        suspended: &bool,
+        archived: &bool,
+        token_claims: &Option<String>,
+        deleted_at: &Option<DateTime<Utc>>,
     ) -> Self {
         Self {
             id: *id,
@@ -27,6 +35,10 @@ impl ProjectModel {
             updated_at: *updated_at,
             admin_id: *admin_id,
             name: name.to_owned(),
+            suspended: *suspended,
+            archived: *archived,
+            token_claims: token_claims.clone(),
+            deleted_at: *deleted_at,
         }
     }
 
@@ -49,4 +61,20 @@ impl ProjectModel {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn suspended(&self) -> &bool {
+        &self.suspended
+    }
+
+    pub fn archived(&self) -> &bool {
+        &self.archived
+    }
+
+    pub fn token_claims(&self) -> &Option<String> {
+        &self.token_claims
+    }
+
+    pub fn deleted_at(&self) -> &Option<DateTime<Utc>> {
+        &self.deleted_at
+    }
 }
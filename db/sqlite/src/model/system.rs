@@ -1,6 +1,6 @@
-pub const LOGICAL_OPERATOR: [&str; 2] = ["AND", "OR"];
+pub const LOGICAL_OPERATOR: [&str; 3] = ["AND", "OR", "NOT"];
 
-pub const COMPARISON_OPERATOR: [&str; 18] = [
+pub const COMPARISON_OPERATOR: [&str; 22] = [
     ">",
     ">=",
     "<",
@@ -19,6 +19,13 @@ pub const COMPARISON_OPERATOR: [&str; 18] = [
     "NOT IN",
     "LIKE",
     "NOT LIKE",
+    "CONTAINS",
+    "SUBSTRING",
+    "STARTS_WITH",
+    "ENDS_WITH",
 ];
 
+// Comparison operators that take no value
+pub const NULLARY_OPERATOR: [&str; 2] = ["IS NULL", "IS NOT NULL"];
+
 pub const ORDER_TYPE: [&str; 2] = ["ASC", "DESC"];
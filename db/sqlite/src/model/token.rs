@@ -18,6 +18,10 @@ pub struct TokenModel {
     token: String,
     rules: Json<HashMap<Uuid, TokenRuleMethodModel>>,
     expired_at: Option<Json<DateTime<Utc>>>,
+    allowed_cidrs: Option<Json<Vec<String>>>,
+    scopes: Option<Json<Vec<String>>>,
+    previous_token: Option<String>,
+    previous_token_expired_at: Option<Json<DateTime<Utc>>>,
 }
 
 impl TokenModel {
@@ -29,6 +33,10 @@ impl TokenModel {
         token: &str,
         rules: &Json<HashMap<Uuid, TokenRuleMethodModel>>,
         expired_at: &Option<Json<DateTime<Utc>>>,
+        allowed_cidrs: &Option<Json<Vec<String>>>,
+        scopes: &Option<Json<Vec<String>>>,
+        previous_token: &Option<String>,
+        previous_token_expired_at: &Option<Json<DateTime<Utc>>>,
     ) -> Self {
         Self {
             id: *id,
@@ -38,6 +46,10 @@ impl TokenModel {
             token: token.to_owned(),
             rules: rules.clone(),
             expired_at: *expired_at,
+            allowed_cidrs: allowed_cidrs.clone(),
+            scopes: scopes.clone(),
+            previous_token: previous_token.clone(),
+            previous_token_expired_at: *previous_token_expired_at,
         }
     }
 
@@ -68,6 +80,22 @@ impl TokenModel {
     pub fn expired_at(&self) -> &Option<Json<DateTime<Utc>>> {
         &self.expired_at
     }
+
+    pub fn allowed_cidrs(&self) -> &Option<Json<Vec<String>>> {
+        &self.allowed_cidrs
+    }
+
+    pub fn scopes(&self) -> &Option<Json<Vec<String>>> {
+        &self.scopes
+    }
+
+    pub fn previous_token(&self) -> &Option<String> {
+        &self.previous_token
+    }
+
+    pub fn previous_token_expired_at(&self) -> &Option<Json<DateTime<Utc>>> {
+        &self.previous_token_expired_at
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -77,6 +105,7 @@ pub struct TokenRuleMethodModel {
     insert: bool,
     update: bool,
     delete: bool,
+    condition: Option<String>,
 }
 
 impl TokenRuleMethodModel {
@@ -86,6 +115,7 @@ impl TokenRuleMethodModel {
         insert: &bool,
         update: &bool,
         delete: &bool,
+        condition: &Option<String>,
     ) -> Self {
         Self {
             find_one: *find_one,
@@ -93,6 +123,7 @@ impl TokenRuleMethodModel {
             insert: *insert,
             update: *update,
             delete: *delete,
+            condition: condition.clone(),
         }
     }
 
@@ -115,4 +146,8 @@ impl TokenRuleMethodModel {
     pub fn delete(&self) -> &bool {
         &self.delete
     }
+
+    pub fn condition(&self) -> &Option<String> {
+        &self.condition
+    }
 }
@@ -1,17 +1,21 @@
 use sqlx::{Executor, Pool, Sqlite};
 
-pub const INSERT: &str = "INSERT INTO \"admin_password_resets\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"code\") VALUES (?, ?, ?, ?, ?)";
-pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"code\" FROM \"admin_password_resets\" WHERE \"id\" = ? AND \"updated_at\" >= ?";
+pub const INSERT: &str = "INSERT INTO \"admin_password_resets\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"code\", \"expired_at\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"code\", \"expired_at\" FROM \"admin_password_resets\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"code\", \"expired_at\" FROM \"admin_password_resets\" WHERE \"admin_id\" = ?";
 pub const UPDATE: &str = "UPDATE \"admin_password_resets\" SET \"updated_at\" = ?, \"code\" = ? WHERE \"id\" = ? AND \"updated_at\" >= ?";
 pub const DELETE: &str = "DELETE FROM \"admin_password_resets\" WHERE \"id\" = ?";
+pub const DELETE_EXPIRED: &str = "DELETE FROM \"admin_password_resets\" WHERE \"expired_at\" <= ?";
+pub const DELETE_MANY_BY_ADMIN_ID: &str = "DELETE FROM \"admin_password_resets\" WHERE \"admin_id\" = ?";
 
 pub async fn init(pool: &Pool<Sqlite>) {
     hb_log::info(Some("🔧"), "SQLite: Setting up admin_password_resets table");
 
-    pool.execute("CREATE TABLE IF NOT EXISTS \"admin_password_resets\" (\"id\" blob, \"created_at\" datetime, \"updated_at\" datetime, \"admin_id\" blob, \"code\" text, PRIMARY KEY (\"id\"))").await.unwrap();
-
     pool.prepare(INSERT).await.unwrap();
     pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
     pool.prepare(UPDATE).await.unwrap();
     pool.prepare(DELETE).await.unwrap();
+    pool.prepare(DELETE_EXPIRED).await.unwrap();
+    pool.prepare(DELETE_MANY_BY_ADMIN_ID).await.unwrap();
 }
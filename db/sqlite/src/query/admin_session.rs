@@ -0,0 +1,17 @@
+use sqlx::{Executor, Pool, Sqlite};
+
+pub const INSERT: &str = "INSERT INTO \"admin_sessions\" (\"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\") VALUES (?, ?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\" FROM \"admin_sessions\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"admin_id\", \"user_agent\", \"ip\", \"expired_at\" FROM \"admin_sessions\" WHERE \"admin_id\" = ? ORDER BY \"created_at\" DESC";
+pub const UPDATE_EXPIRED_AT: &str = "UPDATE \"admin_sessions\" SET \"updated_at\" = ?, \"expired_at\" = ? WHERE \"id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"admin_sessions\" WHERE \"id\" = ?";
+
+pub async fn init(pool: &Pool<Sqlite>) {
+    hb_log::info(Some("🔧"), "SQLite: Setting up admin_sessions table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
+    pool.prepare(UPDATE_EXPIRED_AT).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
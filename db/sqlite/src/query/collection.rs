@@ -1,19 +1,21 @@
 use sqlx::{Executor, Pool, Sqlite};
 
-pub const INSERT: &str = "INSERT INTO \"collections\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\") VALUES (?, ?, ?, ?, ?, ?, ?)";
-pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\" FROM \"collections\" WHERE \"id\" = ?";
-pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\" FROM \"collections\" WHERE \"project_id\" = ?";
-pub const UPDATE: &str = "UPDATE \"collections\" SET \"updated_at\" = ?, \"name\" = ?, \"schema_fields\" = ?, \"indexes\" = ? WHERE \"id\" = ?";
+pub const INSERT: &str = "INSERT INTO \"collections\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\") VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\" FROM \"collections\" WHERE \"id\" = ?";
+pub const SELECT_MANY: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\" FROM \"collections\"";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\" FROM \"collections\" WHERE \"project_id\" = ?";
+pub const SELECT_MANY_WITH_AUTO_DELETE: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"name\", \"schema_fields\", \"indexes\", \"partial_indexes\", \"max_field_size\", \"max_record_size\", \"auto_delete_after_seconds\" FROM \"collections\" WHERE \"auto_delete_after_seconds\" IS NOT NULL";
+pub const UPDATE: &str = "UPDATE \"collections\" SET \"updated_at\" = ?, \"name\" = ?, \"schema_fields\" = ?, \"indexes\" = ?, \"partial_indexes\" = ?, \"max_field_size\" = ?, \"max_record_size\" = ?, \"auto_delete_after_seconds\" = ? WHERE \"id\" = ?";
 pub const DELETE: &str = "DELETE FROM \"collections\" WHERE \"id\" = ?";
 
 pub async fn init(pool: &Pool<Sqlite>) {
     hb_log::info(Some("🔧"), "SQLite: Setting up collections table");
 
-    pool.execute("CREATE TABLE IF NOT EXISTS \"collections\" (\"id\" blob, \"created_at\" datetime, \"updated_at\" datetime, \"project_id\" blob, \"name\" text, \"schema_fields\" blob, \"indexes\" blob, PRIMARY KEY (\"id\"))").await.unwrap();
-
     pool.prepare(INSERT).await.unwrap();
     pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY).await.unwrap();
     pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+    pool.prepare(SELECT_MANY_WITH_AUTO_DELETE).await.unwrap();
     pool.prepare(UPDATE).await.unwrap();
     pool.prepare(DELETE).await.unwrap();
 }
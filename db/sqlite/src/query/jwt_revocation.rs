@@ -0,0 +1,11 @@
+use sqlx::{Executor, Pool, Sqlite};
+
+pub const INSERT: &str = "INSERT INTO \"jwt_revocations\" (\"id\", \"created_at\", \"expired_at\") VALUES (?, ?, ?)";
+pub const COUNT: &str = "SELECT COUNT(1) FROM \"jwt_revocations\" WHERE \"id\" = ?";
+
+pub async fn init(pool: &Pool<Sqlite>) {
+    hb_log::info(Some("🔧"), "SQLite: Setting up jwt_revocations table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(COUNT).await.unwrap();
+}
@@ -0,0 +1,19 @@
+use sqlx::{Executor, Pool, Sqlite};
+
+pub const INSERT: &str = "INSERT INTO \"project_members\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"project_members\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"project_members\" WHERE \"project_id\" = ?";
+pub const SELECT_MANY_BY_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"admin_id\", \"role\" FROM \"project_members\" WHERE \"admin_id\" = ?";
+pub const UPDATE: &str = "UPDATE \"project_members\" SET \"updated_at\" = ?, \"role\" = ? WHERE \"id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"project_members\" WHERE \"id\" = ?";
+
+pub async fn init(pool: &Pool<Sqlite>) {
+    hb_log::info(Some("🔧"), "SQLite: Setting up project_members table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_ADMIN_ID).await.unwrap();
+    pool.prepare(UPDATE).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
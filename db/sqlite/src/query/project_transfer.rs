@@ -0,0 +1,15 @@
+use sqlx::{Executor, Pool, Sqlite};
+
+pub const INSERT: &str = "INSERT INTO \"project_transfers\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\" FROM \"project_transfers\" WHERE \"id\" = ?";
+pub const SELECT_MANY_BY_TO_ADMIN_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"from_admin_id\", \"to_admin_id\" FROM \"project_transfers\" WHERE \"to_admin_id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"project_transfers\" WHERE \"id\" = ?";
+
+pub async fn init(pool: &Pool<Sqlite>) {
+    hb_log::info(Some("🔧"), "SQLite: Setting up project_transfers table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_TO_ADMIN_ID).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
@@ -0,0 +1,11 @@
+use sqlx::{Executor, Pool, Sqlite};
+
+pub const UPSERT: &str = "INSERT INTO \"project_usages\" (\"project_id\", \"period\", \"updated_at\", \"request_count\", \"record_count\", \"storage_bytes\") VALUES (?, ?, ?, ?, ?, ?) ON CONFLICT (\"project_id\", \"period\") DO UPDATE SET \"updated_at\" = excluded.\"updated_at\", \"request_count\" = excluded.\"request_count\", \"record_count\" = excluded.\"record_count\", \"storage_bytes\" = excluded.\"storage_bytes\"";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"project_id\", \"period\", \"updated_at\", \"request_count\", \"record_count\", \"storage_bytes\" FROM \"project_usages\" WHERE \"project_id\" = ? ORDER BY \"period\" DESC LIMIT ?";
+
+pub async fn init(pool: &Pool<Sqlite>) {
+    hb_log::info(Some("🔧"), "SQLite: Setting up project_usages table");
+
+    pool.prepare(UPSERT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+}
@@ -5,14 +5,14 @@ pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"email
 pub const SELECT_BY_EMAIL: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"email\", \"password_hash\", \"code\" FROM \"registrations\" WHERE \"email\" = ? AND \"updated_at\" >= ?";
 pub const UPDATE: &str = "UPDATE \"registrations\" SET \"updated_at\" = ?, \"code\" = ? WHERE \"id\" = ?";
 pub const DELETE: &str = "DELETE FROM \"registrations\" WHERE \"id\" = ?";
+pub const DELETE_EXPIRED: &str = "DELETE FROM \"registrations\" WHERE \"updated_at\" < ?";
 
 pub async fn init(pool: &Pool<Sqlite>) {
     hb_log::info(Some("🔧"), "SQLite: Setting up registrations table");
 
-    pool.execute("CREATE TABLE IF NOT EXISTS \"registrations\" (\"id\" blob, \"created_at\" datetime, \"updated_at\" datetime, \"email\" text, \"password_hash\" text, \"code\" text, PRIMARY KEY (\"id\"))").await.unwrap();
-
     pool.prepare(INSERT).await.unwrap();
     pool.prepare(SELECT).await.unwrap();
     pool.prepare(UPDATE).await.unwrap();
     pool.prepare(DELETE).await.unwrap();
+    pool.prepare(DELETE_EXPIRED).await.unwrap();
 }
@@ -1 +1,3 @@
 pub const COUNT_TABLE: &str = "SELECT COUNT(1) FROM \"sqlite_master\" WHERE \"type\" = 'table' AND \"name\" = ?";
+pub const COUNT_COLUMN: &str = "SELECT COUNT(1) FROM pragma_table_info(?) WHERE \"name\" = ?";
+pub const TABLE_SIZE: &str = "SELECT COALESCE(SUM(\"pgsize\"), 0) FROM \"dbstat\" WHERE \"name\" IN (SELECT \"name\" FROM \"sqlite_master\" WHERE \"tbl_name\" = ?)";
@@ -0,0 +1,19 @@
+use sqlx::{Executor, Pool, Sqlite};
+
+pub const INSERT: &str = "INSERT INTO \"users\" (\"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\") VALUES (?, ?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"users\" WHERE \"id\" = ?";
+pub const SELECT_BY_PROJECT_ID_AND_EMAIL: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"users\" WHERE \"project_id\" = ? AND \"email\" = ?";
+pub const SELECT_MANY_BY_PROJECT_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"project_id\", \"email\", \"password_hash\" FROM \"users\" WHERE \"project_id\" = ? ORDER BY \"created_at\" DESC";
+pub const UPDATE: &str = "UPDATE \"users\" SET \"updated_at\" = ?, \"email\" = ?, \"password_hash\" = ? WHERE \"id\" = ?";
+pub const DELETE: &str = "DELETE FROM \"users\" WHERE \"id\" = ?";
+
+pub async fn init(pool: &Pool<Sqlite>) {
+    hb_log::info(Some("🔧"), "SQLite: Setting up users table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_BY_PROJECT_ID_AND_EMAIL).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_PROJECT_ID).await.unwrap();
+    pool.prepare(UPDATE).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+}
@@ -0,0 +1,21 @@
+use sqlx::{Executor, Pool, Sqlite};
+
+pub const INSERT: &str = "INSERT INTO \"user_password_resets\" (\"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\") VALUES (?, ?, ?, ?, ?)";
+pub const SELECT: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\" FROM \"user_password_resets\" WHERE \"id\" = ? AND \"updated_at\" >= ?";
+pub const SELECT_MANY_BY_USER_ID: &str = "SELECT \"id\", \"created_at\", \"updated_at\", \"user_id\", \"code\" FROM \"user_password_resets\" WHERE \"user_id\" = ?";
+pub const UPDATE: &str = "UPDATE \"user_password_resets\" SET \"updated_at\" = ?, \"code\" = ? WHERE \"id\" = ? AND \"updated_at\" >= ?";
+pub const DELETE: &str = "DELETE FROM \"user_password_resets\" WHERE \"id\" = ?";
+pub const DELETE_EXPIRED: &str = "DELETE FROM \"user_password_resets\" WHERE \"updated_at\" < ?";
+pub const DELETE_MANY_BY_USER_ID: &str = "DELETE FROM \"user_password_resets\" WHERE \"user_id\" = ?";
+
+pub async fn init(pool: &Pool<Sqlite>) {
+    hb_log::info(Some("🔧"), "SQLite: Setting up user_password_resets table");
+
+    pool.prepare(INSERT).await.unwrap();
+    pool.prepare(SELECT).await.unwrap();
+    pool.prepare(SELECT_MANY_BY_USER_ID).await.unwrap();
+    pool.prepare(UPDATE).await.unwrap();
+    pool.prepare(DELETE).await.unwrap();
+    pool.prepare(DELETE_EXPIRED).await.unwrap();
+    pool.prepare(DELETE_MANY_BY_USER_ID).await.unwrap();
+}
@@ -0,0 +1,257 @@
+use std::{str::FromStr, sync::Arc};
+
+use anyhow::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use uuid::Uuid;
+
+// Fans record changes out to the configured publishers. Publishers that fall behind lose the oldest
+// events instead of slowing down the requests that emit them.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: Sender<Arc<RecordEvent>>,
+}
+
+impl EventBus {
+    pub fn new(capacity: &usize) -> Self {
+        let (sender, _) = broadcast::channel(*capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> Receiver<Arc<RecordEvent>> {
+        self.sender.subscribe()
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    pub fn publish_record(
+        &self,
+        kind: &RecordEventKind,
+        project_id: &Uuid,
+        collection_id: &Uuid,
+        record_id: &Uuid,
+        record: Option<&Map<String, Value>>,
+    ) {
+        if !self.has_subscribers() {
+            return;
+        }
+        let _ = self.sender.send(Arc::new(RecordEvent {
+            kind: *kind,
+            project_id: *project_id,
+            collection_id: *collection_id,
+            record_id: *record_id,
+            record: record.cloned(),
+            timestamp: Utc::now(),
+        }));
+    }
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordEventKind {
+    Insert,
+    Update,
+    Upsert,
+    Delete,
+}
+
+impl RecordEventKind {
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Upsert => "upsert",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+// How publishers encode events into message payloads
+#[derive(Clone, Copy)]
+pub enum EventFormat {
+    Json,
+    Cbor,
+}
+
+impl FromStr for EventFormat {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self> {
+        match str {
+            "json" => Ok(Self::Json),
+            "cbor" => Ok(Self::Cbor),
+            _ => Err(Error::msg(format!("Unknown event format '{str}'"))),
+        }
+    }
+}
+
+impl EventFormat {
+    pub fn content_type(&self) -> &str {
+        match self {
+            Self::Json => "application/json",
+            Self::Cbor => "application/cbor",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RecordEvent {
+    kind: RecordEventKind,
+    project_id: Uuid,
+    collection_id: Uuid,
+    record_id: Uuid,
+    record: Option<Map<String, Value>>,
+    timestamp: DateTime<Utc>,
+}
+
+impl RecordEvent {
+    pub fn kind(&self) -> &RecordEventKind {
+        &self.kind
+    }
+
+    pub fn project_id(&self) -> &Uuid {
+        &self.project_id
+    }
+
+    pub fn collection_id(&self) -> &Uuid {
+        &self.collection_id
+    }
+
+    pub fn record_id(&self) -> &Uuid {
+        &self.record_id
+    }
+
+    pub fn record(&self) -> &Option<Map<String, Value>> {
+        &self.record
+    }
+
+    pub fn timestamp(&self) -> &DateTime<Utc> {
+        &self.timestamp
+    }
+
+    pub fn encode(&self, format: &EventFormat) -> Result<Vec<u8>> {
+        match format {
+            EventFormat::Json => Ok(serde_json::to_vec(self)?),
+            EventFormat::Cbor => {
+                let mut payload = Vec::new();
+                ciborium::into_writer(self, &mut payload)?;
+                Ok(payload)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{DateTime, Utc};
+    use serde::Deserialize;
+    use serde_json::{json, Map, Value};
+    use uuid::Uuid;
+
+    use super::{EventBus, EventFormat, RecordEvent, RecordEventKind};
+
+    fn record_event(
+        kind: &RecordEventKind,
+        record: Option<&Map<String, Value>>,
+    ) -> Arc<RecordEvent> {
+        let event_bus = EventBus::new(&8);
+        let mut events = event_bus.subscribe();
+        event_bus.publish_record(
+            kind,
+            &Uuid::now_v7(),
+            &Uuid::now_v7(),
+            &Uuid::now_v7(),
+            record,
+        );
+        events.try_recv().unwrap()
+    }
+
+    #[test]
+    fn publish_reaches_every_subscriber() {
+        let event_bus = EventBus::new(&8);
+        assert!(!event_bus.has_subscribers());
+        let (mut first, mut second) = (event_bus.subscribe(), event_bus.subscribe());
+        let record_id = Uuid::now_v7();
+        event_bus.publish_record(
+            &RecordEventKind::Insert,
+            &Uuid::now_v7(),
+            &Uuid::now_v7(),
+            &record_id,
+            None,
+        );
+        assert_eq!(*first.try_recv().unwrap().record_id(), record_id);
+        assert_eq!(*second.try_recv().unwrap().record_id(), record_id);
+    }
+
+    #[test]
+    fn json_payload_carries_the_change() {
+        let record = json!({ "name": "Ada" }).as_object().cloned().unwrap();
+        let event = record_event(&RecordEventKind::Update, Some(&record));
+        let payload: Value =
+            serde_json::from_slice(&event.encode(&EventFormat::Json).unwrap()).unwrap();
+        assert_eq!(payload["kind"], "update");
+        assert_eq!(payload["project_id"], event.project_id().to_string());
+        assert_eq!(payload["collection_id"], event.collection_id().to_string());
+        assert_eq!(payload["record_id"], event.record_id().to_string());
+        assert_eq!(payload["record"], Value::Object(record));
+        assert!(payload["timestamp"].is_string());
+    }
+
+    #[test]
+    fn deleted_record_is_sent_without_data() {
+        let event = record_event(&RecordEventKind::Delete, None);
+        let payload: Value =
+            serde_json::from_slice(&event.encode(&EventFormat::Json).unwrap()).unwrap();
+        assert_eq!(payload["kind"], "delete");
+        assert!(payload["record"].is_null());
+    }
+
+    #[test]
+    fn format_parses_its_name() {
+        assert_eq!(
+            "json".parse::<EventFormat>().unwrap().content_type(),
+            "application/json"
+        );
+        assert_eq!(
+            "cbor".parse::<EventFormat>().unwrap().content_type(),
+            "application/cbor"
+        );
+        assert!("avro".parse::<EventFormat>().is_err());
+        assert!("JSON".parse::<EventFormat>().is_err());
+    }
+
+    // UUIDs are written as 16-byte strings in CBOR, so the payload is read back through the
+    // types instead of as JSON values
+    #[test]
+    fn cbor_payload_carries_the_change() {
+        #[derive(Deserialize)]
+        struct Payload {
+            kind: String,
+            project_id: Uuid,
+            collection_id: Uuid,
+            record_id: Uuid,
+            record: Option<Map<String, Value>>,
+            timestamp: DateTime<Utc>,
+        }
+
+        let record = json!({ "name": "Ada", "age": 36, "tags": ["math"] })
+            .as_object()
+            .cloned()
+            .unwrap();
+        let event = record_event(&RecordEventKind::Insert, Some(&record));
+        let payload: Payload =
+            ciborium::from_reader(event.encode(&EventFormat::Cbor).unwrap().as_slice()).unwrap();
+        assert_eq!(payload.kind, "insert");
+        assert_eq!(payload.project_id, *event.project_id());
+        assert_eq!(payload.collection_id, *event.collection_id());
+        assert_eq!(payload.record_id, *event.record_id());
+        assert_eq!(payload.record, Some(record));
+        assert_eq!(payload.timestamp, *event.timestamp());
+    }
+}
@@ -39,4 +39,10 @@ impl Argon2Hash {
         let hash = PasswordHash::new(hash)?;
         self.argon2.verify_password(password.as_bytes(), &hash)
     }
+
+    // Only checks that the hash is a well-formed PHC string, since verification reads the
+    // parameters from the hash itself
+    pub fn parse_hash(hash: &str) -> Result<(), password_hash::Error> {
+        PasswordHash::new(hash).map(|_| ())
+    }
 }
@@ -0,0 +1,36 @@
+use hb_config::auth::BootstrapAdminConfig;
+use hb_dao::{admin::AdminDao, Db};
+
+// Creates the configured admin only while no admin exists, so that automated deployments can log
+// in without the emailed registration flow and later edits to the config never add more owners
+pub async fn admin(db: &Db, bootstrap_admin: &BootstrapAdminConfig) {
+    let admins_data = AdminDao::db_select_many(db).await.unwrap();
+    if !admins_data.is_empty() {
+        if !admins_data
+            .iter()
+            .any(|admin_data| admin_data.email() == bootstrap_admin.email())
+        {
+            hb_log::info(
+                Some("👤"),
+                format!(
+                    "Hyperbase: Skipping bootstrap admin {} because other admins already exist",
+                    bootstrap_admin.email()
+                ),
+            );
+        }
+        return;
+    }
+
+    AdminDao::new(bootstrap_admin.email(), bootstrap_admin.password_hash())
+        .db_insert(db)
+        .await
+        .unwrap();
+
+    hb_log::info(
+        Some("👤"),
+        format!(
+            "Hyperbase: Created bootstrap admin {}",
+            bootstrap_admin.email()
+        ),
+    );
+}
@@ -0,0 +1,177 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+};
+
+use futures::stream;
+use hb_config::Config;
+use hb_dao::{admin::AdminDao, backup, Db};
+use hb_hash_argon2::argon2::Argon2Hash;
+use tokio::sync::mpsc;
+
+pub const USAGE: &str = "Usage: hyperbase [COMMAND]
+
+Commands:
+  serve                   Run the server (default)
+  create-admin <EMAIL>    Create an owner admin; the password is read from the first line of stdin
+      --password-hash <HASH>  Use an existing argon2 hash instead of reading a password
+  backup <FILE>           Write a logical backup of all data to FILE
+  restore <FILE>          Restore a backup written by the backup command
+  migrate                 Apply pending schema migrations and exit
+  check-config            Validate the configuration and exit
+  help                    Print this message
+
+The configuration is read from config.yml or HB_CONFIG_PATH, with HB_* environment overrides.";
+
+pub enum Command {
+    Serve,
+    CreateAdmin {
+        email: String,
+        password_hash: Option<String>,
+    },
+    Backup {
+        path: String,
+    },
+    Restore {
+        path: String,
+    },
+    Migrate,
+    CheckConfig,
+    Help,
+}
+
+impl Command {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let command = match args.next().as_deref() {
+            None | Some("serve") => Self::Serve,
+            Some("create-admin") => {
+                let mut email = None;
+                let mut password_hash = None;
+                while let Some(arg) = args.next() {
+                    match arg.as_str() {
+                        "--password-hash" => match args.next() {
+                            Some(hash) => password_hash = Some(hash),
+                            None => return Err("--password-hash needs a value".to_owned()),
+                        },
+                        _ if email.is_none() && !arg.starts_with("--") => email = Some(arg),
+                        _ => return Err(format!("Unexpected argument '{arg}'")),
+                    }
+                }
+                match email {
+                    Some(email) => Self::CreateAdmin {
+                        email,
+                        password_hash,
+                    },
+                    None => return Err("create-admin needs an email".to_owned()),
+                }
+            }
+            Some("backup") => match args.next() {
+                Some(path) => Self::Backup { path },
+                None => return Err("backup needs an output file".to_owned()),
+            },
+            Some("restore") => match args.next() {
+                Some(path) => Self::Restore { path },
+                None => return Err("restore needs an input file".to_owned()),
+            },
+            Some("migrate") => Self::Migrate,
+            Some("check-config") => Self::CheckConfig,
+            Some("help" | "--help" | "-h") => Self::Help,
+            Some(command) => return Err(format!("Unknown command '{command}'")),
+        };
+
+        if let Some(arg) = args.next() {
+            return Err(format!("Unexpected argument '{arg}'"));
+        }
+
+        Ok(command)
+    }
+}
+
+pub async fn create_admin(
+    config: &Config,
+    db: &Db,
+    email: &str,
+    password_hash: &Option<String>,
+) -> Result<(), String> {
+    if AdminDao::db_select_by_email(db, email).await.is_ok() {
+        return Err(format!("An admin with email {email} already exists"));
+    }
+
+    let password_hash = match password_hash {
+        Some(password_hash) => {
+            Argon2Hash::parse_hash(password_hash)
+                .map_err(|err| format!("Password hash is not an argon2 hash: {err}"))?;
+            password_hash.to_owned()
+        }
+        None => {
+            let mut password = String::new();
+            io::stdin()
+                .read_line(&mut password)
+                .map_err(|err| format!("Failed to read the password from stdin: {err}"))?;
+            let password = password.trim_end_matches(['\r', '\n']);
+            if password.is_empty() {
+                return Err("Password must not be empty".to_owned());
+            }
+            Argon2Hash::new(
+                config.hash().argon2().algorithm(),
+                config.hash().argon2().version(),
+                config.hash().argon2().salt(),
+            )
+            .hash_password(password.as_bytes())
+            .map_err(|err| err.to_string())?
+            .to_string()
+        }
+    };
+
+    let admin_data = AdminDao::new(email, &password_hash);
+    admin_data
+        .db_insert(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    println!("Created admin {} ({email})", admin_data.id());
+    Ok(())
+}
+
+pub async fn backup(db: &Db, path: &str) -> Result<(), String> {
+    let file = File::create(path).map_err(|err| format!("Failed to create {path}: {err}"))?;
+    let mut writer = BufWriter::new(file);
+
+    let (tx, mut rx) = mpsc::channel::<String>(64);
+    let (summary, written) = tokio::join!(async move { backup::db_backup(db, &tx).await }, async {
+        while let Some(line) = rx.recv().await {
+            writer.write_all(line.as_bytes())?;
+        }
+        writer.flush()
+    });
+    let summary = summary.map_err(|err| err.to_string())?;
+    written.map_err(|err| format!("Failed to write {path}: {err}"))?;
+
+    println!(
+        "Backed up {} admins, {} projects, {} collections, and {} records to {path}",
+        summary.admins(),
+        summary.projects(),
+        summary.collections(),
+        summary.records()
+    );
+    Ok(())
+}
+
+pub async fn restore(db: &Db, path: &str) -> Result<(), String> {
+    let file = File::open(path).map_err(|err| format!("Failed to open {path}: {err}"))?;
+    let lines = BufReader::new(file).lines().map(|line| Ok(line?));
+
+    let summary = backup::db_restore(db, stream::iter(lines))
+        .await
+        .map_err(|err| err.to_string())?;
+
+    println!(
+        "Restored {} admins, {} projects, {} collections, and {} records from {path}; {} existing rows were kept",
+        summary.admins(),
+        summary.projects(),
+        summary.collections(),
+        summary.records(),
+        summary.skipped()
+    );
+    Ok(())
+}
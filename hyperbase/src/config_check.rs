@@ -0,0 +1,99 @@
+use std::{str::FromStr, time::Duration};
+
+use hb_config::Config;
+use hb_dao::scheduler::JobSchedule;
+use hb_hash_argon2::argon2::Argon2Hash;
+use hb_log::file::LogRotation;
+use tokio::net::TcpStream;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Runs the checks that need other crates or the network on top of Config::validate, then exits
+// listing every problem found
+pub async fn check(config: &Config) {
+    let mut problems = config.validate();
+
+    if let Err(err) = hb_log::validate_filter(config.log().level_filter()) {
+        problems.push(format!(
+            "log.level_filter '{}' is invalid: {err}",
+            config.log().level_filter()
+        ));
+    }
+    if let Some(rotation) = config
+        .log()
+        .file()
+        .as_ref()
+        .and_then(|file| file.rotation().as_ref())
+    {
+        if let Err(err) = LogRotation::from_str(rotation) {
+            problems.push(format!("log.file.rotation: {err}"));
+        }
+    }
+
+    if let Some(bootstrap_admin) = config.auth().bootstrap_admin() {
+        if let Err(err) = Argon2Hash::parse_hash(bootstrap_admin.password_hash()) {
+            problems.push(format!(
+                "auth.bootstrap_admin.password_hash is not an argon2 hash in PHC format ($argon2id$v=19$...): {err}"
+            ));
+        }
+    }
+
+    if let Some(scheduler) = config.scheduler() {
+        let schedules = [
+            ("record_sweep", scheduler.record_sweep()),
+            ("project_purge", scheduler.project_purge()),
+            ("auth_cleanup", scheduler.auth_cleanup()),
+            (
+                "backup.schedule",
+                scheduler
+                    .backup()
+                    .as_ref()
+                    .map_or(&None, |backup| backup.schedule()),
+            ),
+        ];
+        for (name, schedule) in schedules {
+            if let Some(schedule) = schedule {
+                if let Err(err) = JobSchedule::from_cron(schedule) {
+                    problems.push(format!("scheduler.{name}: {err}"));
+                }
+            }
+        }
+    }
+
+    let db_address = if let Some(scylla) = config.db().scylla() {
+        Some(("db.scylla", scylla.host(), scylla.port()))
+    } else if let Some(postgres) = config.db().postgres() {
+        Some(("db.postgres", postgres.host(), postgres.port()))
+    } else {
+        config
+            .db()
+            .mysql()
+            .as_ref()
+            .map(|mysql| ("db.mysql", mysql.host(), mysql.port()))
+    };
+    if let Some((name, host, port)) = db_address {
+        let address = format!("{host}:{port}");
+        match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&address)).await {
+            Ok(Ok(_)) => (),
+            Ok(Err(err)) => problems.push(format!(
+                "{name} at {address} is unreachable: {err}; check {name}.host and {name}.port"
+            )),
+            Err(_) => problems.push(format!(
+                "{name} at {address} did not accept a connection within {} seconds; check {name}.host and {name}.port",
+                CONNECT_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    if !problems.is_empty() {
+        exit(&problems);
+    }
+}
+
+pub fn exit(problems: &[String]) -> ! {
+    eprintln!("Hyperbase cannot start because of configuration problems:");
+    for problem in problems {
+        eprintln!("  - {problem}");
+    }
+    std::process::exit(1)
+}
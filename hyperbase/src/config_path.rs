@@ -1,14 +1,17 @@
 use std::fs;
 
-pub fn get() -> String {
-    let config_path = match std::env::var("HB_CONFIG_PATH") {
-        Ok(path) => path,
-        Err(_) => "config.yml".to_owned(),
-    };
-
-    if let Err(_) = fs::metadata(&config_path) {
-        panic!("config.yml file specified in HB_CONFIG_PATH environment variable or current directory must exist")
+// Without a config file every value must come from HB_* environment variables
+pub fn get() -> Option<String> {
+    match std::env::var("HB_CONFIG_PATH") {
+        Ok(path) => {
+            if fs::metadata(&path).is_err() {
+                panic!("config file specified in HB_CONFIG_PATH environment variable must exist")
+            }
+            Some(path)
+        }
+        Err(_) => match fs::metadata("config.yml") {
+            Ok(_) => Some("config.yml".to_owned()),
+            Err(_) => None,
+        },
     }
-
-    config_path
 }
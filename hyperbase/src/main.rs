@@ -1,25 +1,126 @@
+use std::{fs, str::FromStr, sync::Arc, time::Duration};
+
+use cli::Command;
 use hb_api_rest::{
-    context::{ApiRestCtx, DaoCtx, HashCtx, MailerCtx, TokenCtx},
+    context::{ApiRestCtx, DaoCtx, HashCtx, MailerCtx, PluginCtx, QuotaCtx, TokenCtx},
     ApiRestServer,
 };
-use hb_dao::Db;
+use hb_config::Config;
+use hb_dao::{
+    record::RecordDao,
+    scheduler::{Job, JobSchedule, JobStatuses, Scheduler},
+    usage_meter::{RequestCounter, UsageMeter},
+    Db,
+};
 use hb_db_mysql::db::MysqlDb;
 use hb_db_postgresql::db::PostgresDb;
 use hb_db_scylladb::db::ScyllaDb;
 use hb_db_sqlite::db::SqliteDb;
+use hb_event::{EventBus, EventFormat};
 use hb_hash_argon2::argon2::Argon2Hash;
+use hb_log::{
+    file::{LogFile, LogRotation},
+    otlp::OtlpExporter,
+};
 use hb_mailer::Mailer;
-use hb_token_jwt::token::JwtToken;
+use hb_plugin_wasm::{
+    WasmRuntime, DEFAULT_FUEL, DEFAULT_MAX_MEMORY_BYTES, DEFAULT_MAX_MODULE_BYTES,
+};
+use hb_publisher_amqp::{AmqpPublisher, DEFAULT_EXCHANGE, DEFAULT_ROUTING_KEY};
+use hb_publisher_kafka::{KafkaPublisher, DEFAULT_TOPIC as KAFKA_DEFAULT_TOPIC};
+use hb_publisher_mqtt::{MqttPublisher, DEFAULT_TOPIC as MQTT_DEFAULT_TOPIC};
+use hb_publisher_nats::{NatsPublisher, DEFAULT_SUBJECT};
+use hb_token_jwt::{key::JwtKey, token::JwtToken};
+use hb_token_totp::totp::TotpToken;
+use hb_token_webauthn::webauthn::WebauthnToken;
+use tokio::sync::watch;
 
+mod bootstrap;
+mod cli;
+mod config_check;
 mod config_path;
 
 #[tokio::main]
 async fn main() {
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            std::process::exit(2)
+        }
+    };
+
     let config_path = config_path::get();
-    let config = hb_config::new(&config_path);
+    let config = match hb_config::new(&config_path) {
+        Ok(config) => config,
+        Err(err) => config_check::exit(&[err]),
+    };
+    config_check::check(&config).await;
+    if let Command::CheckConfig = command {
+        println!("Configuration is valid");
+        return;
+    }
+
+    hb_log::init(
+        config.log().display_level(),
+        config.log().level_filter(),
+        &config.log().span_timings().unwrap_or(false),
+        config.log().file().as_ref().map(|file| {
+            LogFile::new(
+                file.path(),
+                &match file.rotation() {
+                    Some(rotation) => match LogRotation::from_str(rotation) {
+                        Ok(rotation) => rotation,
+                        Err(err) => panic!("{err}"),
+                    },
+                    None => LogRotation::Daily,
+                },
+                file.max_size(),
+                file.max_files(),
+            )
+        }),
+        config.log().otlp().as_ref().map(|otlp| {
+            OtlpExporter::new(
+                otlp.endpoint(),
+                otlp.service_name().as_deref().unwrap_or("hyperbase"),
+            )
+        }),
+    );
+
+    if let Command::Serve = command {
+        serve(&config).await;
+        flush_traces().await;
+        return;
+    }
 
-    hb_log::init(config.log().display_level(), config.log().level_filter());
+    // Connecting applies pending migrations, so migrate has nothing left to do afterwards
+    let db = connect_db(&config).await;
+    let result = match &command {
+        Command::CreateAdmin {
+            email,
+            password_hash,
+        } => cli::create_admin(&config, &db, email, password_hash).await,
+        Command::Backup { path } => cli::backup(&db, path).await,
+        Command::Restore { path } => cli::restore(&db, path).await,
+        Command::Migrate => {
+            println!("Schema migrations are up to date");
+            Ok(())
+        }
+        Command::Serve | Command::CheckConfig | Command::Help => unreachable!(),
+    };
+    db.close().await;
+    flush_traces().await;
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
 
+async fn serve(config: &Config) {
     hb_log::info(Some("🚀"), "Hyperbase: Starting");
 
     let argon2_hash = Argon2Hash::new(
@@ -27,10 +128,50 @@ async fn main() {
         config.hash().argon2().version(),
         config.hash().argon2().salt(),
     );
+    let jwt_algorithm = config
+        .token()
+        .jwt()
+        .algorithm()
+        .as_deref()
+        .unwrap_or("HS256");
+    let jwt_keys = match config.token().jwt().keys() {
+        Some(keys) => keys
+            .iter()
+            .map(|key| {
+                jwt_key(
+                    jwt_algorithm,
+                    &Some(key.kid().to_owned()),
+                    key.secret(),
+                    key.private_key_path(),
+                    key.public_key_path(),
+                )
+            })
+            .collect(),
+        None => vec![jwt_key(
+            jwt_algorithm,
+            &None,
+            config.token().jwt().secret(),
+            config.token().jwt().private_key_path(),
+            config.token().jwt().public_key_path(),
+        )],
+    };
     let jwt_token = JwtToken::new(
-        config.token().jwt().secret(),
+        jwt_algorithm,
+        jwt_keys,
         config.token().jwt().expiry_duration(),
-    );
+        config.token().jwt().refresh_expiry_duration(),
+    )
+    .unwrap();
+    let totp_token = TotpToken::new(config.token().totp().issuer());
+    let webauthn_token = config.token().webauthn().as_ref().map(|webauthn| {
+        WebauthnToken::new(
+            webauthn.rp_id(),
+            webauthn.rp_origin(),
+            webauthn.rp_name(),
+            webauthn.ceremony_ttl(),
+        )
+        .unwrap()
+    });
     let (mailer, mailer_sender) = Mailer::new(
         config.mailer().smtp_host(),
         config.mailer().smtp_username(),
@@ -38,6 +179,257 @@ async fn main() {
         config.mailer().sender_name(),
         config.mailer().sender_email(),
     );
+    let db = Arc::new(connect_db(config).await);
+    if let Some(bootstrap_admin) = config.auth().bootstrap_admin() {
+        bootstrap::admin(&db, bootstrap_admin).await;
+    }
+    let job_statuses = JobStatuses::default();
+    let scheduler = Scheduler::new(db.clone(), scheduled_jobs(config), job_statuses.clone());
+    let request_counter = RequestCounter::default();
+    let usage_meter = UsageMeter::new(
+        db.clone(),
+        &Duration::from_secs(config.db().usage_meter_interval().unwrap_or(3600)),
+        request_counter.clone(),
+    );
+    let event_bus = EventBus::new(&1024);
+    let mqtt_publisher = config
+        .publisher()
+        .as_ref()
+        .and_then(|publisher| publisher.mqtt().as_ref())
+        .map(|mqtt| {
+            MqttPublisher::new(
+                &event_bus,
+                mqtt.host(),
+                mqtt.port(),
+                mqtt.client_id().as_deref().unwrap_or("hyperbase"),
+                mqtt.username(),
+                mqtt.password(),
+                mqtt.topic().as_deref().unwrap_or(MQTT_DEFAULT_TOPIC),
+                &mqtt.qos().unwrap_or(1),
+                &mqtt.retain().unwrap_or(false),
+            )
+            .unwrap()
+        });
+
+    let kafka_publisher = config
+        .publisher()
+        .as_ref()
+        .and_then(|publisher| publisher.kafka().as_ref())
+        .map(|kafka| {
+            KafkaPublisher::new(
+                &event_bus,
+                kafka.brokers(),
+                kafka.client_id().as_deref().unwrap_or("hyperbase"),
+                kafka.topic().as_deref().unwrap_or(KAFKA_DEFAULT_TOPIC),
+                &kafka
+                    .format()
+                    .as_deref()
+                    .unwrap_or("json")
+                    .parse::<EventFormat>()
+                    .unwrap(),
+                kafka.properties(),
+            )
+            .unwrap()
+        });
+
+    let nats_publisher = config
+        .publisher()
+        .as_ref()
+        .and_then(|publisher| publisher.nats().as_ref())
+        .map(|nats| {
+            NatsPublisher::new(
+                &event_bus,
+                nats.url(),
+                nats.client_name().as_deref().unwrap_or("hyperbase"),
+                nats.username(),
+                nats.password(),
+                nats.subject().as_deref().unwrap_or(DEFAULT_SUBJECT),
+                &nats
+                    .format()
+                    .as_deref()
+                    .unwrap_or("json")
+                    .parse::<EventFormat>()
+                    .unwrap(),
+            )
+        });
+    let amqp_publisher = config
+        .publisher()
+        .as_ref()
+        .and_then(|publisher| publisher.amqp().as_ref())
+        .map(|amqp| {
+            AmqpPublisher::new(
+                &event_bus,
+                amqp.uri(),
+                amqp.exchange().as_deref().unwrap_or(DEFAULT_EXCHANGE),
+                amqp.routing_key().as_deref().unwrap_or(DEFAULT_ROUTING_KEY),
+                &amqp
+                    .format()
+                    .as_deref()
+                    .unwrap_or("json")
+                    .parse::<EventFormat>()
+                    .unwrap(),
+            )
+        });
+
+    let plugin_config = config.plugin().as_ref();
+
+    let api_rest_server = ApiRestServer::new(
+        config.api().rest().host(),
+        config.api().rest().port(),
+        &config.api().rest().compression().unwrap_or(true),
+        ApiRestCtx::new(
+            HashCtx::new(argon2_hash),
+            TokenCtx::new(jwt_token, totp_token, webauthn_token),
+            MailerCtx::new(mailer_sender, mailer.health()),
+            DaoCtx::new(db.clone()),
+            match config.quota() {
+                Some(quota) => QuotaCtx::new(
+                    *quota.max_collections(),
+                    *quota.max_records_per_collection(),
+                    *quota.max_storage_bytes(),
+                    *quota.max_tokens(),
+                ),
+                None => QuotaCtx::new(None, None, None, None),
+            },
+            PluginCtx::new(
+                WasmRuntime::new(
+                    &plugin_config
+                        .and_then(|plugin| *plugin.fuel())
+                        .unwrap_or(DEFAULT_FUEL),
+                    &plugin_config
+                        .and_then(|plugin| *plugin.max_memory_bytes())
+                        .unwrap_or(DEFAULT_MAX_MEMORY_BYTES),
+                ),
+                plugin_config
+                    .and_then(|plugin| *plugin.max_module_bytes())
+                    .unwrap_or(DEFAULT_MAX_MODULE_BYTES),
+            ),
+            request_counter,
+            event_bus,
+            job_statuses,
+            *config.auth().admin_registration(),
+            *config.auth().access_token_length(),
+            *config.auth().registration_ttl(),
+            *config.auth().reset_password_ttl(),
+            config.auth().operator_emails().clone().unwrap_or_default(),
+        ),
+    );
+
+    let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+    let mqtt_shutdown_receiver = shutdown_receiver.clone();
+    let kafka_shutdown_receiver = shutdown_receiver.clone();
+    let nats_shutdown_receiver = shutdown_receiver.clone();
+    let amqp_shutdown_receiver = shutdown_receiver.clone();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        hb_log::info(Some("🛑"), "Hyperbase: Shutting down");
+        let _ = shutdown_sender.send(true);
+    });
+
+    // The REST server stops first and drops the mailer's only sender, so the mailer exits once
+    // its queue is drained
+    tokio::try_join!(
+        mailer.run(),
+        scheduler.run(shutdown_receiver.clone()),
+        usage_meter.run(shutdown_receiver.clone()),
+        async {
+            match mqtt_publisher {
+                Some(mqtt_publisher) => mqtt_publisher.run(mqtt_shutdown_receiver).await,
+                None => Ok(()),
+            }
+        },
+        async {
+            match kafka_publisher {
+                Some(kafka_publisher) => kafka_publisher.run(kafka_shutdown_receiver).await,
+                None => Ok(()),
+            }
+        },
+        async {
+            match nats_publisher {
+                Some(nats_publisher) => nats_publisher.run(nats_shutdown_receiver).await,
+                None => Ok(()),
+            }
+        },
+        async {
+            match amqp_publisher {
+                Some(amqp_publisher) => amqp_publisher.run(amqp_shutdown_receiver).await,
+                None => Ok(()),
+            }
+        },
+        api_rest_server.run(shutdown_receiver)
+    )
+    .unwrap();
+
+    db.close().await;
+
+    hb_log::info(Some("👋"), "Hyperbase: turned off");
+}
+
+async fn flush_traces() {
+    if let Err(err) = tokio::task::spawn_blocking(hb_log::otlp::shutdown).await {
+        hb_log::error(None, &err);
+    }
+}
+
+// Jobs without a cron expression in the config keep their interval based defaults
+fn scheduled_jobs(config: &Config) -> Vec<(Job, JobSchedule)> {
+    let scheduler = config.scheduler().as_ref();
+    let schedule = |cron: Option<&Option<String>>, default: JobSchedule| match cron {
+        Some(Some(cron)) => JobSchedule::from_cron(cron).unwrap(),
+        _ => default,
+    };
+
+    let mut jobs = vec![
+        (
+            Job::RecordSweep,
+            schedule(
+                scheduler.map(|scheduler| scheduler.record_sweep()),
+                JobSchedule::Every(Duration::from_secs(
+                    config.db().record_sweep_interval().unwrap_or(60),
+                )),
+            ),
+        ),
+        (
+            Job::ProjectPurge {
+                grace_period: Duration::from_secs(
+                    config
+                        .db()
+                        .project_deletion_grace_period()
+                        .unwrap_or(7 * 24 * 3600),
+                ),
+            },
+            schedule(
+                scheduler.map(|scheduler| scheduler.project_purge()),
+                JobSchedule::Every(Duration::from_secs(
+                    config.db().project_purge_interval().unwrap_or(3600),
+                )),
+            ),
+        ),
+        (
+            Job::AuthCleanup,
+            schedule(
+                scheduler.map(|scheduler| scheduler.auth_cleanup()),
+                JobSchedule::Every(Duration::from_secs(3600)),
+            ),
+        ),
+    ];
+    if let Some(backup) = scheduler.and_then(|scheduler| scheduler.backup().as_ref()) {
+        jobs.push((
+            Job::Backup {
+                dir: backup.dir().into(),
+                keep: backup.keep().unwrap_or(7),
+            },
+            JobSchedule::from_cron(backup.schedule().as_deref().unwrap_or("0 0 3 * * *")).unwrap(),
+        ));
+    }
+    jobs
+}
+
+async fn connect_db(config: &Config) -> Db {
+    let slow_query_threshold = config
+        .db()
+        .slow_query_threshold()
+        .map(Duration::from_millis);
     let db = if let Some(scylla) = config.db().scylla() {
         Db::ScyllaDb(
             ScyllaDb::new(
@@ -45,8 +437,11 @@ async fn main() {
                 scylla.port(),
                 scylla.replication_factor(),
                 scylla.prepared_statement_cache_size(),
+                scylla.read_consistency(),
+                scylla.write_consistency(),
                 config.auth().registration_ttl(),
                 config.auth().reset_password_ttl(),
+                &slow_query_threshold,
             )
             .await,
         )
@@ -61,6 +456,7 @@ async fn main() {
                 postgres.max_connections(),
                 &i64::from(*config.auth().registration_ttl()),
                 &i64::from(*config.auth().reset_password_ttl()),
+                &slow_query_threshold,
             )
             .await,
         )
@@ -75,6 +471,7 @@ async fn main() {
                 mysql.max_connections(),
                 &i64::from(*config.auth().registration_ttl()),
                 &i64::from(*config.auth().reset_password_ttl()),
+                &slow_query_threshold,
             )
             .await,
         )
@@ -85,29 +482,59 @@ async fn main() {
                 sqlite.max_connections(),
                 &i64::from(*config.auth().registration_ttl()),
                 &i64::from(*config.auth().reset_password_ttl()),
+                &slow_query_threshold,
             )
             .await,
         )
     } else {
         panic!("No database configuration is specified")
     };
+    RecordDao::db_migrate_version_columns(&db).await.unwrap();
+    RecordDao::db_migrate_created_by_columns(&db).await.unwrap();
+    db
+}
 
-    let api_rest_server = ApiRestServer::new(
-        config.api().rest().host(),
-        config.api().rest().port(),
-        ApiRestCtx::new(
-            HashCtx::new(argon2_hash),
-            TokenCtx::new(jwt_token),
-            MailerCtx::new(mailer_sender),
-            DaoCtx::new(db),
-            *config.auth().admin_registration(),
-            *config.auth().access_token_length(),
-            *config.auth().registration_ttl(),
-            *config.auth().reset_password_ttl(),
-        ),
-    );
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut sigterm =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).unwrap();
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => (),
+            _ = sigterm.recv() => (),
+        }
+    }
 
-    tokio::try_join!(mailer.run(), api_rest_server.run()).unwrap();
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.unwrap();
+}
 
-    hb_log::info(Some("👋"), "Hyperbase: turned off");
+fn jwt_key(
+    algorithm: &str,
+    kid: &Option<String>,
+    secret: &Option<String>,
+    private_key_path: &Option<String>,
+    public_key_path: &Option<String>,
+) -> JwtKey {
+    if algorithm.starts_with("HS") {
+        JwtKey::from_secret(kid, secret.as_ref().expect("JWT secret is not specified"))
+    } else {
+        JwtKey::from_keypair(
+            kid,
+            algorithm,
+            &fs::read(
+                private_key_path
+                    .as_ref()
+                    .expect("JWT private key path is not specified"),
+            )
+            .unwrap(),
+            &fs::read(
+                public_key_path
+                    .as_ref()
+                    .expect("JWT public key path is not specified"),
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
 }
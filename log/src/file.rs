@@ -0,0 +1,175 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::Utc;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogRotation {
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl LogRotation {
+    fn period(&self) -> Option<String> {
+        match self {
+            Self::Never => None,
+            Self::Hourly => Some(Utc::now().format("%Y%m%d%H").to_string()),
+            Self::Daily => Some(Utc::now().format("%Y%m%d").to_string()),
+        }
+    }
+}
+
+impl FromStr for LogRotation {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        match str {
+            "never" => Ok(Self::Never),
+            "hourly" => Ok(Self::Hourly),
+            "daily" => Ok(Self::Daily),
+            _ => Err(format!("Unknown log rotation '{str}'")),
+        }
+    }
+}
+
+pub struct LogFile {
+    path: PathBuf,
+    rotation: LogRotation,
+    max_size: Option<u64>,
+    max_files: Option<usize>,
+}
+
+impl LogFile {
+    pub fn new(
+        path: &str,
+        rotation: &LogRotation,
+        max_size: &Option<u64>,
+        max_files: &Option<usize>,
+    ) -> Self {
+        Self {
+            path: PathBuf::from(path),
+            rotation: *rotation,
+            max_size: *max_size,
+            max_files: *max_files,
+        }
+    }
+}
+
+// Writes to the configured path and moves it aside to `<path>.<timestamp>` whenever the
+// rotation period changes or the next write would exceed max_size
+#[derive(Clone)]
+pub struct RollingFileWriter {
+    state: Arc<Mutex<RollingFileState>>,
+}
+
+struct RollingFileState {
+    config: LogFile,
+    file: File,
+    size: u64,
+    period: Option<String>,
+}
+
+impl RollingFileWriter {
+    pub fn new(config: LogFile) -> io::Result<Self> {
+        if let Some(parent) = config.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        let file = Self::open(&config.path)?;
+        let size = file.metadata()?.len();
+        let period = config.rotation.period();
+        Ok(Self {
+            state: Arc::new(Mutex::new(RollingFileState {
+                config,
+                file,
+                size,
+                period,
+            })),
+        })
+    }
+
+    fn open(path: &PathBuf) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+}
+
+impl RollingFileState {
+    fn should_rotate(&mut self, len: u64) -> bool {
+        if self.size == 0 {
+            self.period = self.config.rotation.period();
+            return false;
+        }
+        if self.config.rotation.period() != self.period {
+            return true;
+        }
+        match self.config.max_size {
+            Some(max_size) => self.size + len > max_size,
+            None => false,
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let mut rotated_path = self.config.path.clone().into_os_string();
+        rotated_path.push(format!(".{}", Utc::now().format("%Y%m%d%H%M%S%3f")));
+        fs::rename(&self.config.path, rotated_path)?;
+        self.file = RollingFileWriter::open(&self.config.path)?;
+        self.size = 0;
+        self.period = self.config.rotation.period();
+        self.prune()
+    }
+
+    fn prune(&self) -> io::Result<()> {
+        let max_files = match self.config.max_files {
+            Some(max_files) => max_files,
+            None => return Ok(()),
+        };
+        let file_name = match self.config.path.file_name().and_then(|name| name.to_str()) {
+            Some(file_name) => format!("{file_name}."),
+            None => return Ok(()),
+        };
+        let dir = match self.config.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let mut rotated_files = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&file_name))
+            })
+            .collect::<Vec<_>>();
+        rotated_files.sort();
+        if rotated_files.len() > max_files {
+            for path in &rotated_files[..rotated_files.len() - max_files] {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Write for RollingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().unwrap();
+        if state.should_rotate(buf.len() as u64) {
+            state.rotate()?;
+        }
+        let len = state.file.write(buf)?;
+        state.size += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state.lock().unwrap().file.flush()
+    }
+}
@@ -1,20 +1,120 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr, sync::OnceLock};
 
 use backtrace::Backtrace;
+use file::{LogFile, RollingFileWriter};
+use otlp::OtlpExporter;
 use tracing::{debug, error, info, level_filters::LevelFilter, warn};
+use tracing_subscriber::{
+    filter::EnvFilter,
+    fmt::{
+        format::FmtSpan,
+        writer::{BoxMakeWriter, MakeWriterExt},
+    },
+    layer::SubscriberExt,
+    reload,
+    util::SubscriberInitExt,
+    Registry,
+};
 
-pub fn init(display_level: &bool, level_filter: &str) {
-    let level_filter = match LevelFilter::from_str(level_filter) {
-        Ok(level) => level,
+pub mod file;
+pub mod otlp;
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+pub fn init(
+    display_level: &bool,
+    level_filter: &str,
+    span_timings: &bool,
+    file: Option<LogFile>,
+    otlp: Option<OtlpExporter>,
+) {
+    let level_filter = match parse_filter(level_filter) {
+        Ok(filter) => filter,
         Err(err) => panic!("{err}"),
     };
 
-    tracing_subscriber::fmt()
-        .with_level(*display_level)
-        .with_max_level(level_filter)
+    let (writer, ansi) = match file {
+        Some(file) => {
+            let file_writer = match RollingFileWriter::new(file) {
+                Ok(writer) => writer,
+                Err(err) => panic!("{err}"),
+            };
+            (
+                BoxMakeWriter::new(std::io::stdout.and(move || file_writer.clone())),
+                false,
+            )
+        }
+        None => (BoxMakeWriter::new(std::io::stdout), true),
+    };
+
+    let otlp = match otlp {
+        Some(exporter) => match otlp::tracer(exporter) {
+            Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+            Err(err) => panic!("{err}"),
+        },
+        None => None,
+    };
+
+    let (filter, filter_handle) = reload::Layer::new(level_filter);
+    if FILTER_HANDLE.set(filter_handle).is_err() {
+        panic!("Logger is already initialized");
+    }
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .with_ansi(ansi)
+                .with_level(*display_level)
+                .with_span_events(match span_timings {
+                    true => FmtSpan::CLOSE,
+                    false => FmtSpan::NONE,
+                }),
+        )
+        .with(otlp)
         .init();
 }
 
+pub fn filter() -> Result<String, String> {
+    match FILTER_HANDLE.get() {
+        Some(handle) => handle
+            .with_current(|filter| filter.to_string())
+            .map_err(|err| err.to_string()),
+        None => Err("Logger is not initialized".to_owned()),
+    }
+}
+
+pub fn set_filter(directives: &str) -> Result<String, String> {
+    let filter = parse_filter(directives)?;
+    let filter_str = filter.to_string();
+    match FILTER_HANDLE.get() {
+        Some(handle) => handle.reload(filter).map_err(|err| err.to_string())?,
+        None => return Err("Logger is not initialized".to_owned()),
+    }
+    Ok(filter_str)
+}
+
+pub fn validate_filter(directives: &str) -> Result<(), String> {
+    parse_filter(directives).map(|_| ())
+}
+
+// Accepts a single level such as `info` or comma-separated EnvFilter directives such as
+// `info,hb_dao=debug,hb_api_rest[request{method=POST}]=trace`, where targets without a directive
+// fall back to the bare level
+fn parse_filter(directives: &str) -> Result<EnvFilter, String> {
+    let filter = EnvFilter::builder()
+        .parse(directives)
+        .map_err(|err| err.to_string())?;
+    match directives
+        .split(',')
+        .any(|directive| LevelFilter::from_str(directive.trim()).is_ok())
+    {
+        true => Ok(filter),
+        false => Ok(filter.add_directive(LevelFilter::INFO.into())),
+    }
+}
+
 pub fn debug<T: Display>(prefix: Option<&str>, msg: T) {
     match prefix {
         Some(prefix) => debug!("{prefix} {msg}"),
@@ -42,3 +142,27 @@ pub fn warn<T: Display>(prefix: Option<&str>, msg: T) {
         None => warn!("⚠️ {msg}"),
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_filter;
+
+    #[test]
+    fn filter_without_bare_level_falls_back_to_info() {
+        let filter = parse_filter("hb_dao=debug").unwrap().to_string();
+        assert!(filter.split(',').any(|directive| directive == "info"));
+    }
+
+    #[test]
+    fn filter_keeps_the_bare_level() {
+        let filter = parse_filter("warn,hb_dao=debug").unwrap().to_string();
+        assert!(filter.split(',').any(|directive| directive == "warn"));
+        assert!(!filter.split(',').any(|directive| directive == "info"));
+    }
+
+    #[test]
+    fn filter_accepts_span_and_field_directives() {
+        assert!(parse_filter("info,hb_api_rest[request{method=POST}]=trace").is_ok());
+        assert!(parse_filter("info,hb_dao=loud").is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::OnceLock;
+
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    runtime,
+    trace::{Config, Tracer, TracerProvider},
+    Resource,
+};
+
+static TRACER_PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+
+pub struct OtlpExporter {
+    endpoint: String,
+    service_name: String,
+}
+
+impl OtlpExporter {
+    pub fn new(endpoint: &str, service_name: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_owned(),
+            service_name: service_name.to_owned(),
+        }
+    }
+}
+
+// Spans are exported over gRPC in batches from a tokio task, so this must run inside the runtime
+pub(crate) fn tracer(exporter: OtlpExporter) -> Result<Tracer, String> {
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(exporter.endpoint),
+        )
+        .with_trace_config(
+            Config::default().with_resource(Resource::new([KeyValue::new(
+                "service.name",
+                exporter.service_name,
+            )])),
+        )
+        .install_batch(runtime::Tokio)
+        .map_err(|err| err.to_string())?;
+    let tracer = provider.tracer("hyperbase");
+    if TRACER_PROVIDER.set(provider).is_err() {
+        return Err("OTLP exporter is already initialized".to_owned());
+    }
+    Ok(tracer)
+}
+
+// Sends the spans still buffered. The flush blocks on the exporter task, so it must not run on a
+// runtime worker.
+pub fn shutdown() {
+    if let Some(provider) = TRACER_PROVIDER.get() {
+        if let Err(err) = provider.shutdown() {
+            crate::warn(None, format!("OTLP exporter: {err}"));
+        }
+    }
+}
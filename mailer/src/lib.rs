@@ -1,4 +1,8 @@
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU32, Ordering},
+    mpsc::{channel, Receiver, Sender},
+    Arc,
+};
 
 use anyhow::Result;
 use lettre::{
@@ -12,6 +16,7 @@ pub struct Mailer {
     message_builder: MessageBuilder,
     smtp_transport: SmtpTransport,
     channel_receiver: Mutex<Receiver<MailPayload>>,
+    health: MailerHealth,
 }
 
 impl Mailer {
@@ -38,11 +43,16 @@ impl Mailer {
                     ))
                     .build(),
                 channel_receiver: Mutex::new(receiver),
+                health: MailerHealth::new(),
             },
             sender,
         )
     }
 
+    pub fn health(&self) -> MailerHealth {
+        self.health.clone()
+    }
+
     pub fn send_mail(&self, payload: &MailPayload) -> Result<()> {
         self.smtp_transport.send(
             &self
@@ -56,15 +66,20 @@ impl Mailer {
     }
 
     pub async fn run(self) -> Result<()> {
-        Ok(tokio::spawn((|| async {
+        // The channel and the SMTP transport both block, so the loop gets its own thread instead of
+        // holding a runtime worker
+        Ok(tokio::task::spawn_blocking(move || {
             hb_log::info(Some("💫"), "Mailer: Running component");
 
             let channel_receiver = self.channel_receiver;
             let message_builder = self.message_builder;
             let smtp_transport = self.smtp_transport;
+            let health = self.health;
+
+            health.running.store(true, Ordering::Relaxed);
 
             loop {
-                match channel_receiver.lock().await.recv() {
+                match channel_receiver.blocking_lock().recv() {
                     Ok(payload) => {
                         let mailbox = match payload.to.parse::<Mailbox>() {
                             Ok(mailbox) => mailbox,
@@ -89,19 +104,47 @@ impl Mailer {
 
                         if let Err(err) = smtp_transport.send(&message) {
                             hb_log::error(None, &err);
+                            health.consecutive_failures.fetch_add(1, Ordering::Relaxed);
                             continue;
                         }
+                        health.consecutive_failures.store(0, Ordering::Relaxed);
                     }
                     Err(_) => {
                         break;
                     }
                 }
             }
-        })())
+
+            health.running.store(false, Ordering::Relaxed);
+            hb_log::info(Some("👋"), "Mailer: Stopped component");
+        })
         .await?)
     }
 }
 
+#[derive(Clone)]
+pub struct MailerHealth {
+    running: Arc<AtomicBool>,
+    consecutive_failures: Arc<AtomicU32>,
+}
+
+impl MailerHealth {
+    fn new() -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(false)),
+            consecutive_failures: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures.load(Ordering::Relaxed)
+    }
+}
+
 pub struct MailPayload {
     to: String,
     subject: String,
@@ -0,0 +1,243 @@
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use wasmi::{
+    Caller, Config, Engine, Extern, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder,
+};
+
+// Plugins talk to the host through JSON in their own linear memory. A module exports `memory`,
+// `alloc(len: i32) -> i32`, and at least one of the hooks:
+//
+// - `before(ptr: i32, len: i32) -> i64` runs before a record is inserted, updated, or upserted.
+//   It returns 0 to keep the record as is, or `(ptr << 32) | len` of a reply that is either
+//   `{"record": {...}}` to replace the record or `{"error": "..."}` to reject the write.
+// - `after(ptr: i32, len: i32)` runs after a record is inserted, updated, upserted, or deleted.
+//
+// Both receive `{"kind", "project_id", "collection_id", "record_id", "record"}`. The only import
+// available is `hyperbase.log(ptr: i32, len: i32)`, which writes a UTF-8 message to the server log.
+pub const HOOK_BEFORE: &str = "before";
+pub const HOOK_AFTER: &str = "after";
+pub const DEFAULT_FUEL: u64 = 10_000_000;
+pub const DEFAULT_MAX_MEMORY_BYTES: usize = 16 << 20;
+pub const DEFAULT_MAX_MODULE_BYTES: usize = 1 << 20;
+const IMPORT_MODULE: &str = "hyperbase";
+const IMPORT_LOG: &str = "log";
+
+pub struct WasmRuntime {
+    engine: Engine,
+    fuel: u64,
+    max_memory_bytes: usize,
+}
+
+impl WasmRuntime {
+    pub fn new(fuel: &u64, max_memory_bytes: &usize) -> Self {
+        hb_log::info(Some("⚡"), "WasmRuntime: Initializing component");
+
+        let mut config = Config::default();
+        config.consume_fuel(true);
+
+        Self {
+            engine: Engine::new(&config),
+            fuel: *fuel,
+            max_memory_bytes: *max_memory_bytes,
+        }
+    }
+
+    // Rejects modules that don't follow the plugin interface so a broken upload fails right away
+    // instead of on the next write
+    pub fn compile(&self, name: &str, wasm: &[u8]) -> Result<WasmPlugin> {
+        let module = Module::new(&self.engine, wasm)?;
+
+        for import in module.imports() {
+            if import.module() != IMPORT_MODULE || import.name() != IMPORT_LOG {
+                return Err(Error::msg(format!(
+                    "Plugin imports '{}.{}', but only '{IMPORT_MODULE}.{IMPORT_LOG}' is available",
+                    import.module(),
+                    import.name()
+                )));
+            }
+        }
+        for export in ["memory", "alloc"] {
+            if module.get_export(export).is_none() {
+                return Err(Error::msg(format!("Plugin must export '{export}'")));
+            }
+        }
+
+        let has_before = module.get_export(HOOK_BEFORE).is_some();
+        let has_after = module.get_export(HOOK_AFTER).is_some();
+        if !has_before && !has_after {
+            return Err(Error::msg(format!(
+                "Plugin must export '{HOOK_BEFORE}', '{HOOK_AFTER}', or both"
+            )));
+        }
+
+        Ok(WasmPlugin {
+            name: name.to_owned(),
+            engine: self.engine.clone(),
+            module,
+            fuel: self.fuel,
+            max_memory_bytes: self.max_memory_bytes,
+            has_before,
+            has_after,
+        })
+    }
+}
+
+pub struct WasmPlugin {
+    name: String,
+    engine: Engine,
+    module: Module,
+    fuel: u64,
+    max_memory_bytes: usize,
+    has_before: bool,
+    has_after: bool,
+}
+
+pub enum BeforeHook {
+    Continue(Option<Map<String, Value>>),
+    Reject(String),
+}
+
+#[derive(Deserialize)]
+struct BeforeReply {
+    record: Option<Map<String, Value>>,
+    error: Option<String>,
+}
+
+struct HostState {
+    name: String,
+    limits: StoreLimits,
+}
+
+impl WasmPlugin {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn has_before(&self) -> &bool {
+        &self.has_before
+    }
+
+    pub fn has_after(&self) -> &bool {
+        &self.has_after
+    }
+
+    pub fn before<T: Serialize>(&self, input: &T) -> Result<BeforeHook> {
+        let (mut store, instance, ptr, len) = self.instantiate(input)?;
+        let reply = instance
+            .get_typed_func::<(i32, i32), i64>(&store, HOOK_BEFORE)?
+            .call(&mut store, (ptr, len))
+            .map_err(|err| self.hook_error(HOOK_BEFORE, &err))?;
+        if reply == 0 {
+            return Ok(BeforeHook::Continue(None));
+        }
+
+        let reply = reply as u64;
+        let memory = self.memory(&store, &instance)?;
+        let mut buffer = vec![0; (reply & 0xffff_ffff) as usize];
+        memory
+            .read(&store, (reply >> 32) as usize, &mut buffer)
+            .map_err(|err| {
+                Error::msg(format!(
+                    "Plugin '{}' returned an invalid reply: {err}",
+                    self.name
+                ))
+            })?;
+        let reply: BeforeReply = serde_json::from_slice(&buffer).map_err(|err| {
+            Error::msg(format!(
+                "Plugin '{}' returned an invalid reply: {err}",
+                self.name
+            ))
+        })?;
+
+        match reply.error {
+            Some(error) => Ok(BeforeHook::Reject(error)),
+            None => Ok(BeforeHook::Continue(reply.record)),
+        }
+    }
+
+    pub fn after<T: Serialize>(&self, input: &T) -> Result<()> {
+        let (mut store, instance, ptr, len) = self.instantiate(input)?;
+        instance
+            .get_typed_func::<(i32, i32), ()>(&store, HOOK_AFTER)?
+            .call(&mut store, (ptr, len))
+            .map_err(|err| self.hook_error(HOOK_AFTER, &err))
+    }
+
+    // Every call gets a fresh instance with its own fuel and memory limit, so nothing carries over
+    // between writes and a runaway plugin only fails the call it is running
+    fn instantiate<T: Serialize>(
+        &self,
+        input: &T,
+    ) -> Result<(Store<HostState>, Instance, i32, i32)> {
+        let mut store = Store::new(
+            &self.engine,
+            HostState {
+                name: self.name.clone(),
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(self.max_memory_bytes)
+                    .instances(1)
+                    .memories(1)
+                    .tables(1)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(self.fuel)
+            .map_err(|err| Error::msg(err.to_string()))?;
+
+        let mut linker = <Linker<HostState>>::new(&self.engine);
+        linker.func_wrap(IMPORT_MODULE, IMPORT_LOG, log)?;
+        let instance = linker
+            .instantiate(&mut store, &self.module)?
+            .start(&mut store)?;
+        let input = serde_json::to_vec(input)?;
+        let len = i32::try_from(input.len())?;
+        let ptr = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")?
+            .call(&mut store, len)
+            .map_err(|err| self.hook_error("alloc", &err))?;
+        self.memory(&store, &instance)?
+            .write(&mut store, usize::try_from(ptr)?, &input)
+            .map_err(|err| {
+                Error::msg(format!(
+                    "Plugin '{}' allocated invalid memory: {err}",
+                    self.name
+                ))
+            })?;
+
+        Ok((store, instance, ptr, len))
+    }
+
+    fn memory(&self, store: &Store<HostState>, instance: &Instance) -> Result<Memory> {
+        instance
+            .get_memory(store, "memory")
+            .ok_or_else(|| Error::msg(format!("Plugin '{}' has no memory", self.name)))
+    }
+
+    fn hook_error(&self, hook: &str, err: &wasmi::Error) -> Error {
+        Error::msg(format!("Plugin '{}' failed in '{hook}': {err}", self.name))
+    }
+}
+
+fn log(caller: Caller<'_, HostState>, ptr: i32, len: i32) {
+    let memory = match caller.get_export("memory").and_then(Extern::into_memory) {
+        Some(memory) => memory,
+        None => return,
+    };
+    let (Ok(ptr), Ok(len)) = (usize::try_from(ptr), usize::try_from(len)) else {
+        return;
+    };
+    if let Some(message) = memory.data(&caller).get(ptr..ptr.saturating_add(len)) {
+        hb_log::info(
+            None,
+            format!(
+                "Plugin '{}': {}",
+                caller.data().name,
+                String::from_utf8_lossy(message)
+            ),
+        );
+    }
+}
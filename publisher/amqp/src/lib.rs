@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use hb_event::{EventBus, EventFormat, RecordEvent};
+use lapin::{
+    options::{BasicPublishOptions, ExchangeDeclareOptions},
+    types::{AMQPValue, FieldTable, ShortString},
+    BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
+};
+use tokio::sync::{
+    broadcast::{error::RecvError, Receiver},
+    watch,
+};
+
+pub const DEFAULT_EXCHANGE: &str = "hyperbase";
+pub const DEFAULT_ROUTING_KEY: &str = "{project_id}.{collection_id}.{kind}";
+
+pub struct AmqpPublisher {
+    uri: String,
+    events: Receiver<Arc<RecordEvent>>,
+    exchange: String,
+    routing_key: String,
+    format: EventFormat,
+    connection: Option<(Connection, Channel)>,
+}
+
+impl AmqpPublisher {
+    pub fn new(
+        event_bus: &EventBus,
+        uri: &str,
+        exchange: &str,
+        routing_key: &str,
+        format: &EventFormat,
+    ) -> Self {
+        hb_log::info(Some("⚡"), "AmqpPublisher: Initializing component");
+
+        Self {
+            uri: uri.to_owned(),
+            events: event_bus.subscribe(),
+            exchange: exchange.to_owned(),
+            routing_key: routing_key.to_owned(),
+            format: *format,
+            connection: None,
+        }
+    }
+
+    pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        hb_log::info(Some("💫"), "AmqpPublisher: Running component");
+
+        loop {
+            tokio::select! {
+                _ = shutdown.wait_for(|shutdown| *shutdown) => break,
+                event = self.events.recv() => match event {
+                    Ok(event) => self.publish(&event).await,
+                    Err(RecvError::Lagged(count)) => hb_log::warn(
+                        None,
+                        format!("AmqpPublisher: Dropped {count} events because the broker is not keeping up"),
+                    ),
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+
+        if let Some((connection, _)) = self.connection.take() {
+            if let Err(err) = connection.close(200, "Shutting down").await {
+                hb_log::error(None, format!("AmqpPublisher: {err}"));
+            }
+        }
+
+        hb_log::info(Some("👋"), "AmqpPublisher: Stopped component");
+        Ok(())
+    }
+
+    async fn publish(&mut self, event: &RecordEvent) {
+        let payload = match event.encode(&self.format) {
+            Ok(payload) => payload,
+            Err(err) => {
+                hb_log::error(None, &err);
+                return;
+            }
+        };
+        let channel = match self.channel().await {
+            Ok(channel) => channel,
+            Err(err) => {
+                hb_log::error(None, format!("AmqpPublisher: {err}"));
+                return;
+            }
+        };
+        let mut headers = FieldTable::default();
+        headers.insert(
+            ShortString::from("kind"),
+            AMQPValue::LongString(event.kind().to_str().into()),
+        );
+        if let Err(err) = channel
+            .basic_publish(
+                &self.exchange,
+                &Self::routing_key(&self.routing_key, event),
+                BasicPublishOptions::default(),
+                &payload,
+                BasicProperties::default()
+                    .with_content_type(ShortString::from(self.format.content_type()))
+                    .with_delivery_mode(2)
+                    .with_headers(headers),
+            )
+            .await
+        {
+            hb_log::error(None, format!("AmqpPublisher: {err}"));
+        }
+    }
+
+    // Connects on first use and again after the broker drops the connection; events published
+    // while the broker is unreachable are dropped
+    async fn channel(&mut self) -> Result<Channel> {
+        if let Some((connection, channel)) = &self.connection {
+            if connection.status().connected() && channel.status().connected() {
+                return Ok(channel.clone());
+            }
+        }
+
+        let connection = Connection::connect(&self.uri, ConnectionProperties::default()).await?;
+        let channel = connection.create_channel().await?;
+        if !self.exchange.is_empty() {
+            channel
+                .exchange_declare(
+                    &self.exchange,
+                    ExchangeKind::Topic,
+                    ExchangeDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    FieldTable::default(),
+                )
+                .await?;
+        }
+        hb_log::info(Some("🔌"), "AmqpPublisher: Connected to broker");
+
+        self.connection = Some((connection, channel.clone()));
+        Ok(channel)
+    }
+
+    fn routing_key(routing_key: &str, event: &RecordEvent) -> String {
+        routing_key
+            .replace("{project_id}", &event.project_id().to_string())
+            .replace("{collection_id}", &event.collection_id().to_string())
+            .replace("{kind}", event.kind().to_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hb_event::{EventBus, RecordEventKind};
+    use uuid::Uuid;
+
+    use super::{AmqpPublisher, DEFAULT_ROUTING_KEY};
+
+    #[test]
+    fn routing_key_is_filled_from_the_event() {
+        let event_bus = EventBus::new(&8);
+        let mut events = event_bus.subscribe();
+        let (project_id, collection_id) = (Uuid::now_v7(), Uuid::now_v7());
+        event_bus.publish_record(
+            &RecordEventKind::Insert,
+            &project_id,
+            &collection_id,
+            &Uuid::now_v7(),
+            None,
+        );
+        let event = events.try_recv().unwrap();
+        assert_eq!(
+            AmqpPublisher::routing_key(DEFAULT_ROUTING_KEY, &event),
+            format!("{project_id}.{collection_id}.insert")
+        );
+        assert_eq!(
+            AmqpPublisher::routing_key("records.{kind}", &event),
+            "records.insert"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use hb_event::{EventBus, EventFormat, RecordEvent};
+use rdkafka::{
+    error::KafkaError,
+    message::{Header, OwnedHeaders},
+    producer::{BaseRecord, DeliveryResult, Producer, ProducerContext, ThreadedProducer},
+    ClientConfig, ClientContext,
+};
+use tokio::sync::{
+    broadcast::{error::RecvError, Receiver},
+    watch,
+};
+
+pub const DEFAULT_TOPIC: &str = "hyperbase.{project_id}.{collection_id}";
+
+pub struct KafkaPublisher {
+    producer: ThreadedProducer<KafkaContext>,
+    events: Receiver<Arc<RecordEvent>>,
+    topic: String,
+    format: EventFormat,
+}
+
+impl KafkaPublisher {
+    pub fn new(
+        event_bus: &EventBus,
+        brokers: &str,
+        client_id: &str,
+        topic: &str,
+        format: &EventFormat,
+        properties: &Option<HashMap<String, String>>,
+    ) -> Result<Self> {
+        hb_log::info(Some("⚡"), "KafkaPublisher: Initializing component");
+
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", brokers)
+            .set("client.id", client_id);
+        if let Some(properties) = properties {
+            for (key, value) in properties {
+                config.set(key, value);
+            }
+        }
+
+        Ok(Self {
+            producer: config.create_with_context(KafkaContext)?,
+            events: event_bus.subscribe(),
+            topic: topic.to_owned(),
+            format: *format,
+        })
+    }
+
+    pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        hb_log::info(Some("💫"), "KafkaPublisher: Running component");
+
+        loop {
+            tokio::select! {
+                _ = shutdown.wait_for(|shutdown| *shutdown) => break,
+                event = self.events.recv() => match event {
+                    Ok(event) => self.publish(&event),
+                    Err(RecvError::Lagged(count)) => hb_log::warn(
+                        None,
+                        format!("KafkaPublisher: Dropped {count} events because the brokers are not keeping up"),
+                    ),
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+
+        // Flushing blocks until queued messages are delivered, so it runs off the async workers
+        let producer = self.producer;
+        if let Err(err) =
+            tokio::task::spawn_blocking(move || producer.flush(Duration::from_secs(5))).await?
+        {
+            hb_log::error(None, format!("KafkaPublisher: {err}"));
+        }
+
+        hb_log::info(Some("👋"), "KafkaPublisher: Stopped component");
+        Ok(())
+    }
+
+    // Messages are keyed by record ID so changes to the same record stay in order within a partition
+    fn publish(&self, event: &RecordEvent) {
+        let payload = match event.encode(&self.format) {
+            Ok(payload) => payload,
+            Err(err) => {
+                hb_log::error(None, &err);
+                return;
+            }
+        };
+        let topic = self.topic(event);
+        let key = event.record_id().to_string();
+        let headers = OwnedHeaders::new()
+            .insert(Header {
+                key: "kind",
+                value: Some(event.kind().to_str()),
+            })
+            .insert(Header {
+                key: "content-type",
+                value: Some(self.format.content_type()),
+            });
+        if let Err((err, _)) = self.producer.send(
+            BaseRecord::to(&topic)
+                .key(&key)
+                .payload(&payload)
+                .headers(headers),
+        ) {
+            hb_log::error(None, format!("KafkaPublisher: {err}"));
+        }
+    }
+
+    fn topic(&self, event: &RecordEvent) -> String {
+        self.topic
+            .replace("{project_id}", &event.project_id().to_string())
+            .replace("{collection_id}", &event.collection_id().to_string())
+            .replace("{kind}", event.kind().to_str())
+    }
+}
+
+struct KafkaContext;
+
+impl ClientContext for KafkaContext {
+    fn error(&self, error: KafkaError, reason: &str) {
+        hb_log::error(None, format!("KafkaPublisher: {error}: {reason}"));
+    }
+}
+
+impl ProducerContext for KafkaContext {
+    type DeliveryOpaque = ();
+
+    fn delivery(&self, delivery_result: &DeliveryResult<'_>, _: Self::DeliveryOpaque) {
+        if let Err((err, _)) = delivery_result {
+            hb_log::error(None, format!("KafkaPublisher: {err}"));
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use hb_event::{EventBus, EventFormat, RecordEvent};
+use rumqttc::{AsyncClient, EventLoop, MqttOptions, QoS};
+use tokio::sync::{
+    broadcast::{error::RecvError, Receiver},
+    watch,
+};
+
+pub const DEFAULT_TOPIC: &str = "hyperbase/{project_id}/{collection_id}/{kind}";
+
+pub struct MqttPublisher {
+    client: AsyncClient,
+    event_loop: EventLoop,
+    events: Receiver<Arc<RecordEvent>>,
+    topic: String,
+    qos: QoS,
+    retain: bool,
+}
+
+impl MqttPublisher {
+    pub fn new(
+        event_bus: &EventBus,
+        host: &str,
+        port: &u16,
+        client_id: &str,
+        username: &Option<String>,
+        password: &Option<String>,
+        topic: &str,
+        qos: &u8,
+        retain: &bool,
+    ) -> Result<Self> {
+        hb_log::info(Some("⚡"), "MqttPublisher: Initializing component");
+
+        let mut options = MqttOptions::new(client_id, host, *port);
+        options.set_keep_alive(Duration::from_secs(30));
+        if let Some(username) = username {
+            options.set_credentials(username, password.as_deref().unwrap_or_default());
+        }
+        let (client, event_loop) = AsyncClient::new(options, 256);
+
+        Ok(Self {
+            client,
+            event_loop,
+            events: event_bus.subscribe(),
+            topic: topic.to_owned(),
+            qos: rumqttc::qos(*qos)?,
+            retain: *retain,
+        })
+    }
+
+    pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        hb_log::info(Some("💫"), "MqttPublisher: Running component");
+
+        loop {
+            tokio::select! {
+                _ = shutdown.wait_for(|shutdown| *shutdown) => break,
+                event = self.events.recv() => match event {
+                    Ok(event) => self.publish(&event),
+                    Err(RecvError::Lagged(count)) => hb_log::warn(
+                        None,
+                        format!("MqttPublisher: Dropped {count} events because the broker is not keeping up"),
+                    ),
+                    Err(RecvError::Closed) => break,
+                },
+                // Polling drives the connection; after an error the next poll reconnects
+                notification = self.event_loop.poll() => {
+                    if let Err(err) = notification {
+                        hb_log::error(None, format!("MqttPublisher: {err}"));
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        }
+
+        if self.client.try_disconnect().is_ok() {
+            let _ = tokio::time::timeout(Duration::from_secs(1), self.event_loop.poll()).await;
+        }
+
+        hb_log::info(Some("👋"), "MqttPublisher: Stopped component");
+        Ok(())
+    }
+
+    fn publish(&self, event: &RecordEvent) {
+        let payload = match event.encode(&EventFormat::Json) {
+            Ok(payload) => payload,
+            Err(err) => {
+                hb_log::error(None, &err);
+                return;
+            }
+        };
+        if let Err(err) = self
+            .client
+            .try_publish(self.topic(event), self.qos, self.retain, payload)
+        {
+            hb_log::error(None, format!("MqttPublisher: {err}"));
+        }
+    }
+
+    fn topic(&self, event: &RecordEvent) -> String {
+        self.topic
+            .replace("{project_id}", &event.project_id().to_string())
+            .replace("{collection_id}", &event.collection_id().to_string())
+            .replace("{record_id}", &event.record_id().to_string())
+            .replace("{kind}", event.kind().to_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hb_event::{EventBus, RecordEventKind};
+    use uuid::Uuid;
+
+    use super::{MqttPublisher, DEFAULT_TOPIC};
+
+    #[test]
+    fn topic_is_filled_from_the_event() {
+        let event_bus = EventBus::new(&8);
+        let publisher = MqttPublisher::new(
+            &event_bus,
+            "localhost",
+            &1883,
+            "hyperbase",
+            &None,
+            &None,
+            DEFAULT_TOPIC,
+            &1,
+            &false,
+        )
+        .unwrap();
+        let mut events = event_bus.subscribe();
+        let (project_id, collection_id) = (Uuid::now_v7(), Uuid::now_v7());
+        event_bus.publish_record(
+            &RecordEventKind::Upsert,
+            &project_id,
+            &collection_id,
+            &Uuid::now_v7(),
+            None,
+        );
+        assert_eq!(
+            publisher.topic(&events.try_recv().unwrap()),
+            format!("hyperbase/{project_id}/{collection_id}/upsert")
+        );
+    }
+}
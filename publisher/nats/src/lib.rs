@@ -0,0 +1,134 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_nats::{Client, ConnectOptions, HeaderMap};
+use hb_event::{EventBus, EventFormat, RecordEvent};
+use tokio::sync::{
+    broadcast::{error::RecvError, Receiver},
+    watch,
+};
+
+pub const DEFAULT_SUBJECT: &str = "hyperbase.{project_id}.{collection_id}.{kind}";
+
+pub struct NatsPublisher {
+    url: String,
+    options: ConnectOptions,
+    events: Receiver<Arc<RecordEvent>>,
+    subject: String,
+    format: EventFormat,
+}
+
+impl NatsPublisher {
+    pub fn new(
+        event_bus: &EventBus,
+        url: &str,
+        client_name: &str,
+        username: &Option<String>,
+        password: &Option<String>,
+        subject: &str,
+        format: &EventFormat,
+    ) -> Self {
+        hb_log::info(Some("⚡"), "NatsPublisher: Initializing component");
+
+        // The client connects in the background and keeps reconnecting, so an unavailable server
+        // doesn't stop Hyperbase from starting
+        let mut options = ConnectOptions::new()
+            .name(client_name)
+            .retry_on_initial_connect()
+            .event_callback(|event| async move {
+                hb_log::warn(None, format!("NatsPublisher: {event}"));
+            });
+        if let Some(username) = username {
+            options = options
+                .user_and_password(username.to_owned(), password.to_owned().unwrap_or_default());
+        }
+
+        Self {
+            url: url.to_owned(),
+            options,
+            events: event_bus.subscribe(),
+            subject: subject.to_owned(),
+            format: *format,
+        }
+    }
+
+    pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) -> Result<()> {
+        hb_log::info(Some("💫"), "NatsPublisher: Running component");
+
+        let client = self.options.connect(self.url).await?;
+        loop {
+            tokio::select! {
+                _ = shutdown.wait_for(|shutdown| *shutdown) => break,
+                event = self.events.recv() => match event {
+                    Ok(event) => Self::publish(&client, &self.subject, &self.format, &event).await,
+                    Err(RecvError::Lagged(count)) => hb_log::warn(
+                        None,
+                        format!("NatsPublisher: Dropped {count} events because the server is not keeping up"),
+                    ),
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+
+        match tokio::time::timeout(Duration::from_secs(5), client.flush()).await {
+            Ok(Ok(_)) => (),
+            Ok(Err(err)) => hb_log::error(None, format!("NatsPublisher: {err}")),
+            Err(err) => hb_log::error(None, format!("NatsPublisher: {err}")),
+        }
+
+        hb_log::info(Some("👋"), "NatsPublisher: Stopped component");
+        Ok(())
+    }
+
+    async fn publish(client: &Client, subject: &str, format: &EventFormat, event: &RecordEvent) {
+        let payload = match event.encode(format) {
+            Ok(payload) => payload,
+            Err(err) => {
+                hb_log::error(None, &err);
+                return;
+            }
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert("Kind", event.kind().to_str());
+        headers.insert("Content-Type", format.content_type());
+        if let Err(err) = client
+            .publish_with_headers(Self::subject(subject, event), headers, payload.into())
+            .await
+        {
+            hb_log::error(None, format!("NatsPublisher: {err}"));
+        }
+    }
+
+    fn subject(subject: &str, event: &RecordEvent) -> String {
+        subject
+            .replace("{project_id}", &event.project_id().to_string())
+            .replace("{collection_id}", &event.collection_id().to_string())
+            .replace("{kind}", event.kind().to_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hb_event::{EventBus, RecordEventKind};
+    use uuid::Uuid;
+
+    use super::{NatsPublisher, DEFAULT_SUBJECT};
+
+    #[test]
+    fn subject_is_filled_from_the_event() {
+        let event_bus = EventBus::new(&8);
+        let mut events = event_bus.subscribe();
+        let (project_id, collection_id) = (Uuid::now_v7(), Uuid::now_v7());
+        event_bus.publish_record(
+            &RecordEventKind::Delete,
+            &project_id,
+            &collection_id,
+            &Uuid::now_v7(),
+            None,
+        );
+        assert_eq!(
+            NatsPublisher::subject(DEFAULT_SUBJECT, &events.try_recv().unwrap()),
+            format!("hyperbase.{project_id}.{collection_id}.delete")
+        );
+    }
+}
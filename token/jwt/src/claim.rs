@@ -1,24 +1,57 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
 use crate::kind::JwtTokenKind;
 
+pub const SESSION_ID_CLAIM: &str = "sid";
+pub const USER_ID_CLAIM: &str = "uid";
+
+// Claims set by the server itself, which custom claims must never override
+pub const RESERVED_CLAIMS: [&str; 8] = [
+    "jti",
+    "id",
+    "kind",
+    "iat",
+    "exp",
+    "nbf",
+    SESSION_ID_CLAIM,
+    USER_ID_CLAIM,
+];
+
 #[derive(Deserialize, Serialize)]
 pub struct Claim {
+    jti: Uuid,
     id: Uuid,
     kind: JwtTokenKind,
+    iat: usize,
     exp: usize,
+    #[serde(flatten)]
+    custom: Map<String, Value>,
 }
 
 impl Claim {
-    pub fn new(id: &Uuid, kind: &JwtTokenKind, exp: &usize) -> Self {
+    pub fn new(
+        id: &Uuid,
+        kind: &JwtTokenKind,
+        iat: &usize,
+        exp: &usize,
+        custom: &Map<String, Value>,
+    ) -> Self {
         Self {
+            jti: Uuid::new_v4(),
             id: *id,
             kind: *kind,
+            iat: *iat,
             exp: *exp,
+            custom: custom.clone(),
         }
     }
 
+    pub fn jti(&self) -> &Uuid {
+        &self.jti
+    }
+
     pub fn id(&self) -> &Uuid {
         &self.id
     }
@@ -27,6 +60,48 @@ impl Claim {
         &self.kind
     }
 
+    pub fn iat(&self) -> &usize {
+        &self.iat
+    }
+
+    pub fn exp(&self) -> &usize {
+        &self.exp
+    }
+
+    pub fn custom(&self) -> &Map<String, Value> {
+        &self.custom
+    }
+
+    pub fn session_id(&self) -> Option<Uuid> {
+        self.custom
+            .get(SESSION_ID_CLAIM)
+            .and_then(|sid| sid.as_str())
+            .and_then(|sid| Uuid::parse_str(sid).ok())
+    }
+
+    pub fn user_id(&self) -> Option<Uuid> {
+        self.custom
+            .get(USER_ID_CLAIM)
+            .and_then(|uid| uid.as_str())
+            .and_then(|uid| Uuid::parse_str(uid).ok())
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CursorClaim<T> {
+    data: T,
+    exp: usize,
+}
+
+impl<T> CursorClaim<T> {
+    pub fn new(data: T, exp: &usize) -> Self {
+        Self { data, exp: *exp }
+    }
+
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
     pub fn exp(&self) -> &usize {
         &self.exp
     }
@@ -0,0 +1,65 @@
+use anyhow::{Error, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm,
+};
+use rsa::{pkcs8::DecodePublicKey, traits::PublicKeyParts, RsaPublicKey};
+
+// DER prefix of an Ed25519 SubjectPublicKeyInfo, followed by the 32-byte key
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+pub fn from_public_key_pem(algorithm: &Algorithm, public_key: &[u8]) -> Result<Jwk> {
+    let (key_algorithm, algorithm_parameters) = match algorithm {
+        Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 => {
+            let public_key = RsaPublicKey::from_public_key_pem(std::str::from_utf8(public_key)?)?;
+            (
+                match algorithm {
+                    Algorithm::RS384 => KeyAlgorithm::RS384,
+                    Algorithm::RS512 => KeyAlgorithm::RS512,
+                    _ => KeyAlgorithm::RS256,
+                },
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be()),
+                    e: URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be()),
+                }),
+            )
+        }
+        Algorithm::EdDSA => {
+            let public_key = pem::parse(public_key)?;
+            let der = public_key.contents();
+            if der.len() != ED25519_SPKI_PREFIX.len() + 32 || !der.starts_with(&ED25519_SPKI_PREFIX)
+            {
+                return Err(Error::msg("Public key is not a valid Ed25519 key"));
+            }
+            (
+                KeyAlgorithm::EdDSA,
+                AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(&der[ED25519_SPKI_PREFIX.len()..]),
+                }),
+            )
+        }
+        _ => {
+            return Err(Error::msg(format!(
+                "Algorithm {algorithm:?} is not supported"
+            )))
+        }
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            ..Default::default()
+        },
+        algorithm: algorithm_parameters,
+    })
+}
@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use anyhow::{Error, Result};
+use jsonwebtoken::{jwk::Jwk, Algorithm, DecodingKey, EncodingKey};
+
+use crate::jwk;
+
+pub struct JwtKey {
+    kid: Option<String>,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    jwk: Option<Jwk>,
+}
+
+impl JwtKey {
+    pub fn from_secret(kid: &Option<String>, secret: &str) -> Self {
+        let secret = secret.as_bytes();
+        Self {
+            kid: kid.clone(),
+            encoding_key: EncodingKey::from_secret(secret),
+            decoding_key: DecodingKey::from_secret(secret),
+            jwk: None,
+        }
+    }
+
+    pub fn from_keypair(
+        kid: &Option<String>,
+        algorithm: &str,
+        private_key: &[u8],
+        public_key: &[u8],
+    ) -> Result<Self> {
+        let algorithm = Algorithm::from_str(algorithm)?;
+        let (encoding_key, decoding_key) = match algorithm {
+            Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 => (
+                EncodingKey::from_rsa_pem(private_key)?,
+                DecodingKey::from_rsa_pem(public_key)?,
+            ),
+            Algorithm::EdDSA => (
+                EncodingKey::from_ed_pem(private_key)?,
+                DecodingKey::from_ed_pem(public_key)?,
+            ),
+            _ => {
+                return Err(Error::msg(format!(
+                    "Algorithm {algorithm:?} is not supported for keypair signing"
+                )))
+            }
+        };
+
+        let mut jwk = jwk::from_public_key_pem(&algorithm, public_key)?;
+        jwk.common.key_id = kid.clone();
+
+        Ok(Self {
+            kid: kid.clone(),
+            encoding_key,
+            decoding_key,
+            jwk: Some(jwk),
+        })
+    }
+
+    pub fn kid(&self) -> &Option<String> {
+        &self.kid
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    pub fn decoding_key(&self) -> &DecodingKey {
+        &self.decoding_key
+    }
+
+    pub fn jwk(&self) -> &Option<Jwk> {
+        &self.jwk
+    }
+}
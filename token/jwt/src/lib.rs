@@ -1,3 +1,5 @@
 pub mod claim;
+mod jwk;
+pub mod key;
 pub mod kind;
 pub mod token;
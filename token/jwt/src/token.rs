@@ -1,70 +1,148 @@
-use std::time;
+use std::{str::FromStr, time};
 
-use anyhow::Result;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use anyhow::{Error, Result};
+use jsonwebtoken::{decode, decode_header, encode, jwk::JwkSet, Algorithm, Header, Validation};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
-use crate::{claim::Claim, kind::JwtTokenKind};
+use crate::{
+    claim::{Claim, CursorClaim},
+    key::JwtKey,
+    kind::JwtTokenKind,
+};
 
 pub struct JwtToken {
     header: Header,
-    encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    keys: Vec<JwtKey>,
+    validation: Validation,
+    jwks: JwkSet,
     expiry_duration: u64,
+    refresh_expiry_duration: u64,
 }
 
 impl JwtToken {
-    pub fn new(secret: &str, expiry_duration: &u64) -> Self {
+    pub fn new(
+        algorithm: &str,
+        keys: Vec<JwtKey>,
+        expiry_duration: &u64,
+        refresh_expiry_duration: &u64,
+    ) -> Result<Self> {
         hb_log::info(Some("⚡"), "JwtToken: Initializing component");
 
-        let secret = secret.as_bytes();
-        Self {
-            header: Header::default(),
-            encoding_key: EncodingKey::from_secret(secret),
-            decoding_key: DecodingKey::from_secret(secret),
+        let algorithm = Algorithm::from_str(algorithm)?;
+
+        // The first key is the newest one and is used for signing
+        let signing_key = match keys.first() {
+            Some(key) => key,
+            None => return Err(Error::msg("At least one JWT key must be specified")),
+        };
+
+        let mut header = Header::new(algorithm);
+        header.kid = signing_key.kid().clone();
+
+        // Audiences are project-defined custom claims meant for downstream
+        // services, so they are not checked here
+        let mut validation = Validation::new(algorithm);
+        validation.validate_aud = false;
+
+        let jwks = JwkSet {
+            keys: keys.iter().filter_map(|key| key.jwk().clone()).collect(),
+        };
+
+        Ok(Self {
+            header,
+            keys,
+            validation,
+            jwks,
             expiry_duration: *expiry_duration,
-        }
+            refresh_expiry_duration: *refresh_expiry_duration,
+        })
+    }
+
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+
+    pub fn expiry_duration(&self) -> &u64 {
+        &self.expiry_duration
+    }
+
+    pub fn refresh_expiry_duration(&self) -> &u64 {
+        &self.refresh_expiry_duration
     }
 
     pub fn encode(&self, id: &Uuid, kind: &JwtTokenKind) -> Result<String> {
-        let expiration_time = match usize::try_from(
-            time::SystemTime::now()
-                .duration_since(time::UNIX_EPOCH)?
-                .as_secs()
-                + self.expiry_duration,
-        ) {
+        self.encode_with_claims(id, kind, &Map::new())
+    }
+
+    pub fn encode_with_claims(
+        &self,
+        id: &Uuid,
+        kind: &JwtTokenKind,
+        custom_claims: &Map<String, Value>,
+    ) -> Result<String> {
+        let issued_at = time::SystemTime::now()
+            .duration_since(time::UNIX_EPOCH)?
+            .as_secs();
+        let expiration_time = match usize::try_from(issued_at + self.expiry_duration) {
             Ok(time) => time,
             Err(err) => return Err(err.into()),
         };
 
         Ok(encode(
             &self.header,
-            &Claim::new(id, kind, &expiration_time),
-            &self.encoding_key,
+            &Claim::new(
+                id,
+                kind,
+                &usize::try_from(issued_at)?,
+                &expiration_time,
+                custom_claims,
+            ),
+            self.keys[0].encoding_key(),
         )?)
     }
 
     pub fn decode(&self, token: &str) -> Result<Claim> {
-        Ok(decode::<Claim>(token, &self.decoding_key, &Validation::default())?.claims)
+        self.decode_with_keys(token)
     }
 
-    pub fn need_renew(&self, claim: &Claim) -> Result<bool> {
-        let expiry = match u64::try_from(*claim.exp()) {
-            Ok(expiry) => expiry,
-            Err(err) => return Err(err.into()),
-        };
-        if expiry - (self.expiry_duration / 2)
-            < time::SystemTime::now()
+    pub fn encode_cursor<T: Serialize>(&self, data: T) -> Result<String> {
+        let expiration_time = usize::try_from(
+            time::SystemTime::now()
                 .duration_since(time::UNIX_EPOCH)?
                 .as_secs()
-        {
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+                + self.expiry_duration,
+        )?;
+
+        Ok(encode(
+            &self.header,
+            &CursorClaim::new(data, &expiration_time),
+            self.keys[0].encoding_key(),
+        )?)
     }
 
-    pub fn renew(&self, claim: &Claim) -> Result<String> {
-        self.encode(claim.id(), claim.kind())
+    pub fn decode_cursor<T: DeserializeOwned>(&self, cursor: &str) -> Result<CursorClaim<T>> {
+        self.decode_with_keys(cursor)
+    }
+
+    fn decode_with_keys<T: DeserializeOwned>(&self, token: &str) -> Result<T> {
+        let kid = decode_header(token)?.kid;
+        let mut last_err = None;
+        for key in &self.keys {
+            // Tokens without a kid were issued before key ids were configured,
+            // so they are checked against every active key
+            if kid.is_some() && key.kid() != &kid {
+                continue;
+            }
+            match decode::<T>(token, key.decoding_key(), &self.validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        match last_err {
+            Some(err) => Err(err.into()),
+            None => Err(Error::msg("JWT key id is unknown")),
+        }
     }
 }
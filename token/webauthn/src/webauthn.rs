@@ -0,0 +1,201 @@
+use std::sync::Mutex;
+
+use ahash::{HashMap, HashMapExt};
+use anyhow::{Error, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+use webauthn_rs::{
+    prelude::{
+        Passkey, PasskeyAuthentication, PasskeyRegistration, PublicKeyCredential,
+        RegisterPublicKeyCredential, Url,
+    },
+    Webauthn, WebauthnBuilder,
+};
+
+pub struct WebauthnToken {
+    webauthn: Webauthn,
+    ceremony_ttl: Duration,
+    registrations: Mutex<HashMap<Uuid, Ceremony<PasskeyRegistration>>>,
+    authentications: Mutex<HashMap<Uuid, Ceremony<PasskeyAuthentication>>>,
+}
+
+// The challenge state of a started registration or login, kept until the browser answers it
+struct Ceremony<T> {
+    admin_id: Uuid,
+    state: T,
+    expired_at: DateTime<Utc>,
+}
+
+impl WebauthnToken {
+    pub fn new(rp_id: &str, rp_origin: &str, rp_name: &str, ceremony_ttl: &u32) -> Result<Self> {
+        hb_log::info(Some("⚡"), "WebauthnToken: Initializing component");
+
+        let rp_origin = Url::parse(rp_origin)?;
+        let webauthn = WebauthnBuilder::new(rp_id, &rp_origin)?
+            .rp_name(rp_name)
+            .build()?;
+
+        Ok(Self {
+            webauthn,
+            ceremony_ttl: Duration::seconds((*ceremony_ttl).into()),
+            registrations: Mutex::new(HashMap::new()),
+            authentications: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // Returns the ceremony id and the creation options to pass to navigator.credentials.create()
+    pub fn start_registration(
+        &self,
+        admin_id: &Uuid,
+        email: &str,
+        passkeys: &[Value],
+    ) -> Result<(Uuid, Value)> {
+        let exclude_credentials = Self::decode_passkeys(passkeys)?
+            .iter()
+            .map(|passkey| passkey.cred_id().clone())
+            .collect();
+        let (options, state) = self.webauthn.start_passkey_registration(
+            *admin_id,
+            email,
+            email,
+            Some(exclude_credentials),
+        )?;
+
+        let ceremony_id = Uuid::now_v7();
+        let now = Utc::now();
+        let mut registrations = self.registrations.lock().unwrap();
+        registrations.retain(|_, ceremony| ceremony.expired_at > now);
+        registrations.insert(
+            ceremony_id,
+            Ceremony {
+                admin_id: *admin_id,
+                state,
+                expired_at: now + self.ceremony_ttl,
+            },
+        );
+
+        Ok((ceremony_id, serde_json::to_value(options)?))
+    }
+
+    // Returns the new passkey in its stored form. A ceremony can only be answered once.
+    pub fn finish_registration(
+        &self,
+        admin_id: &Uuid,
+        ceremony_id: &Uuid,
+        credential: &Value,
+    ) -> Result<Value> {
+        let ceremony = Self::take_ceremony(&self.registrations, admin_id, ceremony_id)?;
+        let credential: RegisterPublicKeyCredential = serde_json::from_value(credential.clone())?;
+        let passkey = self
+            .webauthn
+            .finish_passkey_registration(&credential, &ceremony.state)?;
+        Ok(serde_json::to_value(passkey)?)
+    }
+
+    // Returns the ceremony id and the request options to pass to navigator.credentials.get()
+    pub fn start_authentication(&self, admin_id: &Uuid, passkeys: &[Value]) -> Result<(Uuid, Value)> {
+        let passkeys = Self::decode_passkeys(passkeys)?;
+        if passkeys.is_empty() {
+            return Err(Error::msg("This account has no passkeys"));
+        }
+        let (options, state) = self.webauthn.start_passkey_authentication(&passkeys)?;
+
+        let ceremony_id = Uuid::now_v7();
+        let now = Utc::now();
+        let mut authentications = self.authentications.lock().unwrap();
+        authentications.retain(|_, ceremony| ceremony.expired_at > now);
+        authentications.insert(
+            ceremony_id,
+            Ceremony {
+                admin_id: *admin_id,
+                state,
+                expired_at: now + self.ceremony_ttl,
+            },
+        );
+
+        Ok((ceremony_id, serde_json::to_value(options)?))
+    }
+
+    pub fn authentication_admin_id(&self, ceremony_id: &Uuid) -> Result<Uuid> {
+        match self.authentications.lock().unwrap().get(ceremony_id) {
+            Some(ceremony) if ceremony.expired_at > Utc::now() => Ok(ceremony.admin_id),
+            _ => Err(Error::msg("Passkey login has expired or does not exist")),
+        }
+    }
+
+    // Returns the admin's passkeys with the signature counter of the used one advanced, or None
+    // when nothing about the stored passkeys changed
+    pub fn finish_authentication(
+        &self,
+        admin_id: &Uuid,
+        ceremony_id: &Uuid,
+        credential: &Value,
+        passkeys: &[Value],
+    ) -> Result<Option<Vec<Value>>> {
+        let ceremony = Self::take_ceremony(&self.authentications, admin_id, ceremony_id)?;
+        let credential: PublicKeyCredential = serde_json::from_value(credential.clone())?;
+        let result = self
+            .webauthn
+            .finish_passkey_authentication(&credential, &ceremony.state)?;
+
+        let mut passkeys = Self::decode_passkeys(passkeys)?;
+        let mut changed = false;
+        let mut found = false;
+        for passkey in &mut passkeys {
+            if let Some(passkey_changed) = passkey.update_credential(&result) {
+                found = true;
+                changed |= passkey_changed;
+            }
+        }
+        // The passkey was removed after the login started
+        if !found {
+            return Err(Error::msg("This passkey is not registered to the account"));
+        }
+
+        match changed {
+            true => Ok(Some(
+                passkeys
+                    .iter()
+                    .map(serde_json::to_value)
+                    .collect::<Result<_, _>>()?,
+            )),
+            false => Ok(None),
+        }
+    }
+
+    pub fn credential_id(passkey: &Value) -> Result<String> {
+        let passkey: Passkey = serde_json::from_value(passkey.clone())?;
+        match serde_json::to_value(passkey.cred_id())? {
+            Value::String(credential_id) => Ok(credential_id),
+            _ => Err(Error::msg("Passkey credential id is not a string")),
+        }
+    }
+
+    fn take_ceremony<T>(
+        ceremonies: &Mutex<HashMap<Uuid, Ceremony<T>>>,
+        admin_id: &Uuid,
+        ceremony_id: &Uuid,
+    ) -> Result<Ceremony<T>> {
+        let mut ceremonies = ceremonies.lock().unwrap();
+        match ceremonies.get(ceremony_id) {
+            Some(ceremony) if ceremony.admin_id != *admin_id => {
+                return Err(Error::msg("This passkey ceremony belongs to another account"))
+            }
+            Some(ceremony) if ceremony.expired_at <= Utc::now() => {
+                ceremonies.remove(ceremony_id);
+                return Err(Error::msg("Passkey ceremony has expired or does not exist"));
+            }
+            Some(_) => (),
+            None => return Err(Error::msg("Passkey ceremony has expired or does not exist")),
+        }
+        Ok(ceremonies.remove(ceremony_id).unwrap())
+    }
+
+    fn decode_passkeys(passkeys: &[Value]) -> Result<Vec<Passkey>> {
+        Ok(passkeys
+            .iter()
+            .map(|passkey| serde_json::from_value(passkey.clone()))
+            .collect::<Result<_, _>>()?)
+    }
+}